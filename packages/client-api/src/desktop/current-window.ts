@@ -1,13 +1,14 @@
-import {
-  PhysicalPosition,
-  PhysicalSize,
-  getCurrentWindow,
-  type Window,
-} from '@tauri-apps/api/window';
+import { getCurrentWindow, type Window } from '@tauri-apps/api/window';
 
 import type { ZOrder } from '~/user-config';
 import { createLogger } from '~/utils';
-import { setAlwaysOnTop, setSkipTaskbar } from './desktop-commands';
+import {
+  isUnsupportedOnPlatformError,
+  moveWindow,
+  resizeWindow,
+  setAlwaysOnTop,
+  setSkipTaskbar,
+} from './desktop-commands';
 
 export interface WindowPosition {
   x: number;
@@ -29,30 +30,16 @@ export async function setWindowPosition(
 ) {
   logger.debug(`Setting window position to:`, position);
 
-  const window = getCurrentWindow();
-
   // Avoid setting position if neither x/y are defined.
   if (position.x !== undefined || position.y !== undefined) {
-    const newPosition = new PhysicalPosition(
-      position.x ?? (await window.outerPosition()).x,
-      position.y ?? (await window.outerPosition()).y,
+    await warnIfUnsupported(
+      moveWindow({ x: position.x, y: position.y }),
     );
-
-    // Set position twice to handle DPI changes on cross-monitor moves.
-    await window.setPosition(newPosition);
-    await window.setPosition(newPosition);
   }
 
   // Avoid setting size if neither width/height are defined.
   if (position.width !== undefined || position.height !== undefined) {
-    const newSize = new PhysicalSize(
-      position.width ?? (await window.outerSize()).width,
-      position.height ?? (await window.outerSize()).height,
-    );
-
-    // Set size twice to handle DPI changes on cross-monitor moves.
-    await window.setSize(newSize);
-    await window.setSize(newSize);
+    await resizeWindow({ width: position.width, height: position.height });
   }
 }
 
@@ -60,12 +47,28 @@ export async function setWindowStyles(styles: Partial<WindowStyles>) {
   const window = getCurrentWindow();
 
   await Promise.all([
-    setSkipTaskbar(styles.shownInTaskbar !== true),
+    warnIfUnsupported(setSkipTaskbar(styles.shownInTaskbar !== true)),
     window.setResizable(styles.resizable === true),
-    setWindowZOrder(window, styles.zOrder),
+    warnIfUnsupported(setWindowZOrder(window, styles.zOrder)),
   ]);
 }
 
+/**
+ * Logs a warning instead of rejecting if the command isn't supported on
+ * the current display session (e.g. positioning on Wayland).
+ */
+async function warnIfUnsupported(promise: Promise<void>) {
+  try {
+    await promise;
+  } catch (err) {
+    if (!isUnsupportedOnPlatformError(err)) {
+      throw err;
+    }
+
+    logger.warn(err.cause.message);
+  }
+}
+
 async function setWindowZOrder(window: Window, zOrder?: ZOrder) {
   if (zOrder === 'always_on_bottom') {
     await window.setAlwaysOnBottom(true);
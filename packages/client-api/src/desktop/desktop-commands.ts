@@ -4,8 +4,19 @@ import {
 } from '@tauri-apps/api/core';
 
 import { createLogger } from '../utils';
-import type { ProviderConfig } from '~/user-config';
-import type { OpenWindowArgs } from './shared';
+import type { CornerPreference, ProviderConfig } from '~/user-config';
+import type {
+  ActivationDeniedError,
+  AppError,
+  DisplaySession,
+  InvalidHotkeyError,
+  OpenWindowArgs,
+  ParsedHotkey,
+  ProviderAction,
+  TrayMenuItem,
+  UnknownActionError,
+  UnsupportedOnPlatformError,
+} from './shared';
 
 const logger = createLogger('desktop-commands');
 
@@ -16,6 +27,64 @@ export function readConfigFile(): Promise<string> {
   return invoke<string>('read_config_file');
 }
 
+export interface ConfigReloadResult {
+  /**
+   * Top-level config entries that were added, removed or changed (e.g.
+   * `window/bar`).
+   */
+  changed: string[];
+
+  /**
+   * Validation errors of individual config entries.
+   */
+  errors: string[];
+}
+
+/**
+ * Re-reads the config file and applies changes without restarting.
+ * Windows affected by the changes are reloaded.
+ */
+export function reloadConfig(): Promise<ConfigReloadResult> {
+  return invoke<ConfigReloadResult>('reload_config');
+}
+
+export interface AppInfo {
+  version: string;
+  debugBuild: boolean;
+  targetOs: string;
+  arch: string;
+  displaySession: DisplaySession;
+
+  /**
+   * Provider types that are compiled in this build. Builds can exclude
+   * providers to reduce the binary size.
+   */
+  providers: string[];
+}
+
+/**
+ * Gets info about the app build and the display session it's running
+ * in (e.g. X11 or Wayland on Linux).
+ */
+export function getAppInfo(): Promise<AppInfo> {
+  return invoke<AppInfo>('get_app_info');
+}
+
+/**
+ * Gets up to {@link limit} of the most recent app errors, oldest first.
+ */
+export function getRecentErrors(limit?: number): Promise<AppError[]> {
+  return invoke<AppError[]>('get_recent_errors', { limit });
+}
+
+/**
+ * Emits app errors that occurred while no windows were open as
+ * `zebar://error` events.
+ */
+export function flushPendingErrors(): Promise<void> {
+  return invoke<void>('flush_pending_errors');
+}
+
 /**
  * Get args used to open the window with the {@link windowLabel}.
  */
@@ -27,6 +96,132 @@ export function getOpenWindowArgs(
   });
 }
 
+export interface ProviderSubscription {
+  configHash: string;
+  providerType: string;
+
+  /**
+   * Fields of the provider output that are accessed by the window. Empty
+   * if all fields are accessed.
+   */
+  trackedAccess: string[];
+
+  /**
+   * Unix timestamp in milliseconds of the provider's last output.
+   */
+  lastEmittedAt: number | null;
+
+  /**
+   * Duration in milliseconds of the provider's most recent run. Only set
+   * for interval providers.
+   */
+  lastRunMs: number | null;
+
+  /**
+   * 95th percentile duration in milliseconds of the provider's recent
+   * runs. Only set for interval providers.
+   */
+  p95RunMs: number | null;
+
+  /**
+   * Approximate size in bytes of the state that the provider retains
+   * (e.g. history buffers, caches and its latest output). Measured every
+   * minute, so it's `null` for new providers.
+   */
+  retainedBytes: number | null;
+
+  /**
+   * Actions that can be invoked on the provider via
+   * {@link callProviderAction}.
+   */
+  actions: ProviderAction[];
+}
+
+export interface EmissionStats {
+  configHash: string;
+
+  /**
+   * Number of outputs the provider has emitted, which is also the
+   * sequence number of its latest emission.
+   */
+  produced: number;
+
+  /**
+   * Deliveries to each window that has received emissions.
+   */
+  listeners: ListenerEmissionStats[];
+}
+
+export interface ListenerEmissionStats {
+  windowLabel: string;
+
+  /**
+   * Number of emissions that were sent to the window. An emission can
+   * still be lost after being sent (e.g. while the window reloads).
+   */
+  delivered: number;
+
+  /**
+   * Sequence number of the latest emission sent to the window.
+   */
+  lastSequence: number;
+}
+
+export interface AvailableProvider {
+  /**
+   * Provider type, as used in the config (e.g. `cpu`).
+   */
+  type: string;
+
+  /**
+   * JSON schema of the provider's type-specific config. Shared options
+   * (e.g. `transforms`) aren't included.
+   */
+  configSchema: Record<string, unknown>;
+}
+
+/**
+ * Get the provider types that are available on the current platform,
+ * along with the schemas of their configs.
+ */
+export function getAvailableProviders(): Promise<AvailableProvider[]> {
+  return invoke<AvailableProvider[]>('get_available_providers');
+}
+
+/**
+ * Get the providers that the window with the {@link windowLabel} is
+ * subscribed to. Useful for debugging.
+ */
+export function getWindowProviders(
+  windowLabel: string,
+): Promise<ProviderSubscription[]> {
+  return invoke<ProviderSubscription[]>('get_window_providers', {
+    windowLabel,
+  });
+}
+
+/**
+ * Get how many outputs the provider with the {@link configHash} has
+ * emitted, and how many were delivered to each window. `null` if the
+ * provider isn't running or hasn't emitted yet. Useful for debugging
+ * missed updates.
+ */
+export function getEmissionStats(
+  configHash: string,
+): Promise<EmissionStats | null> {
+  return invoke<EmissionStats | null>('get_emission_stats', {
+    configHash,
+  });
+}
+
+/**
+ * Re-emits the latest output of a provider that the window listens to.
+ * Called automatically when a gap in sequence numbers is detected.
+ */
+export function refreshProvider(configHash: string): Promise<void> {
+  return invoke<void>('refresh_provider', { configHash });
+}
+
 // TODO: Add support for only fetching selected variables.
 export function listenProvider(args: {
   configHash: string;
@@ -40,14 +235,569 @@ export function unlistenProvider(configHash: string): Promise<void> {
   return invoke<void>('unlisten_provider', { configHash });
 }
 
+/**
+ * Safely ejects the removable drive at the given mount point.
+ *
+ * Rejects with a `drive_busy` error if the drive is in use.
+ */
+export function ejectDrive(mountPoint: string): Promise<void> {
+  return invoke<void>('eject_drive', { mountPoint });
+}
+
+/**
+ * Brings another app's window to the foreground, restoring it if
+ * minimized (e.g. when clicking its button in a taskbar widget).
+ *
+ * Rejects with an `activation_denied` error if the OS refuses, and with
+ * an `unsupported_on_platform` error on platforms other than Windows.
+ */
+export function activateWindow(nativeHandle: number): Promise<void> {
+  return invoke<void>('activate_window', { nativeHandle });
+}
+
+/**
+ * @deprecated Use {@link activateWindow} instead.
+ */
+export function focusApp(hwnd: number): Promise<void> {
+  return activateWindow(hwnd);
+}
+
+/**
+ * Whether the error is due to the OS refusing to activate a window.
+ */
+export function isActivationDeniedError(
+  err: unknown,
+): err is Error & { cause: ActivationDeniedError } {
+  const cause = (err as { cause?: ActivationDeniedError })?.cause;
+  return err instanceof Error && cause?.kind === 'activation_denied';
+}
+
+/**
+ * Asks the window with the given handle to close. The app may still
+ * prompt to save changes. Only supported on Windows.
+ */
+export function closeApp(hwnd: number): Promise<void> {
+  return invoke<void>('close_app', { hwnd });
+}
+
+/**
+ * Parses a hotkey binding into its canonical form and its label on the
+ * current keyboard layout. Keys are bound by the character they produce
+ * (e.g. `Ctrl+Alt+Z`), or by their physical location when written in
+ * angle brackets (e.g. `Ctrl+Alt+<KeyZ>`).
+ *
+ * Rejects with an `InvalidHotkeyError` including the offending token if
+ * the binding is invalid.
+ *
+ * The hotkey is only parsed, not registered globally, so conflicts with
+ * hotkeys of other apps aren't detected. Physical keys are labeled as on
+ * US layouts outside of Windows.
+ */
+export function parseHotkey(hotkey: string): Promise<ParsedHotkey> {
+  return invoke<ParsedHotkey>('parse_hotkey', { hotkey });
+}
+
+/**
+ * Whether the error is due to an invalid hotkey binding.
+ */
+export function isInvalidHotkeyError(
+  err: unknown,
+): err is Error & { cause: InvalidHotkeyError } {
+  const cause = (err as { cause?: InvalidHotkeyError })?.cause;
+  return err instanceof Error && cause?.kind === 'invalid_hotkey';
+}
+
+/**
+ * Gets the label of the physical key with the given code (e.g. `KeyZ`)
+ * on the current keyboard layout. Returns `null` if the code is unknown.
+ *
+ * Only Windows provides layout-specific labels. On macOS and Linux, the
+ * key's label on US layouts is returned (e.g. `Z` for `KeyZ`, even on
+ * German layouts).
+ */
+export function getKeyName(code: string): Promise<string | null> {
+  return invoke<string | null>('get_key_name', { code });
+}
+
+/**
+ * Replaces the contents of the system clipboard with the given text.
+ */
+export function setClipboard(text: string): Promise<void> {
+  return invoke<void>('set_clipboard', { text });
+}
+
+/**
+ * Sets all LEDs in a zone of an OpenRGB device to the given hex color
+ * (e.g. `#ff8800`). Requires an `openrgb` provider to be enabled.
+ */
+export function setLedColor(
+  device: number,
+  zone: number,
+  color: string,
+): Promise<void> {
+  return invoke<void>('set_led_color', { device, zone, color });
+}
+
+/**
+ * Sets the value at a dot-separated path (e.g. `tasks.0.done`) of a
+ * `state` provider's document. Missing objects along the path are
+ * created.
+ *
+ * Rejects with an `invalid_state_path` error if the path is malformed.
+ */
+export function stateSet(
+  configHash: string,
+  path: string,
+  value: unknown,
+): Promise<void> {
+  return invoke<void>('state_set', { configHash, path, value });
+}
+
+/**
+ * Adds the delta to the number at a dot-separated path of a `state`
+ * provider's document. Missing values are treated as 0.
+ *
+ * Rejects with an `invalid_state_path` error if the path is malformed
+ * or doesn't point to a number.
+ */
+export function stateIncrement(
+  configHash: string,
+  path: string,
+  delta: number,
+): Promise<void> {
+  return invoke<void>('state_increment', { configHash, path, delta });
+}
+
+/**
+ * Resets a `state` provider's document to its initial value.
+ */
+export function stateReset(configHash: string): Promise<void> {
+  return invoke<void>('state_reset', { configHash });
+}
+
+/**
+ * Invokes an action on a provider that the window is subscribed to (e.g.
+ * `reset_counts` of a `tail` provider), and gets its result.
+ *
+ * Rejects with an `UnknownActionError` listing the valid actions if the
+ * provider doesn't have the given action.
+ */
+export function callProviderAction<T = unknown>(
+  configHash: string,
+  action: string,
+  args?: unknown,
+): Promise<T> {
+  return invoke<T>('call_provider_action', { configHash, action, args });
+}
+
+/**
+ * Whether the error is due to invoking an action that the provider
+ * doesn't have.
+ */
+export function isUnknownActionError(
+  err: unknown,
+): err is Error & { cause: UnknownActionError } {
+  const cause = (err as { cause?: UnknownActionError })?.cause;
+  return err instanceof Error && cause?.kind === 'unknown_action';
+}
+
+/**
+ * Resets the match counts of a `tail` provider to 0.
+ */
+export function tailResetCounts(configHash: string): Promise<void> {
+  return invoke<void>('tail_reset_counts', { configHash });
+}
+
+/**
+ * Reference to a secret that was added via `zebar secret set <name>`.
+ */
+export interface SecretRef {
+  secret: string;
+}
+
+export interface HttpRequestOptions {
+  url: string;
+
+  /**
+   * Defaults to `GET`.
+   */
+  method?: string;
+
+  /**
+   * Header values are either strings or references to secrets (e.g.
+   * `{ secret: 'github_token' }`), which are resolved without being
+   * exposed to the frontend.
+   */
+  headers?: Record<string, string | SecretRef>;
+
+  /**
+   * Query parameters to append to the URL. Values can reference secrets
+   * the same way as headers.
+   */
+  query?: Record<string, string | SecretRef>;
+
+  body?: string;
+
+  /**
+   * Timeout in milliseconds. Defaults to 30 seconds.
+   */
+  timeout?: number;
+
+  /**
+   * Duration in milliseconds to cache successful `GET` responses for.
+   */
+  cacheTtl?: number;
+}
+
+export interface HttpResponse {
+  status: number;
+  headers: Record<string, string>;
+
+  /**
+   * Body as text, or base64-encoded if the response isn't text.
+   */
+  body: string;
+  bodyEncoding: 'text' | 'base64';
+
+  /**
+   * Whether the response was served from the cache.
+   */
+  cached: boolean;
+}
+
+/**
+ * Makes an HTTP request from the desktop app, which avoids CORS and
+ * allows secrets to be used. Requires permission for the URL's host.
+ */
+export function httpRequest(
+  options: HttpRequestOptions,
+): Promise<HttpResponse> {
+  return invoke<HttpResponse>('http_request', { options });
+}
+
+/**
+ * Enables or disables offline mode, which blocks all outbound network
+ * requests from providers. Takes effect without a restart.
+ */
+export function setOfflineMode(enabled: boolean): Promise<void> {
+  return invoke<void>('set_offline_mode', { enabled });
+}
+
+/**
+ * Enables or disables redaction of the provider fields configured under
+ * `redaction` in the global config (e.g. while streaming). Redacted
+ * fields are replaced with a placeholder rather than removed.
+ */
+export function setRedaction(enabled: boolean): Promise<void> {
+  return invoke<void>('set_redaction', { enabled });
+}
+
+/**
+ * Gets the current items of the system tray menu (e.g. to show the same
+ * window toggles as the tray). Changes are available via
+ * `onTrayStateChange`.
+ */
+export function getTrayState(): Promise<TrayMenuItem[]> {
+  return invoke<TrayMenuItem[]>('get_tray_state');
+}
+
+/**
+ * Runs the action of a tray menu item by its ID, the same as if it was
+ * clicked in the tray menu. Rejects if the item isn't currently in the
+ * menu or is disabled.
+ */
+export function triggerTrayAction(id: string): Promise<void> {
+  return invoke<void>('trigger_tray_action', { id });
+}
+
+/**
+ * Rejects with an `unsupported_on_platform` error on Wayland sessions.
+ */
 export function setAlwaysOnTop(): Promise<void> {
   return invoke<void>('set_always_on_top');
 }
 
+/**
+ * Rejects with an `unsupported_on_platform` error on Wayland sessions
+ * when {@link skip} is `true`.
+ */
 export function setSkipTaskbar(skip: boolean): Promise<void> {
   return invoke<void>('set_skip_taskbar', { skip });
 }
 
+/**
+ * Moves the window to the given physical position. Rejects with an
+ * `unsupported_on_platform` error on Wayland sessions.
+ */
+export function moveWindow(position: {
+  x?: number;
+  y?: number;
+}): Promise<void> {
+  return invoke<void>('move_window', position);
+}
+
+/**
+ * Resizes the window to the given physical size. The resulting geometry
+ * is re-applied when the window's scale factor changes (e.g. when the
+ * display scaling is changed).
+ */
+export function resizeWindow(size: {
+  width?: number;
+  height?: number;
+}): Promise<void> {
+  return invoke<void>('resize_window', size);
+}
+
+export interface WindowAtPoint {
+  /**
+   * Label of the window, which is unique across open windows.
+   */
+  windowLabel: string;
+
+  /**
+   * ID of the window in the config (e.g. `bar`).
+   */
+  windowId: string;
+}
+
+/**
+ * Gets the window under the given point in physical screen coordinates,
+ * or `null` if there's none. For example, to find the drop target when
+ * dragging a widget between windows.
+ *
+ * A point within the current window can be converted with
+ * `position.x + clientX * window.devicePixelRatio`, where `position` is
+ * the window's physical outer position. Rejects with an
+ * `unsupported_on_platform` error on Wayland sessions.
+ */
+export function getWindowAtPoint(
+  x: number,
+  y: number,
+): Promise<WindowAtPoint | null> {
+  return invoke<WindowAtPoint | null>('get_window_at_point', {
+    x: Math.round(x),
+    y: Math.round(y),
+  });
+}
+
+/**
+ * Hands off state to another window (e.g. a widget that was dropped on
+ * it). The target window receives it via `onStateTransfer`, even if
+ * it's hidden.
+ *
+ * @param fromWindow Label of the current window.
+ * @param toWindow Label of the target window.
+ */
+export function transferState(
+  fromWindow: string,
+  toWindow: string,
+  payload: unknown,
+): Promise<void> {
+  return invoke<void>('transfer_state', { fromWindow, toWindow, payload });
+}
+
+/**
+ * Shows the window once its initial size and position are applied.
+ * Windows are created hidden, and are otherwise only shown after a
+ * timeout.
+ */
+export function windowReady(): Promise<void> {
+  return invoke<void>('window_ready');
+}
+
+/**
+ * Sets the rounding of the window's corners. Windows that are docked to
+ * a monitor edge get square corners by default. No-op on platforms
+ * other than Windows 11.
+ */
+export function setCornerPreference(
+  preference: CornerPreference,
+): Promise<void> {
+  return invoke<void>('set_corner_preference', { preference });
+}
+
+/**
+ * Sets whether the window is shown on all workspaces (virtual desktops),
+ * or only on the one it's currently on.
+ *
+ * On Windows, windows that are shown in the taskbar are never shown on
+ * all desktops.
+ */
+export function setVisibleOnAllWorkspaces(
+  visible: boolean,
+): Promise<void> {
+  return invoke<void>('set_visible_on_all_workspaces', { visible });
+}
+
+/**
+ * Moves the window to the virtual desktop at the given index (starting
+ * at 0), and only shows it on that desktop. Rejects with an
+ * `unsupported_on_platform` error on platforms other than Windows.
+ */
+export function pinToDesktop(desktopIndex: number): Promise<void> {
+  return invoke<void>('pin_to_desktop', { desktopIndex });
+}
+
+/**
+ * Sets the title of the window, which is shown in window overviews and
+ * screen sharing pickers.
+ */
+export function setWindowTitle(title: string): Promise<void> {
+  return invoke<void>('set_window_title', { title });
+}
+
+/**
+ * Sets the icon of the window from a PNG or ICO file. Relative paths are
+ * resolved against the `assets/` folder within the config directory,
+ * whereas absolute paths require the `filesystem_paths` permission.
+ */
+export function setWindowIcon(icon: string): Promise<void> {
+  return invoke<void>('set_window_icon', { icon });
+}
+
+/**
+ * Whether the error is due to the command not being supported on the
+ * current display session.
+ */
+export function isUnsupportedOnPlatformError(
+  err: unknown,
+): err is Error & { cause: UnsupportedOnPlatformError } {
+  const cause = (err as { cause?: UnsupportedOnPlatformError })?.cause;
+  return err instanceof Error && cause?.kind === 'unsupported_on_platform';
+}
+
+/**
+ * Shows a count badge on the window's taskbar button (or the dock icon on
+ * MacOS) and on the system tray icon. Passing `null` clears the badge.
+ */
+export function setBadge(count: number | null): Promise<void> {
+  return invoke<void>('set_badge', { count });
+}
+
+export type TaskbarProgressState =
+  | 'none'
+  | 'normal'
+  | 'indeterminate'
+  | 'paused'
+  | 'error';
+
+export interface TaskbarProgress {
+  state: TaskbarProgressState;
+
+  /**
+   * Progress from 0 to 100.
+   */
+  value?: number;
+}
+
+/**
+ * Shows progress on the window's taskbar button (or the dock icon on
+ * MacOS). No-op on other platforms.
+ */
+export function setProgress(progress: TaskbarProgress): Promise<void> {
+  return invoke<void>('set_progress', { progress });
+}
+
+export interface TooltipOptions {
+  /**
+   * Plain text content. Shown as a native tooltip on Windows.
+   */
+  text?: string;
+
+  /**
+   * Rich content, shown in an auxiliary webview. Takes precedence over
+   * `text`.
+   */
+  html?: string;
+
+  /**
+   * Position relative to the top-left of the window, in physical pixels.
+   * Can be outside of the window's bounds.
+   */
+  x: number;
+  y: number;
+
+  /**
+   * Logical size of webview tooltips, since they aren't sized to their
+   * content.
+   */
+  width?: number;
+  height?: number;
+
+  /**
+   * Duration in milliseconds after which the tooltip is hidden. Defaults
+   * to 5000. Set to 0 to only hide it via {@link hideTooltip}.
+   */
+  timeout?: number;
+}
+
+/**
+ * Shows a tooltip outside of the window's bounds, without taking focus.
+ * Replaces any tooltip that the window is already showing. Rejects with
+ * an `unsupported_on_platform` error on Wayland sessions.
+ */
+export function showTooltip(options: TooltipOptions): Promise<void> {
+  return invoke<void>('show_tooltip', { options });
+}
+
+/**
+ * Hides the window's tooltip. Should be called on mouse leave.
+ */
+export function hideTooltip(): Promise<void> {
+  return invoke<void>('hide_tooltip');
+}
+
+export interface MeasureTextFont {
+  fontFamily: string;
+
+  /**
+   * Font size in CSS pixels.
+   */
+  size: number;
+
+  /**
+   * CSS font weight. Defaults to 400.
+   */
+  weight?: number;
+}
+
+export interface TextSize {
+  /**
+   * Width in physical pixels.
+   */
+  width: number;
+
+  /**
+   * Height in physical pixels.
+   */
+  height: number;
+}
+
+/**
+ * Measures text with the platform's text layout engine (DirectWrite,
+ * CoreText or Pango), so that sizes match the native font rendering.
+ * Sizes are in physical pixels at the window's scale factor.
+ *
+ * Pass an array to measure many labels in one call.
+ */
+export function measureText(
+  text: string,
+  font: MeasureTextFont,
+): Promise<TextSize>;
+export function measureText(
+  texts: string[],
+  font: MeasureTextFont,
+): Promise<TextSize[]>;
+export async function measureText(
+  text: string | string[],
+  font: MeasureTextFont,
+): Promise<TextSize | TextSize[]> {
+  const texts = Array.isArray(text) ? text : [text];
+  const sizes = await invoke<TextSize[]>('measure_text', { texts, font });
+
+  return Array.isArray(text) ? sizes : sizes[0]!;
+}
+
 // TODO: Implement this. Should kill the window and show error dialog. If
 // there are no windows remaining, then exit the app.
 export function exitWithError(message: string): never {
@@ -69,6 +819,13 @@ export async function invoke<T>(
 
     return response;
   } catch (err) {
-    throw new Error(`Command '${command}' failed: ${err}`);
+    // Structured errors (e.g. `drive_busy`) are kept as the cause.
+    const message = (err as { message?: string })?.message ?? err;
+
+    throw Object.assign(
+      new Error(`Command '${command}' failed: ${message}`),
+      { cause: err },
+    );
   }
 }
+
@@ -3,14 +3,65 @@ import {
   type Event,
   type UnlistenFn,
 } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 
 import { createLogger } from '~/utils';
+import {
+  flushPendingErrors,
+  refreshProvider,
+  type ConfigReloadResult,
+} from './desktop-commands';
+import type {
+  AccessibilitySettings,
+  AppError,
+  NetworkErrorKind,
+  TrayMenuItem,
+} from './shared';
 
 const logger = createLogger('desktop-events');
 
 export interface ProviderEmitEvent<T = unknown> {
   configHash: string;
-  variables: { data: T } | { error: string };
+
+  /**
+   * Position of the emission among those of the provider, starting at 1.
+   * Restarts from 1 when the provider is restarted.
+   */
+  sequence: number;
+
+  /**
+   * Unix timestamp in milliseconds of when the output was produced.
+   */
+  lastUpdated: number | null;
+
+  /**
+   * Age of the output (e.g. `3m ago`). Only present if
+   * `last_updated_relative` is enabled in the global config.
+   */
+  lastUpdatedRelative?: string;
+
+  variables:
+    | {
+        data: T;
+        /**
+         * Whether the data is the provider's configured `fallback` value,
+         * emitted in place of an error. The error is under `_error`.
+         */
+        fallback?: boolean;
+      }
+    | {
+        error: string;
+        /**
+         * Whether the provider was blocked from making network requests
+         * by offline mode.
+         */
+        offlineMode?: boolean;
+        /**
+         * Kind of network failure, for network lookups that failed to
+         * connect or timed out.
+         */
+        networkError?: NetworkErrorKind;
+      };
 }
 
 let listenPromise: Promise<UnlistenFn> | null = null;
@@ -20,15 +71,41 @@ let callbacks: {
   fn: (payload: Event<ProviderEmitEvent<any>>) => void;
 }[] = [];
 
+/**
+ * Sequence number of the latest emission received from each provider.
+ */
+const lastSequences = new Map<string, number>();
+
 /**
  * Listen for provider data.
  */
 export async function onProviderEmit<T = unknown>(
   configHash: string,
-  callback: (payload: T) => void,
+  callback: (payload: T, event: ProviderEmitEvent<T>) => void,
 ): Promise<UnlistenFn> {
   registerEventCallback(configHash, callback);
 
+  return getUnlistenFn(configHash);
+}
+
+/**
+ * Listen for provider emissions as they are sent, including errors.
+ * Unlike `onProviderEmit`, errors are passed to the callback rather than
+ * thrown. Useful for debugging.
+ */
+export async function onRawProviderEmit(
+  configHash: string,
+  callback: (event: ProviderEmitEvent) => void,
+): Promise<UnlistenFn> {
+  callbacks.push({ configHash, fn: event => callback(event.payload) });
+
+  return getUnlistenFn(configHash);
+}
+
+/**
+ * Get function to remove the callbacks of the given config hash.
+ */
+async function getUnlistenFn(configHash: string): Promise<UnlistenFn> {
   const unlisten = await (listenPromise ??
     (listenPromise = listenProviderEmit()));
 
@@ -38,6 +115,8 @@ export async function onProviderEmit<T = unknown>(
       callback => callback.configHash !== configHash,
     );
 
+    lastSequences.delete(configHash);
+
     if (callbacks.length === 0) {
       unlisten();
     }
@@ -49,7 +128,7 @@ export async function onProviderEmit<T = unknown>(
  */
 function registerEventCallback<T>(
   configHash: string,
-  callback: (payload: T) => void,
+  callback: (payload: T, event: ProviderEmitEvent<T>) => void,
 ) {
   const wrappedCallback = (event: Event<ProviderEmitEvent<T>>) => {
     // Ignore provider emissions for different configs.
@@ -65,7 +144,7 @@ function registerEventCallback<T>(
     }
 
     logger.debug('Incoming provider variables:', variables.data);
-    callback(variables.data as T);
+    callback(variables.data as T, event.payload);
   };
 
   callbacks.push({ configHash, fn: wrappedCallback });
@@ -75,13 +154,156 @@ function registerEventCallback<T>(
  * Create listener for provider emissions.
  *
  * Only one Tauri event listener is needed to listen to all providers.
+ * Emissions are targeted at the windows that listen to the provider, so
+ * the listener is scoped to the current window to not receive events
+ * targeted at other windows.
  */
 async function listenProviderEmit(): Promise<UnlistenFn> {
-  return listen('provider-emit', (event: Event<ProviderEmitEvent>) => {
-    callbacks.forEach(callback => {
-      if (event.payload.configHash === callback.configHash) {
-        callback.fn(event);
-      }
-    });
-  });
+  return getCurrentWindow().listen(
+    'provider-emit',
+    (event: Event<ProviderEmitEvent>) => {
+      detectGap(event.payload);
+
+      callbacks.forEach(callback => {
+        if (event.payload.configHash === callback.configHash) {
+          callback.fn(event);
+        }
+      });
+    },
+  );
+}
+
+/**
+ * Resyncs the provider if emissions were missed since the previous one,
+ * as detected from a gap in sequence numbers.
+ */
+function detectGap(event: ProviderEmitEvent) {
+  const { configHash, sequence } = event;
+  const lastSequence = lastSequences.get(configHash);
+
+  lastSequences.set(configHash, sequence);
+
+  // Lower sequence numbers are from a restarted provider.
+  if (lastSequence === undefined || sequence <= lastSequence + 1) {
+    return;
+  }
+
+  logger.warn(
+    `Missed ${sequence - lastSequence - 1} emission(s) from provider ` +
+      `${configHash}. Resyncing.`,
+  );
+
+  refreshProvider(configHash).catch(err =>
+    logger.error('Failed to resync provider:', err),
+  );
+}
+
+/**
+ * Listen for changes to the OS accessibility settings. The initial
+ * settings are available via `OpenWindowArgs.a11y`.
+ */
+export function onAccessibilityChange(
+  callback: (settings: AccessibilitySettings) => void,
+): Promise<UnlistenFn> {
+  return listen(
+    'a11y-changed',
+    (event: Event<AccessibilitySettings>) => callback(event.payload),
+  );
+}
+
+export interface ConfigReloadedEvent extends ConfigReloadResult {
+  /**
+   * Contents of the config file. Only sent to windows that are allowed
+   * to read the config directory.
+   */
+  config: string | null;
+}
+
+/**
+ * Listen for the config being reloaded (via `reloadConfig` or the
+ * `zebar reload` CLI command).
+ */
+export function onConfigReload(
+  callback: (event: ConfigReloadedEvent) => void,
+): Promise<UnlistenFn> {
+  return listen(
+    'config-reloaded',
+    (event: Event<ConfigReloadedEvent>) => callback(event.payload),
+  );
+}
+
+export interface StateTransferEvent<T = unknown> {
+  /**
+   * Label of the window that sent the state.
+   */
+  fromWindow: string;
+
+  payload: T;
+}
+
+/**
+ * Listen for state that is handed off to the current window via
+ * `transferState`.
+ */
+export function onStateTransfer<T = unknown>(
+  callback: (event: StateTransferEvent<T>) => void,
+): Promise<UnlistenFn> {
+  return getCurrentWindow().listen(
+    'state-transfer',
+    (event: Event<StateTransferEvent<T>>) => callback(event.payload),
+  );
+}
+
+export interface ScaleChangedEvent {
+  /**
+   * New scale factor of the window (e.g. `1.5` for 150% scaling).
+   */
+  scaleFactor: number;
+}
+
+/**
+ * Listen for changes to the current window's scale factor, e.g. when the
+ * display scaling is changed or the window's monitor is docked or
+ * undocked. The window's size and position are re-applied at the new
+ * scale factor by the desktop app.
+ */
+export function onScaleChanged(
+  callback: (event: ScaleChangedEvent) => void,
+): Promise<UnlistenFn> {
+  return getCurrentWindow().listen(
+    'scale-changed',
+    (event: Event<ScaleChangedEvent>) => callback(event.payload),
+  );
+}
+
+/**
+ * Listen for changes to the system tray menu, which is rebuilt when
+ * windows are opened, closed, shown or hidden.
+ */
+export function onTrayStateChange(
+  callback: (items: TrayMenuItem[]) => void,
+): Promise<UnlistenFn> {
+  return listen('tray-state-changed', (event: Event<TrayMenuItem[]>) =>
+    callback(event.payload),
+  );
+}
+
+/**
+ * Listen for app-level errors (e.g. a window failing to open), for
+ * example to show a toast or warning icon.
+ *
+ * Errors that occurred before any window was open (e.g. an invalid
+ * config on startup) are delivered once the first listener is added.
+ * Earlier errors are available via `getRecentErrors`.
+ */
+export async function onAppError(
+  callback: (error: AppError) => void,
+): Promise<UnlistenFn> {
+  const unlisten = await listen('zebar://error', (event: Event<AppError>) =>
+    callback(event.payload),
+  );
+
+  await flushPendingErrors();
+
+  return unlisten;
 }
@@ -21,3 +21,17 @@ async function fetchOpenWindowArgs() {
 
   return getOpenWindowArgs(await getCurrentWindow().label);
 }
+
+/**
+ * Whether the current window is the built-in provider playground.
+ */
+export function isPlaygroundWindow(): boolean {
+  return window.__ZEBAR_OPEN_ARGS?.playground === true;
+}
+
+/**
+ * Whether the current window was opened in safe mode.
+ */
+export function isSafeMode(): boolean {
+  return window.__ZEBAR_OPEN_ARGS?.safeMode === true;
+}
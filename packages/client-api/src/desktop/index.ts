@@ -4,3 +4,8 @@ export * from './desktop-commands';
 export * from './desktop-events';
 export * from './dialogs';
 export * from './monitors';
+export * from './window-storage';
+export {
+  isPlaygroundWindow,
+  isSafeMode,
+} from './get-open-window-args';
@@ -0,0 +1,9 @@
+/**
+ * Error returned from `activateWindow` when the OS refuses to bring the
+ * window to the foreground.
+ */
+export interface ActivationDeniedError {
+  kind: 'activation_denied';
+  nativeHandle: number;
+  message: string;
+}
@@ -0,0 +1,27 @@
+export type AppErrorSeverity = 'warning' | 'error';
+
+/**
+ * App-level error that isn't tied to a provider (e.g. a window failing
+ * to open or the system tray failing to set up).
+ */
+export interface AppError {
+  /**
+   * Kind of the error for programmatic handling (e.g.
+   * `window_open_failed`).
+   */
+  kind: string;
+
+  message: string;
+
+  /**
+   * Part of the app that the error originated from (e.g. `tray`).
+   */
+  source: string;
+
+  severity: AppErrorSeverity;
+
+  /**
+   * Unix timestamp in milliseconds of when the error occurred.
+   */
+  timestamp: number;
+}
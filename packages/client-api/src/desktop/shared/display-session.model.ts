@@ -0,0 +1,47 @@
+export type SessionType =
+  | 'windows'
+  | 'macos'
+  | 'x11'
+  | 'wayland'
+  | 'unknown';
+
+export type SessionCapability =
+  | 'absolute_positioning'
+  | 'always_on_top'
+  | 'skip_taskbar'
+  | 'pin_to_desktop'
+  | 'activate_window';
+
+/**
+ * Display session that the desktop app is running in.
+ */
+export interface DisplaySession {
+  sessionType: SessionType;
+
+  /**
+   * Name of the compositor or desktop environment (e.g. `Hyprland` or
+   * `GNOME`). Only detected on Linux.
+   */
+  compositor: string | null;
+
+  /**
+   * Whether windows run through XWayland within a Wayland session.
+   */
+  xwayland: boolean;
+
+  /**
+   * Window capabilities that are supported by the session.
+   */
+  capabilities: SessionCapability[];
+}
+
+/**
+ * Error returned from window commands that can't work on the current
+ * display session.
+ */
+export interface UnsupportedOnPlatformError {
+  kind: 'unsupported_on_platform';
+  sessionType: SessionType;
+  capability: SessionCapability;
+  message: string;
+}
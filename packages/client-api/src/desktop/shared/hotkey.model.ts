@@ -0,0 +1,36 @@
+/**
+ * Hotkey binding in canonical form, along with its label on the current
+ * keyboard layout.
+ */
+export interface ParsedHotkey {
+  /**
+   * Canonical binding (e.g. `Ctrl+Alt+<KeyZ>`).
+   */
+  hotkey: string;
+
+  /**
+   * Binding as shown to the user (e.g. `Ctrl+Alt+Y` on German layouts for
+   * `Ctrl+Alt+<KeyZ>`). Physical keys are labeled as on US layouts
+   * outside of Windows.
+   */
+  label: string;
+
+  /**
+   * Whether the key is bound by its physical location.
+   */
+  isPhysical: boolean;
+}
+
+/**
+ * Error returned from `parseHotkey` when the binding is invalid.
+ */
+export interface InvalidHotkeyError {
+  kind: 'invalid_hotkey';
+  hotkey: string;
+
+  /**
+   * Token of the binding that is invalid (e.g. an unknown key).
+   */
+  token: string;
+  message: string;
+}
@@ -1,3 +1,10 @@
+export * from './activation-denied-error.model';
+export * from './app-error.model';
+export * from './display-session.model';
+export * from './hotkey.model';
 export * from './monitor-info.model';
+export * from './network-error.model';
 export * from './open-window-args.model';
+export * from './provider-action.model';
+export * from './tray-menu-item.model';
 export * from './window-info.model';
@@ -0,0 +1,14 @@
+/**
+ * Kind of network failure of a provider's lookup.
+ *
+ * - `dns`: The DNS lookup of the host failed.
+ * - `connect_timeout`: No connection was established in time (e.g. due
+ *   to a VPN kill switch blocking traffic).
+ * - `connect`: The connection was refused or otherwise failed.
+ * - `timeout`: The request or the lookup as a whole timed out.
+ */
+export type NetworkErrorKind =
+  | 'dns'
+  | 'connect_timeout'
+  | 'connect'
+  | 'timeout';
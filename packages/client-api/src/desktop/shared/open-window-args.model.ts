@@ -2,4 +2,36 @@ export interface OpenWindowArgs {
   args: Record<string, string>;
   env: Record<string, string>;
   windowId: string;
+  title: string | null;
+  icon: string | null;
+
+  /**
+   * Whether this is the built-in provider playground window (opened via
+   * `zebar open --playground`).
+   */
+  playground: boolean;
+
+  /**
+   * Whether the window was opened in safe mode (via `zebar open
+   * --safe-mode` or the system tray), in which case the window's custom
+   * frontend, injected CSS/JS, scripts, and geometry and providers from
+   * the config are disabled, and it uses the default global settings.
+   */
+  safeMode: boolean;
+  a11y: AccessibilitySettings;
+}
+
+/**
+ * Accessibility settings of the OS. These are read by the desktop app,
+ * since the webview's media queries don't always reflect them.
+ */
+export interface AccessibilitySettings {
+  prefersReducedMotion: boolean;
+  prefersColorScheme: 'light' | 'dark';
+  highContrast: boolean;
+
+  /**
+   * Scale factor of the system text size (e.g. `1.25` for 125%).
+   */
+  fontScale: number;
 }
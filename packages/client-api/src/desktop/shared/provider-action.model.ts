@@ -0,0 +1,25 @@
+/**
+ * Action that can be invoked on a running provider via
+ * `callProviderAction`.
+ */
+export interface ProviderAction {
+  name: string;
+  description: string;
+
+  /**
+   * JSON schema of the action's args.
+   */
+  argsSchema: Record<string, unknown>;
+}
+
+/**
+ * Error returned from `callProviderAction` when the provider doesn't
+ * have the given action.
+ */
+export interface UnknownActionError {
+  kind: 'unknown_action';
+  configHash: string;
+  action: string;
+  validActions: string[];
+  message: string;
+}
@@ -0,0 +1,22 @@
+/**
+ * Item of the system tray menu.
+ */
+export type TrayMenuItem =
+  | {
+      type: 'action';
+      id: string;
+      label: string;
+      enabled: boolean;
+    }
+  | {
+      type: 'checkbox';
+      id: string;
+      label: string;
+      checked: boolean;
+    }
+  | {
+      type: 'submenu';
+      label: string;
+      items: TrayMenuItem[];
+    }
+  | { type: 'separator' };
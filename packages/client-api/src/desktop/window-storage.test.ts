@@ -0,0 +1,93 @@
+import assert from 'node:assert/strict';
+import { describe, it } from 'node:test';
+
+import { createWindowStorage } from './window-storage';
+
+/**
+ * In-memory `Storage`, since tests don't run in a webview.
+ */
+class MemoryStorage implements Storage {
+  private items = new Map<string, string>();
+
+  get length() {
+    return this.items.size;
+  }
+
+  key(index: number) {
+    return [...this.items.keys()][index] ?? null;
+  }
+
+  getItem(key: string) {
+    return this.items.get(key) ?? null;
+  }
+
+  setItem(key: string, value: string) {
+    this.items.set(key, value);
+  }
+
+  removeItem(key: string) {
+    this.items.delete(key);
+  }
+
+  clear() {
+    this.items.clear();
+  }
+}
+
+describe('createWindowStorage', () => {
+  it('keeps items of windows separate', () => {
+    const storage = new MemoryStorage();
+    const first = createWindowStorage(storage, '1-bar');
+    const second = createWindowStorage(storage, '2-bar');
+
+    first.setItem('theme', 'dark');
+    second.setItem('theme', 'light');
+
+    assert.equal(first.getItem('theme'), 'dark');
+    assert.equal(second.getItem('theme'), 'light');
+    assert.equal(storage.getItem('theme'), null);
+  });
+
+  it('lists keys without the prefix', () => {
+    const storage = new MemoryStorage();
+    const windowStorage = createWindowStorage(storage, '1-bar');
+
+    windowStorage.setItem('a', '1');
+    windowStorage.setItem('b', '2');
+    storage.setItem('unrelated', '3');
+
+    assert.deepEqual(windowStorage.keys(), ['a', 'b']);
+  });
+
+  it("doesn't match windows whose label shares a prefix", () => {
+    const storage = new MemoryStorage();
+    createWindowStorage(storage, '1-bar-2').setItem('a', '1');
+
+    assert.deepEqual(createWindowStorage(storage, '1-bar').keys(), []);
+  });
+
+  it("clears only the window's items", () => {
+    const storage = new MemoryStorage();
+    const first = createWindowStorage(storage, '1-bar');
+    const second = createWindowStorage(storage, '2-bar');
+
+    first.setItem('a', '1');
+    first.setItem('b', '2');
+    second.setItem('a', '3');
+    first.clear();
+
+    assert.deepEqual(first.keys(), []);
+    assert.equal(second.getItem('a'), '3');
+  });
+
+  it('removes items', () => {
+    const storage = new MemoryStorage();
+    const windowStorage = createWindowStorage(storage, '1-bar');
+
+    windowStorage.setItem('a', '1');
+    windowStorage.removeItem('a');
+
+    assert.equal(windowStorage.getItem('a'), null);
+    assert.equal(storage.length, 0);
+  });
+});
@@ -0,0 +1,58 @@
+import { getCurrentWindow } from '@tauri-apps/api/window';
+
+/**
+ * Key-value storage that's scoped to a single window.
+ */
+export interface WindowStorage {
+  getItem(key: string): string | null;
+  setItem(key: string, value: string): void;
+  removeItem(key: string): void;
+
+  /** Removes all items of the window, leaving other windows' as is. */
+  clear(): void;
+
+  /** Keys of the window's items, without the namespace prefix. */
+  keys(): string[];
+}
+
+/**
+ * Gets `localStorage` namespaced by the current window's label.
+ *
+ * All windows share a webview profile, and therefore the same
+ * `localStorage`. Widgets should use this instead, so that multiple
+ * windows (e.g. the same bar on each monitor) don't overwrite each
+ * other's state.
+ */
+export function getWindowStorage(): WindowStorage {
+  return createWindowStorage(localStorage, getCurrentWindow().label);
+}
+
+export function createWindowStorage(
+  storage: Storage,
+  windowLabel: string,
+): WindowStorage {
+  const prefix = `zebar/${windowLabel}/`;
+
+  function keys() {
+    const keys: string[] = [];
+
+    for (let i = 0; i < storage.length; i++) {
+      const key = storage.key(i);
+
+      if (key?.startsWith(prefix)) {
+        keys.push(key.slice(prefix.length));
+      }
+    }
+
+    return keys;
+  }
+
+  return {
+    getItem: key => storage.getItem(prefix + key),
+    setItem: (key, value) => storage.setItem(prefix + key, value),
+    removeItem: key => storage.removeItem(prefix + key),
+    // Keys are collected first, since removing items shifts indices.
+    clear: () => keys().forEach(key => storage.removeItem(prefix + key)),
+    keys,
+  };
+}
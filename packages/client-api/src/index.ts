@@ -1,4 +1,4 @@
-export { createLogger, toCssSelector } from './utils';
+export { canonicalHash, createLogger, toCssSelector } from './utils';
 export {
   getScriptManager,
   getChildConfigs,
@@ -18,3 +18,18 @@ export {
 } from './element-context.model';
 export { ElementType } from './element-type.model';
 export { initWindow, initWindowAsync } from './init-window';
+export {
+  getAvailableProviders,
+  getEmissionStats,
+  getWindowStorage,
+  isPlaygroundWindow,
+  isSafeMode,
+  listenProvider,
+  onRawProviderEmit,
+  refreshProvider,
+  unlistenProvider,
+  type AvailableProvider,
+  type EmissionStats,
+  type ProviderEmitEvent,
+  type WindowStorage,
+} from './desktop';
@@ -10,9 +10,13 @@ import {
 } from './user-config';
 import {
   getOpenWindowArgs,
+  isSafeMode,
+  onConfigReload,
+  setCornerPreference,
   setWindowPosition,
   setWindowStyles,
   showErrorDialog,
+  windowReady,
   type WindowPosition,
   type WindowStyles,
 } from './desktop';
@@ -46,6 +50,15 @@ export async function initWindowAsync(): Promise<WindowContext> {
     const config = await getUserConfig();
     const styleBuilder = getStyleBuilder();
 
+    // Open args are injected before any page scripts run, so this should
+    // only happen if the page wasn't opened by Zebar.
+    if (!window.__ZEBAR_OPEN_ARGS) {
+      logger.warn(
+        'Open args were not injected at document start. Falling back to ' +
+          '`get_open_window_args`.',
+      );
+    }
+
     const openArgs =
       window.__ZEBAR_OPEN_ARGS ??
       (await getOpenWindowArgs(getCurrentWindow().label));
@@ -62,9 +75,10 @@ export async function initWindowAsync(): Promise<WindowContext> {
       );
     }
 
+    // Windows in safe mode use the default global settings.
     const globalConfig = parseWithSchema(
       GlobalConfigSchema.strip(),
-      (config as UserConfig)?.global ?? {},
+      isSafeMode() ? {} : ((config as UserConfig)?.global ?? {}),
     );
 
     const windowContext = (await initElement({
@@ -109,18 +123,41 @@ export async function initWindowAsync(): Promise<WindowContext> {
           resizable: windowContext.parsedConfig.resizable,
         };
 
-        const position: Partial<WindowPosition> = {
-          x: windowContext.parsedConfig.position_x,
-          y: windowContext.parsedConfig.position_y,
-          width: windowContext.parsedConfig.width,
-          height: windowContext.parsedConfig.height,
-        };
+        // Windows in safe mode keep their default geometry.
+        const position: Partial<WindowPosition> = isSafeMode()
+          ? {}
+          : {
+              x: windowContext.parsedConfig.position_x,
+              y: windowContext.parsedConfig.position_y,
+              width: windowContext.parsedConfig.width,
+              height: windowContext.parsedConfig.height,
+            };
+
+        const cornerPreference =
+          windowContext.parsedConfig.corner_preference;
 
         await setWindowStyles(styles);
         await setWindowPosition(position);
+        await windowReady();
+
+        // Overrides the default that is applied once the window is ready.
+        if (cornerPreference) {
+          await setCornerPreference(cornerPreference);
+        }
       });
     });
 
+    // Reload the window when its config changes.
+    onConfigReload(({ changed }) => {
+      if (
+        changed.includes('global') ||
+        changed.includes(`window/${windowId}`)
+      ) {
+        logger.info('Config changed. Reloading window.');
+        window.location.reload();
+      }
+    });
+
     return windowContext;
   } catch (err) {
     logger.error('Failed to initialize window:', err);
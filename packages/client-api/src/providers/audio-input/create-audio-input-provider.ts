@@ -0,0 +1,87 @@
+import type { Owner } from 'solid-js';
+
+import type { AudioInputProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+
+export interface AudioInputVariables {
+  /**
+   * Name of the default input device. `null` if there's no input
+   * device.
+   */
+  deviceName: string | null;
+
+  /**
+   * Whether the default input device is muted. `null` if the mute state
+   * can't be read on the current platform (e.g. on macOS).
+   */
+  isMuted: boolean | null;
+
+  /**
+   * Whether levels are being captured. `false` if `capture_levels` is
+   * disabled, or if the device couldn't be opened.
+   */
+  isCapturing: boolean;
+
+  /**
+   * Most recent level. `null` if levels aren't captured.
+   */
+  level: AudioLevel | null;
+
+  /**
+   * Levels measured since the previous emission, oldest first. Only set
+   * if `capture_levels` is enabled.
+   */
+  levels: TimedAudioLevel[] | null;
+}
+
+export interface AudioLevel {
+  /**
+   * Peak amplitude from 0 to 1.
+   */
+  peak: number;
+
+  /**
+   * RMS amplitude from 0 to 1.
+   */
+  rms: number;
+
+  /**
+   * Whether any sample reached full scale.
+   */
+  isClipping: boolean;
+}
+
+export interface TimedAudioLevel extends AudioLevel {
+  /**
+   * Unix timestamp in milliseconds of when the level was measured.
+   */
+  timestamp: number;
+}
+
+export async function createAudioInputProvider(
+  config: AudioInputProviderConfig,
+  owner: Owner,
+) {
+  const audioInputVariables = await createProviderListener<
+    AudioInputProviderConfig,
+    AudioInputVariables
+  >(config, owner);
+
+  return {
+    get deviceName() {
+      return audioInputVariables().deviceName;
+    },
+    get isMuted() {
+      return audioInputVariables().isMuted;
+    },
+    get isCapturing() {
+      return audioInputVariables().isCapturing;
+    },
+    get level() {
+      return audioInputVariables().level;
+    },
+    get levels() {
+      return audioInputVariables().levels;
+    },
+  };
+}
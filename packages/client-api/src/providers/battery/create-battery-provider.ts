@@ -3,7 +3,22 @@ import type { Owner } from 'solid-js';
 import type { BatteryProviderConfig } from '~/user-config';
 import { createProviderListener } from '../create-provider-listener';
 
-export interface BatteryVariables {
+export interface BatteryVariables extends Partial<BatteryInfo> {
+  hasBattery: boolean;
+  batteries: BatteryInfo[];
+  peripherals: PeripheralBattery[];
+}
+
+export interface PeripheralBattery {
+  id: string;
+  name: string;
+  kind: 'mouse' | 'keyboard' | 'headset' | 'other';
+  chargePercent: number;
+  isCharging: boolean;
+  band: 'critical' | 'low' | 'normal';
+}
+
+export interface BatteryInfo {
   chargePercent: number;
   cycleCount: number;
   healthPercent: number;
@@ -13,6 +28,19 @@ export interface BatteryVariables {
   timeTillEmpty: number | null;
   timeTillFull: number | null;
   voltage: number | null;
+  isUps: boolean;
+  estimatedRuntime: number | null;
+
+  /**
+   * Charge limit in percent that's set via vendor tools (e.g. ThinkPad
+   * charge thresholds). `null` if there's no limit or no vendor support.
+   */
+  chargeLimit: number | null;
+
+  /**
+   * Whether the battery isn't charging because it reached `chargeLimit`.
+   */
+  chargeLimited: boolean;
 }
 
 export async function createBatteryProvider(
@@ -25,6 +53,27 @@ export async function createBatteryProvider(
   >(config, owner);
 
   return {
+    get hasBattery() {
+      return batteryVariables().hasBattery;
+    },
+    get batteries() {
+      return batteryVariables().batteries;
+    },
+    get peripherals() {
+      return batteryVariables().peripherals;
+    },
+    get isUps() {
+      return batteryVariables().isUps;
+    },
+    get estimatedRuntime() {
+      return batteryVariables().estimatedRuntime;
+    },
+    get chargeLimit() {
+      return batteryVariables().chargeLimit;
+    },
+    get chargeLimited() {
+      return batteryVariables().chargeLimited;
+    },
     get chargePercent() {
       return batteryVariables().chargePercent;
     },
@@ -0,0 +1,48 @@
+import type { Owner } from 'solid-js';
+
+import type { ClipboardProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+import { setClipboard } from '~/desktop';
+
+export interface ClipboardVariables {
+  contentType: ClipboardContentType;
+  preview: string | null;
+  history: ClipboardHistoryEntry[];
+  historyCount: number;
+}
+
+export type ClipboardContentType = 'text' | 'image' | 'files' | 'empty';
+
+export interface ClipboardHistoryEntry {
+  contentType: ClipboardContentType;
+  preview: string | null;
+  copiedAt: number;
+}
+
+export async function createClipboardProvider(
+  config: ClipboardProviderConfig,
+  owner: Owner,
+) {
+  const clipboardVariables = await createProviderListener<
+    ClipboardProviderConfig,
+    ClipboardVariables
+  >(config, owner);
+
+  return {
+    get contentType() {
+      return clipboardVariables().contentType;
+    },
+    get preview() {
+      return clipboardVariables().preview;
+    },
+    get history() {
+      return clipboardVariables().history;
+    },
+    get historyCount() {
+      return clipboardVariables().historyCount;
+    },
+    copy(text: string) {
+      return setClipboard(text);
+    },
+  };
+}
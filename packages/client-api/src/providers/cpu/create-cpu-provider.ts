@@ -5,10 +5,49 @@ import { createProviderListener } from '../create-provider-listener';
 
 export interface CpuVariables {
   frequency: number;
+
+  /**
+   * Average usage over the refresh interval, from samples taken every
+   * 500ms.
+   */
   usage: number;
+
+  /**
+   * Highest sampled usage over the refresh interval.
+   */
+  usageMax: number;
+
+  /**
+   * Most recently sampled usage.
+   */
+  usageLatest: number;
+
   logicalCoreCount: number;
   physicalCoreCount: number;
   vendor: string;
+  loadAverage: LoadAverage | null;
+  contextSwitchesPerSec: number | null;
+  interruptsPerSec: number | null;
+
+  /**
+   * Samples taken since the previous refresh. Only set if
+   * `high_frequency` is enabled.
+   */
+  samples: CpuSample[] | null;
+}
+
+export interface CpuSample {
+  /**
+   * Unix timestamp in milliseconds of when the sample was taken.
+   */
+  timestamp: number;
+  usage: number;
+}
+
+export interface LoadAverage {
+  one: number;
+  five: number;
+  fifteen: number;
 }
 
 export async function createCpuProvider(
@@ -27,6 +66,12 @@ export async function createCpuProvider(
     get usage() {
       return cpuVariables().usage;
     },
+    get usageMax() {
+      return cpuVariables().usageMax;
+    },
+    get usageLatest() {
+      return cpuVariables().usageLatest;
+    },
     get logicalCoreCount() {
       return cpuVariables().logicalCoreCount;
     },
@@ -36,5 +81,17 @@ export async function createCpuProvider(
     get vendor() {
       return cpuVariables().vendor;
     },
+    get loadAverage() {
+      return cpuVariables().loadAverage;
+    },
+    get contextSwitchesPerSec() {
+      return cpuVariables().contextSwitchesPerSec;
+    },
+    get interruptsPerSec() {
+      return cpuVariables().interruptsPerSec;
+    },
+    get samples() {
+      return cpuVariables().samples;
+    },
   };
 }
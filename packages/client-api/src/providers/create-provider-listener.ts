@@ -13,7 +13,7 @@ import {
   unlistenProvider,
 } from '~/desktop';
 import type { ProviderConfig } from '~/user-config';
-import { simpleHash } from '~/utils';
+import { getProviderConfigHash } from './provider-config-hash';
 
 /**
  * Utility for listening to a provider of a given config type.
@@ -25,7 +25,7 @@ export function createProviderListener<
   return new Promise(async resolve => {
     const [payload, setPayload] = createSignal<TVars>();
 
-    const configHash = simpleHash(config);
+    const configHash = getProviderConfigHash(config);
     const unlisten = await onProviderEmit<TVars>(configHash, setPayload);
 
     await listenProvider({
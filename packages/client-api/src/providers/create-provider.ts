@@ -1,18 +1,29 @@
 import type { Owner } from 'solid-js';
 
+import { createAudioInputProvider } from './audio-input/create-audio-input-provider';
 import { createBatteryProvider } from './battery/create-battery-provider';
+import { createClipboardProvider } from './clipboard/create-clipboard-provider';
 import { createCpuProvider } from './cpu/create-cpu-provider';
 import { createDateProvider } from './date/create-date-provider';
 import { createGlazeWmProvider } from './glazewm/create-glazewm-provider';
 import { createHostProvider } from './host/create-host-provider';
 import { createIpProvider } from './ip/create-ip-provider';
 import { createKomorebiProvider } from './komorebi/create-komorebi-provider';
+import { createKubernetesProvider } from './kubernetes/create-kubernetes-provider';
 import { createMemoryProvider } from './memory/create-memory-provider';
 import { createMonitorsProvider } from './monitors/create-monitors-provider';
 import { createNetworkProvider } from './network/create-network-provider';
+import { createOpenRgbProvider } from './openrgb/create-openrgb-provider';
+import { createRemovableDrivesProvider } from './removable-drives/create-removable-drives-provider';
 import { createSelfProvider } from './self/create-self-provider';
+import { createStateProvider } from './state/create-state-provider';
+import { createSteamProvider } from './steam/create-steam-provider';
+import { createSummaryProvider } from './summary/create-summary-provider';
+import { createSystemStateProvider } from './system-state/create-system-state-provider';
+import { createTailProvider } from './tail/create-tail-provider';
 import { createUtilProvider } from './util/create-util-provider';
 import { createWeatherProvider } from './weather/create-weather-provider';
+import { createWindowsListProvider } from './windows-list/create-windows-list-provider';
 import { ProviderType, type ProviderConfig } from '~/user-config';
 import type { ElementContext } from '~/element-context.model';
 import type { PickPartial } from '~/utils';
@@ -26,12 +37,23 @@ export async function createProvider(
   owner: Owner,
 ) {
   switch (config.type) {
+    case ProviderType.AUDIO_INPUT:
+      return createAudioInputProvider(config, owner);
     case ProviderType.BATTERY:
       return createBatteryProvider(config, owner);
+    case ProviderType.CLIPBOARD:
+      return createClipboardProvider(config, owner);
     case ProviderType.CPU:
       return createCpuProvider(config, owner);
     case ProviderType.DATE:
-      return createDateProvider(config, owner);
+      // Falls back to the locale from the global config.
+      return createDateProvider(
+        {
+          ...config,
+          locale: config.locale ?? elementContext.globalConfig.locale,
+        },
+        owner,
+      );
     case ProviderType.GLAZEWM:
       return createGlazeWmProvider(config, owner);
     case ProviderType.HOST:
@@ -40,18 +62,36 @@ export async function createProvider(
       return createIpProvider(config, owner);
     case ProviderType.KOMOREBI:
       return createKomorebiProvider(config, owner);
+    case ProviderType.KUBERNETES:
+      return createKubernetesProvider(config, owner);
     case ProviderType.MEMORY:
       return createMemoryProvider(config, owner);
     case ProviderType.MONITORS:
       return createMonitorsProvider(config, owner);
     case ProviderType.NETWORK:
       return createNetworkProvider(config, owner);
+    case ProviderType.OPENRGB:
+      return createOpenRgbProvider(config, owner);
+    case ProviderType.REMOVABLE_DRIVES:
+      return createRemovableDrivesProvider(config, owner);
     case ProviderType.SELF:
       return createSelfProvider(elementContext);
+    case ProviderType.STATE:
+      return createStateProvider(config, owner);
+    case ProviderType.STEAM:
+      return createSteamProvider(config, owner);
+    case ProviderType.SUMMARY:
+      return createSummaryProvider(config, owner);
+    case ProviderType.SYSTEM_STATE:
+      return createSystemStateProvider(config, owner);
+    case ProviderType.TAIL:
+      return createTailProvider(config, owner);
     case ProviderType.UTIL:
       return createUtilProvider(config, owner);
     case ProviderType.WEATHER:
       return createWeatherProvider(config, owner);
+    case ProviderType.WINDOWS_LIST:
+      return createWindowsListProvider(config, owner);
     default:
       throw new Error('Not a supported provider type.');
   }
@@ -0,0 +1,138 @@
+import assert from 'node:assert/strict';
+import { afterEach, describe, it, mock } from 'node:test';
+import { DateTime } from 'luxon';
+
+import {
+  getNextTransition,
+  getValidZones,
+} from './create-date-provider';
+
+const HOUR_MS = 60 * 60 * 1000;
+
+function utc(iso: string) {
+  return DateTime.fromISO(iso, { zone: 'utc' }).toMillis();
+}
+
+function offsetAt(timezone: string, millis: number) {
+  return DateTime.fromMillis(millis).setZone(timezone).offset;
+}
+
+/**
+ * Transitions are narrowed down to within a second after the instant
+ * the offset changes.
+ */
+function expectTransitionAt(transition: number | null, expected: number) {
+  assert.notEqual(transition, null);
+  assert.ok(transition! >= expected);
+  assert.ok(transition! < expected + 1000);
+}
+
+describe('getNextTransition', () => {
+  it('finds the start of DST', () => {
+    // Clocks in Berlin go from 02:00 CET to 03:00 CEST.
+    const transition = getNextTransition(
+      'Europe/Berlin',
+      utc('2024-03-30T12:00:00'),
+    );
+
+    expectTransitionAt(transition, utc('2024-03-31T01:00:00'));
+
+    assert.equal(offsetAt('Europe/Berlin', transition!), 120);
+  });
+
+  it('finds the end of DST', () => {
+    // Clocks in New York go from 02:00 EDT back to 01:00 EST.
+    const transition = getNextTransition(
+      'America/New_York',
+      utc('2024-11-02T12:00:00'),
+    );
+
+    expectTransitionAt(transition, utc('2024-11-03T06:00:00'));
+
+    assert.equal(offsetAt('America/New_York', transition!), -300);
+  });
+
+  it('finds transitions of less than an hour', () => {
+    // Lord Howe Island shifts by 30 minutes.
+    const transition = getNextTransition(
+      'Australia/Lord_Howe',
+      utc('2024-04-06T00:00:00'),
+    );
+
+    expectTransitionAt(transition, utc('2024-04-06T15:00:00'));
+  });
+
+  it('finds a transition right after the start', () => {
+    const transition = getNextTransition(
+      'Europe/Berlin',
+      utc('2024-03-31T00:59:59'),
+    );
+
+    expectTransitionAt(transition, utc('2024-03-31T01:00:00'));
+  });
+
+  it('ignores transitions beyond the lookahead', () => {
+    assert.equal(
+      getNextTransition('Europe/Berlin', utc('2024-03-29T00:00:00')),
+      null,
+    );
+  });
+
+  it('ignores the transition that just happened', () => {
+    assert.equal(
+      getNextTransition('Europe/Berlin', utc('2024-03-31T01:00:00')),
+      null,
+    );
+  });
+
+  it('returns null for zones without DST', () => {
+    assert.equal(
+      getNextTransition('Asia/Kolkata', utc('2024-03-30T12:00:00')),
+      null,
+    );
+  });
+
+  it('returns null for invalid zones', () => {
+    assert.equal(getNextTransition('Not/AZone', Date.now()), null);
+  });
+
+  it('finds the same transition regardless of the start', () => {
+    const expected = utc('2024-10-27T01:00:00');
+
+    for (let hours = 1; hours <= 47; hours += 7) {
+      expectTransitionAt(
+        getNextTransition('Europe/Berlin', expected - hours * HOUR_MS),
+        expected,
+      );
+    }
+  });
+});
+
+describe('getValidZones', () => {
+  afterEach(() => {
+    mock.restoreAll();
+  });
+
+  it('keeps valid zones in order', () => {
+    const zones = [
+      { timezone: 'Asia/Tokyo', label: 'Tokyo' },
+      { timezone: 'UTC' },
+      { timezone: 'America/Los_Angeles' },
+    ];
+
+    assert.deepEqual(getValidZones(zones), zones);
+  });
+
+  it('drops invalid zones with a warning', () => {
+    const warn = mock.method(console, 'warn', () => {});
+
+    const zones = getValidZones([
+      { timezone: 'Europe/Berlin' },
+      { timezone: 'Not/AZone' },
+      { timezone: 'Mars/Olympus_Mons' },
+    ]);
+
+    assert.deepEqual(zones, [{ timezone: 'Europe/Berlin' }]);
+    assert.equal(warn.mock.callCount(), 2);
+  });
+});
@@ -3,6 +3,17 @@ import { type Owner, onCleanup, runWithOwner } from 'solid-js';
 import { createStore } from 'solid-js/store';
 
 import type { DateProviderConfig } from '~/user-config';
+import { createLogger } from '~/utils';
+
+const logger = createLogger('date-provider');
+
+/**
+ * How far ahead to look for DST transitions. Transitions beyond this are
+ * picked up on a later tick.
+ */
+const TRANSITION_LOOKAHEAD_MS = 48 * 60 * 60 * 1000;
+
+const HOUR_MS = 60 * 60 * 1000;
 
 export interface DateVariables {
   /**
@@ -22,24 +33,76 @@ export interface DateVariables {
    * `2017-04-22T20:47:05.335-04:00`). Uses `date.toISOString()` under the hood.
    **/
   iso: string;
+
+  /**
+   * Current time in each of the configured `zones`.
+   **/
+  zones: ZoneTime[];
+}
+
+export interface ZoneTime {
+  label: string;
+
+  /**
+   * IANA timezone (eg. `Europe/Berlin`).
+   **/
+  timezone: string;
+
+  /**
+   * Current date/time in the zone as an ISO-8601 string.
+   **/
+  iso: string;
+
+  /**
+   * UTC offset currently in effect, in minutes (eg. `120` for UTC+2).
+   **/
+  offset: number;
+
+  /**
+   * UTC offset currently in effect as a string (eg. `+02:00`).
+   **/
+  offsetString: string;
+
+  /**
+   * Whether daylight saving time is currently in effect in the zone.
+   **/
+  isDst: boolean;
+
+  /**
+   * Difference in hours between the zone and the local timezone (eg. `9`
+   * for Berlin when local time is in San Francisco). Can be fractional
+   * for zones such as `Asia/Kolkata`.
+   **/
+  offsetFromLocal: number;
 }
 
 export async function createDateProvider(
   config: DateProviderConfig,
   owner: Owner,
 ) {
+  // Zones are validated once here, rather than on every refresh.
+  const zones = getValidZones(config.zones);
+
   const [dateVariables, setDateVariables] =
     createStore<DateVariables>(getDateVariables());
 
-  const interval = setInterval(
-    () => setDateVariables(getDateVariables()),
-    config.refresh_interval,
-  );
+  const interval = setInterval(refresh, config.refresh_interval);
+
+  // Offsets can change between ticks when a zone crosses a DST boundary,
+  // so an extra refresh is scheduled for the next transition.
+  let transitionTimeout = scheduleTransitionRefresh();
 
   runWithOwner(owner, () => {
-    onCleanup(() => clearInterval(interval));
+    onCleanup(() => {
+      clearInterval(interval);
+      clearTimeout(transitionTimeout);
+    });
   });
 
+  function refresh() {
+    setDateVariables(getDateVariables());
+  }
+
   function getDateVariables() {
     const date = new Date();
 
@@ -47,9 +110,51 @@ export async function createDateProvider(
       new: date,
       now: date.getTime(),
       iso: date.toISOString(),
+      zones: getZoneTimes(date.getTime()),
     };
   }
 
+  function getZoneTimes(now: number): ZoneTime[] {
+    // The local zone is read on every call, so that changes to the system
+    // timezone are reflected on the next refresh.
+    const local = DateTime.fromMillis(now);
+
+    return zones.map(zone => {
+      const dateTime = DateTime.fromMillis(now).setZone(zone.timezone);
+
+      return {
+        label: zone.label ?? zone.timezone,
+        timezone: zone.timezone,
+        iso: dateTime.toISO()!,
+        offset: dateTime.offset,
+        offsetString: dateTime.toFormat('ZZ'),
+        isDst: dateTime.isInDST,
+        offsetFromLocal: (dateTime.offset - local.offset) / 60,
+      };
+    });
+  }
+
+  function scheduleTransitionRefresh() {
+    const now = Date.now();
+
+    const transitions = [
+      ...zones.map(zone => zone.timezone),
+      DateTime.local().zoneName,
+    ]
+      .map(timezone => getNextTransition(timezone, now))
+      .filter((transition): transition is number => transition !== null);
+
+    const nextTransition = Math.min(
+      now + TRANSITION_LOOKAHEAD_MS,
+      ...transitions,
+    );
+
+    return setTimeout(() => {
+      refresh();
+      transitionTimeout = scheduleTransitionRefresh();
+    }, nextTransition - now + 1);
+  }
+
   function toFormat(now: number, format: string) {
     const dateTime = DateTime.fromMillis(now);
 
@@ -70,6 +175,74 @@ export async function createDateProvider(
     get iso() {
       return dateVariables.iso;
     },
+    get zones() {
+      return dateVariables.zones;
+    },
     toFormat,
   };
 }
+
+/**
+ * Filters out zones with invalid IANA timezones, with a warning for
+ * each.
+ */
+export function getValidZones<T extends { timezone: string }>(
+  zones: T[],
+): T[] {
+  return zones.filter(zone => {
+    const isValid = DateTime.local().setZone(zone.timezone).isValid;
+
+    if (!isValid) {
+      logger.warn(`Invalid timezone '${zone.timezone}'.`);
+    }
+
+    return isValid;
+  });
+}
+
+/**
+ * Gets the next instant (in milliseconds since epoch) at which the UTC
+ * offset of the timezone changes, if any within the lookahead window.
+ *
+ * Offsets are sampled hourly, since no zone changes its offset more than
+ * once an hour, and the transition is then narrowed down to the second.
+ */
+export function getNextTransition(
+  timezone: string,
+  from: number,
+): number | null {
+  const offsetAt = (millis: number) =>
+    DateTime.fromMillis(millis).setZone(timezone).offset;
+
+  const initialOffset = offsetAt(from);
+
+  if (Number.isNaN(initialOffset)) {
+    return null;
+  }
+
+  for (
+    let end = from + HOUR_MS;
+    end <= from + TRANSITION_LOOKAHEAD_MS;
+    end += HOUR_MS
+  ) {
+    if (offsetAt(end) === initialOffset) {
+      continue;
+    }
+
+    let start = end - HOUR_MS;
+
+    while (end - start > 1000) {
+      const mid = Math.floor((start + end) / 2);
+
+      if (offsetAt(mid) === initialOffset) {
+        start = mid;
+      } else {
+        end = mid;
+      }
+    }
+
+    return end;
+  }
+
+  return null;
+}
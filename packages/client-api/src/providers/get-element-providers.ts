@@ -10,6 +10,7 @@ import { createStore } from 'solid-js/store';
 import { ProvidersConfigSchema, parseWithSchema } from '~/user-config';
 import type { ElementContext } from '~/element-context.model';
 import { createProvider } from './create-provider';
+import { setProviderConfigHash } from './provider-config-hash';
 import type { PickPartial } from '~/utils';
 
 export async function getElementProviders(
@@ -35,10 +36,19 @@ export async function getElementProviders(
    * Get map of element providers.
    */
   async function getElementProviders() {
+    const rawConfigs =
+      (elementContext.rawConfig as Record<string, unknown>)?.providers ??
+      [];
+
     const providerConfigs = parseWithSchema(
       ProvidersConfigSchema,
-      (elementContext.rawConfig as Record<string, unknown>)?.providers ??
-        [],
+      rawConfigs,
+    );
+
+    // Identify providers by their config as written, which is how
+    // providers of the window are pre-created on the Rust side.
+    providerConfigs.forEach((config, index) =>
+      setProviderConfigHash(config, (rawConfigs as unknown[])[index]),
     );
 
     // Create tuple of configs and the created provider.
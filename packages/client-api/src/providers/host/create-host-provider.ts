@@ -1,5 +1,6 @@
 import type { Owner } from 'solid-js';
 
+import type { DisplaySession } from '~/desktop';
 import type { HostProviderConfig } from '~/user-config';
 import { createProviderListener } from '../create-provider-listener';
 
@@ -10,6 +11,21 @@ export interface HostVariables {
   friendlyOsVersion: string | null;
   bootTime: number;
   uptime: number;
+  machine: MachineInfo;
+
+  /**
+   * Display session that the app is running in (e.g. X11 or Wayland on
+   * Linux).
+   */
+  displaySession: DisplaySession;
+}
+
+export interface MachineInfo {
+  manufacturer: string | null;
+  model: string | null;
+  serialNumber: string | null;
+  assetTag: string | null;
+  requiresElevation: string[];
 }
 
 export async function createHostProvider(
@@ -40,5 +56,11 @@ export async function createHostProvider(
     get uptime() {
       return hostVariables().uptime;
     },
+    get machine() {
+      return hostVariables().machine;
+    },
+    get displaySession() {
+      return hostVariables().displaySession;
+    },
   };
 }
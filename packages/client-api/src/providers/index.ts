@@ -1,13 +1,24 @@
+export * from './audio-input/create-audio-input-provider';
 export * from './battery/create-battery-provider';
+export * from './clipboard/create-clipboard-provider';
 export * from './cpu/create-cpu-provider';
 export * from './date/create-date-provider';
 export * from './glazewm/create-glazewm-provider';
 export * from './ip/create-ip-provider';
+export * from './kubernetes/create-kubernetes-provider';
 export * from './memory/create-memory-provider';
 export * from './network/create-network-provider';
+export * from './openrgb/create-openrgb-provider';
+export * from './removable-drives/create-removable-drives-provider';
 export * from './self/create-self-provider';
+export * from './state/create-state-provider';
+export * from './steam/create-steam-provider';
+export * from './summary/create-summary-provider';
+export * from './system-state/create-system-state-provider';
+export * from './tail/create-tail-provider';
 export * from './util/create-util-provider';
 export * from './weather/create-weather-provider';
+export * from './windows-list/create-windows-list-provider';
 export * from './create-provider-listener';
 export * from './create-provider';
 export * from './get-element-providers';
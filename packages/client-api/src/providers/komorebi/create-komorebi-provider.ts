@@ -9,6 +9,8 @@ import { getCoordinateDistance } from '~/utils';
 interface KomorebiResponse {
   allMonitors: KomorebiMonitor[];
   focusedMonitorIndex: number;
+  resync: boolean;
+  selfMonitorFocused?: boolean;
 }
 
 export interface KomorebiProvider {
@@ -46,6 +48,18 @@ export interface KomorebiProvider {
    * Monitor that is nearest to this Zebar window.
    */
   currentMonitor: KomorebiMonitor;
+
+  /**
+   * Whether this Zebar window is on the monitor that currently has
+   * focus. Based on the window's position as tracked by the desktop app.
+   */
+  selfMonitorFocused: boolean;
+
+  /**
+   * Whether this is a fresh snapshot after Komorebi's config was
+   * reloaded. Any accumulated per-workspace UI state should be reset.
+   */
+  resync: boolean;
 }
 
 export interface KomorebiMonitor {
@@ -60,9 +74,26 @@ export interface KomorebiMonitor {
 }
 
 export interface KomorebiWorkspace {
+  /**
+   * Index of the workspace within its monitor in Komorebi. Unaffected by
+   * `hide_empty`.
+   */
+  index: number;
+
+  /**
+   * Label from `workspace_labels`, falling back to the workspace name.
+   */
+  displayLabel: string;
+
   containerPadding: number | null;
   floatingWindows: KomorebiWindow[];
   focusedContainerIndex: number;
+
+  /**
+   * Whether the workspace's focused window is a floating window.
+   */
+  focusedWindowIsFloating: boolean;
+
   latestLayout: KomorebiRect[];
   layout: KomorebiLayout;
   layoutFlip: KomorebiLayoutFlip | null;
@@ -76,6 +107,16 @@ export interface KomorebiWorkspace {
 export interface KomorebiContainer {
   id: string;
   windows: KomorebiWindow[];
+
+  /**
+   * Index of the focused window within `windows`.
+   */
+  focusedWindowIndex: number;
+
+  /**
+   * Whether the container holds more than one window.
+   */
+  isStacked: boolean;
 }
 
 export interface KomorebiWindow {
@@ -83,6 +124,14 @@ export interface KomorebiWindow {
   exe: string | null;
   hwnd: number;
   title: string | null;
+  isFloating: boolean;
+  isMinimized: boolean;
+
+  /**
+   * Index of the window within its container's stack. `null` for
+   * windows that aren't in a container (e.g. floating windows).
+   */
+  stackIndex: number | null;
 }
 
 export interface KomorebiRect {
@@ -173,6 +222,12 @@ export async function createKomorebiProvider(
       focusedMonitor: focusedKomorebiMonitor,
       currentMonitor: currentKomorebiMonitor,
       allMonitors: state.allMonitors,
+      resync: state.resync,
+      // Falls back to the nearest monitor until the window's position is
+      // known.
+      selfMonitorFocused:
+        state.selfMonitorFocused ??
+        focusedKomorebiMonitor.id === currentKomorebiMonitor.id,
     };
   }
 
@@ -198,5 +253,11 @@ export async function createKomorebiProvider(
     get currentMonitor() {
       return komorebiVariables.currentMonitor;
     },
+    get resync() {
+      return komorebiVariables.resync;
+    },
+    get selfMonitorFocused() {
+      return komorebiVariables.selfMonitorFocused;
+    },
   };
 }
@@ -0,0 +1,74 @@
+import type { Owner } from 'solid-js';
+
+import type { KubernetesProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+
+export interface KubernetesVariables {
+  /**
+   * Name of the active context. `null` if no context is set.
+   */
+  context: string | null;
+  cluster: string | null;
+  namespace: string | null;
+
+  /**
+   * Whether the cluster responded. `null` if the cluster isn't queried.
+   */
+  reachable: boolean | null;
+
+  /**
+   * Node counts. `null` if the cluster isn't queried, unreachable, or
+   * doesn't allow listing nodes.
+   */
+  nodes: NodeCounts | null;
+
+  /**
+   * Pod counts by phase, keyed by namespace. Namespaces that can't be
+   * queried are left out.
+   */
+  pods: Record<string, PodPhaseCounts>;
+}
+
+export interface NodeCounts {
+  total: number;
+  ready: number;
+}
+
+export interface PodPhaseCounts {
+  pending: number;
+  running: number;
+  succeeded: number;
+  failed: number;
+  unknown: number;
+}
+
+export async function createKubernetesProvider(
+  config: KubernetesProviderConfig,
+  owner: Owner,
+) {
+  const kubernetesVariables = await createProviderListener<
+    KubernetesProviderConfig,
+    KubernetesVariables
+  >(config, owner);
+
+  return {
+    get context() {
+      return kubernetesVariables().context;
+    },
+    get cluster() {
+      return kubernetesVariables().cluster;
+    },
+    get namespace() {
+      return kubernetesVariables().namespace;
+    },
+    get reachable() {
+      return kubernetesVariables().reachable;
+    },
+    get nodes() {
+      return kubernetesVariables().nodes;
+    },
+    get pods() {
+      return kubernetesVariables().pods;
+    },
+  };
+}
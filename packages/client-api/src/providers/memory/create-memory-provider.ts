@@ -11,6 +11,13 @@ export interface MemoryVariables {
   freeSwap: number;
   usedSwap: number;
   totalSwap: number;
+  topProcesses: MemoryProcess[] | null;
+}
+
+export interface MemoryProcess {
+  pid: number;
+  name: string;
+  memory: number;
 }
 
 export async function createMemoryProvider(
@@ -44,5 +51,8 @@ export async function createMemoryProvider(
     get totalSwap() {
       return memoryVariables().totalSwap;
     },
+    get topProcesses() {
+      return memoryVariables().topProcesses;
+    },
   };
 }
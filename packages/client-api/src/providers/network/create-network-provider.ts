@@ -7,7 +7,27 @@ export interface NetworkVariables {
   defaultInterface: NetworkInterface | null;
   defaultGateway: NetworkGateway | null;
   interfaces: NetworkInterface[];
+
+  /**
+   * Traffic since the previous refresh. `null` for the first refresh
+   * after the machine resumes from sleep or the clock is stepped.
+   */
   traffic: NetworkTraffic | null;
+  connections: NetworkConnections | null;
+  connectionCost: ConnectionCost | null;
+
+  /**
+   * Traffic samples taken since the previous refresh. Only set if
+   * `high_frequency` is enabled.
+   */
+  samples: NetworkTrafficSample[] | null;
+}
+
+export interface NetworkTrafficSample extends NetworkTraffic {
+  /**
+   * Unix timestamp in milliseconds of when the sample was taken.
+   */
+  timestamp: number;
 }
 
 export interface NetworkInterface {
@@ -51,11 +71,28 @@ export enum InterfaceType {
   BRIDGE = 'bridge',
 }
 
+export interface ConnectionCost {
+  isMetered: boolean;
+  isRoaming: boolean;
+}
+
 export interface NetworkTraffic {
   received: number | null;
   transmitted: number | null;
 }
 
+export interface NetworkConnections {
+  establishedCount: number;
+  topProcesses: NetworkProcess[];
+}
+
+export interface NetworkProcess {
+  pid: number;
+  name: string;
+  connectionCount: number;
+  throughput: number | null;
+}
+
 export async function createNetworkProvider(
   config: NetworkProviderConfig,
   owner: Owner,
@@ -78,5 +115,14 @@ export async function createNetworkProvider(
     get traffic() {
       return networkVariables().traffic;
     },
+    get connections() {
+      return networkVariables().connections;
+    },
+    get connectionCost() {
+      return networkVariables().connectionCost;
+    },
+    get samples() {
+      return networkVariables().samples;
+    },
   };
 }
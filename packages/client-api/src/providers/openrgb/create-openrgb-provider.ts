@@ -0,0 +1,49 @@
+import type { Owner } from 'solid-js';
+
+import type { OpenRgbProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+import { setLedColor } from '~/desktop';
+
+export interface OpenRgbVariables {
+  isConnected: boolean;
+  connectionError: string | null;
+  devices: OpenRgbDevice[];
+}
+
+export interface OpenRgbDevice {
+  id: number;
+  name: string;
+  deviceType: string;
+  zones: OpenRgbZone[];
+}
+
+export interface OpenRgbZone {
+  id: number;
+  name: string;
+  ledCount: number;
+}
+
+export async function createOpenRgbProvider(
+  config: OpenRgbProviderConfig,
+  owner: Owner,
+) {
+  const openRgbVariables = await createProviderListener<
+    OpenRgbProviderConfig,
+    OpenRgbVariables
+  >(config, owner);
+
+  return {
+    get isConnected() {
+      return openRgbVariables().isConnected;
+    },
+    get connectionError() {
+      return openRgbVariables().connectionError;
+    },
+    get devices() {
+      return openRgbVariables().devices;
+    },
+    setColor(device: number, zone: number, color: string) {
+      return setLedColor(device, zone, color);
+    },
+  };
+}
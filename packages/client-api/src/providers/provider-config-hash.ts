@@ -0,0 +1,31 @@
+import type { ProviderConfig } from '~/user-config';
+import { canonicalHash, simpleHash } from '~/utils';
+
+/**
+ * Hashes of parsed provider configs, derived from the configs as written
+ * in the user config.
+ */
+const configHashes = new WeakMap<ProviderConfig, string>();
+
+/**
+ * Sets the hash of a parsed provider config from its raw config, so that
+ * it matches the hash of the provider pre-created on the Rust side.
+ */
+export function setProviderConfigHash(
+  config: ProviderConfig,
+  rawConfig: unknown,
+) {
+  configHashes.set(
+    config,
+    canonicalHash(
+      typeof rawConfig === 'string' ? { type: rawConfig } : rawConfig,
+    ),
+  );
+}
+
+/**
+ * Gets the hash that a provider config is identified by.
+ */
+export function getProviderConfigHash(config: ProviderConfig): string {
+  return configHashes.get(config) ?? simpleHash(config);
+}
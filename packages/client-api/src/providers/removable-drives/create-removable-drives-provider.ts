@@ -0,0 +1,37 @@
+import type { Owner } from 'solid-js';
+
+import type { RemovableDrivesProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+import { ejectDrive } from '~/desktop';
+
+export interface RemovableDrivesVariables {
+  drives: RemovableDrive[];
+}
+
+export interface RemovableDrive {
+  label: string | null;
+  mountPoint: string;
+  fileSystem: string;
+  totalSpace: number;
+  availableSpace: number;
+  busType: string | null;
+}
+
+export async function createRemovableDrivesProvider(
+  config: RemovableDrivesProviderConfig,
+  owner: Owner,
+) {
+  const removableDrivesVariables = await createProviderListener<
+    RemovableDrivesProviderConfig,
+    RemovableDrivesVariables
+  >(config, owner);
+
+  return {
+    get drives() {
+      return removableDrivesVariables().drives;
+    },
+    eject(mountPoint: string) {
+      return ejectDrive(mountPoint);
+    },
+  };
+}
@@ -1,10 +1,22 @@
+import { getCurrentWindow } from '@tauri-apps/api/window';
+
+import {
+  getWindowProviders,
+  type ProviderSubscription,
+} from '~/desktop';
 import type { ElementContext } from '~/element-context.model';
 import type { PickPartial } from '~/utils';
 
 export type SelfProvider = PickPartial<
   ElementContext,
   'parsedConfig' | 'providers'
->;
+> & {
+  /**
+   * Gets the status of the providers that the window is subscribed to,
+   * including their approximate retained memory.
+   */
+  getProviderStatus: () => Promise<ProviderSubscription[]>;
+};
 
 export async function createSelfProvider(
   elementContext: PickPartial<
@@ -12,5 +24,12 @@ export async function createSelfProvider(
     'parsedConfig' | 'providers'
   >,
 ): Promise<SelfProvider> {
-  return elementContext;
+  // The element context is a store that is filled in after providers
+  // are created. Reads are delegated to it, so that they stay reactive.
+  return Object.create(elementContext, {
+    getProviderStatus: {
+      value: () => getWindowProviders(getCurrentWindow().label),
+      enumerable: true,
+    },
+  });
 }
@@ -0,0 +1,37 @@
+import type { Owner } from 'solid-js';
+
+import type { StateProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+import { stateIncrement, stateReset, stateSet } from '~/desktop';
+import { getProviderConfigHash } from '../provider-config-hash';
+
+export interface StateVariables {
+  value: unknown;
+}
+
+export async function createStateProvider(
+  config: StateProviderConfig,
+  owner: Owner,
+) {
+  const stateVariables = await createProviderListener<
+    StateProviderConfig,
+    StateVariables
+  >(config, owner);
+
+  const configHash = getProviderConfigHash(config);
+
+  return {
+    get value() {
+      return stateVariables().value;
+    },
+    set(path: string, value: unknown) {
+      return stateSet(configHash, path, value);
+    },
+    increment(path: string, delta: number = 1) {
+      return stateIncrement(configHash, path, delta);
+    },
+    reset() {
+      return stateReset(configHash);
+    },
+  };
+}
@@ -0,0 +1,83 @@
+import type { Owner } from 'solid-js';
+
+import type { SteamProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+
+export interface SteamVariables {
+  /**
+   * Whether a Steam installation was found.
+   */
+  installed: boolean;
+
+  /**
+   * Whether the Steam client is running.
+   */
+  running: boolean;
+
+  /**
+   * Game that is currently running. `null` if no game is running or
+   * Steam isn't running.
+   */
+  runningGame: SteamGame | null;
+
+  /**
+   * Downloads that are in progress or paused, with active downloads
+   * first.
+   */
+  downloads: SteamDownload[];
+}
+
+export interface SteamGame {
+  appId: number;
+
+  /**
+   * Name of the game. `null` if it isn't installed in a Steam library
+   * (e.g. a non-Steam shortcut).
+   */
+  name: string | null;
+}
+
+export interface SteamDownload {
+  appId: number;
+  name: string;
+  bytesDownloaded: number;
+  bytesToDownload: number;
+
+  /**
+   * Progress from 0 to 100.
+   */
+  progress: number;
+
+  /**
+   * Download rate in bytes per second. `null` until the progress has
+   * been updated at least twice, or if the download is paused.
+   */
+  bytesPerSec: number | null;
+
+  isPaused: boolean;
+}
+
+export async function createSteamProvider(
+  config: SteamProviderConfig,
+  owner: Owner,
+) {
+  const steamVariables = await createProviderListener<
+    SteamProviderConfig,
+    SteamVariables
+  >(config, owner);
+
+  return {
+    get installed() {
+      return steamVariables().installed;
+    },
+    get running() {
+      return steamVariables().running;
+    },
+    get runningGame() {
+      return steamVariables().runningGame;
+    },
+    get downloads() {
+      return steamVariables().downloads;
+    },
+  };
+}
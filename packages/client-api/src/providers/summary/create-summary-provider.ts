@@ -0,0 +1,47 @@
+import type { Owner } from 'solid-js';
+
+import type { SummaryProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+
+export interface SummaryVariables {
+  cpu: number | null;
+  memory: number | null;
+  battery: SummaryBattery | null;
+  network: SummaryNetwork | null;
+}
+
+export interface SummaryBattery {
+  chargePercent: number;
+  state: 'discharging' | 'charging' | 'full' | 'empty' | 'unknown';
+  isCharging: boolean;
+}
+
+export interface SummaryNetwork {
+  received: number;
+  transmitted: number;
+}
+
+export async function createSummaryProvider(
+  config: SummaryProviderConfig,
+  owner: Owner,
+) {
+  const summaryVariables = await createProviderListener<
+    SummaryProviderConfig,
+    SummaryVariables
+  >(config, owner);
+
+  return {
+    get cpu() {
+      return summaryVariables().cpu;
+    },
+    get memory() {
+      return summaryVariables().memory;
+    },
+    get battery() {
+      return summaryVariables().battery;
+    },
+    get network() {
+      return summaryVariables().network;
+    },
+  };
+}
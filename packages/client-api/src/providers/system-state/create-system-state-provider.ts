@@ -0,0 +1,36 @@
+import type { Owner } from 'solid-js';
+
+import type { SystemStateProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+
+/**
+ * States are `null` on platforms or OS versions that don't report
+ * them.
+ */
+export interface SystemStateVariables {
+  gameModeActive: boolean | null;
+  energySaverActive: boolean | null;
+  presentationModeActive: boolean | null;
+}
+
+export async function createSystemStateProvider(
+  config: SystemStateProviderConfig,
+  owner: Owner,
+) {
+  const systemStateVariables = await createProviderListener<
+    SystemStateProviderConfig,
+    SystemStateVariables
+  >(config, owner);
+
+  return {
+    get gameModeActive() {
+      return systemStateVariables().gameModeActive;
+    },
+    get energySaverActive() {
+      return systemStateVariables().energySaverActive;
+    },
+    get presentationModeActive() {
+      return systemStateVariables().presentationModeActive;
+    },
+  };
+}
@@ -0,0 +1,81 @@
+import type { Owner } from 'solid-js';
+
+import type { TailProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+import { tailResetCounts } from '~/desktop';
+import { getProviderConfigHash } from '../provider-config-hash';
+
+export interface TailVariables {
+  path: string;
+
+  /**
+   * Whether the file currently exists. Missing files are picked up once
+   * they're created.
+   */
+  exists: boolean;
+
+  /**
+   * Number of matches per pattern since the provider started or its
+   * counts were last reset.
+   */
+  counts: Record<string, number>;
+
+  /**
+   * Most recent matches, oldest first.
+   */
+  matches: TailMatch[];
+}
+
+export interface TailMatch {
+  line: string;
+
+  /**
+   * Whether the line was cut off at `max_line_length`.
+   */
+  isTruncated: boolean;
+
+  /**
+   * Names of the patterns that matched the line.
+   */
+  patterns: string[];
+
+  /**
+   * Named capture groups of the matching patterns.
+   */
+  captures: Record<string, string>;
+
+  /**
+   * Unix timestamp in milliseconds of when the line was read.
+   */
+  matchedAt: number;
+}
+
+export async function createTailProvider(
+  config: TailProviderConfig,
+  owner: Owner,
+) {
+  const tailVariables = await createProviderListener<
+    TailProviderConfig,
+    TailVariables
+  >(config, owner);
+
+  const configHash = getProviderConfigHash(config);
+
+  return {
+    get path() {
+      return tailVariables().path;
+    },
+    get exists() {
+      return tailVariables().exists;
+    },
+    get counts() {
+      return tailVariables().counts;
+    },
+    get matches() {
+      return tailVariables().matches;
+    },
+    resetCounts() {
+      return tailResetCounts(configHash);
+    },
+  };
+}
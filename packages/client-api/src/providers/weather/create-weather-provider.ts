@@ -1,32 +1,124 @@
 import type { Owner } from 'solid-js';
 
-import { ProviderType, type WeatherProviderConfig } from '~/user-config';
+import {
+  isProviderOutputRef,
+  ProviderType,
+  type WeatherProviderConfig,
+} from '~/user-config';
 import {
   type IpVariables,
   createIpProvider,
 } from '../ip/create-ip-provider';
+import { PressureTrend } from './pressure-trend.enum';
 import { WeatherStatus } from './weather-status.enum';
 import { createProviderListener } from '../create-provider-listener';
 
-export interface WeatherVariables {
+export interface WeatherVariables extends WeatherConditions {
+  locations: Record<string, LocationWeatherResult>;
+
+  /**
+   * Precipitation in mm/h for each minute of the next hour. `null` if
+   * there's no nowcast coverage at the location.
+   */
+  precipitationNowcast: number[] | null;
+
+  /**
+   * Minutes until precipitation starts, if it's currently dry.
+   */
+  precipitationStartingInMinutes: number | null;
+
+  /**
+   * Minutes until precipitation stops, if it's currently precipitating.
+   */
+  precipitationStoppingInMinutes: number | null;
+}
+
+export interface WeatherConditions {
   isDaytime: boolean;
   status: WeatherStatus;
   celsiusTemp: number;
   fahrenheitTemp: number;
   windSpeed: number;
+
+  /**
+   * Apparent temperature, accounting for wind chill and humidity.
+   */
+  celsiusFeelsLikeTemp: number | null;
+  fahrenheitFeelsLikeTemp: number | null;
+
+  /**
+   * Relative humidity in percent.
+   */
+  humidity: number | null;
+
+  celsiusDewPoint: number | null;
+  fahrenheitDewPoint: number | null;
+  uvIndex: number | null;
+
+  /**
+   * Visibility in km, or in miles if `units` is `imperial`.
+   */
+  visibility: number | null;
+
+  /**
+   * Surface pressure in hPa, or in inHg if `units` is `imperial`.
+   */
+  pressure: number | null;
+
+  /**
+   * Trend of the surface pressure over the last few hours. `null` until
+   * enough samples have been retained across refreshes.
+   */
+  pressureTrend: PressureTrend | null;
+
+  resolvedLocation?: ResolvedLocation;
 }
 
+export interface ResolvedLocation {
+  query: string;
+  displayName: string;
+  country: string | null;
+  latitude: number;
+  longitude: number;
+}
+
+export type LocationWeatherResult =
+  | { data: WeatherConditions }
+  | { error: string };
+
 export async function createWeatherProvider(
   config: WeatherProviderConfig,
   owner: Owner,
 ) {
   let ipProvider: IpVariables | null = null;
 
-  const mergedConfig = {
-    ...config,
-    longitude: config.longitude ?? (await getIpProvider()).approxLongitude,
-    latitude: config.latitude ?? (await getIpProvider()).approxLatitude,
-  };
+  const refs = [config.latitude, config.longitude, config.location].filter(
+    isProviderOutputRef,
+  );
+
+  // Referenced IP providers need to be listened to first, so that the
+  // reference can be resolved.
+  if (refs.some(ref => ref.from === ProviderType.IP)) {
+    await getIpProvider();
+  }
+
+  // Coordinates are only estimated if there's no place name or named
+  // locations to retrieve weather for instead.
+  const shouldEstimate =
+    refs.length === 0 &&
+    config.location === undefined &&
+    (config.locations.length === 0 ||
+      config.latitude !== undefined ||
+      config.longitude !== undefined);
+
+  const mergedConfig = shouldEstimate
+    ? {
+        ...config,
+        longitude:
+          config.longitude ?? (await getIpProvider()).approxLongitude,
+        latitude: config.latitude ?? (await getIpProvider()).approxLatitude,
+      }
+    : config;
 
   const weatherVariables = await createProviderListener<
     WeatherProviderConfig,
@@ -62,5 +154,47 @@ export async function createWeatherProvider(
     get windSpeed() {
       return weatherVariables().windSpeed;
     },
+    get celsiusFeelsLikeTemp() {
+      return weatherVariables().celsiusFeelsLikeTemp;
+    },
+    get fahrenheitFeelsLikeTemp() {
+      return weatherVariables().fahrenheitFeelsLikeTemp;
+    },
+    get humidity() {
+      return weatherVariables().humidity;
+    },
+    get celsiusDewPoint() {
+      return weatherVariables().celsiusDewPoint;
+    },
+    get fahrenheitDewPoint() {
+      return weatherVariables().fahrenheitDewPoint;
+    },
+    get uvIndex() {
+      return weatherVariables().uvIndex;
+    },
+    get visibility() {
+      return weatherVariables().visibility;
+    },
+    get pressure() {
+      return weatherVariables().pressure;
+    },
+    get pressureTrend() {
+      return weatherVariables().pressureTrend;
+    },
+    get resolvedLocation() {
+      return weatherVariables().resolvedLocation;
+    },
+    get locations() {
+      return weatherVariables().locations;
+    },
+    get precipitationNowcast() {
+      return weatherVariables().precipitationNowcast;
+    },
+    get precipitationStartingInMinutes() {
+      return weatherVariables().precipitationStartingInMinutes;
+    },
+    get precipitationStoppingInMinutes() {
+      return weatherVariables().precipitationStoppingInMinutes;
+    },
   };
 }
@@ -0,0 +1,5 @@
+export enum PressureTrend {
+  RISING = 'rising',
+  STEADY = 'steady',
+  FALLING = 'falling',
+}
@@ -0,0 +1,63 @@
+import type { Owner } from 'solid-js';
+
+import type { WindowsListProviderConfig } from '~/user-config';
+import { createProviderListener } from '../create-provider-listener';
+import { activateWindow, closeApp } from '~/desktop';
+
+export interface WindowsListVariables {
+  /**
+   * Windows that are shown in the alt-tab switcher, in the order that
+   * they were first seen.
+   */
+  windows: ListedWindow[];
+
+  /**
+   * Handle of the focused window, if it's in the list.
+   */
+  focusedHwnd: number | null;
+}
+
+export interface ListedWindow {
+  hwnd: number;
+  title: string;
+
+  /**
+   * File name of the executable (eg. `firefox.exe`). `null` if the
+   * process can't be inspected (eg. when it's elevated).
+   */
+  exeName: string | null;
+  exePath: string | null;
+
+  /**
+   * URL of the app icon as a PNG. Can be used directly as an `<img>`
+   * source.
+   */
+  iconUrl: string | null;
+  isFocused: boolean;
+  isMinimized: boolean;
+}
+
+export async function createWindowsListProvider(
+  config: WindowsListProviderConfig,
+  owner: Owner,
+) {
+  const windowsListVariables = await createProviderListener<
+    WindowsListProviderConfig,
+    WindowsListVariables
+  >(config, owner);
+
+  return {
+    get windows() {
+      return windowsListVariables().windows;
+    },
+    get focusedHwnd() {
+      return windowsListVariables().focusedHwnd;
+    },
+    focus(hwnd: number) {
+      return activateWindow(hwnd);
+    },
+    close(hwnd: number) {
+      return closeApp(hwnd);
+    },
+  };
+}
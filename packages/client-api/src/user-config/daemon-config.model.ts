@@ -0,0 +1,13 @@
+import { z } from 'zod';
+
+import { ProviderConfigSchema } from './window';
+
+export const DaemonConfigSchema = z.object({
+  /**
+   * Providers to run when Zebar is started via `zebar daemon`, keyed by
+   * name.
+   */
+  providers: z.record(ProviderConfigSchema).default({}),
+});
+
+export type DaemonConfig = z.infer<typeof DaemonConfigSchema>;
@@ -1,6 +1,7 @@
 import { convertFileSrc } from '@tauri-apps/api/core';
 import { join, homeDir } from '@tauri-apps/api/path';
 
+import { isSafeMode } from '~/desktop';
 import { createLogger } from '~/utils';
 import type { ElementContext } from '../element-context.model';
 
@@ -27,6 +28,11 @@ export function getScriptManager() {
 }
 
 async function loadScriptForFn(fnPath: string): Promise<any> {
+  // User scripts aren't loaded in safe mode.
+  if (isSafeMode()) {
+    return null;
+  }
+
   const { modulePath } = parseFnPath(fnPath);
   return resolveModule(modulePath);
 }
@@ -36,6 +42,13 @@ async function callFn(
   event: Event,
   context: ElementContext,
 ): Promise<any> {
+  if (isSafeMode()) {
+    logger.warn(
+      `Skipping '${fnPath}', since scripts are disabled in safe mode.`,
+    );
+    return null;
+  }
+
   const { modulePath, functionName } = parseFnPath(fnPath);
   const foundModule = await resolveModule(modulePath);
   const fn = foundModule[functionName];
@@ -5,6 +5,135 @@ import { BooleanLikeSchema } from './shared';
 export const GlobalConfigSchema = z
   .object({
     enable_devtools: BooleanLikeSchema.default(false),
+
+    /**
+     * Whether to block all outbound network requests from providers. Can
+     * also be enabled via the `--offline` CLI flag.
+     */
+    offline_mode: BooleanLikeSchema.default(false),
+
+    /**
+     * Delay in milliseconds before the first run of each provider.
+     */
+    provider_start_delay: z.coerce.number().default(0),
+
+    /**
+     * Max random delay in milliseconds that is added to the start delay of
+     * each provider. Staggers providers that start at the same time.
+     */
+    provider_jitter: z.coerce.number().default(500),
+
+    /**
+     * Whether to emit provider outputs from a dedicated thread with
+     * above-normal priority. Reduces stutter of widgets under heavy CPU
+     * load. Only has an effect on Windows.
+     */
+    high_priority_emit: BooleanLikeSchema.default(false),
+
+    /**
+     * Whether to include the relative age of provider outputs (e.g. `3m
+     * ago`) as `lastUpdatedRelative`, which is kept up to date without the
+     * providers refreshing.
+     */
+    last_updated_relative: BooleanLikeSchema.default(false),
+
+    /**
+     * Duration in milliseconds after which a provider run is logged as
+     * slow. Set to 0 to disable.
+     */
+    slow_provider_threshold: z.coerce.number().default(1000),
+
+    /**
+     * Size in megabytes of the state that a single provider retains (e.g.
+     * history buffers and caches) above which a warning is logged. Set to
+     * 0 to disable.
+     */
+    provider_memory_cap: z.coerce.number().default(64),
+
+    /**
+     * Whether to clear the caches of providers that exceed
+     * `provider_memory_cap`.
+     */
+    evict_over_memory_cap: BooleanLikeSchema.default(false),
+
+    /**
+     * How providers that use the network behave while the connection is
+     * metered. `throttle` runs them less often, `pause` stops their
+     * scheduled runs until the connection is unmetered.
+     */
+    metered_behavior: z
+      .enum(['ignore', 'throttle', 'pause'])
+      .default('ignore'),
+
+    /**
+     * Factor that refresh intervals are multiplied by when
+     * `metered_behavior` is `throttle`.
+     */
+    metered_interval_multiplier: z.coerce.number().default(4),
+
+    /**
+     * Factor that refresh intervals are multiplied by while energy
+     * saver is on. `1` to refresh as normal.
+     */
+    energy_saver_interval_multiplier: z.coerce.number().default(1),
+
+    /**
+     * Where secrets referenced via `{ secret: "<name>" }` are stored.
+     * `auto` uses the platform's keychain if available, and otherwise
+     * `secrets.yaml` in the config directory. Secrets are managed via
+     * `zebar secret set/get/delete`.
+     */
+    secrets_backend: z.enum(['auto', 'keychain', 'file']).default('auto'),
+
+    /**
+     * Hosts that each secret may be sent to by HTTP requests from
+     * windows, keyed by secret name (e.g. `github_token:
+     * ['api.github.com']`). Hosts can start with `*.` to match
+     * subdomains. Secrets without hosts can't be used in requests.
+     */
+    secret_hosts: z.record(z.array(z.string())).default({}),
+
+    /**
+     * Locale that strings formatted by the app are localized to (e.g.
+     * `de` or `en-GB`). Also the default locale of `date` providers.
+     * Defaults to the OS locale.
+     *
+     * Supported locales are `en`, `de`, `es`, `fr`, `ja`, `ko` and `zh`.
+     * Region subtags are accepted but only used by `date` providers.
+     */
+    locale: z.string().optional(),
+
+    /**
+     * Whether each window gets its own webview user data folder and
+     * browser process, instead of sharing them with other windows. Uses
+     * more memory, so it's mainly for debugging. Applies to windows
+     * opened afterwards.
+     */
+    isolated_webviews: BooleanLikeSchema.default(false),
+
+    /**
+     * Provider fields that are masked in outputs sent to windows while
+     * redaction is enabled (e.g. when streaming). Redaction can also be
+     * toggled from the system tray.
+     */
+    redaction: z
+      .object({
+        enabled: BooleanLikeSchema.default(false),
+
+        /**
+         * Value that redacted fields are replaced with.
+         */
+        placeholder: z.string().optional(),
+
+        /**
+         * Paths of the fields to redact, keyed by provider type (e.g.
+         * `network: [defaultGateway.ssid]`). `*` matches all elements of
+         * an array. Defaults to identifying fields of the `host`, `ip`
+         * and `network` providers.
+         */
+        fields: z.record(z.string(), z.array(z.string())).optional(),
+      })
+      .optional(),
   })
   .partial();
 
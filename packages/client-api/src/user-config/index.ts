@@ -1,9 +1,11 @@
+export * from './daemon-config.model';
 export * from './get-parsed-element-config';
 export * from './get-script-manager';
 export * from './get-style-builder';
 export * from './get-user-config';
 export * from './global-config.model';
 export * from './parse-with-schema';
+export * from './preset-config.model';
 export * from './shared';
 export * from './user-config.model';
 export * from './window';
@@ -0,0 +1,16 @@
+import { z } from 'zod';
+
+export const PresetConfigSchema = z.object({
+  /**
+   * ID of the window to open.
+   */
+  window: z.string(),
+
+  /**
+   * Arguments to pass to the window. Can be overridden via `--args` when
+   * opening the preset.
+   */
+  args: z.record(z.string()).default({}),
+});
+
+export type PresetConfig = z.infer<typeof PresetConfigSchema>;
@@ -1,12 +1,20 @@
 import { z } from 'zod';
 
 import { WindowConfigSchema } from './window';
+import { DaemonConfigSchema } from './daemon-config.model';
 import { GlobalConfigSchema } from './global-config.model';
+import { PresetConfigSchema } from './preset-config.model';
 import { withDynamicKey } from './shared';
 import type { Prettify } from '~/utils';
 
 export const UserConfigP1Schema = z.object({
   global: GlobalConfigSchema,
+  daemon: DaemonConfigSchema.optional(),
+
+  /**
+   * Named presets that can be opened via `zebar open @<name>`.
+   */
+  presets: z.record(PresetConfigSchema).optional(),
 });
 
 export type UserConfigP1 = Prettify<z.infer<typeof UserConfigP1Schema>>;
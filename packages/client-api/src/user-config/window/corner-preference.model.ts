@@ -0,0 +1,9 @@
+import { z } from 'zod';
+
+export const CornerPreferenceSchema = z.enum([
+  'default',
+  'round',
+  'square',
+]);
+
+export type CornerPreference = z.infer<typeof CornerPreferenceSchema>;
@@ -1,7 +1,9 @@
 export * from './base-element-config.model';
+export * from './corner-preference.model';
 export * from './element-events-config.model';
 export * from './group-config.model';
 export * from './provider-config.model';
+export * from './provider-output-ref.model';
 export * from './provider-type.model';
 export * from './providers';
 export * from './providers-config.model';
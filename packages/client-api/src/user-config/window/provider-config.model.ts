@@ -2,37 +2,117 @@ import { z } from 'zod';
 
 import type { Prettify } from '~/utils';
 import {
+  AudioInputProviderConfigSchema,
   BatteryProviderConfigSchema,
+  ClipboardProviderConfigSchema,
   CpuProviderConfigSchema,
   DateProviderConfigSchema,
   GlazeWmProviderConfigSchema,
   HostProviderConfigSchema,
   IpProviderConfigSchema,
   KomorebiProviderConfigSchema,
+  KubernetesProviderConfigSchema,
   MemoryProviderConfigSchema,
   MonitorsProviderConfigSchema,
   NetworkProviderConfigSchema,
+  OpenRgbProviderConfigSchema,
+  RemovableDrivesProviderConfigSchema,
   SelfProviderConfigSchema,
+  StateProviderConfigSchema,
+  SteamProviderConfigSchema,
+  SummaryProviderConfigSchema,
+  SystemStateProviderConfigSchema,
+  TailProviderConfigSchema,
   UtilProviderConfigSchema,
   WeatherProviderConfigSchema,
+  WindowsListProviderConfigSchema,
 } from './providers';
 
-export const ProviderConfigSchema = z.union([
+/**
+ * Options that are shared between all provider types.
+ */
+export const ProviderCommonConfigSchema = z.object({
+  /**
+   * Delay in milliseconds before the provider's first run. Overrides the
+   * global `provider_start_delay`.
+   */
+  start_delay: z.coerce.number().optional(),
+
+  /**
+   * Max random delay in milliseconds that is added to `start_delay`.
+   * Overrides the global `provider_jitter`.
+   */
+  jitter: z.coerce.number().optional(),
+
+  /**
+   * Static value that is emitted in place of data while the provider is
+   * erroring. The error is added under the `_error` key, and the kind of
+   * network failure (if any) under `_networkError`.
+   */
+  fallback: z.record(z.string(), z.unknown()).optional(),
+
+  /**
+   * Schedule on which the provider is refreshed in addition to its
+   * `refresh_interval`, as a cron expression (e.g. `0 7,13 * * *`) or a
+   * list of times on given days (e.g. `weekdays@07:00`).
+   */
+  schedule: z.string().optional(),
+
+  /**
+   * Window in milliseconds within which a trigger that was missed (e.g.
+   * while the machine was asleep) is still fired once.
+   */
+  schedule_grace: z.coerce.number().optional(),
+
+  /**
+   * Dependency that the provider's first run waits for after its start
+   * delay, either `network` or `process:<name>` (e.g.
+   * `process:komorebi.exe`).
+   */
+  wait_for: z
+    .string()
+    .regex(/^\s*(network|process:.+)\s*$/i)
+    .optional(),
+
+  /**
+   * Max time in milliseconds to wait for `wait_for`, after which the
+   * provider runs anyway and reports errors as usual.
+   */
+  wait_for_timeout: z.coerce.number().optional(),
+});
+
+export const ProviderTypeConfigSchema = z.union([
+  AudioInputProviderConfigSchema,
   BatteryProviderConfigSchema,
+  ClipboardProviderConfigSchema,
   CpuProviderConfigSchema,
   DateProviderConfigSchema,
   GlazeWmProviderConfigSchema,
   HostProviderConfigSchema,
   IpProviderConfigSchema,
   KomorebiProviderConfigSchema,
+  KubernetesProviderConfigSchema,
   MemoryProviderConfigSchema,
   MonitorsProviderConfigSchema,
   NetworkProviderConfigSchema,
+  OpenRgbProviderConfigSchema,
+  RemovableDrivesProviderConfigSchema,
   SelfProviderConfigSchema,
+  StateProviderConfigSchema,
+  SteamProviderConfigSchema,
+  SummaryProviderConfigSchema,
+  SystemStateProviderConfigSchema,
+  TailProviderConfigSchema,
   UtilProviderConfigSchema,
   WeatherProviderConfigSchema,
+  WindowsListProviderConfigSchema,
 ]);
 
+export const ProviderConfigSchema = z.intersection(
+  ProviderTypeConfigSchema,
+  ProviderCommonConfigSchema,
+);
+
 export type ProviderConfig = Prettify<
   z.infer<typeof ProviderConfigSchema>
 >;
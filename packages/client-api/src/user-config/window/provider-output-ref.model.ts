@@ -0,0 +1,34 @@
+import { z } from 'zod';
+
+/**
+ * Reference from a provider config field to a value in the output of
+ * another provider that the window listens to (e.g. `{ from: 'ip', path:
+ * 'approxLatitude' }`). The provider is started once the value is
+ * available, and is restarted when it changes.
+ */
+export const ProviderOutputRefSchema = z
+  .object({
+    /**
+     * Type or config hash of the source provider.
+     */
+    from: z.string(),
+
+    /**
+     * Dot-separated path to the value in the source's output.
+     */
+    path: z.string(),
+
+    /**
+     * Min change of a numeric value before the provider is restarted.
+     */
+    delta: z.coerce.number().optional(),
+  })
+  .strict();
+
+export type ProviderOutputRef = z.infer<typeof ProviderOutputRefSchema>;
+
+export function isProviderOutputRef(
+  value: unknown,
+): value is ProviderOutputRef {
+  return ProviderOutputRefSchema.safeParse(value).success;
+}
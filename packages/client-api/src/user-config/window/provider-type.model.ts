@@ -1,19 +1,30 @@
 import { z } from 'zod';
 
 export enum ProviderType {
+  AUDIO_INPUT = 'audio_input',
   BATTERY = 'battery',
+  CLIPBOARD = 'clipboard',
   CPU = 'cpu',
   DATE = 'date',
   GLAZEWM = 'glazewm',
   HOST = 'host',
   IP = 'ip',
   KOMOREBI = 'komorebi',
+  KUBERNETES = 'kubernetes',
   MEMORY = 'memory',
   MONITORS = 'monitors',
   NETWORK = 'network',
+  OPENRGB = 'openrgb',
+  REMOVABLE_DRIVES = 'removable_drives',
   SELF = 'self',
+  STATE = 'state',
+  STEAM = 'steam',
+  SUMMARY = 'summary',
+  SYSTEM_STATE = 'system_state',
+  TAIL = 'tail',
   UTIL = 'util',
   WEATHER = 'weather',
+  WINDOWS_LIST = 'windows_list',
 }
 
 export const ProviderTypeSchema = z.nativeEnum(ProviderType);
@@ -0,0 +1,32 @@
+import { z } from 'zod';
+
+import { BooleanLikeSchema } from '../../shared';
+import { ProviderType } from '../provider-type.model';
+
+export const AudioInputProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.AUDIO_INPUT),
+
+  /**
+   * Levels measured in between emissions are batched under `levels`,
+   * so a few emissions per second are enough for a smooth meter.
+   */
+  refresh_interval: z.coerce.number().default(250),
+
+  /**
+   * Whether to capture levels of the default input device. This opens
+   * the device, which shows the OS privacy indicator while a window is
+   * using the provider. Otherwise, only the device name and mute state
+   * are read.
+   */
+  capture_levels: BooleanLikeSchema.default(false),
+
+  /**
+   * Milliseconds of audio that each level is measured over, at up to
+   * 20 Hz.
+   */
+  level_interval: z.coerce.number().min(50).default(100),
+});
+
+export type AudioInputProviderConfig = z.infer<
+  typeof AudioInputProviderConfigSchema
+>;
@@ -6,6 +6,18 @@ export const BatteryProviderConfigSchema = z.object({
   type: z.literal(ProviderType.BATTERY),
 
   refresh_interval: z.coerce.number().default(5 * 1000),
+
+  /**
+   * Charge percentages at which to send a notification about low battery
+   * on peripheral devices.
+   */
+  low_battery_thresholds: z
+    .object({
+      mouse: z.coerce.number().optional(),
+      keyboard: z.coerce.number().optional(),
+      headset: z.coerce.number().optional(),
+    })
+    .default({}),
 });
 
 export type BatteryProviderConfig = z.infer<
@@ -0,0 +1,29 @@
+import { z } from 'zod';
+
+import { BooleanLikeSchema } from '../../shared';
+import { ProviderType } from '../provider-type.model';
+
+export const ClipboardProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.CLIPBOARD),
+
+  refresh_interval: z.coerce.number().default(500),
+
+  preview_max_length: z.coerce.number().default(50),
+
+  /**
+   * Regex patterns that are masked in text previews. Defaults to patterns
+   * matching common secrets when omitted.
+   */
+  redact_patterns: z.array(z.string()).optional(),
+
+  history: z
+    .object({
+      max_entries: z.coerce.number().default(20),
+      persist: BooleanLikeSchema.default(false),
+    })
+    .optional(),
+});
+
+export type ClipboardProviderConfig = z.infer<
+  typeof ClipboardProviderConfigSchema
+>;
@@ -6,6 +6,17 @@ export const CpuProviderConfigSchema = z.object({
   type: z.literal(ProviderType.CPU),
 
   refresh_interval: z.coerce.number().default(5 * 1000),
+
+  /**
+   * Opt-in sampling between refreshes, at up to 10 Hz. Samples are
+   * batched into each refresh under `samples`, so a `refresh_interval`
+   * of 500-1000ms is recommended.
+   */
+  high_frequency: z
+    .object({
+      sample_interval: z.coerce.number().min(100).default(250),
+    })
+    .optional(),
 });
 
 export type CpuProviderConfig = z.infer<typeof CpuProviderConfigSchema>;
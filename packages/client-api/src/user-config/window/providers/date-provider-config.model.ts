@@ -18,11 +18,29 @@ export const DateProviderConfigSchema = z.object({
   /**
    * An ISO-639-1 locale, which is either a 2-letter language code (eg. `en`) or
    * 4-letter language + country code (eg. `en-gb`). Affects the output of
-   * `toFormat()`.
+   * `toFormat()`. Defaults to `locale` in the global config, and otherwise
+   * the OS locale.
    *
    * A full list of ISO-639-1 locales can be found [here](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes#Table).
    */
   locale: z.string().optional(),
+
+  /**
+   * IANA timezones to show alongside the local time (eg. for a world
+   * clock). Offsets account for daylight saving time.
+   */
+  zones: z
+    .array(
+      z.object({
+        /**
+         * Display name of the zone (eg. `Berlin`). Defaults to the IANA
+         * timezone name.
+         */
+        label: z.string().optional(),
+        timezone: z.string(),
+      }),
+    )
+    .default([]),
 });
 
 export type DateProviderConfig = z.infer<typeof DateProviderConfigSchema>;
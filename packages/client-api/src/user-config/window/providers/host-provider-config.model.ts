@@ -1,11 +1,14 @@
 import { z } from 'zod';
 
+import { BooleanLikeSchema } from '../../shared';
 import { ProviderType } from '../provider-type.model';
 
 export const HostProviderConfigSchema = z.object({
   type: z.literal(ProviderType.HOST),
 
   refresh_interval: z.coerce.number().default(60 * 1000),
+
+  include_serial: BooleanLikeSchema.default(false),
 });
 
 export type HostProviderConfig = z.infer<typeof HostProviderConfigSchema>;
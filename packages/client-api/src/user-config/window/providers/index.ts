@@ -1,13 +1,24 @@
+export * from './audio-input-provider-config.model';
 export * from './battery-provider-config.model';
+export * from './clipboard-provider-config.model';
 export * from './cpu-provider-config.model';
 export * from './date-provider-config.model';
 export * from './glazewm-provider-config.model';
 export * from './host-provider-config.model';
 export * from './ip-provider-config.model';
 export * from './komorebi-provider-config.model';
+export * from './kubernetes-provider-config.model';
 export * from './memory-provider-config.model';
 export * from './monitors-provider-config.model';
 export * from './network-provider-config.model';
+export * from './openrgb-provider-config.model';
+export * from './removable-drives-provider-config.model';
 export * from './self-provider-config.model';
+export * from './state-provider-config.model';
+export * from './steam-provider-config.model';
+export * from './summary-provider-config.model';
+export * from './system-state-provider-config.model';
+export * from './tail-provider-config.model';
 export * from './util-provider-config.model';
 export * from './weather-provider-config.model';
+export * from './windows-list-provider-config.model';
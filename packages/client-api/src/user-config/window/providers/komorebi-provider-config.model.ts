@@ -1,9 +1,23 @@
 import { z } from 'zod';
 
+import { BooleanLikeSchema } from '../../shared';
 import { ProviderType } from '../provider-type.model';
 
 export const KomorebiProviderConfigSchema = z.object({
   type: z.literal(ProviderType.KOMOREBI),
+
+  /**
+   * Map of workspace names or indices to display labels (eg. to show an
+   * icon for workspace `1`). Names take precedence over indices, and
+   * indices are 0-based within each monitor.
+   */
+  workspace_labels: z.record(z.string()).default({}),
+
+  /**
+   * Whether to omit workspaces without any windows. The focused workspace
+   * is always included.
+   */
+  hide_empty: BooleanLikeSchema.default(false),
 });
 
 export type KomorebiProviderConfig = z.infer<
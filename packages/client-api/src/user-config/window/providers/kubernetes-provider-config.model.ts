@@ -0,0 +1,37 @@
+import { z } from 'zod';
+
+import { BooleanLikeSchema } from '../../shared';
+import { ProviderType } from '../provider-type.model';
+
+export const KubernetesProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.KUBERNETES),
+
+  refresh_interval: z.coerce.number().default(60 * 1000),
+
+  /**
+   * Whether to query the cluster of the active context for node and pod
+   * counts. Otherwise, only the kubeconfig is read.
+   */
+  query_cluster: BooleanLikeSchema.default(false),
+
+  /**
+   * Namespaces to count pods by phase in. Requires `query_cluster`.
+   */
+  namespaces: z.array(z.string()).default([]),
+
+  /**
+   * Timeout in milliseconds for queries to the cluster. Clusters that
+   * don't respond in time are reported as unreachable.
+   */
+  timeout: z.coerce.number().default(3000),
+
+  /**
+   * Path to the kubeconfig file. Defaults to `KUBECONFIG` or
+   * `~/.kube/config`.
+   */
+  kubeconfig: z.string().optional(),
+});
+
+export type KubernetesProviderConfig = z.infer<
+  typeof KubernetesProviderConfigSchema
+>;
@@ -6,6 +6,13 @@ export const MemoryProviderConfigSchema = z.object({
   type: z.literal(ProviderType.MEMORY),
 
   refresh_interval: z.coerce.number().default(5 * 1000),
+
+  top_processes: z
+    .object({
+      count: z.coerce.number().default(5),
+      refresh_interval: z.coerce.number().default(10 * 1000),
+    })
+    .optional(),
 });
 
 export type MemoryProviderConfig = z.infer<
@@ -6,6 +6,24 @@ export const NetworkProviderConfigSchema = z.object({
   type: z.literal(ProviderType.NETWORK),
 
   refresh_interval: z.coerce.number().default(5 * 1000),
+
+  connections: z
+    .object({
+      refresh_interval: z.coerce.number().default(10 * 1000),
+      top_processes: z.coerce.number().default(5),
+    })
+    .optional(),
+
+  /**
+   * Opt-in sampling between refreshes, at up to 10 Hz. Samples are
+   * batched into each refresh under `samples`, so a `refresh_interval`
+   * of 500-1000ms is recommended.
+   */
+  high_frequency: z
+    .object({
+      sample_interval: z.coerce.number().min(100).default(250),
+    })
+    .optional(),
 });
 
 export type NetworkProviderConfig = z.infer<
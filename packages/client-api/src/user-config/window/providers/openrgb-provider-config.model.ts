@@ -0,0 +1,23 @@
+import { z } from 'zod';
+
+import { ProviderType } from '../provider-type.model';
+
+export const OpenRgbProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.OPENRGB),
+
+  refresh_interval: z.coerce.number().default(5 * 1000),
+
+  /**
+   * Host of the OpenRGB SDK server.
+   */
+  host: z.string().default('127.0.0.1'),
+
+  /**
+   * Port of the OpenRGB SDK server.
+   */
+  port: z.coerce.number().default(6742),
+});
+
+export type OpenRgbProviderConfig = z.infer<
+  typeof OpenRgbProviderConfigSchema
+>;
@@ -0,0 +1,13 @@
+import { z } from 'zod';
+
+import { ProviderType } from '../provider-type.model';
+
+export const RemovableDrivesProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.REMOVABLE_DRIVES),
+
+  refresh_interval: z.coerce.number().default(1000),
+});
+
+export type RemovableDrivesProviderConfig = z.infer<
+  typeof RemovableDrivesProviderConfigSchema
+>;
@@ -0,0 +1,22 @@
+import { z } from 'zod';
+
+import { ProviderType } from '../provider-type.model';
+
+export const StateProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.STATE),
+
+  /**
+   * Name of the file that the document is persisted to. Providers with
+   * the same name share the persisted document.
+   */
+  name: z.string().regex(/^[\w-]+$/),
+
+  /**
+   * Document to start with if none is persisted, and to reset to.
+   */
+  initial: z.unknown().default({}),
+});
+
+export type StateProviderConfig = z.infer<
+  typeof StateProviderConfigSchema
+>;
@@ -0,0 +1,18 @@
+import { z } from 'zod';
+
+import { ProviderType } from '../provider-type.model';
+
+export const SteamProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.STEAM),
+
+  refresh_interval: z.coerce.number().default(5 * 1000),
+
+  /**
+   * Path to the Steam installation. Detected automatically if not set.
+   */
+  steam_path: z.string().optional(),
+});
+
+export type SteamProviderConfig = z.infer<
+  typeof SteamProviderConfigSchema
+>;
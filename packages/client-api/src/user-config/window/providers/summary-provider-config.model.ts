@@ -0,0 +1,13 @@
+import { z } from 'zod';
+
+import { ProviderType } from '../provider-type.model';
+
+export const SummaryProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.SUMMARY),
+
+  refresh_interval: z.coerce.number().default(5 * 1000),
+});
+
+export type SummaryProviderConfig = z.infer<
+  typeof SummaryProviderConfigSchema
+>;
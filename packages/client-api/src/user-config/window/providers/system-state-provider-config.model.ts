@@ -0,0 +1,13 @@
+import { z } from 'zod';
+
+import { ProviderType } from '../provider-type.model';
+
+export const SystemStateProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.SYSTEM_STATE),
+
+  refresh_interval: z.coerce.number().default(2000),
+});
+
+export type SystemStateProviderConfig = z.infer<
+  typeof SystemStateProviderConfigSchema
+>;
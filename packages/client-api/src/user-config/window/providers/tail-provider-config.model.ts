@@ -0,0 +1,36 @@
+import { z } from 'zod';
+
+import { ProviderType } from '../provider-type.model';
+
+export const TailProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.TAIL),
+
+  refresh_interval: z.coerce.number().default(1000),
+
+  /**
+   * Path to the file to follow. Requires the `filesystem_paths`
+   * permission for the path. The file is followed across rotation and
+   * truncation, the same as `tail -F`.
+   */
+  path: z.string(),
+
+  /**
+   * Map of pattern names to regexes that new lines are matched against
+   * (e.g. `{ error: 'ERROR (?<message>.*)' }`). Named capture groups are
+   * emitted with each match. Every line matches if no patterns are
+   * given.
+   */
+  patterns: z.record(z.string(), z.string()).default({}),
+
+  /**
+   * Max number of recent matches to emit.
+   */
+  max_matches: z.coerce.number().default(10),
+
+  /**
+   * Max length of a line in bytes. Longer lines are truncated.
+   */
+  max_line_length: z.coerce.number().default(1000),
+});
+
+export type TailProviderConfig = z.infer<typeof TailProviderConfigSchema>;
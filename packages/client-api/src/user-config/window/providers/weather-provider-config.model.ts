@@ -1,5 +1,6 @@
 import { z } from 'zod';
 
+import { ProviderOutputRefSchema } from '../provider-output-ref.model';
 import { ProviderType } from '../provider-type.model';
 
 export const WeatherProviderConfigSchema = z.object({
@@ -7,20 +8,60 @@ export const WeatherProviderConfigSchema = z.object({
 
   /**
    * Latitude to retrieve weather for. If not provided, latitude is instead
-   * estimated based on public IP.
+   * estimated based on public IP. Can reference another provider's output
+   * (e.g. `{ from: 'ip', path: 'approxLatitude' }`).
    */
-  latitude: z.coerce.number().optional(),
+  latitude: z
+    .union([ProviderOutputRefSchema, z.coerce.number()])
+    .optional(),
 
   /**
    * Longitude to retrieve weather for. If not provided, longitude is instead
-   * estimated based on public IP.
+   * estimated based on public IP. Can reference another provider's output
+   * (e.g. `{ from: 'ip', path: 'approxLongitude' }`).
    */
-  longitude: z.coerce.number().optional(),
+  longitude: z
+    .union([ProviderOutputRefSchema, z.coerce.number()])
+    .optional(),
+
+  /**
+   * Place name to retrieve weather for (e.g. `Austin, TX`). Resolved to
+   * coordinates on first use. Ignored if coordinates are provided. Can
+   * reference another provider's output (e.g. `{ from: 'ip', path:
+   * 'approxCity' }`).
+   */
+  location: z.union([ProviderOutputRefSchema, z.string()]).optional(),
+
+  /**
+   * Additional named locations to retrieve weather for. Each location
+   * needs either coordinates or a place name (e.g. `Berlin`).
+   */
+  locations: z
+    .array(
+      z.object({
+        name: z.string(),
+        latitude: z.coerce.number().optional(),
+        longitude: z.coerce.number().optional(),
+        location: z.string().optional(),
+      }),
+    )
+    .default([]),
 
   /**
    * How often this component refreshes in milliseconds.
    */
   refresh_interval: z.coerce.number().default(60 * 60 * 1000),
+
+  /**
+   * How often the precipitation nowcast refreshes in milliseconds. Only
+   * fetched for the top-level location.
+   */
+  nowcast_refresh_interval: z.coerce.number().default(10 * 60 * 1000),
+
+  /**
+   * Units for pressure (hPa or inHg) and visibility (km or miles).
+   */
+  units: z.enum(['metric', 'imperial']).default('metric'),
 });
 
 export type WeatherProviderConfig = z.infer<
@@ -0,0 +1,17 @@
+import { z } from 'zod';
+
+import { BooleanLikeSchema } from '../../shared';
+import { ProviderType } from '../provider-type.model';
+
+export const WindowsListProviderConfigSchema = z.object({
+  type: z.literal(ProviderType.WINDOWS_LIST),
+
+  /**
+   * Whether to include minimized windows.
+   */
+  include_minimized: BooleanLikeSchema.default(true),
+});
+
+export type WindowsListProviderConfig = z.infer<
+  typeof WindowsListProviderConfigSchema
+>;
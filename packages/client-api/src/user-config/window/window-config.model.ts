@@ -5,6 +5,7 @@ import { BaseElementConfigSchema } from './base-element-config.model';
 import type { Prettify } from '~/utils';
 import { BooleanLikeSchema, withDynamicKey } from '../shared';
 import { ZOrderSchema } from './z-order.model';
+import { CornerPreferenceSchema } from './corner-preference.model';
 
 export const WindowConfigSchemaP1 = BaseElementConfigSchema.extend({
   class_names: z.array(z.string()).default(['window']),
@@ -17,7 +18,28 @@ export const WindowConfigSchemaP1 = BaseElementConfigSchema.extend({
   show_in_taskbar: BooleanLikeSchema.optional(),
   shown_in_taskbar: BooleanLikeSchema.optional(),
   resizable: BooleanLikeSchema.optional(),
+
+  /**
+   * Rounding of the window's corners on Windows 11. Defaults to
+   * `square` for windows docked to a monitor edge (e.g. bars), and
+   * otherwise to `default`.
+   */
+  corner_preference: CornerPreferenceSchema.optional(),
   global_styles: z.string().optional(),
+
+  /**
+   * CSS injected into the window by the desktop app. Either inline CSS
+   * or a path to a `.css` file within the config's `assets/` folder.
+   * Replaced in place when the config is reloaded.
+   */
+  custom_css: z.string().optional(),
+
+  /**
+   * JS run in the window by the desktop app. Either inline JS or a path
+   * to a `.js` file within the config's `assets/` folder. The window is
+   * reloaded if it changes when the config is reloaded.
+   */
+  custom_js: z.string().optional(),
 });
 
 export type WindowConfigP1 = Prettify<
@@ -0,0 +1,13 @@
+/**
+ * Hash of a JSON value with object keys sorted. Matches the hash of
+ * provider configs that are pre-created on the Rust side.
+ */
+export function canonicalHash(value: unknown): string {
+  return JSON.stringify(value, (_: unknown, val: unknown) =>
+    val && typeof val === 'object' && !Array.isArray(val)
+      ? Object.fromEntries(
+          Object.entries(val).sort(([a], [b]) => (a < b ? -1 : 1)),
+        )
+      : val,
+  );
+}
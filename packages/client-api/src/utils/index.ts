@@ -1,5 +1,6 @@
 export * from './types/pick-partial';
 export * from './types/prettify';
+export * from './canonical-hash';
 export * from './clsx';
 export * from './create-getter-proxy';
 export * from './create-logger';
@@ -0,0 +1,239 @@
+import {
+  For,
+  Show,
+  createResource,
+  createSignal,
+  onCleanup,
+} from 'solid-js';
+import { createStore, produce } from 'solid-js/store';
+import {
+  type AvailableProvider,
+  type ProviderConfig,
+  type ProviderEmitEvent,
+  canonicalHash,
+  getAvailableProviders,
+  listenProvider,
+  onRawProviderEmit,
+  unlistenProvider,
+} from 'zebar';
+
+import './playground.css';
+
+/**
+ * Max number of emissions to keep per provider instance.
+ */
+const MAX_EMISSIONS = 200;
+
+interface Emission {
+  receivedAt: Date;
+  sizeBytes: number;
+  payload: ProviderEmitEvent;
+}
+
+interface ProviderInstance {
+  configHash: string;
+  type: string;
+  emissions: Emission[];
+  unlisten: () => void;
+}
+
+/**
+ * Built-in window for trying out providers (`zebar open --playground`).
+ * Providers are created via the same listen path as regular windows,
+ * and their emissions are shown as-is.
+ */
+export function PlaygroundElement() {
+  const [providers] = createResource(getAvailableProviders);
+  const [selected, setSelected] = createSignal<AvailableProvider | null>(
+    null,
+  );
+  const [configText, setConfigText] = createSignal('');
+  const [error, setError] = createSignal<string | null>(null);
+  const [instances, setInstances] = createStore<ProviderInstance[]>([]);
+
+  onCleanup(() =>
+    instances.forEach(instance => stop(instance.configHash)),
+  );
+
+  function select(provider: AvailableProvider) {
+    setSelected(provider);
+    setConfigText(JSON.stringify(exampleConfig(provider), null, 2));
+    setError(null);
+  }
+
+  async function start() {
+    let config: ProviderConfig;
+
+    try {
+      config = JSON.parse(configText());
+    } catch (err) {
+      return setError(`Invalid JSON: ${(err as Error).message}`);
+    }
+
+    const configHash = canonicalHash(config);
+
+    if (instances.some(instance => instance.configHash === configHash)) {
+      return setError('A provider with this config is already running.');
+    }
+
+    const unlisten = await onRawProviderEmit(configHash, payload =>
+      setInstances(
+        instance => instance.configHash === configHash,
+        produce(instance => {
+          instance.emissions.unshift({
+            receivedAt: new Date(),
+            sizeBytes: new TextEncoder().encode(JSON.stringify(payload))
+              .length,
+            payload,
+          });
+
+          instance.emissions.splice(MAX_EMISSIONS);
+        }),
+      ),
+    );
+
+    setInstances(instances.length, {
+      configHash,
+      type: config.type,
+      emissions: [],
+      unlisten,
+    });
+
+    try {
+      await listenProvider({ configHash, config, trackedAccess: [] });
+      setError(null);
+    } catch (err) {
+      stop(configHash);
+      setError(`Failed to start provider: ${err}`);
+    }
+  }
+
+  function stop(configHash: string) {
+    const instance = instances.find(
+      instance => instance.configHash === configHash,
+    );
+
+    instance?.unlisten();
+    unlistenProvider(configHash);
+
+    setInstances(instances =>
+      instances.filter(instance => instance.configHash !== configHash),
+    );
+  }
+
+  return (
+    <div class="playground">
+      <nav class="playground-providers">
+        <For each={providers()}>
+          {provider => (
+            <button
+              classList={{ selected: selected()?.type === provider.type }}
+              onClick={() => select(provider)}
+            >
+              {provider.type}
+            </button>
+          )}
+        </For>
+      </nav>
+
+      <main class="playground-main">
+        <Show
+          when={selected()}
+          fallback={<p>Select a provider type to configure it.</p>}
+        >
+          {provider => (
+            <section class="playground-config">
+              <textarea
+                spellcheck={false}
+                value={configText()}
+                onInput={e => setConfigText(e.currentTarget.value)}
+              />
+              <button onClick={start}>Start</button>
+              <Show when={error()}>
+                {error => <p class="playground-error">{error()}</p>}
+              </Show>
+              <details>
+                <summary>Config schema</summary>
+                <pre>
+                  {JSON.stringify(provider().configSchema, null, 2)}
+                </pre>
+              </details>
+            </section>
+          )}
+        </Show>
+
+        <For each={instances}>
+          {instance => (
+            <section class="playground-instance">
+              <header>
+                <strong>{instance.type}</strong>
+                <code>{instance.configHash}</code>
+                <button onClick={() => stop(instance.configHash)}>
+                  Stop
+                </button>
+              </header>
+              <ol>
+                <For each={instance.emissions}>
+                  {emission => (
+                    <li>
+                      <span>
+                        {emission.receivedAt.toISOString()} ·{' '}
+                        {emission.sizeBytes} bytes
+                      </span>
+                      <pre>
+                        {JSON.stringify(emission.payload, null, 2)}
+                      </pre>
+                    </li>
+                  )}
+                </For>
+              </ol>
+            </section>
+          )}
+        </For>
+      </main>
+    </div>
+  );
+}
+
+/**
+ * Config to start with, filled with the defaults from the schema and
+ * placeholders for other required fields.
+ */
+function exampleConfig(provider: AvailableProvider) {
+  const schema = provider.configSchema as {
+    properties?: Record<string, { type?: string; default?: unknown }>;
+    required?: string[];
+  };
+
+  const config: Record<string, unknown> = { type: provider.type };
+
+  for (const [key, property] of Object.entries(schema.properties ?? {})) {
+    if (key === 'type') {
+      continue;
+    }
+
+    if (property.default !== undefined) {
+      config[key] = property.default;
+    } else if (schema.required?.includes(key)) {
+      config[key] = placeholder(key, property.type);
+    }
+  }
+
+  return config;
+}
+
+function placeholder(key: string, type?: string) {
+  switch (type) {
+    case 'integer':
+    case 'number':
+      return key.endsWith('interval') ? 5000 : 0;
+    case 'boolean':
+      return false;
+    case 'array':
+      return [];
+    case 'object':
+      return {};
+    default:
+      return '';
+  }
+}
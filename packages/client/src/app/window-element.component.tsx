@@ -3,6 +3,7 @@ import {
   type WindowContext,
   getChildConfigs,
   initWindow,
+  isSafeMode,
   toCssSelector,
 } from 'zebar';
 
@@ -16,19 +17,27 @@ export function WindowElement() {
   return (
     <Show when={context()}>
       {context => (
-        <div
-          class={context().parsedConfig.class_names.join(' ')}
-          id={toCssSelector(context().parsedConfig.id)}
-        >
-          <Index each={getChildConfigs(context().rawConfig)}>
-            {childConfig => (
-              <ChildElement
-                childId={childConfig().id}
-                parentContext={context()}
-              />
-            )}
-          </Index>
-        </div>
+        <>
+          <Show when={isSafeMode()}>
+            <div class="safe-mode-banner" role="status">
+              Safe mode: this window's custom frontend, CSS/JS, scripts,
+              geometry and global settings from the config are disabled.
+            </div>
+          </Show>
+          <div
+            class={context().parsedConfig.class_names.join(' ')}
+            id={toCssSelector(context().parsedConfig.id)}
+          >
+            <Index each={getChildConfigs(context().rawConfig)}>
+              {childConfig => (
+                <ChildElement
+                  childId={childConfig().id}
+                  parentContext={context()}
+                />
+              )}
+            </Index>
+          </div>
+        </>
       )}
     </Show>
   );
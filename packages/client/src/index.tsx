@@ -1,8 +1,10 @@
 /* @refresh reload */
 import { render } from 'solid-js/web';
+import { isPlaygroundWindow } from 'zebar';
 
 import './normalize.css';
 import './index.css';
+import { PlaygroundElement } from './app/playground.component';
 import { WindowElement } from './app/window-element.component';
 
 const root = document.getElementById('zebar');
@@ -11,4 +13,7 @@ if (import.meta.env.DEV && !(root instanceof HTMLElement)) {
   throw new Error('Root element not found.');
 }
 
-render(() => <WindowElement />, root!);
+render(
+  () => (isPlaygroundWindow() ? <PlaygroundElement /> : <WindowElement />),
+  root!,
+);
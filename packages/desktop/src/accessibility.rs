@@ -0,0 +1,237 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::{task, time};
+use tracing::{info, warn};
+#[cfg(target_os = "windows")]
+use windows::{
+  core::w,
+  Win32::{
+    Foundation::BOOL,
+    System::Registry::HKEY_CURRENT_USER,
+    UI::{
+      Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW},
+      WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION,
+        SPI_GETHIGHCONTRAST, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+      },
+    },
+  },
+};
+
+#[cfg(target_os = "windows")]
+use crate::util::registry::read_registry_dword;
+
+/// How often to check for changes to the OS accessibility settings.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Accessibility settings of the OS.
+///
+/// These are read from the OS directly, since the webview's media
+/// queries don't reliably reflect them in frameless windows.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilitySettings {
+  pub prefers_reduced_motion: bool,
+  pub prefers_color_scheme: ColorScheme,
+  pub high_contrast: bool,
+
+  /// Scale factor of the system text size (e.g. `1.25` for 125%).
+  pub font_scale: f32,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorScheme {
+  Light,
+  Dark,
+}
+
+impl Default for AccessibilitySettings {
+  fn default() -> Self {
+    Self {
+      prefers_reduced_motion: false,
+      prefers_color_scheme: ColorScheme::Light,
+      high_contrast: false,
+      font_scale: 1.,
+    }
+  }
+}
+
+impl AccessibilitySettings {
+  /// Reads the current accessibility settings. Settings that can't be
+  /// read fall back to their defaults.
+  #[cfg(target_os = "windows")]
+  pub fn read() -> Self {
+    let mut settings = Self::default();
+
+    unsafe {
+      let mut animations_enabled = BOOL(1);
+
+      if SystemParametersInfoW(
+        SPI_GETCLIENTAREAANIMATION,
+        0,
+        Some(&mut animations_enabled as *mut _ as _),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      )
+      .is_ok()
+      {
+        settings.prefers_reduced_motion = !animations_enabled.as_bool();
+      }
+
+      let mut high_contrast = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        ..Default::default()
+      };
+
+      if SystemParametersInfoW(
+        SPI_GETHIGHCONTRAST,
+        high_contrast.cbSize,
+        Some(&mut high_contrast as *mut _ as _),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      )
+      .is_ok()
+      {
+        settings.high_contrast =
+          high_contrast.dwFlags & HCF_HIGHCONTRASTON == HCF_HIGHCONTRASTON;
+      }
+    }
+
+    if let Some(light_theme) = read_registry_dword(
+      HKEY_CURRENT_USER,
+      w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+      w!("AppsUseLightTheme"),
+    ) {
+      settings.prefers_color_scheme = match light_theme {
+        0 => ColorScheme::Dark,
+        _ => ColorScheme::Light,
+      };
+    }
+
+    // Text size is stored as a percentage from 100 to 225.
+    if let Some(text_scale) = read_registry_dword(
+      HKEY_CURRENT_USER,
+      w!("Software\\Microsoft\\Accessibility"),
+      w!("TextScaleFactor"),
+    ) {
+      settings.font_scale = text_scale as f32 / 100.;
+    }
+
+    settings
+  }
+
+  #[cfg(target_os = "macos")]
+  pub fn read() -> Self {
+    let defaults = |domain: &str, key: &str| {
+      std::process::Command::new("defaults")
+        .args(["read", domain, key])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+          String::from_utf8_lossy(&output.stdout).trim().to_string()
+        })
+    };
+
+    Self {
+      prefers_reduced_motion: defaults(
+        "com.apple.universalaccess",
+        "reduceMotion",
+      )
+      .is_some_and(|value| value == "1"),
+      // `AppleInterfaceStyle` is only set when dark mode is enabled.
+      prefers_color_scheme: match defaults("-g", "AppleInterfaceStyle") {
+        Some(value) if value == "Dark" => ColorScheme::Dark,
+        _ => ColorScheme::Light,
+      },
+      high_contrast: defaults(
+        "com.apple.universalaccess",
+        "increaseContrast",
+      )
+      .is_some_and(|value| value == "1"),
+      font_scale: 1.,
+    }
+  }
+
+  #[cfg(target_os = "linux")]
+  pub fn read() -> Self {
+    let gsettings = |schema: &str, key: &str| {
+      std::process::Command::new("gsettings")
+        .args(["get", schema, key])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+          String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .trim_matches('\'')
+            .to_string()
+        })
+    };
+
+    Self {
+      prefers_reduced_motion: gsettings(
+        "org.gnome.desktop.interface",
+        "enable-animations",
+      )
+      .is_some_and(|value| value == "false"),
+      prefers_color_scheme: match gsettings(
+        "org.gnome.desktop.interface",
+        "color-scheme",
+      ) {
+        Some(value) if value == "prefer-dark" => ColorScheme::Dark,
+        _ => ColorScheme::Light,
+      },
+      high_contrast: gsettings(
+        "org.gnome.desktop.a11y.interface",
+        "high-contrast",
+      )
+      .is_some_and(|value| value == "true"),
+      font_scale: gsettings(
+        "org.gnome.desktop.interface",
+        "text-scaling-factor",
+      )
+      .and_then(|value| value.parse().ok())
+      .unwrap_or(1.),
+    }
+  }
+}
+
+/// Polls for changes to the accessibility settings and emits an
+/// `a11y-changed` event to all windows when they change.
+pub fn watch_accessibility_settings<R: Runtime>(
+  app_handle: &AppHandle<R>,
+) {
+  let app_handle = app_handle.clone();
+
+  task::spawn(async move {
+    let mut settings = read_settings().await;
+    let mut interval = time::interval(POLL_INTERVAL);
+
+    loop {
+      interval.tick().await;
+
+      let new_settings = read_settings().await;
+
+      if new_settings == settings {
+        continue;
+      }
+
+      info!("Accessibility settings changed: {:?}", new_settings);
+      settings = new_settings;
+
+      if let Err(err) = app_handle.emit("a11y-changed", settings.clone()) {
+        warn!("Error emitting accessibility settings: {:?}", err);
+      }
+    }
+  });
+}
+
+/// Reads the accessibility settings without blocking the async runtime,
+/// since reading them can spawn a process.
+pub async fn read_settings() -> AccessibilitySettings {
+  task::spawn_blocking(AccessibilitySettings::read)
+    .await
+    .unwrap_or_default()
+}
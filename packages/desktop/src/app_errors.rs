@@ -0,0 +1,162 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tracing::{error, warn};
+
+use crate::providers::last_updated::now_millis;
+
+/// Name of the event that app-level errors are emitted as.
+pub const APP_ERROR_EVENT: &str = "zebar://error";
+
+/// Max number of errors that are kept for `get_recent_errors`.
+const MAX_RECENT_ERRORS: usize = 50;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorSeverity {
+  Warning,
+  Error,
+}
+
+/// Error that isn't tied to a provider (e.g. a window failing to open).
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+  /// Kind of the error for programmatic handling (e.g.
+  /// `window_open_failed`).
+  pub kind: &'static str,
+  pub message: String,
+
+  /// Part of the app that the error originated from (e.g. `tray`).
+  pub source: &'static str,
+  pub severity: ErrorSeverity,
+
+  /// Unix timestamp in milliseconds of when the error occurred.
+  pub timestamp: u64,
+}
+
+impl AppError {
+  pub fn error(
+    source: &'static str,
+    kind: &'static str,
+    message: impl Into<String>,
+  ) -> Self {
+    Self::new(ErrorSeverity::Error, source, kind, message.into())
+  }
+
+  pub fn warning(
+    source: &'static str,
+    kind: &'static str,
+    message: impl Into<String>,
+  ) -> Self {
+    Self::new(ErrorSeverity::Warning, source, kind, message.into())
+  }
+
+  fn new(
+    severity: ErrorSeverity,
+    source: &'static str,
+    kind: &'static str,
+    message: String,
+  ) -> Self {
+    Self {
+      kind,
+      message,
+      source,
+      severity,
+      timestamp: now_millis(),
+    }
+  }
+}
+
+/// Recently reported app errors, kept in Tauri state.
+#[derive(Default)]
+pub struct AppErrors(Mutex<AppErrorsInner>);
+
+#[derive(Default)]
+struct AppErrorsInner {
+  /// Ring buffer of the most recent errors, oldest first.
+  recent: VecDeque<AppError>,
+
+  /// Errors that were reported while no windows were open. These are
+  /// emitted once a window starts listening for errors.
+  pending: Vec<AppError>,
+}
+
+impl AppErrors {
+  /// Gets up to `limit` of the most recent errors, oldest first.
+  pub fn recent(&self, limit: Option<usize>) -> Vec<AppError> {
+    let inner = self.0.lock().unwrap();
+    let limit = limit.unwrap_or(MAX_RECENT_ERRORS);
+
+    inner
+      .recent
+      .iter()
+      .skip(inner.recent.len().saturating_sub(limit))
+      .cloned()
+      .collect()
+  }
+
+  fn push(&self, error: AppError, is_pending: bool) {
+    let mut inner = self.0.lock().unwrap();
+
+    if inner.recent.len() == MAX_RECENT_ERRORS {
+      inner.recent.pop_front();
+    }
+
+    inner.recent.push_back(error.clone());
+
+    if is_pending {
+      inner.pending.push(error);
+    }
+  }
+
+  fn take_pending(&self) -> Vec<AppError> {
+    std::mem::take(&mut self.0.lock().unwrap().pending)
+  }
+}
+
+/// Logs the error and emits it to all windows as a `zebar://error`
+/// event.
+///
+/// Errors are queued if no windows are open (e.g. during startup), and
+/// are also kept in a ring buffer for `get_recent_errors`.
+pub fn report_error<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  error: AppError,
+) {
+  match error.severity {
+    ErrorSeverity::Warning => {
+      warn!("[{}] {}", error.source, error.message)
+    }
+    ErrorSeverity::Error => error!("[{}] {}", error.source, error.message),
+  }
+
+  let Some(app_errors) = app_handle.try_state::<AppErrors>() else {
+    return;
+  };
+
+  let is_pending = app_handle.webview_windows().is_empty();
+  app_errors.push(error.clone(), is_pending);
+
+  if !is_pending {
+    emit_error(app_handle, &error);
+  }
+}
+
+/// Emits errors that were queued while no windows were open.
+pub fn flush_pending_errors<R: Runtime>(app_handle: &AppHandle<R>) {
+  let Some(app_errors) = app_handle.try_state::<AppErrors>() else {
+    return;
+  };
+
+  for error in app_errors.take_pending() {
+    emit_error(app_handle, &error);
+  }
+}
+
+fn emit_error<R: Runtime>(app_handle: &AppHandle<R>, error: &AppError) {
+  if let Err(err) = app_handle.emit(APP_ERROR_EVENT, error) {
+    warn!("Error emitting app error: {:?}", err);
+  }
+}
@@ -0,0 +1,33 @@
+use serde::Serialize;
+
+use crate::{
+  display_session::{display_session, DisplaySession},
+  providers::config::enabled_provider_types,
+};
+
+/// Info about the app build and the environment it's running in.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+  pub version: &'static str,
+  pub debug_build: bool,
+  pub target_os: &'static str,
+  pub arch: &'static str,
+  pub display_session: DisplaySession,
+
+  /// Provider types that are compiled in this build.
+  pub providers: Vec<&'static str>,
+}
+
+impl AppInfo {
+  pub fn read() -> Self {
+    Self {
+      version: env!("VERSION_NUMBER"),
+      debug_build: cfg!(debug_assertions),
+      target_os: std::env::consts::OS,
+      arch: std::env::consts::ARCH,
+      display_session: display_session().clone(),
+      providers: enabled_provider_types(),
+    }
+  }
+}
@@ -0,0 +1,697 @@
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Component, Path, PathBuf},
+  sync::Mutex,
+  time::UNIX_EPOCH,
+};
+
+use anyhow::{bail, Context};
+use serde::Serialize;
+use tauri::{
+  http::{header, HeaderValue, Request, Response, StatusCode},
+  AppHandle, Manager, Url,
+};
+use tracing::warn;
+
+use crate::user_config;
+
+/// Name of the custom URI scheme for serving user assets and custom
+/// frontends.
+///
+/// Assets are available at `zebar://assets/<path>` on MacOS and Linux,
+/// and at `http://zebar.localhost/assets/<path>` on Windows. Custom
+/// frontends are served from `widgets/<window_label>/<path>` in the
+/// same way, and app icons from the `windows_list` provider from
+/// `icons/<id>.png`.
+pub const ASSET_SCHEME: &str = "zebar";
+
+/// How long the webview is allowed to cache assets before revalidating.
+const CACHE_MAX_AGE_SECS: u64 = 60;
+
+/// How long the webview is allowed to cache app icons. Icon IDs are
+/// derived from their contents, so icons never go stale.
+const ICON_CACHE_MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// Origins of the bundled frontend and of the custom protocol itself,
+/// in the forms used on MacOS and Linux and on Windows. Custom frontends
+/// are allowed via the URLs of their windows instead.
+const APP_ORIGINS: [&str; 5] = [
+  "tauri://localhost",
+  "http://tauri.localhost",
+  "https://tauri.localhost",
+  "zebar://assets",
+  "http://zebar.localhost",
+];
+
+/// Location of user assets on disk and the URL they are served from.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetRoot {
+  pub dir: String,
+  pub url: String,
+}
+
+/// Directories of custom frontends, keyed by window label.
+#[derive(Default)]
+pub struct WidgetRoots(pub Mutex<HashMap<String, PathBuf>>);
+
+/// Gets the location of user assets.
+pub fn get_asset_root(
+  app_handle: &AppHandle,
+) -> anyhow::Result<AssetRoot> {
+  let dir = user_config::assets_dir(app_handle)?;
+
+  Ok(AssetRoot {
+    dir: dir.to_string_lossy().to_string(),
+    url: protocol_url("assets"),
+  })
+}
+
+/// Resolves a path within the `assets/` folder of the config directory.
+pub fn resolve_user_asset(
+  app_handle: &AppHandle,
+  asset_path: &str,
+) -> anyhow::Result<PathBuf> {
+  resolve_asset_path(&user_config::assets_dir(app_handle)?, asset_path)
+}
+
+/// Gets the URL of the given path on the custom protocol.
+pub fn protocol_url(path: &str) -> String {
+  match cfg!(target_os = "windows") {
+    true => format!("http://{}.localhost/{}", ASSET_SCHEME, path),
+    false => format!("{}://{}", ASSET_SCHEME, path),
+  }
+}
+
+/// Handles a request to the custom protocol.
+///
+/// Serves files from the `assets/` folder within the config directory,
+/// or from the directory of a window's custom frontend.
+pub fn handle_asset_request(
+  app_handle: &AppHandle,
+  request: Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+  let response = request_path(&request).and_then(|path| {
+    if let Some(icon_file) = path.strip_prefix("icons/") {
+      return serve_icon(icon_file);
+    }
+
+    if let Some(window_label) = widget_label(&path) {
+      check_widget_access(&request, window_label)?;
+    }
+
+    let (root_dir, asset_path) = resolve_root(app_handle, &path)?;
+    serve_asset(&root_dir, &asset_path, &request)
+  });
+
+  let allowed_origin = allowed_origin(app_handle, &request);
+
+  let response = response.map(|mut response| {
+    let headers = response.headers_mut();
+
+    if let Some(origin) = allowed_origin {
+      headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+
+    // Responses differ by origin, and files of custom frontends by the
+    // requesting page, so they can't be cached across them.
+    headers
+      .insert(header::VARY, HeaderValue::from_static("origin, referer"));
+
+    response
+  });
+
+  response.unwrap_or_else(|err| {
+    warn!("Failed to serve asset {}: {:?}", request.uri(), err);
+
+    Response::builder()
+      .status(StatusCode::NOT_FOUND)
+      .body(Vec::new())
+      .unwrap()
+  })
+}
+
+/// Gets the request's origin if it's allowed to read the response.
+///
+/// Only the app's own origins are allowed: the bundled frontend, the
+/// custom protocol, and the frontends of open windows (e.g. a custom
+/// frontend on a localhost dev server). Other pages can still embed
+/// assets (e.g. via `<img>`), but can't read them with `fetch`.
+fn allowed_origin(
+  app_handle: &AppHandle,
+  request: &Request<Vec<u8>>,
+) -> Option<HeaderValue> {
+  let origin = request.headers().get(header::ORIGIN)?;
+
+  let window_urls = app_handle
+    .webview_windows()
+    .values()
+    .filter_map(|window| window.url().ok())
+    .collect::<Vec<_>>();
+
+  is_allowed_origin(origin.to_str().ok()?, &window_urls)
+    .then(|| origin.clone())
+}
+
+/// Whether the origin is one of the app's own, or that of the given
+/// window URLs.
+fn is_allowed_origin(origin: &str, window_urls: &[Url]) -> bool {
+  APP_ORIGINS.contains(&origin)
+    || window_urls
+      .iter()
+      .filter_map(url_origin)
+      .any(|window_origin| window_origin == origin)
+}
+
+/// Serializes the origin of a URL, as sent in the `Origin` header.
+///
+/// `Url::origin` is opaque for custom schemes, so it's built from the
+/// scheme, host and port instead.
+fn url_origin(url: &Url) -> Option<String> {
+  let host = url.host_str()?;
+
+  Some(match url.port() {
+    Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+    None => format!("{}://{}", url.scheme(), host),
+  })
+}
+
+/// Gets the label of the window whose custom frontend the path is in.
+fn widget_label(path: &str) -> Option<&str> {
+  let rest = path.strip_prefix("widgets/")?;
+  Some(
+    rest
+      .split_once('/')
+      .map_or(rest, |(window_label, _)| window_label),
+  )
+}
+
+/// Checks that a request for a file of a window's custom frontend comes
+/// from that frontend, or is the navigation that loads it.
+///
+/// Custom frontends of all windows are served from the same origin, so
+/// the origin can't tell them apart. Instead, the requesting page (from
+/// the `Referer` header) must be within the window's own directory.
+/// Requests without a referrer are only allowed for top-level
+/// navigations, so that pages can't read other windows' files by hiding
+/// their referrer (e.g. via `referrerPolicy: 'no-referrer'`). Webviews
+/// that don't send `Sec-Fetch-*` headers are trusted to be navigating.
+fn check_widget_access(
+  request: &Request<Vec<u8>>,
+  window_label: &str,
+) -> anyhow::Result<()> {
+  let headers = request.headers();
+
+  let referer = headers
+    .get(header::REFERER)
+    .and_then(|referer| referer.to_str().ok())
+    .and_then(|referer| Url::parse(referer).ok());
+
+  let is_allowed = match referer {
+    Some(referer) => protocol_path(&referer)
+      .and_then(|path| percent_decode(&path).ok())
+      .is_some_and(|path| widget_label(&path) == Some(window_label)),
+    None => {
+      let is_header = |name: &str, expected: &str| {
+        headers.get(name).map_or(true, |value| value == expected)
+      };
+
+      is_header("sec-fetch-mode", "navigate")
+        && is_header("sec-fetch-dest", "document")
+    }
+  };
+
+  if !is_allowed {
+    bail!(
+      "Files of window '{}' can only be read by its own frontend.",
+      window_label
+    );
+  }
+
+  Ok(())
+}
+
+/// Gets the undecoded path of a URL on the custom protocol (e.g. of the
+/// page that made a request), or `None` if it's on another origin.
+fn protocol_path(url: &Url) -> Option<String> {
+  let windows_host = format!("{}.localhost", ASSET_SCHEME);
+
+  match (url.scheme(), url.host_str()?) {
+    (scheme, host) if scheme == ASSET_SCHEME => {
+      Some(format!("{}{}", host, url.path()))
+    }
+    ("http" | "https", host) if host == windows_host => {
+      Some(url.path().trim_start_matches('/').to_string())
+    }
+    _ => None,
+  }
+}
+
+/// Gets the decoded path from the request URI (eg. `assets/icon.png`).
+///
+/// The host of the URI differs by platform (eg. `assets` on MacOS and
+/// Linux vs. `zebar.localhost` on Windows), so both forms are handled
+/// here.
+fn request_path(request: &Request<Vec<u8>>) -> anyhow::Result<String> {
+  let uri = request.uri();
+  let windows_host = format!("{}.localhost", ASSET_SCHEME);
+
+  let path = match uri.host() {
+    Some(host) if host != windows_host => {
+      format!("{}{}", host, uri.path())
+    }
+    _ => uri.path().trim_start_matches('/').to_string(),
+  };
+
+  percent_decode(&path)
+}
+
+/// Gets the root directory to serve from and the path of the file
+/// relative to it.
+fn resolve_root(
+  app_handle: &AppHandle,
+  path: &str,
+) -> anyhow::Result<(PathBuf, String)> {
+  let (namespace, rest) = path.split_once('/').unwrap_or((path, ""));
+
+  match namespace {
+    "assets" => {
+      Ok((user_config::assets_dir(app_handle)?, rest.to_string()))
+    }
+    "widgets" => {
+      let (window_label, widget_path) =
+        rest.split_once('/').unwrap_or((rest, ""));
+
+      let widget_roots = app_handle
+        .try_state::<WidgetRoots>()
+        .context("Custom frontends are not available.")?;
+
+      let root_dir = widget_roots
+        .0
+        .lock()
+        .unwrap()
+        .get(window_label)
+        .cloned()
+        .with_context(|| {
+          format!("No custom frontend for window '{}'.", window_label)
+        })?;
+
+      let widget_path = match widget_path {
+        "" => "index.html",
+        _ => widget_path,
+      };
+
+      Ok((root_dir, widget_path.to_string()))
+    }
+    _ => bail!("Unknown path '{}'.", path),
+  }
+}
+
+/// Reads the asset and builds the response with caching headers.
+fn serve_asset(
+  root_dir: &Path,
+  asset_path: &str,
+  request: &Request<Vec<u8>>,
+) -> anyhow::Result<Response<Vec<u8>>> {
+  let file_path = resolve_asset_path(root_dir, asset_path)?;
+  let metadata = fs::metadata(&file_path)?;
+
+  let modified_secs = metadata
+    .modified()?
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs())
+    .unwrap_or(0);
+
+  let etag = format!("\"{:x}-{:x}\"", modified_secs, metadata.len());
+
+  let is_not_modified = request
+    .headers()
+    .get(header::IF_NONE_MATCH)
+    .is_some_and(|value| value.as_bytes() == etag.as_bytes());
+
+  let response = Response::builder().header(header::ETAG, &etag).header(
+    header::CACHE_CONTROL,
+    format!("max-age={}, must-revalidate", CACHE_MAX_AGE_SECS),
+  );
+
+  if is_not_modified {
+    return Ok(
+      response.status(StatusCode::NOT_MODIFIED).body(Vec::new())?,
+    );
+  }
+
+  let body = fs::read(&file_path)?;
+
+  Ok(
+    response
+      .status(StatusCode::OK)
+      .header(header::CONTENT_TYPE, mime_type(&file_path))
+      .body(body)?,
+  )
+}
+
+/// Serves an app icon that was extracted by the `windows_list` provider.
+fn serve_icon(icon_file: &str) -> anyhow::Result<Response<Vec<u8>>> {
+  let id = icon_file
+    .strip_suffix(".png")
+    .with_context(|| format!("Invalid icon path '{}'.", icon_file))?;
+
+  #[cfg(all(windows, feature = "windows_list"))]
+  let png = crate::providers::windows_list::icon_png(id);
+
+  #[cfg(not(all(windows, feature = "windows_list")))]
+  let png = None::<std::sync::Arc<Vec<u8>>>;
+
+  let png = png.with_context(|| format!("No icon with ID '{}'.", id))?;
+
+  Ok(
+    Response::builder()
+      .status(StatusCode::OK)
+      .header(header::CONTENT_TYPE, "image/png")
+      .header(
+        header::CACHE_CONTROL,
+        format!("max-age={}, immutable", ICON_CACHE_MAX_AGE_SECS),
+      )
+      .body(png.to_vec())?,
+  )
+}
+
+/// Joins the asset path onto the root directory, while ensuring that
+/// the resulting path can't escape the root directory.
+fn resolve_asset_path(
+  root_dir: &Path,
+  asset_path: &str,
+) -> anyhow::Result<PathBuf> {
+  let relative_path = Path::new(asset_path);
+
+  let is_plain_relative = relative_path
+    .components()
+    .all(|component| matches!(component, Component::Normal(_)));
+
+  if asset_path.is_empty() || !is_plain_relative {
+    bail!("Invalid asset path '{}'.", asset_path);
+  }
+
+  // Canonicalize to also guard against symlinks pointing outside of the
+  // root directory.
+  let root_dir = root_dir.canonicalize()?;
+  let file_path = root_dir.join(relative_path).canonicalize()?;
+
+  if !file_path.starts_with(&root_dir) || !file_path.is_file() {
+    bail!("Invalid asset path '{}'.", asset_path);
+  }
+
+  Ok(file_path)
+}
+
+/// Decodes `%XX` escape sequences in a URI path.
+fn percent_decode(input: &str) -> anyhow::Result<String> {
+  let bytes = input.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut index = 0;
+
+  while index < bytes.len() {
+    match bytes[index] {
+      b'%' => {
+        let hex = bytes
+          .get(index + 1..index + 3)
+          .and_then(|hex| std::str::from_utf8(hex).ok())
+          .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+          .context("Invalid percent-encoding in asset path.")?;
+
+        decoded.push(hex);
+        index += 3;
+      }
+      byte => {
+        decoded.push(byte);
+        index += 1;
+      }
+    }
+  }
+
+  String::from_utf8(decoded).context("Asset path is not valid UTF-8.")
+}
+
+/// Gets the MIME type of a file based on its extension.
+fn mime_type(path: &Path) -> &'static str {
+  let extension = path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .map(|extension| extension.to_ascii_lowercase());
+
+  match extension.as_deref() {
+    Some("html" | "htm") => "text/html",
+    Some("css") => "text/css",
+    Some("js" | "mjs") => "text/javascript",
+    Some("json") => "application/json",
+    Some("txt") => "text/plain",
+    Some("png") => "image/png",
+    Some("jpg" | "jpeg") => "image/jpeg",
+    Some("gif") => "image/gif",
+    Some("webp") => "image/webp",
+    Some("avif") => "image/avif",
+    Some("svg") => "image/svg+xml",
+    Some("ico") => "image/x-icon",
+    Some("bmp") => "image/bmp",
+    Some("woff") => "font/woff",
+    Some("woff2") => "font/woff2",
+    Some("ttf") => "font/ttf",
+    Some("otf") => "font/otf",
+    Some("mp3") => "audio/mpeg",
+    Some("wav") => "audio/wav",
+    Some("ogg") => "audio/ogg",
+    Some("mp4") => "video/mp4",
+    Some("webm") => "video/webm",
+    _ => "application/octet-stream",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn url(url: &str) -> Url {
+    Url::parse(url).unwrap()
+  }
+
+  #[test]
+  fn app_origins_are_allowed() {
+    for origin in APP_ORIGINS {
+      assert!(is_allowed_origin(origin, &[]), "{}", origin);
+    }
+  }
+
+  #[test]
+  fn other_origins_are_not_allowed() {
+    for origin in [
+      "https://example.com",
+      "http://localhost:4200",
+      "http://tauri.localhost:8080",
+      "http://zebar.localhost.example.com",
+      "null",
+      "",
+    ] {
+      assert!(!is_allowed_origin(origin, &[]), "{}", origin);
+    }
+  }
+
+  #[test]
+  fn origins_of_open_windows_are_allowed() {
+    let window_urls = [url("http://localhost:5173/bar/index.html?a=1")];
+
+    assert!(is_allowed_origin("http://localhost:5173", &window_urls));
+    assert!(!is_allowed_origin("http://localhost:5174", &window_urls));
+    assert!(!is_allowed_origin("https://localhost:5173", &window_urls));
+  }
+
+  #[test]
+  fn origin_of_custom_scheme_url() {
+    assert_eq!(
+      url_origin(&url("zebar://widgets/1-bar/index.html")).as_deref(),
+      Some("zebar://widgets")
+    );
+    assert_eq!(
+      url_origin(&url("https://example.com:443/a")).as_deref(),
+      Some("https://example.com")
+    );
+    assert_eq!(url_origin(&url("about:blank")), None);
+  }
+
+  fn request(uri: &str, headers: &[(&str, &str)]) -> Request<Vec<u8>> {
+    let mut builder = Request::builder().uri(uri);
+
+    for (name, value) in headers {
+      builder = builder.header(*name, *value);
+    }
+
+    builder.body(Vec::new()).unwrap()
+  }
+
+  /// Creates an empty temporary directory that's unique to the test.
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+      "zebar-asset-protocol-{}-{}",
+      std::process::id(),
+      name
+    ));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn resolves_files_in_root() {
+    let root_dir = temp_dir("resolves");
+    fs::create_dir(root_dir.join("css")).unwrap();
+    fs::write(root_dir.join("css/style.css"), "").unwrap();
+
+    let file_path =
+      resolve_asset_path(&root_dir, "css/style.css").unwrap();
+
+    assert!(file_path.ends_with("css/style.css"));
+    assert!(resolve_asset_path(&root_dir, "css").is_err());
+    assert!(resolve_asset_path(&root_dir, "").is_err());
+  }
+
+  #[test]
+  fn rejects_parent_dir_paths() {
+    let dir = temp_dir("parent-dir");
+    let root_dir = dir.join("root");
+    fs::create_dir(&root_dir).unwrap();
+    fs::write(dir.join("secret.txt"), "").unwrap();
+
+    for path in ["../secret.txt", "a/../../secret.txt", "./../secret.txt"]
+    {
+      assert!(resolve_asset_path(&root_dir, path).is_err(), "{}", path);
+    }
+  }
+
+  #[test]
+  fn rejects_absolute_paths() {
+    let root_dir = temp_dir("absolute");
+    let secret_path = temp_dir("absolute-outside").join("secret.txt");
+    fs::write(&secret_path, "").unwrap();
+
+    let secret_path = secret_path.to_str().unwrap();
+    assert!(resolve_asset_path(&root_dir, secret_path).is_err());
+    assert!(resolve_asset_path(&root_dir, "/etc/hosts").is_err());
+  }
+
+  #[test]
+  fn rejects_percent_encoded_parent_dir_paths() {
+    let dir = temp_dir("percent-encoded");
+    let root_dir = dir.join("root");
+    fs::create_dir(&root_dir).unwrap();
+    fs::write(dir.join("secret.txt"), "").unwrap();
+
+    for uri in [
+      "zebar://assets/%2e%2e/secret.txt",
+      "zebar://assets/%2E%2E%2Fsecret.txt",
+      "http://zebar.localhost/assets/..%2fsecret.txt",
+    ] {
+      let path = request_path(&request(uri, &[])).unwrap();
+      let asset_path = path.strip_prefix("assets/").unwrap();
+
+      assert!(
+        resolve_asset_path(&root_dir, asset_path).is_err(),
+        "{}",
+        uri
+      );
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn rejects_symlinks_outside_root() {
+    let dir = temp_dir("symlink");
+    let root_dir = dir.join("root");
+    fs::create_dir(&root_dir).unwrap();
+    fs::write(dir.join("secret.txt"), "").unwrap();
+
+    std::os::unix::fs::symlink(
+      dir.join("secret.txt"),
+      root_dir.join("link.txt"),
+    )
+    .unwrap();
+
+    std::os::unix::fs::symlink(&dir, root_dir.join("link-dir")).unwrap();
+
+    assert!(resolve_asset_path(&root_dir, "link.txt").is_err());
+    assert!(resolve_asset_path(&root_dir, "link-dir/secret.txt").is_err());
+  }
+
+  #[test]
+  fn widgets_can_read_own_files() {
+    for referer in [
+      "zebar://widgets/1-bar/index.html",
+      "http://zebar.localhost/widgets/1-bar/pages/settings.html",
+    ] {
+      let request = request(
+        "zebar://widgets/1-bar/style.css",
+        &[("referer", referer), ("sec-fetch-mode", "cors")],
+      );
+
+      assert!(
+        check_widget_access(&request, "1-bar").is_ok(),
+        "{}",
+        referer
+      );
+    }
+  }
+
+  #[test]
+  fn widgets_cannot_read_other_windows_files() {
+    for referer in [
+      "zebar://widgets/2-bar/index.html",
+      "zebar://widgets/1-bar-2/index.html",
+      "http://zebar.localhost/widgets/2-bar/index.html",
+      "zebar://assets/index.html",
+      "https://example.com/widgets/1-bar/index.html",
+    ] {
+      let request = request(
+        "zebar://widgets/1-bar/config.json",
+        &[("referer", referer)],
+      );
+
+      assert!(
+        check_widget_access(&request, "1-bar").is_err(),
+        "{}",
+        referer
+      );
+    }
+  }
+
+  #[test]
+  fn requests_without_referer_must_be_navigations() {
+    let navigation = request(
+      "zebar://widgets/1-bar/index.html",
+      &[
+        ("sec-fetch-mode", "navigate"),
+        ("sec-fetch-dest", "document"),
+      ],
+    );
+
+    let fetch = request(
+      "zebar://widgets/1-bar/config.json",
+      &[("sec-fetch-mode", "cors"), ("sec-fetch-dest", "empty")],
+    );
+
+    let iframe = request(
+      "zebar://widgets/1-bar/index.html",
+      &[("sec-fetch-mode", "navigate"), ("sec-fetch-dest", "iframe")],
+    );
+
+    assert!(check_widget_access(&navigation, "1-bar").is_ok());
+    assert!(check_widget_access(&fetch, "1-bar").is_err());
+    assert!(check_widget_access(&iframe, "1-bar").is_err());
+  }
+
+  #[test]
+  fn widget_label_of_path() {
+    assert_eq!(widget_label("widgets/1-bar/index.html"), Some("1-bar"));
+    assert_eq!(widget_label("widgets/1-bar"), Some("1-bar"));
+    assert_eq!(widget_label("assets/widgets/1-bar/a.css"), None);
+  }
+}
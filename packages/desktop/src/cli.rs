@@ -1,6 +1,8 @@
-use std::process;
+use std::{path::PathBuf, process};
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::json;
 
 const VERSION: &'static str = env!("VERSION_NUMBER");
 
@@ -9,21 +11,85 @@ const VERSION: &'static str = env!("VERSION_NUMBER");
 pub struct Cli {
   #[command(subcommand)]
   pub command: CliCommand,
+
+  /// Block all outbound network requests from providers. Overrides
+  /// `offline_mode` in the config.
+  #[clap(long, global = true)]
+  pub offline: bool,
+
+  /// Format of the command output.
+  ///
+  /// With `json`, a single JSON document of the form `{ "ok": true,
+  /// "data": ... }` or `{ "ok": false, "error": { "message": ... } }` is
+  /// printed to stdout.
+  #[clap(long, global = true, value_enum, default_value_t)]
+  pub output: OutputFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum OutputFormat {
+  #[default]
+  Text,
+  Json,
+}
+
+/// Output of a CLI command that can be printed as text or as JSON.
+pub trait CommandOutput: Serialize {
+  fn to_text(&self) -> String;
+}
+
+/// Used for commands that have no output on success.
+impl CommandOutput for () {
+  fn to_text(&self) -> String {
+    String::new()
+  }
+}
+
+/// Output of the `open --list-presets` CLI command.
+#[derive(Serialize, Debug)]
+pub struct PresetsOutput {
+  pub presets: Vec<String>,
+}
+
+impl CommandOutput for PresetsOutput {
+  fn to_text(&self) -> String {
+    self
+      .presets
+      .iter()
+      .map(|name| format!("{}\n", name))
+      .collect()
+  }
 }
 
 #[derive(Subcommand, Debug)]
 pub enum CliCommand {
-  /// Open a window by its ID (eg. `zebar open bar`).
-  Open {
-    /// ID of the window to open (eg. `bar`).
-    window_id: String,
-
-    /// Arguments to pass to the window.
-    ///
-    /// These become available via the `self` provider.
-    #[clap(short, long, num_args = 1.., value_parser=parse_open_args)]
-    args: Option<Vec<(String, String)>>,
-  },
+  /// Open a window by its ID (eg. `zebar open bar`) or by a preset
+  /// defined in the config (eg. `zebar open @laptop-bar`).
+  Open(OpenCommandArgs),
+  /// Run providers in the background without opening any windows.
+  ///
+  /// Providers are defined in the `daemon` section of the config.
+  /// Windows can still be opened later via `zebar open`, which attaches
+  /// to the running daemon.
+  Daemon,
+  /// Reload the config of the running instance.
+  ///
+  /// Changes and validation errors are logged by the running instance.
+  Reload,
+  /// Export a zip file with info for bug reports.
+  ///
+  /// Includes the config (with secrets redacted), recent log files,
+  /// monitors, and app and OS info. The provider status snapshot is only
+  /// included when exporting via the system tray.
+  ExportDiagnostics(ExportDiagnosticsArgs),
+  /// Manage secrets that can be referenced from the config by name (eg.
+  /// `{ secret: "owm_key" }`).
+  ///
+  /// Secrets are stored in the platform's keychain or in `secrets.yaml`
+  /// within the config directory, depending on `secrets_backend` in the
+  /// global config.
+  #[clap(subcommand)]
+  Secret(SecretCommand),
   /// Output available monitors.
   Monitors {
     /// Use ASCII NUL character (character code 0) instead of newlines
@@ -35,28 +101,226 @@ pub enum CliCommand {
   },
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct OpenCommandArgs {
+  /// ID of the window to open (eg. `bar`), or the name of a preset
+  /// prefixed with `@` (eg. `@laptop-bar`).
+  #[clap(required_unless_present_any = ["list_presets", "playground"])]
+  pub window_id: Option<String>,
+
+  /// Arguments to pass to the window.
+  ///
+  /// These become available via the `self` provider. When opening a
+  /// preset, these override the preset's arguments.
+  #[clap(short, long, num_args = 1.., value_parser=parse_open_args)]
+  pub args: Option<Vec<(String, String)>>,
+
+  /// URL of a custom frontend to load instead of the bundled one.
+  ///
+  /// Either a `file://` URL to an HTML file (eg.
+  /// `file:///path/to/dist/index.html`) or a dev server URL on localhost
+  /// (eg. `http://localhost:5173`).
+  #[clap(long)]
+  pub url: Option<String>,
+
+  /// Title of the window, which is shown in window overviews and screen
+  /// sharing pickers. Defaults to `Zebar - <window_id>`.
+  #[clap(long)]
+  pub title: Option<String>,
+
+  /// PNG or ICO file to use as the window's icon. Relative paths are
+  /// resolved against the `assets/` folder within the config directory.
+  #[clap(long)]
+  pub icon: Option<String>,
+
+  /// Initial width of the window in physical pixels. Overrides `width`
+  /// in the window's config.
+  #[clap(long)]
+  pub width: Option<f64>,
+
+  /// Initial height of the window in physical pixels. Overrides `height`
+  /// in the window's config.
+  #[clap(long)]
+  pub height: Option<f64>,
+
+  /// Allow `--url` to point to hosts other than localhost.
+  ///
+  /// Note that pages on remote hosts don't have access to Zebar's
+  /// commands.
+  #[clap(long)]
+  pub allow_remote_url: bool,
+
+  /// Whether the window is shown on all workspaces (virtual desktops).
+  /// Defaults to `true`.
+  #[clap(long)]
+  pub visible_on_all_workspaces: Option<bool>,
+
+  /// Print the names of presets defined in the config.
+  #[clap(long)]
+  pub list_presets: bool,
+
+  /// Open the provider playground, a window for trying out providers
+  /// and inspecting their raw output.
+  #[clap(long, conflicts_with_all = ["window_id", "url", "list_presets"])]
+  pub playground: bool,
+
+  /// Don't wait for a running instance to open the window.
+  ///
+  /// By default, the command waits and exits with a non-zero code if the
+  /// window fails to open (eg. if it isn't defined in the config).
+  #[clap(long)]
+  pub no_wait: bool,
+
+  /// Don't inject the window's `custom_css` and `custom_js`. Useful for
+  /// checking whether they cause a rendering issue.
+  #[clap(long)]
+  pub no_custom_inject: bool,
+
+  /// Open the window in safe mode, e.g. when a broken config or widget
+  /// makes windows crash on load.
+  ///
+  /// Custom frontend URLs, `custom_css` and `custom_js`, presets, and
+  /// the window's permissions, size and providers from the config are
+  /// ignored, scripts aren't loaded, and the frontend uses the default
+  /// global settings. Safe mode only applies to the opened window.
+  #[clap(long, conflicts_with_all = ["url", "playground"])]
+  pub safe_mode: bool,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SecretCommand {
+  /// Set the value of a secret.
+  Set {
+    name: String,
+
+    /// Value of the secret. Read from stdin if omitted, so that it
+    /// doesn't end up in the shell history.
+    value: Option<String>,
+  },
+  /// Print the value of a secret.
+  Get { name: String },
+  /// Delete a secret.
+  Delete { name: String },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportDiagnosticsArgs {
+  /// Path of the zip file to write (eg. `diagnostics.zip`).
+  pub path: PathBuf,
+
+  /// Exclude the config from the export.
+  #[clap(long)]
+  pub no_config: bool,
+}
+
 /// Print to stdout/stderror and exit the process.
-pub fn print_and_exit(output: anyhow::Result<String>) {
-  match output {
-    Ok(output) => {
-      print!("{}", output);
-      process::exit(0);
-    }
-    Err(err) => {
-      eprintln!("Error: {}", err);
-      process::exit(1);
+///
+/// The exit code is 0 on success and 1 on failure, for both text and
+/// JSON output.
+pub fn print_and_exit<T: CommandOutput>(
+  output: anyhow::Result<T>,
+  format: OutputFormat,
+) {
+  let exit_code = match output {
+    Ok(_) => 0,
+    Err(_) => 1,
+  };
+
+  match (format, output) {
+    (OutputFormat::Text, Ok(output)) => print!("{}", output.to_text()),
+    (OutputFormat::Text, Err(err)) => eprintln!("Error: {}", err),
+    (OutputFormat::Json, Ok(output)) => {
+      println!("{}", json!({ "ok": true, "data": output }))
     }
+    (OutputFormat::Json, Err(err)) => println!(
+      "{}",
+      json!({ "ok": false, "error": { "message": err.to_string() } })
+    ),
   }
+
+  process::exit(exit_code);
 }
 
 /// Parses arguments passed to the `open` CLI command into a string tuple.
+///
+/// Only the first `=` separates the key from the value, so values can
+/// contain `=`.
 fn parse_open_args(
   input: &str,
 ) -> anyhow::Result<(String, String), String> {
-  let mut parts = input.split('=');
+  match input.split_once('=') {
+    Some((key, value)) if !key.is_empty() => {
+      Ok((key.into(), value.into()))
+    }
+    _ => Err(format!(
+      "Invalid argument '{}'. Arguments must be of format KEY=VALUE, \
+      where values containing spaces are quoted (eg. \"label=CPU: 50%\").",
+      input
+    )),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn open_args(args: &[&str]) -> Vec<(String, String)> {
+    let cli = Cli::try_parse_from(
+      ["zebar", "open", "bar", "--args"].iter().chain(args),
+    )
+    .unwrap();
+
+    match cli.command {
+      CliCommand::Open(open_args) => open_args.args.unwrap(),
+      _ => panic!("Expected open command."),
+    }
+  }
+
+  fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+      .iter()
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn values_keep_equals_signs() {
+    assert_eq!(
+      open_args(&["query=a=b", "empty=", "eq=="]),
+      pairs(&[("query", "a=b"), ("empty", ""), ("eq", "=")])
+    );
+  }
+
+  #[test]
+  fn values_keep_pipes() {
+    assert_eq!(
+      open_args(&["cmd=a|b", "sep=|", "mixed=x=|=y"]),
+      pairs(&[("cmd", "a|b"), ("sep", "|"), ("mixed", "x=|=y")])
+    );
+  }
+
+  #[test]
+  fn values_keep_spaces_and_unicode() {
+    assert_eq!(
+      open_args(&["label=CPU: 50%", "name=Grüße ✓"]),
+      pairs(&[("label", "CPU: 50%"), ("name", "Grüße ✓")])
+    );
+  }
+
+  #[test]
+  fn invalid_args_are_rejected() {
+    for arg in ["novalue", "=value", ""] {
+      let res =
+        Cli::try_parse_from(["zebar", "open", "bar", "--args", arg]);
+
+      assert!(res.is_err(), "Expected '{}' to be rejected.", arg);
+    }
+  }
+
+  #[test]
+  fn invalid_arg_error_names_entry() {
+    let err = parse_open_args("novalue").unwrap_err();
 
-  match (parts.next(), parts.next()) {
-    (Some(key), Some(value)) => Ok((key.into(), value.into())),
-    _ => Err("Arguments must be of format KEY1=VAL1".into()),
+    assert!(err.contains("'novalue'"));
   }
 }
@@ -0,0 +1,81 @@
+use serde::Serialize;
+
+#[cfg(feature = "removable_drives")]
+use crate::providers::removable_drives::DriveBusyError;
+#[cfg(target_os = "windows")]
+use crate::window_activation::ActivationDeniedError;
+use crate::{
+  display_session::UnsupportedOnPlatformError,
+  hotkey::InvalidHotkeyError,
+  permissions::PermissionError,
+  providers::{actions::UnknownActionError, state::StatePathError},
+};
+
+/// Error returned from Tauri commands.
+///
+/// Most errors are serialized as plain strings, whereas errors that the
+/// frontend might want to handle are serialized as objects with a `kind`
+/// field.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum CommandError {
+  PermissionDenied(PermissionError),
+  #[cfg(feature = "removable_drives")]
+  DriveBusy(DriveBusyError),
+  InvalidStatePath(StatePathError),
+  UnknownAction(UnknownActionError),
+  InvalidHotkey(InvalidHotkeyError),
+  UnsupportedOnPlatform(UnsupportedOnPlatformError),
+  #[cfg(target_os = "windows")]
+  ActivationDenied(ActivationDeniedError),
+  Other(String),
+}
+
+impl From<PermissionError> for CommandError {
+  fn from(err: PermissionError) -> Self {
+    CommandError::PermissionDenied(err)
+  }
+}
+
+impl From<InvalidHotkeyError> for CommandError {
+  fn from(err: InvalidHotkeyError) -> Self {
+    CommandError::InvalidHotkey(err)
+  }
+}
+
+impl From<UnsupportedOnPlatformError> for CommandError {
+  fn from(err: UnsupportedOnPlatformError) -> Self {
+    CommandError::UnsupportedOnPlatform(err)
+  }
+}
+
+impl From<anyhow::Error> for CommandError {
+  fn from(err: anyhow::Error) -> Self {
+    #[cfg(feature = "removable_drives")]
+    let err = match err.downcast::<DriveBusyError>() {
+      Ok(err) => return CommandError::DriveBusy(err),
+      Err(err) => err,
+    };
+
+    let err = match err.downcast::<StatePathError>() {
+      Ok(err) => return CommandError::InvalidStatePath(err),
+      Err(err) => err,
+    };
+
+    let err = match err.downcast::<UnknownActionError>() {
+      Ok(err) => return CommandError::UnknownAction(err),
+      Err(err) => err,
+    };
+
+    #[cfg(target_os = "windows")]
+    let err = match err.downcast::<ActivationDeniedError>() {
+      Ok(err) => return CommandError::ActivationDenied(err),
+      Err(err) => err,
+    };
+
+    match err.downcast::<UnsupportedOnPlatformError>() {
+      Ok(err) => CommandError::UnsupportedOnPlatform(err),
+      Err(err) => CommandError::Other(err.to_string()),
+    }
+  }
+}
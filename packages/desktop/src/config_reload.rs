@@ -0,0 +1,154 @@
+use anyhow::Context;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::{
+  custom_inject,
+  daemon::restart_daemon_providers,
+  permissions::{check_permission, Capability},
+  providers::provider_manager::ProviderManager,
+  secrets::SecretsStore,
+  user_config::{self, UserConfig},
+  webview_profile,
+};
+
+/// The most recently loaded config. Used to determine which entries
+/// changed when the config is reloaded.
+pub struct LoadedConfig(Mutex<UserConfig>);
+
+impl LoadedConfig {
+  pub fn new(config: UserConfig) -> Self {
+    Self(Mutex::new(config))
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadResult {
+  /// Top-level entries that were added, removed or changed (e.g.
+  /// `window/bar`).
+  pub changed: Vec<String>,
+
+  /// Validation errors of individual entries. Invalid entries fall back
+  /// to their defaults, same as on startup.
+  pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ConfigReloadedEvent {
+  /// Contents of the config file. Only sent to windows that are allowed
+  /// to read the config directory.
+  config: Option<String>,
+
+  #[serde(flatten)]
+  result: ConfigReloadResult,
+}
+
+/// Re-reads the config file and applies changes to Rust-held state.
+///
+/// Emits a `config-reloaded` event to all windows with the new config
+/// contents. If the file can't be read or parsed (e.g. while it's still
+/// being synced), the last good config is kept and nothing is emitted.
+pub async fn reload_config(
+  app_handle: &AppHandle,
+) -> anyhow::Result<ConfigReloadResult> {
+  let read_res = user_config::read_file_async(None, app_handle.clone())
+    .await
+    .and_then(|config_str| {
+      let config: UserConfig = serde_yaml::from_str(&config_str)
+        .context("Unable to parse config file.")?;
+
+      Ok((config_str, config))
+    });
+
+  let (config_str, config) = match read_res {
+    Ok(res) => res,
+    Err(err) => {
+      warn!(
+        "Failed to reload config, keeping last good config: {:#}",
+        err
+      );
+      return Err(err);
+    }
+  };
+
+  let errors = config.validate();
+  let global_config = config.global();
+
+  let (changed, window_configs) = {
+    let loaded_config = app_handle.state::<LoadedConfig>();
+    let mut loaded_config = loaded_config.0.lock().await;
+    let changed = loaded_config.changed_entries(&config);
+
+    // Configs of changed windows, for re-injecting their custom CSS/JS.
+    // Invalid configs are skipped, so that the previous CSS/JS is kept.
+    let window_configs = changed
+      .iter()
+      .filter_map(|key| key.strip_prefix("window/"))
+      .filter_map(|window_id| {
+        let window_config = config.window(window_id).ok()?;
+        Some((window_id.to_string(), window_config))
+      })
+      .collect::<Vec<_>>();
+
+    *loaded_config = config;
+    (changed, window_configs)
+  };
+
+  info!("Reloaded config. Changed entries: {:?}", changed);
+
+  for error in &errors {
+    warn!("Invalid config: {}", error);
+  }
+
+  if changed.iter().any(|key| key == "global") {
+    if let Ok(global_config) = global_config {
+      app_handle
+        .state::<ProviderManager>()
+        .update_global_config(&global_config)
+        .await;
+
+      let secrets_store = app_handle.state::<SecretsStore>();
+      secrets_store.set_backend(global_config.secrets_backend);
+      secrets_store.set_hosts(global_config.secret_hosts.clone());
+
+      webview_profile::set_isolated(global_config.isolated_webviews);
+    }
+  }
+
+  if changed.iter().any(|key| key == "daemon") {
+    restart_daemon_providers(app_handle).await;
+  }
+
+  for (window_id, window_config) in &window_configs {
+    custom_inject::reinject_window(app_handle, window_id, window_config);
+  }
+
+  let result = ConfigReloadResult { changed, errors };
+  let config_dir = user_config::config_dir(app_handle)?;
+
+  for window_label in app_handle.webview_windows().into_keys() {
+    let can_read_config = check_permission(
+      app_handle,
+      &window_label,
+      Capability::FilesystemPath(&config_dir),
+    )
+    .is_ok();
+
+    let event = ConfigReloadedEvent {
+      config: can_read_config.then(|| config_str.clone()),
+      result: result.clone(),
+    };
+
+    if let Err(err) =
+      app_handle.emit_to(window_label.as_str(), "config-reloaded", event)
+    {
+      warn!("Error emitting config reload: {:?}", err);
+    }
+  }
+
+  Ok(result)
+}
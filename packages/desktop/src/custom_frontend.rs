@@ -0,0 +1,75 @@
+use anyhow::{bail, Context};
+use tauri::{AppHandle, Manager, Url, WebviewUrl};
+
+use crate::asset_protocol::{protocol_url, WidgetRoots};
+
+/// Hosts that custom frontend URLs can point to without explicitly
+/// allowing remote URLs.
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+/// Resolves the URL to load in a window.
+///
+/// Defaults to the bundled frontend if no URL is given. File URLs are
+/// served via the custom protocol, rooted at the directory of the given
+/// file, so that the frontend can't read files outside of it.
+pub fn resolve_webview_url(
+  app_handle: &AppHandle,
+  window_label: &str,
+  url: Option<&str>,
+  allow_remote_url: bool,
+) -> anyhow::Result<WebviewUrl> {
+  let Some(url) = url else {
+    return Ok(WebviewUrl::default());
+  };
+
+  let parsed_url = Url::parse(url)
+    .with_context(|| format!("Invalid frontend URL '{}'.", url))?;
+
+  match parsed_url.scheme() {
+    "http" | "https" => {
+      let host = parsed_url.host_str().unwrap_or_default();
+
+      if !allow_remote_url && !LOCAL_HOSTS.contains(&host) {
+        bail!(
+          "Frontend URL '{}' is not on localhost. Use `--allow-remote-url` to allow other hosts.",
+          url
+        );
+      }
+
+      Ok(WebviewUrl::External(parsed_url))
+    }
+    "file" => {
+      let file_path = parsed_url
+        .to_file_path()
+        .ok()
+        .and_then(|path| path.canonicalize().ok())
+        .filter(|path| path.is_file())
+        .with_context(|| format!("Frontend file '{}' not found.", url))?;
+
+      let root_dir = file_path
+        .parent()
+        .context("Invalid frontend file path.")?
+        .to_path_buf();
+
+      let file_name = file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Invalid frontend file name.")?;
+
+      app_handle
+        .state::<WidgetRoots>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(window_label.to_string(), root_dir);
+
+      let widget_url = Url::parse(&protocol_url(&format!(
+        "widgets/{}/{}",
+        window_label, file_name
+      )))?;
+
+      Ok(WebviewUrl::External(widget_url))
+    }
+    other => bail!("Unsupported frontend URL scheme '{}'.", other),
+  }
+}
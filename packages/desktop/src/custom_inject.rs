@@ -0,0 +1,311 @@
+use std::{collections::HashMap, fs, sync::Mutex};
+
+use anyhow::Context;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::{
+  app_errors::{report_error, AppError},
+  asset_protocol,
+  user_config::WindowConfig,
+};
+
+/// ID of the `<style>` element that custom CSS is written to. Re-used on
+/// re-injection, so that styles from the previous config are replaced.
+const STYLE_ELEMENT_ID: &str = "__zebar-custom-css";
+
+/// Key in `sessionStorage` of the latest custom CSS/JS of the window.
+///
+/// Initialization scripts can't be changed after the window is created,
+/// so the latest contents are stored here to be picked up on reload.
+const STORAGE_KEY: &str = "__zebar-custom-inject";
+
+/// Windows that have custom CSS/JS injected, keyed by their label.
+/// Windows opened with `--no-custom-inject` aren't included.
+#[derive(Default)]
+pub struct InjectedWindows(Mutex<HashMap<String, InjectedWindow>>);
+
+struct InjectedWindow {
+  window_id: String,
+
+  /// Custom CSS/JS that's currently applied to the window.
+  injection: CustomInjection,
+}
+
+impl InjectedWindows {
+  pub fn insert(
+    &self,
+    window_label: &str,
+    window_id: &str,
+    injection: CustomInjection,
+  ) {
+    self.0.lock().unwrap().insert(
+      window_label.to_string(),
+      InjectedWindow {
+        window_id: window_id.to_string(),
+        injection,
+      },
+    );
+  }
+
+  pub fn remove(&self, window_label: &str) {
+    self.0.lock().unwrap().remove(window_label);
+  }
+
+  /// Gets the labels and current custom CSS/JS of injected windows with
+  /// the given window ID.
+  fn injections(&self, window_id: &str) -> Vec<(String, CustomInjection)> {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|(_, window)| window.window_id == window_id)
+      .map(|(label, window)| (label.clone(), window.injection.clone()))
+      .collect()
+  }
+
+  /// Updates the custom CSS/JS that's applied to the window, unless it
+  /// has been closed in the meantime.
+  fn set_injection(&self, window_label: &str, injection: CustomInjection) {
+    if let Some(window) = self.0.lock().unwrap().get_mut(window_label) {
+      window.injection = injection;
+    }
+  }
+}
+
+/// Custom CSS and JS of a window, with paths resolved to the contents of
+/// the files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CustomInjection {
+  css: Option<String>,
+  js: Option<String>,
+}
+
+impl CustomInjection {
+  /// Resolves the `custom_css` and `custom_js` fields of a window's
+  /// config.
+  ///
+  /// Values ending in `.css` and `.js` respectively are read as paths
+  /// within the `assets/` folder of the config directory. Anything else
+  /// is used as inline CSS/JS.
+  pub fn resolve(
+    app_handle: &AppHandle,
+    window_config: &WindowConfig,
+  ) -> anyhow::Result<Self> {
+    let css = window_config
+      .custom_css
+      .as_deref()
+      .map(|source| read_source(app_handle, source, "css"))
+      .transpose()
+      .context("Invalid `custom_css`.")?;
+
+    let js = window_config
+      .custom_js
+      .as_deref()
+      .map(|source| read_source(app_handle, source, "js"))
+      .transpose()
+      .context("Invalid `custom_js`.")?;
+
+    Ok(Self { css, js })
+  }
+
+  /// Initialization script that applies the custom CSS and runs the
+  /// custom JS once the document is parsed.
+  ///
+  /// If the contents were changed after the window was created, the
+  /// latest ones are read from `sessionStorage` instead (see
+  /// [`CustomInjection::update_script`]).
+  pub fn script(&self) -> String {
+    // Serialized as JSON, so that the contents can't break out of the
+    // script. JS is run via `Function` for the same reason, and so that
+    // syntax errors are caught.
+    format!(
+      r#"(() => {{
+  let injection = {injection};
+  try {{
+    const stored = sessionStorage.getItem("{key}");
+    if (stored) injection = JSON.parse(stored);
+  }} catch {{}}
+  const apply = () => {{
+    const style = document.createElement("style");
+    style.id = "{id}";
+    style.textContent = injection.css ?? "";
+    document.head.appendChild(style);
+    try {{
+      new Function(injection.js ?? "")();
+    }} catch (err) {{
+      console.error("Error in custom JS:", err);
+    }}
+  }};
+  if (document.readyState === "loading") {{
+    document.addEventListener("DOMContentLoaded", apply, {{ once: true }});
+  }} else {{
+    apply();
+  }}
+}})();"#,
+      injection = serde_json::to_string(self).unwrap(),
+      key = STORAGE_KEY,
+      id = STYLE_ELEMENT_ID,
+    )
+  }
+
+  /// Script that changes the custom CSS/JS of a page that was injected
+  /// with `previous`.
+  ///
+  /// CSS is replaced in place. Custom JS can't be undone (e.g. listeners
+  /// or intervals it added), so the page is reloaded instead if it
+  /// changed.
+  pub fn update_script(&self, previous: &CustomInjection) -> String {
+    let update = match self.js == previous.js {
+      true => format!(
+        r#"let style = document.getElementById("{id}");
+  if (!style) {{
+    style = document.createElement("style");
+    style.id = "{id}";
+    document.head.appendChild(style);
+  }}
+  style.textContent = {css};"#,
+        id = STYLE_ELEMENT_ID,
+        css = serde_json::to_string(self.css.as_deref().unwrap_or(""))
+          .unwrap(),
+      ),
+      false => "location.reload();".to_string(),
+    };
+
+    format!(
+      r#"(() => {{
+  try {{
+    sessionStorage.setItem("{key}", JSON.stringify({injection}));
+  }} catch {{}}
+  {update}
+}})();"#,
+      key = STORAGE_KEY,
+      injection = serde_json::to_string(self).unwrap(),
+      update = update,
+    )
+  }
+}
+
+/// Reads inline CSS/JS, or the contents of a file if the source is a
+/// path with the given extension.
+fn read_source(
+  app_handle: &AppHandle,
+  source: &str,
+  extension: &str,
+) -> anyhow::Result<String> {
+  let is_path = !source.contains('\n')
+    && source
+      .trim()
+      .to_ascii_lowercase()
+      .ends_with(&format!(".{}", extension));
+
+  if !is_path {
+    return Ok(source.to_string());
+  }
+
+  // Restricted to the assets folder, which also rejects absolute paths
+  // and paths that escape it via `..` or symlinks.
+  let path =
+    asset_protocol::resolve_user_asset(app_handle, source.trim())?;
+
+  fs::read_to_string(&path)
+    .with_context(|| format!("Failed to read '{}'.", path.display()))
+}
+
+/// Re-injects custom CSS/JS into open windows with the given window ID,
+/// e.g. after their config has changed. Windows whose custom CSS/JS is
+/// unchanged are left as is.
+pub fn reinject_window(
+  app_handle: &AppHandle,
+  window_id: &str,
+  window_config: &WindowConfig,
+) {
+  let injected_windows = app_handle.state::<InjectedWindows>();
+  let injections = injected_windows.injections(window_id);
+
+  if injections.is_empty() {
+    return;
+  }
+
+  let injection = CustomInjection::resolve(app_handle, window_config);
+
+  for (window_label, previous) in injections {
+    let injection = match &injection {
+      Ok(injection) if *injection == previous => continue,
+      Ok(injection) => injection,
+      Err(err) => {
+        report_injection_error(app_handle, &window_label, err);
+        continue;
+      }
+    };
+
+    let res = app_handle
+      .get_webview_window(&window_label)
+      .context("Window not found.")
+      .and_then(|window| {
+        Ok(window.eval(&injection.update_script(&previous))?)
+      });
+
+    match res {
+      Ok(()) => {
+        injected_windows.set_injection(&window_label, injection.clone());
+        info!("Re-injected custom CSS/JS into '{}'.", window_label);
+      }
+      Err(err) => report_injection_error(app_handle, &window_label, &err),
+    }
+  }
+}
+
+/// Reports that custom CSS/JS couldn't be injected into a window.
+pub fn report_injection_error(
+  app_handle: &AppHandle,
+  window_label: &str,
+  err: &anyhow::Error,
+) {
+  report_error(
+    app_handle,
+    AppError::warning(
+      "window",
+      "custom_inject_failed",
+      format!(
+        "Failed to inject custom CSS/JS into window '{}': {:#}",
+        window_label, err
+      ),
+    ),
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn injection(css: &str, js: &str) -> CustomInjection {
+    CustomInjection {
+      css: Some(css.to_string()),
+      js: Some(js.to_string()),
+    }
+  }
+
+  #[test]
+  fn css_changes_are_applied_in_place() {
+    let previous = injection("a {}", "console.log(1)");
+    let script =
+      injection("b {}", "console.log(1)").update_script(&previous);
+
+    assert!(script.contains(r#"style.textContent = "b {}";"#));
+    assert!(!script.contains("location.reload()"));
+    assert!(!script.contains("new Function"));
+  }
+
+  #[test]
+  fn js_changes_reload_the_page() {
+    let previous = injection("a {}", "console.log(1)");
+    let script =
+      injection("a {}", "console.log(2)").update_script(&previous);
+
+    assert!(script.contains("location.reload()"));
+    assert!(script.contains(r#""js":"console.log(2)""#));
+  }
+}
@@ -0,0 +1,96 @@
+use tauri::{App, AppHandle, Manager};
+use tokio::{sync::Mutex, task};
+use tracing::{info, warn};
+
+use crate::{
+  app_errors::{report_error, AppError},
+  providers::{config::ProviderConfig, provider_manager::ProviderManager},
+  secrets::{self, SecretsStore},
+  user_config,
+};
+
+/// Config hashes of the running daemon providers.
+///
+/// Only managed when running via `zebar daemon`.
+#[derive(Default)]
+pub struct DaemonProviders(Mutex<Vec<String>>);
+
+/// Initializes daemon mode and starts the providers defined in the
+/// `daemon` section of the config.
+pub fn init_daemon(app: &mut App) {
+  app.manage(DaemonProviders::default());
+
+  let app_handle = app.handle().clone();
+  task::spawn(async move { restart_daemon_providers(&app_handle).await });
+}
+
+/// Stops any running daemon providers and starts the providers defined
+/// in the `daemon` section of the config. No-op when not in daemon mode.
+///
+/// Outputs are emitted the same way as for providers created by windows,
+/// so they're available to any windows that are opened later.
+pub async fn restart_daemon_providers(app_handle: &AppHandle) {
+  let Some(daemon_providers) = app_handle.try_state::<DaemonProviders>()
+  else {
+    return;
+  };
+
+  let config = user_config::read_parsed_async(app_handle.clone()).await;
+
+  let daemon_config = match config.and_then(|config| config.daemon()) {
+    Ok(daemon_config) => daemon_config,
+    Err(err) => {
+      warn!("Failed to read daemon config: {:?}", err);
+      return;
+    }
+  };
+
+  let provider_manager = app_handle.state::<ProviderManager>();
+  let mut running = daemon_providers.0.lock().await;
+
+  for config_hash in running.drain(..) {
+    if let Err(err) = provider_manager.destroy(config_hash).await {
+      warn!("Failed to stop daemon provider: {:?}", err);
+    }
+  }
+
+  if daemon_config.providers.is_empty() {
+    info!("No providers defined in daemon config.");
+  }
+
+  let secrets_store = app_handle.state::<SecretsStore>();
+
+  for (name, config) in daemon_config.providers {
+    info!("Starting daemon provider: {}", name);
+    let config_hash = format!("daemon/{}", name);
+
+    let config = secrets::resolve_provider_refs(&config, &secrets_store)
+      .and_then(|config| {
+        serde_json::from_value::<ProviderConfig>(config)
+          .map_err(secrets::redact_error)
+      });
+
+    // Daemon providers track all fields, since there's no frontend to
+    // report which fields are accessed.
+    let res = match config {
+      Ok(config) => {
+        provider_manager
+          .create(config_hash.clone(), config, Vec::new())
+          .await
+      }
+      Err(err) => Err(err),
+    };
+
+    match res {
+      Ok(_) => running.push(config_hash),
+      Err(err) => report_error(
+        app_handle,
+        AppError::error(
+          "daemon",
+          "daemon_provider_failed",
+          format!("Failed to start daemon provider '{}': {:#}", name, err),
+        ),
+      ),
+    }
+  }
+}
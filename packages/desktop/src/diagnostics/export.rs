@@ -0,0 +1,200 @@
+use std::{
+  collections::BTreeMap,
+  fs::{self, File},
+  io::Write,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use serde::Serialize;
+use sysinfo::System;
+use tauri::{AppHandle, Runtime};
+use tracing::{info, warn};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use super::redact::redact_config;
+use crate::{
+  app_info::AppInfo,
+  cli::CommandOutput,
+  logging,
+  monitors::get_monitors,
+  providers::subscriptions::ProviderSubscription,
+  secrets::{self, SecretsBackend, SecretsStore},
+  user_config,
+};
+
+/// Max number of log files to include, starting from the most recent.
+const MAX_LOG_FILES: usize = 3;
+
+/// Output of the `export-diagnostics` CLI command.
+#[derive(Serialize, Debug)]
+pub struct DiagnosticsOutput {
+  /// Path of the written zip file.
+  pub path: PathBuf,
+
+  /// Names of the files that were included in the zip file.
+  pub files: Vec<String>,
+}
+
+impl CommandOutput for DiagnosticsOutput {
+  fn to_text(&self) -> String {
+    let mut text =
+      format!("Exported diagnostics to {}\n", self.path.display());
+
+    for file in &self.files {
+      text += &format!("  {}\n", file);
+    }
+
+    text
+  }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ExportedAppInfo {
+  #[serde(flatten)]
+  app_info: AppInfo,
+
+  /// Unix timestamp in milliseconds of when the diagnostics were
+  /// exported.
+  exported_at: u128,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct OsInfo {
+  name: Option<String>,
+  version: Option<String>,
+  long_version: Option<String>,
+  kernel_version: Option<String>,
+}
+
+/// Writes a zip file with info for bug reports to the given path.
+///
+/// Files that fail to be collected are skipped, and the returned output
+/// lists exactly which files were included. The provider status snapshot
+/// is only available from within the running instance, so `providers`
+/// is `None` when exporting from the CLI.
+pub fn export_diagnostics<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  path: &Path,
+  include_config: bool,
+  providers: Option<&[(String, Vec<ProviderSubscription>)]>,
+) -> anyhow::Result<DiagnosticsOutput> {
+  let mut entries = Vec::<(String, Vec<u8>)>::new();
+
+  let mut add_json =
+    |name: &str, value: anyhow::Result<String>| match value {
+      Ok(value) => entries.push((name.into(), value.into_bytes())),
+      Err(err) => warn!("Skipping {} in diagnostics: {:?}", name, err),
+    };
+
+  add_json("app.json", to_json(&app_info()));
+  add_json("os.json", to_json(&os_info()));
+  add_json(
+    "monitors.json",
+    get_monitors(app_handle, false)
+      .and_then(|monitors| to_json(&monitors)),
+  );
+
+  if let Some(providers) = providers {
+    let providers = providers.iter().cloned().collect::<BTreeMap<_, _>>();
+    add_json("providers.json", to_json(&providers));
+  }
+
+  // Values of secrets are masked wherever they appear. Keychain values
+  // are only known if they've been read by this process.
+  let secret_values = user_config::config_dir(app_handle)
+    .map(|config_dir| {
+      SecretsStore::new(SecretsBackend::File, &config_dir).file_values()
+    })
+    .unwrap_or_default();
+
+  if include_config {
+    match user_config::read_file(None, app_handle.clone()) {
+      Ok(config_str) => entries.push((
+        "config.yaml".into(),
+        secrets::redact_known(&redact_config(&config_str), &secret_values)
+          .into_bytes(),
+      )),
+      Err(err) => warn!("Skipping config in diagnostics: {:?}", err),
+    }
+  }
+
+  for log_path in logging::recent_log_files(MAX_LOG_FILES) {
+    let Some(file_name) = log_path.file_name() else {
+      continue;
+    };
+
+    match fs::read(&log_path) {
+      Ok(contents) => entries.push((
+        format!("logs/{}", file_name.to_string_lossy()),
+        secrets::redact_known(
+          &String::from_utf8_lossy(&contents),
+          &secret_values,
+        )
+        .into_bytes(),
+      )),
+      Err(err) => warn!(
+        "Skipping log file {} in diagnostics: {:?}",
+        log_path.display(),
+        err
+      ),
+    }
+  }
+
+  write_zip(path, &entries)?;
+
+  let files = entries.into_iter().map(|(name, _)| name).collect();
+  info!("Exported diagnostics to {}.", path.display());
+
+  Ok(DiagnosticsOutput {
+    path: path.to_path_buf(),
+    files,
+  })
+}
+
+fn write_zip(
+  path: &Path,
+  entries: &[(String, Vec<u8>)],
+) -> anyhow::Result<()> {
+  let file = File::create(path)
+    .with_context(|| format!("Unable to create {}.", path.display()))?;
+
+  let mut zip = ZipWriter::new(file);
+  let options = SimpleFileOptions::default()
+    .compression_method(CompressionMethod::Deflated);
+
+  for (name, contents) in entries {
+    zip.start_file(name.as_str(), options)?;
+    zip.write_all(contents)?;
+  }
+
+  zip.finish()?;
+
+  Ok(())
+}
+
+fn to_json<T: Serialize>(value: &T) -> anyhow::Result<String> {
+  Ok(serde_json::to_string_pretty(value)?)
+}
+
+fn app_info() -> ExportedAppInfo {
+  ExportedAppInfo {
+    app_info: AppInfo::read(),
+    exported_at: SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_millis())
+      .unwrap_or_default(),
+  }
+}
+
+fn os_info() -> OsInfo {
+  OsInfo {
+    name: System::name(),
+    version: System::os_version(),
+    long_version: System::long_os_version(),
+    kernel_version: System::kernel_version(),
+  }
+}
@@ -0,0 +1,4 @@
+mod export;
+mod redact;
+
+pub use export::*;
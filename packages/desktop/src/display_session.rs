@@ -0,0 +1,257 @@
+use std::{fmt, sync::OnceLock};
+
+use serde::Serialize;
+
+/// Type of the display session that the app is running in.
+///
+/// Only the variants for the current platform are constructed.
+#[allow(dead_code)]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionType {
+  Windows,
+  Macos,
+  X11,
+  Wayland,
+  Unknown,
+}
+
+impl SessionType {
+  fn display_name(&self) -> &'static str {
+    match self {
+      SessionType::Windows => "Windows",
+      SessionType::Macos => "MacOS",
+      SessionType::X11 => "X11",
+      SessionType::Wayland => "Wayland",
+      SessionType::Unknown => "unknown",
+    }
+  }
+}
+
+/// Window features that aren't available on every session type.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionCapability {
+  AbsolutePositioning,
+  AlwaysOnTop,
+  SkipTaskbar,
+  PinToDesktop,
+  ActivateWindow,
+}
+
+impl SessionCapability {
+  fn display_name(&self) -> &'static str {
+    match self {
+      SessionCapability::AbsolutePositioning => "Absolute positioning",
+      SessionCapability::AlwaysOnTop => "Always on top",
+      SessionCapability::SkipTaskbar => "Hiding from the taskbar",
+      SessionCapability::PinToDesktop => "Pinning to a virtual desktop",
+      SessionCapability::ActivateWindow => {
+        "Activating other apps' windows"
+      }
+    }
+  }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplaySession {
+  pub session_type: SessionType,
+
+  /// Name of the compositor or desktop environment (e.g. `Hyprland` or
+  /// `GNOME`). Only detected on Linux.
+  pub compositor: Option<String>,
+
+  /// Whether the app's windows run through XWayland within a Wayland
+  /// session (e.g. when `GDK_BACKEND=x11` is set).
+  pub xwayland: bool,
+
+  /// Capabilities that are supported by the session.
+  pub capabilities: Vec<SessionCapability>,
+}
+
+impl DisplaySession {
+  pub fn supports(&self, capability: SessionCapability) -> bool {
+    self.capabilities.contains(&capability)
+  }
+}
+
+/// Error returned from window commands that can't work on the current
+/// display session.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedOnPlatformError {
+  pub kind: &'static str,
+  pub session_type: SessionType,
+  pub capability: SessionCapability,
+  pub message: String,
+}
+
+impl fmt::Display for UnsupportedOnPlatformError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for UnsupportedOnPlatformError {}
+
+/// Gets the display session that the app is running in. Detection only
+/// runs once, since the session can't change while the app is running.
+pub fn display_session() -> &'static DisplaySession {
+  static SESSION: OnceLock<DisplaySession> = OnceLock::new();
+  SESSION.get_or_init(detect_session)
+}
+
+/// Returns an `UnsupportedOnPlatformError` if the capability isn't
+/// supported by the current display session.
+pub fn require(
+  capability: SessionCapability,
+) -> Result<(), UnsupportedOnPlatformError> {
+  let session = display_session();
+
+  if session.supports(capability) {
+    return Ok(());
+  }
+
+  Err(UnsupportedOnPlatformError {
+    kind: "unsupported_on_platform",
+    session_type: session.session_type,
+    capability,
+    message: format!(
+      "{} isn't supported on {} sessions.",
+      capability.display_name(),
+      session.session_type.display_name()
+    ),
+  })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_session() -> DisplaySession {
+  DisplaySession {
+    #[cfg(target_os = "windows")]
+    session_type: SessionType::Windows,
+    #[cfg(target_os = "macos")]
+    session_type: SessionType::Macos,
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    session_type: SessionType::Unknown,
+    compositor: None,
+    xwayland: false,
+    capabilities: all_capabilities(),
+  }
+}
+
+/// Detects the session type from the display connection that's
+/// available, falling back to `XDG_SESSION_TYPE`.
+#[cfg(target_os = "linux")]
+fn detect_session() -> DisplaySession {
+  let session_type = if has_wayland_socket() {
+    SessionType::Wayland
+  } else if has_x11_display() {
+    SessionType::X11
+  } else {
+    match std::env::var("XDG_SESSION_TYPE").as_deref() {
+      Ok("wayland") => SessionType::Wayland,
+      Ok("x11") => SessionType::X11,
+      _ => SessionType::Unknown,
+    }
+  };
+
+  // GTK uses its X11 backend within a Wayland session if forced to.
+  let xwayland = session_type == SessionType::Wayland
+    && has_x11_display()
+    && std::env::var("GDK_BACKEND")
+      .is_ok_and(|backend| backend.starts_with("x11"));
+
+  // Wayland has no protocol for absolute positioning or stacking of
+  // regular windows (only via layer-shell, which isn't used).
+  let capabilities = match session_type {
+    SessionType::Wayland if !xwayland => Vec::new(),
+    _ => all_capabilities(),
+  };
+
+  DisplaySession {
+    session_type,
+    compositor: detect_compositor(),
+    xwayland,
+    capabilities,
+  }
+}
+
+fn all_capabilities() -> Vec<SessionCapability> {
+  vec![
+    SessionCapability::AbsolutePositioning,
+    SessionCapability::AlwaysOnTop,
+    SessionCapability::SkipTaskbar,
+    // Relies on the virtual desktop APIs of Windows.
+    #[cfg(target_os = "windows")]
+    SessionCapability::PinToDesktop,
+    // Window handles are only exposed by providers on Windows.
+    #[cfg(target_os = "windows")]
+    SessionCapability::ActivateWindow,
+  ]
+}
+
+/// Whether the Wayland socket given by `WAYLAND_DISPLAY` exists.
+#[cfg(target_os = "linux")]
+fn has_wayland_socket() -> bool {
+  use std::{env, path::PathBuf};
+
+  let Ok(display) = env::var("WAYLAND_DISPLAY") else {
+    return false;
+  };
+
+  let socket_path = PathBuf::from(&display);
+
+  if socket_path.is_absolute() {
+    return socket_path.exists();
+  }
+
+  env::var("XDG_RUNTIME_DIR").is_ok_and(|runtime_dir| {
+    PathBuf::from(runtime_dir).join(display).exists()
+  })
+}
+
+/// Whether the X server given by `DISPLAY` is reachable. Displays on
+/// remote hosts (e.g. via SSH forwarding) are assumed to be reachable.
+#[cfg(target_os = "linux")]
+fn has_x11_display() -> bool {
+  let Ok(display) = std::env::var("DISPLAY") else {
+    return false;
+  };
+
+  match display.strip_prefix(':') {
+    Some(local_display) => {
+      // Strip the screen number (e.g. `:0.0`).
+      let number = local_display.split('.').next().unwrap_or_default();
+      std::path::Path::new(&format!("/tmp/.X11-unix/X{}", number)).exists()
+    }
+    None => !display.is_empty(),
+  }
+}
+
+/// Detects the compositor from the environment variables that it sets,
+/// falling back to the desktop environment name.
+#[cfg(target_os = "linux")]
+fn detect_compositor() -> Option<String> {
+  use std::env;
+
+  let known = [
+    ("HYPRLAND_INSTANCE_SIGNATURE", "Hyprland"),
+    ("SWAYSOCK", "sway"),
+    ("NIRI_SOCKET", "niri"),
+  ];
+
+  if let Some((_, name)) =
+    known.iter().find(|(var, _)| env::var_os(var).is_some())
+  {
+    return Some(name.to_string());
+  }
+
+  // `XDG_CURRENT_DESKTOP` can be a colon-separated list (e.g.
+  // `ubuntu:GNOME`), where the last entry is the most generic.
+  env::var("XDG_CURRENT_DESKTOP")
+    .ok()
+    .and_then(|desktop| desktop.rsplit(':').next().map(str::to_string))
+    .or_else(|| env::var("DESKTOP_SESSION").ok())
+    .filter(|name| !name.is_empty())
+}
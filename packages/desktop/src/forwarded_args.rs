@@ -0,0 +1,149 @@
+use std::{
+  collections::hash_map::DefaultHasher,
+  fs,
+  hash::{Hash, Hasher},
+  path::PathBuf,
+};
+
+use tracing::warn;
+
+/// Spools the CLI args of this instance, so that they arrive intact if
+/// they're forwarded to an already running instance.
+///
+/// On Windows, the single-instance plugin forwards args as a single
+/// `|`-joined string, which splits args that contain `|` and can mangle
+/// non-ASCII text. The spooled args are keyed by that joined string, so
+/// that the running instance can look them up from what it receives.
+pub fn spool_args() {
+  spool(&std::env::args().collect::<Vec<_>>());
+}
+
+fn spool(args: &[String]) {
+  if !needs_spooling(args) {
+    return;
+  }
+
+  let res = fs::create_dir_all(spool_dir())
+    .and_then(|_| fs::write(spool_path(args), serde_json::to_vec(args)?));
+
+  if let Err(err) = res {
+    warn!("Failed to spool CLI args: {:?}", err);
+  }
+}
+
+/// Removes the spooled args of this instance. Called once this instance
+/// is known to be the first one, since its args aren't forwarded.
+pub fn remove_spooled_args() {
+  let args = std::env::args().collect::<Vec<_>>();
+
+  if needs_spooling(&args) {
+    let _ = fs::remove_file(spool_path(&args));
+  }
+}
+
+/// Gets the args of another instance from the args that were forwarded
+/// by the single-instance plugin. Falls back to the forwarded args if
+/// they weren't spooled (e.g. if they didn't need to be).
+pub fn take_forwarded_args(forwarded_args: Vec<String>) -> Vec<String> {
+  let path = spool_path(&forwarded_args);
+
+  let Ok(contents) = fs::read(&path) else {
+    return forwarded_args;
+  };
+
+  let _ = fs::remove_file(&path);
+
+  serde_json::from_slice(&contents).unwrap_or_else(|err| {
+    warn!("Failed to read spooled CLI args: {:?}", err);
+    forwarded_args
+  })
+}
+
+/// Whether the args would be altered by being forwarded.
+fn needs_spooling(args: &[String]) -> bool {
+  args.iter().any(|arg| arg.contains('|') || !arg.is_ascii())
+}
+
+fn spool_dir() -> PathBuf {
+  std::env::temp_dir().join("zebar-forwarded-args")
+}
+
+/// Path of the spooled args, keyed by a hash of the `|`-joined args.
+/// Joining the split args that are received gives back the same string.
+///
+/// Non-ASCII characters and `?` (which they can be mangled into) are
+/// left out of the hash, so that mangled args still find their spooled
+/// original.
+fn spool_path(args: &[String]) -> PathBuf {
+  let mut hasher = DefaultHasher::new();
+
+  args
+    .join("|")
+    .chars()
+    .filter(|char| char.is_ascii() && *char != '?')
+    .collect::<String>()
+    .hash(&mut hasher);
+
+  spool_dir().join(format!("{:016x}.json", hasher.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  /// Args as the single-instance plugin forwards them.
+  fn forward(args: &[String]) -> Vec<String> {
+    args.join("|").split('|').map(String::from).collect()
+  }
+
+  #[test]
+  fn args_with_pipes_round_trip() {
+    let original =
+      args(&["zebar.exe", "open", "bar", "--args", "cmd=a|b=c", "sep=|"]);
+
+    spool(&original);
+
+    assert_eq!(take_forwarded_args(forward(&original)), original);
+  }
+
+  #[test]
+  fn mangled_non_ascii_args_round_trip() {
+    let original =
+      args(&["zebar.exe", "open", "bar", "--args", "name=Grüße|✓"]);
+
+    spool(&original);
+
+    let mangled = forward(&original)
+      .into_iter()
+      .map(|arg| arg.replace(|char: char| !char.is_ascii(), "?"))
+      .collect();
+
+    assert_eq!(take_forwarded_args(mangled), original);
+  }
+
+  #[test]
+  fn spooled_args_are_taken_once() {
+    let original = args(&["zebar.exe", "open", "bar", "--args", "x=1|2"]);
+
+    spool(&original);
+    take_forwarded_args(forward(&original));
+
+    assert_eq!(
+      take_forwarded_args(forward(&original)),
+      forward(&original)
+    );
+  }
+
+  #[test]
+  fn plain_args_are_forwarded_as_is() {
+    let original =
+      args(&["zebar.exe", "open", "bar", "--args", "query=a=b"]);
+
+    assert!(!needs_spooling(&original));
+    assert_eq!(take_forwarded_args(forward(&original)), original);
+  }
+}
@@ -0,0 +1,147 @@
+/// Physical key, identified by its `KeyboardEvent.code` name (e.g.
+/// `KeyZ` for the key right of left shift on US layouts).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCode {
+  pub code: &'static str,
+
+  /// Set 1 scancode. Extended keys are prefixed with `0xE0`. Only used
+  /// for layout-specific labels on Windows.
+  #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+  pub scancode: u16,
+
+  /// Label of the key on US layouts, used where the OS can't provide
+  /// a layout-specific label.
+  pub us_label: &'static str,
+}
+
+impl KeyCode {
+  /// Gets the key with the given code name (case-insensitive).
+  pub fn from_code(code: &str) -> Option<KeyCode> {
+    KEY_CODES
+      .iter()
+      .find(|key_code| key_code.code.eq_ignore_ascii_case(code))
+      .copied()
+  }
+}
+
+macro_rules! key_codes {
+  ($(($code:literal, $scancode:literal, $us_label:literal)),* $(,)?) => {
+    &[$(KeyCode {
+      code: $code,
+      scancode: $scancode,
+      us_label: $us_label,
+    }),*]
+  };
+}
+
+/// Supported physical keys, following the UI Events `code` values.
+const KEY_CODES: &[KeyCode] = key_codes![
+  ("KeyA", 0x1E, "A"),
+  ("KeyB", 0x30, "B"),
+  ("KeyC", 0x2E, "C"),
+  ("KeyD", 0x20, "D"),
+  ("KeyE", 0x12, "E"),
+  ("KeyF", 0x21, "F"),
+  ("KeyG", 0x22, "G"),
+  ("KeyH", 0x23, "H"),
+  ("KeyI", 0x17, "I"),
+  ("KeyJ", 0x24, "J"),
+  ("KeyK", 0x25, "K"),
+  ("KeyL", 0x26, "L"),
+  ("KeyM", 0x32, "M"),
+  ("KeyN", 0x31, "N"),
+  ("KeyO", 0x18, "O"),
+  ("KeyP", 0x19, "P"),
+  ("KeyQ", 0x10, "Q"),
+  ("KeyR", 0x13, "R"),
+  ("KeyS", 0x1F, "S"),
+  ("KeyT", 0x14, "T"),
+  ("KeyU", 0x16, "U"),
+  ("KeyV", 0x2F, "V"),
+  ("KeyW", 0x11, "W"),
+  ("KeyX", 0x2D, "X"),
+  ("KeyY", 0x15, "Y"),
+  ("KeyZ", 0x2C, "Z"),
+  ("Digit1", 0x02, "1"),
+  ("Digit2", 0x03, "2"),
+  ("Digit3", 0x04, "3"),
+  ("Digit4", 0x05, "4"),
+  ("Digit5", 0x06, "5"),
+  ("Digit6", 0x07, "6"),
+  ("Digit7", 0x08, "7"),
+  ("Digit8", 0x09, "8"),
+  ("Digit9", 0x0A, "9"),
+  ("Digit0", 0x0B, "0"),
+  ("Minus", 0x0C, "-"),
+  ("Equal", 0x0D, "="),
+  ("BracketLeft", 0x1A, "["),
+  ("BracketRight", 0x1B, "]"),
+  ("Backslash", 0x2B, "\\"),
+  ("IntlBackslash", 0x56, "\\"),
+  ("Semicolon", 0x27, ";"),
+  ("Quote", 0x28, "'"),
+  ("Backquote", 0x29, "`"),
+  ("Comma", 0x33, ","),
+  ("Period", 0x34, "."),
+  ("Slash", 0x35, "/"),
+  ("Escape", 0x01, "Escape"),
+  ("Backspace", 0x0E, "Backspace"),
+  ("Tab", 0x0F, "Tab"),
+  ("Enter", 0x1C, "Enter"),
+  ("Space", 0x39, "Space"),
+  ("CapsLock", 0x3A, "CapsLock"),
+  ("F1", 0x3B, "F1"),
+  ("F2", 0x3C, "F2"),
+  ("F3", 0x3D, "F3"),
+  ("F4", 0x3E, "F4"),
+  ("F5", 0x3F, "F5"),
+  ("F6", 0x40, "F6"),
+  ("F7", 0x41, "F7"),
+  ("F8", 0x42, "F8"),
+  ("F9", 0x43, "F9"),
+  ("F10", 0x44, "F10"),
+  ("F11", 0x57, "F11"),
+  ("F12", 0x58, "F12"),
+  ("F13", 0x64, "F13"),
+  ("F14", 0x65, "F14"),
+  ("F15", 0x66, "F15"),
+  ("F16", 0x67, "F16"),
+  ("F17", 0x68, "F17"),
+  ("F18", 0x69, "F18"),
+  ("F19", 0x6A, "F19"),
+  ("F20", 0x6B, "F20"),
+  ("F21", 0x6C, "F21"),
+  ("F22", 0x6D, "F22"),
+  ("F23", 0x6E, "F23"),
+  ("F24", 0x76, "F24"),
+  ("PrintScreen", 0xE037, "PrintScreen"),
+  ("ScrollLock", 0x46, "ScrollLock"),
+  ("Pause", 0x45, "Pause"),
+  ("Insert", 0xE052, "Insert"),
+  ("Delete", 0xE053, "Delete"),
+  ("Home", 0xE047, "Home"),
+  ("End", 0xE04F, "End"),
+  ("PageUp", 0xE049, "PageUp"),
+  ("PageDown", 0xE051, "PageDown"),
+  ("ArrowUp", 0xE048, "Up"),
+  ("ArrowDown", 0xE050, "Down"),
+  ("ArrowLeft", 0xE04B, "Left"),
+  ("ArrowRight", 0xE04D, "Right"),
+  ("NumLock", 0xE045, "NumLock"),
+  ("Numpad0", 0x52, "Num 0"),
+  ("Numpad1", 0x4F, "Num 1"),
+  ("Numpad2", 0x50, "Num 2"),
+  ("Numpad3", 0x51, "Num 3"),
+  ("Numpad4", 0x4B, "Num 4"),
+  ("Numpad5", 0x4C, "Num 5"),
+  ("Numpad6", 0x4D, "Num 6"),
+  ("Numpad7", 0x47, "Num 7"),
+  ("Numpad8", 0x48, "Num 8"),
+  ("Numpad9", 0x49, "Num 9"),
+  ("NumpadAdd", 0x4E, "Num +"),
+  ("NumpadSubtract", 0x4A, "Num -"),
+  ("NumpadMultiply", 0x37, "Num *"),
+  ("NumpadDivide", 0xE035, "Num /"),
+  ("NumpadDecimal", 0x53, "Num ."),
+  ("NumpadEnter", 0xE01C, "Num Enter"),
+];
@@ -0,0 +1,36 @@
+use super::KeyCode;
+
+/// Gets the label of the physical key on the current keyboard layout
+/// (e.g. `Z` for `KeyY` on German layouts).
+///
+/// Falls back to the key's label on US layouts if the OS doesn't
+/// provide one.
+pub fn key_name(key_code: &KeyCode) -> String {
+  platform_key_name(key_code)
+    .unwrap_or_else(|| key_code.us_label.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_key_name(key_code: &KeyCode) -> Option<String> {
+  use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyNameTextW;
+
+  let is_extended = key_code.scancode & 0xE000 == 0xE000;
+
+  // Bits 16-23 hold the scancode and bit 24 the extended flag.
+  let lparam = ((key_code.scancode as i32 & 0xFF) << 16)
+    | ((is_extended as i32) << 24);
+
+  let mut buffer = [0u16; 64];
+  let len = unsafe { GetKeyNameTextW(lparam, &mut buffer) };
+
+  match len {
+    0 => None,
+    len => Some(String::from_utf16_lossy(&buffer[..len as usize])),
+  }
+}
+
+/// Layout-specific labels are only available on Windows.
+#[cfg(not(target_os = "windows"))]
+fn platform_key_name(_: &KeyCode) -> Option<String> {
+  None
+}
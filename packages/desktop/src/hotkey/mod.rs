@@ -0,0 +1,467 @@
+use std::{fmt, str::FromStr};
+
+use serde::Serialize;
+
+pub use self::key_code::KeyCode;
+use self::key_name::key_name;
+
+mod key_code;
+mod key_name;
+
+/// Named keys that can be bound by the character or function they
+/// produce, along with their accepted aliases.
+const NAMED_KEYS: &[(&str, &[&str])] = &[
+  ("Space", &[]),
+  ("Enter", &["Return"]),
+  ("Tab", &[]),
+  ("Escape", &["Esc"]),
+  ("Backspace", &[]),
+  ("Delete", &["Del"]),
+  ("Insert", &["Ins"]),
+  ("Home", &[]),
+  ("End", &[]),
+  ("PageUp", &["PgUp"]),
+  ("PageDown", &["PgDn"]),
+  ("Up", &["ArrowUp"]),
+  ("Down", &["ArrowDown"]),
+  ("Left", &["ArrowLeft"]),
+  ("Right", &["ArrowRight"]),
+  ("PrintScreen", &["PrtSc"]),
+  ("CapsLock", &[]),
+  ("Plus", &[]),
+  ("Minus", &[]),
+  ("Comma", &[]),
+  ("Period", &[]),
+];
+
+/// Modifier keys, in the order they're written in canonical bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modifier {
+  Ctrl,
+  Alt,
+  Shift,
+  Super,
+}
+
+impl Modifier {
+  const ALL: [Modifier; 4] = [
+    Modifier::Ctrl,
+    Modifier::Alt,
+    Modifier::Shift,
+    Modifier::Super,
+  ];
+
+  fn parse(token: &str) -> Option<Modifier> {
+    match token.to_ascii_lowercase().as_str() {
+      "ctrl" | "control" => Some(Modifier::Ctrl),
+      "alt" | "option" => Some(Modifier::Alt),
+      "shift" => Some(Modifier::Shift),
+      "super" | "win" | "meta" | "cmd" | "command" => {
+        Some(Modifier::Super)
+      }
+      // Same as Tauri's accelerators.
+      "cmdorctrl" | "commandorcontrol" => {
+        match cfg!(target_os = "macos") {
+          true => Some(Modifier::Super),
+          false => Some(Modifier::Ctrl),
+        }
+      }
+      _ => None,
+    }
+  }
+
+  fn bit(self) -> u8 {
+    1 << self as u8
+  }
+
+  fn name(self) -> &'static str {
+    match self {
+      Modifier::Ctrl => "Ctrl",
+      Modifier::Alt => "Alt",
+      Modifier::Shift => "Shift",
+      Modifier::Super => "Super",
+    }
+  }
+}
+
+/// Key of a hotkey, other than its modifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyKey {
+  /// Key that produces the given character or function on the current
+  /// layout (e.g. `Z`), wherever it's located.
+  Virtual(String),
+
+  /// Key at the given physical location, regardless of layout (e.g.
+  /// `<KeyZ>`).
+  Physical(KeyCode),
+}
+
+impl HotkeyKey {
+  fn parse(token: &str) -> Option<HotkeyKey> {
+    if let Some(code) = token
+      .strip_prefix('<')
+      .and_then(|rest| rest.strip_suffix('>'))
+    {
+      return KeyCode::from_code(code).map(HotkeyKey::Physical);
+    }
+
+    let mut chars = token.chars();
+
+    if let (Some(char), None) = (chars.next(), chars.next()) {
+      return char
+        .is_ascii_alphanumeric()
+        .then(|| HotkeyKey::Virtual(char.to_ascii_uppercase().into()));
+    }
+
+    let is_function_key = token
+      .strip_prefix(|char| char == 'F' || char == 'f')
+      .and_then(|number| number.parse::<u8>().ok())
+      .is_some_and(|number| (1..=24).contains(&number));
+
+    if is_function_key {
+      return Some(HotkeyKey::Virtual(token.to_ascii_uppercase()));
+    }
+
+    NAMED_KEYS
+      .iter()
+      .find(|(name, aliases)| {
+        name.eq_ignore_ascii_case(token)
+          || aliases
+            .iter()
+            .any(|alias| alias.eq_ignore_ascii_case(token))
+      })
+      .map(|(name, _)| HotkeyKey::Virtual(name.to_string()))
+  }
+
+  /// Gets the label of the key, with physical keys labeled by the given
+  /// function (e.g. as on the current keyboard layout).
+  fn label_with(&self, key_name: impl Fn(&KeyCode) -> String) -> String {
+    match self {
+      HotkeyKey::Virtual(name) => name.clone(),
+      HotkeyKey::Physical(key_code) => key_name(key_code),
+    }
+  }
+}
+
+impl fmt::Display for HotkeyKey {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HotkeyKey::Virtual(name) => write!(f, "{}", name),
+      HotkeyKey::Physical(key_code) => write!(f, "<{}>", key_code.code),
+    }
+  }
+}
+
+/// Key combination, parsed from a binding such as `Ctrl+Alt+Z` or
+/// `Ctrl+Alt+<KeyZ>`.
+///
+/// Keys written as is (e.g. `Z`) are bound by the character they produce
+/// on the current layout, whereas keys in angle brackets are bound by
+/// their physical location (e.g. `<KeyZ>` is `Y` on German layouts).
+///
+/// Hotkeys are only parsed and labeled. They aren't registered globally,
+/// so conflicts with hotkeys of other apps aren't detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkey {
+  modifiers: u8,
+  pub key: HotkeyKey,
+}
+
+impl Hotkey {
+  fn modifiers(&self) -> impl Iterator<Item = Modifier> + '_ {
+    Modifier::ALL
+      .into_iter()
+      .filter(|modifier| self.modifiers & modifier.bit() != 0)
+  }
+
+  /// Gets the binding as shown to the user, with the key labeled as on
+  /// the current keyboard layout.
+  pub fn label(&self) -> String {
+    self.label_with(key_name)
+  }
+
+  fn label_with(&self, key_name: impl Fn(&KeyCode) -> String) -> String {
+    self
+      .modifiers()
+      .map(|modifier| modifier.name().to_string())
+      .chain([self.key.label_with(key_name)])
+      .collect::<Vec<_>>()
+      .join("+")
+  }
+}
+
+/// Bindings are formatted canonically (e.g. `control+alt+z` becomes
+/// `Ctrl+Alt+Z`), and parse back to the same hotkey.
+impl fmt::Display for Hotkey {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for modifier in self.modifiers() {
+      write!(f, "{}+", modifier.name())?;
+    }
+
+    write!(f, "{}", self.key)
+  }
+}
+
+impl FromStr for Hotkey {
+  type Err = InvalidHotkeyError;
+
+  fn from_str(hotkey: &str) -> Result<Self, Self::Err> {
+    let invalid = |token: &str, message: String| {
+      InvalidHotkeyError::new(hotkey, token, message)
+    };
+
+    let mut modifiers = 0;
+    let mut key = None;
+
+    for token in hotkey.split('+').map(str::trim) {
+      if token.is_empty() {
+        return Err(invalid(
+          token,
+          "Empty key. Use 'Plus' to bind the + key.".into(),
+        ));
+      }
+
+      if let Some(modifier) = Modifier::parse(token) {
+        if modifiers & modifier.bit() != 0 {
+          return Err(invalid(
+            token,
+            format!("Duplicate modifier '{}'.", token),
+          ));
+        }
+
+        modifiers |= modifier.bit();
+        continue;
+      }
+
+      if key.is_some() {
+        return Err(invalid(
+          token,
+          format!(
+            "Unexpected key '{}'. Only one non-modifier key is allowed.",
+            token
+          ),
+        ));
+      }
+
+      key = Some(HotkeyKey::parse(token).ok_or_else(|| {
+        invalid(token, format!("Unknown key '{}'.", token))
+      })?);
+    }
+
+    let key = key.ok_or_else(|| {
+      let last_token = hotkey.rsplit('+').next().unwrap_or_default();
+      invalid(last_token.trim(), "Missing a non-modifier key.".into())
+    })?;
+
+    Ok(Hotkey { modifiers, key })
+  }
+}
+
+/// Error returned when a hotkey binding can't be parsed. Includes the
+/// offending token of the binding.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidHotkeyError {
+  pub kind: &'static str,
+  pub hotkey: String,
+  pub token: String,
+  pub message: String,
+}
+
+impl InvalidHotkeyError {
+  fn new(hotkey: &str, token: &str, message: String) -> Self {
+    Self {
+      kind: "invalid_hotkey",
+      hotkey: hotkey.to_string(),
+      token: token.to_string(),
+      message: format!("Invalid hotkey '{}': {}", hotkey, message),
+    }
+  }
+}
+
+impl fmt::Display for InvalidHotkeyError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for InvalidHotkeyError {}
+
+/// Hotkey binding in canonical form, along with its label on the
+/// current keyboard layout.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedHotkey {
+  /// Canonical binding (e.g. `Ctrl+Alt+<KeyZ>`).
+  pub hotkey: String,
+
+  /// Binding as shown to the user (e.g. `Ctrl+Alt+Y` on German layouts
+  /// for `Ctrl+Alt+<KeyZ>`). Physical keys are labeled as on US layouts
+  /// outside of Windows.
+  pub label: String,
+
+  /// Whether the key is bound by its physical location.
+  pub is_physical: bool,
+}
+
+impl From<&Hotkey> for ParsedHotkey {
+  fn from(hotkey: &Hotkey) -> Self {
+    Self {
+      hotkey: hotkey.to_string(),
+      label: hotkey.label(),
+      is_physical: matches!(hotkey.key, HotkeyKey::Physical(_)),
+    }
+  }
+}
+
+/// Gets the label of the physical key with the given code (e.g. `KeyZ`)
+/// on the current keyboard layout. `None` if the code is unknown.
+///
+/// Only Windows provides layout-specific labels. Elsewhere, the key's
+/// label on US layouts is returned.
+pub fn get_key_name(code: &str) -> Option<String> {
+  KeyCode::from_code(code).map(|key_code| key_name(&key_code))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Labels of keys that differ on German (QWERTZ) layouts.
+  fn de_key_name(key_code: &KeyCode) -> String {
+    match key_code.code {
+      "KeyY" => "Z",
+      "KeyZ" => "Y",
+      "Minus" => "ß",
+      "Equal" => "´",
+      "BracketLeft" => "Ü",
+      "Semicolon" => "Ö",
+      "Quote" => "Ä",
+      "Backquote" => "^",
+      "Slash" => "-",
+      "IntlBackslash" => "<",
+      _ => key_code.us_label,
+    }
+    .to_string()
+  }
+
+  fn us_key_name(key_code: &KeyCode) -> String {
+    key_code.us_label.to_string()
+  }
+
+  fn hotkey(binding: &str) -> Hotkey {
+    binding.parse().unwrap()
+  }
+
+  /// Asserts that the binding formats canonically, and that the
+  /// canonical form parses back to the same hotkey.
+  fn assert_round_trip(binding: &str, canonical: &str) {
+    let parsed = hotkey(binding);
+
+    assert_eq!(parsed.to_string(), canonical);
+    assert_eq!(hotkey(canonical), parsed);
+    assert_eq!(hotkey(canonical).to_string(), canonical);
+  }
+
+  #[test]
+  fn round_trips_virtual_keys() {
+    assert_round_trip("ctrl+alt+z", "Ctrl+Alt+Z");
+    assert_round_trip("Shift + Control + 5", "Ctrl+Shift+5");
+    assert_round_trip("win+f12", "Super+F12");
+    assert_round_trip("Meta+PgUp", "Super+PageUp");
+    assert_round_trip("alt+ArrowLeft", "Alt+Left");
+    assert_round_trip("Ctrl+Plus", "Ctrl+Plus");
+    assert_round_trip("Escape", "Escape");
+  }
+
+  #[test]
+  fn round_trips_physical_keys() {
+    assert_round_trip("Ctrl+Alt+<KeyZ>", "Ctrl+Alt+<KeyZ>");
+    assert_round_trip("super+<keyy>", "Super+<KeyY>");
+    assert_round_trip("<numpadenter>", "<NumpadEnter>");
+  }
+
+  #[test]
+  fn modifiers_are_ordered_canonically() {
+    assert_eq!(
+      hotkey("Super+Shift+Alt+Ctrl+A"),
+      hotkey("Ctrl+Alt+Shift+Super+A")
+    );
+    assert_eq!(
+      hotkey("Super+Shift+Alt+Ctrl+A").to_string(),
+      "Ctrl+Alt+Shift+Super+A"
+    );
+  }
+
+  #[test]
+  fn physical_keys_are_labeled_by_layout() {
+    let z = hotkey("Ctrl+Alt+<KeyZ>");
+    let y = hotkey("Ctrl+Alt+<KeyY>");
+    let minus = hotkey("Ctrl+<Minus>");
+
+    assert_eq!(z.label_with(us_key_name), "Ctrl+Alt+Z");
+    assert_eq!(z.label_with(de_key_name), "Ctrl+Alt+Y");
+    assert_eq!(y.label_with(us_key_name), "Ctrl+Alt+Y");
+    assert_eq!(y.label_with(de_key_name), "Ctrl+Alt+Z");
+    assert_eq!(minus.label_with(us_key_name), "Ctrl+-");
+    assert_eq!(minus.label_with(de_key_name), "Ctrl+ß");
+
+    // The canonical binding doesn't depend on the layout.
+    assert_eq!(z.to_string(), "Ctrl+Alt+<KeyZ>");
+  }
+
+  #[test]
+  fn virtual_keys_are_labeled_the_same_on_all_layouts() {
+    let z = hotkey("Ctrl+Alt+Z");
+
+    assert_eq!(z.label_with(us_key_name), "Ctrl+Alt+Z");
+    assert_eq!(z.label_with(de_key_name), "Ctrl+Alt+Z");
+  }
+
+  #[test]
+  fn cmd_or_ctrl_depends_on_platform() {
+    let expected = match cfg!(target_os = "macos") {
+      true => "Super+K",
+      false => "Ctrl+K",
+    };
+
+    assert_eq!(hotkey("CmdOrCtrl+K").to_string(), expected);
+  }
+
+  #[test]
+  fn rejects_invalid_bindings() {
+    let cases = [
+      ("Ctrl++", ""),
+      ("Ctrl+Alt+Foo", "Foo"),
+      ("Ctrl+ctrl+A", "ctrl"),
+      ("Ctrl+A+B", "B"),
+      ("Ctrl+Alt", "Alt"),
+      ("<KeyÄ>", "<KeyÄ>"),
+      ("F25", "F25"),
+      ("Ctrl+ä", "ä"),
+    ];
+
+    for (binding, token) in cases {
+      let err = binding.parse::<Hotkey>().unwrap_err();
+
+      assert_eq!(err.kind, "invalid_hotkey");
+      assert_eq!(err.hotkey, binding);
+      assert_eq!(err.token, token, "Unexpected token for '{}'.", binding);
+    }
+  }
+
+  #[test]
+  fn key_codes_are_case_insensitive() {
+    assert_eq!(
+      KeyCode::from_code("keyz").map(|key_code| key_code.us_label),
+      Some("Z")
+    );
+    assert!(KeyCode::from_code("KeyÄ").is_none());
+  }
+
+  #[test]
+  fn parsed_hotkey_flags_physical_keys() {
+    assert!(ParsedHotkey::from(&hotkey("Ctrl+<KeyZ>")).is_physical);
+    assert!(!ParsedHotkey::from(&hotkey("Ctrl+Z")).is_physical);
+  }
+}
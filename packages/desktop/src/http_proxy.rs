@@ -0,0 +1,296 @@
+use std::{
+  collections::HashMap,
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{header::CONTENT_TYPE, Method, Url};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+  providers::provider_manager::ProviderManager,
+  secrets::{SecretsStore, ValueOrSecret},
+};
+
+/// Timeout of requests that don't specify one.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Max size of a response body in bytes. Larger responses are rejected,
+/// since they're passed to the webview over IPC.
+const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Max number of cached responses.
+const MAX_CACHE_ENTRIES: usize = 256;
+
+/// Request made from Rust on behalf of a window.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpRequestOptions {
+  pub url: String,
+
+  #[serde(default = "default_method")]
+  pub method: String,
+
+  /// Header values are either strings or references to secrets (e.g.
+  /// `{ secret: "github_token" }`). Secrets are only sent to the hosts
+  /// in their `secret_hosts`.
+  #[serde(default)]
+  pub headers: HashMap<String, ValueOrSecret>,
+
+  /// Query parameters to append to the URL. Values can reference
+  /// secrets the same way as headers.
+  #[serde(default)]
+  pub query: HashMap<String, ValueOrSecret>,
+
+  pub body: Option<String>,
+
+  /// Timeout in milliseconds. Defaults to 30 seconds.
+  pub timeout: Option<u64>,
+
+  /// Duration in milliseconds to cache successful `GET` responses for.
+  /// Responses aren't cached if omitted.
+  pub cache_ttl: Option<u64>,
+}
+
+fn default_method() -> String {
+  "GET".into()
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpResponse {
+  pub status: u16,
+  pub headers: HashMap<String, String>,
+
+  /// Body as text, or base64-encoded if it isn't text.
+  pub body: String,
+  pub body_encoding: BodyEncoding,
+
+  /// Whether the response was served from the cache.
+  pub cached: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyEncoding {
+  Text,
+  Base64,
+}
+
+/// Cached responses to `GET` requests, keyed by the request.
+#[derive(Default)]
+pub struct HttpResponseCache(Mutex<HashMap<String, CachedResponse>>);
+
+struct CachedResponse {
+  expires_at: Instant,
+  response: HttpResponse,
+}
+
+impl HttpResponseCache {
+  fn get(&self, key: &str) -> Option<HttpResponse> {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .get(key)
+      .filter(|cached| cached.expires_at > Instant::now())
+      .map(|cached| cached.response.clone())
+  }
+
+  fn insert(&self, key: String, response: HttpResponse, ttl: Duration) {
+    let mut cache = self.0.lock().unwrap();
+    let now = Instant::now();
+
+    cache.retain(|_, cached| cached.expires_at > now);
+
+    // Evict the entry that expires soonest if still full.
+    if cache.len() >= MAX_CACHE_ENTRIES {
+      let soonest = cache
+        .iter()
+        .min_by_key(|(_, cached)| cached.expires_at)
+        .map(|(key, _)| key.clone());
+
+      if let Some(soonest) = soonest {
+        cache.remove(&soonest);
+      }
+    }
+
+    cache.insert(
+      key,
+      CachedResponse {
+        expires_at: now + ttl,
+        response,
+      },
+    );
+  }
+}
+
+/// Parses the URL of a request. Only HTTP(S) URLs with a host are
+/// allowed.
+pub fn parse_url(url: &str) -> anyhow::Result<Url> {
+  let url =
+    Url::parse(url).with_context(|| format!("Invalid URL '{}'.", url))?;
+
+  if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none()
+  {
+    bail!("URL '{}' must be an HTTP(S) URL with a host.", url);
+  }
+
+  Ok(url)
+}
+
+/// Sends the request, or serves it from the cache if possible.
+///
+/// Expects the window to have been checked for access to the URL's
+/// host.
+pub async fn send_request(
+  app_handle: &AppHandle,
+  mut url: Url,
+  options: HttpRequestOptions,
+) -> anyhow::Result<HttpResponse> {
+  let method =
+    Method::from_bytes(options.method.to_uppercase().as_bytes())
+      .with_context(|| format!("Invalid method '{}'.", options.method))?;
+
+  let cache_ttl = options
+    .cache_ttl
+    .filter(|_| method == Method::GET)
+    .map(Duration::from_millis);
+
+  // Keyed by the names rather than the values of secrets, so that
+  // secrets aren't kept in memory longer than needed.
+  let cache_key = cache_ttl
+    .map(|_| {
+      serde_json::to_string(&(
+        url.as_str(),
+        sorted(&options.headers),
+        sorted(&options.query),
+      ))
+    })
+    .transpose()?;
+
+  let cache = app_handle.state::<HttpResponseCache>();
+
+  if let Some(cached) = cache_key.as_deref().and_then(|key| cache.get(key))
+  {
+    return Ok(HttpResponse {
+      cached: true,
+      ..cached
+    });
+  }
+
+  let secrets_store = app_handle.state::<SecretsStore>();
+  let host = url.host_str().unwrap_or_default().to_string();
+
+  for (param, value) in sorted(&options.query) {
+    let value = value.resolve_for_host(&secrets_store, &host)?;
+    url.query_pairs_mut().append_pair(param, &value);
+  }
+
+  let mut request = app_handle
+    .state::<ProviderManager>()
+    .http_client()
+    .request(method, url)?
+    .timeout(
+      options
+        .timeout
+        .map_or(DEFAULT_TIMEOUT, Duration::from_millis),
+    );
+
+  for (name, value) in &options.headers {
+    request =
+      request.header(name, value.resolve_for_host(&secrets_store, &host)?);
+  }
+
+  if let Some(body) = options.body {
+    request = request.body(body);
+  }
+
+  // The URL is stripped from errors, since the query can contain
+  // secrets.
+  let response = request.send().await.map_err(|err| err.without_url())?;
+  let status = response.status();
+
+  let headers = response
+    .headers()
+    .iter()
+    .filter_map(|(name, value)| {
+      Some((name.to_string(), value.to_str().ok()?.to_string()))
+    })
+    .collect::<HashMap<_, _>>();
+
+  let is_text = response
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|value| value.to_str().ok())
+    .is_some_and(is_text_content_type);
+
+  if response
+    .content_length()
+    .is_some_and(|length| length as usize > MAX_BODY_SIZE)
+  {
+    bail!("Response body exceeds {} bytes.", MAX_BODY_SIZE);
+  }
+
+  let bytes = response.bytes().await?;
+
+  if bytes.len() > MAX_BODY_SIZE {
+    bail!("Response body exceeds {} bytes.", MAX_BODY_SIZE);
+  }
+
+  let (body, body_encoding) = match is_text {
+    true => match String::from_utf8(bytes.to_vec()) {
+      Ok(text) => (text, BodyEncoding::Text),
+      Err(_) => (STANDARD.encode(&bytes), BodyEncoding::Base64),
+    },
+    false => (STANDARD.encode(&bytes), BodyEncoding::Base64),
+  };
+
+  let response = HttpResponse {
+    status: status.as_u16(),
+    headers,
+    body,
+    body_encoding,
+    cached: false,
+  };
+
+  if let (Some(cache_key), Some(cache_ttl)) = (cache_key, cache_ttl) {
+    if status.is_success() {
+      cache.insert(cache_key, response.clone(), cache_ttl);
+    }
+  }
+
+  Ok(response)
+}
+
+/// Whether the content type is text that can be returned as a string.
+fn is_text_content_type(content_type: &str) -> bool {
+  let mime = content_type
+    .split(';')
+    .next()
+    .unwrap_or_default()
+    .trim()
+    .to_ascii_lowercase();
+
+  mime.starts_with("text/")
+    || mime.ends_with("+json")
+    || mime.ends_with("+xml")
+    || matches!(
+      mime.as_str(),
+      "application/json"
+        | "application/xml"
+        | "application/javascript"
+        | "application/x-www-form-urlencoded"
+    )
+}
+
+fn sorted(
+  map: &HashMap<String, ValueOrSecret>,
+) -> Vec<(&String, &ValueOrSecret)> {
+  let mut entries = map.iter().collect::<Vec<_>>();
+  entries.sort();
+  entries
+}
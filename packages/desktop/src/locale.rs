@@ -0,0 +1,187 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use anyhow::bail;
+use serde::Deserialize;
+
+/// Locale that strings formatted on the Rust side (e.g. the relative age
+/// of provider outputs) are localized to.
+///
+/// Only the language is used, so region subtags are accepted but ignored
+/// (e.g. `de-AT` is the same as `de`).
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum Locale {
+  #[default]
+  En,
+  De,
+  Es,
+  Fr,
+  Ja,
+  Ko,
+  Zh,
+}
+
+/// Locales that strings can be formatted in.
+pub const SUPPORTED_LOCALES: &[Locale] = &[
+  Locale::En,
+  Locale::De,
+  Locale::Es,
+  Locale::Fr,
+  Locale::Ja,
+  Locale::Ko,
+  Locale::Zh,
+];
+
+impl Locale {
+  /// Language code of the locale (e.g. `de`).
+  pub fn tag(&self) -> &'static str {
+    match self {
+      Locale::En => "en",
+      Locale::De => "de",
+      Locale::Es => "es",
+      Locale::Fr => "fr",
+      Locale::Ja => "ja",
+      Locale::Ko => "ko",
+      Locale::Zh => "zh",
+    }
+  }
+
+  /// Gets the locale from the config, falling back to the OS locale.
+  pub fn resolve(configured: Option<Locale>) -> Locale {
+    configured.unwrap_or_else(Locale::system)
+  }
+
+  /// Gets the locale of the OS. Falls back to English if the OS locale
+  /// isn't supported or can't be read.
+  ///
+  /// The OS locale is only read once, so changes to it require a
+  /// restart.
+  pub fn system() -> Locale {
+    static SYSTEM_LOCALE: OnceLock<Locale> = OnceLock::new();
+
+    *SYSTEM_LOCALE.get_or_init(|| {
+      system_locale_tag()
+        .and_then(|tag| tag.parse().ok())
+        .unwrap_or_default()
+    })
+  }
+}
+
+impl FromStr for Locale {
+  type Err = anyhow::Error;
+
+  /// Parses a BCP 47 or POSIX locale tag (e.g. `de`, `de-AT`, `zh-Hans`
+  /// or `de_AT.UTF-8`).
+  fn from_str(tag: &str) -> anyhow::Result<Self> {
+    let language = tag
+      .split(['-', '_', '.', '@'])
+      .next()
+      .unwrap_or_default()
+      .to_lowercase();
+
+    let locale = SUPPORTED_LOCALES
+      .iter()
+      .find(|locale| locale.tag() == language);
+
+    match locale {
+      Some(locale) => Ok(*locale),
+      None => bail!(
+        "Unsupported locale '{}'. Supported locales: {}.",
+        tag,
+        SUPPORTED_LOCALES
+          .iter()
+          .map(Locale::tag)
+          .collect::<Vec<_>>()
+          .join(", ")
+      ),
+    }
+  }
+}
+
+impl TryFrom<String> for Locale {
+  type Error = anyhow::Error;
+
+  fn try_from(tag: String) -> anyhow::Result<Self> {
+    tag.parse()
+  }
+}
+
+/// Reads the tag of the user's locale on Windows (e.g. `de-DE`).
+#[cfg(windows)]
+fn system_locale_tag() -> Option<String> {
+  use windows::Win32::Globalization::GetUserDefaultLocaleName;
+
+  let mut buffer = [0u16; 85];
+  let len = unsafe { GetUserDefaultLocaleName(&mut buffer) };
+
+  // The returned length includes the null terminator.
+  (len > 1).then(|| String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+/// Reads the tag of the user's locale from the environment (e.g.
+/// `de_DE.UTF-8`). Apps launched from the macOS Finder don't have these
+/// set, in which case the system preference is read instead.
+#[cfg(not(windows))]
+fn system_locale_tag() -> Option<String> {
+  let env_tag = ["LC_ALL", "LC_MESSAGES", "LANG"]
+    .iter()
+    .filter_map(|name| std::env::var(name).ok())
+    .find(|tag| !tag.is_empty() && tag != "C" && tag != "POSIX");
+
+  #[cfg(target_os = "macos")]
+  let env_tag = env_tag.or_else(|| {
+    let output = std::process::Command::new("defaults")
+      .args(["read", "-g", "AppleLocale"])
+      .output()
+      .ok()?;
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+      .filter(|tag| !tag.is_empty())
+  });
+
+  env_tag
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_bcp47_and_posix_tags() {
+    assert_eq!("en".parse::<Locale>().unwrap(), Locale::En);
+    assert_eq!("de-AT".parse::<Locale>().unwrap(), Locale::De);
+    assert_eq!("de_DE.UTF-8".parse::<Locale>().unwrap(), Locale::De);
+    assert_eq!("fr_FR@euro".parse::<Locale>().unwrap(), Locale::Fr);
+    assert_eq!("zh-Hans-CN".parse::<Locale>().unwrap(), Locale::Zh);
+    assert_eq!("JA".parse::<Locale>().unwrap(), Locale::Ja);
+  }
+
+  #[test]
+  fn unsupported_tag_lists_supported_locales() {
+    let err = "pt-BR".parse::<Locale>().unwrap_err().to_string();
+
+    assert!(err.contains("'pt-BR'"));
+    assert!(err.contains("en, de, es, fr, ja, ko, zh"));
+    assert!("".parse::<Locale>().is_err());
+  }
+
+  #[test]
+  fn deserializes_from_string() {
+    let locale = serde_json::from_str::<Locale>("\"ko-KR\"").unwrap();
+
+    assert_eq!(locale, Locale::Ko);
+    assert!(serde_json::from_str::<Locale>("\"xx\"").is_err());
+  }
+
+  #[test]
+  fn configured_locale_takes_precedence() {
+    assert_eq!(Locale::resolve(Some(Locale::De)), Locale::De);
+  }
+
+  #[test]
+  fn tags_round_trip() {
+    for locale in SUPPORTED_LOCALES {
+      assert_eq!(locale.tag().parse::<Locale>().unwrap(), *locale);
+    }
+  }
+}
@@ -0,0 +1,82 @@
+use std::{fs, path::PathBuf};
+
+use tracing::level_filters::LevelFilter;
+use tracing_appender::{
+  non_blocking::WorkerGuard,
+  rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{
+  fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter,
+};
+
+/// Number of daily log files that are kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 5;
+
+/// Gets the path to the log directory at `~/.glzr/zebar/logs`.
+pub fn log_dir() -> Option<PathBuf> {
+  dirs::home_dir().map(|home| home.join(".glzr/zebar/logs"))
+}
+
+/// Initializes logging to stdout and to a daily rotated file in the log
+/// directory.
+///
+/// The returned guard flushes pending writes to the log file when
+/// dropped, so it needs to be held for the lifetime of the app.
+pub fn init_logging() -> Option<WorkerGuard> {
+  let file_appender = log_dir().and_then(|log_dir| {
+    RollingFileAppender::builder()
+      .rotation(Rotation::DAILY)
+      .filename_prefix("zebar")
+      .filename_suffix("log")
+      .max_log_files(MAX_LOG_FILES)
+      .build(log_dir)
+      .ok()
+  });
+
+  let (file_layer, guard) = match file_appender {
+    Some(file_appender) => {
+      let (writer, guard) = tracing_appender::non_blocking(file_appender);
+      let layer = fmt::layer().with_ansi(false).with_writer(writer);
+      (Some(layer), Some(guard))
+    }
+    None => (None, None),
+  };
+
+  tracing_subscriber::registry()
+    .with(
+      EnvFilter::from_env("LOG_LEVEL")
+        .add_directive(LevelFilter::INFO.into()),
+    )
+    .with(fmt::layer())
+    .with(file_layer)
+    .init();
+
+  guard
+}
+
+/// Gets the paths of log files in the log directory, most recently
+/// modified first.
+pub fn recent_log_files(limit: usize) -> Vec<PathBuf> {
+  let Some(entries) = log_dir().and_then(|dir| fs::read_dir(dir).ok())
+  else {
+    return Vec::new();
+  };
+
+  let mut files = entries
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| {
+      entry.path().extension().is_some_and(|ext| ext == "log")
+    })
+    .filter_map(|entry| {
+      let modified = entry.metadata().and_then(|meta| meta.modified());
+      modified.ok().map(|modified| (modified, entry.path()))
+    })
+    .collect::<Vec<_>>();
+
+  files.sort_by(|a, b| b.0.cmp(&a.0));
+  files
+    .into_iter()
+    .take(limit)
+    .map(|(_, path)| path)
+    .collect()
+}
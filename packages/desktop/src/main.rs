@@ -1,37 +1,113 @@
-use std::{collections::HashMap, env, sync::Arc};
+use std::{
+  collections::HashMap,
+  env,
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::Duration,
+};
 
+use anyhow::Context;
 use clap::Parser;
 use providers::{
-  config::ProviderConfig, provider_manager::init_provider_manager,
+  config::{available_providers, AvailableProvider, ProviderConfig},
+  provider_manager::init_provider_manager,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::{
-  AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder, Window,
+  window::{ProgressBarState, ProgressBarStatus},
+  AppHandle, Manager, PhysicalPosition, PhysicalSize, State,
+  WebviewWindowBuilder, Window, WindowEvent,
 };
 use tokio::{
   sync::{
     mpsc::{self, UnboundedSender},
-    Mutex,
+    oneshot, Mutex,
   },
-  task,
+  task, time,
 };
-use tracing::{info, level_filters::LevelFilter};
-use tracing_subscriber::EnvFilter;
+use tracing::{info, warn};
 
 use crate::{
-  cli::{Cli, CliCommand},
-  monitors::get_monitors_str,
-  providers::provider_manager::ProviderManager,
-  sys_tray::setup_sys_tray,
-  util::window_ext::WindowExt,
+  accessibility::{watch_accessibility_settings, AccessibilitySettings},
+  app_errors::{report_error, AppError, AppErrors},
+  app_info::AppInfo,
+  asset_protocol::{AssetRoot, WidgetRoots, ASSET_SCHEME},
+  cli::{Cli, CliCommand, OpenCommandArgs, PresetsOutput, SecretCommand},
+  command_error::CommandError,
+  config_reload::{ConfigReloadResult, LoadedConfig},
+  custom_inject::{CustomInjection, InjectedWindows},
+  display_session::SessionCapability,
+  hotkey::{Hotkey, ParsedHotkey},
+  http_proxy::{HttpRequestOptions, HttpResponse, HttpResponseCache},
+  monitors::get_monitors,
+  open_endpoint::OpenAck,
+  permissions::{
+    check_permission, Capability, PermissionsMap, WindowPermissions,
+  },
+  providers::{
+    dependencies, emission_tracker::EmissionStats, prewarm,
+    provider_manager::ProviderManager, state::StateCommand,
+    subscriptions::ProviderSubscription,
+  },
+  secrets::{SecretOutput, SecretSetOutput, SecretsStore},
+  sys_tray::{
+    set_tray_badge, setup_sys_tray, update_tray_menu, TrayMenuItem,
+    TrayState,
+  },
+  text_metrics::{FontOptions, TextSize},
+  tooltip::{TooltipOptions, Tooltips},
+  util::window_ext::{CornerPreference, WindowExt},
+  virtual_desktops::PinnedWindows,
+  window_scale::WindowGeometries,
 };
 
+mod accessibility;
+mod app_errors;
+mod app_info;
+mod asset_protocol;
 mod cli;
+mod command_error;
+mod config_reload;
+mod custom_frontend;
+mod custom_inject;
+mod daemon;
+mod diagnostics;
+mod display_session;
+#[cfg(target_os = "windows")]
+mod forwarded_args;
+mod hotkey;
+mod http_proxy;
+mod locale;
+mod logging;
 mod monitors;
+mod open_endpoint;
+mod permissions;
 mod providers;
+mod safe_mode;
+mod secrets;
 mod sys_tray;
+mod text_metrics;
+mod tooltip;
 mod user_config;
 mod util;
+mod virtual_desktops;
+mod webview_profile;
+#[cfg(target_os = "windows")]
+mod window_activation;
+mod window_icon;
+mod window_scale;
+mod window_transfer;
+
+/// How long to wait for the frontend to call `window_ready` before
+/// showing the window anyway. Custom frontends might not call it.
+const WINDOW_READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Window ID of the built-in provider playground (`zebar open
+/// --playground`).
+const PLAYGROUND_WINDOW_ID: &str = "playground";
+
+/// Initial size of the provider playground window in physical pixels.
+const PLAYGROUND_SIZE: (f64, f64) = (960., 720.);
 
 #[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -39,6 +115,51 @@ struct OpenWindowArgs {
   window_id: String,
   args: HashMap<String, String>,
   env: HashMap<String, String>,
+  url: Option<String>,
+  title: Option<String>,
+  icon: Option<String>,
+  /// Whether this is the built-in provider playground window.
+  playground: bool,
+  #[serde(skip)]
+  width: Option<f64>,
+  #[serde(skip)]
+  height: Option<f64>,
+  #[serde(skip)]
+  allow_remote_url: bool,
+  #[serde(skip)]
+  visible_on_all_workspaces: Option<bool>,
+  #[serde(skip)]
+  no_custom_inject: bool,
+
+  /// Whether the window is opened in safe mode, in which case the
+  /// frontend shows a banner.
+  safe_mode: bool,
+
+  /// OS accessibility settings at the time the window is created.
+  a11y: AccessibilitySettings,
+}
+
+/// Gets the initialization script that sets the open args of a window.
+///
+/// U+2028 and U+2029 are valid in JSON strings, but were line
+/// terminators in JS before ES2019, so they're escaped.
+fn open_args_script(open_args: &impl Serialize) -> String {
+  let json = serde_json::to_string(open_args)
+    .unwrap()
+    .replace('\u{2028}', "\\u2028")
+    .replace('\u{2029}', "\\u2029");
+
+  format!("window.__ZEBAR_OPEN_ARGS={}", json)
+}
+
+/// Request to open a window, which is handled by the task that creates
+/// windows.
+struct OpenWindowRequest {
+  args: OpenWindowArgs,
+
+  /// Receives whether the window was created, if the invoking process
+  /// waits for it.
+  ack_tx: Option<oneshot::Sender<OpenAck>>,
 }
 
 struct OpenWindowArgsMap(Arc<Mutex<HashMap<String, OpenWindowArgs>>>);
@@ -47,16 +168,62 @@ struct OpenWindowArgsMap(Arc<Mutex<HashMap<String, OpenWindowArgs>>>);
 fn read_config_file(
   config_path_override: Option<&str>,
   app_handle: AppHandle,
-) -> anyhow::Result<String, String> {
-  user_config::read_file(config_path_override, app_handle)
+  window: Window,
+) -> anyhow::Result<String, CommandError> {
+  let config_path = match config_path_override {
+    Some(config_path) => PathBuf::from(config_path),
+    None => user_config::config_dir(&app_handle)?,
+  };
+
+  check_permission(
+    &app_handle,
+    window.label(),
+    Capability::FilesystemPath(&config_path),
+  )?;
+
+  Ok(user_config::read_file(config_path_override, app_handle)?)
+}
+
+#[tauri::command]
+fn get_asset_path(
+  app_handle: AppHandle,
+) -> anyhow::Result<AssetRoot, String> {
+  asset_protocol::get_asset_root(&app_handle)
     .map_err(|err| err.to_string())
 }
 
+/// Gets info about the app build and the display session it's running
+/// in.
+#[tauri::command]
+fn get_app_info() -> AppInfo {
+  AppInfo::read()
+}
+
+/// Gets up to `limit` of the most recent app errors, oldest first.
+#[tauri::command]
+fn get_recent_errors(
+  limit: Option<usize>,
+  app_errors: State<'_, AppErrors>,
+) -> Vec<AppError> {
+  app_errors.recent(limit)
+}
+
+/// Emits app errors that occurred while no windows were open. Called
+/// once the frontend starts listening for errors.
+#[tauri::command]
+fn flush_pending_errors(app_handle: AppHandle) {
+  app_errors::flush_pending_errors(&app_handle);
+}
+
 #[tauri::command]
 async fn get_open_window_args(
   window_label: String,
   open_window_args_map: State<'_, OpenWindowArgsMap>,
-) -> anyhow::Result<Option<OpenWindowArgs>, String> {
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<Option<OpenWindowArgs>, CommandError> {
+  permissions_map.check_window_access(window.label(), &window_label)?;
+
   Ok(
     open_window_args_map
       .0
@@ -67,73 +234,928 @@ async fn get_open_window_args(
   )
 }
 
+#[tauri::command]
+async fn reload_config(
+  app_handle: AppHandle,
+  window: Window,
+) -> anyhow::Result<ConfigReloadResult, CommandError> {
+  check_permission(
+    &app_handle,
+    window.label(),
+    Capability::FilesystemPath(&user_config::config_dir(&app_handle)?),
+  )?;
+
+  Ok(config_reload::reload_config(&app_handle).await?)
+}
+
+/// Starts listening to a provider for the window.
+///
+/// Fields of the config can reference the output of another provider
+/// that the window listens to (e.g. `{ from: "ip", path: "approxCity" }`),
+/// in which case the provider is started once the value is available.
+///
+/// Fields can also reference secrets (e.g. `{ secret: "owm_key" }`),
+/// which are resolved on the Rust side and never sent to the window.
 #[tauri::command]
 async fn listen_provider(
   config_hash: String,
-  config: ProviderConfig,
+  config: serde_json::Value,
   tracked_access: Vec<String>,
   provider_manager: State<'_, ProviderManager>,
-) -> anyhow::Result<(), String> {
+  permissions_map: State<'_, PermissionsMap>,
+  secrets_store: State<'_, SecretsStore>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  let refs = dependencies::find_refs(&config);
+
+  // Referencing fields are left empty and secrets are masked for
+  // validating the config and checking permissions.
+  let template =
+    serde_json::from_value::<ProviderConfig>(dependencies::with_values(
+      &secrets::mask_refs(&config),
+      &refs,
+      &vec![serde_json::Value::Null; refs.len()],
+    ))
+    .map_err(anyhow::Error::from)?;
+
+  for host in template.type_config.http_hosts() {
+    permissions_map.check(window.label(), Capability::HttpHost(host))?;
+  }
+
+  for path in template.type_config.filesystem_paths() {
+    permissions_map
+      .check(window.label(), Capability::FilesystemPath(path))?;
+  }
+
+  if template.type_config.reads_clipboard() {
+    permissions_map.check(window.label(), Capability::Clipboard)?;
+  }
+
+  let config = secrets::resolve_provider_refs(&config, &secrets_store)?;
+
   provider_manager
-    .create(config_hash, config, tracked_access)
-    .await
-    .map_err(|err| err.to_string())
+    .subscribe(window.label(), &config_hash, &template, &tracked_access)
+    .await;
+
+  if let Err(err) = update_tray_menu(window.app_handle()).await {
+    warn!("Failed to update tray menu: {:?}", err);
+  }
+
+  if refs.is_empty() {
+    let config = serde_json::from_value::<ProviderConfig>(config)
+      .map_err(secrets::redact_error)?;
+
+    return Ok(
+      provider_manager
+        .create(config_hash, config, tracked_access)
+        .await?,
+    );
+  }
+
+  let res = provider_manager
+    .create_dependent(
+      window.label(),
+      config_hash.clone(),
+      config,
+      refs,
+      tracked_access,
+    )
+    .await;
+
+  // Rejected references (e.g. cycles) shouldn't leave a subscription
+  // behind.
+  if res.is_err() {
+    provider_manager
+      .unsubscribe(window.label(), &config_hash)
+      .await;
+  }
+
+  Ok(res?)
 }
 
 #[tauri::command]
 async fn unlisten_provider(
   config_hash: String,
   provider_manager: State<'_, ProviderManager>,
+  window: Window,
 ) -> anyhow::Result<(), String> {
+  let is_unused = provider_manager
+    .unsubscribe(window.label(), &config_hash)
+    .await;
+
+  if let Err(err) = update_tray_menu(window.app_handle()).await {
+    warn!("Failed to update tray menu: {:?}", err);
+  }
+
+  // Providers are shared between windows with the same config, so they
+  // are only destroyed once the last window unlistens.
+  if !is_unused {
+    return Ok(());
+  }
+
   provider_manager
     .destroy(config_hash)
     .await
     .map_err(|err| err.to_string())
 }
 
+/// Gets the current items of the system tray menu. Changes are emitted
+/// as `tray-state-changed` events.
+#[tauri::command]
+fn get_tray_state(tray_state: State<'_, TrayState>) -> Vec<TrayMenuItem> {
+  tray_state.items()
+}
+
+/// Runs the action of a tray menu item (e.g. toggling the visibility of
+/// a window), the same as if it was clicked in the tray menu.
+#[tauri::command]
+fn trigger_tray_action(
+  id: String,
+  app_handle: AppHandle,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  permissions_map.check(window.label(), Capability::ShellExec)?;
+
+  Ok(sys_tray::trigger_tray_action(&app_handle, &id)?)
+}
+
+/// Gets the providers that the window with the given label is subscribed
+/// to. Used for debugging.
+#[tauri::command]
+async fn get_window_providers(
+  window_label: String,
+  provider_manager: State<'_, ProviderManager>,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<Vec<ProviderSubscription>, CommandError> {
+  permissions_map.check_window_access(window.label(), &window_label)?;
+
+  Ok(provider_manager.window_subscriptions(&window_label).await)
+}
+
+/// Gets how many outputs the provider with the given config hash has
+/// emitted, and how many were delivered to each window. Used for
+/// debugging missed updates.
+#[tauri::command]
+fn get_emission_stats(
+  config_hash: String,
+  provider_manager: State<'_, ProviderManager>,
+) -> Option<EmissionStats> {
+  provider_manager.emission_stats(&config_hash)
+}
+
+/// Re-emits the latest output of a provider that the window is
+/// subscribed to. Used to resync after a gap in sequence numbers.
+#[tauri::command]
+async fn refresh_provider(
+  config_hash: String,
+  provider_manager: State<'_, ProviderManager>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  Ok(
+    provider_manager
+      .resync(window.label(), &config_hash)
+      .await?,
+  )
+}
+
+#[tauri::command]
+fn get_available_providers() -> Vec<AvailableProvider> {
+  available_providers()
+}
+
+#[tauri::command]
+#[cfg(feature = "removable_drives")]
+async fn eject_drive(
+  mount_point: String,
+  provider_manager: State<'_, ProviderManager>,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  use crate::providers::removable_drives;
+
+  permissions_map.check(window.label(), Capability::ShellExec)?;
+
+  let eject_mount_point = mount_point.clone();
+
+  // Ejecting can take a few seconds while pending writes are flushed.
+  task::spawn_blocking(move || {
+    removable_drives::eject_drive(&eject_mount_point)
+  })
+  .await
+  .map_err(anyhow::Error::from)??;
+
+  info!("Ejected drive: {}", mount_point);
+  provider_manager.notify_drives_changed();
+
+  Ok(())
+}
+
+#[tauri::command]
+#[cfg(not(feature = "removable_drives"))]
+#[allow(unused_variables)]
+async fn eject_drive(
+  mount_point: String,
+  provider_manager: State<'_, ProviderManager>,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  Err(not_compiled("removable_drives").into())
+}
+
+/// Sets the value at the path of a `state` provider's document.
+#[tauri::command]
+async fn state_set(
+  config_hash: String,
+  path: String,
+  value: serde_json::Value,
+  provider_manager: State<'_, ProviderManager>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  let command = StateCommand::Set { path, value };
+
+  Ok(
+    provider_manager
+      .mutate_state(window.label(), &config_hash, command)
+      .await?,
+  )
+}
+
+/// Adds the delta to the number at the path of a `state` provider's
+/// document.
+#[tauri::command]
+async fn state_increment(
+  config_hash: String,
+  path: String,
+  delta: f64,
+  provider_manager: State<'_, ProviderManager>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  let command = StateCommand::Increment { path, delta };
+
+  Ok(
+    provider_manager
+      .mutate_state(window.label(), &config_hash, command)
+      .await?,
+  )
+}
+
+/// Resets a `state` provider's document to its initial value.
+#[tauri::command]
+async fn state_reset(
+  config_hash: String,
+  provider_manager: State<'_, ProviderManager>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  Ok(
+    provider_manager
+      .mutate_state(window.label(), &config_hash, StateCommand::Reset)
+      .await?,
+  )
+}
+
+/// Invokes an action on a provider that the window is subscribed to
+/// (e.g. `reset_counts` of a `tail` provider), and gets its result.
+///
+/// Actions of each provider are listed in its subscription status.
+/// Rejects with an `UnknownActionError` listing the valid actions if the
+/// provider doesn't have the given action.
+#[tauri::command]
+async fn call_provider_action(
+  config_hash: String,
+  action: String,
+  args: Option<serde_json::Value>,
+  provider_manager: State<'_, ProviderManager>,
+  window: Window,
+) -> anyhow::Result<serde_json::Value, CommandError> {
+  Ok(
+    provider_manager
+      .call_action(
+        window.label(),
+        &config_hash,
+        &action,
+        args.unwrap_or_default(),
+      )
+      .await?,
+  )
+}
+
+/// Resets the match counts of a `tail` provider. Shorthand for its
+/// `reset_counts` action.
+#[tauri::command]
+#[cfg_attr(not(feature = "tail"), allow(unused_variables))]
+async fn tail_reset_counts(
+  config_hash: String,
+  provider_manager: State<'_, ProviderManager>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  #[cfg(feature = "tail")]
+  let res = provider_manager
+    .call_action(
+      window.label(),
+      &config_hash,
+      "reset_counts",
+      serde_json::Value::Null,
+    )
+    .await
+    .map(|_| ());
+
+  #[cfg(not(feature = "tail"))]
+  let res = Err(not_compiled("tail"));
+
+  Ok(res?)
+}
+
+/// Brings another app's window to the foreground, restoring it if
+/// minimized (e.g. when clicking its button in a taskbar widget).
+///
+/// Rejects with an `ActivationDeniedError` if the OS refuses to change
+/// the foreground window. Only supported on Windows.
+#[tauri::command]
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+fn activate_window(
+  native_handle: u64,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  permissions_map.check(window.label(), Capability::ShellExec)?;
+  display_session::require(SessionCapability::ActivateWindow)?;
+
+  #[cfg(target_os = "windows")]
+  window_activation::activate_window(native_handle)?;
+
+  Ok(())
+}
+
+/// Asks the window with the given handle to close. Only supported on
+/// Windows.
+#[tauri::command]
+#[cfg_attr(
+  not(all(windows, feature = "windows_list")),
+  allow(unused_variables)
+)]
+fn close_app(
+  hwnd: u64,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  permissions_map.check(window.label(), Capability::ShellExec)?;
+
+  #[cfg(all(windows, feature = "windows_list"))]
+  let res = providers::windows_list::close_window(hwnd);
+
+  #[cfg(all(windows, not(feature = "windows_list")))]
+  let res = Err(not_compiled("windows_list"));
+
+  #[cfg(not(windows))]
+  let res = Err(anyhow::anyhow!(
+    "Closing apps is not supported on this operating system."
+  ));
+
+  Ok(res?)
+}
+
+/// Makes an HTTP request from Rust on behalf of the window, which avoids
+/// CORS and allows secrets to be used without exposing them to the
+/// frontend.
+#[tauri::command]
+async fn http_request(
+  options: HttpRequestOptions,
+  app_handle: AppHandle,
+  window: Window,
+) -> anyhow::Result<HttpResponse, CommandError> {
+  let url = http_proxy::parse_url(&options.url)?;
+
+  check_permission(
+    &app_handle,
+    window.label(),
+    Capability::HttpHost(url.host_str().unwrap_or_default()),
+  )?;
+
+  Ok(http_proxy::send_request(&app_handle, url, options).await?)
+}
+
+#[tauri::command]
+async fn set_offline_mode(
+  enabled: bool,
+  provider_manager: State<'_, ProviderManager>,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  permissions_map.check(window.label(), Capability::AppSettings)?;
+
+  provider_manager.set_offline_mode(enabled).await;
+  Ok(())
+}
+
+#[tauri::command]
+async fn set_redaction(
+  enabled: bool,
+  provider_manager: State<'_, ProviderManager>,
+  app_handle: AppHandle,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  permissions_map.check(window.label(), Capability::AppSettings)?;
+
+  provider_manager.set_redaction(enabled).await;
+
+  // Keep the tray checkbox in sync.
+  Ok(update_tray_menu(&app_handle).await?)
+}
+
+#[tauri::command]
+#[cfg_attr(not(feature = "clipboard"), allow(unused_variables))]
+fn set_clipboard(
+  text: String,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  permissions_map.check(window.label(), Capability::Clipboard)?;
+
+  #[cfg(feature = "clipboard")]
+  let res = providers::clipboard::set_clipboard_text(&text);
+
+  #[cfg(not(feature = "clipboard"))]
+  let res = Err(not_compiled("clipboard"));
+
+  Ok(res?)
+}
+
+#[tauri::command]
+#[cfg_attr(not(feature = "openrgb"), allow(unused_variables))]
+async fn set_led_color(
+  device: u32,
+  zone: u32,
+  color: String,
+  provider_manager: State<'_, ProviderManager>,
+  permissions_map: State<'_, PermissionsMap>,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  permissions_map.check(window.label(), Capability::RgbLighting)?;
+
+  #[cfg(feature = "openrgb")]
+  let res = provider_manager.set_led_color(device, zone, &color).await;
+
+  #[cfg(not(feature = "openrgb"))]
+  let res = Err(not_compiled("openrgb"));
+
+  Ok(res?)
+}
+
+/// Error for commands that rely on a provider that's excluded from this
+/// build via Cargo features.
+#[allow(dead_code)]
+fn not_compiled(provider_type: &str) -> anyhow::Error {
+  anyhow::anyhow!(
+    "Provider type '{}' isn't compiled in this build.",
+    provider_type
+  )
+}
+
 /// Tauri's implementation of `always_on_top` places the window above
 /// all normal windows (but not the MacOS menu bar). The following instead
 /// sets the z-order of the window to be above the menu bar.
 #[tauri::command]
-fn set_always_on_top(window: Window) -> anyhow::Result<(), String> {
+fn set_always_on_top(window: Window) -> anyhow::Result<(), CommandError> {
+  display_session::require(SessionCapability::AlwaysOnTop)?;
+
   #[cfg(target_os = "macos")]
   let res = window.set_above_menu_bar();
 
   #[cfg(not(target_os = "macos"))]
   let res = window.set_always_on_top(true);
 
-  res.map_err(|err| err.to_string())
+  res.map_err(anyhow::Error::from)?;
+  Ok(())
 }
 
 #[tauri::command]
 fn set_skip_taskbar(
   window: Window,
   skip: bool,
-) -> anyhow::Result<(), String> {
+  pinned_windows: State<'_, PinnedWindows>,
+) -> anyhow::Result<(), CommandError> {
+  // Showing the window in the taskbar is the default on all sessions.
+  if skip {
+    display_session::require(SessionCapability::SkipTaskbar)?;
+  }
+
+  window.set_skip_taskbar(skip).map_err(anyhow::Error::from)?;
+
+  // Tool windows are shown on all virtual desktops, so pinned windows
+  // are only hidden via the taskbar.
+  #[cfg(target_os = "windows")]
+  match pinned_windows.is_pinned(window.label()) {
+    true => window.set_visible_on_all_desktops(false)?,
+    false => window.set_tool_window(skip)?,
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  let _ = pinned_windows;
+
+  Ok(())
+}
+
+/// Moves the window to the given physical position. Coordinates that
+/// aren't given are kept as is.
+#[tauri::command]
+fn move_window(
+  x: Option<i32>,
+  y: Option<i32>,
+  window: Window,
+  window_geometries: State<'_, WindowGeometries>,
+) -> anyhow::Result<(), CommandError> {
+  display_session::require(SessionCapability::AbsolutePositioning)?;
+
+  let current = window.outer_position().map_err(anyhow::Error::from)?;
+  let position =
+    PhysicalPosition::new(x.unwrap_or(current.x), y.unwrap_or(current.y));
+
+  // Set position twice to handle DPI changes on cross-monitor moves.
+  for _ in 0..2 {
+    window.set_position(position).map_err(anyhow::Error::from)?;
+  }
+
+  window_geometries.record(&window)?;
+  Ok(())
+}
+
+/// Resizes the window to the given physical size. Dimensions that aren't
+/// given are kept as is.
+///
+/// The resulting geometry is re-applied when the window's scale factor
+/// changes.
+#[tauri::command]
+fn resize_window(
+  width: Option<u32>,
+  height: Option<u32>,
+  window: Window,
+  window_geometries: State<'_, WindowGeometries>,
+) -> anyhow::Result<(), CommandError> {
+  let current = window.outer_size().map_err(anyhow::Error::from)?;
+  let size = PhysicalSize::new(
+    width.unwrap_or(current.width),
+    height.unwrap_or(current.height),
+  );
+
+  // Set size twice to handle DPI changes on cross-monitor moves.
+  for _ in 0..2 {
+    window.set_size(size).map_err(anyhow::Error::from)?;
+  }
+
+  window_geometries.record(&window)?;
+  Ok(())
+}
+
+/// Window under a point on the screen.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct WindowAtPoint {
+  window_label: String,
+  window_id: String,
+}
+
+/// Gets the Zebar window under the given point in physical screen
+/// coordinates, e.g. to find the drop target when dragging a widget
+/// between windows.
+#[tauri::command]
+async fn get_window_at_point(
+  x: i32,
+  y: i32,
+  app_handle: AppHandle,
+  open_window_args_map: State<'_, OpenWindowArgsMap>,
+) -> anyhow::Result<Option<WindowAtPoint>, CommandError> {
+  display_session::require(SessionCapability::AbsolutePositioning)?;
+
+  let Some(window) = window_transfer::window_at_point(
+    &app_handle,
+    PhysicalPosition::new(x, y),
+  ) else {
+    return Ok(None);
+  };
+
+  let window_label = window.label().to_string();
+  let window_id = open_window_args_map
+    .0
+    .lock()
+    .await
+    .get(&window_label)
+    .map(|open_args| open_args.window_id.clone())
+    .unwrap_or_default();
+
+  Ok(Some(WindowAtPoint {
+    window_label,
+    window_id,
+  }))
+}
+
+/// Hands off state to another window (e.g. a widget that was dropped on
+/// it). The target receives a `state-transfer` event, even if it's
+/// hidden.
+#[tauri::command]
+fn transfer_state(
+  from_window: String,
+  to_window: String,
+  payload: serde_json::Value,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  window_transfer::transfer_state(
+    &window,
+    &from_window,
+    &to_window,
+    payload,
+  )?;
+
+  Ok(())
+}
+
+/// Shows the window once the frontend has applied its initial size and
+/// position.
+///
+/// On Windows, windows that are docked to a monitor edge (i.e. bars)
+/// get square corners and are excluded from snap layouts.
+#[tauri::command]
+fn window_ready(window: Window) -> anyhow::Result<(), CommandError> {
+  #[cfg(target_os = "windows")]
+  if window.is_docked_to_edge().unwrap_or(false) {
+    // Corner preferences aren't supported before Windows 11.
+    let _ = window.set_corner_preference(CornerPreference::Square);
+
+    if let Err(err) = window.exclude_from_snap() {
+      warn!("Failed to exclude window from snap layouts: {:?}", err);
+    }
+  }
+
+  window.show().map_err(anyhow::Error::from)?;
+
+  // The tray menu shows whether each window is visible.
+  let app_handle = window.app_handle().clone();
+
+  task::spawn(async move {
+    if let Err(err) = update_tray_menu(&app_handle).await {
+      warn!("Failed to update tray menu: {:?}", err);
+    }
+  });
+
+  Ok(())
+}
+
+/// Sets the rounding of the window's corners, which overrides the square
+/// corners that bars get by default. No-op on platforms other than
+/// Windows 11.
+#[tauri::command]
+fn set_corner_preference(
+  preference: CornerPreference,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  #[cfg(target_os = "windows")]
+  if let Err(err) = window.set_corner_preference(preference) {
+    warn!("Failed to set corner preference: {:?}", err);
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  let _ = (preference, window);
+
+  Ok(())
+}
+
+/// Sets whether the window is shown on all workspaces (virtual desktops),
+/// or only on the one it's currently on.
+#[tauri::command]
+fn set_visible_on_all_workspaces(
+  visible: bool,
+  window: Window,
+  pinned_windows: State<'_, PinnedWindows>,
+) -> anyhow::Result<(), CommandError> {
+  pinned_windows.set(window.label(), !visible);
+
+  #[cfg(target_os = "windows")]
+  window.set_visible_on_all_desktops(visible)?;
+
+  #[cfg(not(target_os = "windows"))]
   window
-    .set_skip_taskbar(skip)
-    .map_err(|err| err.to_string())?;
+    .set_visible_on_all_workspaces(visible)
+    .map_err(anyhow::Error::from)?;
+
+  Ok(())
+}
+
+/// Moves the window to the virtual desktop at the given index (starting
+/// at 0), and only shows it on that desktop. Only supported on Windows.
+#[tauri::command]
+fn pin_to_desktop(
+  desktop_index: usize,
+  window: Window,
+  pinned_windows: State<'_, PinnedWindows>,
+) -> anyhow::Result<(), CommandError> {
+  display_session::require(SessionCapability::PinToDesktop)?;
 
   #[cfg(target_os = "windows")]
+  {
+    let desktop_id = virtual_desktops::desktop_id(desktop_index)?;
+    pinned_windows.set(window.label(), true);
+    window.move_to_desktop(desktop_id)?;
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  let _ = (desktop_index, window, pinned_windows);
+
+  Ok(())
+}
+
+/// Sets the title of the window, which is shown in window overviews and
+/// screen sharing pickers.
+#[tauri::command]
+fn set_window_title(
+  title: String,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  window.set_title(&title).map_err(anyhow::Error::from)?;
+  Ok(())
+}
+
+/// Sets the icon of the window from a PNG or ICO file. Relative paths
+/// are resolved against the `assets/` folder within the config
+/// directory, whereas absolute paths require filesystem access.
+#[tauri::command]
+fn set_window_icon(
+  icon: String,
+  app_handle: AppHandle,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  let path = window_icon::resolve_icon_path(&app_handle, &icon)?;
+
+  if Path::new(&icon).is_absolute() {
+    check_permission(
+      &app_handle,
+      window.label(),
+      Capability::FilesystemPath(&path),
+    )?;
+  }
+
+  let image = window_icon::load_icon(&path)?;
+  window.set_icon(image).map_err(anyhow::Error::from)?;
+  Ok(())
+}
+
+/// Sets a count badge on the calling window's taskbar button (or the dock
+/// icon on MacOS) and on the system tray icon. Passing `None` clears it.
+#[tauri::command]
+fn set_badge(
+  count: Option<u32>,
+  window: Window,
+) -> anyhow::Result<(), String> {
+  #[cfg(any(target_os = "windows", target_os = "macos"))]
+  window.set_badge(count).map_err(|err| err.to_string())?;
+
+  set_tray_badge(window.app_handle(), count).map_err(|err| err.to_string())
+}
+
+#[derive(Deserialize, Debug)]
+struct TaskbarProgress {
+  state: ProgressBarStatus,
+
+  /// Progress from 0 to 100.
+  value: Option<u64>,
+}
+
+/// Sets the progress shown on the calling window's taskbar button (or
+/// the dock icon on MacOS).
+#[tauri::command]
+fn set_progress(
+  progress: TaskbarProgress,
+  window: Window,
+) -> anyhow::Result<(), String> {
   window
-    .set_tool_window(skip)
-    .map_err(|err| err.to_string())?;
+    .set_progress_bar(ProgressBarState {
+      status: Some(progress.state),
+      progress: progress.value.map(|value| value.min(100)),
+    })
+    .map_err(|err| err.to_string())
+}
 
+/// Measures the texts with the platform's text layout engine. Sizes are
+/// in physical pixels at the calling window's scale factor.
+///
+/// Runs on the main thread, which the measurement caches are kept on.
+#[tauri::command]
+fn measure_text(
+  texts: Vec<String>,
+  font: FontOptions,
+  window: Window,
+) -> anyhow::Result<Vec<TextSize>, CommandError> {
+  let scale_factor = window.scale_factor().map_err(anyhow::Error::from)?;
+  Ok(text_metrics::measure_texts(&texts, &font, scale_factor)?)
+}
+
+/// Parses a hotkey binding (e.g. `Ctrl+Alt+Z` or `Ctrl+Alt+<KeyZ>`)
+/// into its canonical form and its label on the current keyboard layout.
+///
+/// Rejects with an `InvalidHotkeyError` including the offending token if
+/// the binding is invalid. The hotkey isn't registered, so conflicts with
+/// hotkeys of other apps aren't detected.
+#[tauri::command]
+fn parse_hotkey(
+  hotkey: String,
+) -> anyhow::Result<ParsedHotkey, CommandError> {
+  let hotkey = hotkey.parse::<Hotkey>()?;
+  Ok(ParsedHotkey::from(&hotkey))
+}
+
+/// Gets the label of the physical key with the given code (e.g. `KeyZ`)
+/// on the current keyboard layout. US labels are returned outside of
+/// Windows.
+#[tauri::command]
+fn get_key_name(code: String) -> Option<String> {
+  hotkey::get_key_name(&code)
+}
+
+/// Shows a tooltip positioned relative to the calling window. Replaces
+/// any tooltip that the window is already showing.
+#[tauri::command]
+async fn show_tooltip(
+  options: TooltipOptions,
+  window: Window,
+) -> anyhow::Result<(), CommandError> {
+  display_session::require(SessionCapability::AbsolutePositioning)?;
+  tooltip::show_tooltip(&window, options).await?;
   Ok(())
 }
 
+/// Hides the calling window's tooltip (e.g. on mouse leave).
+#[tauri::command]
+fn hide_tooltip(window: Window) {
+  tooltip::hide_tooltip(window.app_handle(), window.label());
+}
+
 #[tokio::main]
 async fn main() {
-  tracing_subscriber::fmt()
-    .with_env_filter(
-      EnvFilter::from_env("LOG_LEVEL")
-        .add_directive(LevelFilter::INFO.into()),
-    )
-    .init();
+  let _log_guard = logging::init_logging();
 
   tauri::async_runtime::set(tokio::runtime::Handle::current());
 
   tauri::Builder::default()
+    .register_uri_scheme_protocol(ASSET_SCHEME, |app_handle, request| {
+      asset_protocol::handle_asset_request(app_handle, request)
+    })
+    .on_window_event(|window, event| {
+      // Track the bounds of windows, so that outputs of window manager
+      // providers can include whether the window is on the focused
+      // monitor.
+      if let Some(provider_manager) = window.try_state::<ProviderManager>()
+      {
+        let window_bounds = provider_manager.window_bounds();
+
+        match event {
+          WindowEvent::Moved(position) => {
+            window_bounds.set_position(
+              window.label(),
+              position.x,
+              position.y,
+            );
+          }
+          WindowEvent::Resized(size) => {
+            window_bounds.set_size(
+              window.label(),
+              size.width,
+              size.height,
+            );
+          }
+          _ => {}
+        }
+      }
+
+      if let WindowEvent::ScaleFactorChanged { scale_factor, .. } = event {
+        window_scale::handle_scale_change(window, *scale_factor);
+      }
+
+      // Clean up providers of windows that are closed without
+      // unlistening (e.g. when reloaded or closed externally).
+      if let WindowEvent::Destroyed = event {
+        let app_handle = window.app_handle().clone();
+        let window_label = window.label().to_string();
+
+        tooltip::hide_tooltip(&app_handle, &window_label);
+        app_handle
+          .state::<PinnedWindows>()
+          .set(&window_label, false);
+        app_handle.state::<WindowGeometries>().remove(&window_label);
+        app_handle.state::<InjectedWindows>().remove(&window_label);
+
+        task::spawn(async move {
+          app_handle
+            .state::<ProviderManager>()
+            .destroy_window_providers(&window_label)
+            .await;
+
+          if let Err(err) = update_tray_menu(&app_handle).await {
+            warn!("Failed to update tray menu: {:?}", err);
+          }
+        });
+      }
+    })
     .setup(|app| {
       let cli = Cli::parse();
 
@@ -142,42 +1164,276 @@ async fn main() {
       // the CLI command.
       match cli.command {
         CliCommand::Monitors { print0 } => {
-          let monitors_str = get_monitors_str(app, print0);
-          cli::print_and_exit(monitors_str);
+          let monitors = get_monitors(app.handle(), print0);
+          cli::print_and_exit(monitors, cli.output);
+          Ok(())
+        }
+        CliCommand::ExportDiagnostics(args) => {
+          let output = diagnostics::export_diagnostics(
+            app.handle(),
+            &args.path,
+            !args.no_config,
+            None,
+          );
+
+          cli::print_and_exit(output, cli.output);
+          Ok(())
+        }
+        CliCommand::Secret(command) => {
+          let secrets_store = SecretsStore::from_config(app.handle());
+
+          match command {
+            SecretCommand::Set { name, value } => {
+              let output = secrets_store.and_then(|secrets_store| {
+                let value = match value {
+                  Some(value) => value,
+                  None => secrets::read_value_from_stdin()?,
+                };
+
+                let backend = secrets_store.set(&name, &value)?;
+                Ok(SecretSetOutput { name, backend })
+              });
+
+              cli::print_and_exit(output, cli.output);
+            }
+            SecretCommand::Get { name } => {
+              let output = secrets_store.and_then(|secrets_store| {
+                Ok(SecretOutput {
+                  value: secrets_store.get(&name)?,
+                  name,
+                })
+              });
+
+              cli::print_and_exit(output, cli.output);
+            }
+            SecretCommand::Delete { name } => {
+              let output = secrets_store
+                .and_then(|secrets_store| secrets_store.delete(&name));
+
+              cli::print_and_exit(output, cli.output);
+            }
+          }
+
+          Ok(())
+        }
+        CliCommand::Open(open_command_args)
+          if open_command_args.list_presets =>
+        {
+          let presets = user_config::read_parsed(app.handle().clone())
+            .and_then(|config| config.presets())
+            .map(|presets| {
+              let mut presets = presets.into_keys().collect::<Vec<_>>();
+              presets.sort();
+              PresetsOutput { presets }
+            });
+
+          cli::print_and_exit(presets, cli.output);
           Ok(())
         }
-        CliCommand::Open { window_id, args } => {
-          let (tx, mut rx) = mpsc::unbounded_channel::<OpenWindowArgs>();
+        CliCommand::Open(_) | CliCommand::Daemon | CliCommand::Reload => {
+          // If there's a running instance, wait for it to report whether
+          // the window was opened, so that failures are reflected in the
+          // exit code.
+          if let CliCommand::Open(open_command_args) = &cli.command {
+            if !open_command_args.no_wait {
+              if let Some(res) = open_endpoint::forward_open(
+                app.handle(),
+                env::args().collect(),
+              ) {
+                cli::print_and_exit(res, cli.output);
+              }
+            }
+          }
+
+          // Managed first, so that errors during setup are queued.
+          app.manage(AppErrors::default());
+
+          let (tx, mut rx) =
+            mpsc::unbounded_channel::<OpenWindowRequest>();
           let tx_clone = tx.clone();
+          let endpoint_tx = tx.clone();
+          let safe_mode_tx = tx.clone();
+
+          #[cfg(target_os = "windows")]
+          forwarded_args::spool_args();
 
           // If this is not the first instance of the app, this will emit
           // to the original instance and exit immediately.
           app.handle().plugin(tauri_plugin_single_instance::init(
-            move |_, args, _| {
-              let cli = Cli::parse_from(args);
+            move |app_handle, args, _| {
+              #[cfg(target_os = "windows")]
+              let args = forwarded_args::take_forwarded_args(args);
 
-              // CLI command is guaranteed to be an open command here.
-              if let CliCommand::Open { window_id, args } = cli.command {
-                emit_open_args(window_id, args, tx.clone());
-              }
+              handle_forwarded_args(app_handle, args, &tx, None);
             },
           ))?;
 
-          emit_open_args(window_id, args, tx_clone);
+          // Reaching this point means that there's no running instance.
+          #[cfg(target_os = "windows")]
+          forwarded_args::remove_spooled_args();
+
+          if matches!(cli.command, CliCommand::Reload) {
+            cli::print_and_exit::<()>(
+              Err(anyhow::anyhow!(
+                "No running instance of Zebar to reload."
+              )),
+              cli.output,
+            );
+          }
+
+          // Subsequent `open` invocations forward their args to the
+          // endpoint to get back whether the window was opened.
+          let endpoint_handle = app.handle().clone();
+
+          open_endpoint::start_endpoint(app.handle(), move |args| {
+            let (ack_tx, ack_rx) = oneshot::channel();
+
+            handle_forwarded_args(
+              &endpoint_handle,
+              args,
+              &endpoint_tx,
+              Some(ack_tx),
+            );
+
+            async move {
+              ack_rx.await.unwrap_or_else(|_| {
+                Err("Window wasn't opened by the running instance.".into())
+              })
+            }
+          });
+
+          // No windows are opened in daemon mode.
+          if let CliCommand::Open(open_command_args) = &cli.command {
+            emit_open_args(
+              app.handle(),
+              open_command_args.clone(),
+              tx_clone,
+              None,
+            );
+          }
 
           app.handle().plugin(tauri_plugin_shell::init())?;
           app.handle().plugin(tauri_plugin_http::init())?;
           app.handle().plugin(tauri_plugin_dialog::init())?;
+          app.handle().plugin(tauri_plugin_notification::init())?;
 
-          // Add application icon to system tray.
-          setup_sys_tray(app)?;
+          // Add application icon to system tray. The app is still usable
+          // via the CLI without it.
+          app.manage(TrayState::default());
 
-          init_provider_manager(app);
+          if let Err(err) = setup_sys_tray(app) {
+            report_error(
+              app.handle(),
+              AppError::error(
+                "tray",
+                "tray_setup_failed",
+                format!("Failed to set up system tray: {:#}", err),
+              ),
+            );
+          }
+
+          let config = user_config::read_parsed(app.handle().clone())
+            .unwrap_or_else(|err| {
+              report_error(
+                app.handle(),
+                AppError::warning(
+                  "config",
+                  "config_read_failed",
+                  format!("Failed to read config: {:#}", err),
+                ),
+              );
+
+              Default::default()
+            });
+
+          let mut global_config = config.global().unwrap_or_else(|err| {
+            report_error(
+              app.handle(),
+              AppError::warning(
+                "config",
+                "global_config_invalid",
+                format!("Failed to read global config: {:#}", err),
+              ),
+            );
+
+            Default::default()
+          });
+
+          global_config.offline_mode |= cli.offline;
+          webview_profile::set_isolated(global_config.isolated_webviews);
+
+          init_provider_manager(app, &global_config);
+          app.manage(LoadedConfig::new(config));
+          let secrets_store = SecretsStore::new(
+            global_config.secrets_backend,
+            &user_config::config_dir(app.handle())?,
+          );
+
+          secrets_store.set_hosts(global_config.secret_hosts.clone());
+          app.manage(secrets_store);
+
+          if matches!(cli.command, CliCommand::Daemon) {
+            daemon::init_daemon(app);
+          }
+
+          watch_accessibility_settings(app.handle());
+
+          app.manage(WidgetRoots::default());
+          app.manage(PermissionsMap::default());
+          app.manage(PinnedWindows::default());
+          app.manage(WindowGeometries::default());
+          app.manage(InjectedWindows::default());
+          app.manage(Tooltips::default());
+          app.manage(HttpResponseCache::default());
 
           let args_map = OpenWindowArgsMap(Default::default());
           let args_map_ref = args_map.0.clone();
           app.manage(args_map);
 
+          // Reopens the open windows via the same path as `zebar open
+          // <window_id> --safe-mode`.
+          let safe_mode_handle = app.handle().clone();
+          let safe_mode_args_map = args_map_ref.clone();
+
+          safe_mode::init(app, move || {
+            let app_handle = safe_mode_handle.clone();
+            let args_map = safe_mode_args_map.clone();
+            let tx = safe_mode_tx.clone();
+
+            task::spawn(async move {
+              let windows = args_map
+                .lock()
+                .await
+                .iter()
+                .filter(|(_, open_args)| {
+                  !open_args.playground && !open_args.safe_mode
+                })
+                .filter_map(|(label, open_args)| {
+                  let window = app_handle.get_webview_window(label)?;
+                  Some((window, open_args.window_id.clone()))
+                })
+                .collect::<Vec<_>>();
+
+              for (window, window_id) in windows {
+                if let Err(err) = window.destroy() {
+                  warn!("Failed to close window for safe mode: {:?}", err);
+                }
+
+                handle_forwarded_args(
+                  &app_handle,
+                  vec![
+                    "zebar".into(),
+                    "open".into(),
+                    window_id,
+                    "--safe-mode".into(),
+                  ],
+                  &tx,
+                  None,
+                );
+              }
+            });
+          });
+
           let app_handle = app.handle().clone();
 
           // Prevent the app icon from showing up in the dock on MacOS.
@@ -189,7 +1445,11 @@ async fn main() {
           _ = task::spawn(async move {
             let window_count = Arc::new(Mutex::new(0));
 
-            while let Some(open_args) = rx.recv().await {
+            while let Some(OpenWindowRequest {
+              args: mut open_args,
+              ack_tx,
+            }) = rx.recv().await
+            {
               let mut window_count = window_count.lock().await;
               *window_count += 1;
 
@@ -198,41 +1458,301 @@ async fn main() {
                 window_count, open_args.window_id, open_args.args
               );
 
+              open_args.a11y = accessibility::read_settings().await;
+
               // Window label needs to be globally unique. Hence add a
               // prefix with the window count to handle cases where
               // multiple of the same window are opened.
               let window_label =
                 format!("{}-{}", window_count, &open_args.window_id);
 
-              let window = WebviewWindowBuilder::new(
+              // The playground isn't defined in the config, and is always
+              // shown with the bundled frontend. Windows in safe mode
+              // ignore their config (e.g. custom frontend, permissions,
+              // size and providers).
+              let window_config = match open_args.playground {
+                true => user_config::WindowConfig {
+                  width: Some(PLAYGROUND_SIZE.0),
+                  height: Some(PLAYGROUND_SIZE.1),
+                  ..Default::default()
+                },
+                false if open_args.safe_mode => Default::default(),
+                false => {
+                  user_config::read_parsed_async(app_handle.clone())
+                    .await
+                    .and_then(|config| config.window(&open_args.window_id))
+                    .unwrap_or_else(|err| {
+                      report_error(
+                        &app_handle,
+                        AppError::warning(
+                          "window",
+                          "window_config_invalid",
+                          format!(
+                            "Failed to read config of window '{}': {:#}",
+                            open_args.window_id, err
+                          ),
+                        ),
+                      );
+
+                      Default::default()
+                    })
+                }
+              };
+
+              // URL passed via CLI takes precedence over the config.
+              let url = open_args.url.clone().or(window_config.url);
+
+              let initial_size = open_args
+                .width
+                .or(window_config.width)
+                .zip(open_args.height.or(window_config.height));
+
+              // Windows with a custom frontend default to having no
+              // permissions, unless they are granted in the config.
+              let permissions = match window_config.permissions {
+                Some(permissions) => permissions,
+                None if url.is_some() => {
+                  WindowPermissions::least_privilege()
+                }
+                None => WindowPermissions::bundled(
+                  user_config::config_dir(&app_handle).unwrap_or_default(),
+                ),
+              };
+
+              let webview_url = match custom_frontend::resolve_webview_url(
+                &app_handle,
+                &window_label,
+                url.as_deref(),
+                open_args.allow_remote_url
+                  || window_config.allow_remote_url,
+              ) {
+                Ok(webview_url) => webview_url,
+                Err(err) => {
+                  let message = format!(
+                    "Failed to open window '{}': {:#}",
+                    open_args.window_id, err
+                  );
+
+                  report_error(
+                    &app_handle,
+                    AppError::error(
+                      "window",
+                      "window_open_failed",
+                      message.clone(),
+                    ),
+                  );
+
+                  send_open_ack(ack_tx, Err(message));
+                  continue;
+                }
+              };
+
+              if let Err(err) = permissions::grant_plugin_permissions(
+                &app_handle,
+                &window_label,
+                &webview_url,
+                &permissions,
+              ) {
+                report_error(
+                  &app_handle,
+                  AppError::warning(
+                    "window",
+                    "permissions_grant_failed",
+                    format!(
+                      "Failed to grant plugin permissions to '{}': {:#}",
+                      open_args.window_id, err
+                    ),
+                  ),
+                );
+              }
+
+              app_handle
+                .state::<PermissionsMap>()
+                .insert(&window_label, permissions);
+
+              // Custom CSS/JS is skipped for the playground, since it
+              // isn't defined in the config, and in safe mode.
+              let is_injected = !open_args.no_custom_inject
+                && !open_args.playground
+                && !open_args.safe_mode;
+
+              // Injected even without custom CSS/JS, so that it's applied
+              // if added to the config later on.
+              let custom_injection = match is_injected {
+                false => None,
+                true => Some(
+                  CustomInjection::resolve(&app_handle, &window_config)
+                    .unwrap_or_else(|err| {
+                      custom_inject::report_injection_error(
+                        &app_handle,
+                        &window_label,
+                        &err,
+                      );
+
+                      CustomInjection::default()
+                    }),
+                ),
+              };
+
+              // Providers are created while the window loads, so that
+              // their first outputs are often cached by the time the
+              // frontend subscribes.
+              if !window_config.providers.is_empty() {
+                let app_handle = app_handle.clone();
+                let window_label = window_label.clone();
+                let provider_configs = window_config.providers;
+
+                task::spawn(async move {
+                  prewarm::prewarm_window_providers(
+                    &app_handle,
+                    &window_label,
+                    &provider_configs,
+                  )
+                  .await;
+                });
+              }
+
+              // Store the open args before the window is created, so that
+              // they're available to `get_open_window_args` right away.
+              args_map_ref
+                .lock()
+                .await
+                .insert(window_label.clone(), open_args.clone());
+
+              let visible_on_all_workspaces =
+                open_args.visible_on_all_workspaces.unwrap_or(true);
+
+              app_handle
+                .state::<PinnedWindows>()
+                .set(&window_label, !visible_on_all_workspaces);
+
+              let mut window_builder = WebviewWindowBuilder::new(
                 &app_handle,
                 &window_label,
-                WebviewUrl::default(),
+                webview_url,
               )
-              .title(format!("Zebar - {}", open_args.window_id))
-              .inner_size(500., 500.)
-              .focused(false)
-              .skip_taskbar(true)
-              .visible_on_all_workspaces(true)
-              .transparent(true)
-              .shadow(false)
-              .decorations(false)
-              .resizable(false)
-              .build()
-              .unwrap();
-
-              _ = window.eval(&format!(
-                "window.__ZEBAR_OPEN_ARGS={}",
-                serde_json::to_string(&open_args).unwrap()
-              ));
+              .title(open_args.title.clone().unwrap_or_else(|| {
+                format!("Zebar - {}", open_args.window_id)
+              }))
+              // Windows are created hidden and at a minimal size, so that
+              // they don't block input until they're sized and positioned.
+              .inner_size(1., 1.)
+              .visible(false)
+              .focused(open_args.playground)
+              .skip_taskbar(!open_args.playground)
+              .visible_on_all_workspaces(visible_on_all_workspaces)
+              // The playground is a regular window, since it's a dev tool
+              // rather than part of the desktop.
+              .transparent(!open_args.playground)
+              .shadow(open_args.playground)
+              .decorations(open_args.playground)
+              .resizable(open_args.playground)
+              // Initialization scripts run before any page scripts, so
+              // the open args are guaranteed to be set on page load.
+              .initialization_script(&open_args_script(&open_args));
+
+              window_builder = webview_profile::window_profile(
+                window_builder,
+                &app_handle,
+                &window_label,
+              );
+
+              if let Some(custom_injection) = &custom_injection {
+                window_builder = window_builder
+                  .initialization_script(&custom_injection.script());
+              }
+
+              let window_res = window_builder.build();
+
+              let window = match window_res {
+                Ok(window) => window,
+                Err(err) => {
+                  let message = format!(
+                    "Failed to create window '{}': {}",
+                    open_args.window_id, err
+                  );
+
+                  report_error(
+                    &app_handle,
+                    AppError::error(
+                      "window",
+                      "window_open_failed",
+                      message.clone(),
+                    ),
+                  );
+
+                  send_open_ack(ack_tx, Err(message));
+                  continue;
+                }
+              };
+
+              send_open_ack(ack_tx, Ok(()));
+
+              if let Some(custom_injection) = custom_injection {
+                app_handle.state::<InjectedWindows>().insert(
+                  &window_label,
+                  &open_args.window_id,
+                  custom_injection,
+                );
+              }
 
               // Tauri's `skip_taskbar` option isn't 100% reliable, so we
-              // also set the window as a tool window.
+              // also set the window as a tool window. Tool windows are
+              // shown on all virtual desktops, so pinned windows aren't.
               #[cfg(target_os = "windows")]
-              let _ = window.as_ref().window().set_tool_window(true);
+              if visible_on_all_workspaces && !open_args.playground {
+                let _ = window.as_ref().window().set_tool_window(true);
+              }
+
+              match initial_size {
+                Some((width, height)) => {
+                  let size = PhysicalSize::new(width, height);
+                  let _ = window.set_size(size);
+                  let _ = window.show();
+                }
+                None => {
+                  let window = window.clone();
+
+                  task::spawn(async move {
+                    time::sleep(WINDOW_READY_TIMEOUT).await;
+
+                    // Errors if the window has been closed in the
+                    // meantime.
+                    if window.is_visible().is_ok_and(|visible| !visible) {
+                      warn!(
+                        "Window '{}' didn't call `window_ready`. Showing \
+                        it anyway.",
+                        window.label()
+                      );
 
-              let mut args_map = args_map_ref.lock().await;
-              args_map.insert(window_label, open_args);
+                      let _ = window.show();
+                    }
+                  });
+                }
+              }
+
+              if let Some(icon) = &open_args.icon {
+                let icon_res =
+                  window_icon::resolve_icon_path(&app_handle, icon)
+                    .and_then(|path| window_icon::load_icon(&path))
+                    .and_then(|image| {
+                      window.set_icon(image).map_err(anyhow::Error::from)
+                    });
+
+                if let Err(err) = icon_res {
+                  report_error(
+                    &app_handle,
+                    AppError::warning(
+                      "window",
+                      "window_icon_invalid",
+                      format!(
+                        "Failed to set icon of window '{}': {:#}",
+                        open_args.window_id, err
+                      ),
+                    ),
+                  );
+                }
+              }
             }
           });
 
@@ -242,29 +1762,291 @@ async fn main() {
     })
     .invoke_handler(tauri::generate_handler![
       read_config_file,
+      reload_config,
+      get_app_info,
+      get_asset_path,
+      get_recent_errors,
+      flush_pending_errors,
       get_open_window_args,
       listen_provider,
       unlisten_provider,
+      get_window_providers,
+      get_emission_stats,
+      refresh_provider,
+      get_available_providers,
+      get_tray_state,
+      trigger_tray_action,
+      eject_drive,
+      state_set,
+      state_increment,
+      state_reset,
+      tail_reset_counts,
+      call_provider_action,
+      activate_window,
+      close_app,
+      set_clipboard,
+      set_led_color,
+      set_offline_mode,
+      set_redaction,
+      http_request,
       set_always_on_top,
-      set_skip_taskbar
+      set_badge,
+      set_progress,
+      set_skip_taskbar,
+      move_window,
+      resize_window,
+      get_window_at_point,
+      transfer_state,
+      window_ready,
+      set_corner_preference,
+      set_visible_on_all_workspaces,
+      pin_to_desktop,
+      set_window_title,
+      set_window_icon,
+      show_tooltip,
+      hide_tooltip,
+      measure_text,
+      parse_hotkey,
+      get_key_name
     ])
     .run(tauri::generate_context!())
     .expect("Failed to build Tauri application.");
 }
 
+/// Handles CLI args that were forwarded from a subsequent instance.
+///
+/// `ack_tx` receives whether the window was opened for `open` commands,
+/// and is resolved right away for other commands.
+fn handle_forwarded_args(
+  app_handle: &AppHandle,
+  args: Vec<String>,
+  tx: &UnboundedSender<OpenWindowRequest>,
+  ack_tx: Option<oneshot::Sender<OpenAck>>,
+) {
+  // Invalid args shouldn't exit the running instance.
+  let cli = match Cli::try_parse_from(args) {
+    Ok(cli) => cli,
+    Err(err) => {
+      let message = format!("Invalid CLI arguments: {}", err.render());
+
+      report_error(
+        app_handle,
+        AppError::error("cli", "invalid_args", message.clone()),
+      );
+
+      send_open_ack(ack_tx, Err(message));
+      return;
+    }
+  };
+
+  // Offline mode can be turned on, but not off, from subsequent
+  // instances.
+  if cli.offline {
+    let app_handle = app_handle.clone();
+
+    task::spawn(async move {
+      app_handle
+        .state::<ProviderManager>()
+        .set_offline_mode(true)
+        .await;
+    });
+  }
+
+  // Subsequent `daemon` commands are ignored, since the daemon is
+  // already running.
+  match cli.command {
+    CliCommand::Open(open_command_args) => {
+      emit_open_args(app_handle, open_command_args, tx.clone(), ack_tx);
+      return;
+    }
+    CliCommand::Reload => {
+      let app_handle = app_handle.clone();
+
+      task::spawn(async move {
+        if let Err(err) = config_reload::reload_config(&app_handle).await {
+          report_error(
+            &app_handle,
+            AppError::error(
+              "config",
+              "config_reload_failed",
+              format!("Failed to reload config: {:#}", err),
+            ),
+          );
+        }
+      });
+    }
+    _ => {}
+  }
+
+  send_open_ack(ack_tx, Ok(()));
+}
+
+/// Reports back whether a window was opened, if the invoking process is
+/// waiting for it. The receiver might have been dropped in the meantime.
+fn send_open_ack(ack_tx: Option<oneshot::Sender<OpenAck>>, ack: OpenAck) {
+  if let Some(ack_tx) = ack_tx {
+    let _ = ack_tx.send(ack);
+  }
+}
+
 /// Create and emit `OpenWindowArgs` to a channel.
 fn emit_open_args(
-  window_id: String,
-  args: Option<Vec<(String, String)>>,
-  tx: UnboundedSender<OpenWindowArgs>,
+  app_handle: &AppHandle,
+  open_command_args: OpenCommandArgs,
+  tx: UnboundedSender<OpenWindowRequest>,
+  ack_tx: Option<oneshot::Sender<OpenAck>>,
 ) {
+  let open_command_args =
+    match expand_preset(app_handle, open_command_args).and_then(
+      |open_command_args| {
+        validate_window_id(app_handle, &open_command_args)?;
+        Ok(open_command_args)
+      },
+    ) {
+      Ok(open_command_args) => open_command_args,
+      Err(err) => {
+        let message = format!("Failed to open window: {:#}", err);
+
+        report_error(
+          app_handle,
+          AppError::error("window", "window_open_failed", message.clone()),
+        );
+
+        send_open_ack(ack_tx, Err(message));
+        return;
+      }
+    };
+
+  let window_id = match open_command_args.playground {
+    true => PLAYGROUND_WINDOW_ID.to_string(),
+    false => open_command_args.window_id.unwrap_or_default(),
+  };
+
   let open_args = OpenWindowArgs {
     window_id,
-    args: args.unwrap_or(vec![]).into_iter().collect(),
+    args: open_command_args
+      .args
+      .unwrap_or(vec![])
+      .into_iter()
+      .collect(),
     env: env::vars().collect(),
+    url: open_command_args.url,
+    title: open_command_args.title,
+    icon: open_command_args.icon,
+    playground: open_command_args.playground,
+    width: open_command_args.width,
+    height: open_command_args.height,
+    allow_remote_url: open_command_args.allow_remote_url,
+    visible_on_all_workspaces: open_command_args.visible_on_all_workspaces,
+    no_custom_inject: open_command_args.no_custom_inject,
+    safe_mode: open_command_args.safe_mode,
+    // Read right before the window is created.
+    a11y: AccessibilitySettings::default(),
   };
 
-  if let Err(err) = tx.send(open_args.clone()) {
+  if let Err(err) = tx.send(OpenWindowRequest {
+    args: open_args,
+    ack_tx,
+  }) {
     info!("Failed to emit window's open args: {}", err);
   };
 }
+
+/// Checks that the window is defined in the config. The playground and
+/// windows with a custom frontend don't need to be.
+fn validate_window_id(
+  app_handle: &AppHandle,
+  open_command_args: &OpenCommandArgs,
+) -> anyhow::Result<()> {
+  if open_command_args.playground || open_command_args.url.is_some() {
+    return Ok(());
+  }
+
+  let window_id = open_command_args.window_id.as_deref().unwrap_or("");
+
+  if !user_config::read_parsed(app_handle.clone())?.has_window(window_id) {
+    anyhow::bail!(
+      "Window '{}' isn't defined in the config. Is there a property for \
+      `window/{}`?",
+      window_id,
+      window_id
+    );
+  }
+
+  Ok(())
+}
+
+/// Expands a preset (i.e. a window ID of the form `@<name>`) into its
+/// window ID and arguments. Arguments passed via the CLI take precedence
+/// over the preset's arguments.
+fn expand_preset(
+  app_handle: &AppHandle,
+  open_command_args: OpenCommandArgs,
+) -> anyhow::Result<OpenCommandArgs> {
+  let Some(preset_name) = open_command_args
+    .window_id
+    .as_deref()
+    .and_then(|window_id| window_id.strip_prefix('@'))
+  else {
+    return Ok(open_command_args);
+  };
+
+  if open_command_args.safe_mode {
+    anyhow::bail!(
+      "Preset '{}' can't be opened in safe mode. Open its window by ID \
+      instead.",
+      preset_name
+    );
+  }
+
+  let preset = user_config::read_parsed(app_handle.clone())?
+    .presets()?
+    .remove(preset_name)
+    .with_context(|| {
+      format!("Preset '{}' isn't defined in the config.", preset_name)
+    })?;
+
+  info!(
+    "Expanding preset '{}' to window '{}'.",
+    preset_name, preset.window
+  );
+
+  // Later args override earlier ones when collected into the args map.
+  let args = preset
+    .args
+    .into_iter()
+    .chain(open_command_args.args.unwrap_or_default())
+    .collect();
+
+  Ok(OpenCommandArgs {
+    window_id: Some(preset.window),
+    args: Some(args),
+    ..open_command_args
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::{json, Value};
+
+  use super::*;
+
+  #[test]
+  fn open_args_script_round_trips() {
+    let open_args = json!({
+      "windowId": "bar",
+      "args": {
+        "quoted": "\"a\" 'b'",
+        "html": "</script><script>alert(1)</script>",
+        "separators": "a\u{2028}b\u{2029}c\nd",
+        "equals": "a=b|c",
+      },
+    });
+
+    let script = open_args_script(&open_args);
+    let json = script.strip_prefix("window.__ZEBAR_OPEN_ARGS=").unwrap();
+
+    assert!(!json.contains(['\u{2028}', '\u{2029}', '\n']));
+    assert_eq!(serde_json::from_str::<Value>(json).unwrap(), open_args);
+  }
+}
@@ -1,11 +1,60 @@
 use anyhow::{bail, Context};
-use tauri::{App, Runtime};
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
 
-pub fn get_monitors_str<R: Runtime>(
-  app: &mut App<R>,
+use crate::cli::CommandOutput;
+
+/// Output of the `monitors` CLI command.
+#[derive(Serialize, Debug)]
+pub struct MonitorsOutput {
+  monitors: Vec<MonitorOutput>,
+
+  /// Whether to delimit monitors with NUL characters in text output.
+  #[serde(skip)]
+  print0: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct MonitorOutput {
+  name: String,
+  x: i32,
+  y: i32,
+  width: u32,
+  height: u32,
+  scale_factor: f64,
+}
+
+impl CommandOutput for MonitorsOutput {
+  fn to_text(&self) -> String {
+    let mut monitors_str = String::new();
+
+    for monitor in &self.monitors {
+      monitors_str += &format!(
+        "MONITOR_NAME=\"{}\" MONITOR_X=\"{}\" MONITOR_Y=\"{}\" MONITOR_WIDTH=\"{}\" MONITOR_HEIGHT=\"{}\" MONITOR_SCALE_FACTOR=\"{}\"",
+        monitor.name,
+        monitor.x,
+        monitor.y,
+        monitor.width,
+        monitor.height,
+        monitor.scale_factor
+      );
+
+      monitors_str += match self.print0 {
+        true => "\0",
+        false => "\n",
+      };
+    }
+
+    monitors_str
+  }
+}
+
+pub fn get_monitors<R: Runtime>(
+  app_handle: &AppHandle<R>,
   print0: bool,
-) -> anyhow::Result<String> {
-  let monitors = app
+) -> anyhow::Result<MonitorsOutput> {
+  let monitors = app_handle
     .available_monitors()
     .context("Unable to detect monitors")?;
 
@@ -13,24 +62,22 @@ pub fn get_monitors_str<R: Runtime>(
     bail!("No monitors found")
   }
 
-  let mut monitors_str = String::new();
-
-  for monitor in monitors {
-    monitors_str += &format!(
-      "MONITOR_NAME=\"{}\" MONITOR_X=\"{}\" MONITOR_Y=\"{}\" MONITOR_WIDTH=\"{}\" MONITOR_HEIGHT=\"{}\" MONITOR_SCALE_FACTOR=\"{}\"",
-      monitor.name().context("Unable to read monitor name")?,
-      monitor.position().x,
-      monitor.position().y,
-      monitor.size().width,
-      monitor.size().height,
-      monitor.scale_factor()
-    );
-
-    monitors_str += match print0 {
-      true => "\0",
-      false => "\n",
-    };
-  }
+  let monitors = monitors
+    .into_iter()
+    .map(|monitor| {
+      Ok(MonitorOutput {
+        name: monitor
+          .name()
+          .context("Unable to read monitor name")?
+          .to_string(),
+        x: monitor.position().x,
+        y: monitor.position().y,
+        width: monitor.size().width,
+        height: monitor.size().height,
+        scale_factor: monitor.scale_factor(),
+      })
+    })
+    .collect::<anyhow::Result<Vec<_>>>()?;
 
-  Ok(monitors_str)
+  Ok(MonitorsOutput { monitors, print0 })
 }
@@ -0,0 +1,310 @@
+use std::{
+  fs::{self, OpenOptions},
+  future::Future,
+  io::{BufRead, BufReader, Write},
+  net::{Ipv4Addr, TcpStream},
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::Duration,
+};
+
+use anyhow::{bail, Context};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt},
+  net::TcpListener,
+  task,
+};
+use tracing::{info, warn};
+
+/// Name of the file within the app data directory that holds the port
+/// and token of the running instance's endpoint.
+const ENDPOINT_FILE: &str = "open-endpoint.json";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Max size of a request in bytes. Requests only contain CLI args, so
+/// anything larger is rejected.
+const MAX_REQUEST_SIZE: u64 = 64 * 1024;
+
+/// How long to wait for a connected client to send its request, so that
+/// idle connections don't keep their task alive.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for the running instance to report whether the
+/// window was opened.
+const ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Result of opening a window, as reported back to the invoking process.
+pub type OpenAck = Result<(), String>;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct EndpointInfo {
+  port: u16,
+
+  /// Random token that requests need to include. The endpoint listens on
+  /// localhost, so this prevents other users on the machine from
+  /// opening windows, since they can't read the endpoint file.
+  token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct OpenRequest {
+  token: String,
+  args: Vec<String>,
+}
+
+/// Forwards CLI args of the `open` command to the running instance, and
+/// waits for it to report whether the window was opened.
+///
+/// Unlike the single-instance plugin, which exits right after
+/// forwarding, this lets the invoking process exit with the result.
+///
+/// Returns `None` if there's no reachable endpoint (i.e. no running
+/// instance, or a stale endpoint file), in which case the args should be
+/// handled as usual.
+pub fn forward_open(
+  app_handle: &AppHandle,
+  args: Vec<String>,
+) -> Option<anyhow::Result<()>> {
+  let endpoint_path = endpoint_path(app_handle).ok()?;
+  let endpoint_info =
+    fs::read(endpoint_path).ok().and_then(|contents| {
+      serde_json::from_slice::<EndpointInfo>(&contents).ok()
+    })?;
+
+  let stream = TcpStream::connect_timeout(
+    &(Ipv4Addr::LOCALHOST, endpoint_info.port).into(),
+    CONNECT_TIMEOUT,
+  )
+  .ok()?;
+
+  let request = OpenRequest {
+    token: endpoint_info.token,
+    args,
+  };
+
+  match send_request(stream, &request) {
+    Ok(Some(ack)) => Some(ack.map_err(anyhow::Error::msg)),
+    Ok(None) => None,
+    Err(err) => Some(Err(err)),
+  }
+}
+
+/// Sends the request and reads the ack. Returns `None` if the connection
+/// is closed without an ack (e.g. the token didn't match).
+fn send_request(
+  mut stream: TcpStream,
+  request: &OpenRequest,
+) -> anyhow::Result<Option<OpenAck>> {
+  stream.set_read_timeout(Some(ACK_TIMEOUT))?;
+
+  let mut request_bytes = serde_json::to_vec(request)?;
+  request_bytes.push(b'\n');
+  stream.write_all(&request_bytes)?;
+
+  let mut line = String::new();
+  BufReader::new(stream)
+    .read_line(&mut line)
+    .context("No response from the running instance.")?;
+
+  if line.is_empty() {
+    return Ok(None);
+  }
+
+  let ack = serde_json::from_str(&line)
+    .context("Invalid response from the running instance.")?;
+
+  Ok(Some(ack))
+}
+
+/// Starts the endpoint that subsequent `open` invocations forward their
+/// args to. Each request's args are passed to `on_request`, which
+/// resolves once the window is opened or fails to be.
+///
+/// The endpoint file isn't removed on exit. A stale file is detected by
+/// the connection being refused or closed without an ack.
+pub fn start_endpoint<F, Fut>(app_handle: &AppHandle, on_request: F)
+where
+  F: Fn(Vec<String>) -> Fut + Send + Sync + 'static,
+  Fut: Future<Output = OpenAck> + Send + 'static,
+{
+  let app_handle = app_handle.clone();
+
+  task::spawn(async move {
+    if let Err(err) = run_endpoint(&app_handle, on_request).await {
+      warn!("Open endpoint stopped: {:?}", err);
+    }
+  });
+}
+
+async fn run_endpoint<F, Fut>(
+  app_handle: &AppHandle,
+  on_request: F,
+) -> anyhow::Result<()>
+where
+  F: Fn(Vec<String>) -> Fut + Send + Sync + 'static,
+  Fut: Future<Output = OpenAck> + Send + 'static,
+{
+  let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+
+  let endpoint_info = EndpointInfo {
+    port: listener.local_addr()?.port(),
+    token: format!("{:032x}", rand::thread_rng().gen::<u128>()),
+  };
+
+  let endpoint_path = endpoint_path(app_handle)?;
+
+  if let Some(parent) = endpoint_path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+
+  write_endpoint_info(&endpoint_path, &endpoint_info)?;
+  info!("Open endpoint listening on port {}.", endpoint_info.port);
+
+  let token = Arc::new(endpoint_info.token);
+  let on_request = Arc::new(on_request);
+
+  loop {
+    let (stream, _) = listener.accept().await?;
+    let token = token.clone();
+    let on_request = on_request.clone();
+
+    task::spawn(async move {
+      if let Err(err) =
+        handle_connection(stream, &token, on_request.as_ref()).await
+      {
+        warn!("Failed to handle open request: {:?}", err);
+      }
+    });
+  }
+}
+
+/// Writes the endpoint file. It's only readable by the current user on
+/// Unix, since the token grants opening windows.
+fn write_endpoint_info(
+  path: &Path,
+  endpoint_info: &EndpointInfo,
+) -> anyhow::Result<()> {
+  let mut options = OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    options.mode(0o600);
+
+    // The mode only applies to newly created files.
+    if path.exists() {
+      fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+  }
+
+  let mut file = options
+    .open(path)
+    .with_context(|| format!("Unable to write {}.", path.display()))?;
+
+  file.write_all(&serde_json::to_vec(endpoint_info)?)?;
+
+  Ok(())
+}
+
+async fn handle_connection<F, Fut>(
+  stream: tokio::net::TcpStream,
+  token: &str,
+  on_request: &F,
+) -> anyhow::Result<()>
+where
+  F: Fn(Vec<String>) -> Fut,
+  Fut: Future<Output = OpenAck>,
+{
+  let (reader, mut writer) = stream.into_split();
+
+  // Reading stops at the size limit, in which case the truncated line
+  // fails to parse.
+  let mut line = String::new();
+  let mut reader =
+    tokio::io::BufReader::new(reader.take(MAX_REQUEST_SIZE));
+
+  tokio::time::timeout(REQUEST_TIMEOUT, reader.read_line(&mut line))
+    .await
+    .context("Timed out waiting for open request.")??;
+
+  let request = serde_json::from_str::<OpenRequest>(&line)
+    .context("Invalid open request.")?;
+
+  // The connection is closed without an ack.
+  if request.token != token {
+    bail!("Open request has an invalid token.");
+  }
+
+  let ack = on_request(request.args).await;
+
+  let mut ack_bytes = serde_json::to_vec(&ack)?;
+  ack_bytes.push(b'\n');
+  writer.write_all(&ack_bytes).await?;
+
+  Ok(())
+}
+
+fn endpoint_path(app_handle: &AppHandle) -> anyhow::Result<PathBuf> {
+  Ok(app_handle.path().app_data_dir()?.join(ENDPOINT_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::net::TcpStream;
+
+  use super::*;
+
+  /// Connects a client to a local listener, returning both ends.
+  async fn connect() -> (TcpStream, TcpStream) {
+    let listener =
+      TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (client, accepted) =
+      tokio::join!(TcpStream::connect(addr), listener.accept());
+
+    (client.unwrap(), accepted.unwrap().0)
+  }
+
+  #[tokio::test]
+  async fn acks_valid_requests() {
+    let (mut client, server) = connect().await;
+
+    client
+      .write_all(b"{\"token\":\"abc\",\"args\":[\"zebar\"]}\n")
+      .await
+      .unwrap();
+
+    handle_connection(server, "abc", &|args| async move {
+      assert_eq!(args, ["zebar"]);
+      Ok(())
+    })
+    .await
+    .unwrap();
+
+    let mut ack = String::new();
+    tokio::io::BufReader::new(client)
+      .read_line(&mut ack)
+      .await
+      .unwrap();
+
+    assert_eq!(ack, "{\"Ok\":null}\n");
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn idle_connections_time_out() {
+    let (_client, server) = connect().await;
+
+    let err = handle_connection(server, "abc", &|_| async { Ok(()) })
+      .await
+      .unwrap_err();
+
+    assert!(err.to_string().contains("Timed out"));
+  }
+}
@@ -0,0 +1,330 @@
+use std::{
+  collections::HashMap,
+  fmt,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{ipc::CapabilityBuilder, AppHandle, Manager, WebviewUrl};
+use tracing::warn;
+
+/// Capabilities that can be granted to a window via the `permissions`
+/// field in its config.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct WindowPermissions {
+  /// Whether the window can execute shell commands and act on the
+  /// system or other apps (e.g. ejecting drives, closing and activating
+  /// app windows and triggering tray actions).
+  #[serde(default)]
+  pub shell_exec: bool,
+
+  /// Whether the window can toggle offline mode and redaction (e.g. from
+  /// a streaming mode toggle).
+  #[serde(default)]
+  pub app_settings: bool,
+
+  /// Whether the window can set the colors of RGB devices.
+  #[serde(default)]
+  pub rgb_lighting: bool,
+
+  /// Whether the window can read the open args (including `env`) and
+  /// provider subscriptions of other windows. Windows can always read
+  /// their own.
+  #[serde(default)]
+  pub inspect_windows: bool,
+
+  /// Hosts that the window can make HTTP requests to. Supports `*` to
+  /// allow all hosts and wildcard subdomains (eg. `*.example.com`).
+  #[serde(default)]
+  pub http_hosts: Vec<String>,
+
+  /// Paths that the window can read from. Subpaths are included.
+  #[serde(default)]
+  pub filesystem_paths: Vec<PathBuf>,
+
+  /// Whether the window can read and write the clipboard.
+  #[serde(default)]
+  pub clipboard: bool,
+}
+
+/// A capability that is checked before running a privileged command.
+#[derive(Debug, Clone)]
+pub enum Capability<'a> {
+  ShellExec,
+  AppSettings,
+  RgbLighting,
+  InspectWindows,
+  HttpHost(&'a str),
+  FilesystemPath(&'a Path),
+  Clipboard,
+}
+
+impl fmt::Display for Capability<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Capability::ShellExec => write!(f, "shell_exec"),
+      Capability::AppSettings => write!(f, "app_settings"),
+      Capability::RgbLighting => write!(f, "rgb_lighting"),
+      Capability::InspectWindows => write!(f, "inspect_windows"),
+      Capability::HttpHost(host) => write!(f, "http_hosts: {}", host),
+      Capability::FilesystemPath(path) => {
+        write!(f, "filesystem_paths: {}", path.display())
+      }
+      Capability::Clipboard => write!(f, "clipboard"),
+    }
+  }
+}
+
+/// Error returned from commands when a window lacks a capability.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionError {
+  pub kind: &'static str,
+  pub window_label: String,
+  pub capability: String,
+  pub message: String,
+}
+
+impl WindowPermissions {
+  /// Permissions for windows that load the bundled frontend.
+  ///
+  /// Matches what windows have always been able to do.
+  pub fn bundled(config_dir: PathBuf) -> Self {
+    Self {
+      shell_exec: true,
+      app_settings: true,
+      rgb_lighting: true,
+      inspect_windows: true,
+      http_hosts: vec!["*".into()],
+      filesystem_paths: vec![config_dir],
+      clipboard: true,
+    }
+  }
+
+  /// Permissions for windows that load a custom frontend and haven't
+  /// been granted any capabilities in the config.
+  pub fn least_privilege() -> Self {
+    Self::default()
+  }
+
+  pub fn allows(&self, capability: &Capability) -> bool {
+    match capability {
+      Capability::ShellExec => self.shell_exec,
+      Capability::AppSettings => self.app_settings,
+      Capability::RgbLighting => self.rgb_lighting,
+      Capability::InspectWindows => self.inspect_windows,
+      Capability::HttpHost(host) => self
+        .http_hosts
+        .iter()
+        .any(|allowed| host_matches(allowed, host)),
+      Capability::FilesystemPath(path) => {
+        let Ok(path) = path.canonicalize() else {
+          return false;
+        };
+
+        self.filesystem_paths.iter().any(|allowed| {
+          allowed
+            .canonicalize()
+            .is_ok_and(|allowed| path.starts_with(allowed))
+        })
+      }
+      Capability::Clipboard => self.clipboard,
+    }
+  }
+}
+
+/// Whether a host matches an allowed host pattern.
+pub(crate) fn host_matches(pattern: &str, host: &str) -> bool {
+  match pattern.strip_prefix("*") {
+    Some("") => true,
+    Some(suffix) if suffix.starts_with('.') => {
+      let host = host.to_ascii_lowercase();
+      let suffix = suffix.to_ascii_lowercase();
+
+      host.ends_with(&suffix) || host == suffix[1..]
+    }
+    _ => pattern.eq_ignore_ascii_case(host),
+  }
+}
+
+/// Permissions of open windows, keyed by window label.
+#[derive(Default)]
+pub struct PermissionsMap(pub Mutex<HashMap<String, WindowPermissions>>);
+
+impl PermissionsMap {
+  pub fn insert(
+    &self,
+    window_label: &str,
+    permissions: WindowPermissions,
+  ) {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .insert(window_label.to_string(), permissions);
+  }
+
+  /// Checks whether the window has the given capability. Windows without
+  /// any registered permissions are denied.
+  pub fn check(
+    &self,
+    window_label: &str,
+    capability: Capability,
+  ) -> Result<(), PermissionError> {
+    let is_allowed = self
+      .0
+      .lock()
+      .unwrap()
+      .get(window_label)
+      .is_some_and(|permissions| permissions.allows(&capability));
+
+    if is_allowed {
+      return Ok(());
+    }
+
+    warn!(
+      "Denied '{}' permission for window '{}'.",
+      capability, window_label
+    );
+
+    Err(PermissionError {
+      kind: "permission_denied",
+      window_label: window_label.to_string(),
+      capability: capability.to_string(),
+      message: format!(
+        "Window '{}' is missing the '{}' permission.",
+        window_label, capability
+      ),
+    })
+  }
+}
+
+impl PermissionsMap {
+  /// Checks whether the window can read the state of the target window
+  /// (e.g. its open args).
+  pub fn check_window_access(
+    &self,
+    window_label: &str,
+    target_label: &str,
+  ) -> Result<(), PermissionError> {
+    match window_label == target_label {
+      true => Ok(()),
+      false => self.check(window_label, Capability::InspectWindows),
+    }
+  }
+}
+
+/// Checks whether the window has the given capability.
+pub fn check_permission(
+  app_handle: &AppHandle,
+  window_label: &str,
+  capability: Capability,
+) -> Result<(), PermissionError> {
+  app_handle
+    .state::<PermissionsMap>()
+    .check(window_label, capability)
+}
+
+/// Grants plugin permissions to a window based on its capabilities.
+///
+/// The static capabilities only contain permissions that every window
+/// gets, so e.g. `shell:allow-open` is granted here to windows with
+/// `shell_exec`. Custom frontends are loaded from remote origins, which
+/// are added to the granted capability.
+pub fn grant_plugin_permissions(
+  app_handle: &AppHandle,
+  window_label: &str,
+  webview_url: &WebviewUrl,
+  permissions: &WindowPermissions,
+) -> anyhow::Result<()> {
+  if !permissions.allows(&Capability::ShellExec) {
+    return Ok(());
+  }
+
+  let mut capability =
+    CapabilityBuilder::new(format!("{}-shell-exec", window_label))
+      .window(window_label)
+      .permission("shell:allow-open");
+
+  if let WebviewUrl::External(url) = webview_url {
+    let origin = match url.port() {
+      Some(port) => format!(
+        "{}://{}:{}",
+        url.scheme(),
+        url.host_str().unwrap_or_default(),
+        port
+      ),
+      None => format!(
+        "{}://{}",
+        url.scheme(),
+        url.host_str().unwrap_or_default()
+      ),
+    };
+
+    capability = capability.remote(format!("{}/*", origin));
+  }
+
+  app_handle.add_capability(capability)?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn permissions_map(
+    permissions: &[(&str, WindowPermissions)],
+  ) -> PermissionsMap {
+    let permissions_map = PermissionsMap::default();
+
+    for (window_label, permissions) in permissions {
+      permissions_map.insert(window_label, permissions.clone());
+    }
+
+    permissions_map
+  }
+
+  #[test]
+  fn windows_can_access_themselves() {
+    let permissions_map =
+      permissions_map(&[("1-bar", WindowPermissions::least_privilege())]);
+
+    assert!(permissions_map
+      .check_window_access("1-bar", "1-bar")
+      .is_ok());
+  }
+
+  #[test]
+  fn other_windows_require_inspect_windows() {
+    let permissions_map = permissions_map(&[
+      ("1-bar", WindowPermissions::least_privilege()),
+      (
+        "2-debug",
+        WindowPermissions {
+          inspect_windows: true,
+          ..Default::default()
+        },
+      ),
+    ]);
+
+    let err = permissions_map
+      .check_window_access("1-bar", "2-debug")
+      .unwrap_err();
+
+    assert_eq!(err.capability, "inspect_windows");
+    assert!(permissions_map
+      .check_window_access("2-debug", "1-bar")
+      .is_ok());
+  }
+
+  #[test]
+  fn unknown_windows_are_denied() {
+    let permissions_map = PermissionsMap::default();
+
+    assert!(permissions_map
+      .check_window_access("1-bar", "2-bar")
+      .is_err());
+  }
+}
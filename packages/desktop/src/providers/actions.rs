@@ -0,0 +1,177 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use anyhow::Context;
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+/// Action that the frontend can invoke on a running provider via
+/// `call_provider_action` (e.g. resetting counts).
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAction {
+  pub name: &'static str,
+  pub description: &'static str,
+
+  /// JSON schema of the action's args.
+  pub args_schema: RootSchema,
+}
+
+impl ProviderAction {
+  /// Creates an action that takes args of type `T`. Actions without
+  /// args use `()`.
+  pub fn new<T: JsonSchema>(
+    name: &'static str,
+    description: &'static str,
+  ) -> Self {
+    Self {
+      name,
+      description,
+      args_schema: schema_for!(T),
+    }
+  }
+}
+
+/// Action invoked on a provider, along with a channel for the result.
+#[derive(Debug)]
+pub struct ActionRequest {
+  pub action: String,
+  pub args: Value,
+  pub result_tx: oneshot::Sender<anyhow::Result<Value>>,
+}
+
+#[derive(Debug)]
+struct ActionChannel {
+  actions: Vec<ProviderAction>,
+  request_tx: mpsc::Sender<ActionRequest>,
+}
+
+/// Actions of the running providers, keyed by config hash.
+///
+/// Providers register their actions on start, and handle requests in
+/// their own task, same as commands to `state` providers.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderActions(Arc<Mutex<HashMap<String, ActionChannel>>>);
+
+impl ProviderActions {
+  pub async fn register(
+    &self,
+    config_hash: &str,
+    actions: Vec<ProviderAction>,
+    request_tx: mpsc::Sender<ActionRequest>,
+  ) {
+    self.0.lock().await.insert(
+      config_hash.to_string(),
+      ActionChannel {
+        actions,
+        request_tx,
+      },
+    );
+  }
+
+  pub async fn unregister(&self, config_hash: &str) {
+    self.0.lock().await.remove(config_hash);
+  }
+
+  /// Gets the actions of the provider with the given config hash.
+  pub async fn actions(&self, config_hash: &str) -> Vec<ProviderAction> {
+    self
+      .0
+      .lock()
+      .await
+      .get(config_hash)
+      .map(|channel| channel.actions.clone())
+      .unwrap_or_default()
+  }
+
+  /// Invokes the action on the provider with the given config hash and
+  /// waits for its result.
+  ///
+  /// Errors with an `UnknownActionError` if the provider doesn't have an
+  /// action with the given name.
+  pub async fn call(
+    &self,
+    config_hash: &str,
+    action: &str,
+    args: Value,
+  ) -> anyhow::Result<Value> {
+    let request_tx = {
+      let channels = self.0.lock().await;
+      let channel = channels.get(config_hash);
+
+      let actions = channel
+        .map(|channel| channel.actions.as_slice())
+        .unwrap_or_default();
+
+      if !actions.iter().any(|valid| valid.name == action) {
+        return Err(
+          UnknownActionError::new(config_hash, action, actions).into(),
+        );
+      }
+
+      channel.map(|channel| channel.request_tx.clone())
+    };
+
+    let (result_tx, result_rx) = oneshot::channel();
+
+    request_tx
+      .context("Provider has stopped.")?
+      .send(ActionRequest {
+        action: action.to_string(),
+        args,
+        result_tx,
+      })
+      .await
+      .context("Provider has stopped.")?;
+
+    result_rx.await.context("Provider has stopped.")?
+  }
+}
+
+/// Error returned when invoking an action that the provider doesn't
+/// have. Lists the provider's valid actions.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownActionError {
+  pub kind: &'static str,
+  pub config_hash: String,
+  pub action: String,
+  pub valid_actions: Vec<&'static str>,
+  pub message: String,
+}
+
+impl UnknownActionError {
+  fn new(
+    config_hash: &str,
+    action: &str,
+    actions: &[ProviderAction],
+  ) -> Self {
+    let valid_actions =
+      actions.iter().map(|action| action.name).collect::<Vec<_>>();
+
+    let valid_list = match valid_actions.is_empty() {
+      true => "none".to_string(),
+      false => valid_actions.join(", "),
+    };
+
+    Self {
+      kind: "unknown_action",
+      config_hash: config_hash.to_string(),
+      action: action.to_string(),
+      message: format!(
+        "Provider '{}' has no action '{}'. Valid actions: {}.",
+        config_hash, action, valid_list
+      ),
+      valid_actions,
+    }
+  }
+}
+
+impl fmt::Display for UnknownActionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for UnknownActionError {}
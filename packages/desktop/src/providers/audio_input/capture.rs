@@ -0,0 +1,275 @@
+use std::{
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, RecvTimeoutError},
+    Arc, Mutex,
+  },
+  thread,
+  time::Duration,
+};
+
+use anyhow::bail;
+use cpal::{
+  traits::{DeviceTrait, HostTrait, StreamTrait},
+  Device, FromSample, Sample, SampleFormat, SizedSample, Stream,
+  StreamConfig,
+};
+use tracing::{info, warn};
+
+use super::AudioLevel;
+use crate::providers::high_frequency::SampleBuffer;
+
+/// How often the default input device is checked for changes.
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Min duration that each level is measured over (i.e. 20 Hz).
+const MIN_LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Amplitude at which a sample counts as clipping.
+const CLIPPING_THRESHOLD: f32 = 0.999;
+
+/// State of the capture that's read on each emission.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureStatus {
+  pub device_name: Option<String>,
+  pub is_capturing: bool,
+  pub last_level: Option<AudioLevel>,
+}
+
+/// Captures levels of the default input device on a background thread.
+///
+/// The stream is re-opened on the new default device when it changes
+/// (e.g. when a USB mic is plugged in). The device is closed and the
+/// thread exits once this is dropped.
+pub struct AudioCapture {
+  /// Disconnects on drop, which stops the capture thread.
+  _stop_tx: mpsc::Sender<()>,
+  status: Arc<Mutex<CaptureStatus>>,
+  levels: Arc<SampleBuffer<AudioLevel>>,
+}
+
+impl AudioCapture {
+  pub fn start(level_interval: Duration) -> Self {
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let status = Arc::new(Mutex::new(CaptureStatus::default()));
+    let levels = Arc::new(SampleBuffer::default());
+
+    {
+      let status = status.clone();
+      let levels = levels.clone();
+      let level_interval = level_interval.max(MIN_LEVEL_INTERVAL);
+
+      // Streams can't be sent between threads on all platforms, so the
+      // stream is owned by a dedicated thread.
+      thread::spawn(move || {
+        run_capture(level_interval, &stop_rx, &status, &levels)
+      });
+    }
+
+    Self {
+      _stop_tx: stop_tx,
+      status,
+      levels,
+    }
+  }
+
+  pub fn status(&self) -> CaptureStatus {
+    self.status.lock().unwrap().clone()
+  }
+
+  pub fn levels(&self) -> &Arc<SampleBuffer<AudioLevel>> {
+    &self.levels
+  }
+}
+
+/// Keeps a stream open on the default input device until the stop
+/// channel disconnects.
+fn run_capture(
+  level_interval: Duration,
+  stop_rx: &mpsc::Receiver<()>,
+  status: &Arc<Mutex<CaptureStatus>>,
+  levels: &Arc<SampleBuffer<AudioLevel>>,
+) {
+  let host = cpal::default_host();
+  let stream_failed = Arc::new(AtomicBool::new(false));
+  let mut stream = None::<Stream>;
+  let mut prev_device_name = None;
+  let mut is_first_check = true;
+
+  loop {
+    let device = host.default_input_device();
+    let device_name =
+      device.as_ref().and_then(|device| device.name().ok());
+
+    // Re-open the stream if the default device changed, or if the
+    // stream errored (e.g. because the device was unplugged).
+    let should_open = is_first_check
+      || device_name != prev_device_name
+      || stream_failed.swap(false, Ordering::Relaxed);
+
+    if should_open {
+      // Close the previous device before opening the new one.
+      stream = None;
+      is_first_check = false;
+      prev_device_name = device_name.clone();
+
+      if let (Some(device), Some(device_name)) = (&device, &device_name) {
+        match open_stream(
+          device,
+          level_interval,
+          status,
+          levels,
+          &stream_failed,
+        ) {
+          Ok(opened) => {
+            info!("Capturing levels of input device '{}'.", device_name);
+            stream = Some(opened);
+          }
+          Err(err) => warn!(
+            "Failed to open input device '{}': {:?}",
+            device_name, err
+          ),
+        }
+      }
+
+      *status.lock().unwrap() = CaptureStatus {
+        device_name,
+        is_capturing: stream.is_some(),
+        last_level: None,
+      };
+    }
+
+    match stop_rx.recv_timeout(DEVICE_CHECK_INTERVAL) {
+      Err(RecvTimeoutError::Timeout) => continue,
+      _ => break,
+    }
+  }
+
+  // Dropping the stream closes the device.
+  drop(stream);
+  info!("Stopped capturing input levels.");
+}
+
+fn open_stream(
+  device: &Device,
+  level_interval: Duration,
+  status: &Arc<Mutex<CaptureStatus>>,
+  levels: &Arc<SampleBuffer<AudioLevel>>,
+  stream_failed: &Arc<AtomicBool>,
+) -> anyhow::Result<Stream> {
+  let supported_config = device.default_input_config()?;
+  let config = supported_config.config();
+
+  // Channels are interleaved, so each level is measured over all of
+  // them.
+  let window_len = (config.sample_rate.0 as f64
+    * level_interval.as_secs_f64()) as usize
+    * config.channels as usize;
+
+  let meter =
+    LevelMeter::new(window_len.max(1), status.clone(), levels.clone());
+
+  let stream = match supported_config.sample_format() {
+    SampleFormat::F32 => {
+      build_stream::<f32>(device, &config, meter, stream_failed.clone())
+    }
+    SampleFormat::I16 => {
+      build_stream::<i16>(device, &config, meter, stream_failed.clone())
+    }
+    SampleFormat::I32 => {
+      build_stream::<i32>(device, &config, meter, stream_failed.clone())
+    }
+    SampleFormat::U16 => {
+      build_stream::<u16>(device, &config, meter, stream_failed.clone())
+    }
+    format => bail!("Unsupported sample format '{}'.", format),
+  }?;
+
+  stream.play()?;
+
+  Ok(stream)
+}
+
+fn build_stream<T>(
+  device: &Device,
+  config: &StreamConfig,
+  mut meter: LevelMeter,
+  stream_failed: Arc<AtomicBool>,
+) -> anyhow::Result<Stream>
+where
+  T: SizedSample,
+  f32: FromSample<T>,
+{
+  let stream = device.build_input_stream(
+    config,
+    move |data: &[T], _: &cpal::InputCallbackInfo| meter.process(data),
+    move |err| {
+      warn!("Error in input stream: {}", err);
+      stream_failed.store(true, Ordering::Relaxed);
+    },
+    None,
+  )?;
+
+  Ok(stream)
+}
+
+/// Measures the peak and RMS amplitude over fixed-size windows of
+/// samples.
+struct LevelMeter {
+  window_len: usize,
+  count: usize,
+  peak: f32,
+  sum_squares: f64,
+  status: Arc<Mutex<CaptureStatus>>,
+  levels: Arc<SampleBuffer<AudioLevel>>,
+}
+
+impl LevelMeter {
+  fn new(
+    window_len: usize,
+    status: Arc<Mutex<CaptureStatus>>,
+    levels: Arc<SampleBuffer<AudioLevel>>,
+  ) -> Self {
+    Self {
+      window_len,
+      count: 0,
+      peak: 0.,
+      sum_squares: 0.,
+      status,
+      levels,
+    }
+  }
+
+  fn process<T>(&mut self, data: &[T])
+  where
+    T: Sample,
+    f32: FromSample<T>,
+  {
+    for &sample in data {
+      let amplitude = f32::from_sample(sample).abs();
+
+      self.peak = self.peak.max(amplitude);
+      self.sum_squares += (amplitude as f64).powi(2);
+      self.count += 1;
+
+      if self.count >= self.window_len {
+        self.flush();
+      }
+    }
+  }
+
+  fn flush(&mut self) {
+    let level = AudioLevel {
+      peak: self.peak.min(1.),
+      rms: (self.sum_squares / self.count as f64).sqrt() as f32,
+      is_clipping: self.peak >= CLIPPING_THRESHOLD,
+    };
+
+    self.levels.push(level);
+    self.status.lock().unwrap().last_level = Some(level);
+
+    self.count = 0;
+    self.peak = 0.;
+    self.sum_squares = 0.;
+  }
+}
@@ -0,0 +1,30 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::impl_interval_config;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "audio_input")]
+pub struct AudioInputProviderConfig {
+  /// Milliseconds between emissions. Levels measured in between are
+  /// batched under `levels`.
+  pub refresh_interval: u64,
+
+  /// Whether to capture the input levels of the default input device.
+  /// This opens the device, which shows the OS privacy indicator while
+  /// a window is using the provider. Otherwise, only the device name
+  /// and mute state are read.
+  #[serde(default)]
+  pub capture_levels: bool,
+
+  /// Milliseconds of audio that each level is measured over. Clamped to
+  /// at least 50ms (i.e. 20 Hz).
+  #[serde(default = "default_level_interval")]
+  pub level_interval: u64,
+}
+
+fn default_level_interval() -> u64 {
+  100
+}
+
+impl_interval_config!(AudioInputProviderConfig);
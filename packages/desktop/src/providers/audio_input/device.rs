@@ -0,0 +1,60 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+
+/// Gets the name of the default input device.
+pub fn default_input_name() -> Option<String> {
+  cpal::default_host().default_input_device()?.name().ok()
+}
+
+/// Reads whether the default input device is muted.
+#[cfg(target_os = "windows")]
+pub fn is_muted() -> Option<bool> {
+  use windows::Win32::{
+    Media::Audio::{
+      eCapture, eConsole, Endpoints::IAudioEndpointVolume,
+      IMMDeviceEnumerator, MMDeviceEnumerator,
+    },
+    System::Com::{
+      CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED,
+    },
+  };
+
+  // Fails harmlessly if COM is already initialized on the thread.
+  _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+
+  unsafe {
+    let enumerator: IMMDeviceEnumerator =
+      CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).ok()?;
+
+    let device = enumerator
+      .GetDefaultAudioEndpoint(eCapture, eConsole)
+      .ok()?;
+
+    let volume: IAudioEndpointVolume =
+      device.Activate(CLSCTX_ALL, None).ok()?;
+
+    volume.GetMute().ok().map(|is_muted| is_muted.as_bool())
+  }
+}
+
+/// Reads whether the default PulseAudio/PipeWire source is muted.
+#[cfg(target_os = "linux")]
+pub fn is_muted() -> Option<bool> {
+  let output = std::process::Command::new("pactl")
+    .args(["get-source-mute", "@DEFAULT_SOURCE@"])
+    .output()
+    .ok()
+    .filter(|output| output.status.success())?;
+
+  // Output is e.g. `Mute: yes`.
+  match String::from_utf8_lossy(&output.stdout).trim() {
+    "Mute: yes" => Some(true),
+    "Mute: no" => Some(false),
+    _ => None,
+  }
+}
+
+/// Mute state isn't exposed for input devices on other platforms.
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn is_muted() -> Option<bool> {
+  None
+}
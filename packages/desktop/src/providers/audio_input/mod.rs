@@ -0,0 +1,9 @@
+mod capture;
+mod config;
+mod device;
+mod provider;
+mod variables;
+
+pub use config::*;
+pub use provider::*;
+pub use variables::*;
@@ -0,0 +1,130 @@
+use std::{
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use tokio::task::{self, AbortHandle};
+
+use super::{
+  capture::AudioCapture, device, AudioInputProviderConfig,
+  AudioInputVariables,
+};
+use crate::providers::{
+  provider::IntervalProvider,
+  retained_size::{RetainedSize, RetainedState},
+  variables::ProviderVariables,
+};
+
+/// How long a read of the mute state is reused for, since reading it
+/// can involve spawning a process.
+const MUTE_CACHE_DURATION: Duration = Duration::from_secs(1);
+
+pub struct AudioInputProvider {
+  pub config: Arc<AudioInputProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  state: Arc<AudioInputState>,
+}
+
+pub struct AudioInputState {
+  /// Capture of the default input device. Only set if `capture_levels`
+  /// is enabled, and stopped once the provider is destroyed.
+  capture: Option<AudioCapture>,
+  mute_cache: Mutex<Option<(Instant, Option<bool>)>>,
+}
+
+impl AudioInputProvider {
+  pub fn new(config: AudioInputProviderConfig) -> AudioInputProvider {
+    let capture = config.capture_levels.then(|| {
+      AudioCapture::start(Duration::from_millis(config.level_interval))
+    });
+
+    AudioInputProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      state: Arc::new(AudioInputState {
+        capture,
+        mute_cache: Mutex::new(None),
+      }),
+    }
+  }
+
+  async fn is_muted(
+    state: &AudioInputState,
+  ) -> anyhow::Result<Option<bool>> {
+    if let Some((read_at, is_muted)) = *state.mute_cache.lock().unwrap() {
+      if read_at.elapsed() < MUTE_CACHE_DURATION {
+        return Ok(is_muted);
+      }
+    }
+
+    let is_muted = task::spawn_blocking(device::is_muted).await?;
+    *state.mute_cache.lock().unwrap() = Some((Instant::now(), is_muted));
+
+    Ok(is_muted)
+  }
+}
+
+#[async_trait]
+impl IntervalProvider for AudioInputProvider {
+  type Config = AudioInputProviderConfig;
+  type State = AudioInputState;
+
+  fn config(&self) -> Arc<AudioInputProviderConfig> {
+    self.config.clone()
+  }
+
+  fn state(&self) -> Arc<AudioInputState> {
+    self.state.clone()
+  }
+
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(
+      self
+        .state
+        .capture
+        .iter()
+        .map(|capture| capture.levels().clone() as Arc<dyn RetainedSize>)
+        .collect(),
+    )
+  }
+
+  fn abort_handle(&self) -> &Option<AbortHandle> {
+    &self.abort_handle
+  }
+
+  fn set_abort_handle(&mut self, abort_handle: AbortHandle) {
+    self.abort_handle = Some(abort_handle)
+  }
+
+  async fn get_refreshed_variables(
+    _: &AudioInputProviderConfig,
+    state: &AudioInputState,
+  ) -> anyhow::Result<ProviderVariables> {
+    let is_muted = Self::is_muted(state).await?;
+
+    let variables = match &state.capture {
+      Some(capture) => {
+        let status = capture.status();
+
+        AudioInputVariables {
+          device_name: status.device_name,
+          is_muted,
+          is_capturing: status.is_capturing,
+          level: status.last_level,
+          levels: Some(capture.levels().drain()),
+        }
+      }
+      None => AudioInputVariables {
+        device_name: task::spawn_blocking(device::default_input_name)
+          .await?,
+        is_muted,
+        is_capturing: false,
+        level: None,
+        levels: None,
+      },
+    };
+
+    Ok(ProviderVariables::AudioInput(variables))
+  }
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+use crate::providers::high_frequency::TimedSample;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioInputVariables {
+  /// Name of the default input device. `None` if there's no input
+  /// device.
+  pub device_name: Option<String>,
+
+  /// Whether the default input device is muted. `None` if the mute
+  /// state can't be read on the current platform.
+  pub is_muted: Option<bool>,
+
+  /// Whether levels are being captured. `false` if `capture_levels` is
+  /// disabled, or if the device couldn't be opened.
+  pub is_capturing: bool,
+
+  /// Most recent level. `None` if levels aren't captured or none have
+  /// been measured yet.
+  pub level: Option<AudioLevel>,
+
+  /// Levels measured since the previous emission, oldest first. Only
+  /// set if `capture_levels` is enabled.
+  pub levels: Option<Vec<TimedSample<AudioLevel>>>,
+}
+
+/// Level of the input signal over a short window.
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevel {
+  /// Peak amplitude from 0 to 1.
+  pub peak: f32,
+
+  /// RMS amplitude from 0 to 1.
+  pub rms: f32,
+
+  /// Whether any sample reached full scale.
+  pub is_clipping: bool,
+}
@@ -0,0 +1,106 @@
+/// Charge percentage below the limit at which the battery is still
+/// considered held at the limit. Batteries commonly stop charging a
+/// percent or two short of the configured threshold.
+const LIMIT_TOLERANCE: f32 = 2.;
+
+/// Whether the battery has stopped charging because it reached its
+/// charge limit.
+pub fn is_charge_limited(
+  charge_limit: Option<u8>,
+  charge_percent: f32,
+  is_charging: bool,
+) -> bool {
+  charge_limit.is_some_and(|limit| {
+    !is_charging && charge_percent >= limit as f32 - LIMIT_TOLERANCE
+  })
+}
+
+/// Reads the charge limit in percent that's set via vendor tools (e.g.
+/// ThinkPad charge thresholds or IdeaPad conservation mode).
+///
+/// Returns `None` if there's no vendor support or the limit is disabled.
+/// Sources that can't be read without elevation are skipped.
+#[cfg(target_os = "linux")]
+pub fn read_charge_limit() -> Option<u8> {
+  use std::fs;
+
+  // The standard threshold is exposed by most vendor drivers (e.g.
+  // `thinkpad_acpi`, `asus-wmi` and `huawei-wmi`). With multiple
+  // batteries, the lowest threshold is used.
+  let threshold = fs::read_dir("/sys/class/power_supply")
+    .ok()?
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| {
+      fs::read_to_string(entry.path().join("type"))
+        .is_ok_and(|kind| kind.trim() == "Battery")
+    })
+    .filter_map(|entry| {
+      fs::read_to_string(entry.path().join("charge_control_end_threshold"))
+        .ok()
+        .and_then(|value| value.trim().parse::<u8>().ok())
+    })
+    .min();
+
+  // A threshold of 100% means that charging isn't limited.
+  if let Some(threshold) = threshold.filter(|threshold| *threshold < 100) {
+    return Some(threshold);
+  }
+
+  // IdeaPad conservation mode caps the charge at 60%.
+  let conservation_mode =
+    fs::read_dir("/sys/bus/platform/drivers/ideapad_acpi")
+      .ok()?
+      .filter_map(|entry| entry.ok())
+      .any(|entry| {
+        fs::read_to_string(entry.path().join("conservation_mode"))
+          .is_ok_and(|value| value.trim() == "1")
+      });
+
+  conservation_mode.then_some(60)
+}
+
+/// Reads the charge limit in percent that's set via vendor tools (e.g.
+/// MyASUS battery care mode or Lenovo Vantage charge thresholds).
+///
+/// The vendor tools store their state in the registry, which doesn't
+/// require elevation to read, unlike their WMI interfaces.
+#[cfg(target_os = "windows")]
+pub fn read_charge_limit() -> Option<u8> {
+  use windows::{core::w, Win32::System::Registry::HKEY_LOCAL_MACHINE};
+
+  use crate::util::registry::read_registry_dword;
+
+  // Battery care mode of MyASUS and Armoury Crate. Either 60, 80 or 100.
+  let asus_limit = read_registry_dword(
+    HKEY_LOCAL_MACHINE,
+    w!("SOFTWARE\\ASUS\\ASUS System Control Interface\\AsusOptimization\\ASUS Keyboard Hotkeys"),
+    w!("ChargingRate"),
+  );
+
+  // Custom charge threshold of Lenovo Vantage on ThinkPads, which is
+  // only applied if the stop control is enabled.
+  let lenovo_limit = read_registry_dword(
+    HKEY_LOCAL_MACHINE,
+    w!("SOFTWARE\\Lenovo\\PWRMGRV\\ConfKeys\\Data"),
+    w!("ChargeStopControl"),
+  )
+  .filter(|enabled| *enabled == 1)
+  .and_then(|_| {
+    read_registry_dword(
+      HKEY_LOCAL_MACHINE,
+      w!("SOFTWARE\\Lenovo\\PWRMGRV\\ConfKeys\\Data"),
+      w!("ChargeStopPercentage"),
+    )
+  });
+
+  asus_limit
+    .or(lenovo_limit)
+    .filter(|limit| *limit > 0 && *limit < 100)
+    .map(|limit| limit as u8)
+}
+
+/// Charge limits aren't exposed on other platforms.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn read_charge_limit() -> Option<u8> {
+  None
+}
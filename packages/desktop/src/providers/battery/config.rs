@@ -1,11 +1,38 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
+use super::PeripheralKind;
 use crate::impl_interval_config;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "battery")]
 pub struct BatteryProviderConfig {
   pub refresh_interval: u64,
+
+  /// Charge percentages at which to notify about low battery on
+  /// peripheral devices.
+  #[serde(default)]
+  pub low_battery_thresholds: LowBatteryThresholds,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct LowBatteryThresholds {
+  pub mouse: Option<f32>,
+  pub keyboard: Option<f32>,
+  pub headset: Option<f32>,
+}
+
+impl LowBatteryThresholds {
+  /// Gets the threshold for a device class. Devices without a configured
+  /// threshold don't trigger notifications.
+  pub fn get(&self, kind: &PeripheralKind) -> Option<f32> {
+    match kind {
+      PeripheralKind::Mouse => self.mouse,
+      PeripheralKind::Keyboard => self.keyboard,
+      PeripheralKind::Headset => self.headset,
+      PeripheralKind::Other => None,
+    }
+  }
 }
 
 impl_interval_config!(BatteryProviderConfig);
@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+
+use tokio::sync::mpsc;
+
+use super::{BatteryBand, LowBatteryThresholds, PeripheralBattery};
+use crate::providers::provider_manager::ProviderNotification;
+
+/// Threshold used for banding devices without a configured threshold.
+const DEFAULT_THRESHOLD: f32 = 20.;
+
+/// Charge at which a device is considered recharged, and can be
+/// notified about again.
+const RECHARGED_PERCENT: f32 = 95.;
+
+/// Notifies when peripheral batteries cross their low battery threshold.
+#[derive(Debug, Default)]
+pub struct LowBatteryNotifier {
+  /// IDs of devices that have been notified about in their current
+  /// discharge cycle.
+  notified: HashSet<String>,
+}
+
+impl LowBatteryNotifier {
+  /// Sets the band of each device and sends notifications for devices
+  /// that have dropped below their threshold.
+  ///
+  /// A device is notified about at most once per discharge cycle. Devices
+  /// that report charge in coarse steps can bounce between steps, so
+  /// a cycle only ends once the device is charging or nearly full.
+  pub fn update(
+    &mut self,
+    peripherals: &mut [PeripheralBattery],
+    thresholds: &LowBatteryThresholds,
+    notification_tx: &mpsc::UnboundedSender<ProviderNotification>,
+  ) {
+    for device in peripherals.iter_mut() {
+      let threshold = thresholds.get(&device.kind);
+
+      device.band = Self::band(
+        device.charge_percent,
+        threshold.unwrap_or(DEFAULT_THRESHOLD),
+      );
+
+      if device.is_charging || device.charge_percent >= RECHARGED_PERCENT {
+        self.notified.remove(&device.id);
+        continue;
+      }
+
+      let Some(threshold) = threshold else {
+        continue;
+      };
+
+      if device.charge_percent <= threshold
+        && self.notified.insert(device.id.clone())
+      {
+        _ = notification_tx.send(ProviderNotification {
+          title: format!("{} battery low", device.name),
+          body: format!("{}% remaining.", device.charge_percent.round()),
+        });
+      }
+    }
+  }
+
+  fn band(charge_percent: f32, threshold: f32) -> BatteryBand {
+    if charge_percent <= threshold / 2. {
+      BatteryBand::Critical
+    } else if charge_percent <= threshold {
+      BatteryBand::Low
+    } else {
+      BatteryBand::Normal
+    }
+  }
+}
@@ -1,5 +1,8 @@
+mod charge_limit;
 mod config;
+mod low_battery;
 mod provider;
+mod upower;
 mod variables;
 
 pub use config::*;
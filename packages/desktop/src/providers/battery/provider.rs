@@ -1,50 +1,137 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use anyhow::Context;
 use async_trait::async_trait;
 use starship_battery::{
   units::{
-    electric_potential::volt, power::watt, ratio::percent,
-    time::millisecond,
+    electric_potential::volt, energy::watt_hour, power::watt,
+    ratio::percent, time::millisecond,
   },
-  Manager, State,
+  Battery, Manager, State, Technology,
 };
-use tokio::task::AbortHandle;
+use tokio::{sync::mpsc, task::AbortHandle};
 
-use super::{BatteryProviderConfig, BatteryVariables};
+use super::{
+  charge_limit::{is_charge_limited, read_charge_limit},
+  low_battery::LowBatteryNotifier,
+  upower::upower_devices,
+  BatteryInfo, BatteryProviderConfig, BatteryVariables,
+};
 use crate::providers::{
-  provider::IntervalProvider, variables::ProviderVariables,
+  provider::IntervalProvider, provider_manager::ProviderNotification,
+  variables::ProviderVariables,
 };
 
 pub struct BatteryProvider {
   pub config: Arc<BatteryProviderConfig>,
   abort_handle: Option<AbortHandle>,
-  battery_manager: Arc<Manager>,
+  state: Arc<BatteryState>,
+}
+
+pub struct BatteryState {
+  battery_manager: Manager,
+  low_battery_notifier: Mutex<LowBatteryNotifier>,
+  notification_tx: mpsc::UnboundedSender<ProviderNotification>,
 }
 
 impl BatteryProvider {
   pub fn new(
     config: BatteryProviderConfig,
+    notification_tx: mpsc::UnboundedSender<ProviderNotification>,
   ) -> anyhow::Result<BatteryProvider> {
-    let manager = Manager::new()?;
+    let battery_manager = Manager::new()?;
 
     Ok(BatteryProvider {
       config: Arc::new(config),
       abort_handle: None,
-      battery_manager: Arc::new(manager),
+      state: Arc::new(BatteryState {
+        battery_manager,
+        low_battery_notifier: Mutex::new(LowBatteryNotifier::default()),
+        notification_tx,
+      }),
     })
   }
 
   /// Battery manager from `starship_battery` is not thread-safe, so it
   /// requires its own non-async function.
-  fn get_variables(manager: &Manager) -> anyhow::Result<BatteryVariables> {
-    let first_battery = manager
-      .batteries()
-      .and_then(|mut batteries| batteries.nth(0).transpose())
-      .unwrap_or(None)
-      .context("No battery found.");
-
-    first_battery.map(|battery| BatteryVariables {
+  fn get_variables(
+    config: &BatteryProviderConfig,
+    state: &BatteryState,
+  ) -> anyhow::Result<BatteryVariables> {
+    let mut batteries = Self::system_batteries(&state.battery_manager)?;
+
+    let mut upower_devices = upower_devices();
+    batteries.append(&mut upower_devices.ups);
+
+    let mut peripherals = upower_devices.peripherals;
+
+    state.low_battery_notifier.lock().unwrap().update(
+      &mut peripherals,
+      &config.low_battery_thresholds,
+      &state.notification_tx,
+    );
+
+    let primary = Self::primary_battery(&batteries).cloned();
+
+    Ok(BatteryVariables {
+      has_battery: !batteries.is_empty(),
+      primary,
+      batteries,
+      peripherals,
+    })
+  }
+
+  /// Gets batteries reported by the OS power APIs. UPS devices that are
+  /// only exposed via UPower aren't included.
+  pub fn system_batteries(
+    battery_manager: &Manager,
+  ) -> anyhow::Result<Vec<BatteryInfo>> {
+    let mut batteries = battery_manager
+      .batteries()?
+      .filter_map(|battery| battery.ok())
+      .map(|battery| Self::battery_info(&battery))
+      .collect::<Vec<_>>();
+
+    // Vendor charge limits apply to all internal batteries.
+    let charge_limit = read_charge_limit();
+
+    for battery in batteries.iter_mut().filter(|battery| !battery.is_ups) {
+      battery.charge_limit = charge_limit;
+      battery.charge_limited = is_charge_limited(
+        charge_limit,
+        battery.charge_percent,
+        battery.is_charging,
+      );
+    }
+
+    Ok(batteries)
+  }
+
+  /// Gets the battery to report as the primary one, preferring internal
+  /// batteries over UPS devices.
+  pub fn primary_battery(
+    batteries: &[BatteryInfo],
+  ) -> Option<&BatteryInfo> {
+    batteries
+      .iter()
+      .find(|battery| !battery.is_ups)
+      .or(batteries.first())
+  }
+
+  fn battery_info(battery: &Battery) -> BatteryInfo {
+    let power_consumption = battery.energy_rate().get::<watt>();
+
+    // Runtime at the current power draw, based on the remaining energy.
+    let estimated_runtime = match power_consumption > 0. {
+      true => Some(
+        battery.energy().get::<watt_hour>() / power_consumption
+          * 60.
+          * 60.
+          * 1000.,
+      ),
+      false => None,
+    };
+
+    BatteryInfo {
       charge_percent: battery.state_of_charge().get::<percent>(),
       health_percent: battery.state_of_health().get::<percent>(),
       state: battery.state().to_string(),
@@ -55,24 +142,40 @@ impl BatteryProvider {
       time_till_empty: battery
         .time_to_empty()
         .map(|time| time.get::<millisecond>()),
-      power_consumption: battery.energy_rate().get::<watt>(),
+      power_consumption,
       voltage: battery.voltage().get::<volt>(),
       cycle_count: battery.cycle_count(),
-    })
+      is_ups: Self::is_ups(battery),
+      estimated_runtime,
+      charge_limit: None,
+      charge_limited: false,
+    }
+  }
+
+  /// Whether a battery is a UPS. HID UPS devices show up as regular
+  /// batteries on Windows, so they're identified by their model name and
+  /// lead-acid chemistry, which laptop batteries don't use.
+  fn is_ups(battery: &Battery) -> bool {
+    let model_is_ups = [battery.vendor(), battery.model()]
+      .into_iter()
+      .flatten()
+      .any(|name| name.to_uppercase().contains("UPS"));
+
+    model_is_ups || battery.technology() == Technology::LeadAcid
   }
 }
 
 #[async_trait]
 impl IntervalProvider for BatteryProvider {
   type Config = BatteryProviderConfig;
-  type State = Manager;
+  type State = BatteryState;
 
   fn config(&self) -> Arc<BatteryProviderConfig> {
     self.config.clone()
   }
 
-  fn state(&self) -> Arc<Manager> {
-    self.battery_manager.clone()
+  fn state(&self) -> Arc<BatteryState> {
+    self.state.clone()
   }
 
   fn abort_handle(&self) -> &Option<AbortHandle> {
@@ -84,11 +187,11 @@ impl IntervalProvider for BatteryProvider {
   }
 
   async fn get_refreshed_variables(
-    _: &BatteryProviderConfig,
-    battery_manager: &Manager,
+    config: &BatteryProviderConfig,
+    state: &BatteryState,
   ) -> anyhow::Result<ProviderVariables> {
     Ok(ProviderVariables::Battery(Self::get_variables(
-      battery_manager,
+      config, state,
     )?))
   }
 }
@@ -0,0 +1,157 @@
+use super::{BatteryInfo, PeripheralBattery};
+
+/// Devices classified by UPower that aren't exposed as batteries in
+/// sysfs, and are therefore missed by `starship_battery`.
+#[derive(Debug, Default)]
+pub struct UpowerDevices {
+  pub ups: Vec<BatteryInfo>,
+  pub peripherals: Vec<PeripheralBattery>,
+}
+
+/// Gets UPS and peripheral devices via UPower.
+#[cfg(target_os = "linux")]
+pub fn upower_devices() -> UpowerDevices {
+  use std::process::Command;
+
+  use super::{BatteryBand, PeripheralKind};
+
+  let Ok(output) = Command::new("upower").arg("--enumerate").output()
+  else {
+    return UpowerDevices::default();
+  };
+
+  let mut devices = UpowerDevices::default();
+
+  for device_path in String::from_utf8_lossy(&output.stdout).lines() {
+    let device_name = device_path.rsplit('/').next().unwrap_or_default();
+
+    let kind = match device_name.split('_').next() {
+      Some("ups") => None,
+      Some("mouse") => Some(PeripheralKind::Mouse),
+      Some("keyboard") => Some(PeripheralKind::Keyboard),
+      Some("headset" | "headphones") => Some(PeripheralKind::Headset),
+      Some("gaming" | "pen" | "touchpad" | "bluetooth") => {
+        Some(PeripheralKind::Other)
+      }
+      _ => continue,
+    };
+
+    let Ok(output) = Command::new("upower")
+      .args(["--show-info", device_path])
+      .output()
+    else {
+      continue;
+    };
+
+    let info = UpowerInfo(String::from_utf8_lossy(&output.stdout).into());
+
+    match kind {
+      None => devices.ups.extend(info.ups()),
+      Some(kind) => {
+        let Some(charge_percent) = info.numeric_property("percentage")
+        else {
+          continue;
+        };
+
+        devices.peripherals.push(PeripheralBattery {
+          id: device_name.to_string(),
+          name: info.property("model").unwrap_or(device_name).to_string(),
+          kind,
+          charge_percent,
+          is_charging: info.state() == "charging",
+          // Band is set by the provider, since it depends on the
+          // configured thresholds.
+          band: BatteryBand::Normal,
+        });
+      }
+    }
+  }
+
+  devices
+}
+
+/// Output of `upower --show-info` for a device.
+#[cfg(target_os = "linux")]
+struct UpowerInfo(String);
+
+#[cfg(target_os = "linux")]
+impl UpowerInfo {
+  fn property(&self, key: &str) -> Option<&str> {
+    self.0.lines().find_map(|line| {
+      let (line_key, value) = line.split_once(':')?;
+      (line_key.trim() == key).then(|| value.trim())
+    })
+  }
+
+  /// Gets a numeric property. These are suffixed with their unit (e.g.
+  /// `13.5 V`).
+  fn numeric_property(&self, key: &str) -> Option<f32> {
+    self
+      .property(key)?
+      .trim_end_matches(|c: char| c.is_alphabetic() || c == '%')
+      .trim()
+      .parse::<f32>()
+      .ok()
+  }
+
+  fn state(&self) -> &'static str {
+    match self.property("state") {
+      Some("charging" | "pending-charge") => "charging",
+      Some("discharging" | "pending-discharge") => "discharging",
+      Some("fully-charged") => "full",
+      Some("empty") => "empty",
+      _ => "unknown",
+    }
+  }
+
+  fn ups(&self) -> Option<BatteryInfo> {
+    let state = self.state();
+    let time_till_empty =
+      self.property("time to empty").and_then(parse_duration);
+
+    Some(BatteryInfo {
+      charge_percent: self.numeric_property("percentage")?,
+      health_percent: self.numeric_property("capacity").unwrap_or(100.),
+      state: state.to_string(),
+      is_charging: state == "charging",
+      time_till_full: self
+        .property("time to full")
+        .and_then(parse_duration),
+      time_till_empty,
+      power_consumption: self
+        .numeric_property("energy-rate")
+        .unwrap_or(0.),
+      voltage: self.numeric_property("voltage").unwrap_or(0.),
+      cycle_count: None,
+      is_ups: true,
+      estimated_runtime: time_till_empty,
+      charge_limit: None,
+      charge_limited: false,
+    })
+  }
+}
+
+/// Parses a duration from UPower (e.g. `45.3 minutes`) into
+/// milliseconds.
+#[cfg(target_os = "linux")]
+fn parse_duration(duration: &str) -> Option<f32> {
+  let (value, unit) = duration.split_once(' ')?;
+  let value = value.parse::<f32>().ok()?;
+
+  let unit_ms = match unit.trim_end_matches('s') {
+    "second" => 1000.,
+    "minute" => 60. * 1000.,
+    "hour" => 60. * 60. * 1000.,
+    "day" => 24. * 60. * 60. * 1000.,
+    _ => return None,
+  };
+
+  Some(value * unit_ms)
+}
+
+/// UPS devices on Windows are exposed as batteries via the power APIs.
+/// Peripheral batteries aren't currently supported outside of Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn upower_devices() -> UpowerDevices {
+  UpowerDevices::default()
+}
@@ -3,6 +3,25 @@ use serde::Serialize;
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BatteryVariables {
+  /// Whether any batteries or UPS devices were found. This is `false`
+  /// on most desktops.
+  pub has_battery: bool,
+
+  /// The primary battery, which is the first internal battery if there
+  /// is one. Its fields are flattened into the output.
+  #[serde(flatten)]
+  pub primary: Option<BatteryInfo>,
+
+  /// All batteries and UPS devices.
+  pub batteries: Vec<BatteryInfo>,
+
+  /// Batteries of peripheral devices (mice, keyboards and headsets).
+  pub peripherals: Vec<PeripheralBattery>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryInfo {
   pub charge_percent: f32,
   pub health_percent: f32,
   pub state: String,
@@ -12,4 +31,51 @@ pub struct BatteryVariables {
   pub power_consumption: f32,
   pub voltage: f32,
   pub cycle_count: Option<u32>,
+
+  /// Whether the device is an uninterruptible power supply rather than
+  /// an internal battery.
+  pub is_ups: bool,
+
+  /// Estimated runtime in milliseconds at the current power draw. Unlike
+  /// `time_till_empty`, this is also available while charging.
+  pub estimated_runtime: Option<f32>,
+
+  /// Charge limit in percent that's set via vendor tools (e.g. ThinkPad
+  /// charge thresholds). `None` if there's no limit or no vendor support.
+  pub charge_limit: Option<u8>,
+
+  /// Whether the battery isn't charging because it reached
+  /// `charge_limit`.
+  pub charge_limited: bool,
+}
+
+/// Battery of a peripheral device (e.g. a wireless mouse).
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PeripheralBattery {
+  /// Unique identifier of the device.
+  pub id: String,
+  pub name: String,
+  pub kind: PeripheralKind,
+  pub charge_percent: f32,
+  pub is_charging: bool,
+  pub band: BatteryBand,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PeripheralKind {
+  Mouse,
+  Keyboard,
+  Headset,
+  Other,
+}
+
+/// Coarse charge level, for coloring battery icons.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryBand {
+  Critical,
+  Low,
+  Normal,
 }
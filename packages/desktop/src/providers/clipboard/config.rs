@@ -0,0 +1,73 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "clipboard")]
+pub struct ClipboardProviderConfig {
+  /// How often to check whether the clipboard has changed. Output is only
+  /// emitted when the clipboard changes.
+  #[serde(default = "default_refresh_interval")]
+  pub refresh_interval: u64,
+
+  /// Max number of characters in the text preview.
+  #[serde(default = "default_preview_max_length")]
+  pub preview_max_length: usize,
+
+  /// Regex patterns that are masked in text previews. Defaults to
+  /// patterns matching common secrets (e.g. API keys and passwords).
+  #[serde(default = "default_redact_patterns")]
+  pub redact_patterns: Vec<String>,
+
+  #[serde(default)]
+  pub history: ClipboardHistoryConfig,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+pub struct ClipboardHistoryConfig {
+  /// Max number of previews to keep in the history.
+  #[serde(default = "default_history_max_entries")]
+  pub max_entries: usize,
+
+  /// Whether to persist the history to disk across restarts. Only
+  /// redacted previews are stored.
+  #[serde(default)]
+  pub persist: bool,
+}
+
+impl Default for ClipboardHistoryConfig {
+  fn default() -> Self {
+    Self {
+      max_entries: default_history_max_entries(),
+      persist: false,
+    }
+  }
+}
+
+fn default_refresh_interval() -> u64 {
+  500
+}
+
+fn default_preview_max_length() -> usize {
+  50
+}
+
+fn default_history_max_entries() -> usize {
+  20
+}
+
+fn default_redact_patterns() -> Vec<String> {
+  [
+    // Key-value pairs like `password=hunter2` or `api_key: abc123`.
+    r"(?i)(pass(word|wd)?|secret|token|api[_-]?key)\s*[:=]\s*\S+",
+    // Prefixed tokens (e.g. GitHub, Slack, Stripe, OpenAI).
+    r"\b(gh[pousr]|xox[abprs]|sk|pk|rk)[-_][A-Za-z0-9_-]{10,}",
+    // AWS access key IDs.
+    r"\bAKIA[0-9A-Z]{16}\b",
+    // Private key blocks.
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----",
+    // Long opaque strings, such as hex or base64 encoded keys.
+    r"\b[A-Za-z0-9+/_-]{32,}={0,2}",
+  ]
+  .map(String::from)
+  .to_vec()
+}
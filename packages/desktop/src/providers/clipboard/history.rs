@@ -0,0 +1,94 @@
+use std::{collections::VecDeque, fs, path::PathBuf};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::{ClipboardHistoryConfig, ClipboardHistoryEntry};
+use crate::providers::retained_size::{json_size, RetainedSize};
+
+/// Previews of previous clipboard contents.
+///
+/// History is kept in memory only, unless persistence is explicitly
+/// enabled in the config.
+#[derive(Debug)]
+pub struct ClipboardHistory {
+  entries: VecDeque<ClipboardHistoryEntry>,
+  max_entries: usize,
+  persist_path: Option<PathBuf>,
+}
+
+impl ClipboardHistory {
+  pub fn new(
+    config: &ClipboardHistoryConfig,
+    data_dir: Option<PathBuf>,
+  ) -> Self {
+    let persist_path = data_dir
+      .filter(|_| config.persist)
+      .map(|dir| dir.join("clipboard_history.json"));
+
+    let mut entries: VecDeque<ClipboardHistoryEntry> = persist_path
+      .as_ref()
+      .and_then(|path| fs::read_to_string(path).ok())
+      .and_then(|contents| serde_json::from_str(&contents).ok())
+      .unwrap_or_default();
+
+    entries.truncate(config.max_entries);
+
+    Self {
+      entries,
+      max_entries: config.max_entries,
+      persist_path,
+    }
+  }
+
+  pub fn entries(&self) -> Vec<ClipboardHistoryEntry> {
+    self.entries.iter().cloned().collect()
+  }
+
+  /// Adds an entry to the front of the history. Consecutive duplicates
+  /// are skipped.
+  pub fn push(&mut self, entry: ClipboardHistoryEntry) {
+    if self.max_entries == 0 {
+      return;
+    }
+
+    let is_duplicate = self.entries.front().is_some_and(|front| {
+      front.content_type == entry.content_type
+        && front.preview == entry.preview
+    });
+
+    if is_duplicate {
+      return;
+    }
+
+    self.entries.push_front(entry);
+    self.entries.truncate(self.max_entries);
+
+    if let Err(err) = self.persist() {
+      warn!("Failed to write clipboard history: {:?}", err);
+    }
+  }
+
+  fn persist(&self) -> anyhow::Result<()> {
+    let Some(persist_path) = &self.persist_path else {
+      return Ok(());
+    };
+
+    if let Some(parent) = persist_path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    fs::write(persist_path, serde_json::to_string(&self.entries)?)?;
+
+    Ok(())
+  }
+}
+
+/// History is bounded by `max_entries`, so it isn't evicted.
+#[async_trait]
+impl RetainedSize for Mutex<ClipboardHistory> {
+  async fn retained_size(&self) -> usize {
+    json_size(&self.lock().await.entries)
+  }
+}
@@ -0,0 +1,11 @@
+mod config;
+mod history;
+mod provider;
+mod redact;
+mod system_clipboard;
+mod variables;
+
+pub use config::*;
+pub use provider::*;
+pub use system_clipboard::set_clipboard_text;
+pub use variables::*;
@@ -0,0 +1,203 @@
+use std::{
+  path::PathBuf,
+  sync::Arc,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use tokio::{
+  sync::{mpsc::Sender, Mutex},
+  task::{self, AbortHandle},
+  time,
+};
+use tracing::warn;
+
+use super::{
+  history::ClipboardHistory,
+  redact::Redactor,
+  system_clipboard::{change_count, read_clipboard},
+  ClipboardHistoryEntry, ClipboardProviderConfig, ClipboardVariables,
+};
+use crate::providers::{
+  provider::Provider,
+  provider_ref::{ProviderOutput, VariablesResult},
+  retained_size::RetainedState,
+  variables::ProviderVariables,
+};
+
+pub struct ClipboardProvider {
+  pub config: Arc<ClipboardProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  redactor: Arc<Redactor>,
+  history: Arc<Mutex<ClipboardHistory>>,
+}
+
+impl ClipboardProvider {
+  pub fn new(
+    config: ClipboardProviderConfig,
+    data_dir: Option<PathBuf>,
+  ) -> anyhow::Result<ClipboardProvider> {
+    let redactor = Redactor::new(&config.redact_patterns)?;
+    let history = ClipboardHistory::new(&config.history, data_dir);
+
+    Ok(ClipboardProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      redactor: Arc::new(redactor),
+      history: Arc::new(Mutex::new(history)),
+    })
+  }
+
+  /// Reads the clipboard and adds its contents to the history.
+  ///
+  /// Returns `None` if the contents are unchanged since the last read,
+  /// based on the given fingerprint.
+  async fn get_variables(
+    config: &ClipboardProviderConfig,
+    redactor: &Redactor,
+    history: &Mutex<ClipboardHistory>,
+    prev_fingerprint: &mut Option<u64>,
+  ) -> anyhow::Result<Option<ClipboardVariables>> {
+    // Reading can block while the clipboard owner serves the contents.
+    let (content, fingerprint) =
+      task::spawn_blocking(read_clipboard).await??;
+
+    if *prev_fingerprint == Some(fingerprint) {
+      return Ok(None);
+    }
+
+    *prev_fingerprint = Some(fingerprint);
+
+    let preview = content
+      .text
+      .map(|text| redactor.preview(&text, config.preview_max_length));
+
+    let mut history = history.lock().await;
+
+    history.push(ClipboardHistoryEntry {
+      content_type: content.content_type,
+      preview: preview.clone(),
+      copied_at: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis(),
+    });
+
+    let history = history.entries();
+
+    Ok(Some(ClipboardVariables {
+      content_type: content.content_type,
+      preview,
+      history_count: history.len(),
+      history,
+    }))
+  }
+
+  fn to_output(
+    config_hash: &str,
+    variables: anyhow::Result<ClipboardVariables>,
+  ) -> ProviderOutput {
+    ProviderOutput {
+      config_hash: config_hash.to_string(),
+      variables: variables.map(ProviderVariables::Clipboard).into(),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+}
+
+#[async_trait]
+impl Provider for ClipboardProvider {
+  fn min_refresh_interval(&self) -> Option<Duration> {
+    // Output is only emitted on changes, so it's always up to date.
+    None
+  }
+
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(vec![self.history.clone()])
+  }
+
+  async fn on_start(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let config = self.config.clone();
+    let config_hash = config_hash.to_string();
+    let redactor = self.redactor.clone();
+    let history = self.history.clone();
+
+    let task_handle = task::spawn(async move {
+      let mut interval =
+        time::interval(Duration::from_millis(config.refresh_interval));
+
+      let mut prev_change_count = None;
+      let mut prev_fingerprint = None;
+
+      loop {
+        interval.tick().await;
+
+        // Skip reading the contents if the OS reports no changes.
+        let change_count = change_count();
+
+        if change_count.is_some() && change_count == prev_change_count {
+          continue;
+        }
+
+        prev_change_count = change_count;
+
+        let variables = match Self::get_variables(
+          &config,
+          &redactor,
+          &history,
+          &mut prev_fingerprint,
+        )
+        .await
+        {
+          Ok(Some(variables)) => Ok(variables),
+          Ok(None) => continue,
+          Err(err) => {
+            warn!("Failed to read clipboard: {:?}", err);
+            Err(err)
+          }
+        };
+
+        _ = emit_output_tx
+          .send(Self::to_output(&config_hash, variables))
+          .await;
+      }
+    });
+
+    self.abort_handle = Some(task_handle.abort_handle());
+    _ = task_handle.await;
+  }
+
+  async fn on_refresh(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    // Force a read regardless of whether the contents changed.
+    let variables = Self::get_variables(
+      &self.config,
+      &self.redactor,
+      &self.history,
+      &mut None,
+    )
+    .await
+    .map(|variables| variables.unwrap());
+
+    _ = emit_output_tx
+      .send(Self::to_output(config_hash, variables))
+      .await;
+  }
+
+  async fn on_stop(&mut self) {
+    if let Some(handle) = &self.abort_handle {
+      handle.abort();
+    }
+  }
+}
@@ -0,0 +1,47 @@
+use anyhow::Context;
+use regex::Regex;
+
+/// Replacement for redacted matches.
+const MASK: &str = "••••••";
+
+/// Builds text previews with sensitive content masked.
+#[derive(Debug)]
+pub struct Redactor {
+  patterns: Vec<Regex>,
+}
+
+impl Redactor {
+  pub fn new(patterns: &[String]) -> anyhow::Result<Self> {
+    let patterns = patterns
+      .iter()
+      .map(|pattern| {
+        Regex::new(pattern).with_context(|| {
+          format!("Invalid clipboard redact pattern '{}'.", pattern)
+        })
+      })
+      .collect::<anyhow::Result<_>>()?;
+
+    Ok(Self { patterns })
+  }
+
+  /// Masks sensitive content and truncates the text to `max_length`
+  /// characters.
+  ///
+  /// Redaction happens before truncation, so that secrets cut off at the
+  /// end of the preview are still matched.
+  pub fn preview(&self, text: &str, max_length: usize) -> String {
+    let mut preview = text.to_string();
+
+    for pattern in &self.patterns {
+      preview = pattern.replace_all(&preview, MASK).into_owned();
+    }
+
+    // Collapse whitespace so that multi-line content fits on one line.
+    let preview = preview.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match preview.char_indices().nth(max_length) {
+      Some((index, _)) => format!("{}…", &preview[..index]),
+      None => preview,
+    }
+  }
+}
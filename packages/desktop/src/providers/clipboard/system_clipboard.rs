@@ -0,0 +1,115 @@
+use std::{
+  collections::hash_map::DefaultHasher,
+  hash::{Hash, Hasher},
+  sync::{Mutex, OnceLock},
+};
+
+use anyhow::Context;
+use arboard::Clipboard;
+
+use super::ClipboardContentType;
+
+/// Clipboard handle shared between providers and commands.
+///
+/// On Linux, copied content is served by the process that owns the
+/// clipboard, so the handle is kept alive rather than being created per
+/// call.
+static CLIPBOARD: OnceLock<Mutex<Option<Clipboard>>> = OnceLock::new();
+
+/// Current contents of the system clipboard.
+#[derive(Debug)]
+pub struct ClipboardContent {
+  pub content_type: ClipboardContentType,
+  pub text: Option<String>,
+}
+
+fn with_clipboard<T>(
+  f: impl FnOnce(&mut Clipboard) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+  let mut clipboard =
+    CLIPBOARD.get_or_init(|| Mutex::new(None)).lock().unwrap();
+
+  if clipboard.is_none() {
+    *clipboard =
+      Some(Clipboard::new().context("Failed to access clipboard.")?);
+  }
+
+  f(clipboard.as_mut().unwrap())
+}
+
+/// Reads the current contents of the system clipboard.
+///
+/// Also returns a fingerprint of the contents, which is used for change
+/// detection on platforms without a clipboard change counter.
+pub fn read_clipboard() -> anyhow::Result<(ClipboardContent, u64)> {
+  with_clipboard(|clipboard| {
+    let mut hasher = DefaultHasher::new();
+
+    if let Ok(text) = clipboard.get_text() {
+      text.hash(&mut hasher);
+
+      return Ok((
+        ClipboardContent {
+          content_type: ClipboardContentType::Text,
+          text: Some(text),
+        },
+        hasher.finish(),
+      ));
+    }
+
+    let content_type = if let Ok(image) = clipboard.get_image() {
+      (image.width, image.height, &image.bytes).hash(&mut hasher);
+      ClipboardContentType::Image
+    } else if let Ok(files) = clipboard.get().file_list() {
+      files.hash(&mut hasher);
+      ClipboardContentType::Files
+    } else {
+      ClipboardContentType::Empty
+    };
+
+    Ok((
+      ClipboardContent {
+        content_type,
+        text: None,
+      },
+      hasher.finish(),
+    ))
+  })
+}
+
+/// Replaces the contents of the system clipboard with the given text.
+pub fn set_clipboard_text(text: &str) -> anyhow::Result<()> {
+  with_clipboard(|clipboard| {
+    clipboard
+      .set_text(text)
+      .context("Failed to write to clipboard.")
+  })
+}
+
+/// Gets a counter that's incremented by the OS whenever the clipboard
+/// changes.
+///
+/// This is much cheaper than reading the clipboard contents, so the
+/// contents are only read when the counter changes.
+#[cfg(target_os = "windows")]
+pub fn change_count() -> Option<u64> {
+  use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+
+  Some(unsafe { GetClipboardSequenceNumber() } as u64)
+}
+
+#[cfg(target_os = "macos")]
+pub fn change_count() -> Option<u64> {
+  use cocoa::{appkit::NSPasteboard, base::nil};
+
+  Some(
+    unsafe { NSPasteboard::generalPasteboard(nil).changeCount() } as u64,
+  )
+}
+
+/// X11 and Wayland don't expose a change counter, so changes are instead
+/// detected by comparing the contents.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn change_count() -> Option<u64> {
+  None
+}
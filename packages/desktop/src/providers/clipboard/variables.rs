@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardVariables {
+  pub content_type: ClipboardContentType,
+
+  /// Redacted and truncated preview of the clipboard text. This is `None`
+  /// if the clipboard doesn't contain text.
+  pub preview: Option<String>,
+
+  /// Previous clipboard contents, most recent first.
+  pub history: Vec<ClipboardHistoryEntry>,
+  pub history_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardContentType {
+  Text,
+  Image,
+  Files,
+  Empty,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardHistoryEntry {
+  pub content_type: ClipboardContentType,
+  pub preview: Option<String>,
+
+  /// Unix timestamp in milliseconds of when the content was copied.
+  pub copied_at: u128,
+}
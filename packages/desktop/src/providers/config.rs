@@ -1,24 +1,428 @@
-use serde::Deserialize;
+use std::{collections::HashMap, path::Path};
 
-#[cfg(windows)]
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "audio_input")]
+use super::audio_input::AudioInputProviderConfig;
+#[cfg(feature = "battery")]
+use super::battery::BatteryProviderConfig;
+#[cfg(feature = "clipboard")]
+use super::clipboard::ClipboardProviderConfig;
+#[cfg(feature = "cpu")]
+use super::cpu::CpuProviderConfig;
+#[cfg(feature = "host")]
+use super::host::HostProviderConfig;
+#[cfg(feature = "ip")]
+use super::ip::IpProviderConfig;
+#[cfg(all(windows, feature = "komorebi"))]
 use super::komorebi::KomorebiProviderConfig;
+#[cfg(feature = "kubernetes")]
+use super::kubernetes::KubernetesProviderConfig;
+#[cfg(feature = "memory")]
+use super::memory::MemoryProviderConfig;
+#[cfg(feature = "network")]
+use super::network::NetworkProviderConfig;
+#[cfg(feature = "openrgb")]
+use super::openrgb::OpenRgbProviderConfig;
+#[cfg(feature = "removable_drives")]
+use super::removable_drives::RemovableDrivesProviderConfig;
+#[cfg(feature = "steam")]
+use super::steam::SteamProviderConfig;
+#[cfg(feature = "summary")]
+use super::summary::SummaryProviderConfig;
+#[cfg(feature = "system_state")]
+use super::system_state::SystemStateProviderConfig;
+#[cfg(feature = "tail")]
+use super::tail::TailProviderConfig;
+#[cfg(feature = "weather")]
+use super::weather::WeatherProviderConfig;
+#[cfg(all(windows, feature = "windows_list"))]
+use super::windows_list::WindowsListProviderConfig;
 use super::{
-  battery::BatteryProviderConfig, cpu::CpuProviderConfig,
-  host::HostProviderConfig, ip::IpProviderConfig,
-  memory::MemoryProviderConfig, network::NetworkProviderConfig,
-  weather::WeatherProviderConfig,
+  schedule::{Schedule, DEFAULT_SCHEDULE_GRACE},
+  state::StateProviderConfig,
+  wait_for::{WaitFor, DEFAULT_WAIT_FOR_TIMEOUT},
 };
 
+/// All provider types, along with whether they're compiled in this
+/// build. Each type other than `state` has a Cargo feature of the same
+/// name, which is enabled by default.
+const PROVIDER_TYPES: &[(&str, bool)] = &[
+  ("audio_input", cfg!(feature = "audio_input")),
+  ("battery", cfg!(feature = "battery")),
+  ("clipboard", cfg!(feature = "clipboard")),
+  ("cpu", cfg!(feature = "cpu")),
+  ("host", cfg!(feature = "host")),
+  ("ip", cfg!(feature = "ip")),
+  ("komorebi", cfg!(all(windows, feature = "komorebi"))),
+  ("kubernetes", cfg!(feature = "kubernetes")),
+  ("memory", cfg!(feature = "memory")),
+  ("network", cfg!(feature = "network")),
+  ("openrgb", cfg!(feature = "openrgb")),
+  ("removable_drives", cfg!(feature = "removable_drives")),
+  ("state", true),
+  ("steam", cfg!(feature = "steam")),
+  ("summary", cfg!(feature = "summary")),
+  ("system_state", cfg!(feature = "system_state")),
+  ("tail", cfg!(feature = "tail")),
+  ("weather", cfg!(feature = "weather")),
+  ("windows_list", cfg!(all(windows, feature = "windows_list"))),
+];
+
+/// Names of the provider types that are compiled in this build.
+pub fn enabled_provider_types() -> Vec<&'static str> {
+  PROVIDER_TYPES
+    .iter()
+    .filter(|(_, is_enabled)| *is_enabled)
+    .map(|(type_name, _)| *type_name)
+    .collect()
+}
+
+/// Config for a provider, along with options that are shared between all
+/// provider types.
+#[derive(Deserialize, Debug)]
+pub struct ProviderConfig {
+  #[serde(flatten, deserialize_with = "deserialize_type_config")]
+  pub type_config: ProviderTypeConfig,
+
+  /// Map of transform names to expressions that are evaluated against
+  /// the provider's output. Results are emitted under `computed`.
+  #[serde(default)]
+  pub transforms: HashMap<String, String>,
+
+  /// Delay in milliseconds before the provider's first run. Overrides
+  /// the global `provider_start_delay`.
+  pub start_delay: Option<u64>,
+
+  /// Max random delay in milliseconds that is added to `start_delay`.
+  /// Overrides the global `provider_jitter`.
+  pub jitter: Option<u64>,
+
+  /// Static value that is emitted in place of data while the provider is
+  /// erroring. The error is added under the `_error` key, and the kind of
+  /// network failure (if any) under `_networkError`.
+  pub fallback: Option<Map<String, Value>>,
+
+  /// Schedule on which the provider is refreshed in addition to its
+  /// `refresh_interval`, as a cron expression (e.g. `0 7,13 * * *`) or a
+  /// list of times on given days (e.g. `weekdays@07:00`). Combine with a
+  /// long `refresh_interval` to mainly refresh on schedule.
+  pub schedule: Option<Schedule>,
+
+  /// Window in milliseconds within which a trigger that was missed (e.g.
+  /// while the machine was asleep) is still fired once.
+  #[serde(default = "default_schedule_grace")]
+  pub schedule_grace: u64,
+
+  /// Dependency that the provider's first run waits for after its start
+  /// delay, either `network` or `process:<name>` (e.g.
+  /// `process:komorebi.exe`). Avoids errors while the system is still
+  /// starting up at login.
+  pub wait_for: Option<WaitFor>,
+
+  /// Max time in milliseconds to wait for `wait_for`, after which the
+  /// provider runs anyway and reports errors as usual.
+  #[serde(default = "default_wait_for_timeout")]
+  pub wait_for_timeout: u64,
+}
+
+impl ProviderConfig {
+  /// Keys of the options that are shared between all provider types, as
+  /// opposed to fields of `type_config`.
+  pub const SHARED_OPTIONS: &'static [&'static str] = &[
+    "transforms",
+    "start_delay",
+    "jitter",
+    "fallback",
+    "schedule",
+    "schedule_grace",
+    "wait_for",
+    "wait_for_timeout",
+  ];
+}
+
+fn default_schedule_grace() -> u64 {
+  DEFAULT_SCHEDULE_GRACE
+}
+
+fn default_wait_for_timeout() -> u64 {
+  DEFAULT_WAIT_FOR_TIMEOUT
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
-pub enum ProviderConfig {
+pub enum ProviderTypeConfig {
+  #[cfg(feature = "audio_input")]
+  AudioInput(AudioInputProviderConfig),
+  #[cfg(feature = "battery")]
   Battery(BatteryProviderConfig),
+  #[cfg(feature = "clipboard")]
+  Clipboard(ClipboardProviderConfig),
+  #[cfg(feature = "cpu")]
   Cpu(CpuProviderConfig),
+  #[cfg(feature = "host")]
   Host(HostProviderConfig),
+  #[cfg(feature = "ip")]
   Ip(IpProviderConfig),
-  #[cfg(windows)]
+  #[cfg(all(windows, feature = "komorebi"))]
   Komorebi(KomorebiProviderConfig),
+  #[cfg(feature = "kubernetes")]
+  Kubernetes(KubernetesProviderConfig),
+  #[cfg(feature = "memory")]
   Memory(MemoryProviderConfig),
+  #[cfg(feature = "network")]
   Network(NetworkProviderConfig),
+  #[cfg(feature = "openrgb")]
+  #[serde(rename = "openrgb")]
+  OpenRgb(OpenRgbProviderConfig),
+  #[cfg(feature = "removable_drives")]
+  RemovableDrives(RemovableDrivesProviderConfig),
+  State(StateProviderConfig),
+  #[cfg(feature = "steam")]
+  Steam(SteamProviderConfig),
+  #[cfg(feature = "summary")]
+  Summary(SummaryProviderConfig),
+  #[cfg(feature = "system_state")]
+  SystemState(SystemStateProviderConfig),
+  #[cfg(feature = "tail")]
+  Tail(TailProviderConfig),
+  #[cfg(feature = "weather")]
   Weather(WeatherProviderConfig),
+  #[cfg(all(windows, feature = "windows_list"))]
+  WindowsList(WindowsListProviderConfig),
+}
+
+impl ProviderTypeConfig {
+  /// Name of the provider type, as used in the config (e.g. `cpu`).
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      #[cfg(feature = "audio_input")]
+      ProviderTypeConfig::AudioInput(_) => "audio_input",
+      #[cfg(feature = "battery")]
+      ProviderTypeConfig::Battery(_) => "battery",
+      #[cfg(feature = "clipboard")]
+      ProviderTypeConfig::Clipboard(_) => "clipboard",
+      #[cfg(feature = "cpu")]
+      ProviderTypeConfig::Cpu(_) => "cpu",
+      #[cfg(feature = "host")]
+      ProviderTypeConfig::Host(_) => "host",
+      #[cfg(feature = "ip")]
+      ProviderTypeConfig::Ip(_) => "ip",
+      #[cfg(all(windows, feature = "komorebi"))]
+      ProviderTypeConfig::Komorebi(_) => "komorebi",
+      #[cfg(feature = "kubernetes")]
+      ProviderTypeConfig::Kubernetes(_) => "kubernetes",
+      #[cfg(feature = "memory")]
+      ProviderTypeConfig::Memory(_) => "memory",
+      #[cfg(feature = "network")]
+      ProviderTypeConfig::Network(_) => "network",
+      #[cfg(feature = "openrgb")]
+      ProviderTypeConfig::OpenRgb(_) => "openrgb",
+      #[cfg(feature = "removable_drives")]
+      ProviderTypeConfig::RemovableDrives(_) => "removable_drives",
+      ProviderTypeConfig::State(_) => "state",
+      #[cfg(feature = "steam")]
+      ProviderTypeConfig::Steam(_) => "steam",
+      #[cfg(feature = "summary")]
+      ProviderTypeConfig::Summary(_) => "summary",
+      #[cfg(feature = "system_state")]
+      ProviderTypeConfig::SystemState(_) => "system_state",
+      #[cfg(feature = "tail")]
+      ProviderTypeConfig::Tail(_) => "tail",
+      #[cfg(feature = "weather")]
+      ProviderTypeConfig::Weather(_) => "weather",
+      #[cfg(all(windows, feature = "windows_list"))]
+      ProviderTypeConfig::WindowsList(_) => "windows_list",
+    }
+  }
+
+  /// Hosts that the provider makes HTTP requests to.
+  pub fn http_hosts(&self) -> &'static [&'static str] {
+    match self {
+      #[cfg(feature = "ip")]
+      ProviderTypeConfig::Ip(_) => &["ipinfo.io"],
+      #[cfg(feature = "weather")]
+      ProviderTypeConfig::Weather(_) => {
+        &["api.open-meteo.com", "geocoding-api.open-meteo.com"]
+      }
+      _ => &[],
+    }
+  }
+
+  /// Files that the provider reads, which require the
+  /// `filesystem_paths` permission.
+  pub fn filesystem_paths(&self) -> Vec<&Path> {
+    match self {
+      #[cfg(feature = "tail")]
+      ProviderTypeConfig::Tail(config) => vec![Path::new(&config.path)],
+      _ => Vec::new(),
+    }
+  }
+
+  /// Whether the provider reads the clipboard, which requires the
+  /// `clipboard` permission.
+  pub fn reads_clipboard(&self) -> bool {
+    match self {
+      #[cfg(feature = "clipboard")]
+      ProviderTypeConfig::Clipboard(_) => true,
+      _ => false,
+    }
+  }
+
+  /// Whether the provider opens an input device, which shows the OS
+  /// privacy indicator. These providers are only created once a window
+  /// subscribes.
+  pub fn captures_audio(&self) -> bool {
+    match self {
+      #[cfg(feature = "audio_input")]
+      ProviderTypeConfig::AudioInput(config) => config.capture_levels,
+      _ => false,
+    }
+  }
+}
+
+/// Deserializes the type-specific config. Gives a clear error for
+/// provider types that aren't compiled in this build, rather than
+/// serde's unknown variant error.
+fn deserialize_type_config<'de, D>(
+  deserializer: D,
+) -> Result<ProviderTypeConfig, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let value = Value::deserialize(deserializer)?;
+
+  if let Some(type_name) = value.get("type").and_then(Value::as_str) {
+    let is_disabled = PROVIDER_TYPES
+      .iter()
+      .any(|(name, is_enabled)| *name == type_name && !is_enabled);
+
+    if is_disabled {
+      return Err(D::Error::custom(format!(
+        "Provider type '{}' isn't compiled in this build.",
+        type_name
+      )));
+    }
+  }
+
+  ProviderTypeConfig::deserialize(value).map_err(D::Error::custom)
+}
+
+/// Provider type that is compiled in this build.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableProvider {
+  #[serde(rename = "type")]
+  pub type_name: &'static str,
+
+  /// JSON schema of the provider's type-specific config. Shared options
+  /// (e.g. `transforms`) aren't included.
+  pub config_schema: RootSchema,
+}
+
+impl AvailableProvider {
+  fn new<T: JsonSchema>(type_name: &'static str) -> Self {
+    Self {
+      type_name,
+      config_schema: schema_for!(T),
+    }
+  }
+}
+
+/// Gets all provider types that are compiled in this build, in the same
+/// order as `ProviderTypeConfig`.
+pub fn available_providers() -> Vec<AvailableProvider> {
+  vec![
+    #[cfg(feature = "audio_input")]
+    AvailableProvider::new::<AudioInputProviderConfig>("audio_input"),
+    #[cfg(feature = "battery")]
+    AvailableProvider::new::<BatteryProviderConfig>("battery"),
+    #[cfg(feature = "clipboard")]
+    AvailableProvider::new::<ClipboardProviderConfig>("clipboard"),
+    #[cfg(feature = "cpu")]
+    AvailableProvider::new::<CpuProviderConfig>("cpu"),
+    #[cfg(feature = "host")]
+    AvailableProvider::new::<HostProviderConfig>("host"),
+    #[cfg(feature = "ip")]
+    AvailableProvider::new::<IpProviderConfig>("ip"),
+    #[cfg(all(windows, feature = "komorebi"))]
+    AvailableProvider::new::<KomorebiProviderConfig>("komorebi"),
+    #[cfg(feature = "kubernetes")]
+    AvailableProvider::new::<KubernetesProviderConfig>("kubernetes"),
+    #[cfg(feature = "memory")]
+    AvailableProvider::new::<MemoryProviderConfig>("memory"),
+    #[cfg(feature = "network")]
+    AvailableProvider::new::<NetworkProviderConfig>("network"),
+    #[cfg(feature = "openrgb")]
+    AvailableProvider::new::<OpenRgbProviderConfig>("openrgb"),
+    #[cfg(feature = "removable_drives")]
+    AvailableProvider::new::<RemovableDrivesProviderConfig>(
+      "removable_drives",
+    ),
+    AvailableProvider::new::<StateProviderConfig>("state"),
+    #[cfg(feature = "steam")]
+    AvailableProvider::new::<SteamProviderConfig>("steam"),
+    #[cfg(feature = "summary")]
+    AvailableProvider::new::<SummaryProviderConfig>("summary"),
+    #[cfg(feature = "system_state")]
+    AvailableProvider::new::<SystemStateProviderConfig>("system_state"),
+    #[cfg(feature = "tail")]
+    AvailableProvider::new::<TailProviderConfig>("tail"),
+    #[cfg(feature = "weather")]
+    AvailableProvider::new::<WeatherProviderConfig>("weather"),
+    #[cfg(all(windows, feature = "windows_list"))]
+    AvailableProvider::new::<WindowsListProviderConfig>("windows_list"),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn parse_type(type_name: &str) -> Result<ProviderConfig, String> {
+    serde_json::from_value::<ProviderConfig>(json!({ "type": type_name }))
+      .map_err(|err| err.to_string())
+  }
+
+  #[test]
+  fn excluded_types_give_clear_error() {
+    for (type_name, is_enabled) in PROVIDER_TYPES {
+      let res = parse_type(type_name);
+      let is_excluded_err = res
+        .as_ref()
+        .is_err_and(|err| err.contains("isn't compiled in this build"));
+
+      assert_eq!(
+        is_excluded_err, !is_enabled,
+        "Unexpected result for '{}': {:?}",
+        type_name, res
+      );
+    }
+  }
+
+  #[test]
+  fn available_providers_match_enabled_types() {
+    let available = available_providers()
+      .into_iter()
+      .map(|provider| provider.type_name)
+      .collect::<Vec<_>>();
+
+    assert_eq!(available, enabled_provider_types());
+  }
+
+  #[test]
+  fn state_is_always_enabled() {
+    assert!(enabled_provider_types().contains(&"state"));
+  }
+
+  #[test]
+  fn unknown_type_isnt_reported_as_excluded() {
+    let err = parse_type("nonexistent").unwrap_err();
+
+    assert!(!err.contains("isn't compiled in this build"));
+  }
 }
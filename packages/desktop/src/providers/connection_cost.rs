@@ -0,0 +1,116 @@
+use serde::Serialize;
+#[cfg(target_os = "windows")]
+use windows::Networking::Connectivity::{
+  ConnectionProfile, NetworkConnectivityLevel, NetworkCostType,
+  NetworkInformation,
+};
+
+/// Cost of the connection that is used for internet access.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionCost {
+  /// Whether data usage is limited or charged (e.g. a mobile hotspot).
+  pub is_metered: bool,
+
+  /// Whether the connection is roaming outside of its home network. Only
+  /// detected on Windows.
+  pub is_roaming: bool,
+}
+
+/// Gets the cost of the active internet connection. `None` if there's
+/// no connection or the platform doesn't report it.
+///
+/// Can block briefly, so it should be called off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn connection_cost() -> Option<ConnectionCost> {
+  let profile = internet_connection_profile()?;
+  let cost = profile.GetConnectionCost().ok()?;
+
+  let is_roaming = cost.Roaming().unwrap_or(false);
+  let is_over_limit = cost.OverDataLimit().unwrap_or(false);
+
+  let is_metered = matches!(
+    cost.NetworkCostType().ok()?,
+    NetworkCostType::Fixed | NetworkCostType::Variable
+  );
+
+  Some(ConnectionCost {
+    is_metered: is_metered || is_roaming || is_over_limit,
+    is_roaming,
+  })
+}
+
+/// Reads the metered state of the primary connection from
+/// NetworkManager.
+#[cfg(target_os = "linux")]
+pub fn connection_cost() -> Option<ConnectionCost> {
+  let metered = network_manager_property("Metered")?;
+
+  Some(ConnectionCost {
+    // `NM_METERED_YES` and `NM_METERED_GUESS_YES`.
+    is_metered: matches!(metered, 1 | 3),
+    is_roaming: false,
+  })
+}
+
+#[cfg(target_os = "macos")]
+pub fn connection_cost() -> Option<ConnectionCost> {
+  None
+}
+
+/// Gets whether the OS reports internet access on the active
+/// connection. `None` if the platform doesn't report it.
+///
+/// Can block briefly, so it should be called off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn internet_access() -> Option<bool> {
+  let Some(profile) = internet_connection_profile() else {
+    // No connection is used for internet access (e.g. Wi-Fi hasn't
+    // associated yet).
+    return Some(false);
+  };
+
+  let level = profile.GetNetworkConnectivityLevel().ok()?;
+  Some(level == NetworkConnectivityLevel::InternetAccess)
+}
+
+/// Reads the connectivity state from NetworkManager.
+#[cfg(target_os = "linux")]
+pub fn internet_access() -> Option<bool> {
+  // `NM_CONNECTIVITY_FULL`.
+  network_manager_property("Connectivity").map(|state| state == 4)
+}
+
+#[cfg(target_os = "macos")]
+pub fn internet_access() -> Option<bool> {
+  None
+}
+
+#[cfg(target_os = "windows")]
+fn internet_connection_profile() -> Option<ConnectionProfile> {
+  NetworkInformation::GetInternetConnectionProfile().ok()
+}
+
+/// Reads an integer property of NetworkManager over D-Bus.
+#[cfg(target_os = "linux")]
+fn network_manager_property(name: &str) -> Option<u32> {
+  let output = std::process::Command::new("busctl")
+    .args([
+      "--system",
+      "get-property",
+      "org.freedesktop.NetworkManager",
+      "/org/freedesktop/NetworkManager",
+      "org.freedesktop.NetworkManager",
+      name,
+    ])
+    .output()
+    .ok()
+    .filter(|output| output.status.success())?;
+
+  // Output is the D-Bus type followed by the value (e.g. `u 4`).
+  String::from_utf8_lossy(&output.stdout)
+    .trim()
+    .strip_prefix("u ")?
+    .parse::<u32>()
+    .ok()
+}
@@ -1,11 +1,18 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-use crate::impl_interval_config;
+use crate::{
+  impl_interval_config, providers::high_frequency::HighFrequencyConfig,
+};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "cpu")]
 pub struct CpuProviderConfig {
   pub refresh_interval: u64,
+
+  /// Opt-in sampling of usage between refreshes. Samples are emitted
+  /// under `samples` on each refresh.
+  pub high_frequency: Option<HighFrequencyConfig>,
 }
 
 impl_interval_config!(CpuProviderConfig);
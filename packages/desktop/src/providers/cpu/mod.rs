@@ -1,5 +1,7 @@
 mod config;
 mod provider;
+mod system_counters;
+mod usage_window;
 mod variables;
 
 pub use config::*;
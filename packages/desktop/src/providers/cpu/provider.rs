@@ -1,18 +1,56 @@
-use std::sync::Arc;
+use std::{
+  sync::Arc,
+  time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use sysinfo::System;
-use tokio::{sync::Mutex, task::AbortHandle};
+use sysinfo::{System, MINIMUM_CPU_UPDATE_INTERVAL};
+use tokio::{
+  sync::Mutex,
+  task::{self, AbortHandle},
+  time::{self, MissedTickBehavior},
+};
 
-use super::{CpuProviderConfig, CpuVariables};
+use super::{
+  system_counters::{RateCounter, SystemCountersReader},
+  usage_window::{UsageStats, UsageWindow},
+  CpuProviderConfig, CpuSample, CpuVariables, LoadAverage,
+};
 use crate::providers::{
-  provider::IntervalProvider, variables::ProviderVariables,
+  high_frequency::{spawn_sampler, SampleBuffer},
+  provider::IntervalProvider,
+  retained_size::{RetainedSize, RetainedState},
+  sample_clock::SampleClock,
+  variables::ProviderVariables,
 };
 
+/// Interval at which usage is sampled between refreshes. Usage is
+/// aggregated over the samples, so that short spikes aren't missed at
+/// long refresh intervals.
+const USAGE_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
 pub struct CpuProvider {
   pub config: Arc<CpuProviderConfig>,
   abort_handle: Option<AbortHandle>,
+  state: Arc<CpuState>,
+}
+
+pub struct CpuState {
   sysinfo: Arc<Mutex<System>>,
+  counters: Mutex<CpuCounters>,
+  samples: Option<Arc<SampleBuffer<CpuSample>>>,
+
+  /// Usage samples since the last refresh. `None` if the refresh
+  /// interval is too short for sampling in between.
+  usage_window: Option<Arc<std::sync::Mutex<UsageWindow>>>,
+}
+
+#[derive(Default)]
+struct CpuCounters {
+  reader: SystemCountersReader,
+  clock: SampleClock,
+  context_switches: RateCounter,
+  interrupts: RateCounter,
 }
 
 impl CpuProvider {
@@ -20,25 +58,126 @@ impl CpuProvider {
     config: CpuProviderConfig,
     sysinfo: Arc<Mutex<System>>,
   ) -> CpuProvider {
+    let samples = config.high_frequency.as_ref().map(|hf_config| {
+      // Uses a separate `System`, so that sampling doesn't affect the
+      // usage that's measured between refreshes.
+      let mut system = System::new();
+      let mut last_refresh = None::<Instant>;
+
+      spawn_sampler(hf_config, move || {
+        // Usage is inaccurate if refreshed more often than this.
+        if last_refresh.is_some_and(|last_refresh| {
+          last_refresh.elapsed() < MINIMUM_CPU_UPDATE_INTERVAL
+        }) {
+          return None;
+        }
+
+        system.refresh_cpu_usage();
+        let is_first_refresh = last_refresh.is_none();
+        last_refresh = Some(Instant::now());
+
+        // The first refresh only sets the baseline.
+        (!is_first_refresh).then(|| CpuSample {
+          usage: system.global_cpu_info().cpu_usage(),
+        })
+      })
+    });
+
+    let usage_window = (config.refresh_interval
+      > USAGE_SAMPLE_INTERVAL.as_millis() as u64)
+      .then(|| Self::spawn_usage_sampler(sysinfo.clone()));
+
     CpuProvider {
       config: Arc::new(config),
       abort_handle: None,
-      sysinfo,
+      state: Arc::new(CpuState {
+        sysinfo,
+        counters: Mutex::new(CpuCounters::default()),
+        samples,
+        usage_window,
+      }),
     }
   }
+
+  /// Samples usage every `USAGE_SAMPLE_INTERVAL` in the background,
+  /// using the shared `System`.
+  ///
+  /// Sampling stops once the returned window is dropped (i.e. when the
+  /// provider is dropped).
+  fn spawn_usage_sampler(
+    sysinfo: Arc<Mutex<System>>,
+  ) -> Arc<std::sync::Mutex<UsageWindow>> {
+    let window = Arc::new(std::sync::Mutex::new(UsageWindow::default()));
+    let weak_window = Arc::downgrade(&window);
+
+    task::spawn(async move {
+      let mut interval = time::interval(USAGE_SAMPLE_INTERVAL);
+      interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+      // The first tick fires immediately, and only sets the baseline
+      // that the next sample's usage is measured against.
+      interval.tick().await;
+      sysinfo.lock().await.refresh_cpu_usage();
+
+      loop {
+        interval.tick().await;
+
+        let Some(window) = weak_window.upgrade() else {
+          break;
+        };
+
+        let usage = {
+          let mut sysinfo = sysinfo.lock().await;
+          sysinfo.refresh_cpu_usage();
+          sysinfo.global_cpu_info().cpu_usage()
+        };
+
+        window.lock().unwrap().push(usage);
+      }
+    });
+
+    window
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  fn load_average() -> Option<LoadAverage> {
+    let load_avg = System::load_average();
+
+    Some(LoadAverage {
+      one: load_avg.one,
+      five: load_avg.five,
+      fifteen: load_avg.fifteen,
+    })
+  }
+
+  #[cfg(target_os = "windows")]
+  fn load_average() -> Option<LoadAverage> {
+    None
+  }
 }
 
 #[async_trait]
 impl IntervalProvider for CpuProvider {
   type Config = CpuProviderConfig;
-  type State = Mutex<System>;
+  type State = CpuState;
 
   fn config(&self) -> Arc<CpuProviderConfig> {
     self.config.clone()
   }
 
-  fn state(&self) -> Arc<Mutex<System>> {
-    self.sysinfo.clone()
+  fn state(&self) -> Arc<CpuState> {
+    self.state.clone()
+  }
+
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(
+      self
+        .state
+        .samples
+        .iter()
+        .map(|samples| samples.clone() as Arc<dyn RetainedSize>)
+        .collect(),
+    )
   }
 
   fn abort_handle(&self) -> &Option<AbortHandle> {
@@ -50,20 +189,62 @@ impl IntervalProvider for CpuProvider {
   }
 
   async fn get_refreshed_variables(
-    _: &CpuProviderConfig,
-    sysinfo: &Mutex<System>,
+    config: &CpuProviderConfig,
+    state: &CpuState,
   ) -> anyhow::Result<ProviderVariables> {
-    let mut sysinfo = sysinfo.lock().await;
-    sysinfo.refresh_cpu();
+    let mut sysinfo = state.sysinfo.lock().await;
+
+    let usage_stats = state
+      .usage_window
+      .as_ref()
+      .and_then(|window| window.lock().unwrap().take());
+
+    // Falls back to measuring usage since the previous refresh if no
+    // samples were taken in between (e.g. on the first refresh).
+    let usage_stats = match usage_stats {
+      Some(usage_stats) => {
+        sysinfo.refresh_cpu_frequency();
+        usage_stats
+      }
+      None => {
+        sysinfo.refresh_cpu();
+        UsageStats::point(sysinfo.global_cpu_info().cpu_usage())
+      }
+    };
+
+    let mut counters = state.counters.lock().await;
+
+    let (context_switches_per_sec, interrupts_per_sec) =
+      match counters.reader.read() {
+        Some(sample) => {
+          let elapsed = counters
+            .clock
+            .tick(Duration::from_millis(config.refresh_interval));
+
+          (
+            counters
+              .context_switches
+              .sample(sample.context_switches, elapsed),
+            counters.interrupts.sample(sample.interrupts, elapsed),
+          )
+        }
+        None => (None, None),
+      };
 
     Ok(ProviderVariables::Cpu(CpuVariables {
-      usage: sysinfo.global_cpu_info().cpu_usage(),
+      usage: usage_stats.average,
+      usage_max: usage_stats.max,
+      usage_latest: usage_stats.latest,
       frequency: sysinfo.global_cpu_info().frequency(),
       logical_core_count: sysinfo.cpus().len(),
       physical_core_count: sysinfo
         .physical_core_count()
         .unwrap_or(sysinfo.cpus().len()),
       vendor: sysinfo.global_cpu_info().vendor_id().into(),
+      load_average: Self::load_average(),
+      context_switches_per_sec,
+      interrupts_per_sec,
+      samples: state.samples.as_ref().map(|samples| samples.drain()),
     }))
   }
 }
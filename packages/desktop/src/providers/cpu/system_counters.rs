@@ -0,0 +1,262 @@
+use std::time::Duration;
+
+/// Converts a cumulative counter into a per-second rate by taking the
+/// delta between two consecutive samples.
+#[derive(Debug, Default)]
+pub struct RateCounter {
+  prev_value: Option<u64>,
+}
+
+impl RateCounter {
+  /// Records a sample of the counter and gets the rate since the
+  /// previous sample, given the time elapsed in between as measured by
+  /// a `SampleClock`.
+  ///
+  /// This is `None` for the first sample, and if `elapsed` is `None`
+  /// (i.e. there was a clock discontinuity in between).
+  pub fn sample(
+    &mut self,
+    value: u64,
+    elapsed: Option<Duration>,
+  ) -> Option<f64> {
+    let prev_value = self.prev_value.replace(value)?;
+    let elapsed_secs = elapsed?.as_secs_f64();
+
+    counter_delta(prev_value, value)
+      .map(|delta| delta as f64 / elapsed_secs)
+  }
+}
+
+/// Gets the difference between two samples of a cumulative counter.
+///
+/// Some counters are 32-bit and wrap around on overflow. A decrease in
+/// a counter that doesn't fit a wrap (i.e. it was reset) has no
+/// meaningful delta.
+pub fn counter_delta(prev: u64, current: u64) -> Option<u64> {
+  if current >= prev {
+    return Some(current - prev);
+  }
+
+  match prev <= u32::MAX as u64 {
+    true => Some(u32::MAX as u64 - prev + current + 1),
+    false => None,
+  }
+}
+
+/// Cumulative number of context switches and interrupts since boot.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemCounters {
+  pub context_switches: u64,
+  pub interrupts: u64,
+}
+
+/// Reads system-wide counters.
+#[derive(Default)]
+pub struct SystemCountersReader {
+  #[cfg(target_os = "windows")]
+  pdh_query: Option<pdh::PdhQuery>,
+}
+
+impl SystemCountersReader {
+  #[cfg(target_os = "linux")]
+  pub fn read(&mut self) -> Option<SystemCounters> {
+    parse_proc_stat(&std::fs::read_to_string("/proc/stat").ok()?)
+  }
+
+  #[cfg(target_os = "windows")]
+  pub fn read(&mut self) -> Option<SystemCounters> {
+    if self.pdh_query.is_none() {
+      self.pdh_query = pdh::PdhQuery::new();
+    }
+
+    self.pdh_query.as_ref()?.read()
+  }
+
+  /// Counters aren't exposed on other platforms without elevation.
+  #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+  pub fn read(&mut self) -> Option<SystemCounters> {
+    None
+  }
+}
+
+/// Parses the counters from the contents of `/proc/stat`.
+#[cfg(target_os = "linux")]
+fn parse_proc_stat(stat: &str) -> Option<SystemCounters> {
+  let counter = |name: &str| {
+    stat.lines().find_map(|line| {
+      let mut fields = line.split_whitespace();
+
+      match fields.next() == Some(name) {
+        // The first value of `intr` is the total across all interrupt
+        // sources.
+        true => fields.next()?.parse::<u64>().ok(),
+        false => None,
+      }
+    })
+  };
+
+  Some(SystemCounters {
+    context_switches: counter("ctxt")?,
+    interrupts: counter("intr")?,
+  })
+}
+
+#[cfg(target_os = "windows")]
+mod pdh {
+  use windows::{
+    core::w,
+    Win32::System::Performance::{
+      PdhAddEnglishCounterW, PdhCloseQuery, PdhCollectQueryData,
+      PdhGetRawCounterValue, PdhOpenQueryW, PDH_HCOUNTER, PDH_HQUERY,
+      PDH_RAW_COUNTER,
+    },
+  };
+
+  use super::SystemCounters;
+
+  /// Query for raw performance counters. Raw values are used instead of
+  /// PDH's formatted rates, so that the rates are calculated the same
+  /// way on all platforms.
+  pub struct PdhQuery {
+    query: PDH_HQUERY,
+    context_switches: PDH_HCOUNTER,
+    interrupts: PDH_HCOUNTER,
+  }
+
+  // SAFETY: PDH handles can be used from any thread.
+  unsafe impl Send for PdhQuery {}
+  unsafe impl Sync for PdhQuery {}
+
+  impl PdhQuery {
+    pub fn new() -> Option<Self> {
+      unsafe {
+        let mut query = PDH_HQUERY::default();
+
+        if PdhOpenQueryW(None, 0, &mut query) != 0 {
+          return None;
+        }
+
+        let mut pdh_query = Self {
+          query,
+          context_switches: PDH_HCOUNTER::default(),
+          interrupts: PDH_HCOUNTER::default(),
+        };
+
+        let context_switches_res = PdhAddEnglishCounterW(
+          query,
+          w!("\\System\\Context Switches/sec"),
+          0,
+          &mut pdh_query.context_switches,
+        );
+
+        let interrupts_res = PdhAddEnglishCounterW(
+          query,
+          w!("\\Processor(_Total)\\Interrupts/sec"),
+          0,
+          &mut pdh_query.interrupts,
+        );
+
+        // Query is closed on drop if adding either counter fails.
+        (context_switches_res == 0 && interrupts_res == 0)
+          .then_some(pdh_query)
+      }
+    }
+
+    pub fn read(&self) -> Option<SystemCounters> {
+      unsafe {
+        if PdhCollectQueryData(self.query) != 0 {
+          return None;
+        }
+
+        Some(SystemCounters {
+          context_switches: Self::raw_value(self.context_switches)?,
+          interrupts: Self::raw_value(self.interrupts)?,
+        })
+      }
+    }
+
+    unsafe fn raw_value(counter: PDH_HCOUNTER) -> Option<u64> {
+      let mut value = PDH_RAW_COUNTER::default();
+
+      match PdhGetRawCounterValue(counter, None, &mut value) {
+        0 => u64::try_from(value.FirstValue).ok(),
+        _ => None,
+      }
+    }
+  }
+
+  impl Drop for PdhQuery {
+    fn drop(&mut self) {
+      unsafe {
+        PdhCloseQuery(self.query);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn counter_delta_handles_wraps_and_resets() {
+    assert_eq!(counter_delta(10, 25), Some(15));
+    assert_eq!(counter_delta(10, 10), Some(0));
+
+    // 32-bit counter wrapped around.
+    assert_eq!(counter_delta(u32::MAX as u64 - 4, 5), Some(10));
+
+    // 64-bit counter can't have wrapped, so it was reset.
+    assert_eq!(counter_delta(u32::MAX as u64 + 10, 5), None);
+  }
+
+  #[test]
+  fn rate_counter_needs_two_samples() {
+    let mut counter = RateCounter::default();
+    let second = Some(Duration::from_secs(1));
+
+    assert_eq!(counter.sample(100, second), None);
+    assert_eq!(
+      counter.sample(300, Some(Duration::from_secs(2))),
+      Some(100.)
+    );
+    assert_eq!(
+      counter.sample(350, Some(Duration::from_millis(500))),
+      Some(100.)
+    );
+  }
+
+  #[test]
+  fn rate_counter_skips_discontinuities() {
+    let mut counter = RateCounter::default();
+    let second = Some(Duration::from_secs(1));
+
+    counter.sample(100, second);
+
+    // The value is still taken as the baseline for the next sample.
+    assert_eq!(counter.sample(5000, None), None);
+    assert_eq!(counter.sample(5010, second), Some(10.));
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn parses_proc_stat() {
+    let stat = "\
+      cpu  4705 356 584 3699 23 23 0 0 0 0\n\
+      cpu0 1393280 32966 572056 13343292 6130 0 17875 0 0 0\n\
+      intr 114930548 113199788 3 0 5 263 0 4 [...]\n\
+      ctxt 1990473\n\
+      btime 1062191376\n";
+
+    let counters = parse_proc_stat(stat).unwrap();
+    assert_eq!(counters.context_switches, 1990473);
+    assert_eq!(counters.interrupts, 114930548);
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn proc_stat_without_counters() {
+    assert!(parse_proc_stat("cpu  4705 356 584\nctxt 1990473\n").is_none());
+    assert!(parse_proc_stat("intr abc\nctxt 1\n").is_none());
+  }
+}
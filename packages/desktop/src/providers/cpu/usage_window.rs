@@ -0,0 +1,54 @@
+/// Aggregate of the usage samples taken within an emission window.
+#[derive(Debug, Clone, Copy)]
+pub struct UsageStats {
+  pub average: f32,
+  pub max: f32,
+  pub latest: f32,
+}
+
+impl UsageStats {
+  /// Stats of a single point-in-time measurement.
+  pub fn point(usage: f32) -> Self {
+    Self {
+      average: usage,
+      max: usage,
+      latest: usage,
+    }
+  }
+}
+
+/// Running aggregate of usage samples since the last emission. Only the
+/// aggregate is kept, so memory use doesn't grow with the emission
+/// interval.
+#[derive(Debug, Default)]
+pub struct UsageWindow {
+  sum: f64,
+  count: u32,
+  max: f32,
+  latest: f32,
+}
+
+impl UsageWindow {
+  pub fn push(&mut self, usage: f32) {
+    self.sum += usage as f64;
+    self.max = if self.count == 0 {
+      usage
+    } else {
+      self.max.max(usage)
+    };
+    self.latest = usage;
+    self.count += 1;
+  }
+
+  /// Gets the stats of the samples since the last call, and starts a new
+  /// window. This is `None` if no samples were taken.
+  pub fn take(&mut self) -> Option<UsageStats> {
+    let window = std::mem::take(self);
+
+    (window.count > 0).then(|| UsageStats {
+      average: (window.sum / window.count as f64) as f32,
+      max: window.max,
+      latest: window.latest,
+    })
+  }
+}
@@ -1,11 +1,49 @@
 use serde::Serialize;
 
+use crate::providers::high_frequency::TimedSample;
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CpuVariables {
   pub frequency: u64,
+
+  /// Average usage over the refresh interval, from samples taken every
+  /// 500ms. Short spikes between refreshes are therefore included.
   pub usage: f32,
+
+  /// Highest sampled usage over the refresh interval.
+  pub usage_max: f32,
+
+  /// Most recently sampled usage.
+  pub usage_latest: f32,
+
   pub logical_core_count: usize,
   pub physical_core_count: usize,
   pub vendor: String,
+
+  /// 1, 5 and 15-minute load averages. Not available on Windows.
+  pub load_average: Option<LoadAverage>,
+
+  /// Rates are null on the first sample, or if the counters aren't
+  /// available on the current platform.
+  pub context_switches_per_sec: Option<f64>,
+  pub interrupts_per_sec: Option<f64>,
+
+  /// Samples taken since the previous refresh. Only set if
+  /// `high_frequency` is enabled.
+  pub samples: Option<Vec<TimedSample<CpuSample>>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuSample {
+  pub usage: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadAverage {
+  pub one: f64,
+  pub five: f64,
+  pub fifteen: f64,
 }
@@ -0,0 +1,170 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+  provider_ref::{ProviderOutput, VariablesResult},
+  subscriptions::ProviderSubscription,
+};
+
+/// Reference from a field of a provider config to a value in the output
+/// of another provider (e.g. `{ from: "ip", path: "approxLatitude" }`).
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OutputRef {
+  /// Type or config hash of the source provider. Resolved against the
+  /// providers that the window is subscribed to.
+  pub from: String,
+
+  /// Dot-separated path to the value in the source's output (e.g.
+  /// `approxLatitude`). Array items are accessed by index.
+  pub path: String,
+
+  /// Min change of a numeric value before the dependent provider is
+  /// updated. Other values update it on any change.
+  #[serde(default)]
+  pub delta: f64,
+}
+
+/// Reference in a provider config, along with the field that it's in.
+#[derive(Debug, Clone)]
+pub struct ConfigRef {
+  /// JSON pointer to the field (e.g. `/latitude`).
+  pub pointer: String,
+  pub output_ref: OutputRef,
+}
+
+/// Finds all fields of the config that reference another provider's
+/// output.
+pub fn find_refs(config: &Value) -> Vec<ConfigRef> {
+  let mut refs = Vec::new();
+  collect_refs(config, String::new(), &mut refs);
+  refs
+}
+
+fn collect_refs(
+  value: &Value,
+  pointer: String,
+  refs: &mut Vec<ConfigRef>,
+) {
+  match value {
+    Value::Object(map) => {
+      // Only objects with a `from` and `path` key are references, so
+      // that `deny_unknown_fields` doesn't reject other objects.
+      if map.contains_key("from") && map.contains_key("path") {
+        if let Ok(output_ref) = OutputRef::deserialize(value) {
+          refs.push(ConfigRef {
+            pointer,
+            output_ref,
+          });
+
+          return;
+        }
+      }
+
+      for (key, value) in map {
+        let key = key.replace('~', "~0").replace('/', "~1");
+        collect_refs(value, format!("{}/{}", pointer, key), refs);
+      }
+    }
+    Value::Array(items) => {
+      for (index, value) in items.iter().enumerate() {
+        collect_refs(value, format!("{}/{}", pointer, index), refs);
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Replaces the referencing fields of the config with the given values.
+pub fn with_values(
+  config: &Value,
+  refs: &[ConfigRef],
+  values: &[Value],
+) -> Value {
+  let mut config = config.clone();
+
+  for (config_ref, value) in refs.iter().zip(values) {
+    if let Some(field) = config.pointer_mut(&config_ref.pointer) {
+      *field = value.clone();
+    }
+  }
+
+  config
+}
+
+/// Gets the value at the path of the output's variables. Returns `None`
+/// if the output is an error or the path doesn't exist.
+pub fn output_value(output: &ProviderOutput, path: &str) -> Option<Value> {
+  let VariablesResult::Data(variables) = &output.variables else {
+    return None;
+  };
+
+  let mut value = serde_json::to_value(variables).ok()?;
+
+  for segment in path.split('.') {
+    value = match value {
+      Value::Object(mut map) => map.remove(segment)?,
+      Value::Array(mut items) => {
+        let index = segment.parse::<usize>().ok()?;
+        (index < items.len()).then(|| items.swap_remove(index))?
+      }
+      _ => return None,
+    };
+  }
+
+  (!value.is_null()).then_some(value)
+}
+
+/// Whether the value changed enough to update the dependent provider.
+pub fn has_changed(prev: &Value, next: &Value, delta: f64) -> bool {
+  match (prev.as_f64(), next.as_f64()) {
+    (Some(prev), Some(next)) => (next - prev).abs() > delta,
+    _ => prev != next,
+  }
+}
+
+/// Finds the provider that a reference points to. Providers with a
+/// matching config hash take precedence over ones of a matching type.
+pub fn resolve_source(
+  subscriptions: &[ProviderSubscription],
+  from: &str,
+) -> Option<String> {
+  subscriptions
+    .iter()
+    .find(|subscription| subscription.config_hash == from)
+    .or_else(|| {
+      subscriptions
+        .iter()
+        .find(|subscription| subscription.provider_type == from)
+    })
+    .map(|subscription| subscription.config_hash.clone())
+}
+
+/// Whether making `dependent` depend on `sources` would create a cycle,
+/// given the sources of existing dependent providers.
+pub fn creates_cycle(
+  graph: &HashMap<String, Vec<String>>,
+  dependent: &str,
+  sources: &[String],
+) -> bool {
+  let mut visited = HashSet::new();
+  let mut stack = sources.to_vec();
+
+  while let Some(config_hash) = stack.pop() {
+    if config_hash == dependent {
+      return true;
+    }
+
+    if !visited.insert(config_hash.clone()) {
+      continue;
+    }
+
+    if let Some(sources) = graph.get(&config_hash) {
+      stack.extend(sources.iter().cloned());
+    }
+  }
+
+  false
+}
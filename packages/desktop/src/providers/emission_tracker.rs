@@ -0,0 +1,129 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use serde::Serialize;
+
+/// Output as emitted to frontend clients, along with its sequence
+/// number. Serializes as the output with an added `sequence` field.
+#[derive(Serialize, Debug, Clone)]
+pub struct Emission<T> {
+  #[serde(flatten)]
+  pub output: T,
+
+  /// Position of the emission among those of the provider instance,
+  /// starting at 1. Clients can detect dropped emissions from gaps.
+  pub sequence: u64,
+}
+
+#[derive(Debug, Default)]
+struct ProviderEmissions {
+  produced: u64,
+  listeners: HashMap<String, ListenerEmissionStats>,
+}
+
+/// Sequence numbers and delivery counts of emissions, keyed by config
+/// hash.
+///
+/// Counts are kept per provider instance, and are reset when the
+/// provider is stopped.
+#[derive(Debug, Default)]
+pub struct EmissionTracker(Mutex<HashMap<String, ProviderEmissions>>);
+
+impl EmissionTracker {
+  /// Starts tracking emissions of a newly created provider.
+  pub fn start(&self, config_hash: &str) {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .insert(config_hash.to_string(), ProviderEmissions::default());
+  }
+
+  /// Records an emission of the provider and gets its sequence number.
+  /// `None` if the provider has been stopped.
+  pub fn next_sequence(&self, config_hash: &str) -> Option<u64> {
+    let mut providers = self.0.lock().unwrap();
+    let emissions = providers.get_mut(config_hash)?;
+
+    emissions.produced += 1;
+    Some(emissions.produced)
+  }
+
+  /// Records that the emission with the given sequence number was sent
+  /// to the window with the given label.
+  pub fn record_delivery(
+    &self,
+    config_hash: &str,
+    window_label: &str,
+    sequence: u64,
+  ) {
+    let mut providers = self.0.lock().unwrap();
+
+    let Some(emissions) = providers.get_mut(config_hash) else {
+      return;
+    };
+
+    let listener = emissions
+      .listeners
+      .entry(window_label.to_string())
+      .or_insert_with(|| ListenerEmissionStats {
+        window_label: window_label.to_string(),
+        delivered: 0,
+        last_sequence: 0,
+      });
+
+    listener.delivered += 1;
+    listener.last_sequence = sequence;
+  }
+
+  /// Clears the counts of a stopped provider, and stops tracking its
+  /// emissions.
+  pub fn remove(&self, config_hash: &str) {
+    self.0.lock().unwrap().remove(config_hash);
+  }
+
+  /// Gets the counts of the provider with the given config hash. `None`
+  /// if it hasn't emitted yet.
+  pub fn stats(&self, config_hash: &str) -> Option<EmissionStats> {
+    let providers = self.0.lock().unwrap();
+    let emissions = providers.get(config_hash)?;
+
+    let mut listeners =
+      emissions.listeners.values().cloned().collect::<Vec<_>>();
+
+    listeners.sort_by(|a, b| a.window_label.cmp(&b.window_label));
+
+    Some(EmissionStats {
+      config_hash: config_hash.to_string(),
+      produced: emissions.produced,
+      listeners,
+    })
+  }
+}
+
+/// Emission counts of a provider instance, returned by
+/// `get_emission_stats`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EmissionStats {
+  pub config_hash: String,
+
+  /// Number of emissions, which is also the latest sequence number.
+  pub produced: u64,
+
+  /// Deliveries to each window that has received emissions.
+  pub listeners: Vec<ListenerEmissionStats>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenerEmissionStats {
+  pub window_label: String,
+
+  /// Number of emissions that were handed to the window's webview. An
+  /// emission can still be lost after this point (e.g. if the window is
+  /// reloading).
+  pub delivered: u64,
+
+  /// Sequence number of the latest emission sent to the window.
+  pub last_sequence: u64,
+}
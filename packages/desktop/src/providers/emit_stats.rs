@@ -0,0 +1,59 @@
+use std::{
+  sync::atomic::{AtomicU64, Ordering},
+  time::Duration,
+};
+
+use tracing::info;
+
+/// How often emission counts are logged.
+pub const EMIT_STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counts of provider emissions since they were last logged.
+///
+/// Outputs are only delivered to the windows that are subscribed to
+/// their provider, so the counts include how many deliveries a broadcast
+/// to all windows would have taken for comparison.
+#[derive(Debug, Default)]
+pub struct EmitStats {
+  outputs: AtomicU64,
+  deliveries: AtomicU64,
+  broadcast_deliveries: AtomicU64,
+}
+
+impl EmitStats {
+  /// Records an output that was delivered to `deliveries` windows out
+  /// of `window_count` open windows.
+  pub fn record(&self, deliveries: usize, window_count: usize) {
+    self.outputs.fetch_add(1, Ordering::Relaxed);
+
+    self
+      .deliveries
+      .fetch_add(deliveries as u64, Ordering::Relaxed);
+
+    self
+      .broadcast_deliveries
+      .fetch_add(window_count as u64, Ordering::Relaxed);
+  }
+
+  /// Logs the counts and resets them. Nothing is logged if there were no
+  /// outputs.
+  pub fn log_and_reset(&self) {
+    let outputs = self.outputs.swap(0, Ordering::Relaxed);
+    let deliveries = self.deliveries.swap(0, Ordering::Relaxed);
+    let broadcast_deliveries =
+      self.broadcast_deliveries.swap(0, Ordering::Relaxed);
+
+    if outputs == 0 {
+      return;
+    }
+
+    info!(
+      "Provider emissions in the last {}s: {} outputs, {} window deliveries \
+      ({} if broadcast).",
+      EMIT_STATS_INTERVAL.as_secs(),
+      outputs,
+      deliveries,
+      broadcast_deliveries
+    );
+  }
+}
@@ -0,0 +1,193 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::{
+  http_client::HttpClient,
+  retained_size::{json_size, RetainedSize},
+};
+
+/// Locations resolved from place names, persisted to disk so that each
+/// place name only needs to be resolved once.
+#[derive(Debug, Default)]
+pub struct GeocodingCache {
+  cache_path: Option<PathBuf>,
+  entries: Option<HashMap<String, GeocodedLocation>>,
+}
+
+/// A place name resolved to coordinates.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeocodedLocation {
+  /// Place name that was resolved (e.g. `Austin, TX`).
+  pub query: String,
+
+  /// Name of the matched location, including its region and country
+  /// (e.g. `Austin, Texas, United States`).
+  pub display_name: String,
+
+  pub country: Option<String>,
+  pub latitude: f32,
+  pub longitude: f32,
+}
+
+#[derive(Deserialize, Debug)]
+struct GeocodingRes {
+  #[serde(default)]
+  results: Vec<GeocodingResult>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GeocodingResult {
+  name: String,
+  latitude: f32,
+  longitude: f32,
+  country: Option<String>,
+  country_code: Option<String>,
+  admin1: Option<String>,
+}
+
+impl GeocodingResult {
+  /// Whether the result matches a qualifier from the query (e.g. the
+  /// `TX` in `Austin, TX`).
+  fn matches_qualifier(&self, qualifier: &str) -> bool {
+    [&self.country, &self.country_code, &self.admin1]
+      .into_iter()
+      .flatten()
+      .any(|value| value.eq_ignore_ascii_case(qualifier))
+  }
+
+  fn into_location(self, query: &str) -> GeocodedLocation {
+    let display_name =
+      [Some(self.name), self.admin1, self.country.clone()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    GeocodedLocation {
+      query: query.to_string(),
+      display_name,
+      country: self.country,
+      latitude: self.latitude,
+      longitude: self.longitude,
+    }
+  }
+}
+
+impl GeocodingCache {
+  pub fn new(data_dir: Option<PathBuf>) -> Self {
+    Self {
+      cache_path: data_dir.map(|dir| dir.join("geocoding.json")),
+      entries: None,
+    }
+  }
+
+  /// Resolves a place name (e.g. `Austin, TX`) to a location.
+  ///
+  /// Ambiguous names resolve to the top result. Any qualifiers after the
+  /// first comma are used to prefer results in a matching region or
+  /// country.
+  pub async fn resolve(
+    &mut self,
+    query: &str,
+    http_client: &HttpClient,
+  ) -> anyhow::Result<GeocodedLocation> {
+    let key = query.trim().to_lowercase();
+
+    if let Some(location) = self.entries().get(&key) {
+      return Ok(location.clone());
+    }
+
+    let mut parts = query.split(',').map(str::trim);
+    let name = parts.next().unwrap_or_default();
+    let qualifiers = parts.collect::<Vec<_>>();
+
+    let res = http_client
+      .get("https://geocoding-api.open-meteo.com/v1/search")?
+      .query(&[("name", name), ("count", "10")])
+      .send()
+      .await
+      .and_then(|res| res.error_for_status());
+
+    let results = match res {
+      Ok(res) => res.json::<GeocodingRes>().await?.results,
+      Err(err) => bail!(
+        "Failed to resolve location '{}': {}. Check your network connection or use `latitude` and `longitude` instead.",
+        query,
+        err
+      ),
+    };
+
+    let top_index = results
+      .iter()
+      .position(|result| {
+        qualifiers
+          .iter()
+          .all(|qualifier| result.matches_qualifier(qualifier))
+      })
+      .unwrap_or(0);
+
+    let Some(result) = results.into_iter().nth(top_index) else {
+      bail!(
+        "No location found for '{}'. Check the spelling or use `latitude` and `longitude` instead.",
+        query
+      );
+    };
+
+    let location = result.into_location(query);
+    self.entries().insert(key, location.clone());
+
+    if let Err(err) = self.persist() {
+      warn!("Failed to write geocoding cache: {:?}", err);
+    }
+
+    Ok(location)
+  }
+
+  /// Gets cached entries, reading them from disk on first access.
+  fn entries(&mut self) -> &mut HashMap<String, GeocodedLocation> {
+    let cache_path = &self.cache_path;
+
+    self.entries.get_or_insert_with(|| {
+      cache_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+    })
+  }
+
+  fn persist(&self) -> anyhow::Result<()> {
+    let (Some(cache_path), Some(entries)) =
+      (&self.cache_path, &self.entries)
+    else {
+      return Ok(());
+    };
+
+    if let Some(parent) = cache_path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    fs::write(cache_path, serde_json::to_string(entries)?)?;
+
+    Ok(())
+  }
+}
+
+/// Evicted entries are read from disk again on next access, or are
+/// re-fetched if the cache isn't persisted.
+#[async_trait]
+impl RetainedSize for Mutex<GeocodingCache> {
+  async fn retained_size(&self) -> usize {
+    self.lock().await.entries.as_ref().map_or(0, json_size)
+  }
+
+  async fn evict(&self) {
+    self.lock().await.entries = None;
+  }
+}
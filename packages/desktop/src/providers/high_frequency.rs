@@ -0,0 +1,124 @@
+use std::{
+  mem,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{
+  task,
+  time::{self, MissedTickBehavior},
+};
+
+use super::{last_updated::now_millis, retained_size::RetainedSize};
+
+/// Min interval between samples in milliseconds (i.e. 10 Hz).
+const MIN_SAMPLE_INTERVAL: u64 = 100;
+
+/// Max number of samples that are buffered between emissions. Older
+/// samples are dropped if emissions fall behind.
+const MAX_BUFFERED_SAMPLES: usize = 100;
+
+/// Config for sampling more often than the provider emits. Samples are
+/// batched into the provider's regular emissions.
+#[derive(Deserialize, JsonSchema, Debug)]
+pub struct HighFrequencyConfig {
+  /// Milliseconds between samples. Clamped to at least 100ms.
+  #[serde(default = "default_sample_interval")]
+  pub sample_interval: u64,
+}
+
+fn default_sample_interval() -> u64 {
+  250
+}
+
+impl HighFrequencyConfig {
+  pub fn sample_interval(&self) -> Duration {
+    Duration::from_millis(self.sample_interval.max(MIN_SAMPLE_INTERVAL))
+  }
+}
+
+/// Sample along with when it was taken.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedSample<T> {
+  /// Unix timestamp in milliseconds.
+  pub timestamp: u64,
+
+  #[serde(flatten)]
+  pub value: T,
+}
+
+/// Samples taken since the last emission.
+#[derive(Debug)]
+pub struct SampleBuffer<T>(Mutex<Vec<TimedSample<T>>>);
+
+impl<T> Default for SampleBuffer<T> {
+  fn default() -> Self {
+    Self(Mutex::new(Vec::new()))
+  }
+}
+
+/// Samples are bounded by `MAX_BUFFERED_SAMPLES`, so they aren't
+/// evicted.
+#[async_trait]
+impl<T: Send> RetainedSize for SampleBuffer<T> {
+  async fn retained_size(&self) -> usize {
+    self.0.lock().unwrap().capacity() * mem::size_of::<TimedSample<T>>()
+  }
+}
+
+impl<T> SampleBuffer<T> {
+  pub fn push(&self, value: T) {
+    let mut samples = self.0.lock().unwrap();
+
+    if samples.len() >= MAX_BUFFERED_SAMPLES {
+      samples.remove(0);
+    }
+
+    samples.push(TimedSample {
+      timestamp: now_millis(),
+      value,
+    });
+  }
+
+  /// Takes all samples from the buffer, oldest first.
+  pub fn drain(&self) -> Vec<TimedSample<T>> {
+    std::mem::take(&mut *self.0.lock().unwrap())
+  }
+}
+
+/// Takes samples on the configured interval in the background and adds
+/// them to a new buffer.
+///
+/// Sampling stops once the returned buffer is dropped (i.e. when the
+/// provider is dropped).
+pub fn spawn_sampler<T: Send + 'static>(
+  config: &HighFrequencyConfig,
+  mut sample: impl FnMut() -> Option<T> + Send + 'static,
+) -> Arc<SampleBuffer<T>> {
+  let buffer = Arc::new(SampleBuffer::default());
+  let weak_buffer = Arc::downgrade(&buffer);
+  let sample_interval = config.sample_interval();
+
+  task::spawn(async move {
+    let mut interval = time::interval(sample_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+      interval.tick().await;
+
+      let Some(buffer) = weak_buffer.upgrade() else {
+        break;
+      };
+
+      if let Some(value) = sample() {
+        buffer.push(value);
+      }
+    }
+  });
+
+  buffer
+}
@@ -1,11 +1,16 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::impl_interval_config;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "host")]
 pub struct HostProviderConfig {
   pub refresh_interval: u64,
+
+  /// Whether to read the serial number and asset tag of the machine.
+  #[serde(default)]
+  pub include_serial: bool,
 }
 
 impl_interval_config!(HostProviderConfig);
@@ -0,0 +1,273 @@
+use serde::Serialize;
+
+/// Hardware identifiers of the machine. These don't change while the
+/// app is running, so they're only read once on provider start.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MachineInfo {
+  pub manufacturer: Option<String>,
+  pub model: Option<String>,
+
+  /// Only read if `include_serial` is enabled in the provider config.
+  pub serial_number: Option<String>,
+
+  /// Only read if `include_serial` is enabled in the provider config.
+  pub asset_tag: Option<String>,
+
+  /// Names of fields that are null because reading them requires
+  /// elevated privileges.
+  pub requires_elevation: Vec<String>,
+}
+
+impl MachineInfo {
+  pub fn read(include_serial: bool) -> Self {
+    let mut info = read_platform_info(include_serial);
+
+    // Firmware commonly contains placeholder values when the OEM hasn't
+    // filled in a field.
+    for field in [
+      &mut info.manufacturer,
+      &mut info.model,
+      &mut info.serial_number,
+      &mut info.asset_tag,
+    ] {
+      *field = field.take().filter(|value| !is_placeholder(value));
+    }
+
+    if !include_serial {
+      info.serial_number = None;
+      info.asset_tag = None;
+      info
+        .requires_elevation
+        .retain(|field| field != "serialNumber" && field != "assetTag");
+    }
+
+    info
+  }
+}
+
+fn is_placeholder(value: &str) -> bool {
+  let value = value.trim().to_lowercase();
+
+  value.is_empty()
+    || [
+      "default string",
+      "to be filled by o.e.m.",
+      "system serial number",
+      "system product name",
+      "system manufacturer",
+      "not specified",
+      "none",
+      "0",
+    ]
+    .contains(&value.as_str())
+}
+
+/// Reads machine info from the raw SMBIOS tables. No elevation is
+/// needed on Windows.
+#[cfg(target_os = "windows")]
+fn read_platform_info(include_serial: bool) -> MachineInfo {
+  use windows::Win32::System::SystemInformation::{
+    GetSystemFirmwareTable, FIRMWARE_TABLE_PROVIDER,
+  };
+
+  let provider = FIRMWARE_TABLE_PROVIDER(u32::from_be_bytes(*b"RSMB"));
+
+  let size = unsafe { GetSystemFirmwareTable(provider, 0, None) };
+  let mut buffer = vec![0u8; size as usize];
+  let written =
+    unsafe { GetSystemFirmwareTable(provider, 0, Some(&mut buffer)) };
+
+  // The table data is preceded by an 8-byte `RawSMBIOSData` header.
+  let Some(table) = buffer.get(8..written as usize) else {
+    return MachineInfo::default();
+  };
+
+  let mut info = MachineInfo::default();
+
+  for structure in SmbiosStructures::new(table) {
+    match structure.kind {
+      // System information.
+      1 => {
+        info.manufacturer = structure.string_at(0x04);
+        info.model = structure.string_at(0x05);
+
+        if include_serial {
+          info.serial_number = structure.string_at(0x07);
+        }
+      }
+      // System enclosure.
+      3 if include_serial => {
+        info.asset_tag = structure.string_at(0x08);
+      }
+      _ => {}
+    }
+  }
+
+  info
+}
+
+/// A structure within the SMBIOS table.
+#[cfg(target_os = "windows")]
+struct SmbiosStructure<'a> {
+  kind: u8,
+  formatted: &'a [u8],
+  strings: Vec<&'a [u8]>,
+}
+
+#[cfg(target_os = "windows")]
+impl SmbiosStructure<'_> {
+  /// Gets the string referenced by the byte at the given offset of the
+  /// formatted area. String references are 1-based.
+  fn string_at(&self, offset: usize) -> Option<String> {
+    let index = *self.formatted.get(offset)? as usize;
+    let bytes = self.strings.get(index.checked_sub(1)?)?;
+
+    Some(String::from_utf8_lossy(bytes).trim().to_string())
+  }
+}
+
+#[cfg(target_os = "windows")]
+struct SmbiosStructures<'a> {
+  table: &'a [u8],
+  offset: usize,
+}
+
+#[cfg(target_os = "windows")]
+impl<'a> SmbiosStructures<'a> {
+  fn new(table: &'a [u8]) -> Self {
+    Self { table, offset: 0 }
+  }
+}
+
+#[cfg(target_os = "windows")]
+impl<'a> Iterator for SmbiosStructures<'a> {
+  type Item = SmbiosStructure<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let header = self.table.get(self.offset..self.offset + 4)?;
+    let (kind, length) = (header[0], header[1] as usize);
+
+    // Type 127 marks the end of the table.
+    if kind == 127 || length < 4 {
+      return None;
+    }
+
+    let formatted = self.table.get(self.offset..self.offset + length)?;
+
+    // The string area is a list of null-terminated strings, ending with
+    // an additional null byte.
+    let strings_start = self.offset + length;
+    let strings_len = self
+      .table
+      .get(strings_start..)?
+      .windows(2)
+      .position(|pair| pair == [0, 0])?;
+
+    let strings_area =
+      &self.table[strings_start..strings_start + strings_len];
+
+    let strings = strings_area
+      .split(|byte| *byte == 0)
+      .filter(|string| !string.is_empty())
+      .collect();
+
+    self.offset = strings_start + strings_len + 2;
+
+    Some(SmbiosStructure {
+      kind,
+      formatted,
+      strings,
+    })
+  }
+}
+
+/// Reads machine info from DMI via sysfs. The serial number is only
+/// readable by root.
+#[cfg(target_os = "linux")]
+fn read_platform_info(include_serial: bool) -> MachineInfo {
+  use std::{fs, io::ErrorKind};
+
+  let mut requires_elevation = Vec::new();
+
+  let mut read_dmi = |file: &str, field: &str| match fs::read_to_string(
+    format!("/sys/class/dmi/id/{}", file),
+  ) {
+    Ok(value) => Some(value.trim().to_string()),
+    Err(err) => {
+      if err.kind() == ErrorKind::PermissionDenied {
+        requires_elevation.push(field.to_string());
+      }
+
+      None
+    }
+  };
+
+  let manufacturer = read_dmi("sys_vendor", "manufacturer");
+  let model = read_dmi("product_name", "model");
+
+  let (serial_number, asset_tag) = match include_serial {
+    true => (
+      read_dmi("product_serial", "serialNumber"),
+      read_dmi("chassis_asset_tag", "assetTag"),
+    ),
+    false => (None, None),
+  };
+
+  MachineInfo {
+    manufacturer,
+    model,
+    serial_number,
+    asset_tag,
+    requires_elevation,
+  }
+}
+
+/// Reads machine info from the IORegistry. Macs don't have an asset
+/// tag.
+#[cfg(target_os = "macos")]
+fn read_platform_info(include_serial: bool) -> MachineInfo {
+  use std::process::Command;
+
+  let Ok(output) = Command::new("ioreg")
+    .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+    .output()
+  else {
+    return MachineInfo::default();
+  };
+
+  let output = String::from_utf8_lossy(&output.stdout);
+
+  // Properties are formatted as `"key" = "value"` or `"key" = <"value">`.
+  let property = |key: &str| {
+    output.lines().find_map(|line| {
+      let (line_key, value) = line.trim().split_once(" = ")?;
+
+      (line_key.trim_matches('"') == key).then(|| {
+        value
+          .trim_matches(|c| c == '<' || c == '>' || c == '"')
+          .trim_end_matches('\0')
+          .to_string()
+      })
+    })
+  };
+
+  MachineInfo {
+    manufacturer: property("manufacturer"),
+    model: property("model"),
+    serial_number: include_serial
+      .then(|| property("IOPlatformSerialNumber"))
+      .flatten(),
+    asset_tag: None,
+    requires_elevation: Vec::new(),
+  }
+}
+
+#[cfg(not(any(
+  target_os = "windows",
+  target_os = "linux",
+  target_os = "macos"
+)))]
+fn read_platform_info(_include_serial: bool) -> MachineInfo {
+  MachineInfo::default()
+}
@@ -1,7 +1,9 @@
 mod config;
+mod machine_info;
 mod provider;
 mod variables;
 
 pub use config::*;
+pub use machine_info::*;
 pub use provider::*;
 pub use variables::*;
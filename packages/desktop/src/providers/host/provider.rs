@@ -2,28 +2,32 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use sysinfo::System;
-use tokio::{sync::Mutex, task::AbortHandle};
+use tokio::task::AbortHandle;
 
-use super::{HostProviderConfig, HostVariables};
-use crate::providers::{
-  provider::IntervalProvider, variables::ProviderVariables,
+use super::{HostProviderConfig, HostVariables, MachineInfo};
+use crate::{
+  display_session::display_session,
+  providers::{provider::IntervalProvider, variables::ProviderVariables},
 };
 
 pub struct HostProvider {
   pub config: Arc<HostProviderConfig>,
   abort_handle: Option<AbortHandle>,
-  sysinfo: Arc<Mutex<System>>,
+  state: Arc<HostState>,
+}
+
+pub struct HostState {
+  machine_info: MachineInfo,
 }
 
 impl HostProvider {
-  pub fn new(
-    config: HostProviderConfig,
-    sysinfo: Arc<Mutex<System>>,
-  ) -> HostProvider {
+  pub fn new(config: HostProviderConfig) -> HostProvider {
+    let machine_info = MachineInfo::read(config.include_serial);
+
     HostProvider {
       config: Arc::new(config),
       abort_handle: None,
-      sysinfo,
+      state: Arc::new(HostState { machine_info }),
     }
   }
 }
@@ -31,14 +35,14 @@ impl HostProvider {
 #[async_trait]
 impl IntervalProvider for HostProvider {
   type Config = HostProviderConfig;
-  type State = Mutex<System>;
+  type State = HostState;
 
   fn config(&self) -> Arc<HostProviderConfig> {
     self.config.clone()
   }
 
-  fn state(&self) -> Arc<Mutex<System>> {
-    self.sysinfo.clone()
+  fn state(&self) -> Arc<HostState> {
+    self.state.clone()
   }
 
   fn abort_handle(&self) -> &Option<AbortHandle> {
@@ -51,7 +55,7 @@ impl IntervalProvider for HostProvider {
 
   async fn get_refreshed_variables(
     _: &HostProviderConfig,
-    __: &Mutex<System>,
+    state: &HostState,
   ) -> anyhow::Result<ProviderVariables> {
     Ok(ProviderVariables::Host(HostVariables {
       hostname: System::host_name(),
@@ -60,6 +64,8 @@ impl IntervalProvider for HostProvider {
       friendly_os_version: System::long_os_version(),
       boot_time: System::boot_time() * 1000,
       uptime: System::uptime() * 1000,
+      machine: state.machine_info.clone(),
+      display_session: display_session().clone(),
     }))
   }
 }
@@ -1,5 +1,8 @@
 use serde::Serialize;
 
+use super::MachineInfo;
+use crate::display_session::DisplaySession;
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct HostVariables {
@@ -9,4 +12,6 @@ pub struct HostVariables {
   pub friendly_os_version: Option<String>,
   pub boot_time: u64,
   pub uptime: u64,
+  pub machine: MachineInfo,
+  pub display_session: DisplaySession,
 }
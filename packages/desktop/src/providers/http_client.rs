@@ -0,0 +1,321 @@
+use std::{
+  error::Error as StdError,
+  fmt,
+  future::Future,
+  sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
+
+use anyhow::Context;
+use reqwest::{Client, IntoUrl, Method, RequestBuilder};
+use serde::Serialize;
+use tokio::{
+  sync::Semaphore,
+  time::{self, error::Elapsed},
+};
+
+use crate::user_config::MeteredBehavior;
+
+/// Error returned when a request is blocked by offline mode.
+#[derive(Debug, Clone, Copy)]
+pub struct OfflineModeError;
+
+impl fmt::Display for OfflineModeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Network requests are disabled while offline mode is on.")
+  }
+}
+
+impl std::error::Error for OfflineModeError {}
+
+/// Timeout for establishing a connection. Kept short, so that lookups
+/// fail fast when connections are blocked (e.g. by a VPN kill switch).
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Timeout for a request as a whole, including reading the response.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Timeout for a run of a network-bound provider, which can make several
+/// requests.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Limits concurrent runs of network-bound providers, so that lookups
+/// piling up while the network is unreachable are bounded.
+static LOOKUP_LIMIT: Semaphore = Semaphore::const_new(4);
+
+/// Kind of network failure, so that widgets can tell a blocked
+/// connection apart from a failed DNS lookup.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkErrorKind {
+  /// The DNS lookup of the host failed.
+  Dns,
+  /// No connection was established within the connect timeout.
+  ConnectTimeout,
+  /// The connection was refused or otherwise failed.
+  Connect,
+  /// The connection was established, but the request or the lookup as
+  /// a whole timed out.
+  Timeout,
+}
+
+impl NetworkErrorKind {
+  /// Gets the kind of network failure that caused the error, if any.
+  pub fn from_error(err: &anyhow::Error) -> Option<Self> {
+    if err.downcast_ref::<Elapsed>().is_some() {
+      return Some(Self::Timeout);
+    }
+
+    let err = err.downcast_ref::<reqwest::Error>()?;
+
+    if err.is_connect() {
+      // The resolver's error isn't exposed, so DNS failures are told
+      // apart by the message of the underlying connector error.
+      let is_dns = error_chain(err)
+        .any(|source| source.to_string().starts_with("dns error"));
+
+      return Some(match (is_dns, err.is_timeout()) {
+        (true, _) => Self::Dns,
+        (false, true) => Self::ConnectTimeout,
+        (false, false) => Self::Connect,
+      });
+    }
+
+    err.is_timeout().then_some(Self::Timeout)
+  }
+}
+
+fn error_chain<'a>(
+  err: &'a (dyn StdError + 'static),
+) -> impl Iterator<Item = &'a (dyn StdError + 'static)> {
+  std::iter::successors(Some(err), |err| err.source())
+}
+
+/// HTTP client shared between providers.
+///
+/// All outbound requests from providers go through this client, so that
+/// offline mode is enforced in one place. Requests are refused before
+/// they're built, so no connections are attempted while offline.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+  client: Client,
+  offline_mode: Arc<AtomicBool>,
+  metered: Arc<MeteredState>,
+}
+
+/// Whether the active connection is metered, and how providers that use
+/// the client should refresh while it is.
+#[derive(Debug, Default)]
+struct MeteredState {
+  is_metered: AtomicBool,
+  behavior: AtomicU8,
+  interval_multiplier: AtomicU64,
+}
+
+impl HttpClient {
+  pub fn new(offline_mode: bool) -> Self {
+    Self {
+      client: Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client."),
+      offline_mode: Arc::new(AtomicBool::new(offline_mode)),
+      metered: Arc::new(MeteredState::default()),
+    }
+  }
+
+  pub fn is_offline(&self) -> bool {
+    self.offline_mode.load(Ordering::Relaxed)
+  }
+
+  /// Enables or disables offline mode for all clones of the client.
+  ///
+  /// Returns the previous value.
+  pub fn set_offline_mode(&self, enabled: bool) -> bool {
+    self.offline_mode.swap(enabled, Ordering::Relaxed)
+  }
+
+  /// Sets whether the active connection is metered for all clones of
+  /// the client.
+  ///
+  /// Returns the previous value.
+  pub fn set_metered(&self, is_metered: bool) -> bool {
+    self.metered.is_metered.swap(is_metered, Ordering::Relaxed)
+  }
+
+  /// Sets how providers refresh while the connection is metered.
+  pub fn set_metered_behavior(
+    &self,
+    behavior: MeteredBehavior,
+    interval_multiplier: u64,
+  ) {
+    self
+      .metered
+      .behavior
+      .store(behavior as u8, Ordering::Relaxed);
+
+    self
+      .metered
+      .interval_multiplier
+      .store(interval_multiplier.max(1), Ordering::Relaxed);
+  }
+
+  pub fn metered_behavior(&self) -> MeteredBehavior {
+    match self.metered.behavior.load(Ordering::Relaxed) {
+      value if value == MeteredBehavior::Throttle as u8 => {
+        MeteredBehavior::Throttle
+      }
+      value if value == MeteredBehavior::Pause as u8 => {
+        MeteredBehavior::Pause
+      }
+      _ => MeteredBehavior::Ignore,
+    }
+  }
+
+  /// Factor that refresh intervals of providers using the client are
+  /// multiplied by. `None` if refreshes are paused.
+  pub fn interval_multiplier(&self) -> Option<u64> {
+    if !self.metered.is_metered.load(Ordering::Relaxed) {
+      return Some(1);
+    }
+
+    match self.metered_behavior() {
+      MeteredBehavior::Ignore => Some(1),
+      MeteredBehavior::Throttle => {
+        Some(self.metered.interval_multiplier.load(Ordering::Relaxed))
+      }
+      MeteredBehavior::Pause => None,
+    }
+  }
+
+  /// Returns an error if offline mode is on.
+  pub fn ensure_online(&self) -> Result<(), OfflineModeError> {
+    match self.is_offline() {
+      true => Err(OfflineModeError),
+      false => Ok(()),
+    }
+  }
+
+  /// Runs a lookup of a network-bound provider.
+  ///
+  /// Concurrent lookups across providers are limited, and each is timed
+  /// out after `LOOKUP_TIMEOUT` (including time spent waiting for other
+  /// lookups), so that a hung lookup can't hold up the provider's
+  /// refreshes and stop requests indefinitely.
+  pub async fn lookup<T>(
+    &self,
+    lookup: impl Future<Output = anyhow::Result<T>>,
+  ) -> anyhow::Result<T> {
+    let bounded_lookup = async {
+      let _permit = LOOKUP_LIMIT.acquire().await?;
+      lookup.await
+    };
+
+    time::timeout(LOOKUP_TIMEOUT, bounded_lookup)
+      .await
+      .with_context(|| {
+        format!("Lookup timed out after {}s.", LOOKUP_TIMEOUT.as_secs())
+      })?
+  }
+
+  pub fn get(
+    &self,
+    url: impl IntoUrl,
+  ) -> Result<RequestBuilder, OfflineModeError> {
+    self.ensure_online()?;
+    Ok(self.client.get(url))
+  }
+
+  pub fn request(
+    &self,
+    method: Method,
+    url: impl IntoUrl,
+  ) -> Result<RequestBuilder, OfflineModeError> {
+    self.ensure_online()?;
+    Ok(self.client.request(method, url))
+  }
+}
+
+/// Counts the scheduled refreshes of a provider, so that only every n-th
+/// one runs while the connection is metered.
+#[derive(Debug, Default)]
+pub struct MeteredTicks {
+  /// Number of refreshes skipped since the last run. `None` until the
+  /// first run, which always happens.
+  skipped: Option<u64>,
+}
+
+impl MeteredTicks {
+  /// Whether the scheduled refresh should run.
+  pub fn should_run(&mut self, http_client: &HttpClient) -> bool {
+    let Some(skipped) = self.skipped else {
+      self.skipped = Some(0);
+      return true;
+    };
+
+    let should_run = http_client
+      .interval_multiplier()
+      .is_some_and(|multiplier| skipped + 1 >= multiplier);
+
+    self.skipped = Some(if should_run { 0 } else { skipped + 1 });
+    should_run
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::providers::{
+    provider_ref::VariablesResult, variables::ProviderVariables,
+  };
+
+  #[test]
+  fn offline_mode_blocks_requests() {
+    let client = HttpClient::new(true);
+
+    assert!(client.get("https://example.com").is_err());
+    assert!(client.request(Method::POST, "https://example.com").is_err());
+
+    assert!(client.set_offline_mode(false));
+    assert!(client.get("https://example.com").is_ok());
+    assert!(client.ensure_online().is_ok());
+  }
+
+  #[test]
+  fn offline_mode_is_shared_between_clones() {
+    let client = HttpClient::new(false);
+    let clone = client.clone();
+
+    assert!(!client.set_offline_mode(true));
+    assert!(clone.is_offline());
+    assert!(clone.get("https://example.com").is_err());
+  }
+
+  #[test]
+  fn offline_errors_are_reported_as_offline_mode() {
+    let err = anyhow::Error::from(OfflineModeError)
+      .context("Failed to fetch weather.");
+
+    assert!(matches!(
+      VariablesResult::from(Err::<ProviderVariables, _>(err)),
+      VariablesResult::OfflineMode(_)
+    ));
+
+    assert!(matches!(
+      VariablesResult::from(Err::<ProviderVariables, _>(anyhow::anyhow!(
+        "Other error."
+      ))),
+      VariablesResult::Error(_)
+    ));
+  }
+
+  #[test]
+  fn offline_errors_are_not_network_errors() {
+    let err = anyhow::Error::from(OfflineModeError);
+    assert_eq!(NetworkErrorKind::from_error(&err), None);
+  }
+}
@@ -1,8 +1,9 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::impl_interval_config;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "ip")]
 pub struct IpProviderConfig {
   pub refresh_interval: u64,
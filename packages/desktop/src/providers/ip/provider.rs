@@ -2,26 +2,29 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use async_trait::async_trait;
-use reqwest::Client;
 use tokio::task::AbortHandle;
 
 use super::{ipinfo_res::IpinfoRes, IpProviderConfig, IpVariables};
 use crate::providers::{
-  provider::IntervalProvider, variables::ProviderVariables,
+  http_client::HttpClient, provider::IntervalProvider,
+  variables::ProviderVariables,
 };
 
 pub struct IpProvider {
   pub config: Arc<IpProviderConfig>,
   abort_handle: Option<AbortHandle>,
-  http_client: Arc<Client>,
+  http_client: Arc<HttpClient>,
 }
 
 impl IpProvider {
-  pub fn new(config: IpProviderConfig) -> IpProvider {
+  pub fn new(
+    config: IpProviderConfig,
+    http_client: HttpClient,
+  ) -> IpProvider {
     IpProvider {
       config: Arc::new(config),
       abort_handle: None,
-      http_client: Arc::new(Client::new()),
+      http_client: Arc::new(http_client),
     }
   }
 }
@@ -29,13 +32,17 @@ impl IpProvider {
 #[async_trait]
 impl IntervalProvider for IpProvider {
   type Config = IpProviderConfig;
-  type State = Client;
+  type State = HttpClient;
+
+  fn http_client(&self) -> Option<HttpClient> {
+    Some((*self.http_client).clone())
+  }
 
   fn config(&self) -> Arc<IpProviderConfig> {
     self.config.clone()
   }
 
-  fn state(&self) -> Arc<Client> {
+  fn state(&self) -> Arc<HttpClient> {
     self.http_client.clone()
   }
 
@@ -49,10 +56,10 @@ impl IntervalProvider for IpProvider {
 
   async fn get_refreshed_variables(
     _: &IpProviderConfig,
-    http_client: &Client,
+    http_client: &HttpClient,
   ) -> anyhow::Result<ProviderVariables> {
     let res = http_client
-      .get("https://ipinfo.io/json")
+      .get("https://ipinfo.io/json")?
       .send()
       .await?
       .json::<IpinfoRes>()
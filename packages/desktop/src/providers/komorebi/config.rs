@@ -1,5 +1,19 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "komorebi")]
-pub struct KomorebiProviderConfig {}
+pub struct KomorebiProviderConfig {
+  /// Map of workspace names or indices to display labels (e.g. to show
+  /// an icon for workspace `1`). Names take precedence over indices, and
+  /// indices are 0-based within each monitor.
+  #[serde(default)]
+  pub workspace_labels: HashMap<String, String>,
+
+  /// Whether to omit workspaces without any windows. The focused
+  /// workspace is always included.
+  #[serde(default)]
+  pub hide_empty: bool,
+}
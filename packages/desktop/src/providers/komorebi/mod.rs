@@ -1,6 +1,9 @@
 mod config;
 mod provider;
+mod resync;
+mod state;
 mod variables;
+mod workspace_labels;
 
 pub use config::*;
 pub use provider::*;
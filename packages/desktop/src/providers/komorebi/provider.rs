@@ -5,14 +5,24 @@ use std::{
 };
 
 use async_trait::async_trait;
-use komorebi_client::{Container, Monitor, Window, Workspace};
+use serde::Deserialize;
 use tokio::{
   sync::mpsc::Sender,
   task::{self, AbortHandle},
 };
-use tracing::debug;
+use tracing::{debug, warn};
+use windows::Win32::{
+  Foundation::HWND,
+  UI::WindowsAndMessaging::{GetForegroundWindow, IsIconic},
+};
 
 use super::{
+  resync::{query_state, ResyncDetector},
+  state::{
+    komorebi_version, ContainerState, KomorebiState, MonitorState,
+    WindowState, WorkspaceState,
+  },
+  workspace_labels::WorkspaceLabels,
   KomorebiContainer, KomorebiLayout, KomorebiLayoutFlip, KomorebiMonitor,
   KomorebiProviderConfig, KomorebiWindow, KomorebiWorkspace,
 };
@@ -25,6 +35,22 @@ use crate::providers::{
 
 const SOCKET_NAME: &str = "zebar.sock";
 
+/// Subset of a Komorebi notification that is needed for emitting the
+/// state.
+///
+/// Komorebi sends a notification for every event (including cycling
+/// through a stack), but notifications for events that aren't known to
+/// `komorebi_client` fail to deserialize in full. The event is therefore
+/// kept as raw JSON, and the state is parsed leniently via
+/// `KomorebiState`.
+#[derive(Deserialize, Debug)]
+struct KomorebiNotification {
+  #[serde(default)]
+  event: serde_json::Value,
+  #[serde(default)]
+  state: serde_json::Value,
+}
+
 pub struct KomorebiProvider {
   pub config: Arc<KomorebiProviderConfig>,
   abort_handle: Option<AbortHandle>,
@@ -39,84 +65,147 @@ impl KomorebiProvider {
   }
 
   fn transform_response(
-    state: komorebi_client::State,
+    state: KomorebiState,
+    config: &KomorebiProviderConfig,
+    labels: &mut WorkspaceLabels,
+    resync: bool,
   ) -> KomorebiVariables {
+    labels.warn_unknown_keys(
+      state.monitors.elements.iter().flat_map(|monitor| {
+        monitor.workspaces.elements.iter().enumerate()
+      }),
+    );
+
     let all_monitors = state
       .monitors
-      .elements()
-      .into_iter()
-      .map(Self::transform_monitor)
+      .elements
+      .iter()
+      .map(|monitor| Self::transform_monitor(monitor, config, labels))
       .collect();
 
     KomorebiVariables {
       all_monitors,
-      focused_monitor_index: state.monitors.focused_idx(),
+      focused_monitor_index: state.monitors.focused,
+      resync,
+      // Set per window when the output is emitted.
+      self_monitor_focused: None,
     }
   }
 
-  fn transform_monitor(monitor: &Monitor) -> KomorebiMonitor {
+  fn transform_monitor(
+    monitor: &MonitorState,
+    config: &KomorebiProviderConfig,
+    labels: &WorkspaceLabels,
+  ) -> KomorebiMonitor {
+    let focused_index = monitor.workspaces.focused;
+
+    let workspaces = monitor
+      .workspaces
+      .elements
+      .iter()
+      .enumerate()
+      .filter(|(index, workspace)| {
+        !config.hide_empty
+          || *index == focused_index
+          || !workspace.is_empty()
+      })
+      .map(|(index, workspace)| {
+        Self::transform_workspace(workspace, index, labels)
+      })
+      .collect::<Vec<_>>();
+
     KomorebiMonitor {
-      id: monitor.id(),
-      name: monitor.name().to_string(),
-      device_id: monitor.device_id().clone(),
-      focused_workspace_index: monitor.focused_workspace_idx(),
-      size: *monitor.size(),
-      work_area_size: *monitor.work_area_size(),
-      work_area_offset: monitor.work_area_offset(),
-      workspaces: monitor
-        .workspaces()
-        .into_iter()
-        .map(Self::transform_workspace)
-        .collect(),
+      id: monitor.id,
+      name: monitor.name.clone(),
+      device_id: monitor.device_id.clone().unwrap_or_default(),
+      // Hidden workspaces shift the position of the focused workspace.
+      focused_workspace_index: workspaces
+        .iter()
+        .position(|workspace| workspace.index == focused_index)
+        .unwrap_or(0),
+      size: monitor.size,
+      work_area_size: monitor.work_area_size,
+      work_area_offset: monitor.work_area_offset,
+      workspaces,
     }
   }
 
-  fn transform_workspace(workspace: &Workspace) -> KomorebiWorkspace {
+  fn transform_workspace(
+    workspace: &WorkspaceState,
+    index: usize,
+    labels: &WorkspaceLabels,
+  ) -> KomorebiWorkspace {
+    let foreground_hwnd = unsafe { GetForegroundWindow() }.0 as u64;
+
+    let floating_windows = workspace
+      .floating_windows
+      .iter()
+      .map(|window| Self::transform_window(window, true, None))
+      .collect::<Vec<_>>();
+
     KomorebiWorkspace {
-      container_padding: workspace.container_padding(),
-      floating_windows: workspace
-        .floating_windows()
-        .into_iter()
-        .map(Self::transform_window)
-        .collect(),
-      focused_container_index: workspace.focused_container_idx(),
-      latest_layout: (*workspace.latest_layout()).clone(),
-      layout: KomorebiLayout::from((*workspace.layout()).clone()),
-      layout_flip: workspace.layout_flip().map(KomorebiLayoutFlip::from),
-      name: workspace.name().clone(),
+      index,
+      display_label: labels.resolve(workspace.name.as_deref(), index),
+      container_padding: workspace.container_padding,
+      focused_window_is_floating: floating_windows
+        .iter()
+        .any(|window| window.hwnd == foreground_hwnd),
+      floating_windows,
+      focused_container_index: workspace.containers.focused,
+      latest_layout: workspace.latest_layout.clone(),
+      layout: KomorebiLayout::from_komorebi(&workspace.layout),
+      layout_flip: workspace.layout_flip.map(KomorebiLayoutFlip::from),
+      name: workspace.name.clone(),
       maximized_window: workspace
-        .maximized_window()
-        .map(|w| Self::transform_window(&w)),
+        .maximized_window
+        .as_ref()
+        .map(|w| Self::transform_window(w, false, None)),
       monocle_container: workspace
-        .monocle_container()
+        .monocle_container
         .as_ref()
-        .map(|c| Self::transform_container(&c)),
+        .map(Self::transform_container),
       tiling_containers: workspace
-        .containers()
-        .into_iter()
+        .containers
+        .elements
+        .iter()
         .map(Self::transform_container)
         .collect(),
-      workspace_padding: workspace.workspace_padding(),
+      workspace_padding: workspace.workspace_padding,
     }
   }
 
-  fn transform_container(container: &Container) -> KomorebiContainer {
+  fn transform_container(container: &ContainerState) -> KomorebiContainer {
+    let windows = &container.windows.elements;
+
     KomorebiContainer {
-      id: container.id().to_string(),
-      windows: container
-        .windows()
+      id: container.id.clone(),
+      windows: windows
         .iter()
-        .map(Self::transform_window)
+        .enumerate()
+        .map(|(index, window)| {
+          Self::transform_window(window, false, Some(index))
+        })
         .collect(),
+      focused_window_index: container.windows.focused,
+      is_stacked: windows.len() > 1,
     }
   }
 
-  fn transform_window(window: &Window) -> KomorebiWindow {
+  fn transform_window(
+    window: &WindowState,
+    is_floating: bool,
+    stack_index: Option<usize>,
+  ) -> KomorebiWindow {
+    let hwnd = window.hwnd;
+
     KomorebiWindow {
-      class: window.class().ok(),
-      exe: window.exe().ok(),
-      hwnd: window.hwnd().0 as u64,
-      title: window.title().ok(),
+      class: window.class.clone(),
+      exe: window.exe.clone(),
+      hwnd: hwnd as u64,
+      title: window.title.clone(),
+      is_floating,
+      is_minimized: unsafe { IsIconic(HWND(hwnd as _)) }.as_bool(),
+      stack_index,
     }
   }
 }
@@ -133,9 +222,19 @@ impl Provider for KomorebiProvider {
     config_hash: &str,
     emit_output_tx: Sender<ProviderOutput>,
   ) {
+    let config = self.config.clone();
     let config_hash = config_hash.to_string();
 
     let task_handle = task::spawn(async move {
+      let mut labels =
+        WorkspaceLabels::new(config.workspace_labels.clone());
+
+      let mut resync_detector = ResyncDetector::default();
+
+      // Unexpected shapes are only warned about once, since they're
+      // likely to be seen in every notification.
+      let mut warned_drift = false;
+
       let socket = komorebi_client::subscribe(SOCKET_NAME).unwrap();
       debug!("Connected to Komorebi socket.");
 
@@ -147,19 +246,55 @@ impl Provider for KomorebiProvider {
             let reader = BufReader::new(data.try_clone().unwrap());
 
             for line in reader.lines().flatten() {
-              if let Ok(notification) = serde_json::from_str::<
-                komorebi_client::Notification,
-              >(&line)
+              if let Ok(notification) =
+                serde_json::from_str::<KomorebiNotification>(&line)
               {
+                let (state, drift) =
+                  KomorebiState::parse(notification.state);
+
+                let resync = resync_detector
+                  .needs_resync(&notification.event, &state);
+
+                // After a config reload, the state from the notification
+                // can be stale, so the complete state is fetched instead.
+                let (state, drift) = match resync {
+                  true => query_state()
+                    .map(KomorebiState::parse)
+                    .unwrap_or((state, drift)),
+                  false => (state, drift),
+                };
+
+                // Whatever could be parsed is still emitted.
+                if !drift.is_empty() && !warned_drift {
+                  warned_drift = true;
+
+                  warn!(
+                    "Komorebi state has an unexpected shape, so some \
+                    fields are left out (Komorebi version: {}): {}",
+                    komorebi_version().as_deref().unwrap_or("unknown"),
+                    drift.join("; ")
+                  );
+                }
+
                 // Transform and emit the incoming Komorebi state.
                 _ = emit_output_tx
                   .send(ProviderOutput {
                     config_hash: config_hash.clone(),
                     variables: VariablesResult::Data(
                       ProviderVariables::Komorebi(
-                        Self::transform_response(notification.state),
+                        Self::transform_response(
+                          state,
+                          &config,
+                          &mut labels,
+                          resync,
+                        ),
                       ),
                     ),
+                    computed: None,
+                    last_updated: None,
+                    last_updated_relative: None,
+                    run_duration: None,
+                    type_name: None,
                   })
                   .await;
               }
@@ -170,6 +305,11 @@ impl Provider for KomorebiProvider {
               .send(ProviderOutput {
                 config_hash: config_hash.to_string(),
                 variables: VariablesResult::Error(error.to_string()),
+                computed: None,
+                last_updated: None,
+                last_updated_relative: None,
+                run_duration: None,
+                type_name: None,
               })
               .await;
           }
@@ -0,0 +1,194 @@
+use komorebi_client::SocketMessage;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use super::state::KomorebiState;
+
+/// Socket messages that cause Komorebi to reload its config, after which
+/// workspace names and counts may have changed.
+const RELOAD_MESSAGES: &[&str] = &[
+  "ReloadConfiguration",
+  "ReloadStaticConfiguration",
+  "ReplaceConfiguration",
+  "CompleteConfiguration",
+];
+
+/// Detects when Komorebi's config was reloaded, so that a complete
+/// snapshot of the state can be emitted instead of the state from the
+/// notification, which may be inconsistent mid-reload.
+///
+/// Besides reload events, a change to the monitors or their workspace
+/// names (e.g. from the config file being edited) also counts as a
+/// reload.
+#[derive(Default)]
+pub struct ResyncDetector {
+  /// IDs of monitors and the names of their workspaces from the previous
+  /// notification.
+  last_layout: Option<Vec<(isize, Vec<Option<String>>)>>,
+}
+
+impl ResyncDetector {
+  /// Whether the given notification requires a resync. Should be called
+  /// for every notification.
+  pub fn needs_resync(
+    &mut self,
+    event: &Value,
+    state: &KomorebiState,
+  ) -> bool {
+    let layout = state
+      .monitors
+      .elements
+      .iter()
+      .map(|monitor| {
+        let workspace_names = monitor
+          .workspaces
+          .elements
+          .iter()
+          .map(|workspace| workspace.name.clone())
+          .collect();
+
+        (monitor.id, workspace_names)
+      })
+      .collect::<Vec<_>>();
+
+    let layout_changed = self
+      .last_layout
+      .as_ref()
+      .is_some_and(|last_layout| *last_layout != layout);
+
+    self.last_layout = Some(layout);
+
+    Self::is_reload_event(event) || layout_changed
+  }
+
+  /// Whether the event is a socket message that reloads the config.
+  ///
+  /// Events are of the form `{ type: "Socket", content: { type: "..." }
+  /// }`.
+  fn is_reload_event(event: &Value) -> bool {
+    event["type"] == "Socket"
+      && event["content"]["type"]
+        .as_str()
+        .is_some_and(|message| RELOAD_MESSAGES.contains(&message))
+  }
+}
+
+/// Queries Komorebi for its complete current state. The state is
+/// returned as JSON, so that it can be parsed leniently.
+pub fn query_state() -> Option<Value> {
+  let res = komorebi_client::send_query(&SocketMessage::State)
+    .map_err(anyhow::Error::from)
+    .and_then(|state| Ok(serde_json::from_str::<Value>(&state)?));
+
+  match res {
+    Ok(state) => {
+      debug!("Fetched full Komorebi state for resync.");
+      Some(state)
+    }
+    Err(err) => {
+      warn!("Failed to query Komorebi state: {:?}", err);
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn state(layout: &[(isize, &[Option<&str>])]) -> KomorebiState {
+    let monitors = layout
+      .iter()
+      .map(|(id, names)| {
+        let workspaces = names
+          .iter()
+          .map(|name| json!({ "name": name }))
+          .collect::<Vec<_>>();
+
+        json!({ "id": id, "workspaces": { "elements": workspaces } })
+      })
+      .collect::<Vec<_>>();
+
+    KomorebiState::parse(json!({ "monitors": { "elements": monitors } })).0
+  }
+
+  fn socket_event(message: &str) -> Value {
+    json!({ "type": "Socket", "content": { "type": message } })
+  }
+
+  #[test]
+  fn reload_messages_need_resync() {
+    let mut detector = ResyncDetector::default();
+    let state = state(&[(1, &[Some("a")])]);
+
+    for message in RELOAD_MESSAGES {
+      assert!(detector.needs_resync(&socket_event(message), &state));
+    }
+  }
+
+  #[test]
+  fn other_events_dont_need_resync() {
+    let mut detector = ResyncDetector::default();
+    let state = state(&[(1, &[Some("a")])]);
+
+    assert!(!detector
+      .needs_resync(&socket_event("FocusWorkspaceNumber"), &state));
+    assert!(!detector.needs_resync(
+      &json!({ "type": "FocusChange", "content": ["Show", {}] }),
+      &state
+    ));
+    assert!(!detector
+      .needs_resync(&json!({ "type": "ReloadConfiguration" }), &state));
+  }
+
+  #[test]
+  fn first_notification_isnt_a_layout_change() {
+    let mut detector = ResyncDetector::default();
+    let state = state(&[(1, &[Some("a"), Some("b")])]);
+
+    assert!(!detector.needs_resync(&json!({}), &state));
+    assert!(!detector.needs_resync(&json!({}), &state));
+  }
+
+  #[test]
+  fn renamed_workspace_needs_resync() {
+    let mut detector = ResyncDetector::default();
+    detector.needs_resync(&json!({}), &state(&[(1, &[Some("a")])]));
+
+    assert!(
+      detector.needs_resync(&json!({}), &state(&[(1, &[Some("b")])]))
+    );
+    assert!(
+      !detector.needs_resync(&json!({}), &state(&[(1, &[Some("b")])]))
+    );
+  }
+
+  #[test]
+  fn added_workspace_or_monitor_needs_resync() {
+    let mut detector = ResyncDetector::default();
+    detector.needs_resync(&json!({}), &state(&[(1, &[Some("a")])]));
+
+    assert!(detector
+      .needs_resync(&json!({}), &state(&[(1, &[Some("a"), None])])));
+    assert!(detector.needs_resync(
+      &json!({}),
+      &state(&[(1, &[Some("a"), None]), (2, &[None])])
+    ));
+  }
+
+  #[test]
+  fn swapped_monitor_ids_need_resync() {
+    let mut detector = ResyncDetector::default();
+    detector.needs_resync(
+      &json!({}),
+      &state(&[(1, &[Some("a")]), (2, &[Some("a")])]),
+    );
+
+    assert!(detector.needs_resync(
+      &json!({}),
+      &state(&[(2, &[Some("a")]), (1, &[Some("a")])])
+    ));
+  }
+}
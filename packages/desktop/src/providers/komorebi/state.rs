@@ -0,0 +1,397 @@
+use std::{any::type_name, cell::RefCell, process::Command};
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::Value;
+
+use super::KomorebiRect;
+
+thread_local! {
+  /// Unexpected shapes seen while parsing a state. Parsing is
+  /// synchronous, so they're collected per thread.
+  static DRIFT: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+/// Komorebi's state, parsed leniently so that schema changes between
+/// Komorebi releases don't break the provider.
+///
+/// Unknown fields are ignored and missing fields are defaulted. Fields
+/// with an unexpected shape are defaulted, and list elements with an
+/// unexpected shape are skipped.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct KomorebiState {
+  #[serde(deserialize_with = "lenient")]
+  pub monitors: Ring<MonitorState>,
+}
+
+impl KomorebiState {
+  /// Parses the state from its JSON. Also returns a description of each
+  /// unexpected shape that was seen.
+  pub fn parse(value: Value) -> (Self, Vec<String>) {
+    DRIFT.with(|drift| drift.borrow_mut().clear());
+    let state = parse_or_record(value).unwrap_or_default();
+    let drift = DRIFT.with(|drift| drift.take());
+
+    (state, drift)
+  }
+}
+
+/// List with a focused element, e.g. `{ elements: [...], focused: 0 }`.
+#[derive(Deserialize, Debug)]
+#[serde(default, bound(deserialize = "T: DeserializeOwned"))]
+pub struct Ring<T> {
+  #[serde(deserialize_with = "lenient_elements")]
+  pub elements: Vec<T>,
+  #[serde(deserialize_with = "lenient")]
+  pub focused: usize,
+}
+
+impl<T> Default for Ring<T> {
+  fn default() -> Self {
+    Self {
+      elements: Vec::new(),
+      focused: 0,
+    }
+  }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct MonitorState {
+  #[serde(deserialize_with = "lenient")]
+  pub id: isize,
+  #[serde(deserialize_with = "lenient")]
+  pub name: String,
+  /// Optional in older releases.
+  #[serde(deserialize_with = "lenient")]
+  pub device_id: Option<String>,
+  #[serde(deserialize_with = "lenient")]
+  pub size: KomorebiRect,
+  #[serde(deserialize_with = "lenient")]
+  pub work_area_size: KomorebiRect,
+  #[serde(deserialize_with = "lenient")]
+  pub work_area_offset: Option<KomorebiRect>,
+  #[serde(deserialize_with = "lenient")]
+  pub workspaces: Ring<WorkspaceState>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct WorkspaceState {
+  #[serde(deserialize_with = "lenient")]
+  pub name: Option<String>,
+  #[serde(deserialize_with = "lenient")]
+  pub containers: Ring<ContainerState>,
+  #[serde(deserialize_with = "lenient")]
+  pub monocle_container: Option<ContainerState>,
+  #[serde(deserialize_with = "lenient")]
+  pub maximized_window: Option<WindowState>,
+  /// A list in older releases, and a ring in newer ones.
+  #[serde(deserialize_with = "lenient_elements")]
+  pub floating_windows: Vec<WindowState>,
+  /// Either `{ "Default": "<name>" }` or `{ "Custom": ... }`. Kept as
+  /// JSON, since layouts are added between releases.
+  pub layout: Value,
+  #[serde(deserialize_with = "lenient")]
+  pub layout_flip: Option<AxisState>,
+  #[serde(deserialize_with = "lenient")]
+  pub workspace_padding: Option<i32>,
+  #[serde(deserialize_with = "lenient")]
+  pub container_padding: Option<i32>,
+  #[serde(deserialize_with = "lenient_elements")]
+  pub latest_layout: Vec<KomorebiRect>,
+}
+
+impl WorkspaceState {
+  pub fn is_empty(&self) -> bool {
+    self.containers.elements.is_empty()
+      && self.floating_windows.is_empty()
+      && self.monocle_container.is_none()
+      && self.maximized_window.is_none()
+  }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ContainerState {
+  #[serde(deserialize_with = "lenient")]
+  pub id: String,
+  #[serde(deserialize_with = "lenient")]
+  pub windows: Ring<WindowState>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct WindowState {
+  #[serde(deserialize_with = "lenient")]
+  pub hwnd: isize,
+  #[serde(deserialize_with = "lenient")]
+  pub title: Option<String>,
+  #[serde(deserialize_with = "lenient")]
+  pub exe: Option<String>,
+  #[serde(deserialize_with = "lenient")]
+  pub class: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub enum AxisState {
+  Horizontal,
+  Vertical,
+  HorizontalAndVertical,
+}
+
+/// Deserializes a field, falling back to its default if it has an
+/// unexpected shape.
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+  D: Deserializer<'de>,
+  T: DeserializeOwned + Default,
+{
+  let value = Value::deserialize(deserializer)?;
+  Ok(parse_or_record(value).unwrap_or_default())
+}
+
+/// Deserializes a list, skipping elements with an unexpected shape. A
+/// ring is also accepted, since some lists have been changed to rings
+/// between releases.
+fn lenient_elements<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: DeserializeOwned,
+{
+  let elements = match Value::deserialize(deserializer)? {
+    Value::Array(elements) => elements,
+    Value::Object(mut ring) => match ring.remove("elements") {
+      Some(Value::Array(elements)) => elements,
+      _ => {
+        record_drift(format!("{}: expected a list", type_name::<T>()));
+        Vec::new()
+      }
+    },
+    Value::Null => Vec::new(),
+    _ => {
+      record_drift(format!("{}: expected a list", type_name::<T>()));
+      Vec::new()
+    }
+  };
+
+  Ok(elements.into_iter().filter_map(parse_or_record).collect())
+}
+
+fn parse_or_record<T: DeserializeOwned>(value: Value) -> Option<T> {
+  match T::deserialize(value) {
+    Ok(parsed) => Some(parsed),
+    Err(err) => {
+      record_drift(format!("{}: {}", type_name::<T>(), err));
+      None
+    }
+  }
+}
+
+fn record_drift(message: String) {
+  DRIFT.with(|drift| drift.borrow_mut().push(message));
+}
+
+/// Gets the version of the installed Komorebi (e.g. `0.1.28`) from
+/// `komorebic --version`.
+pub fn komorebi_version() -> Option<String> {
+  use std::os::windows::process::CommandExt;
+
+  use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+  let output = Command::new("komorebic")
+    .arg("--version")
+    .creation_flags(CREATE_NO_WINDOW.0)
+    .output()
+    .ok()?;
+
+  String::from_utf8_lossy(&output.stdout)
+    .split_whitespace()
+    .last()
+    .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::providers::komorebi::KomorebiLayout;
+
+  fn parse_fixture(json: &str) -> (KomorebiState, Vec<String>) {
+    KomorebiState::parse(serde_json::from_str(json).unwrap())
+  }
+
+  fn workspace_names(monitor: &MonitorState) -> Vec<Option<&str>> {
+    monitor
+      .workspaces
+      .elements
+      .iter()
+      .map(|workspace| workspace.name.as_deref())
+      .collect()
+  }
+
+  #[test]
+  fn parses_v0_1_22() {
+    let (state, drift) =
+      parse_fixture(include_str!("fixtures/state-v0.1.22.json"));
+
+    assert!(drift.is_empty(), "Unexpected drift: {:?}", drift);
+    assert_eq!(state.monitors.elements.len(), 1);
+
+    let monitor = &state.monitors.elements[0];
+    assert_eq!(monitor.id, 65537);
+    assert_eq!(monitor.device_id, None);
+    assert_eq!(monitor.size.right, 1920);
+    assert_eq!(workspace_names(monitor), [Some("1"), Some("2")]);
+
+    let workspace = &monitor.workspaces.elements[0];
+    assert_eq!(workspace.containers.elements.len(), 1);
+    assert_eq!(workspace.floating_windows.len(), 1);
+    assert_eq!(workspace.floating_windows[0].hwnd, 1002);
+    assert_eq!(workspace.workspace_padding, Some(10));
+    assert_eq!(workspace.latest_layout.len(), 1);
+    assert!(matches!(
+      KomorebiLayout::from_komorebi(&workspace.layout),
+      KomorebiLayout::Bsp
+    ));
+
+    let empty_workspace = &monitor.workspaces.elements[1];
+    assert!(empty_workspace.is_empty());
+    assert!(matches!(
+      empty_workspace.layout_flip,
+      Some(AxisState::Horizontal)
+    ));
+  }
+
+  #[test]
+  fn parses_v0_1_28() {
+    let (state, drift) =
+      parse_fixture(include_str!("fixtures/state-v0.1.28.json"));
+
+    assert!(drift.is_empty(), "Unexpected drift: {:?}", drift);
+
+    let monitor = &state.monitors.elements[0];
+    assert_eq!(
+      monitor.device_id.as_deref(),
+      Some("DEL40A3-5&1a2b3c4d&0&UID4352")
+    );
+    assert_eq!(monitor.work_area_offset.map(|rect| rect.top), Some(40));
+    assert_eq!(monitor.workspaces.focused, 1);
+    assert_eq!(workspace_names(monitor), [Some("code"), None]);
+
+    let workspace = &monitor.workspaces.elements[0];
+    let container = &workspace.containers.elements[0];
+    assert_eq!(workspace.containers.focused, 1);
+    assert_eq!(container.id, "b1");
+    assert_eq!(container.windows.elements.len(), 2);
+    assert_eq!(container.windows.focused, 1);
+    assert_eq!(
+      container.windows.elements[0].exe.as_deref(),
+      Some("Code.exe")
+    );
+
+    let monocle_workspace = &monitor.workspaces.elements[1];
+    assert!(!monocle_workspace.is_empty());
+    assert_eq!(
+      monocle_workspace
+        .monocle_container
+        .as_ref()
+        .map(|container| container.windows.elements[0].hwnd),
+      Some(2004)
+    );
+    assert!(matches!(
+      KomorebiLayout::from_komorebi(&monocle_workspace.layout),
+      KomorebiLayout::Custom
+    ));
+  }
+
+  #[test]
+  fn parses_v0_1_35() {
+    let (state, drift) =
+      parse_fixture(include_str!("fixtures/state-v0.1.35.json"));
+
+    assert!(drift.is_empty(), "Unexpected drift: {:?}", drift);
+    assert_eq!(state.monitors.elements.len(), 2);
+    assert_eq!(state.monitors.focused, 1);
+
+    let monitor = &state.monitors.elements[0];
+    let workspace = &monitor.workspaces.elements[0];
+
+    // Floating windows are a ring rather than a list.
+    assert_eq!(workspace.floating_windows.len(), 1);
+    assert_eq!(workspace.floating_windows[0].hwnd, 3003);
+    assert_eq!(
+      workspace
+        .maximized_window
+        .as_ref()
+        .map(|window| window.hwnd),
+      Some(3002)
+    );
+    assert!(matches!(workspace.layout_flip, Some(AxisState::Vertical)));
+
+    // Layouts that were added later are treated as custom.
+    assert!(matches!(
+      KomorebiLayout::from_komorebi(&workspace.layout),
+      KomorebiLayout::Custom
+    ));
+    assert!(matches!(
+      KomorebiLayout::from_komorebi(
+        &monitor.workspaces.elements[1].layout
+      ),
+      KomorebiLayout::Grid
+    ));
+
+    assert_eq!(
+      workspace_names(&state.monitors.elements[1]),
+      [Some("music")]
+    );
+  }
+
+  #[test]
+  fn unexpected_shapes_are_defaulted_or_skipped() {
+    let (state, drift) =
+      parse_fixture(include_str!("fixtures/state-drift.json"));
+
+    // The monitor that isn't an object is skipped.
+    assert_eq!(state.monitors.elements.len(), 1);
+
+    let monitor = &state.monitors.elements[0];
+    assert_eq!(monitor.id, 0);
+    assert_eq!(monitor.name, "DISPLAY1");
+    assert_eq!(monitor.size.right, 0);
+    assert_eq!(monitor.work_area_size.bottom, 1040);
+
+    let workspace = &monitor.workspaces.elements[0];
+    assert_eq!(workspace.name.as_deref(), Some("1"));
+    assert_eq!(workspace.containers.elements.len(), 1);
+    assert!(workspace.floating_windows.is_empty());
+    assert!(workspace.layout_flip.is_none());
+    assert_eq!(workspace.workspace_padding, None);
+
+    let window = &workspace.containers.elements[0].windows.elements[0];
+    assert_eq!(window.hwnd, 4001);
+    assert_eq!(window.title, None);
+    assert_eq!(window.exe.as_deref(), Some("app.exe"));
+
+    // Monitor, id, size, container, title, floating windows, layout flip
+    // and workspace padding.
+    assert_eq!(drift.len(), 8, "Unexpected drift: {:?}", drift);
+  }
+
+  #[test]
+  fn drift_is_reset_between_parses() {
+    parse_fixture(include_str!("fixtures/state-drift.json"));
+
+    let (_, drift) =
+      parse_fixture(include_str!("fixtures/state-v0.1.35.json"));
+
+    assert!(drift.is_empty());
+  }
+
+  #[test]
+  fn non_object_state_is_defaulted() {
+    let (state, drift) = KomorebiState::parse(Value::Null);
+
+    assert!(state.monitors.elements.is_empty());
+    assert_eq!(drift.len(), 1);
+  }
+}
@@ -1,11 +1,22 @@
-use komorebi_client::{Axis, DefaultLayout, Layout, Rect};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::state::AxisState;
 
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct KomorebiVariables {
   pub all_monitors: Vec<KomorebiMonitor>,
   pub focused_monitor_index: usize,
+
+  /// Whether this is a fresh snapshot after Komorebi's config was
+  /// reloaded. Any accumulated per-workspace UI state should be reset.
+  pub resync: bool,
+
+  /// Whether the window that the output is emitted to is on the focused
+  /// monitor. Omitted if the window's position isn't known yet.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub self_monitor_focused: Option<bool>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -13,21 +24,33 @@ pub struct KomorebiVariables {
 pub struct KomorebiMonitor {
   pub id: isize,
   pub device_id: String,
+
+  /// Index of the focused workspace within `workspaces`.
   pub focused_workspace_index: usize,
   pub name: String,
-  pub size: Rect,
-  pub work_area_offset: Option<Rect>,
-  pub work_area_size: Rect,
+  pub size: KomorebiRect,
+  pub work_area_offset: Option<KomorebiRect>,
+  pub work_area_size: KomorebiRect,
+  /// Workspaces on the monitor. Excludes empty workspaces if
+  /// `hide_empty` is enabled.
   pub workspaces: Vec<KomorebiWorkspace>,
 }
 
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct KomorebiWorkspace {
+  /// Index of the workspace within its monitor in Komorebi. Unaffected
+  /// by `hide_empty`.
+  pub index: usize,
+
+  /// Label from `workspace_labels`, falling back to the workspace name.
+  pub display_label: String,
+
   pub container_padding: Option<i32>,
   pub floating_windows: Vec<KomorebiWindow>,
   pub focused_container_index: usize,
-  pub latest_layout: Vec<Rect>,
+  pub focused_window_is_floating: bool,
+  pub latest_layout: Vec<KomorebiRect>,
   pub layout: KomorebiLayout,
   pub layout_flip: Option<KomorebiLayoutFlip>,
   pub maximized_window: Option<KomorebiWindow>,
@@ -42,6 +65,8 @@ pub struct KomorebiWorkspace {
 pub struct KomorebiContainer {
   pub id: String,
   pub windows: Vec<KomorebiWindow>,
+  pub focused_window_index: usize,
+  pub is_stacked: bool,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -51,6 +76,19 @@ pub struct KomorebiWindow {
   pub exe: Option<String>,
   pub hwnd: u64,
   pub title: Option<String>,
+  pub is_floating: bool,
+  pub is_minimized: bool,
+  /// Index of the window within its container's stack. `None` for
+  /// windows that aren't in a container (e.g. floating windows).
+  pub stack_index: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct KomorebiRect {
+  pub left: i32,
+  pub top: i32,
+  pub right: i32,
+  pub bottom: i32,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -66,23 +104,23 @@ pub enum KomorebiLayout {
   Custom,
 }
 
-impl From<Layout> for KomorebiLayout {
-  fn from(layout: Layout) -> Self {
-    match layout {
-      Layout::Default(layout) => match layout {
-        DefaultLayout::BSP => KomorebiLayout::Bsp,
-        DefaultLayout::Columns => KomorebiLayout::Custom,
-        DefaultLayout::Rows => KomorebiLayout::Rows,
-        DefaultLayout::VerticalStack => KomorebiLayout::VerticalStack,
-        DefaultLayout::HorizontalStack => KomorebiLayout::HorizontalStack,
-        DefaultLayout::UltrawideVerticalStack => {
-          KomorebiLayout::UltrawideVerticalStack
-        }
-        DefaultLayout::Grid => KomorebiLayout::Grid,
-        DefaultLayout::RightMainVerticalStack => {
-          KomorebiLayout::RightMainVerticalStack
-        }
-      },
+impl KomorebiLayout {
+  /// Gets the layout from Komorebi's JSON for it (e.g. `{ "Default":
+  /// "BSP" }`). Custom layouts and default layouts that aren't known
+  /// are treated as custom.
+  pub fn from_komorebi(layout: &Value) -> Self {
+    match layout["Default"].as_str() {
+      Some("BSP") => KomorebiLayout::Bsp,
+      Some("Rows") => KomorebiLayout::Rows,
+      Some("VerticalStack") => KomorebiLayout::VerticalStack,
+      Some("HorizontalStack") => KomorebiLayout::HorizontalStack,
+      Some("UltrawideVerticalStack") => {
+        KomorebiLayout::UltrawideVerticalStack
+      }
+      Some("Grid") => KomorebiLayout::Grid,
+      Some("RightMainVerticalStack") => {
+        KomorebiLayout::RightMainVerticalStack
+      }
       _ => KomorebiLayout::Custom,
     }
   }
@@ -96,12 +134,12 @@ pub enum KomorebiLayoutFlip {
   HorizontalAndVertical,
 }
 
-impl From<Axis> for KomorebiLayoutFlip {
-  fn from(axis: Axis) -> Self {
+impl From<AxisState> for KomorebiLayoutFlip {
+  fn from(axis: AxisState) -> Self {
     match axis {
-      Axis::Horizontal => KomorebiLayoutFlip::Horizontal,
-      Axis::Vertical => KomorebiLayoutFlip::Vertical,
-      Axis::HorizontalAndVertical => {
+      AxisState::Horizontal => KomorebiLayoutFlip::Horizontal,
+      AxisState::Vertical => KomorebiLayoutFlip::Vertical,
+      AxisState::HorizontalAndVertical => {
         KomorebiLayoutFlip::HorizontalAndVertical
       }
     }
@@ -0,0 +1,71 @@
+use std::collections::{HashMap, HashSet};
+
+use tracing::warn;
+
+use super::state::WorkspaceState;
+
+/// Resolves display labels for workspaces from the configured mapping.
+#[derive(Debug)]
+pub struct WorkspaceLabels {
+  labels: HashMap<String, String>,
+
+  /// Keys that have already been warned about, so that each unknown key
+  /// is only warned about once rather than on every emission.
+  warned_keys: HashSet<String>,
+}
+
+impl WorkspaceLabels {
+  pub fn new(labels: HashMap<String, String>) -> Self {
+    Self {
+      labels,
+      warned_keys: HashSet::new(),
+    }
+  }
+
+  /// Gets the label for a workspace by its name, falling back to its
+  /// index. The index fallback keeps labels working if workspaces are
+  /// renamed at runtime.
+  ///
+  /// Defaults to the workspace name, or the 1-based index for unnamed
+  /// workspaces.
+  pub fn resolve(&self, name: Option<&str>, index: usize) -> String {
+    name
+      .and_then(|name| self.labels.get(name))
+      .or_else(|| self.labels.get(&index.to_string()))
+      .cloned()
+      .or(name.map(str::to_string))
+      .unwrap_or_else(|| (index + 1).to_string())
+  }
+
+  /// Warns about keys that don't match any of the given workspaces by
+  /// name or index. Workspaces are passed along with their index within
+  /// their monitor.
+  pub fn warn_unknown_keys<'a>(
+    &mut self,
+    workspaces: impl Iterator<Item = (usize, &'a WorkspaceState)>,
+  ) {
+    if self.warned_keys.len() == self.labels.len() {
+      return;
+    }
+
+    let mut known_keys = HashSet::new();
+
+    for (index, workspace) in workspaces {
+      known_keys.insert(index.to_string());
+
+      if let Some(name) = &workspace.name {
+        known_keys.insert(name.clone());
+      }
+    }
+
+    for key in self.labels.keys() {
+      if !known_keys.contains(key) && self.warned_keys.insert(key.clone())
+      {
+        warn!(
+          "Komorebi workspace label '{}' doesn't match any workspace name or index.",
+          key
+        );
+      }
+    }
+  }
+}
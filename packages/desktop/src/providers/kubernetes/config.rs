@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::impl_interval_config;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "kubernetes")]
+pub struct KubernetesProviderConfig {
+  pub refresh_interval: u64,
+
+  /// Whether to query the API server for node readiness and pod phases.
+  /// Otherwise, only the active context is read from the kubeconfig.
+  #[serde(default)]
+  pub query_cluster: bool,
+
+  /// Namespaces to count pods by phase in. Only used if `query_cluster`
+  /// is enabled.
+  #[serde(default)]
+  pub namespaces: Vec<String>,
+
+  /// Timeout in milliseconds for each query to the API server. An
+  /// unreachable cluster is reported as `reachable: false` once it's
+  /// exceeded.
+  #[serde(default = "default_timeout")]
+  pub timeout: u64,
+
+  /// Path to the kubeconfig file. Defaults to the files in `KUBECONFIG`,
+  /// and otherwise `~/.kube/config`.
+  pub kubeconfig: Option<String>,
+}
+
+fn default_timeout() -> u64 {
+  3000
+}
+
+impl_interval_config!(KubernetesProviderConfig);
@@ -0,0 +1,99 @@
+use std::{env, fs, path::PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+/// Subset of a kubeconfig file. Credentials aren't read, since queries
+/// go through `kubectl`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case", default)]
+struct Kubeconfig {
+  current_context: Option<String>,
+  /// Set to `null` by some tools when there are no contexts.
+  contexts: Option<Vec<NamedContext>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NamedContext {
+  name: String,
+  #[serde(default)]
+  context: ContextEntry,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct ContextEntry {
+  cluster: Option<String>,
+  namespace: Option<String>,
+}
+
+/// Active context of the kubeconfig.
+#[derive(Debug, Clone)]
+pub struct ActiveContext {
+  pub name: String,
+  pub cluster: Option<String>,
+  pub namespace: Option<String>,
+}
+
+/// Gets the kubeconfig files to read, in the same order as `kubectl`.
+pub fn kubeconfig_paths(configured: Option<&str>) -> Vec<PathBuf> {
+  if let Some(path) = configured {
+    return vec![PathBuf::from(path)];
+  }
+
+  match env::var_os("KUBECONFIG") {
+    Some(paths) if !paths.is_empty() => env::split_paths(&paths)
+      .filter(|path| !path.as_os_str().is_empty())
+      .collect(),
+    _ => dirs::home_dir()
+      .map(|home| vec![home.join(".kube").join("config")])
+      .unwrap_or_default(),
+  }
+}
+
+/// Reads the active context from the given kubeconfig files.
+///
+/// Files are merged the same way as by `kubectl`: the first file to set
+/// `current-context` wins, as does the first file to define a context
+/// of a given name. Missing files are skipped.
+pub fn active_context(
+  paths: &[PathBuf],
+) -> anyhow::Result<Option<ActiveContext>> {
+  let mut current_context = None;
+  let mut contexts = Vec::new();
+
+  for path in paths {
+    let Ok(contents) = fs::read_to_string(path) else {
+      continue;
+    };
+
+    let kubeconfig = serde_yaml::from_str::<Kubeconfig>(&contents)
+      .with_context(|| {
+        format!("Invalid kubeconfig at '{}'.", path.display())
+      })?;
+
+    if current_context.is_none() {
+      current_context = kubeconfig
+        .current_context
+        .filter(|context| !context.is_empty());
+    }
+
+    contexts.extend(kubeconfig.contexts.unwrap_or_default());
+  }
+
+  let Some(name) = current_context else {
+    return Ok(None);
+  };
+
+  let entry = contexts
+    .into_iter()
+    .find(|context| context.name == name)
+    .map(|context| context.context)
+    .unwrap_or_default();
+
+  Ok(Some(ActiveContext {
+    name,
+    cluster: entry.cluster,
+    namespace: entry.namespace,
+  }))
+}
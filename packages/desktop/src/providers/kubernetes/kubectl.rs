@@ -0,0 +1,186 @@
+use std::{io, process::Stdio, time::Duration};
+
+use anyhow::{bail, Context};
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::{process::Command, time};
+
+use super::{NodeCounts, PodPhaseCounts};
+
+/// Extra time given to `kubectl` on top of its request timeout, e.g. for
+/// exec credential plugins to run.
+const PROCESS_GRACE: Duration = Duration::from_secs(2);
+
+/// Result of a query to the API server.
+pub enum QueryResult<T> {
+  Ok(T),
+
+  /// The API server responded, but rejected the context's credentials
+  /// or doesn't allow the query with them.
+  Forbidden,
+
+  /// The API server didn't respond in time or the connection failed
+  /// (e.g. because the VPN is down).
+  Unreachable,
+}
+
+#[derive(Deserialize, Debug)]
+struct List<T> {
+  #[serde(default)]
+  items: Vec<T>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Node {
+  #[serde(default)]
+  status: NodeStatus,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct NodeStatus {
+  #[serde(default)]
+  conditions: Vec<NodeCondition>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NodeCondition {
+  #[serde(rename = "type")]
+  condition_type: String,
+  status: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Pod {
+  #[serde(default)]
+  status: PodStatus,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct PodStatus {
+  phase: Option<String>,
+}
+
+/// Runs `kubectl` against the API server of a context. Credentials are
+/// loaded by `kubectl` from the kubeconfig as usual.
+pub struct Kubectl {
+  pub context: String,
+  pub kubeconfig: Option<String>,
+  pub timeout: Duration,
+}
+
+impl Kubectl {
+  pub async fn node_counts(
+    &self,
+  ) -> anyhow::Result<QueryResult<NodeCounts>> {
+    let res = self.get::<Node>(&["get", "nodes"]).await?;
+
+    Ok(res.map(|nodes| {
+      NodeCounts {
+        total: nodes.len() as u32,
+        ready: nodes
+          .iter()
+          .filter(|node| {
+            node.status.conditions.iter().any(|condition| {
+              condition.condition_type == "Ready"
+                && condition.status == "True"
+            })
+          })
+          .count() as u32,
+      }
+    }))
+  }
+
+  pub async fn pod_phase_counts(
+    &self,
+    namespace: &str,
+  ) -> anyhow::Result<QueryResult<PodPhaseCounts>> {
+    let res = self
+      .get::<Pod>(&["get", "pods", "--namespace", namespace])
+      .await?;
+
+    Ok(res.map(|pods| {
+      let mut counts = PodPhaseCounts::default();
+
+      for pod in pods {
+        match pod.status.phase.as_deref() {
+          Some("Pending") => counts.pending += 1,
+          Some("Running") => counts.running += 1,
+          Some("Succeeded") => counts.succeeded += 1,
+          Some("Failed") => counts.failed += 1,
+          _ => counts.unknown += 1,
+        }
+      }
+
+      counts
+    }))
+  }
+
+  /// Lists resources as JSON.
+  ///
+  /// The process is killed if it outlives the request timeout, so that
+  /// a hung connection (e.g. to a cluster behind a VPN that's down)
+  /// degrades to `Unreachable` quickly.
+  async fn get<T: DeserializeOwned>(
+    &self,
+    args: &[&str],
+  ) -> anyhow::Result<QueryResult<Vec<T>>> {
+    let mut command = Command::new("kubectl");
+
+    command
+      .args(args)
+      .args(["--output", "json", "--context", self.context.as_str()])
+      .arg(format!("--request-timeout={}ms", self.timeout.as_millis()))
+      .stdin(Stdio::null())
+      .kill_on_drop(true);
+
+    if let Some(kubeconfig) = &self.kubeconfig {
+      command.args(["--kubeconfig", kubeconfig.as_str()]);
+    }
+
+    #[cfg(windows)]
+    {
+      use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+      command.creation_flags(CREATE_NO_WINDOW.0);
+    }
+
+    let output =
+      match time::timeout(self.timeout + PROCESS_GRACE, command.output())
+        .await
+      {
+        Ok(Ok(output)) => output,
+        Ok(Err(err)) if err.kind() == io::ErrorKind::NotFound => {
+          bail!(
+            "kubectl wasn't found. Make sure it's installed and on PATH."
+          )
+        }
+        Ok(Err(err)) => return Err(err).context("Failed to run kubectl."),
+        Err(_) => return Ok(QueryResult::Unreachable),
+      };
+
+    if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+
+      let is_forbidden =
+        stderr.contains("Forbidden") || stderr.contains("Unauthorized");
+
+      return Ok(match is_forbidden {
+        true => QueryResult::Forbidden,
+        false => QueryResult::Unreachable,
+      });
+    }
+
+    let list = serde_json::from_slice::<List<T>>(&output.stdout)
+      .context("Invalid output from kubectl.")?;
+
+    Ok(QueryResult::Ok(list.items))
+  }
+}
+
+impl<T> QueryResult<T> {
+  fn map<U>(self, f: impl FnOnce(T) -> U) -> QueryResult<U> {
+    match self {
+      QueryResult::Ok(value) => QueryResult::Ok(f(value)),
+      QueryResult::Forbidden => QueryResult::Forbidden,
+      QueryResult::Unreachable => QueryResult::Unreachable,
+    }
+  }
+}
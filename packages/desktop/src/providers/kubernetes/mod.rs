@@ -0,0 +1,9 @@
+mod config;
+mod kubeconfig;
+mod kubectl;
+mod provider;
+mod variables;
+
+pub use config::*;
+pub use provider::*;
+pub use variables::*;
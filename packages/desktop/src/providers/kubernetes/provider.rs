@@ -0,0 +1,130 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tokio::task::{AbortHandle, JoinSet};
+
+use super::{
+  kubeconfig::{active_context, kubeconfig_paths},
+  kubectl::{Kubectl, QueryResult},
+  KubernetesProviderConfig, KubernetesVariables,
+};
+use crate::providers::{
+  http_client::HttpClient, provider::IntervalProvider,
+  variables::ProviderVariables,
+};
+
+pub struct KubernetesProvider {
+  pub config: Arc<KubernetesProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  http_client: Arc<HttpClient>,
+}
+
+impl KubernetesProvider {
+  pub fn new(
+    config: KubernetesProviderConfig,
+    http_client: HttpClient,
+  ) -> KubernetesProvider {
+    KubernetesProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      http_client: Arc::new(http_client),
+    }
+  }
+}
+
+#[async_trait]
+impl IntervalProvider for KubernetesProvider {
+  type Config = KubernetesProviderConfig;
+  type State = HttpClient;
+
+  /// The client isn't used for the queries themselves, but runs are
+  /// bounded and throttled like other network lookups.
+  fn http_client(&self) -> Option<HttpClient> {
+    Some((*self.http_client).clone())
+  }
+
+  fn config(&self) -> Arc<KubernetesProviderConfig> {
+    self.config.clone()
+  }
+
+  fn state(&self) -> Arc<HttpClient> {
+    self.http_client.clone()
+  }
+
+  fn abort_handle(&self) -> &Option<AbortHandle> {
+    &self.abort_handle
+  }
+
+  fn set_abort_handle(&mut self, abort_handle: AbortHandle) {
+    self.abort_handle = Some(abort_handle)
+  }
+
+  async fn get_refreshed_variables(
+    config: &KubernetesProviderConfig,
+    http_client: &HttpClient,
+  ) -> anyhow::Result<ProviderVariables> {
+    let paths = kubeconfig_paths(config.kubeconfig.as_deref());
+    let context = active_context(&paths)?;
+
+    let mut variables = KubernetesVariables {
+      context: context.as_ref().map(|context| context.name.clone()),
+      cluster: context
+        .as_ref()
+        .and_then(|context| context.cluster.clone()),
+      namespace: context
+        .as_ref()
+        .and_then(|context| context.namespace.clone()),
+      reachable: None,
+      nodes: None,
+      pods: HashMap::new(),
+    };
+
+    // The cluster is treated as an outbound request, so it isn't queried
+    // while offline.
+    let Some(context) = context
+      .filter(|_| config.query_cluster && !http_client.is_offline())
+    else {
+      return Ok(ProviderVariables::Kubernetes(variables));
+    };
+
+    let kubectl = Arc::new(Kubectl {
+      context: context.name,
+      kubeconfig: config.kubeconfig.clone(),
+      timeout: Duration::from_millis(config.timeout),
+    });
+
+    // Nodes are queried first, so that an unreachable cluster only costs
+    // a single timeout.
+    match kubectl.node_counts().await? {
+      QueryResult::Ok(nodes) => variables.nodes = Some(nodes),
+      QueryResult::Forbidden => {}
+      QueryResult::Unreachable => {
+        variables.reachable = Some(false);
+        return Ok(ProviderVariables::Kubernetes(variables));
+      }
+    }
+
+    variables.reachable = Some(true);
+
+    let mut namespace_tasks = JoinSet::new();
+
+    for namespace in config.namespaces.clone() {
+      let kubectl = kubectl.clone();
+
+      namespace_tasks.spawn(async move {
+        let res = kubectl.pod_phase_counts(&namespace).await;
+        (namespace, res)
+      });
+    }
+
+    while let Some(task_result) = namespace_tasks.join_next().await {
+      let (namespace, res) = task_result?;
+
+      if let QueryResult::Ok(counts) = res? {
+        variables.pods.insert(namespace, counts);
+      }
+    }
+
+    Ok(ProviderVariables::Kubernetes(variables))
+  }
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesVariables {
+  /// Name of the active context. `None` if no context is set.
+  pub context: Option<String>,
+
+  /// Name of the active context's cluster.
+  pub cluster: Option<String>,
+
+  /// Default namespace of the active context.
+  pub namespace: Option<String>,
+
+  /// Whether the API server responded. `None` if the cluster isn't
+  /// queried (e.g. `query_cluster` is disabled or offline mode is on).
+  pub reachable: Option<bool>,
+
+  /// Node counts. `None` if the cluster isn't reachable or nodes can't
+  /// be listed with the context's credentials.
+  pub nodes: Option<NodeCounts>,
+
+  /// Pod counts by phase, keyed by namespace. Namespaces whose pods
+  /// can't be listed are omitted.
+  pub pods: HashMap<String, PodPhaseCounts>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeCounts {
+  pub total: u32,
+  pub ready: u32,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PodPhaseCounts {
+  pub pending: u32,
+  pub running: u32,
+  pub succeeded: u32,
+  pub failed: u32,
+  pub unknown: u32,
+}
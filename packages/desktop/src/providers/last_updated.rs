@@ -0,0 +1,119 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::locale::Locale;
+
+/// How often the relative age of emitted outputs is re-emitted. A single
+/// tick is shared by all providers.
+pub const RELATIVE_TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Gets the current Unix timestamp in milliseconds.
+pub fn now_millis() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_millis() as u64)
+    .unwrap_or_default()
+}
+
+/// Formats the age of an output relative to `now` (e.g. `3m ago`).
+///
+/// Ages are rounded down to the largest unit, so that the string only
+/// changes a few times per unit and re-emits are kept to a minimum.
+pub fn format_relative(
+  last_updated: u64,
+  now: u64,
+  locale: Locale,
+) -> String {
+  let seconds = now.saturating_sub(last_updated) / 1000;
+
+  let (count, unit) = match seconds {
+    0..=59 => return just_now(locale).into(),
+    60..=3599 => (seconds / 60, TimeUnit::Minutes),
+    3600..=86399 => (seconds / 3600, TimeUnit::Hours),
+    _ => (seconds / 86400, TimeUnit::Days),
+  };
+
+  match (locale, unit) {
+    (Locale::En, TimeUnit::Minutes) => format!("{}m ago", count),
+    (Locale::En, TimeUnit::Hours) => format!("{}h ago", count),
+    (Locale::En, TimeUnit::Days) => format!("{}d ago", count),
+    (Locale::De, TimeUnit::Minutes) => format!("vor {} Min.", count),
+    (Locale::De, TimeUnit::Hours) => format!("vor {} Std.", count),
+    (Locale::De, TimeUnit::Days) => format!("vor {} T.", count),
+    (Locale::Es, TimeUnit::Minutes) => format!("hace {} min", count),
+    (Locale::Es, TimeUnit::Hours) => format!("hace {} h", count),
+    (Locale::Es, TimeUnit::Days) => format!("hace {} d", count),
+    (Locale::Fr, TimeUnit::Minutes) => format!("il y a {} min", count),
+    (Locale::Fr, TimeUnit::Hours) => format!("il y a {} h", count),
+    (Locale::Fr, TimeUnit::Days) => format!("il y a {} j", count),
+    (Locale::Ja, TimeUnit::Minutes) => format!("{}分前", count),
+    (Locale::Ja, TimeUnit::Hours) => format!("{}時間前", count),
+    (Locale::Ja, TimeUnit::Days) => format!("{}日前", count),
+    (Locale::Ko, TimeUnit::Minutes) => format!("{}분 전", count),
+    (Locale::Ko, TimeUnit::Hours) => format!("{}시간 전", count),
+    (Locale::Ko, TimeUnit::Days) => format!("{}일 전", count),
+    (Locale::Zh, TimeUnit::Minutes) => format!("{}分钟前", count),
+    (Locale::Zh, TimeUnit::Hours) => format!("{}小时前", count),
+    (Locale::Zh, TimeUnit::Days) => format!("{}天前", count),
+  }
+}
+
+enum TimeUnit {
+  Minutes,
+  Hours,
+  Days,
+}
+
+fn just_now(locale: Locale) -> &'static str {
+  match locale {
+    Locale::En => "just now",
+    Locale::De => "gerade eben",
+    Locale::Es => "ahora mismo",
+    Locale::Fr => "à l'instant",
+    Locale::Ja => "たった今",
+    Locale::Ko => "방금",
+    Locale::Zh => "刚刚",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const NOW: u64 = 1_700_000_000_000;
+
+  fn ago(seconds: u64, locale: Locale) -> String {
+    format_relative(NOW - seconds * 1000, NOW, locale)
+  }
+
+  #[test]
+  fn english() {
+    assert_eq!(ago(0, Locale::En), "just now");
+    assert_eq!(ago(59, Locale::En), "just now");
+    assert_eq!(ago(60, Locale::En), "1m ago");
+    assert_eq!(ago(3599, Locale::En), "59m ago");
+    assert_eq!(ago(3600, Locale::En), "1h ago");
+    assert_eq!(ago(86399, Locale::En), "23h ago");
+    assert_eq!(ago(86400 * 3, Locale::En), "3d ago");
+  }
+
+  #[test]
+  fn german() {
+    assert_eq!(ago(30, Locale::De), "gerade eben");
+    assert_eq!(ago(5 * 60, Locale::De), "vor 5 Min.");
+    assert_eq!(ago(2 * 3600, Locale::De), "vor 2 Std.");
+    assert_eq!(ago(86400, Locale::De), "vor 1 T.");
+  }
+
+  #[test]
+  fn cjk() {
+    assert_eq!(ago(30, Locale::Ja), "たった今");
+    assert_eq!(ago(5 * 60, Locale::Ja), "5分前");
+    assert_eq!(ago(2 * 3600, Locale::Ko), "2시간 전");
+    assert_eq!(ago(86400, Locale::Zh), "1天前");
+  }
+
+  #[test]
+  fn future_timestamps_are_just_now() {
+    assert_eq!(format_relative(NOW + 5000, NOW, Locale::En), "just now");
+  }
+}
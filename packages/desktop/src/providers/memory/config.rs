@@ -1,11 +1,25 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::impl_interval_config;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "memory")]
 pub struct MemoryProviderConfig {
   pub refresh_interval: u64,
+
+  /// Opt-in list of the processes using the most memory. Enumerating
+  /// processes is expensive, so it's refreshed on its own interval.
+  pub top_processes: Option<MemoryTopProcessesConfig>,
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
+pub struct MemoryTopProcessesConfig {
+  /// Number of processes to include.
+  pub count: usize,
+
+  /// Has a floor of 3 seconds.
+  pub refresh_interval: u64,
 }
 
 impl_interval_config!(MemoryProviderConfig);
@@ -1,5 +1,6 @@
 mod config;
 mod provider;
+mod top_processes;
 mod variables;
 
 pub use config::*;
@@ -4,41 +4,61 @@ use async_trait::async_trait;
 use sysinfo::System;
 use tokio::{sync::Mutex, task::AbortHandle};
 
-use super::{MemoryProviderConfig, MemoryVariables};
+use super::{
+  top_processes::TopProcessesSampler, MemoryProviderConfig,
+  MemoryVariables,
+};
 use crate::providers::{
-  provider::IntervalProvider, variables::ProviderVariables,
+  process_snapshot::ProcessSnapshot, provider::IntervalProvider,
+  variables::ProviderVariables,
 };
 
 pub struct MemoryProvider {
   pub config: Arc<MemoryProviderConfig>,
   abort_handle: Option<AbortHandle>,
+  state: Arc<MemoryState>,
+}
+
+pub struct MemoryState {
   sysinfo: Arc<Mutex<System>>,
+  process_snapshot: Arc<ProcessSnapshot>,
+  top_processes_sampler: Mutex<TopProcessesSampler>,
 }
 
 impl MemoryProvider {
   pub fn new(
     config: MemoryProviderConfig,
     sysinfo: Arc<Mutex<System>>,
+    process_snapshot: Arc<ProcessSnapshot>,
   ) -> MemoryProvider {
     MemoryProvider {
       config: Arc::new(config),
       abort_handle: None,
-      sysinfo,
+      state: Arc::new(MemoryState {
+        sysinfo,
+        process_snapshot,
+        top_processes_sampler: Mutex::new(TopProcessesSampler::default()),
+      }),
     }
   }
+
+  /// Percentage of memory in use. Expects memory to have been refreshed.
+  pub fn usage(sysinfo: &System) -> f32 {
+    (sysinfo.used_memory() as f32 / sysinfo.total_memory() as f32) * 100.0
+  }
 }
 
 #[async_trait]
 impl IntervalProvider for MemoryProvider {
   type Config = MemoryProviderConfig;
-  type State = Mutex<System>;
+  type State = MemoryState;
 
   fn config(&self) -> Arc<MemoryProviderConfig> {
     self.config.clone()
   }
 
-  fn state(&self) -> Arc<Mutex<System>> {
-    self.sysinfo.clone()
+  fn state(&self) -> Arc<MemoryState> {
+    self.state.clone()
   }
 
   fn abort_handle(&self) -> &Option<AbortHandle> {
@@ -50,24 +70,32 @@ impl IntervalProvider for MemoryProvider {
   }
 
   async fn get_refreshed_variables(
-    _: &MemoryProviderConfig,
-    sysinfo: &Mutex<System>,
+    config: &MemoryProviderConfig,
+    state: &MemoryState,
   ) -> anyhow::Result<ProviderVariables> {
-    let mut sysinfo = sysinfo.lock().await;
+    let mut sysinfo = state.sysinfo.lock().await;
     sysinfo.refresh_memory();
 
-    let usage = (sysinfo.used_memory() as f32
-      / sysinfo.total_memory() as f32)
-      * 100.0;
+    let top_processes = match &config.top_processes {
+      Some(top_processes_config) => {
+        Some(state.top_processes_sampler.lock().await.sample(
+          top_processes_config,
+          &mut sysinfo,
+          &state.process_snapshot,
+        ))
+      }
+      None => None,
+    };
 
     Ok(ProviderVariables::Memory(MemoryVariables {
-      usage,
+      usage: Self::usage(&sysinfo),
       free_memory: sysinfo.free_memory(),
       used_memory: sysinfo.used_memory(),
       total_memory: sysinfo.total_memory(),
       free_swap: sysinfo.free_swap(),
       used_swap: sysinfo.used_swap(),
       total_swap: sysinfo.total_swap(),
+      top_processes,
     }))
   }
 }
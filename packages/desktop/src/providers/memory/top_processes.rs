@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant};
+
+use sysinfo::System;
+
+use super::{MemoryProcess, MemoryTopProcessesConfig};
+use crate::providers::process_snapshot::ProcessSnapshot;
+
+/// Minimum interval between enumerating processes, regardless of the
+/// configured interval.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Samples the processes using the most memory.
+#[derive(Debug, Default)]
+pub struct TopProcessesSampler {
+  last_sample: Option<(Instant, Vec<MemoryProcess>)>,
+}
+
+impl TopProcessesSampler {
+  /// Gets the top processes by resident memory. A new sample is only
+  /// taken if the previous one is older than the refresh interval.
+  pub fn sample(
+    &mut self,
+    config: &MemoryTopProcessesConfig,
+    sysinfo: &mut System,
+    process_snapshot: &ProcessSnapshot,
+  ) -> Vec<MemoryProcess> {
+    let refresh_interval = Duration::from_millis(config.refresh_interval)
+      .max(MIN_REFRESH_INTERVAL);
+
+    if let Some((timestamp, processes)) = &self.last_sample {
+      if timestamp.elapsed() < refresh_interval {
+        return processes.clone();
+      }
+    }
+
+    process_snapshot.refresh_if_stale(sysinfo);
+
+    // Processes that exited after being enumerated report no memory, and
+    // are skipped.
+    let mut processes = sysinfo
+      .processes()
+      .values()
+      .filter(|process| process.memory() > 0)
+      .map(|process| MemoryProcess {
+        pid: process.pid().as_u32(),
+        name: process.name().to_string(),
+        memory: process.memory(),
+      })
+      .collect::<Vec<_>>();
+
+    processes.sort_by(|a, b| b.memory.cmp(&a.memory));
+    processes.truncate(config.count);
+
+    self.last_sample = Some((Instant::now(), processes.clone()));
+
+    processes
+  }
+}
@@ -10,4 +10,15 @@ pub struct MemoryVariables {
   pub free_swap: u64,
   pub used_swap: u64,
   pub total_swap: u64,
+  pub top_processes: Option<Vec<MemoryProcess>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryProcess {
+  pub pid: u32,
+  pub name: String,
+
+  /// Resident memory in bytes.
+  pub memory: u64,
 }
@@ -1,14 +1,91 @@
+// Shared state and helpers (e.g. `SharedProviderState`) can go unused
+// in builds with some providers disabled.
+#![cfg_attr(
+  not(all(
+    feature = "audio_input",
+    feature = "battery",
+    feature = "clipboard",
+    feature = "cpu",
+    feature = "host",
+    feature = "ip",
+    feature = "komorebi",
+    feature = "kubernetes",
+    feature = "memory",
+    feature = "network",
+    feature = "openrgb",
+    feature = "removable_drives",
+    feature = "steam",
+    feature = "summary",
+    feature = "system_state",
+    feature = "tail",
+    feature = "weather",
+    feature = "windows_list",
+  )),
+  allow(dead_code)
+)]
+
+pub mod actions;
+#[cfg(feature = "audio_input")]
+pub mod audio_input;
+#[cfg(feature = "battery")]
 pub mod battery;
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
 pub mod config;
+pub mod connection_cost;
+#[cfg(feature = "cpu")]
 pub mod cpu;
+pub mod dependencies;
+pub mod emission_tracker;
+pub mod emit_stats;
+#[cfg(feature = "weather")]
+pub mod geocoding;
+pub mod high_frequency;
+#[cfg(feature = "host")]
 pub mod host;
+pub mod http_client;
+#[cfg(feature = "ip")]
 pub mod ip;
-#[cfg(windows)]
+#[cfg(all(windows, feature = "komorebi"))]
 pub mod komorebi;
+#[cfg(feature = "kubernetes")]
+pub mod kubernetes;
+pub mod last_updated;
+#[cfg(feature = "memory")]
 pub mod memory;
+#[cfg(feature = "network")]
 pub mod network;
+pub mod network_counters;
+#[cfg(feature = "openrgb")]
+pub mod openrgb;
+pub mod power_state;
+pub mod prewarm;
+pub mod process_snapshot;
 pub mod provider;
 pub mod provider_manager;
 pub mod provider_ref;
+pub mod redaction;
+#[cfg(feature = "removable_drives")]
+pub mod removable_drives;
+pub mod retained_size;
+pub mod run_timings;
+pub mod sample_clock;
+pub mod schedule;
+pub mod self_monitor;
+pub mod state;
+#[cfg(feature = "steam")]
+pub mod steam;
+pub mod subscriptions;
+#[cfg(feature = "summary")]
+pub mod summary;
+#[cfg(feature = "system_state")]
+pub mod system_state;
+#[cfg(feature = "tail")]
+pub mod tail;
+pub mod transforms;
 pub mod variables;
+pub mod wait_for;
+#[cfg(feature = "weather")]
 pub mod weather;
+#[cfg(all(windows, feature = "windows_list"))]
+pub mod windows_list;
@@ -1,11 +1,35 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-use crate::impl_interval_config;
+use crate::{
+  impl_interval_config, providers::high_frequency::HighFrequencyConfig,
+};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "network")]
 pub struct NetworkProviderConfig {
   pub refresh_interval: u64,
+
+  /// Opt-in info about active connections. This is expensive to gather,
+  /// so it's refreshed on its own interval.
+  pub connections: Option<NetworkConnectionsConfig>,
+
+  /// Opt-in sampling of traffic between refreshes. Samples are emitted
+  /// under `samples` on each refresh.
+  pub high_frequency: Option<HighFrequencyConfig>,
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
+pub struct NetworkConnectionsConfig {
+  pub refresh_interval: u64,
+
+  /// Number of processes to include in `top_processes`.
+  #[serde(default = "default_top_processes")]
+  pub top_processes: usize,
+}
+
+fn default_top_processes() -> usize {
+  5
 }
 
 impl_interval_config!(NetworkProviderConfig);
@@ -0,0 +1,378 @@
+use std::{
+  collections::{HashMap, HashSet},
+  time::{Duration, Instant},
+};
+
+use sysinfo::{Pid, System};
+
+use super::{
+  NetworkConnections, NetworkConnectionsConfig, NetworkProcess,
+};
+use crate::providers::{
+  process_snapshot::ProcessSnapshot, sample_clock::SampleClock,
+};
+
+/// Samples active TCP connections and the processes that own them.
+#[derive(Debug, Default)]
+pub struct ConnectionsSampler {
+  last_sample: Option<(Instant, NetworkConnections)>,
+
+  /// Previous I/O byte counters by PID. Used for calculating rates.
+  prev_io_bytes: HashMap<u32, u64>,
+
+  /// Time between samples of the I/O byte counters.
+  clock: SampleClock,
+}
+
+impl ConnectionsSampler {
+  /// Gets info about active connections. A new sample is only taken if
+  /// the previous one is older than the configured refresh interval.
+  ///
+  /// `provider_interval` is the refresh interval of the network
+  /// provider, which samples are taken no more often than.
+  pub fn sample(
+    &mut self,
+    config: &NetworkConnectionsConfig,
+    provider_interval: u64,
+    sysinfo: &mut System,
+    process_snapshot: &ProcessSnapshot,
+  ) -> NetworkConnections {
+    if let Some((timestamp, connections)) = &self.last_sample {
+      if timestamp.elapsed()
+        < Duration::from_millis(config.refresh_interval)
+      {
+        return connections.clone();
+      }
+    }
+
+    let expected_interval = Duration::from_millis(
+      config.refresh_interval.max(provider_interval),
+    );
+
+    let connections = self.take_sample(
+      config.top_processes,
+      expected_interval,
+      sysinfo,
+      process_snapshot,
+    );
+    self.last_sample = Some((Instant::now(), connections.clone()));
+
+    connections
+  }
+
+  fn take_sample(
+    &mut self,
+    top_count: usize,
+    expected_interval: Duration,
+    sysinfo: &mut System,
+    process_snapshot: &ProcessSnapshot,
+  ) -> NetworkConnections {
+    let owner_pids = established_connections();
+
+    let mut connection_counts = HashMap::<u32, usize>::new();
+
+    for pid in owner_pids.iter().flatten() {
+      *connection_counts.entry(*pid).or_default() += 1;
+    }
+
+    process_snapshot.refresh_if_stale(sysinfo);
+
+    let elapsed = self.clock.tick(expected_interval);
+
+    let mut processes = connection_counts
+      .into_iter()
+      .filter_map(|(pid, connection_count)| {
+        // Processes that have exited since the connections were
+        // enumerated are skipped.
+        let name = sysinfo.process(Pid::from_u32(pid))?.name().to_string();
+
+        let throughput = process_io_bytes(pid).and_then(|bytes| {
+          let prev_bytes = self.prev_io_bytes.insert(pid, bytes)?;
+          let elapsed_secs = elapsed?.as_secs_f64();
+
+          Some(
+            (bytes.saturating_sub(prev_bytes) as f64 / elapsed_secs)
+              as u64,
+          )
+        });
+
+        Some(NetworkProcess {
+          pid,
+          name,
+          connection_count,
+          throughput,
+        })
+      })
+      .collect::<Vec<_>>();
+
+    // Avoid holding onto counters of processes that no longer have any
+    // connections.
+    let active_pids = processes
+      .iter()
+      .map(|process| process.pid)
+      .collect::<HashSet<_>>();
+
+    self
+      .prev_io_bytes
+      .retain(|pid, _| active_pids.contains(pid));
+
+    processes.sort_by(|a, b| {
+      b.throughput
+        .unwrap_or(0)
+        .cmp(&a.throughput.unwrap_or(0))
+        .then(b.connection_count.cmp(&a.connection_count))
+    });
+
+    processes.truncate(top_count);
+
+    NetworkConnections {
+      established_count: owner_pids.len(),
+      top_processes: processes,
+    }
+  }
+}
+
+/// Gets the owning PID of each established TCP connection (IPv4 and
+/// IPv6).
+#[cfg(target_os = "windows")]
+fn established_connections() -> Vec<Option<u32>> {
+  use windows::Win32::{
+    NetworkManagement::IpHelper::{
+      MIB_TCP6TABLE_OWNER_PID, MIB_TCPTABLE_OWNER_PID, MIB_TCP_STATE_ESTAB,
+    },
+    Networking::WinSock::{AF_INET, AF_INET6},
+  };
+
+  let mut owner_pids = Vec::new();
+  let established_state = MIB_TCP_STATE_ESTAB.0 as u32;
+
+  if let Some(buffer) = tcp_table(AF_INET.0 as u32) {
+    let table =
+      unsafe { &*(buffer.as_ptr() as *const MIB_TCPTABLE_OWNER_PID) };
+
+    let rows = unsafe {
+      std::slice::from_raw_parts(
+        table.table.as_ptr(),
+        table.dwNumEntries as usize,
+      )
+    };
+
+    owner_pids.extend(
+      rows
+        .iter()
+        .filter(|row| row.dwState == established_state)
+        .map(|row| Some(row.dwOwningPid)),
+    );
+  }
+
+  if let Some(buffer) = tcp_table(AF_INET6.0 as u32) {
+    let table =
+      unsafe { &*(buffer.as_ptr() as *const MIB_TCP6TABLE_OWNER_PID) };
+
+    let rows = unsafe {
+      std::slice::from_raw_parts(
+        table.table.as_ptr(),
+        table.dwNumEntries as usize,
+      )
+    };
+
+    owner_pids.extend(
+      rows
+        .iter()
+        .filter(|row| row.dwState == established_state)
+        .map(|row| Some(row.dwOwningPid)),
+    );
+  }
+
+  owner_pids
+}
+
+/// Gets the raw TCP table for the given address family.
+///
+/// A `u32` buffer is used to guarantee the alignment of the table.
+#[cfg(target_os = "windows")]
+fn tcp_table(address_family: u32) -> Option<Vec<u32>> {
+  use windows::Win32::{
+    Foundation::NO_ERROR,
+    NetworkManagement::IpHelper::{
+      GetExtendedTcpTable, TCP_TABLE_OWNER_PID_ALL,
+    },
+  };
+
+  let mut size = 0;
+
+  // The table can grow in between getting the required size and reading
+  // it, so retry a few times.
+  for _ in 0..3 {
+    let mut buffer = vec![0u32; size as usize / 4 + 1];
+
+    let res = unsafe {
+      GetExtendedTcpTable(
+        Some(buffer.as_mut_ptr() as *mut _),
+        &mut size,
+        false,
+        address_family,
+        TCP_TABLE_OWNER_PID_ALL,
+        0,
+      )
+    };
+
+    if res == NO_ERROR.0 {
+      return Some(buffer);
+    }
+  }
+
+  None
+}
+
+/// Gets the number of bytes transferred by the process that aren't
+/// reads or writes to disk, which includes network traffic.
+#[cfg(target_os = "windows")]
+fn process_io_bytes(pid: u32) -> Option<u64> {
+  use windows::Win32::{
+    Foundation::CloseHandle,
+    System::Threading::{
+      GetProcessIoCounters, OpenProcess, IO_COUNTERS,
+      PROCESS_QUERY_LIMITED_INFORMATION,
+    },
+  };
+
+  unsafe {
+    let handle =
+      OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+    let mut counters = IO_COUNTERS::default();
+    let res = GetProcessIoCounters(handle, &mut counters);
+    _ = CloseHandle(handle);
+    res.ok()?;
+
+    Some(counters.OtherTransferCount)
+  }
+}
+
+/// Gets the owning PID of each established TCP connection (IPv4 and
+/// IPv6).
+///
+/// Owners can only be resolved for processes whose file descriptors are
+/// readable by the current user.
+#[cfg(target_os = "linux")]
+fn established_connections() -> Vec<Option<u32>> {
+  let inode_pids = socket_inode_pids();
+
+  ["/proc/net/tcp", "/proc/net/tcp6"]
+    .iter()
+    .filter_map(|path| std::fs::read_to_string(path).ok())
+    .flat_map(|contents| {
+      contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+          let fields = line.split_whitespace().collect::<Vec<_>>();
+
+          // State `01` is `TCP_ESTABLISHED`.
+          if fields.get(3) != Some(&"01") {
+            return None;
+          }
+
+          Some(
+            fields
+              .get(9)
+              .and_then(|inode| inode.parse::<u64>().ok())
+              .and_then(|inode| inode_pids.get(&inode).copied()),
+          )
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+/// Maps socket inodes to the PID of the process that holds them.
+#[cfg(target_os = "linux")]
+fn socket_inode_pids() -> HashMap<u64, u32> {
+  let mut inode_pids = HashMap::new();
+
+  let Ok(proc_entries) = std::fs::read_dir("/proc") else {
+    return inode_pids;
+  };
+
+  for proc_entry in proc_entries.flatten() {
+    let Some(pid) = proc_entry
+      .file_name()
+      .to_str()
+      .and_then(|name| name.parse::<u32>().ok())
+    else {
+      continue;
+    };
+
+    // Reading the file descriptors fails for processes owned by other
+    // users, or for processes that have exited.
+    let Ok(fd_entries) = std::fs::read_dir(proc_entry.path().join("fd"))
+    else {
+      continue;
+    };
+
+    for fd_entry in fd_entries.flatten() {
+      let inode =
+        std::fs::read_link(fd_entry.path()).ok().and_then(|target| {
+          target
+            .to_str()?
+            .strip_prefix("socket:[")?
+            .strip_suffix(']')?
+            .parse::<u64>()
+            .ok()
+        });
+
+      if let Some(inode) = inode {
+        inode_pids.insert(inode, pid);
+      }
+    }
+  }
+
+  inode_pids
+}
+
+/// Gets the number of bytes read and written by the process that aren't
+/// reads or writes to disk, which includes network traffic.
+#[cfg(target_os = "linux")]
+fn process_io_bytes(pid: u32) -> Option<u64> {
+  let contents =
+    std::fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+
+  let counters = contents
+    .lines()
+    .filter_map(|line| {
+      let (key, value) = line.split_once(':')?;
+      Some((key, value.trim().parse::<u64>().ok()?))
+    })
+    .collect::<HashMap<_, _>>();
+
+  let total_bytes = counters.get("rchar")? + counters.get("wchar")?;
+  let disk_bytes =
+    counters.get("read_bytes")? + counters.get("write_bytes")?;
+
+  Some(total_bytes.saturating_sub(disk_bytes))
+}
+
+/// Gets established TCP connections via `netstat`. Owning processes
+/// aren't available without elevation.
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn established_connections() -> Vec<Option<u32>> {
+  let Ok(output) = std::process::Command::new("netstat")
+    .args(["-an", "-p", "tcp"])
+    .output()
+  else {
+    return Vec::new();
+  };
+
+  let established_count = String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .filter(|line| line.contains("ESTABLISHED"))
+    .count();
+
+  vec![None; established_count]
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn process_io_bytes(_pid: u32) -> Option<u64> {
+  None
+}
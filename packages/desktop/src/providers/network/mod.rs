@@ -1,4 +1,5 @@
 mod config;
+mod connections;
 mod provider;
 mod variables;
 mod wifi_hotspot;
@@ -1,36 +1,79 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use netdev::interface::get_interfaces;
-use sysinfo::Networks;
-use tokio::{sync::Mutex, task::AbortHandle};
+use sysinfo::{Networks, System};
+use tokio::{
+  sync::Mutex,
+  task::{self, AbortHandle},
+};
 
 use super::{
+  connections::ConnectionsSampler,
   wifi_hotspot::{default_gateway_wifi, WifiHotstop},
   InterfaceType, NetworkGateway, NetworkInterface, NetworkProviderConfig,
   NetworkTraffic, NetworkVariables,
 };
 use crate::providers::{
-  provider::IntervalProvider, variables::ProviderVariables,
+  connection_cost::connection_cost,
+  high_frequency::{spawn_sampler, SampleBuffer},
+  network_counters::NetworkCounters,
+  process_snapshot::ProcessSnapshot,
+  provider::{is_tracked, IntervalProvider},
+  retained_size::{RetainedSize, RetainedState},
+  sample_clock::SampleClock,
+  variables::ProviderVariables,
 };
 
 pub struct NetworkProvider {
   pub config: Arc<NetworkProviderConfig>,
   abort_handle: Option<AbortHandle>,
-  _state: Arc<Mutex<()>>,
-  netinfo: Arc<Mutex<Networks>>,
+  state: Arc<NetworkState>,
+}
+
+pub struct NetworkState {
+  netinfo: Arc<Mutex<NetworkCounters>>,
+  sysinfo: Arc<Mutex<System>>,
+  process_snapshot: Arc<ProcessSnapshot>,
+  tracked_access: Vec<String>,
+  connections_sampler: Mutex<ConnectionsSampler>,
+  samples: Option<Arc<SampleBuffer<NetworkTraffic>>>,
 }
 
 impl NetworkProvider {
   pub fn new(
     config: NetworkProviderConfig,
-    netinfo: Arc<Mutex<Networks>>,
+    netinfo: Arc<Mutex<NetworkCounters>>,
+    sysinfo: Arc<Mutex<System>>,
+    process_snapshot: Arc<ProcessSnapshot>,
+    tracked_access: Vec<String>,
   ) -> NetworkProvider {
+    let samples = config.high_frequency.as_ref().map(|hf_config| {
+      // Uses a separate `Networks`, so that sampling doesn't affect the
+      // traffic that's measured between refreshes.
+      let mut networks = Networks::new_with_refreshed_list();
+      let mut clock = SampleClock::started();
+      let sample_interval = hf_config.sample_interval();
+
+      spawn_sampler(hf_config, move || {
+        networks.refresh();
+        let elapsed = clock.tick(sample_interval)?;
+
+        Some(to_traffic(&networks, elapsed))
+      })
+    });
+
     NetworkProvider {
       config: Arc::new(config),
       abort_handle: None,
-      _state: Arc::new(Mutex::new(())),
-      netinfo,
+      state: Arc::new(NetworkState {
+        netinfo,
+        sysinfo,
+        process_snapshot,
+        tracked_access,
+        connections_sampler: Mutex::new(ConnectionsSampler::default()),
+        samples,
+      }),
     }
   }
 
@@ -89,14 +132,25 @@ impl NetworkProvider {
 #[async_trait]
 impl IntervalProvider for NetworkProvider {
   type Config = NetworkProviderConfig;
-  type State = Mutex<Networks>;
+  type State = NetworkState;
 
   fn config(&self) -> Arc<NetworkProviderConfig> {
     self.config.clone()
   }
 
-  fn state(&self) -> Arc<Mutex<Networks>> {
-    self.netinfo.clone()
+  fn state(&self) -> Arc<NetworkState> {
+    self.state.clone()
+  }
+
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(
+      self
+        .state
+        .samples
+        .iter()
+        .map(|samples| samples.clone() as Arc<dyn RetainedSize>)
+        .collect(),
+    )
   }
 
   fn abort_handle(&self) -> &Option<AbortHandle> {
@@ -109,13 +163,31 @@ impl IntervalProvider for NetworkProvider {
 
   async fn get_refreshed_variables(
     config: &NetworkProviderConfig,
-    netinfo: &Mutex<Networks>,
+    state: &NetworkState,
   ) -> anyhow::Result<ProviderVariables> {
-    let mut netinfo = netinfo.lock().await;
-    netinfo.refresh();
+    let mut netinfo = state.netinfo.lock().await;
+
+    let connections = match &config.connections {
+      Some(connections_config)
+        if is_tracked(&state.tracked_access, "connections") =>
+      {
+        let mut sysinfo = state.sysinfo.lock().await;
+
+        Some(state.connections_sampler.lock().await.sample(
+          connections_config,
+          config.refresh_interval,
+          &mut sysinfo,
+          &state.process_snapshot,
+        ))
+      }
+      _ => None,
+    };
 
     let interfaces = get_interfaces();
 
+    let connection_cost =
+      task::spawn_blocking(connection_cost).await.ok().flatten();
+
     let default_interface = netdev::get_default_interface().ok();
 
     let variables = NetworkVariables {
@@ -133,22 +205,39 @@ impl IntervalProvider for NetworkProvider {
         .iter()
         .map(Self::transform_interface)
         .collect(),
-      traffic: NetworkTraffic {
-        received: to_bytes_per_seconds(
-          get_network_down(&netinfo),
-          config.refresh_interval,
-        ),
-        transmitted: to_bytes_per_seconds(
-          get_network_up(&netinfo),
-          config.refresh_interval,
-        ),
-      },
+      traffic: network_traffic(&mut netinfo, config.refresh_interval),
+      connections,
+      connection_cost,
+      samples: state.samples.as_ref().map(|samples| samples.drain()),
     };
 
     Ok(ProviderVariables::Network(variables))
   }
 }
 
+/// Refreshes the shared counters and gets the total traffic across all
+/// interfaces in bytes per second since they were last refreshed.
+///
+/// `None` if there was a clock discontinuity since the last refresh
+/// (e.g. the machine resumed from sleep), since the traffic can't be
+/// attributed to a known timespan.
+pub fn network_traffic(
+  netinfo: &mut NetworkCounters,
+  refresh_interval: u64,
+) -> Option<NetworkTraffic> {
+  let (networks, elapsed) =
+    netinfo.refresh(Duration::from_millis(refresh_interval))?;
+
+  Some(to_traffic(networks, elapsed))
+}
+
+fn to_traffic(networks: &Networks, elapsed: Duration) -> NetworkTraffic {
+  NetworkTraffic {
+    received: to_bytes_per_second(get_network_down(networks), elapsed),
+    transmitted: to_bytes_per_second(get_network_up(networks), elapsed),
+  }
+}
+
 // Get the total network (down) usage
 fn get_network_down(req_net: &sysinfo::Networks) -> u64 {
   // Get the total bytes recieved by every network interface
@@ -171,6 +260,6 @@ fn get_network_up(req_net: &sysinfo::Networks) -> u64 {
   transmitted_total.iter().sum()
 }
 
-fn to_bytes_per_seconds(input_in_bytes: u64, timespan_in_ms: u64) -> u64 {
-  input_in_bytes / (timespan_in_ms / 1000)
+fn to_bytes_per_second(input_in_bytes: u64, timespan: Duration) -> u64 {
+  (input_in_bytes as f64 / timespan.as_secs_f64()) as u64
 }
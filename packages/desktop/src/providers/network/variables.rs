@@ -1,13 +1,46 @@
 use netdev::interface::InterfaceType as NdInterfaceType;
 use serde::Serialize;
 
+use crate::providers::{
+  connection_cost::ConnectionCost, high_frequency::TimedSample,
+};
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NetworkVariables {
   pub default_interface: Option<NetworkInterface>,
   pub default_gateway: Option<NetworkGateway>,
   pub interfaces: Vec<NetworkInterface>,
-  pub traffic: NetworkTraffic,
+  /// Traffic across all interfaces since the last refresh. `None` for
+  /// the first refresh after the machine resumes from sleep or the
+  /// clock is stepped.
+  pub traffic: Option<NetworkTraffic>,
+  pub connections: Option<NetworkConnections>,
+
+  /// Cost of the active internet connection. `None` if unknown.
+  pub connection_cost: Option<ConnectionCost>,
+
+  /// Traffic samples taken since the previous refresh. Only set if
+  /// `high_frequency` is enabled.
+  pub samples: Option<Vec<TimedSample<NetworkTraffic>>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConnections {
+  pub established_count: usize,
+  pub top_processes: Vec<NetworkProcess>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkProcess {
+  pub pid: u32,
+  pub name: String,
+  pub connection_count: usize,
+  /// Approximate bytes per second transferred by the process. `None` if
+  /// unavailable on the platform or on the first sample.
+  pub throughput: Option<u64>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use sysinfo::Networks;
+
+use super::sample_clock::SampleClock;
+
+/// Network interface counters shared between providers, along with when
+/// they were last refreshed.
+///
+/// Counters are deltas since the previous refresh by any provider, so
+/// rates have to be calculated from the time since that refresh rather
+/// than from the caller's own refresh interval.
+pub struct NetworkCounters {
+  networks: Networks,
+  clock: SampleClock,
+}
+
+impl Default for NetworkCounters {
+  fn default() -> Self {
+    Self {
+      networks: Networks::new_with_refreshed_list(),
+      clock: SampleClock::started(),
+    }
+  }
+}
+
+impl NetworkCounters {
+  /// Refreshes the counters. Gets them along with the time since the
+  /// previous refresh, or `None` if there was a clock discontinuity in
+  /// between.
+  ///
+  /// `expected_interval` is the caller's refresh interval.
+  pub fn refresh(
+    &mut self,
+    expected_interval: Duration,
+  ) -> Option<(&Networks, Duration)> {
+    self.networks.refresh();
+    let elapsed = self.clock.tick(expected_interval)?;
+
+    Some((&self.networks, elapsed))
+  }
+}
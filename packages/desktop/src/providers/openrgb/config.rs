@@ -0,0 +1,30 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::impl_interval_config;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "openrgb")]
+pub struct OpenRgbProviderConfig {
+  /// How often to refresh the device list. Reconnection is also attempted
+  /// on this interval if the connection is lost.
+  pub refresh_interval: u64,
+
+  /// Host of the OpenRGB SDK server.
+  #[serde(default = "default_host")]
+  pub host: String,
+
+  /// Port of the OpenRGB SDK server.
+  #[serde(default = "default_port")]
+  pub port: u16,
+}
+
+fn default_host() -> String {
+  "127.0.0.1".into()
+}
+
+fn default_port() -> u16 {
+  6742
+}
+
+impl_interval_config!(OpenRgbProviderConfig);
@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use openrgb::{data::Color, OpenRGB};
+use tokio::{net::TcpStream, time};
+
+use super::{OpenRgbDevice, OpenRgbZone};
+
+/// Max time to wait when connecting to the SDK server.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Connection to an OpenRGB SDK server, shared between the provider and
+/// the `set_led_color` command.
+///
+/// No connection is made until an `openrgb` provider sets the address,
+/// so the TCP connection is only ever opened when opted into.
+#[derive(Default)]
+pub struct OpenRgbConnection {
+  address: Option<(String, u16)>,
+  client: Option<OpenRGB<TcpStream>>,
+}
+
+impl OpenRgbConnection {
+  /// Sets the address of the SDK server. Reconnects on the next request
+  /// if the address changed.
+  pub fn set_address(&mut self, host: &str, port: u16) {
+    let address = (host.to_string(), port);
+
+    if self.address.as_ref() != Some(&address) {
+      self.address = Some(address);
+      self.client = None;
+    }
+  }
+
+  pub fn is_connected(&self) -> bool {
+    self.client.is_some()
+  }
+
+  /// Gets the connected client, connecting if needed.
+  async fn client(&mut self) -> anyhow::Result<&OpenRGB<TcpStream>> {
+    if self.client.is_none() {
+      let Some((host, port)) = &self.address else {
+        bail!(
+          "OpenRGB isn't enabled. Add an `openrgb` provider to use it."
+        );
+      };
+
+      let client = time::timeout(
+        CONNECT_TIMEOUT,
+        OpenRGB::connect_to((host.as_str(), *port)),
+      )
+      .await
+      .context("Timed out connecting to OpenRGB SDK server.")?
+      .with_context(|| {
+        format!(
+          "Failed to connect to OpenRGB SDK server at {}:{}. Check \
+           that the SDK server is running.",
+          host, port
+        )
+      })?;
+
+      client.set_name("Zebar").await?;
+      self.client = Some(client);
+    }
+
+    Ok(self.client.as_ref().unwrap())
+  }
+
+  /// Drops the connection if a request failed, so that the next request
+  /// reconnects.
+  fn disconnect_on_err<T>(
+    &mut self,
+    res: anyhow::Result<T>,
+  ) -> anyhow::Result<T> {
+    if res.is_err() {
+      self.client = None;
+    }
+
+    res
+  }
+
+  /// Gets connected devices and their zones.
+  pub async fn devices(&mut self) -> anyhow::Result<Vec<OpenRgbDevice>> {
+    let client = self.client().await?;
+    let res = Self::fetch_devices(client).await;
+    self.disconnect_on_err(res)
+  }
+
+  /// Sets all LEDs in a zone to the given color.
+  pub async fn set_zone_color(
+    &mut self,
+    device_id: u32,
+    zone_id: u32,
+    color: Color,
+  ) -> anyhow::Result<()> {
+    let client = self.client().await?;
+    let controller = client.get_controller(device_id).await;
+
+    let controller =
+      self.disconnect_on_err(controller.map_err(Into::into))?;
+
+    let Some(zone) = controller.zones.get(zone_id as usize) else {
+      bail!(
+        "No zone {} on OpenRGB device '{}'.",
+        zone_id,
+        controller.name
+      );
+    };
+
+    let res = self
+      .client()
+      .await?
+      .update_zone_leds(
+        device_id,
+        zone_id,
+        vec![color; zone.leds_count as usize],
+      )
+      .await;
+
+    self.disconnect_on_err(res.map_err(Into::into))
+  }
+
+  async fn fetch_devices(
+    client: &OpenRGB<TcpStream>,
+  ) -> anyhow::Result<Vec<OpenRgbDevice>> {
+    let count = client.get_controller_count().await?;
+    let mut devices = Vec::new();
+
+    for id in 0..count {
+      let controller = client.get_controller(id).await?;
+
+      devices.push(OpenRgbDevice {
+        id,
+        name: controller.name,
+        device_type: format!("{:?}", controller.r#type).to_lowercase(),
+        zones: controller
+          .zones
+          .into_iter()
+          .enumerate()
+          .map(|(zone_id, zone)| OpenRgbZone {
+            id: zone_id as u32,
+            name: zone.name,
+            led_count: zone.leds_count,
+          })
+          .collect(),
+      });
+    }
+
+    Ok(devices)
+  }
+}
+
+/// Parses a hex color (e.g. `#ff8800`).
+pub fn parse_hex_color(color: &str) -> anyhow::Result<Color> {
+  let hex = color.trim_start_matches('#');
+
+  let channel = |index: usize| {
+    hex
+      .get(index..index + 2)
+      .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+  };
+
+  match (hex.len(), channel(0), channel(2), channel(4)) {
+    (6, Some(r), Some(g), Some(b)) => Ok(Color::new(r, g, b)),
+    _ => bail!(
+      "Invalid color '{}'. Expected a hex color like `#ff8800`.",
+      color
+    ),
+  }
+}
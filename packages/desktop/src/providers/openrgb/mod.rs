@@ -0,0 +1,9 @@
+mod config;
+mod connection;
+mod provider;
+mod variables;
+
+pub use config::*;
+pub use connection::*;
+pub use provider::*;
+pub use variables::*;
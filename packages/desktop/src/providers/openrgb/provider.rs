@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::{sync::Mutex, task::AbortHandle};
+
+use super::{OpenRgbConnection, OpenRgbProviderConfig, OpenRgbVariables};
+use crate::providers::{
+  provider::IntervalProvider, variables::ProviderVariables,
+};
+
+pub struct OpenRgbProvider {
+  pub config: Arc<OpenRgbProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  connection: Arc<Mutex<OpenRgbConnection>>,
+}
+
+impl OpenRgbProvider {
+  pub fn new(
+    config: OpenRgbProviderConfig,
+    connection: Arc<Mutex<OpenRgbConnection>>,
+  ) -> OpenRgbProvider {
+    OpenRgbProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      connection,
+    }
+  }
+}
+
+#[async_trait]
+impl IntervalProvider for OpenRgbProvider {
+  type Config = OpenRgbProviderConfig;
+  type State = Mutex<OpenRgbConnection>;
+
+  fn config(&self) -> Arc<OpenRgbProviderConfig> {
+    self.config.clone()
+  }
+
+  fn state(&self) -> Arc<Mutex<OpenRgbConnection>> {
+    self.connection.clone()
+  }
+
+  fn abort_handle(&self) -> &Option<AbortHandle> {
+    &self.abort_handle
+  }
+
+  fn set_abort_handle(&mut self, abort_handle: AbortHandle) {
+    self.abort_handle = Some(abort_handle)
+  }
+
+  async fn get_refreshed_variables(
+    config: &OpenRgbProviderConfig,
+    connection: &Mutex<OpenRgbConnection>,
+  ) -> anyhow::Result<ProviderVariables> {
+    let mut connection = connection.lock().await;
+    connection.set_address(&config.host, config.port);
+
+    // Connection errors are reported in the output rather than as a
+    // provider error, so that widgets can show the connection state.
+    // Reconnection is attempted on the next refresh.
+    let variables = match connection.devices().await {
+      Ok(devices) => OpenRgbVariables {
+        is_connected: true,
+        connection_error: None,
+        devices,
+      },
+      Err(err) => OpenRgbVariables {
+        is_connected: connection.is_connected(),
+        connection_error: Some(err.to_string()),
+        devices: Vec::new(),
+      },
+    };
+
+    Ok(ProviderVariables::OpenRgb(variables))
+  }
+}
@@ -0,0 +1,33 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRgbVariables {
+  pub is_connected: bool,
+
+  /// Reason the connection to the SDK server failed or was lost.
+  pub connection_error: Option<String>,
+
+  pub devices: Vec<OpenRgbDevice>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRgbDevice {
+  /// Index of the device in OpenRGB, used for setting colors.
+  pub id: u32,
+  pub name: String,
+
+  /// Type of device (e.g. `keyboard` or `mouse`).
+  pub device_type: String,
+  pub zones: Vec<OpenRgbZone>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRgbZone {
+  /// Index of the zone within its device, used for setting colors.
+  pub id: u32,
+  pub name: String,
+  pub led_count: u32,
+}
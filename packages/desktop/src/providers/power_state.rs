@@ -0,0 +1,96 @@
+use std::sync::{
+  atomic::{AtomicBool, AtomicU64, Ordering},
+  Arc,
+};
+
+use tokio::sync::Notify;
+
+/// Whether energy saver was on at the last check.
+static ENERGY_SAVER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Factor that refresh intervals of interval providers are multiplied by
+/// while energy saver is on.
+static ENERGY_SAVER_MULTIPLIER: AtomicU64 = AtomicU64::new(1);
+
+/// Records whether energy saver is on. Returns the previous value.
+pub fn set_energy_saver_active(is_active: bool) -> bool {
+  ENERGY_SAVER_ACTIVE.swap(is_active, Ordering::Relaxed)
+}
+
+/// Sets the factor that refresh intervals are multiplied by while energy
+/// saver is on.
+pub fn set_energy_saver_multiplier(multiplier: u64) {
+  ENERGY_SAVER_MULTIPLIER.store(multiplier.max(1), Ordering::Relaxed);
+}
+
+/// Factor that refresh intervals of interval providers are currently
+/// multiplied by.
+pub fn interval_multiplier() -> u64 {
+  match ENERGY_SAVER_ACTIVE.load(Ordering::Relaxed) {
+    true => ENERGY_SAVER_MULTIPLIER.load(Ordering::Relaxed),
+    false => 1,
+  }
+}
+
+/// Counts the scheduled refreshes of a provider, so that only every n-th
+/// one runs while energy saver is on.
+#[derive(Debug, Default)]
+pub struct EnergySaverTicks {
+  /// Number of refreshes skipped since the last run. `None` until the
+  /// first run, which always happens.
+  skipped: Option<u64>,
+}
+
+impl EnergySaverTicks {
+  /// Whether the scheduled refresh should run.
+  pub fn should_run(&mut self) -> bool {
+    let Some(skipped) = self.skipped else {
+      self.skipped = Some(0);
+      return true;
+    };
+
+    let should_run = skipped + 1 >= interval_multiplier();
+    self.skipped = Some(if should_run { 0 } else { skipped + 1 });
+    should_run
+  }
+}
+
+/// Gets whether energy saver (battery saver before Windows 11 24H2) is
+/// on. `None` on other platforms, or if the OS doesn't report it.
+///
+/// Can block briefly, so it should be called off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn energy_saver_active() -> Option<bool> {
+  use windows::System::Power::{EnergySaverStatus, PowerManager};
+
+  let status = PowerManager::EnergySaverStatus().ok()?;
+  Some(status == EnergySaverStatus::On)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn energy_saver_active() -> Option<bool> {
+  None
+}
+
+/// Notifies the given `Notify` whenever energy saver is turned on or
+/// off. Returns whether the OS supports change notifications, in which
+/// case the subscription lasts for the rest of the app's lifetime.
+#[cfg(target_os = "windows")]
+pub fn subscribe_energy_saver(changed: Arc<Notify>) -> bool {
+  use windows::{
+    core::IInspectable, Foundation::EventHandler,
+    System::Power::PowerManager,
+  };
+
+  let handler = EventHandler::<IInspectable>::new(move |_, _| {
+    changed.notify_waiters();
+    Ok(())
+  });
+
+  PowerManager::EnergySaverStatusChanged(&handler).is_ok()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn subscribe_energy_saver(_: Arc<Notify>) -> bool {
+  false
+}
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use serde_json::{Map, Number, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::{debug, warn};
+
+use super::{
+  config::ProviderConfig, dependencies, provider_manager::ProviderManager,
+};
+use crate::{
+  permissions::{Capability, PermissionsMap},
+  secrets::{self, SecretsStore},
+};
+
+/// Duration that a pre-created provider is kept for without any window
+/// subscribing to it.
+pub const PREWARM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pre-creates the providers declared in a window's config, so that
+/// their first outputs are often cached by the time the frontend
+/// subscribes.
+///
+/// Providers that can't be created on the Rust side (e.g. ones that
+/// reference other providers, or frontend-only types like `date`) are
+/// skipped and left to the frontend, as are ones that capture audio.
+pub async fn prewarm_window_providers<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  window_label: &str,
+  configs: &[Value],
+) {
+  for config in configs {
+    let config = normalize(config);
+    let config_hash = canonical_hash(&config);
+
+    if !dependencies::find_refs(&config).is_empty() {
+      continue;
+    }
+
+    let secrets_store = app_handle.state::<SecretsStore>();
+
+    let res = secrets::resolve_provider_refs(&config, &secrets_store)
+      .and_then(|config| {
+        serde_json::from_value::<ProviderConfig>(config)
+          .map_err(anyhow::Error::from)
+      });
+
+    let config = match res {
+      Ok(config) => config,
+      Err(err) => {
+        debug!(
+          "Not pre-creating provider {} of window '{}': {}",
+          config_hash,
+          window_label,
+          secrets::redact_error(err)
+        );
+        continue;
+      }
+    };
+
+    if !is_permitted(app_handle, window_label, &config) {
+      continue;
+    }
+
+    // Opening an input device shows the OS privacy indicator, which
+    // shouldn't happen unless a window is actually listening.
+    if config.type_config.captures_audio() {
+      continue;
+    }
+
+    if let Err(err) = app_handle
+      .state::<ProviderManager>()
+      .prewarm(app_handle, config_hash, config)
+      .await
+    {
+      warn!("Error pre-creating provider: {:?}", err);
+    }
+  }
+}
+
+/// Whether the window has the permissions that the provider requires.
+/// Same checks as when the frontend subscribes.
+fn is_permitted<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  window_label: &str,
+  config: &ProviderConfig,
+) -> bool {
+  let permissions_map = app_handle.state::<PermissionsMap>();
+
+  let has_hosts = config.type_config.http_hosts().iter().all(|host| {
+    permissions_map
+      .check(window_label, Capability::HttpHost(host))
+      .is_ok()
+  });
+
+  let has_paths =
+    config.type_config.filesystem_paths().iter().all(|path| {
+      permissions_map
+        .check(window_label, Capability::FilesystemPath(path))
+        .is_ok()
+    });
+
+  has_hosts
+    && has_paths
+    && (!config.type_config.reads_clipboard()
+      || permissions_map
+        .check(window_label, Capability::Clipboard)
+        .is_ok())
+}
+
+/// Hash of a provider config as written in the user config. Keys are
+/// sorted, so that the hash matches the one computed by the frontend.
+pub fn canonical_hash(config: &Value) -> String {
+  normalize(config).to_string()
+}
+
+/// Expands the string shorthand of a provider config (e.g. `cpu`) into
+/// an object, and converts integral floats into integers, since
+/// JavaScript doesn't distinguish between them.
+fn normalize(config: &Value) -> Value {
+  match config {
+    Value::String(provider_type) => Value::Object(Map::from_iter([(
+      "type".to_string(),
+      Value::String(provider_type.clone()),
+    )])),
+    _ => normalize_numbers(config),
+  }
+}
+
+fn normalize_numbers(value: &Value) -> Value {
+  match value {
+    Value::Number(number) => number
+      .as_f64()
+      .filter(|float| {
+        float.fract() == 0.0 && float.abs() < i64::MAX as f64
+      })
+      .filter(|_| number.is_f64())
+      .map(|float| Value::Number(Number::from(float as i64)))
+      .unwrap_or_else(|| value.clone()),
+    Value::Array(items) => {
+      Value::Array(items.iter().map(normalize_numbers).collect())
+    }
+    Value::Object(map) => Value::Object(
+      map
+        .iter()
+        .map(|(key, value)| (key.clone(), normalize_numbers(value)))
+        .collect(),
+    ),
+    _ => value.clone(),
+  }
+}
@@ -0,0 +1,33 @@
+use std::{
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use sysinfo::System;
+
+/// Process lists refreshed within this duration are reused.
+const MAX_SNAPSHOT_AGE: Duration = Duration::from_secs(1);
+
+/// Tracks when the process list in the shared `System` was last
+/// refreshed, so that providers that need processes at around the same
+/// time only enumerate them once.
+#[derive(Debug, Default)]
+pub struct ProcessSnapshot {
+  last_refresh: Mutex<Option<Instant>>,
+}
+
+impl ProcessSnapshot {
+  /// Refreshes the process list unless it was recently refreshed by
+  /// another provider.
+  pub fn refresh_if_stale(&self, sysinfo: &mut System) {
+    let mut last_refresh = self.last_refresh.lock().unwrap();
+
+    let is_stale = last_refresh
+      .map_or(true, |timestamp| timestamp.elapsed() > MAX_SNAPSHOT_AGE);
+
+    if is_stale {
+      sysinfo.refresh_processes();
+      *last_refresh = Some(Instant::now());
+    }
+  }
+}
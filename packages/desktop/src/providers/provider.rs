@@ -1,13 +1,23 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+  sync::Arc,
+  time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use tokio::{
   sync::mpsc::Sender,
   task::{self, AbortHandle},
-  time,
+  time::{self, MissedTickBehavior},
 };
+use tracing::{info, warn};
 
-use super::{provider_ref::ProviderOutput, variables::ProviderVariables};
+use super::{
+  http_client::{HttpClient, MeteredTicks},
+  power_state::EnergySaverTicks,
+  provider_ref::ProviderOutput,
+  retained_size::RetainedState,
+  variables::ProviderVariables,
+};
 
 #[async_trait]
 pub trait Provider {
@@ -26,12 +36,21 @@ pub trait Provider {
   );
 
   /// Callback for when the provider is stopped.
+  ///
+  /// Any in-flight `on_start` or `on_refresh` call has been dropped by
+  /// this point, so cleanup shouldn't rely on it having completed.
   async fn on_stop(&mut self);
 
   /// Minimum interval between refreshes.
   ///
   /// Affects how the provider output is cached.
   fn min_refresh_interval(&self) -> Option<Duration>;
+
+  /// State that the provider retains between runs (e.g. history
+  /// buffers and caches), for memory accounting.
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::default()
+  }
 }
 
 #[async_trait]
@@ -44,6 +63,19 @@ pub trait IntervalProvider {
     Some(Duration::from_secs(2))
   }
 
+  /// Client that the provider makes network requests with. Refreshes of
+  /// providers with a client are throttled or paused while the
+  /// connection is metered.
+  fn http_client(&self) -> Option<HttpClient> {
+    None
+  }
+
+  /// State that the provider retains between runs, for memory
+  /// accounting.
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::default()
+  }
+
   fn config(&self) -> Arc<Self::Config>;
 
   fn state(&self) -> Arc<Self::State>;
@@ -64,6 +96,10 @@ impl<T: IntervalProvider + Send> Provider for T {
     T::min_refresh_interval(self)
   }
 
+  fn retained_state(&self) -> RetainedState {
+    T::retained_state(self)
+  }
+
   async fn on_start(
     &mut self,
     config_hash: &str,
@@ -72,23 +108,66 @@ impl<T: IntervalProvider + Send> Provider for T {
     let config = self.config();
     let state = self.state();
     let config_hash = config_hash.to_string();
+    let http_client = self.http_client();
 
     let interval_task = task::spawn(async move {
-      let mut interval =
-        time::interval(Duration::from_millis(config.refresh_interval()));
+      let refresh_interval =
+        Duration::from_millis(config.refresh_interval());
+
+      let mut interval = time::interval(refresh_interval);
+
+      // Ticks missed while a run overruns the interval are skipped,
+      // rather than being fired back-to-back once it completes.
+      interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+      let mut metered_ticks = MeteredTicks::default();
+      let mut energy_saver_ticks = EnergySaverTicks::default();
+      let mut is_overrunning = false;
 
       loop {
         // The first tick fires immediately.
         interval.tick().await;
 
-        _ = emit_output_tx
-          .send(ProviderOutput {
-            config_hash: config_hash.clone(),
-            variables: T::get_refreshed_variables(&config, &state)
-              .await
-              .into(),
-          })
-          .await;
+        if !energy_saver_ticks.should_run() {
+          continue;
+        }
+
+        if let Some(http_client) = &http_client {
+          if !metered_ticks.should_run(http_client) {
+            continue;
+          }
+        }
+
+        let output = timed_output::<T>(
+          &config_hash,
+          &config,
+          &state,
+          http_client.as_ref(),
+        )
+        .await;
+
+        let run_duration = output.run_duration.unwrap_or_default();
+        let was_overrunning = is_overrunning;
+        is_overrunning = run_duration > refresh_interval;
+
+        // Only warn when the provider starts overrunning, rather than on
+        // every tick.
+        if is_overrunning && !was_overrunning {
+          warn!(
+            "Provider {} took {}ms, which overruns its refresh interval of \
+            {}ms. Skipping missed ticks.",
+            config_hash,
+            run_duration.as_millis(),
+            refresh_interval.as_millis()
+          );
+        } else if !is_overrunning && was_overrunning {
+          info!(
+            "Provider {} no longer overruns its refresh interval.",
+            config_hash
+          );
+        }
+
+        _ = emit_output_tx.send(output).await;
       }
     });
 
@@ -101,19 +180,19 @@ impl<T: IntervalProvider + Send> Provider for T {
     config_hash: &str,
     emit_output_tx: Sender<ProviderOutput>,
   ) {
-    _ = emit_output_tx
-      .send(ProviderOutput {
-        config_hash: config_hash.to_string(),
-        variables: T::get_refreshed_variables(
-          &self.config(),
-          &self.state(),
-        )
-        .await
-        .into(),
-      })
-      .await;
+    let output = timed_output::<T>(
+      config_hash,
+      &self.config(),
+      &self.state(),
+      self.http_client().as_ref(),
+    )
+    .await;
+
+    _ = emit_output_tx.send(output).await;
   }
 
+  /// Aborts the interval task, which drops its in-flight run (and any
+  /// requests it's making) at the next await point.
   async fn on_stop(&mut self) {
     if let Some(handle) = &self.abort_handle() {
       handle.abort();
@@ -121,6 +200,47 @@ impl<T: IntervalProvider + Send> Provider for T {
   }
 }
 
+/// Runs the interval provider once and records how long it took.
+///
+/// Runs of providers with a client are bounded as network lookups.
+async fn timed_output<T: IntervalProvider>(
+  config_hash: &str,
+  config: &T::Config,
+  state: &T::State,
+  http_client: Option<&HttpClient>,
+) -> ProviderOutput {
+  let start = Instant::now();
+  let refresh = T::get_refreshed_variables(config, state);
+
+  let variables = match http_client {
+    Some(http_client) => http_client.lookup(refresh).await,
+    None => refresh.await,
+  };
+
+  ProviderOutput {
+    config_hash: config_hash.to_string(),
+    variables: variables.into(),
+    computed: None,
+    last_updated: None,
+    last_updated_relative: None,
+    run_duration: Some(start.elapsed()),
+    type_name: None,
+  }
+}
+
+/// Whether a field of the provider output is accessed by the frontend.
+///
+/// An empty list of tracked fields means that all fields are tracked.
+pub fn is_tracked(tracked_access: &[String], field: &str) -> bool {
+  tracked_access.is_empty()
+    || tracked_access.iter().any(|tracked| {
+      tracked == field
+        || tracked
+          .strip_prefix(field)
+          .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
 /// Require interval providers to have a refresh interval in their config.
 pub trait IntervalConfig {
   fn refresh_interval(&self) -> u64;
@@ -138,3 +258,112 @@ macro_rules! impl_interval_config {
     }
   };
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Mutex;
+
+  use anyhow::bail;
+  use tokio::sync::mpsc;
+
+  use super::*;
+
+  struct SlowConfig {
+    refresh_interval: u64,
+
+    /// How long the first run takes. Later runs complete right away.
+    first_run_time: Duration,
+  }
+
+  impl IntervalConfig for SlowConfig {
+    fn refresh_interval(&self) -> u64 {
+      self.refresh_interval
+    }
+  }
+
+  /// Provider whose first run overruns its refresh interval. Records
+  /// when each run starts.
+  struct SlowProvider {
+    config: Arc<SlowConfig>,
+    run_starts: Arc<Mutex<Vec<time::Instant>>>,
+    abort_handle: Option<AbortHandle>,
+  }
+
+  #[async_trait]
+  impl IntervalProvider for SlowProvider {
+    type Config = SlowConfig;
+    type State = Mutex<Vec<time::Instant>>;
+
+    fn config(&self) -> Arc<SlowConfig> {
+      self.config.clone()
+    }
+
+    fn state(&self) -> Arc<Self::State> {
+      self.run_starts.clone()
+    }
+
+    fn abort_handle(&self) -> &Option<AbortHandle> {
+      &self.abort_handle
+    }
+
+    fn set_abort_handle(&mut self, abort_handle: AbortHandle) {
+      self.abort_handle = Some(abort_handle);
+    }
+
+    async fn get_refreshed_variables(
+      config: &SlowConfig,
+      run_starts: &Self::State,
+    ) -> anyhow::Result<ProviderVariables> {
+      let is_first_run = {
+        let mut run_starts = run_starts.lock().unwrap();
+        run_starts.push(time::Instant::now());
+        run_starts.len() == 1
+      };
+
+      if is_first_run {
+        time::sleep(config.first_run_time).await;
+      }
+
+      bail!("No variables.")
+    }
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn overrunning_provider_skips_missed_ticks() {
+    let run_starts = Arc::new(Mutex::new(Vec::new()));
+    let (emit_output_tx, mut emit_output_rx) = mpsc::channel(16);
+
+    let mut provider = SlowProvider {
+      config: Arc::new(SlowConfig {
+        refresh_interval: 1000,
+        first_run_time: Duration::from_millis(3500),
+      }),
+      run_starts: run_starts.clone(),
+      abort_handle: None,
+    };
+
+    let start = time::Instant::now();
+
+    task::spawn(async move {
+      provider.on_start("hash", emit_output_tx).await;
+    });
+
+    task::spawn(
+      async move { while emit_output_rx.recv().await.is_some() {} },
+    );
+
+    time::sleep(Duration::from_millis(6500)).await;
+
+    // Only one of the three ticks missed during the first run is fired
+    // late. The rest are skipped rather than fired back-to-back, and
+    // the schedule continues at the next tick.
+    let run_starts = run_starts
+      .lock()
+      .unwrap()
+      .iter()
+      .map(|run_start| (*run_start - start).as_millis())
+      .collect::<Vec<_>>();
+
+    assert_eq!(run_starts, [0, 3500, 4000, 5000, 6000]);
+  }
+}
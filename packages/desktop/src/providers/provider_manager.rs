@@ -1,55 +1,268 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+  collections::HashMap,
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, RwLock,
+  },
+  time::{Duration, UNIX_EPOCH},
+};
 
-use sysinfo::{Networks, System};
-use tauri::{App, AppHandle, Emitter, Manager, Runtime};
+use anyhow::{bail, Context};
+use chrono::Local;
+use serde::Serialize;
+use serde_json::Value;
+use sysinfo::System;
+use tauri::{App, AppHandle, Emitter, EventTarget, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
 use tokio::{
   sync::{
     mpsc::{self},
-    Mutex,
+    Mutex, Notify,
   },
-  task,
+  task, time,
 };
-use tracing::{info, warn};
+use tracing::{debug, error, info, warn};
 
+#[cfg(feature = "weather")]
+use super::geocoding::GeocodingCache;
+#[cfg(feature = "openrgb")]
+use super::openrgb::{parse_hex_color, OpenRgbConnection};
 use super::{
+  actions::ProviderActions,
   config::ProviderConfig,
-  provider_ref::{ProviderOutput, ProviderRef},
+  connection_cost::connection_cost,
+  dependencies::{
+    creates_cycle, has_changed, output_value, resolve_source, with_values,
+    ConfigRef,
+  },
+  emission_tracker::{Emission, EmissionStats, EmissionTracker},
+  emit_stats::{EmitStats, EMIT_STATS_INTERVAL},
+  http_client::HttpClient,
+  last_updated::{format_relative, now_millis, RELATIVE_TICK_INTERVAL},
+  network_counters::NetworkCounters,
+  power_state,
+  prewarm::PREWARM_TIMEOUT,
+  process_snapshot::ProcessSnapshot,
+  provider_ref::{jittered_delay, ProviderOutput, ProviderRef},
+  redaction::Redaction,
+  retained_size::{json_size, RETAINED_SIZE_INTERVAL},
+  run_timings::SlowProviders,
+  schedule::Scheduler,
+  self_monitor::{self, WindowBoundsMap},
+  state::{StateChannels, StateCommand},
+  subscriptions::{ProviderSubscription, WindowSubscriptions},
+};
+use crate::{
+  locale::Locale,
+  secrets,
+  user_config::{GlobalConfig, MeteredBehavior},
+  util::thread_priority::spawn_high_priority,
 };
 
+/// How often to check whether the active connection is metered.
+const METERED_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often to check whether energy saver is on, in case a change
+/// notification is missed or unsupported.
+const ENERGY_SAVER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Initializes `ProviderManager` in Tauri state.
-pub fn init_provider_manager<R: Runtime>(app: &mut App<R>) {
-  let mut manager = ProviderManager::new();
+pub fn init_provider_manager<R: Runtime>(
+  app: &mut App<R>,
+  global_config: &GlobalConfig,
+) {
+  let data_dir = app.path().app_data_dir().ok();
+  let mut manager = ProviderManager::new(data_dir, global_config);
   manager.start(app.handle());
   app.manage(manager);
 }
 
+/// Desktop notification requested by a provider.
+#[derive(Debug, Clone)]
+pub struct ProviderNotification {
+  pub title: String,
+  pub body: String,
+}
+
 /// State shared between providers.
 pub struct SharedProviderState {
   pub sysinfo: Arc<Mutex<System>>,
-  pub netinfo: Arc<Mutex<Networks>>,
+  pub netinfo: Arc<Mutex<NetworkCounters>>,
+  #[cfg(feature = "weather")]
+  pub geocoding_cache: Arc<Mutex<GeocodingCache>>,
+
+  /// Client for all outbound requests from providers. Enforces offline
+  /// mode.
+  pub http_client: HttpClient,
+  pub process_snapshot: Arc<ProcessSnapshot>,
+  pub notification_tx: mpsc::UnboundedSender<ProviderNotification>,
+
+  /// App data directory, used by providers that persist state to disk.
+  pub data_dir: Option<PathBuf>,
+
+  /// Notified when a drive is ejected, so that the drive list can be
+  /// updated immediately.
+  pub drives_changed: Arc<Notify>,
+
+  /// Notified when energy saver is turned on or off.
+  pub energy_saver_changed: Arc<Notify>,
+
+  /// Connection to the OpenRGB SDK server. Only connects once an
+  /// `openrgb` provider is created.
+  #[cfg(feature = "openrgb")]
+  pub openrgb: Arc<Mutex<OpenRgbConnection>>,
+
+  /// Channels for mutating the documents of `state` providers.
+  pub state_channels: StateChannels,
+
+  /// Actions of the running providers, invoked via
+  /// `call_provider_action`.
+  pub actions: ProviderActions,
+}
+
+/// Provider whose config references the output of other providers.
+struct DependentProvider {
+  /// Raw config, which is resolved into a `ProviderConfig` once the
+  /// referenced values are available.
+  config: Value,
+  refs: Vec<ConfigRef>,
+
+  /// Config hash of the source provider of each reference.
+  sources: Vec<String>,
+  tracked_access: Vec<String>,
+
+  /// Values that the running provider was started with. `None` until
+  /// all referenced values are available.
+  values: Option<Vec<Value>>,
 }
 
 /// Manages the creation and cleanup of providers.
 pub struct ProviderManager {
   emit_output_tx: mpsc::Sender<ProviderOutput>,
   emit_output_rx: Option<mpsc::Receiver<ProviderOutput>>,
+  notification_rx: Option<mpsc::UnboundedReceiver<ProviderNotification>>,
   providers: Arc<Mutex<HashMap<String, ProviderRef>>>,
   shared_state: SharedProviderState,
+
+  /// Providers that depend on the output of other providers, keyed by
+  /// config hash.
+  dependents: Arc<Mutex<HashMap<String, DependentProvider>>>,
+
+  /// Providers that each window is subscribed to. Also used to only
+  /// emit outputs to the windows that are subscribed to them.
+  subscriptions: Arc<Mutex<WindowSubscriptions>>,
+
+  /// Counts of emitted outputs, which are logged periodically.
+  emit_stats: Arc<EmitStats>,
+
+  /// Sequence numbers and delivery counts of each provider's emissions.
+  emission_tracker: Arc<EmissionTracker>,
+
+  /// Providers with a `schedule`, which are refreshed when it triggers.
+  scheduler: Arc<Mutex<Scheduler>>,
+
+  /// Bounds of open windows, for determining whether a window is on the
+  /// monitor that a window manager provider reports as focused.
+  window_bounds: Arc<WindowBoundsMap>,
+
+  /// Default start delay and jitter of providers in milliseconds.
+  start_delay: AtomicU64,
+  jitter: AtomicU64,
+
+  /// Whether to emit outputs from a dedicated high priority thread.
+  high_priority_emit: bool,
+
+  /// Whether to include the relative age of outputs, which is kept up to
+  /// date by re-emitting cached outputs on a shared tick.
+  last_updated_relative: Arc<AtomicBool>,
+
+  /// Locale that relative ages are formatted in.
+  locale: Arc<RwLock<Locale>>,
+
+  /// Duration in milliseconds after which a provider run is logged as
+  /// slow. 0 if disabled.
+  slow_provider_threshold: Arc<AtomicU64>,
+
+  /// Retained size in megabytes above which a provider is logged. 0 if
+  /// disabled.
+  memory_cap: Arc<AtomicU64>,
+
+  /// Whether to clear the caches of providers over `memory_cap`.
+  evict_over_memory_cap: Arc<AtomicBool>,
+
+  /// Fields that are masked in emitted outputs while redaction is
+  /// enabled.
+  redaction: Arc<Redaction>,
 }
 
 impl ProviderManager {
-  pub fn new() -> Self {
+  pub fn new(
+    data_dir: Option<PathBuf>,
+    global_config: &GlobalConfig,
+  ) -> Self {
     let (emit_output_tx, emit_output_rx) =
       mpsc::channel::<ProviderOutput>(1);
 
+    let (notification_tx, notification_rx) =
+      mpsc::unbounded_channel::<ProviderNotification>();
+
+    let http_client = HttpClient::new(global_config.offline_mode);
+    http_client.set_metered_behavior(
+      global_config.metered_behavior,
+      global_config.metered_interval_multiplier,
+    );
+
+    power_state::set_energy_saver_multiplier(
+      global_config.energy_saver_interval_multiplier,
+    );
+
     Self {
       emit_output_tx,
       emit_output_rx: Some(emit_output_rx),
+      notification_rx: Some(notification_rx),
       providers: Arc::new(Mutex::new(HashMap::new())),
+      dependents: Arc::new(Mutex::new(HashMap::new())),
+      subscriptions: Arc::new(Mutex::new(WindowSubscriptions::default())),
+      emit_stats: Arc::new(EmitStats::default()),
+      emission_tracker: Arc::new(EmissionTracker::default()),
+      scheduler: Arc::new(Mutex::new(Scheduler::default())),
+      window_bounds: Arc::new(WindowBoundsMap::default()),
       shared_state: SharedProviderState {
         sysinfo: Arc::new(Mutex::new(System::new_all())),
-        netinfo: Arc::new(Mutex::new(Networks::new_with_refreshed_list())),
+        netinfo: Arc::new(Mutex::new(NetworkCounters::default())),
+        #[cfg(feature = "weather")]
+        geocoding_cache: Arc::new(Mutex::new(GeocodingCache::new(
+          data_dir.clone(),
+        ))),
+        http_client,
+        process_snapshot: Arc::new(ProcessSnapshot::default()),
+        notification_tx,
+        data_dir,
+        drives_changed: Arc::new(Notify::new()),
+        energy_saver_changed: Arc::new(Notify::new()),
+        #[cfg(feature = "openrgb")]
+        openrgb: Arc::new(Mutex::new(OpenRgbConnection::default())),
+        state_channels: StateChannels::default(),
+        actions: ProviderActions::default(),
       },
+      start_delay: AtomicU64::new(global_config.provider_start_delay),
+      jitter: AtomicU64::new(global_config.provider_jitter),
+      high_priority_emit: global_config.high_priority_emit,
+      last_updated_relative: Arc::new(AtomicBool::new(
+        global_config.last_updated_relative,
+      )),
+      locale: Arc::new(RwLock::new(Locale::resolve(global_config.locale))),
+      slow_provider_threshold: Arc::new(AtomicU64::new(
+        global_config.slow_provider_threshold,
+      )),
+      memory_cap: Arc::new(AtomicU64::new(
+        global_config.provider_memory_cap,
+      )),
+      evict_over_memory_cap: Arc::new(AtomicBool::new(
+        global_config.evict_over_memory_cap,
+      )),
+      redaction: Arc::new(Redaction::new(&global_config.redaction)),
     }
   }
 
@@ -57,20 +270,86 @@ impl ProviderManager {
   /// clients.
   pub fn start<R: Runtime>(&mut self, app_handle: &AppHandle<R>) {
     let mut emit_output_rx = self.emit_output_rx.take().unwrap();
+    let mut notification_rx = self.notification_rx.take().unwrap();
     let providers = self.providers.clone();
     let app_handle = app_handle.clone();
+    let notification_app_handle = app_handle.clone();
+    let last_updated_relative = self.last_updated_relative.clone();
+    let locale = self.locale.clone();
+    let subscriptions = self.subscriptions.clone();
+    let emit_stats = self.emit_stats.clone();
+    let emission_tracker = self.emission_tracker.clone();
+    let window_bounds = self.window_bounds.clone();
+    let redaction = self.redaction.clone();
+    let slow_provider_threshold = self.slow_provider_threshold.clone();
+    let dependents = self.dependents.clone();
+
+    self.start_relative_tick(&app_handle);
+    self.start_emit_stats_log();
+    self.start_metered_watch();
+    self.start_energy_saver_watch();
+    self.start_memory_watchdog();
+    self.start_scheduler();
 
     task::spawn(async move {
-      while let Some(output) = emit_output_rx.recv().await {
+      while let Some(notification) = notification_rx.recv().await {
+        let res = notification_app_handle
+          .notification()
+          .builder()
+          .title(notification.title)
+          .body(notification.body)
+          .show();
+
+        if let Err(err) = res {
+          warn!("Error showing provider notification: {:?}", err);
+        }
+      }
+    });
+
+    let emit_task = async move {
+      let mut slow_providers = SlowProviders::default();
+
+      while let Some(mut output) = emit_output_rx.recv().await {
         info!("Emitting for provider: {}", output.config_hash);
 
+        if last_updated_relative.load(Ordering::Relaxed) {
+          output.last_updated_relative =
+            output.last_updated.map(|last_updated| {
+              format_relative(
+                last_updated,
+                now_millis(),
+                *locale.read().unwrap(),
+              )
+            });
+        }
+
+        warn_if_slow(
+          &mut slow_providers,
+          &output,
+          slow_provider_threshold.load(Ordering::Relaxed),
+        );
+
         let output = Box::new(output);
+        let is_emitted = emit_output(
+          &app_handle,
+          &subscriptions,
+          &emit_stats,
+          &emission_tracker,
+          &window_bounds,
+          &redaction,
+          &output,
+        )
+        .await;
 
-        if let Err(err) = app_handle.emit("provider-emit", output.clone())
-        {
-          warn!("Error emitting provider output: {:?}", err);
+        // The provider was stopped, so there's no cache or dependents
+        // to update.
+        if !is_emitted {
+          slow_providers.remove(&output.config_hash);
+          continue;
         }
 
+        let config_hash = output.config_hash.clone();
+
         // Update the provider's output cache.
         if let Ok(mut providers) = providers.try_lock() {
           if let Some(found_provider) =
@@ -81,6 +360,351 @@ impl ProviderManager {
         } else {
           warn!("Failed to update provider output cache.");
         }
+
+        // Update providers that reference the output. Done in a separate
+        // task, since it can restart providers.
+        let dependent_hashes = dependents
+          .lock()
+          .await
+          .iter()
+          .filter(|(_, dependent)| {
+            dependent.sources.contains(&config_hash)
+          })
+          .map(|(dependent_hash, _)| dependent_hash.clone())
+          .collect::<Vec<_>>();
+
+        for dependent_hash in dependent_hashes {
+          let app_handle = app_handle.clone();
+
+          task::spawn(async move {
+            let manager = app_handle.state::<ProviderManager>();
+
+            if let Err(err) =
+              manager.update_dependent(&dependent_hash).await
+            {
+              warn!("Error updating dependent provider: {:?}", err);
+            }
+          });
+        }
+      }
+    };
+
+    // Providers keep running on the normal priority runtime, so that
+    // only emitting their outputs is prioritized.
+    if self.high_priority_emit {
+      if let Err(err) = spawn_high_priority("provider-emit", emit_task) {
+        error!("Failed to start provider emit thread: {:?}", err);
+      }
+    } else {
+      task::spawn(emit_task);
+    }
+  }
+
+  /// Re-emits cached outputs whose relative age has changed, so that
+  /// widgets show accurate ages without their providers refreshing.
+  ///
+  /// A single tick is shared by all providers to avoid extra wakeups.
+  fn start_relative_tick<R: Runtime>(&self, app_handle: &AppHandle<R>) {
+    let providers = self.providers.clone();
+    let last_updated_relative = self.last_updated_relative.clone();
+    let locale = self.locale.clone();
+    let subscriptions = self.subscriptions.clone();
+    let emit_stats = self.emit_stats.clone();
+    let emission_tracker = self.emission_tracker.clone();
+    let window_bounds = self.window_bounds.clone();
+    let redaction = self.redaction.clone();
+    let app_handle = app_handle.clone();
+
+    task::spawn(async move {
+      let mut interval = time::interval(RELATIVE_TICK_INTERVAL);
+
+      loop {
+        interval.tick().await;
+
+        if !last_updated_relative.load(Ordering::Relaxed) {
+          continue;
+        }
+
+        let now = now_millis();
+        let locale = *locale.read().unwrap();
+        let mut outputs = Vec::new();
+
+        for provider in providers.lock().await.values_mut() {
+          let Some(cache) = provider.cache.as_mut() else {
+            continue;
+          };
+
+          let Some(last_updated) = cache.output.last_updated else {
+            continue;
+          };
+
+          let relative = Some(format_relative(last_updated, now, locale));
+
+          if cache.output.last_updated_relative != relative {
+            cache.output.last_updated_relative = relative;
+            outputs.push(cache.output.clone());
+          }
+        }
+
+        // Emit after releasing the lock, since the emit task needs it to
+        // update the output cache.
+        for output in outputs {
+          emit_output(
+            &app_handle,
+            &subscriptions,
+            &emit_stats,
+            &emission_tracker,
+            &window_bounds,
+            &redaction,
+            &output,
+          )
+          .await;
+        }
+      }
+    });
+  }
+
+  /// Periodically logs how many outputs were emitted and to how many
+  /// windows.
+  fn start_emit_stats_log(&self) {
+    let emit_stats = self.emit_stats.clone();
+
+    task::spawn(async move {
+      let mut interval = time::interval(EMIT_STATS_INTERVAL);
+
+      // First tick completes immediately.
+      interval.tick().await;
+
+      loop {
+        interval.tick().await;
+        emit_stats.log_and_reset();
+      }
+    });
+  }
+
+  /// Periodically checks whether the active connection is metered, so
+  /// that network-hungry providers can be throttled or paused.
+  ///
+  /// Providers that use the network are refreshed right away when the
+  /// connection becomes unmetered, so that their data isn't stale.
+  fn start_metered_watch(&self) {
+    let http_client = self.shared_state.http_client.clone();
+    let providers = self.providers.clone();
+
+    task::spawn(async move {
+      let mut interval = time::interval(METERED_POLL_INTERVAL);
+
+      loop {
+        interval.tick().await;
+
+        if http_client.metered_behavior() == MeteredBehavior::Ignore {
+          http_client.set_metered(false);
+          continue;
+        }
+
+        let is_metered = task::spawn_blocking(connection_cost)
+          .await
+          .ok()
+          .flatten()
+          .is_some_and(|cost| cost.is_metered);
+
+        let was_metered = http_client.set_metered(is_metered);
+
+        if was_metered == is_metered {
+          continue;
+        }
+
+        info!(
+          "Connection is now {}.",
+          if is_metered { "metered" } else { "unmetered" }
+        );
+
+        if !is_metered {
+          for provider in providers.lock().await.values() {
+            if provider.uses_network {
+              provider.force_refresh();
+            }
+          }
+        }
+      }
+    });
+  }
+
+  /// Keeps track of whether energy saver is on, so that interval
+  /// providers can refresh less often while it is.
+  ///
+  /// Changes are picked up via OS notifications where supported, with
+  /// polling as a fallback.
+  fn start_energy_saver_watch(&self) {
+    let energy_saver_changed =
+      self.shared_state.energy_saver_changed.clone();
+    let providers = self.providers.clone();
+
+    // Separate from `energy_saver_changed`, which is only notified once
+    // the new state has been recorded.
+    let os_changed = Arc::new(Notify::new());
+
+    if !power_state::subscribe_energy_saver(os_changed.clone()) {
+      debug!("Energy saver notifications unavailable, polling instead.");
+    }
+
+    task::spawn(async move {
+      let mut interval = time::interval(ENERGY_SAVER_POLL_INTERVAL);
+
+      loop {
+        tokio::select! {
+          _ = interval.tick() => {},
+          _ = os_changed.notified() => {},
+        }
+
+        let is_active =
+          task::spawn_blocking(power_state::energy_saver_active)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+
+        let was_throttled = power_state::interval_multiplier() > 1;
+        let was_active = power_state::set_energy_saver_active(is_active);
+
+        if was_active == is_active {
+          continue;
+        }
+
+        info!(
+          "Energy saver is now {}.",
+          if is_active { "on" } else { "off" }
+        );
+
+        energy_saver_changed.notify_waiters();
+
+        // Catch up on refreshes that were skipped while throttled.
+        if was_throttled {
+          for provider in providers.lock().await.values() {
+            provider.force_refresh();
+          }
+        }
+      }
+    });
+  }
+
+  /// Periodically measures the state that each provider retains, and
+  /// logs providers whose retained size exceeds the memory cap.
+  ///
+  /// Caches of providers over the cap are cleared if enabled in the
+  /// global config.
+  fn start_memory_watchdog(&self) {
+    let providers = self.providers.clone();
+    let memory_cap = self.memory_cap.clone();
+    let evict_over_memory_cap = self.evict_over_memory_cap.clone();
+
+    task::spawn(async move {
+      let mut interval = time::interval(RETAINED_SIZE_INTERVAL);
+
+      loop {
+        interval.tick().await;
+
+        // Measured without holding the lock, since retained state can be
+        // locked by running providers.
+        let measurements = providers
+          .lock()
+          .await
+          .iter()
+          .map(|(config_hash, provider)| {
+            let cache_size = provider
+              .cache
+              .as_ref()
+              .map_or(0, |cache| json_size(&cache.output));
+
+            (
+              config_hash.clone(),
+              provider.retained_state.clone(),
+              cache_size,
+              provider.over_memory_cap,
+            )
+          })
+          .collect::<Vec<_>>();
+
+        let cap_bytes = memory_cap.load(Ordering::Relaxed) * 1024 * 1024;
+
+        for (config_hash, retained_state, cache_size, was_over_cap) in
+          measurements
+        {
+          let mut size = retained_state.size().await + cache_size;
+          let is_over_cap = cap_bytes != 0 && size as u64 > cap_bytes;
+
+          // Only warn and evict when the cap is first exceeded, rather
+          // than on every measurement. The state from before evicting is
+          // kept, so a provider that stays over the cap isn't evicted
+          // again until it has dropped below it.
+          if is_over_cap && !was_over_cap {
+            warn!(
+              "Provider {} retains ~{} KB, which exceeds the memory cap of \
+              {} MB.",
+              config_hash,
+              size / 1024,
+              cap_bytes / 1024 / 1024
+            );
+
+            if evict_over_memory_cap.load(Ordering::Relaxed) {
+              retained_state.evict().await;
+              size = retained_state.size().await + cache_size;
+
+              info!(
+                "Evicted caches of provider {}. Now retains ~{} KB.",
+                config_hash,
+                size / 1024
+              );
+            }
+          } else if !is_over_cap && was_over_cap {
+            info!(
+              "Provider {} retains ~{} KB, which is back under the memory \
+              cap.",
+              config_hash,
+              size / 1024
+            );
+          }
+
+          if let Some(provider) =
+            providers.lock().await.get_mut(&config_hash)
+          {
+            provider.retained_size = Some(size);
+            provider.over_memory_cap = is_over_cap;
+          }
+        }
+      }
+    });
+  }
+
+  /// Refreshes providers with a `schedule` whenever it triggers.
+  ///
+  /// A single task is shared by all scheduled providers, and sleeps
+  /// until the next provider is due.
+  fn start_scheduler(&self) {
+    let providers = self.providers.clone();
+    let scheduler = self.scheduler.clone();
+
+    task::spawn(async move {
+      loop {
+        let sleep_duration =
+          scheduler.lock().await.sleep_duration(Local::now());
+
+        time::sleep(sleep_duration).await;
+
+        let due = scheduler.lock().await.take_due(Local::now());
+
+        if due.is_empty() {
+          continue;
+        }
+
+        let providers = providers.lock().await;
+
+        for config_hash in due {
+          if let Some(provider) = providers.get(&config_hash) {
+            info!("Refreshing provider {} on schedule.", config_hash);
+            provider.force_refresh();
+          }
+        }
       }
     });
   }
@@ -90,7 +714,7 @@ impl ProviderManager {
     &self,
     config_hash: String,
     config: ProviderConfig,
-    _tracked_access: Vec<String>,
+    tracked_access: Vec<String>,
   ) -> anyhow::Result<()> {
     let mut providers = self.providers.lock().await;
 
@@ -104,30 +728,782 @@ impl ProviderManager {
       return Ok(());
     };
 
+    // Stagger the first runs of providers that are created at the same
+    // time.
+    let start_delay = jittered_delay(
+      config
+        .start_delay
+        .unwrap_or(self.start_delay.load(Ordering::Relaxed)),
+      config.jitter.unwrap_or(self.jitter.load(Ordering::Relaxed)),
+    );
+
+    let schedule = config
+      .schedule
+      .clone()
+      .map(|schedule| (schedule, config.schedule_grace));
+
     let provider_ref = ProviderRef::new(
       config_hash.clone(),
       config,
+      tracked_access,
+      start_delay,
       self.emit_output_tx.clone(),
       &self.shared_state,
     )?;
 
+    if let Some((schedule, grace)) = schedule {
+      self
+        .scheduler
+        .lock()
+        .await
+        .add(&config_hash, schedule, grace);
+    }
+
+    self.emission_tracker.start(&config_hash);
     providers.insert(config_hash, provider_ref);
 
     Ok(())
   }
 
+  /// Creates a provider ahead of any window subscribing to it. The
+  /// provider is destroyed if no window has subscribed to it within
+  /// `PREWARM_TIMEOUT`.
+  pub async fn prewarm<R: Runtime>(
+    &self,
+    app_handle: &AppHandle<R>,
+    config_hash: String,
+    config: ProviderConfig,
+  ) -> anyhow::Result<()> {
+    // An existing provider is already cached, and is cleaned up once
+    // its windows unsubscribe.
+    if self.providers.lock().await.contains_key(&config_hash) {
+      return Ok(());
+    }
+
+    // Outputs of pre-created providers include all fields, since the
+    // fields accessed by the frontend aren't known yet.
+    self.create(config_hash.clone(), config, Vec::new()).await?;
+
+    let app_handle = app_handle.clone();
+
+    task::spawn(async move {
+      time::sleep(PREWARM_TIMEOUT).await;
+
+      let manager = app_handle.state::<ProviderManager>();
+
+      if manager
+        .subscriptions
+        .lock()
+        .await
+        .is_subscribed(&config_hash)
+      {
+        return;
+      }
+
+      info!(
+        "Destroying pre-created provider {} without subscribers.",
+        config_hash
+      );
+
+      if let Err(err) = manager.destroy(config_hash).await {
+        warn!("Error destroying provider: {:?}", err);
+      }
+    });
+
+    Ok(())
+  }
+
+  /// Creates a provider whose config references the output of other
+  /// providers that the window is subscribed to.
+  ///
+  /// The provider is started once all referenced values are available,
+  /// and is restarted whenever they change. References that would form
+  /// a cycle are rejected.
+  pub async fn create_dependent(
+    &self,
+    window_label: &str,
+    config_hash: String,
+    config: Value,
+    refs: Vec<ConfigRef>,
+    tracked_access: Vec<String>,
+  ) -> anyhow::Result<()> {
+    let mut dependents = self.dependents.lock().await;
+
+    // Same as with other providers, a provider with the same config is
+    // shared between windows.
+    if dependents.contains_key(&config_hash) {
+      drop(dependents);
+
+      // Refreshed without holding any locks, since the refresh can wait
+      // on the emit task, which locks `dependents` after each emission.
+      let found_provider =
+        self.providers.lock().await.get(&config_hash).cloned();
+
+      if let Some(found_provider) = found_provider {
+        if let Err(err) = found_provider.refresh().await {
+          warn!("Error refreshing provider: {:?}", err);
+        }
+      }
+
+      return Ok(());
+    }
+
+    let subscriptions = self.subscriptions.lock().await.get(window_label);
+
+    let sources = refs
+      .iter()
+      .map(|config_ref| {
+        let from = &config_ref.output_ref.from;
+
+        resolve_source(&subscriptions, from).with_context(|| {
+          format!(
+            "No provider '{}' to reference in '{}'. The window needs to \
+            listen to it first.",
+            from, config_ref.pointer
+          )
+        })
+      })
+      .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let graph = dependents
+      .iter()
+      .map(|(config_hash, dependent)| {
+        (config_hash.clone(), dependent.sources.clone())
+      })
+      .collect::<HashMap<_, _>>();
+
+    if creates_cycle(&graph, &config_hash, &sources) {
+      bail!("Provider references form a cycle.");
+    }
+
+    dependents.insert(
+      config_hash.clone(),
+      DependentProvider {
+        config,
+        refs,
+        sources,
+        tracked_access,
+        values: None,
+      },
+    );
+
+    drop(dependents);
+    self.update_dependent(&config_hash).await
+  }
+
+  /// Resolves the references of a dependent provider from the latest
+  /// outputs of its sources.
+  ///
+  /// Starts the provider once all values are available, and restarts it
+  /// if any value changed by more than its delta.
+  async fn update_dependent(
+    &self,
+    config_hash: &str,
+  ) -> anyhow::Result<()> {
+    let mut dependents = self.dependents.lock().await;
+
+    let Some(dependent) = dependents.get_mut(config_hash) else {
+      return Ok(());
+    };
+
+    let values = {
+      let providers = self.providers.lock().await;
+
+      dependent
+        .refs
+        .iter()
+        .zip(&dependent.sources)
+        .map(|(config_ref, source)| {
+          let cache = providers.get(source)?.cache.as_ref()?;
+          output_value(&cache.output, &config_ref.output_ref.path)
+        })
+        .collect::<Option<Vec<_>>>()
+    };
+
+    // Wait for the sources to emit the referenced values.
+    let Some(values) = values else {
+      return Ok(());
+    };
+
+    let is_running = dependent.values.is_some();
+
+    let is_changed = match &dependent.values {
+      None => true,
+      Some(prev_values) => {
+        prev_values.iter().zip(&values).zip(&dependent.refs).any(
+          |((prev, next), config_ref)| {
+            has_changed(prev, next, config_ref.output_ref.delta)
+          },
+        )
+      }
+    };
+
+    if !is_changed {
+      return Ok(());
+    }
+
+    let config = serde_json::from_value::<ProviderConfig>(with_values(
+      &dependent.config,
+      &dependent.refs,
+      &values,
+    ))
+    .context("Invalid provider config after resolving references.")
+    .map_err(secrets::redact_error)?;
+
+    dependent.values = Some(values);
+    let tracked_access = dependent.tracked_access.clone();
+    drop(dependents);
+
+    if is_running {
+      info!(
+        "Restarting provider {} after referenced values changed.",
+        config_hash
+      );
+
+      self.stop_provider(config_hash).await;
+    }
+
+    self
+      .create(config_hash.to_string(), config, tracked_access)
+      .await
+  }
+
+  /// Records that a window is subscribed to a provider.
+  pub async fn subscribe(
+    &self,
+    window_label: &str,
+    config_hash: &str,
+    config: &ProviderConfig,
+    tracked_access: &[String],
+  ) {
+    self.subscriptions.lock().await.insert(
+      window_label,
+      ProviderSubscription {
+        config_hash: config_hash.to_string(),
+        provider_type: config.type_config.type_name().to_string(),
+        tracked_access: tracked_access.to_vec(),
+        last_emitted_at: None,
+        last_run_ms: None,
+        p95_run_ms: None,
+        retained_bytes: None,
+        actions: Vec::new(),
+      },
+    );
+  }
+
+  /// Removes a window's subscription to a provider. Returns whether no
+  /// other window is subscribed to the provider, in which case it can be
+  /// destroyed.
+  pub async fn unsubscribe(
+    &self,
+    window_label: &str,
+    config_hash: &str,
+  ) -> bool {
+    self
+      .subscriptions
+      .lock()
+      .await
+      .remove(window_label, config_hash)
+  }
+
+  /// Gets the providers that a window is subscribed to.
+  pub async fn window_subscriptions(
+    &self,
+    window_label: &str,
+  ) -> Vec<ProviderSubscription> {
+    let subscriptions = self.subscriptions.lock().await.get(window_label);
+    self.with_provider_status(subscriptions).await
+  }
+
+  /// Gets the providers that each window is subscribed to, sorted by
+  /// window label.
+  pub async fn all_subscriptions(
+    &self,
+  ) -> Vec<(String, Vec<ProviderSubscription>)> {
+    let all = self.subscriptions.lock().await.all();
+    let mut res = Vec::new();
+
+    for (window_label, subscriptions) in all {
+      res.push((
+        window_label,
+        self.with_provider_status(subscriptions).await,
+      ));
+    }
+
+    res
+  }
+
+  /// Fills in the time of each provider's last output, the durations of
+  /// its recent runs, its retained size and its actions.
+  async fn with_provider_status(
+    &self,
+    mut subscriptions: Vec<ProviderSubscription>,
+  ) -> Vec<ProviderSubscription> {
+    let providers = self.providers.lock().await;
+
+    for subscription in &mut subscriptions {
+      let Some(provider) = providers.get(&subscription.config_hash) else {
+        continue;
+      };
+
+      subscription.last_emitted_at = provider
+        .cache
+        .as_ref()
+        .and_then(|cache| cache.emitted_at.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64);
+
+      subscription.last_run_ms = provider
+        .run_timings
+        .last()
+        .map(|duration| duration.as_millis() as u64);
+
+      subscription.p95_run_ms = provider
+        .run_timings
+        .p95()
+        .map(|duration| duration.as_millis() as u64);
+
+      subscription.retained_bytes =
+        provider.retained_size.map(|size| size as u64);
+
+      subscription.actions = self
+        .shared_state
+        .actions
+        .actions(&subscription.config_hash)
+        .await;
+    }
+
+    subscriptions
+  }
+
+  /// Removes the subscriptions of a closed window, and destroys providers
+  /// that no other window is subscribed to.
+  pub async fn destroy_window_providers(&self, window_label: &str) {
+    self.window_bounds.remove(window_label);
+
+    let config_hashes =
+      self.subscriptions.lock().await.remove_window(window_label);
+
+    for config_hash in config_hashes {
+      info!(
+        "Destroying provider {} of closed window '{}'.",
+        config_hash, window_label
+      );
+
+      if let Err(err) = self.destroy(config_hash).await {
+        warn!("Error destroying provider: {:?}", err);
+      }
+    }
+  }
+
+  /// Client for outbound requests, which enforces offline mode.
+  pub fn http_client(&self) -> &HttpClient {
+    &self.shared_state.http_client
+  }
+
+  /// Bounds of open windows, which are updated on window events.
+  pub fn window_bounds(&self) -> &WindowBoundsMap {
+    &self.window_bounds
+  }
+
+  /// Enables or disables offline mode.
+  ///
+  /// Running providers are refreshed, so that network-based providers
+  /// react to the change without needing to be restarted.
+  pub async fn set_offline_mode(&self, enabled: bool) {
+    if self.shared_state.http_client.set_offline_mode(enabled) == enabled {
+      return;
+    }
+
+    info!(
+      "Offline mode {}.",
+      if enabled { "enabled" } else { "disabled" }
+    );
+
+    for provider in self.providers.lock().await.values() {
+      provider.force_refresh();
+    }
+  }
+
+  /// Applies changes to the global config after it's reloaded.
+  ///
+  /// Start delay and jitter only apply to providers created afterwards.
+  /// Same as with the `--offline` flag, offline mode can be turned on
+  /// but not off by a reload.
+  pub async fn update_global_config(&self, global_config: &GlobalConfig) {
+    self
+      .start_delay
+      .store(global_config.provider_start_delay, Ordering::Relaxed);
+
+    self
+      .jitter
+      .store(global_config.provider_jitter, Ordering::Relaxed);
+
+    self
+      .last_updated_relative
+      .store(global_config.last_updated_relative, Ordering::Relaxed);
+
+    *self.locale.write().unwrap() = Locale::resolve(global_config.locale);
+
+    self
+      .slow_provider_threshold
+      .store(global_config.slow_provider_threshold, Ordering::Relaxed);
+
+    self
+      .memory_cap
+      .store(global_config.provider_memory_cap, Ordering::Relaxed);
+
+    self
+      .evict_over_memory_cap
+      .store(global_config.evict_over_memory_cap, Ordering::Relaxed);
+
+    self.shared_state.http_client.set_metered_behavior(
+      global_config.metered_behavior,
+      global_config.metered_interval_multiplier,
+    );
+
+    power_state::set_energy_saver_multiplier(
+      global_config.energy_saver_interval_multiplier,
+    );
+
+    self.redaction.update_config(&global_config.redaction);
+
+    if global_config.offline_mode {
+      self.set_offline_mode(true).await;
+    }
+  }
+
+  /// Whether fields of emitted outputs are currently redacted.
+  pub fn is_redaction_enabled(&self) -> bool {
+    self.redaction.is_enabled()
+  }
+
+  /// Enables or disables redaction of emitted outputs.
+  ///
+  /// Cached outputs are re-emitted, so that windows update right away
+  /// rather than on the next refresh.
+  pub async fn set_redaction(&self, enabled: bool) {
+    if self.redaction.set_enabled(enabled) == enabled {
+      return;
+    }
+
+    info!(
+      "Redaction {}.",
+      if enabled { "enabled" } else { "disabled" }
+    );
+
+    // Collected first, since the emit task locks the providers to
+    // update their caches.
+    let outputs = self
+      .providers
+      .lock()
+      .await
+      .values()
+      .filter_map(|provider| provider.cache.as_ref())
+      .map(|cache| *cache.output.clone())
+      .collect::<Vec<_>>();
+
+    for output in outputs {
+      _ = self.emit_output_tx.send(output).await;
+    }
+  }
+
+  /// Triggers removable drive providers to update their drive list.
+  pub fn notify_drives_changed(&self) {
+    self.shared_state.drives_changed.notify_waiters();
+  }
+
+  /// Sets all LEDs in a zone of an OpenRGB device to the given hex
+  /// color.
+  #[cfg(feature = "openrgb")]
+  pub async fn set_led_color(
+    &self,
+    device_id: u32,
+    zone_id: u32,
+    color: &str,
+  ) -> anyhow::Result<()> {
+    let color = parse_hex_color(color)?;
+
+    self
+      .shared_state
+      .openrgb
+      .lock()
+      .await
+      .set_zone_color(device_id, zone_id, color)
+      .await
+  }
+
+  /// Applies the command to the document of a `state` provider that the
+  /// window is subscribed to.
+  pub async fn mutate_state(
+    &self,
+    window_label: &str,
+    config_hash: &str,
+    command: StateCommand,
+  ) -> anyhow::Result<()> {
+    self.require_subscribed(window_label, config_hash).await?;
+
+    self
+      .shared_state
+      .state_channels
+      .send(config_hash, command)
+      .await
+  }
+
+  /// Invokes an action on a provider that the window is subscribed to,
+  /// and gets its result.
+  pub async fn call_action(
+    &self,
+    window_label: &str,
+    config_hash: &str,
+    action: &str,
+    args: Value,
+  ) -> anyhow::Result<Value> {
+    self.require_subscribed(window_label, config_hash).await?;
+
+    self
+      .shared_state
+      .actions
+      .call(config_hash, action, args)
+      .await
+  }
+
+  /// Re-emits the latest output of a provider that the window is
+  /// subscribed to, e.g. after the window detected a dropped emission.
+  ///
+  /// Refreshes the provider instead if it hasn't produced an output yet.
+  pub async fn resync(
+    &self,
+    window_label: &str,
+    config_hash: &str,
+  ) -> anyhow::Result<()> {
+    self.require_subscribed(window_label, config_hash).await?;
+
+    // Sent after releasing the lock, since the emit task needs it to
+    // update the output cache.
+    let output = {
+      let providers = self.providers.lock().await;
+
+      let provider = providers
+        .get(config_hash)
+        .context("Provider isn't running.")?;
+
+      match &provider.cache {
+        Some(cache) => *cache.output.clone(),
+        None => {
+          provider.force_refresh();
+          return Ok(());
+        }
+      }
+    };
+
+    self.emit_output_tx.send(output).await?;
+
+    Ok(())
+  }
+
+  /// Gets the emission counts of the provider with the given config
+  /// hash. `None` if it isn't running or hasn't emitted yet.
+  pub fn emission_stats(
+    &self,
+    config_hash: &str,
+  ) -> Option<EmissionStats> {
+    self.emission_tracker.stats(config_hash)
+  }
+
+  /// Errors if the window isn't subscribed to the provider.
+  async fn require_subscribed(
+    &self,
+    window_label: &str,
+    config_hash: &str,
+  ) -> anyhow::Result<()> {
+    let is_subscribed = self
+      .subscriptions
+      .lock()
+      .await
+      .get(window_label)
+      .iter()
+      .any(|subscription| subscription.config_hash == config_hash);
+
+    if !is_subscribed {
+      bail!(
+        "Window '{}' isn't subscribed to provider '{}'.",
+        window_label,
+        config_hash
+      );
+    }
+
+    Ok(())
+  }
+
   /// Destroys and cleans up the provider with the given config.
   pub async fn destroy(&self, config_hash: String) -> anyhow::Result<()> {
+    self.dependents.lock().await.remove(&config_hash);
+    self.stop_provider(&config_hash).await;
+
+    Ok(())
+  }
+
+  /// Stops the provider and removes it from the running providers.
+  async fn stop_provider(&self, config_hash: &str) {
     let mut providers = self.providers.lock().await;
 
-    if let Some(found_provider) = providers.get_mut(&config_hash) {
-      if let Err(err) = found_provider.stop().await {
-        warn!("Error stopping provider: {:?}", err);
-      }
+    if let Some(found_provider) = providers.get(config_hash) {
+      found_provider.stop();
     }
 
-    providers.remove(&config_hash);
+    providers.remove(config_hash);
+    self.scheduler.lock().await.remove(config_hash);
+    self.emission_tracker.remove(config_hash);
+  }
+}
 
-    Ok(())
+/// Logs a warning if the run that produced the output took longer than
+/// the threshold in milliseconds.
+///
+/// Each provider is only warned about when its runs become slow, rather
+/// than on every slow run.
+fn warn_if_slow(
+  slow_providers: &mut SlowProviders,
+  output: &ProviderOutput,
+  threshold: u64,
+) {
+  let Some(run_duration) = output.run_duration else {
+    return;
+  };
+
+  let is_slow =
+    threshold != 0 && run_duration.as_millis() > threshold as u128;
+
+  if slow_providers.record(&output.config_hash, is_slow) {
+    warn!(
+      "Slow provider run: {} took {}ms (threshold {}ms).",
+      output.config_hash,
+      run_duration.as_millis(),
+      threshold
+    );
   }
 }
+
+/// Emits the output to the windows that are subscribed to its provider.
+///
+/// Outputs are never broadcast, since windows mustn't receive outputs
+/// of providers they aren't subscribed to (e.g. clipboard contents).
+///
+/// Returns `false` if the output was dropped, since its provider has
+/// been stopped.
+async fn emit_output<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  subscriptions: &Mutex<WindowSubscriptions>,
+  emit_stats: &EmitStats,
+  emission_tracker: &EmissionTracker,
+  window_bounds: &WindowBoundsMap,
+  redaction: &Redaction,
+  output: &ProviderOutput,
+) -> bool {
+  // Outputs can still be queued for emission when their provider is
+  // stopped, in which case they're dropped.
+  let Some(sequence) = emission_tracker.next_sequence(&output.config_hash)
+  else {
+    debug!(
+      "Dropping output of stopped provider: {}",
+      output.config_hash
+    );
+    return false;
+  };
+
+  let windows = app_handle.webview_windows();
+
+  let subscribers =
+    subscriptions.lock().await.subscribers(&output.config_hash);
+
+  let focused_monitor =
+    self_monitor::focused_monitor_bounds(&output.variables);
+
+  let record_delivery = |label: &str| {
+    emission_tracker.record_delivery(&output.config_hash, label, sequence)
+  };
+
+  match focused_monitor {
+    // Outputs of window manager providers are emitted to each window
+    // separately, with whether the window is on the focused monitor.
+    Some(focused_monitor) => {
+      for label in &subscribers {
+        let mut window_output = output.clone();
+
+        self_monitor::set_self_monitor_focused(
+          &mut window_output.variables,
+          window_bounds
+            .get(label)
+            .map(|bounds| focused_monitor.contains_center_of(&bounds)),
+        );
+
+        let res = match redaction.redact(&window_output) {
+          Some(redacted) => app_handle.emit_to(
+            label.as_str(),
+            "provider-emit",
+            Emission {
+              output: redacted,
+              sequence,
+            },
+          ),
+          None => app_handle.emit_to(
+            label.as_str(),
+            "provider-emit",
+            Emission {
+              output: window_output,
+              sequence,
+            },
+          ),
+        };
+
+        match res {
+          Ok(()) => record_delivery(label),
+          Err(err) => warn!("Error emitting provider output: {:?}", err),
+        }
+      }
+    }
+    None => {
+      let res = match redaction.redact(output) {
+        Some(redacted) => emit_payload(
+          app_handle,
+          &subscribers,
+          &Emission {
+            output: redacted,
+            sequence,
+          },
+        ),
+        None => emit_payload(
+          app_handle,
+          &subscribers,
+          &Emission { output, sequence },
+        ),
+      };
+
+      match res {
+        Ok(()) => {
+          subscribers.iter().for_each(|label| record_delivery(label))
+        }
+        Err(err) => warn!("Error emitting provider output: {:?}", err),
+      }
+    }
+  };
+
+  emit_stats.record(subscribers.len(), windows.len());
+
+  true
+}
+
+/// Emits an output payload to the subscribed windows.
+fn emit_payload<R: Runtime, S: Serialize + Clone>(
+  app_handle: &AppHandle<R>,
+  subscribers: &[String],
+  payload: &S,
+) -> tauri::Result<()> {
+  app_handle.emit_filter("provider-emit", payload, |target| match target {
+    EventTarget::AnyLabel { label }
+    | EventTarget::Window { label }
+    | EventTarget::Webview { label }
+    | EventTarget::WebviewWindow { label } => subscribers.contains(label),
+    _ => false,
+  })
+}
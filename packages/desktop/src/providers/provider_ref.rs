@@ -1,18 +1,63 @@
-use std::time::{Duration, Instant};
+use std::{
+  future::Future,
+  time::{Duration, Instant, SystemTime},
+};
 
 use anyhow::bail;
-use serde::Serialize;
-use tokio::{sync::mpsc, task};
+use rand::Rng;
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use tokio::{sync::mpsc, task, time};
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
-#[cfg(windows)]
+#[cfg(feature = "audio_input")]
+use super::audio_input::AudioInputProvider;
+#[cfg(feature = "battery")]
+use super::battery::BatteryProvider;
+#[cfg(feature = "clipboard")]
+use super::clipboard::ClipboardProvider;
+#[cfg(feature = "cpu")]
+use super::cpu::CpuProvider;
+#[cfg(feature = "host")]
+use super::host::HostProvider;
+#[cfg(feature = "ip")]
+use super::ip::IpProvider;
+#[cfg(all(windows, feature = "komorebi"))]
 use super::komorebi::KomorebiProvider;
+#[cfg(feature = "kubernetes")]
+use super::kubernetes::KubernetesProvider;
+#[cfg(feature = "memory")]
+use super::memory::MemoryProvider;
+#[cfg(feature = "network")]
+use super::network::NetworkProvider;
+#[cfg(feature = "openrgb")]
+use super::openrgb::OpenRgbProvider;
+#[cfg(feature = "removable_drives")]
+use super::removable_drives::RemovableDrivesProvider;
+#[cfg(feature = "steam")]
+use super::steam::SteamProvider;
+#[cfg(feature = "summary")]
+use super::summary::SummaryProvider;
+#[cfg(feature = "system_state")]
+use super::system_state::SystemStateProvider;
+#[cfg(feature = "tail")]
+use super::tail::TailProvider;
+#[cfg(feature = "weather")]
+use super::weather::WeatherProvider;
+#[cfg(all(windows, feature = "windows_list"))]
+use super::windows_list::WindowsListProvider;
 use super::{
-  battery::BatteryProvider, config::ProviderConfig, cpu::CpuProvider,
-  host::HostProvider, ip::IpProvider, memory::MemoryProvider,
-  network::NetworkProvider, provider::Provider,
-  provider_manager::SharedProviderState, variables::ProviderVariables,
-  weather::WeatherProvider,
+  config::{ProviderConfig, ProviderTypeConfig},
+  http_client::{NetworkErrorKind, OfflineModeError},
+  last_updated::now_millis,
+  provider::Provider,
+  provider_manager::SharedProviderState,
+  retained_size::RetainedState,
+  run_timings::RunTimings,
+  state::StateProvider,
+  transforms::Transforms,
+  variables::ProviderVariables,
 };
 
 /// Reference to an active provider.
@@ -20,15 +65,41 @@ use super::{
 pub struct ProviderRef {
   pub config_hash: String,
   pub min_refresh_interval: Option<Duration>,
+
+  /// Whether the provider makes network requests, and is refreshed when
+  /// the connection becomes unmetered.
+  pub uses_network: bool,
   pub cache: Option<ProviderCache>,
+
+  /// Execution times of the provider's recent runs.
+  pub run_timings: RunTimings,
+
+  /// State that the provider retains between runs (e.g. history
+  /// buffers and caches).
+  pub retained_state: RetainedState,
+
+  /// Approximate size in bytes of the retained state and the cached
+  /// output, as of the last measurement.
+  pub retained_size: Option<usize>,
+
+  /// Whether the retained size exceeded `provider_memory_cap` at the
+  /// last measurement (before any eviction). Used to only warn and evict
+  /// once per crossing.
+  pub over_memory_cap: bool,
   pub emit_output_tx: mpsc::Sender<ProviderOutput>,
   pub refresh_tx: mpsc::Sender<()>,
-  pub stop_tx: mpsc::Sender<()>,
+
+  /// Cancelled when the provider is stopped. In-flight runs are dropped
+  /// on cancellation, and outputs produced after it are discarded.
+  pub cancel_token: CancellationToken,
 }
 
 #[derive(Debug, Clone)]
 pub struct ProviderCache {
   pub timestamp: Instant,
+
+  /// Wall-clock time of the output, for reporting to the frontend.
+  pub emitted_at: SystemTime,
   pub output: Box<ProviderOutput>,
 }
 
@@ -38,17 +109,84 @@ pub struct ProviderCache {
 pub struct ProviderOutput {
   pub config_hash: String,
   pub variables: VariablesResult,
+
+  /// Results of the provider config's transforms, if any are defined.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub computed: Option<Map<String, Value>>,
+
+  /// Unix timestamp in milliseconds of when the output was produced.
+  /// Set for all outputs before they're emitted.
+  pub last_updated: Option<u64>,
+
+  /// Age of the output (e.g. `3m ago`). Only set if
+  /// `last_updated_relative` is enabled in the global config.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub last_updated_relative: Option<String>,
+
+  /// How long the provider took to produce the output. Only set for
+  /// interval providers.
+  #[serde(skip)]
+  pub run_duration: Option<Duration>,
+
+  /// Type of the provider (e.g. `ip`), for redacting its fields. Set for
+  /// all outputs before they're emitted.
+  #[serde(skip)]
+  pub type_name: Option<&'static str>,
 }
 
 /// Provider variable output emitted to frontend clients.
 ///
 /// This is used instead of a normal `Result` type to serialize it in a
 /// nicer way.
-#[derive(Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone)]
 pub enum VariablesResult {
   Data(ProviderVariables),
   Error(String),
+
+  /// Error for providers that were blocked from making network requests
+  /// by offline mode.
+  OfflineMode(String),
+
+  /// Error for network lookups that failed to connect or timed out.
+  Network(String, NetworkErrorKind),
+
+  /// Fallback value from the provider config, emitted in place of an
+  /// error. Contains the error under the `_error` key.
+  Fallback(Map<String, Value>),
+}
+
+/// Serializes as either `{ data }` or `{ error }`, with offline mode
+/// errors additionally having `offlineMode: true`, network errors having
+/// their kind under `networkError`, and fallback values having
+/// `fallback: true`.
+impl Serialize for VariablesResult {
+  fn serialize<S: Serializer>(
+    &self,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+
+    match self {
+      VariablesResult::Data(data) => map.serialize_entry("data", data)?,
+      VariablesResult::Error(error) => {
+        map.serialize_entry("error", error)?
+      }
+      VariablesResult::OfflineMode(error) => {
+        map.serialize_entry("error", error)?;
+        map.serialize_entry("offlineMode", &true)?;
+      }
+      VariablesResult::Network(error, kind) => {
+        map.serialize_entry("error", error)?;
+        map.serialize_entry("networkError", kind)?;
+      }
+      VariablesResult::Fallback(data) => {
+        map.serialize_entry("data", data)?;
+        map.serialize_entry("fallback", &true)?;
+      }
+    }
+
+    map.end()
+  }
 }
 
 /// Implements conversion from an `anyhow::Result`.
@@ -56,7 +194,13 @@ impl From<anyhow::Result<ProviderVariables>> for VariablesResult {
   fn from(result: anyhow::Result<ProviderVariables>) -> Self {
     match result {
       Ok(data) => VariablesResult::Data(data),
-      Err(err) => VariablesResult::Error(err.to_string()),
+      Err(err) if err.is::<OfflineModeError>() => {
+        VariablesResult::OfflineMode(err.to_string())
+      }
+      Err(err) => match NetworkErrorKind::from_error(&err) {
+        Some(kind) => VariablesResult::Network(err.to_string(), kind),
+        None => VariablesResult::Error(err.to_string()),
+      },
     }
   }
 }
@@ -65,101 +209,329 @@ impl ProviderRef {
   pub fn new(
     config_hash: String,
     config: ProviderConfig,
+    tracked_access: Vec<String>,
+    start_delay: Duration,
     emit_output_tx: mpsc::Sender<ProviderOutput>,
     shared_state: &SharedProviderState,
   ) -> anyhow::Result<Self> {
-    let provider = Self::create_provider(config, shared_state)?;
+    let transforms = Transforms::new(&config.transforms)?;
+    let uses_network = !config.type_config.http_hosts().is_empty();
+    let fallback = config.fallback;
+    let type_name = config.type_config.type_name();
+
+    let startup_wait = {
+      let wait_for = config.wait_for;
+      let timeout = Duration::from_millis(config.wait_for_timeout);
+      let dns_host = config.type_config.http_hosts().first().copied();
+      let http_client = shared_state.http_client.clone();
+      let config_hash = config_hash.clone();
+
+      async move {
+        if let Some(wait_for) = wait_for {
+          wait_for
+            .wait(&config_hash, timeout, dns_host, &http_client)
+            .await;
+        }
+      }
+    };
+
+    let provider = Self::create_provider(
+      config.type_config,
+      tracked_access,
+      shared_state,
+    )?;
 
     let (refresh_tx, refresh_rx) = mpsc::channel::<()>(1);
-    let (stop_tx, stop_rx) = mpsc::channel::<()>(1);
+    let cancel_token = CancellationToken::new();
+
+    // Outputs from the provider are passed through an intermediate
+    // channel, so that they can be post-processed before emission.
+    let (provider_output_tx, provider_output_rx) =
+      mpsc::channel::<ProviderOutput>(1);
 
     let min_refresh_interval = provider.min_refresh_interval();
+    let retained_state = provider.retained_state();
     let config_hash_clone = config_hash.clone();
-    let emit_output_tx_clone = emit_output_tx.clone();
 
-    task::spawn(async move {
-      Self::start_provider(
-        provider,
-        config_hash_clone,
-        emit_output_tx_clone,
-        refresh_rx,
-        stop_rx,
-      )
-      .await;
-    });
+    task::spawn(Self::forward_outputs(
+      provider_output_rx,
+      emit_output_tx.clone(),
+      transforms,
+      fallback,
+      type_name,
+      cancel_token.clone(),
+    ));
+
+    task::spawn(Self::start_provider(
+      provider,
+      config_hash_clone,
+      start_delay,
+      startup_wait,
+      provider_output_tx,
+      refresh_rx,
+      cancel_token.clone(),
+    ));
 
     Ok(Self {
       config_hash,
       min_refresh_interval,
+      uses_network,
       cache: None,
+      run_timings: RunTimings::default(),
+      retained_state,
+      retained_size: None,
+      over_memory_cap: false,
       emit_output_tx,
       refresh_tx,
-      stop_tx,
+      cancel_token,
     })
   }
 
-  /// Starts the provider.
+  /// Starts the provider and runs it until it's cancelled.
+  ///
+  /// The first run happens after `start_delay`, followed by
+  /// `startup_wait` (i.e. waiting for the provider's `wait_for`
+  /// dependency).
   async fn start_provider(
     mut provider: Box<dyn Provider + Send>,
     config_hash: String,
+    start_delay: Duration,
+    startup_wait: impl Future<Output = ()>,
+    emit_output_tx: mpsc::Sender<ProviderOutput>,
+    refresh_rx: mpsc::Receiver<()>,
+    cancel_token: CancellationToken,
+  ) {
+    // Delay the first run, while still allowing the provider to be
+    // stopped in the meantime.
+    let delay = async {
+      if !start_delay.is_zero() {
+        time::sleep(start_delay).await;
+      }
+
+      startup_wait.await;
+    };
+
+    tokio::select! {
+      _ = delay => {},
+      _ = cancel_token.cancelled() => {
+        info!("Provider stopped before starting: {}", config_hash);
+        return;
+      },
+    }
+
+    // On cancellation, the provider's in-flight `on_start` or
+    // `on_refresh` is dropped, which aborts any pending I/O (e.g. HTTP
+    // requests) at its next await point.
+    tokio::select! {
+      _ = Self::run_provider(
+        &mut provider,
+        &config_hash,
+        emit_output_tx,
+        refresh_rx,
+      ) => {},
+      _ = cancel_token.cancelled() => {},
+    }
+
+    // Perform any necessary clean up (e.g. aborting background tasks).
+    info!("Stopping provider: {}", config_hash);
+    provider.on_stop().await;
+
+    info!("Provider stopped: {}", config_hash);
+  }
+
+  async fn run_provider(
+    provider: &mut Box<dyn Provider + Send>,
+    config_hash: &str,
     emit_output_tx: mpsc::Sender<ProviderOutput>,
     mut refresh_rx: mpsc::Receiver<()>,
-    mut stop_rx: mpsc::Receiver<()>,
   ) {
     info!("Starting provider: {}", config_hash);
-    provider
-      .on_start(&config_hash, emit_output_tx.clone())
-      .await;
+    provider.on_start(config_hash, emit_output_tx.clone()).await;
 
-    // Loop to avoid exiting the select on refresh.
+    // On refresh, re-emit provider variables.
+    while refresh_rx.recv().await.is_some() {
+      info!("Refreshing provider: {}", config_hash);
+      provider
+        .on_refresh(config_hash, emit_output_tx.clone())
+        .await;
+    }
+  }
+
+  /// Post-processes outputs from the provider and forwards them for
+  /// emission.
+  ///
+  /// Exits once the provider has stopped and dropped its sender, or once
+  /// it's cancelled. Outputs that were produced by a run that completed
+  /// just before cancellation are discarded.
+  async fn forward_outputs(
+    mut provider_output_rx: mpsc::Receiver<ProviderOutput>,
+    emit_output_tx: mpsc::Sender<ProviderOutput>,
+    mut transforms: Transforms,
+    fallback: Option<Map<String, Value>>,
+    type_name: &'static str,
+    cancel_token: CancellationToken,
+  ) {
     loop {
-      tokio::select! {
-        // On refresh, re-emit provider variables and continue looping.
-        Some(_) = refresh_rx.recv() => {
-          info!("Refreshing provider: {}", config_hash);
-          _ = provider.on_refresh(&config_hash, emit_output_tx.clone()).await;
-        },
+      let mut output = tokio::select! {
+        Some(output) = provider_output_rx.recv() => output,
+        _ = cancel_token.cancelled() => break,
+        else => break,
+      };
+
+      if cancel_token.is_cancelled() {
+        break;
+      }
 
-        // On stop, perform any necessary clean up and exit the loop.
-        Some(_) = stop_rx.recv() => {
-          info!("Stopping provider: {}", config_hash);
-          _ = provider.on_stop().await;
-          break;
+      output.last_updated = Some(now_millis());
+      output.type_name = Some(type_name);
+
+      match (&output.variables, &fallback) {
+        (VariablesResult::Data(variables), _) => {
+          output.computed = transforms.apply(variables);
+        }
+        (
+          VariablesResult::Error(error)
+          | VariablesResult::OfflineMode(error),
+          Some(fallback),
+        ) => {
+          let mut data = fallback.clone();
+          data.insert("_error".into(), Value::String(error.clone()));
+          output.variables = VariablesResult::Fallback(data);
+        }
+        (VariablesResult::Network(error, kind), Some(fallback)) => {
+          let mut data = fallback.clone();
+          data.insert("_error".into(), Value::String(error.clone()));
+          data.insert("_networkError".into(), json!(kind));
+          output.variables = VariablesResult::Fallback(data);
+        }
+        _ => {}
+      }
+
+      tokio::select! {
+        res = emit_output_tx.send(output) => {
+          if res.is_err() {
+            break;
+          }
         },
+        _ = cancel_token.cancelled() => break,
       }
     }
-
-    info!("Provider stopped: {}", config_hash);
   }
 
+  #[cfg_attr(
+    not(any(feature = "network", feature = "summary")),
+    allow(unused_variables)
+  )]
   fn create_provider(
-    config: ProviderConfig,
+    config: ProviderTypeConfig,
+    tracked_access: Vec<String>,
     shared_state: &SharedProviderState,
   ) -> anyhow::Result<Box<dyn Provider + Send>> {
     let provider: Box<dyn Provider + Send> = match config {
-      ProviderConfig::Battery(config) => {
-        Box::new(BatteryProvider::new(config)?)
+      #[cfg(feature = "audio_input")]
+      ProviderTypeConfig::AudioInput(config) => {
+        Box::new(AudioInputProvider::new(config))
       }
-      ProviderConfig::Cpu(config) => {
+      #[cfg(feature = "battery")]
+      ProviderTypeConfig::Battery(config) => {
+        Box::new(BatteryProvider::new(
+          config,
+          shared_state.notification_tx.clone(),
+        )?)
+      }
+      #[cfg(feature = "clipboard")]
+      ProviderTypeConfig::Clipboard(config) => Box::new(
+        ClipboardProvider::new(config, shared_state.data_dir.clone())?,
+      ),
+      #[cfg(feature = "cpu")]
+      ProviderTypeConfig::Cpu(config) => {
         Box::new(CpuProvider::new(config, shared_state.sysinfo.clone()))
       }
-      ProviderConfig::Host(config) => {
-        Box::new(HostProvider::new(config, shared_state.sysinfo.clone()))
+      #[cfg(feature = "host")]
+      ProviderTypeConfig::Host(config) => {
+        Box::new(HostProvider::new(config))
+      }
+      #[cfg(feature = "ip")]
+      ProviderTypeConfig::Ip(config) => {
+        Box::new(IpProvider::new(config, shared_state.http_client.clone()))
       }
-      ProviderConfig::Ip(config) => Box::new(IpProvider::new(config)),
-      #[cfg(windows)]
-      ProviderConfig::Komorebi(config) => {
+      #[cfg(all(windows, feature = "komorebi"))]
+      ProviderTypeConfig::Komorebi(config) => {
         Box::new(KomorebiProvider::new(config))
       }
-      ProviderConfig::Memory(config) => {
-        Box::new(MemoryProvider::new(config, shared_state.sysinfo.clone()))
+      #[cfg(feature = "kubernetes")]
+      ProviderTypeConfig::Kubernetes(config) => Box::new(
+        KubernetesProvider::new(config, shared_state.http_client.clone()),
+      ),
+      #[cfg(feature = "memory")]
+      ProviderTypeConfig::Memory(config) => Box::new(MemoryProvider::new(
+        config,
+        shared_state.sysinfo.clone(),
+        shared_state.process_snapshot.clone(),
+      )),
+      #[cfg(feature = "network")]
+      ProviderTypeConfig::Network(config) => {
+        Box::new(NetworkProvider::new(
+          config,
+          shared_state.netinfo.clone(),
+          shared_state.sysinfo.clone(),
+          shared_state.process_snapshot.clone(),
+          tracked_access,
+        ))
+      }
+      #[cfg(feature = "openrgb")]
+      ProviderTypeConfig::OpenRgb(config) => Box::new(
+        OpenRgbProvider::new(config, shared_state.openrgb.clone()),
+      ),
+      #[cfg(feature = "removable_drives")]
+      ProviderTypeConfig::RemovableDrives(config) => {
+        Box::new(RemovableDrivesProvider::new(
+          config,
+          shared_state.drives_changed.clone(),
+        ))
       }
-      ProviderConfig::Network(config) => Box::new(NetworkProvider::new(
+      ProviderTypeConfig::State(config) => Box::new(StateProvider::new(
+        config,
+        shared_state.data_dir.clone(),
+        shared_state.state_channels.clone(),
+      )?),
+      #[cfg(feature = "steam")]
+      ProviderTypeConfig::Steam(config) => Box::new(SteamProvider::new(
         config,
-        shared_state.netinfo.clone(),
+        shared_state.sysinfo.clone(),
+        shared_state.process_snapshot.clone(),
       )),
-      ProviderConfig::Weather(config) => {
-        Box::new(WeatherProvider::new(config))
+      #[cfg(feature = "summary")]
+      ProviderTypeConfig::Summary(config) => {
+        Box::new(SummaryProvider::new(
+          config,
+          shared_state.sysinfo.clone(),
+          shared_state.netinfo.clone(),
+          tracked_access,
+        ))
+      }
+      #[cfg(feature = "system_state")]
+      ProviderTypeConfig::SystemState(config) => {
+        Box::new(SystemStateProvider::new(
+          config,
+          shared_state.energy_saver_changed.clone(),
+        ))
+      }
+      #[cfg(feature = "tail")]
+      ProviderTypeConfig::Tail(config) => {
+        Box::new(TailProvider::new(config, shared_state.actions.clone())?)
+      }
+      #[cfg(feature = "weather")]
+      ProviderTypeConfig::Weather(config) => {
+        Box::new(WeatherProvider::new(
+          config,
+          shared_state.geocoding_cache.clone(),
+          shared_state.http_client.clone(),
+        ))
+      }
+      #[cfg(all(windows, feature = "windows_list"))]
+      ProviderTypeConfig::WindowsList(config) => {
+        Box::new(WindowsListProvider::new(config))
       }
       #[allow(unreachable_patterns)]
       _ => bail!("Provider not supported on this operating system."),
@@ -168,10 +540,18 @@ impl ProviderRef {
     Ok(provider)
   }
 
-  /// Updates cache with the given output.
-  pub fn update_cache(&mut self, output: Box<ProviderOutput>) {
+  /// Updates cache with the given output, and records the duration of
+  /// the run that produced it.
+  pub fn update_cache(&mut self, mut output: Box<ProviderOutput>) {
+    // Cleared so that re-emits of the cached output aren't recorded as
+    // runs.
+    if let Some(run_duration) = output.run_duration.take() {
+      self.run_timings.record(run_duration);
+    }
+
     self.cache = Some(ProviderCache {
       timestamp: Instant::now(),
+      emitted_at: SystemTime::now(),
       output,
     });
   }
@@ -194,12 +574,432 @@ impl ProviderRef {
     Ok(())
   }
 
+  /// Refreshes the provider, bypassing the output cache.
+  ///
+  /// Does nothing if a refresh is already pending.
+  pub fn force_refresh(&self) {
+    _ = self.refresh_tx.try_send(());
+  }
+
   /// Stops the given provider.
   ///
-  /// This triggers any necessary cleanup.
-  pub async fn stop(&self) -> anyhow::Result<()> {
-    self.stop_tx.send(()).await?;
+  /// In-flight runs are dropped rather than awaited, and this triggers
+  /// any necessary cleanup.
+  pub fn stop(&self) {
+    self.cancel_token.cancel();
+  }
+}
 
-    Ok(())
+/// Gets a delay of `start_delay` plus a random jitter of up to `jitter`
+/// milliseconds.
+pub fn jittered_delay(start_delay: u64, jitter: u64) -> Duration {
+  let jitter = match jitter {
+    0 => 0,
+    _ => rand::thread_rng().gen_range(0..=jitter),
+  };
+
+  Duration::from_millis(start_delay.saturating_add(jitter))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    collections::HashMap,
+    net::Ipv4Addr,
+    sync::{Arc, Mutex},
+  };
+
+  use async_trait::async_trait;
+  use tokio::{io::AsyncReadExt, net::TcpListener, sync::oneshot};
+
+  use super::*;
+  use crate::providers::http_client::HttpClient;
+
+  /// Provider that records the calls it gets.
+  #[derive(Clone, Default)]
+  struct MockProvider {
+    events: Arc<Mutex<Vec<&'static str>>>,
+
+    /// How long `on_start` and `on_refresh` take. Runs that take time
+    /// also record when they complete.
+    run_time: Option<Duration>,
+  }
+
+  impl MockProvider {
+    fn with_run_time(run_time: Duration) -> Self {
+      Self {
+        run_time: Some(run_time),
+        ..Default::default()
+      }
+    }
+
+    fn events(&self) -> Vec<&'static str> {
+      self.events.lock().unwrap().clone()
+    }
+
+    fn record(&self, event: &'static str) {
+      self.events.lock().unwrap().push(event);
+    }
+
+    async fn run(&self, event: &'static str, done_event: &'static str) {
+      self.record(event);
+
+      if let Some(run_time) = self.run_time {
+        time::sleep(run_time).await;
+        self.record(done_event);
+      }
+    }
+  }
+
+  #[async_trait]
+  impl Provider for MockProvider {
+    async fn on_start(
+      &mut self,
+      _config_hash: &str,
+      _emit_output_tx: mpsc::Sender<ProviderOutput>,
+    ) {
+      self.run("start", "start done").await;
+    }
+
+    async fn on_refresh(
+      &mut self,
+      _config_hash: &str,
+      _emit_output_tx: mpsc::Sender<ProviderOutput>,
+    ) {
+      self.run("refresh", "refresh done").await;
+    }
+
+    async fn on_stop(&mut self) {
+      self.record("stop");
+    }
+
+    fn min_refresh_interval(&self) -> Option<Duration> {
+      None
+    }
+  }
+
+  /// Starts the provider in a task. Refreshes are sent via the returned
+  /// sender, which keeps the provider running while it's alive.
+  fn spawn_provider(
+    provider: &MockProvider,
+    start_delay: Duration,
+    startup_wait: impl Future<Output = ()> + Send + 'static,
+    cancel_token: &CancellationToken,
+  ) -> (task::JoinHandle<()>, mpsc::Sender<()>) {
+    let (emit_output_tx, _) = mpsc::channel(1);
+    let (refresh_tx, refresh_rx) = mpsc::channel(1);
+
+    let handle = task::spawn(ProviderRef::start_provider(
+      Box::new(provider.clone()),
+      "hash".into(),
+      start_delay,
+      startup_wait,
+      emit_output_tx,
+      refresh_rx,
+      cancel_token.clone(),
+    ));
+
+    (handle, refresh_tx)
+  }
+
+  #[test]
+  fn jittered_delay_is_within_bounds() {
+    assert_eq!(jittered_delay(500, 0), Duration::from_millis(500));
+
+    for _ in 0..100 {
+      let delay = jittered_delay(500, 100);
+      assert!(delay >= Duration::from_millis(500));
+      assert!(delay <= Duration::from_millis(600));
+    }
+
+    assert_eq!(
+      jittered_delay(u64::MAX, 100),
+      Duration::from_millis(u64::MAX)
+    );
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn first_run_waits_for_start_delay() {
+    let provider = MockProvider::default();
+    let cancel_token = CancellationToken::new();
+
+    let (_handle, _refresh_tx) = spawn_provider(
+      &provider,
+      Duration::from_secs(5),
+      async {},
+      &cancel_token,
+    );
+
+    time::sleep(Duration::from_millis(4900)).await;
+    assert!(provider.events().is_empty());
+
+    time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(provider.events(), ["start"]);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn startup_wait_follows_start_delay() {
+    let provider = MockProvider::default();
+    let cancel_token = CancellationToken::new();
+    let events = provider.events.clone();
+
+    let (_handle, _refresh_tx) = spawn_provider(
+      &provider,
+      Duration::from_secs(1),
+      async move {
+        events.lock().unwrap().push("wait");
+        time::sleep(Duration::from_secs(2)).await;
+      },
+      &cancel_token,
+    );
+
+    time::sleep(Duration::from_millis(500)).await;
+    assert!(provider.events().is_empty());
+
+    time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(provider.events(), ["wait"]);
+
+    time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(provider.events(), ["wait", "start"]);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn provider_stopped_during_start_delay_never_starts() {
+    let provider = MockProvider::default();
+    let cancel_token = CancellationToken::new();
+
+    let (handle, _refresh_tx) = spawn_provider(
+      &provider,
+      Duration::from_secs(5),
+      async {},
+      &cancel_token,
+    );
+
+    time::sleep(Duration::from_secs(1)).await;
+    cancel_token.cancel();
+    handle.await.unwrap();
+
+    // Nothing was started, so there's nothing to clean up either.
+    assert!(provider.events().is_empty());
+  }
+
+  fn output() -> ProviderOutput {
+    ProviderOutput {
+      config_hash: "hash".into(),
+      variables: VariablesResult::Error("error".into()),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+
+  /// Starts forwarding outputs in a task. Returns the sender for
+  /// provider outputs and the receiver for forwarded ones.
+  fn spawn_forwarder(
+    cancel_token: &CancellationToken,
+  ) -> (mpsc::Sender<ProviderOutput>, mpsc::Receiver<ProviderOutput>) {
+    let (provider_output_tx, provider_output_rx) = mpsc::channel(4);
+    let (emit_output_tx, emit_output_rx) = mpsc::channel(4);
+
+    task::spawn(ProviderRef::forward_outputs(
+      provider_output_rx,
+      emit_output_tx,
+      Transforms::new(&HashMap::new()).unwrap(),
+      None,
+      "mock",
+      cancel_token.clone(),
+    ));
+
+    (provider_output_tx, emit_output_rx)
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn cancel_during_start_drops_run() {
+    let provider = MockProvider::with_run_time(Duration::from_secs(10));
+    let cancel_token = CancellationToken::new();
+
+    let (handle, _refresh_tx) =
+      spawn_provider(&provider, Duration::ZERO, async {}, &cancel_token);
+
+    time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(provider.events(), ["start"]);
+
+    cancel_token.cancel();
+    handle.await.unwrap();
+
+    // The run never completes, but the provider is still cleaned up.
+    time::sleep(Duration::from_secs(20)).await;
+    assert_eq!(provider.events(), ["start", "stop"]);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn cancel_during_refresh_drops_run() {
+    let provider = MockProvider::with_run_time(Duration::from_secs(1));
+    let cancel_token = CancellationToken::new();
+
+    let (handle, refresh_tx) =
+      spawn_provider(&provider, Duration::ZERO, async {}, &cancel_token);
+
+    time::sleep(Duration::from_secs(2)).await;
+    refresh_tx.send(()).await.unwrap();
+    time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(provider.events(), ["start", "start done", "refresh"]);
+
+    cancel_token.cancel();
+    handle.await.unwrap();
+
+    time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(
+      provider.events(),
+      ["start", "start done", "refresh", "stop"]
+    );
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn cancel_while_idle_stops_provider() {
+    let provider = MockProvider::default();
+    let cancel_token = CancellationToken::new();
+
+    let (handle, refresh_tx) =
+      spawn_provider(&provider, Duration::ZERO, async {}, &cancel_token);
+
+    time::sleep(Duration::from_secs(1)).await;
+    cancel_token.cancel();
+    handle.await.unwrap();
+
+    assert_eq!(provider.events(), ["start", "stop"]);
+
+    // Refreshes after stopping aren't run.
+    _ = refresh_tx.send(()).await;
+    time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(provider.events(), ["start", "stop"]);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn outputs_are_forwarded_until_cancelled() {
+    let cancel_token = CancellationToken::new();
+    let (provider_output_tx, mut emit_output_rx) =
+      spawn_forwarder(&cancel_token);
+
+    provider_output_tx.send(output()).await.unwrap();
+    let forwarded = emit_output_rx.recv().await.unwrap();
+    assert!(forwarded.last_updated.is_some());
+
+    cancel_token.cancel();
+    _ = provider_output_tx.send(output()).await;
+
+    // The forwarder exits and drops its sender without forwarding.
+    assert!(emit_output_rx.recv().await.is_none());
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn output_buffered_at_cancellation_is_dropped() {
+    let cancel_token = CancellationToken::new();
+    let (provider_output_tx, mut emit_output_rx) =
+      spawn_forwarder(&cancel_token);
+
+    // Produced by a run that completed just before cancellation, before
+    // the forwarder got to it.
+    provider_output_tx.send(output()).await.unwrap();
+    cancel_token.cancel();
+
+    assert!(emit_output_rx.recv().await.is_none());
+  }
+
+  /// Provider that sends a request via the shared HTTP client on start.
+  struct HttpProvider {
+    http_client: HttpClient,
+    url: String,
+    events: Arc<Mutex<Vec<&'static str>>>,
+  }
+
+  #[async_trait]
+  impl Provider for HttpProvider {
+    async fn on_start(
+      &mut self,
+      _config_hash: &str,
+      _emit_output_tx: mpsc::Sender<ProviderOutput>,
+    ) {
+      self.events.lock().unwrap().push("request");
+      let _ = self.http_client.get(&self.url).unwrap().send().await;
+      self.events.lock().unwrap().push("response");
+    }
+
+    async fn on_refresh(
+      &mut self,
+      _config_hash: &str,
+      _emit_output_tx: mpsc::Sender<ProviderOutput>,
+    ) {
+    }
+
+    async fn on_stop(&mut self) {
+      self.events.lock().unwrap().push("stop");
+    }
+
+    fn min_refresh_interval(&self) -> Option<Duration> {
+      None
+    }
+  }
+
+  // Runs in real time, since the HTTP client's own timeouts would fire
+  // right away with time paused.
+  #[tokio::test]
+  async fn cancel_during_request_drops_connection() {
+    // Server that accepts the request but never responds. Its task
+    // finishes once the client closes the connection.
+    let listener =
+      TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let (accepted_tx, accepted_rx) = oneshot::channel();
+
+    let server = task::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      accepted_tx.send(()).unwrap();
+
+      let mut buf = [0; 1024];
+      while stream.read(&mut buf).await.is_ok_and(|len| len > 0) {}
+    });
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let cancel_token = CancellationToken::new();
+    let (emit_output_tx, _) = mpsc::channel(1);
+    let (_refresh_tx, refresh_rx) = mpsc::channel(1);
+
+    let provider = HttpProvider {
+      http_client: HttpClient::new(false),
+      url,
+      events: events.clone(),
+    };
+
+    let handle = task::spawn(ProviderRef::start_provider(
+      Box::new(provider),
+      "hash".into(),
+      Duration::ZERO,
+      async {},
+      emit_output_tx,
+      refresh_rx,
+      cancel_token.clone(),
+    ));
+
+    time::timeout(Duration::from_secs(5), accepted_rx)
+      .await
+      .unwrap()
+      .unwrap();
+
+    cancel_token.cancel();
+
+    time::timeout(Duration::from_secs(1), handle)
+      .await
+      .expect("Provider didn't stop promptly.")
+      .unwrap();
+
+    time::timeout(Duration::from_secs(1), server)
+      .await
+      .expect("Connection wasn't closed promptly.")
+      .unwrap();
+
+    assert_eq!(*events.lock().unwrap(), ["request", "stop"]);
   }
 }
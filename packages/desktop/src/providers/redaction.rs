@@ -0,0 +1,430 @@
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    RwLock,
+  },
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::provider_ref::{ProviderOutput, VariablesResult};
+
+/// Fields that are redacted if `fields` isn't set in the config. Covers
+/// values that identify the user or their location.
+const DEFAULT_FIELDS: &[(&str, &[&str])] = &[
+  ("host", &["hostname"]),
+  (
+    "ip",
+    &[
+      "address",
+      "approxCity",
+      "approxCountry",
+      "approxLatitude",
+      "approxLongitude",
+    ],
+  ),
+  (
+    "network",
+    &[
+      "defaultGateway.ssid",
+      "defaultGateway.macAddress",
+      "defaultGateway.ipv4Addresses",
+      "defaultGateway.ipv6Addresses",
+      "defaultInterface.macAddress",
+      "defaultInterface.ipv4Addresses",
+      "defaultInterface.ipv6Addresses",
+      "interfaces.*.macAddress",
+      "interfaces.*.ipv4Addresses",
+      "interfaces.*.ipv6Addresses",
+    ],
+  ),
+];
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RedactionConfig {
+  /// Whether redaction is enabled when the app starts. Can be toggled
+  /// at runtime from the system tray or via `set_redaction`.
+  #[serde(default)]
+  pub enabled: bool,
+
+  /// Value that redacted fields are replaced with.
+  #[serde(default = "default_placeholder")]
+  pub placeholder: String,
+
+  /// Paths of the fields to redact, keyed by provider type (e.g.
+  /// `network: [defaultGateway.ssid]`). Path segments are separated by
+  /// `.`, and `*` matches all elements of an array or values of an
+  /// object. Defaults to fields of the `host`, `ip` and `network`
+  /// providers that identify the user or their location.
+  #[serde(default = "default_fields")]
+  pub fields: HashMap<String, Vec<String>>,
+}
+
+impl Default for RedactionConfig {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      placeholder: default_placeholder(),
+      fields: default_fields(),
+    }
+  }
+}
+
+fn default_placeholder() -> String {
+  "•••".into()
+}
+
+fn default_fields() -> HashMap<String, Vec<String>> {
+  DEFAULT_FIELDS
+    .iter()
+    .map(|(provider_type, paths)| {
+      let paths = paths.iter().map(|path| path.to_string()).collect();
+      (provider_type.to_string(), paths)
+    })
+    .collect()
+}
+
+/// Masks configured fields of provider outputs before they're emitted
+/// to windows (e.g. while streaming).
+pub struct Redaction {
+  enabled: AtomicBool,
+  config: RwLock<RedactionConfig>,
+}
+
+impl Redaction {
+  pub fn new(config: &RedactionConfig) -> Self {
+    Self {
+      enabled: AtomicBool::new(config.enabled),
+      config: RwLock::new(config.clone()),
+    }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.enabled.load(Ordering::Relaxed)
+  }
+
+  /// Enables or disables redaction. Returns the previous state.
+  pub fn set_enabled(&self, enabled: bool) -> bool {
+    self.enabled.swap(enabled, Ordering::Relaxed)
+  }
+
+  /// Updates the fields and placeholder after the config is reloaded.
+  /// Whether redaction is enabled is kept as is.
+  pub fn update_config(&self, config: &RedactionConfig) {
+    *self.config.write().unwrap() = config.clone();
+  }
+
+  /// Gets the output as JSON with its redacted fields masked, or `None`
+  /// if nothing needs to be redacted.
+  ///
+  /// Masked fields are replaced rather than removed, so that widget
+  /// layouts don't change when toggling redaction.
+  pub fn redact(&self, output: &ProviderOutput) -> Option<Value> {
+    if !self.is_enabled() {
+      return None;
+    }
+
+    let type_name = match &output.variables {
+      VariablesResult::Data(variables) => Some(variables.type_name()),
+      _ => output.type_name,
+    };
+
+    let config = self.config.read().unwrap();
+    let paths = config.fields.get(type_name?)?;
+
+    if paths.is_empty() {
+      return None;
+    }
+
+    let mut output = serde_json::to_value(output).ok()?;
+    redact_output(&mut output, paths, &config.placeholder);
+
+    Some(output)
+  }
+}
+
+/// Masks the fields at the given paths of a serialized output.
+///
+/// Transforms can read any field of the data, so computed values are
+/// masked as a whole. Errors (including that of a fallback value) are
+/// masked too, since they can quote redacted values (e.g. an address
+/// that couldn't be reached).
+fn redact_output(output: &mut Value, paths: &[String], placeholder: &str) {
+  if let Some(error) = output.pointer_mut("/variables/error") {
+    mask(error, placeholder);
+  }
+
+  if let Some(data) = output.pointer_mut("/variables/data") {
+    for path in paths {
+      let segments = path.split('.').collect::<Vec<_>>();
+      redact_path(data, &segments, placeholder);
+    }
+
+    let is_fallback = output
+      .pointer("/variables/fallback")
+      .is_some_and(|fallback| fallback == true);
+
+    if is_fallback {
+      if let Some(error) = output.pointer_mut("/variables/data/_error") {
+        mask(error, placeholder);
+      }
+    }
+  }
+
+  if let Some(computed) = output.get_mut("computed") {
+    mask(computed, placeholder);
+  }
+}
+
+/// Masks the value at the given path. Missing fields are ignored.
+fn redact_path(value: &mut Value, path: &[&str], placeholder: &str) {
+  let Some((segment, rest)) = path.split_first() else {
+    mask(value, placeholder);
+    return;
+  };
+
+  match value {
+    Value::Array(items) if *segment == "*" => {
+      for item in items {
+        redact_path(item, rest, placeholder);
+      }
+    }
+    Value::Object(map) if *segment == "*" => {
+      for item in map.values_mut() {
+        redact_path(item, rest, placeholder);
+      }
+    }
+    Value::Object(map) => {
+      if let Some(item) = map.get_mut(*segment) {
+        redact_path(item, rest, placeholder);
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Replaces all values with the placeholder, while keeping the shape of
+/// arrays and objects. Null values are kept, since there's nothing to
+/// hide.
+fn mask(value: &mut Value, placeholder: &str) {
+  match value {
+    Value::Null => {}
+    Value::Array(items) => {
+      for item in items {
+        mask(item, placeholder);
+      }
+    }
+    Value::Object(map) => {
+      for item in map.values_mut() {
+        mask(item, placeholder);
+      }
+    }
+    _ => *value = Value::String(placeholder.to_string()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::{json, Map};
+
+  use super::*;
+  use crate::providers::http_client::NetworkErrorKind;
+
+  fn redaction(fields: &[(&str, &[&str])]) -> Redaction {
+    let fields = fields
+      .iter()
+      .map(|(provider_type, paths)| {
+        let paths = paths.iter().map(|path| path.to_string()).collect();
+        (provider_type.to_string(), paths)
+      })
+      .collect();
+
+    Redaction::new(&RedactionConfig {
+      enabled: true,
+      placeholder: "x".into(),
+      fields,
+    })
+  }
+
+  fn fallback_output(data: Value) -> ProviderOutput {
+    let Value::Object(data) = data else {
+      panic!("Fallback data must be an object.");
+    };
+
+    ProviderOutput {
+      config_hash: "hash".into(),
+      variables: VariablesResult::Fallback(data),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: Some("ip"),
+    }
+  }
+
+  #[test]
+  fn redacts_nested_and_wildcard_paths() {
+    let mut output = json!({
+      "variables": {
+        "data": {
+          "hostname": "my-pc",
+          "interfaces": [
+            { "name": "eth0", "macAddress": "aa:bb" },
+            { "name": "wlan0", "macAddress": null },
+          ],
+          "gateway": { "ipv4Addresses": ["10.0.0.1", "10.0.0.2"] },
+        },
+      },
+    });
+
+    redact_output(
+      &mut output,
+      &[
+        "hostname".into(),
+        "interfaces.*.macAddress".into(),
+        "gateway.ipv4Addresses".into(),
+        "missing.path".into(),
+      ],
+      "x",
+    );
+
+    assert_eq!(
+      output,
+      json!({
+        "variables": {
+          "data": {
+            "hostname": "x",
+            "interfaces": [
+              { "name": "eth0", "macAddress": "x" },
+              { "name": "wlan0", "macAddress": null },
+            ],
+            "gateway": { "ipv4Addresses": ["x", "x"] },
+          },
+        },
+      })
+    );
+  }
+
+  #[test]
+  fn masks_computed_values() {
+    let mut output = json!({
+      "variables": { "data": { "hostname": "my-pc" } },
+      "computed": {
+        "label": "host: my-pc",
+        "length": 5,
+        "parts": ["my", "pc"],
+        "missing": null,
+      },
+    });
+
+    redact_output(&mut output, &["hostname".into()], "x");
+
+    assert_eq!(
+      output["computed"],
+      json!({
+        "label": "x",
+        "length": "x",
+        "parts": ["x", "x"],
+        "missing": null,
+      })
+    );
+  }
+
+  #[test]
+  fn redacts_fallback_values() {
+    let redaction = redaction(&[("ip", &["address"])]);
+
+    let output = fallback_output(json!({
+      "address": "203.0.113.7",
+      "label": "offline",
+      "_error": "Failed to reach 203.0.113.7.",
+    }));
+
+    let redacted = redaction.redact(&output).unwrap();
+
+    assert_eq!(
+      redacted["variables"],
+      json!({
+        "data": {
+          "address": "x",
+          "label": "offline",
+          "_error": "x",
+        },
+        "fallback": true,
+      })
+    );
+  }
+
+  #[test]
+  fn skips_outputs_without_redacted_fields() {
+    let output = fallback_output(json!({ "address": "203.0.113.7" }));
+
+    assert!(redaction(&[("host", &["hostname"])])
+      .redact(&output)
+      .is_none());
+
+    assert!(redaction(&[("ip", &[])]).redact(&output).is_none());
+
+    let disabled = redaction(&[("ip", &["address"])]);
+    disabled.set_enabled(false);
+    assert!(disabled.redact(&output).is_none());
+  }
+
+  #[test]
+  fn masks_errors() {
+    let output = ProviderOutput {
+      variables: VariablesResult::Error(
+        "Failed to reach 203.0.113.7.".into(),
+      ),
+      ..fallback_output(Value::Object(Map::new()))
+    };
+
+    let redacted =
+      redaction(&[("ip", &["address"])]).redact(&output).unwrap();
+
+    assert_eq!(redacted["variables"], json!({ "error": "x" }));
+  }
+
+  #[test]
+  fn masks_network_errors_and_keeps_kind() {
+    let output = ProviderOutput {
+      variables: VariablesResult::Network(
+        "Timed out connecting to 203.0.113.7.".into(),
+        NetworkErrorKind::Timeout,
+      ),
+      ..fallback_output(Value::Object(Map::new()))
+    };
+
+    let redacted =
+      redaction(&[("ip", &["address"])]).redact(&output).unwrap();
+
+    assert_eq!(redacted["variables"]["error"], "x");
+    assert_eq!(
+      redacted["variables"]["networkError"],
+      json!(NetworkErrorKind::Timeout)
+    );
+  }
+
+  #[test]
+  fn skips_errors_without_redacted_fields() {
+    let output = ProviderOutput {
+      variables: VariablesResult::Error("Failed.".into()),
+      ..fallback_output(Value::Object(Map::new()))
+    };
+
+    assert!(redaction(&[("host", &["hostname"])])
+      .redact(&output)
+      .is_none());
+
+    let unknown_type = ProviderOutput {
+      type_name: None,
+      ..output
+    };
+
+    assert!(redaction(&[("ip", &["address"])])
+      .redact(&unknown_type)
+      .is_none());
+  }
+}
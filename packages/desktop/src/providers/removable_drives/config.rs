@@ -0,0 +1,15 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "removable_drives")]
+pub struct RemovableDrivesProviderConfig {
+  /// How often to check for drives being plugged in or removed. Output
+  /// is only emitted when the list of drives changes.
+  #[serde(default = "default_refresh_interval")]
+  pub refresh_interval: u64,
+}
+
+fn default_refresh_interval() -> u64 {
+  1000
+}
@@ -0,0 +1,153 @@
+use sysinfo::Disks;
+
+use super::RemovableDrive;
+
+/// Gets mounted removable drives.
+///
+/// External drives connected via USB are included even if the OS
+/// doesn't report them as removable, which is common for USB hard
+/// drives.
+pub fn removable_drives() -> Vec<RemovableDrive> {
+  Disks::new_with_refreshed_list()
+    .iter()
+    .filter_map(|disk| {
+      let mount_point = disk.mount_point().to_string_lossy().to_string();
+      let bus_type =
+        bus_type(&disk.name().to_string_lossy(), &mount_point);
+
+      if !disk.is_removable() && bus_type.as_deref() != Some("usb") {
+        return None;
+      }
+
+      let label = disk.name().to_string_lossy().to_string();
+
+      Some(RemovableDrive {
+        // Disk names on Linux and MacOS are device paths rather than
+        // volume labels.
+        label: (!label.is_empty() && !label.starts_with("/dev/"))
+          .then_some(label),
+        mount_point,
+        file_system: disk.file_system().to_string_lossy().to_string(),
+        total_space: disk.total_space(),
+        available_space: disk.available_space(),
+        bus_type,
+      })
+    })
+    .collect()
+}
+
+/// Gets the bus type from the sysfs path of the block device.
+#[cfg(target_os = "linux")]
+fn bus_type(device_name: &str, _mount_point: &str) -> Option<String> {
+  let block_name = device_name.strip_prefix("/dev/")?;
+
+  let sysfs_path =
+    std::fs::canonicalize(format!("/sys/class/block/{}", block_name))
+      .ok()?;
+
+  let sysfs_path = sysfs_path.to_string_lossy();
+
+  let bus_type = if sysfs_path.contains("/usb") {
+    "usb"
+  } else if sysfs_path.contains("/mmc") {
+    "sd"
+  } else if sysfs_path.contains("/nvme") {
+    "nvme"
+  } else if sysfs_path.contains("/ata") {
+    "sata"
+  } else {
+    return None;
+  };
+
+  Some(bus_type.to_string())
+}
+
+/// Gets the bus type by querying the storage device of the volume.
+#[cfg(target_os = "windows")]
+fn bus_type(_device_name: &str, mount_point: &str) -> Option<String> {
+  use std::mem;
+
+  use windows::Win32::{
+    Foundation::CloseHandle,
+    System::{
+      Ioctl::{
+        BusTypeMmc, BusTypeNvme, BusTypeSata, BusTypeSd, BusTypeUsb,
+        PropertyStandardQuery, StorageDeviceProperty,
+        IOCTL_STORAGE_QUERY_PROPERTY, STORAGE_DEVICE_DESCRIPTOR,
+        STORAGE_PROPERTY_QUERY,
+      },
+      IO::DeviceIoControl,
+    },
+  };
+
+  let handle = super::eject::open_volume(mount_point, 0).ok()?;
+
+  let query = STORAGE_PROPERTY_QUERY {
+    PropertyId: StorageDeviceProperty,
+    QueryType: PropertyStandardQuery,
+    ..Default::default()
+  };
+
+  let mut descriptor = STORAGE_DEVICE_DESCRIPTOR::default();
+
+  let res = unsafe {
+    DeviceIoControl(
+      handle,
+      IOCTL_STORAGE_QUERY_PROPERTY,
+      Some(&query as *const _ as *const _),
+      mem::size_of::<STORAGE_PROPERTY_QUERY>() as u32,
+      Some(&mut descriptor as *mut _ as *mut _),
+      mem::size_of::<STORAGE_DEVICE_DESCRIPTOR>() as u32,
+      None,
+      None,
+    )
+  };
+
+  _ = unsafe { CloseHandle(handle) };
+  res.ok()?;
+
+  let bus_type = match descriptor.BusType {
+    BusTypeUsb => "usb",
+    BusTypeSd | BusTypeMmc => "sd",
+    BusTypeNvme => "nvme",
+    BusTypeSata => "sata",
+    _ => return None,
+  };
+
+  Some(bus_type.to_string())
+}
+
+/// Gets the bus type via `diskutil`.
+#[cfg(target_os = "macos")]
+fn bus_type(_device_name: &str, mount_point: &str) -> Option<String> {
+  let output = std::process::Command::new("diskutil")
+    .args(["info", mount_point])
+    .output()
+    .ok()?;
+
+  let protocol = String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .find_map(|line| {
+      let (key, value) = line.split_once(':')?;
+      (key.trim() == "Protocol").then(|| value.trim().to_lowercase())
+    })?;
+
+  let bus_type = match protocol.as_str() {
+    "usb" => "usb",
+    "secure digital" => "sd",
+    "pci-express" | "apple fabric" => "nvme",
+    "sata" => "sata",
+    _ => return None,
+  };
+
+  Some(bus_type.to_string())
+}
+
+#[cfg(not(any(
+  target_os = "windows",
+  target_os = "linux",
+  target_os = "macos"
+)))]
+fn bus_type(_device_name: &str, _mount_point: &str) -> Option<String> {
+  None
+}
@@ -0,0 +1,473 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Error returned when a drive can't be ejected because it's in use.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveBusyError {
+  pub kind: &'static str,
+  pub mount_point: String,
+  pub message: String,
+
+  /// Processes that have files open on the drive. This is `None` if
+  /// the processes can't be determined on the current platform.
+  pub processes: Option<Vec<DriveHolder>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveHolder {
+  pub pid: u32,
+  pub name: String,
+}
+
+impl DriveBusyError {
+  fn new(mount_point: &str, processes: Option<Vec<DriveHolder>>) -> Self {
+    let holder_names = processes
+      .as_ref()
+      .filter(|processes| !processes.is_empty())
+      .map(|processes| {
+        processes
+          .iter()
+          .map(|process| process.name.as_str())
+          .collect::<Vec<_>>()
+          .join(", ")
+      });
+
+    let message = match holder_names {
+      Some(names) => format!(
+        "Drive '{}' is in use by: {}. Close these programs and try again.",
+        mount_point, names
+      ),
+      None => format!(
+        "Drive '{}' is in use. Close any programs using it and try again.",
+        mount_point
+      ),
+    };
+
+    Self {
+      kind: "drive_busy",
+      mount_point: mount_point.to_string(),
+      message,
+      processes,
+    }
+  }
+}
+
+impl fmt::Display for DriveBusyError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for DriveBusyError {}
+
+/// Flushes and safely removes the drive mounted at the given path.
+///
+/// Returns a `DriveBusyError` if the drive is in use.
+#[cfg(target_os = "linux")]
+pub fn eject_drive(mount_point: &str) -> anyhow::Result<()> {
+  use std::process::Command;
+
+  use anyhow::{bail, Context};
+  use sysinfo::Disks;
+
+  let disks = Disks::new_with_refreshed_list();
+
+  let device = disks
+    .iter()
+    .find(|disk| disk.mount_point().to_string_lossy() == mount_point)
+    .map(|disk| disk.name().to_string_lossy().to_string())
+    .with_context(|| format!("No drive mounted at '{}'.", mount_point))?;
+
+  // Flush pending writes before unmounting.
+  _ = Command::new("sync")
+    .arg("--file-system")
+    .arg(mount_point)
+    .status();
+
+  let unmount = Command::new("udisksctl")
+    .args(["unmount", "--block-device", &device])
+    .output()
+    .context("Failed to run `udisksctl`. Is udisks2 installed?")?;
+
+  if !unmount.status.success() {
+    let stderr = String::from_utf8_lossy(&unmount.stderr);
+
+    if stderr.contains("busy") {
+      return Err(
+        DriveBusyError::new(mount_point, Some(drive_holders(mount_point)))
+          .into(),
+      );
+    }
+
+    bail!("Failed to unmount '{}': {}", mount_point, stderr.trim());
+  }
+
+  // Powering off the parent device makes it safe to unplug. Not all
+  // devices support this, and the drive is already unmounted, so
+  // failures are ignored.
+  _ = Command::new("udisksctl")
+    .args(["power-off", "--block-device", &device])
+    .output();
+
+  Ok(())
+}
+
+/// Gets processes that have files open under the given mount point.
+#[cfg(target_os = "linux")]
+fn drive_holders(mount_point: &str) -> Vec<DriveHolder> {
+  use std::{fs, path::Path};
+
+  let Ok(proc_entries) = fs::read_dir("/proc") else {
+    return Vec::new();
+  };
+
+  proc_entries
+    .flatten()
+    .filter_map(|proc_entry| {
+      let pid = proc_entry.file_name().to_str()?.parse::<u32>().ok()?;
+
+      // Processes owned by other users can't be inspected.
+      let mut open_paths = fs::read_dir(proc_entry.path().join("fd"))
+        .ok()?
+        .flatten()
+        .filter_map(|fd| fs::read_link(fd.path()).ok())
+        .collect::<Vec<_>>();
+
+      open_paths.extend(fs::read_link(proc_entry.path().join("cwd")).ok());
+
+      let holds_drive = open_paths
+        .iter()
+        .any(|path| path.starts_with(Path::new(mount_point)));
+
+      holds_drive.then(|| DriveHolder {
+        pid,
+        name: fs::read_to_string(proc_entry.path().join("comm"))
+          .map(|name| name.trim().to_string())
+          .unwrap_or_default(),
+      })
+    })
+    .collect()
+}
+
+/// Flushes and safely removes the drive mounted at the given path.
+///
+/// Returns a `DriveBusyError` if the drive is in use.
+#[cfg(target_os = "macos")]
+pub fn eject_drive(mount_point: &str) -> anyhow::Result<()> {
+  use std::process::Command;
+
+  use anyhow::bail;
+
+  let output = Command::new("diskutil")
+    .args(["eject", mount_point])
+    .output()?;
+
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if stderr.contains("in use") || stderr.contains("dissented") {
+      return Err(DriveBusyError::new(mount_point, None).into());
+    }
+
+    bail!("Failed to eject '{}': {}", mount_point, stderr.trim());
+  }
+
+  Ok(())
+}
+
+/// Opens a handle to the volume with the given mount point (e.g. `E:\`).
+#[cfg(target_os = "windows")]
+pub(super) fn open_volume(
+  mount_point: &str,
+  desired_access: u32,
+) -> windows::core::Result<windows::Win32::Foundation::HANDLE> {
+  use windows::{
+    core::HSTRING,
+    Win32::{
+      Foundation::HANDLE,
+      Storage::FileSystem::{
+        CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_READ,
+        FILE_SHARE_WRITE, OPEN_EXISTING,
+      },
+    },
+  };
+
+  let volume_path = format!(r"\\.\{}", mount_point.trim_end_matches('\\'));
+
+  unsafe {
+    CreateFileW(
+      &HSTRING::from(volume_path),
+      desired_access,
+      FILE_SHARE_READ | FILE_SHARE_WRITE,
+      None,
+      OPEN_EXISTING,
+      FILE_FLAGS_AND_ATTRIBUTES(0),
+      HANDLE::default(),
+    )
+  }
+}
+
+/// Flushes and safely removes the drive mounted at the given path.
+///
+/// The volume is locked and dismounted before requesting the device be
+/// ejected, so that ejection fails early if any files are open.
+/// Processes holding the drive aren't available on Windows.
+#[cfg(target_os = "windows")]
+pub fn eject_drive(mount_point: &str) -> anyhow::Result<()> {
+  use std::{mem, thread, time::Duration};
+
+  use anyhow::{bail, Context};
+  use windows::Win32::{
+    Devices::DeviceAndDriverInstallation::{
+      CM_Get_Parent, CM_Request_Device_EjectW, CR_SUCCESS, PNP_VETO_TYPE,
+    },
+    Foundation::{CloseHandle, GENERIC_READ, GENERIC_WRITE},
+    Storage::FileSystem::FlushFileBuffers,
+    System::{
+      Ioctl::{
+        FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME,
+        IOCTL_STORAGE_GET_DEVICE_NUMBER, STORAGE_DEVICE_NUMBER,
+      },
+      IO::DeviceIoControl,
+    },
+  };
+
+  let volume = open_volume(mount_point, (GENERIC_READ | GENERIC_WRITE).0)
+    .with_context(|| format!("No drive mounted at '{}'.", mount_point))?;
+
+  let result = (|| -> anyhow::Result<()> {
+    let mut device_number = STORAGE_DEVICE_NUMBER::default();
+
+    unsafe {
+      _ = FlushFileBuffers(volume);
+
+      // Locking fails if any files on the volume are open.
+      if DeviceIoControl(
+        volume,
+        FSCTL_LOCK_VOLUME,
+        None,
+        0,
+        None,
+        0,
+        None,
+        None,
+      )
+      .is_err()
+      {
+        return Err(DriveBusyError::new(mount_point, None).into());
+      }
+
+      DeviceIoControl(
+        volume,
+        FSCTL_DISMOUNT_VOLUME,
+        None,
+        0,
+        None,
+        0,
+        None,
+        None,
+      )?;
+
+      DeviceIoControl(
+        volume,
+        IOCTL_STORAGE_GET_DEVICE_NUMBER,
+        None,
+        0,
+        Some(&mut device_number as *mut _ as *mut _),
+        mem::size_of::<STORAGE_DEVICE_NUMBER>() as u32,
+        None,
+        None,
+      )?;
+    }
+
+    let device_instance = disk_device_instance(device_number.DeviceNumber)
+      .context("Failed to find device of drive.")?;
+
+    // The removable device is the parent of the disk (e.g. the USB mass
+    // storage device).
+    let mut parent_instance = 0;
+
+    if unsafe { CM_Get_Parent(&mut parent_instance, device_instance, 0) }
+      != CR_SUCCESS
+    {
+      bail!("Failed to find device of drive.");
+    }
+
+    // Ejection can be vetoed temporarily right after dismounting, so
+    // retry a few times.
+    for _ in 0..3 {
+      let mut veto_type = PNP_VETO_TYPE::default();
+      let mut veto_name = [0u16; 260];
+
+      let res = unsafe {
+        CM_Request_Device_EjectW(
+          parent_instance,
+          Some(&mut veto_type),
+          Some(&mut veto_name),
+          0,
+        )
+      };
+
+      if res == CR_SUCCESS && veto_type.0 == 0 {
+        return Ok(());
+      }
+
+      thread::sleep(Duration::from_millis(500));
+    }
+
+    Err(DriveBusyError::new(mount_point, None).into())
+  })();
+
+  // Closing the handle also unlocks the volume.
+  _ = unsafe { CloseHandle(volume) };
+
+  result
+}
+
+/// Gets the device instance of the disk with the given device number.
+#[cfg(target_os = "windows")]
+fn disk_device_instance(device_number: u32) -> Option<u32> {
+  use std::mem;
+
+  use windows::{
+    core::PCWSTR,
+    Win32::{
+      Devices::DeviceAndDriverInstallation::{
+        SetupDiDestroyDeviceInfoList, SetupDiEnumDeviceInterfaces,
+        SetupDiGetClassDevsW, SetupDiGetDeviceInterfaceDetailW,
+        DIGCF_DEVICEINTERFACE, DIGCF_PRESENT, SP_DEVICE_INTERFACE_DATA,
+        SP_DEVICE_INTERFACE_DETAIL_DATA_W, SP_DEVINFO_DATA,
+      },
+      Foundation::{CloseHandle, HANDLE, HWND},
+      Storage::FileSystem::{
+        CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_READ,
+        FILE_SHARE_WRITE, OPEN_EXISTING,
+      },
+      System::{
+        Ioctl::{
+          GUID_DEVINTERFACE_DISK, IOCTL_STORAGE_GET_DEVICE_NUMBER,
+          STORAGE_DEVICE_NUMBER,
+        },
+        IO::DeviceIoControl,
+      },
+    },
+  };
+
+  unsafe {
+    let device_info = SetupDiGetClassDevsW(
+      Some(&GUID_DEVINTERFACE_DISK),
+      PCWSTR::null(),
+      HWND::default(),
+      DIGCF_PRESENT | DIGCF_DEVICEINTERFACE,
+    )
+    .ok()?;
+
+    let mut found_instance = None;
+
+    for index in 0.. {
+      let mut interface_data = SP_DEVICE_INTERFACE_DATA {
+        cbSize: mem::size_of::<SP_DEVICE_INTERFACE_DATA>() as u32,
+        ..Default::default()
+      };
+
+      if SetupDiEnumDeviceInterfaces(
+        device_info,
+        None,
+        &GUID_DEVINTERFACE_DISK,
+        index,
+        &mut interface_data,
+      )
+      .is_err()
+      {
+        break;
+      }
+
+      // Detail data is variable-length, so a `u32` buffer is used to
+      // guarantee its alignment.
+      let mut required_size = 0;
+      _ = SetupDiGetDeviceInterfaceDetailW(
+        device_info,
+        &interface_data,
+        None,
+        0,
+        Some(&mut required_size),
+        None,
+      );
+
+      let mut buffer = vec![0u32; required_size as usize / 4 + 1];
+      let detail_data =
+        buffer.as_mut_ptr() as *mut SP_DEVICE_INTERFACE_DETAIL_DATA_W;
+
+      (*detail_data).cbSize =
+        mem::size_of::<SP_DEVICE_INTERFACE_DETAIL_DATA_W>() as u32;
+
+      let mut devinfo_data = SP_DEVINFO_DATA {
+        cbSize: mem::size_of::<SP_DEVINFO_DATA>() as u32,
+        ..Default::default()
+      };
+
+      if SetupDiGetDeviceInterfaceDetailW(
+        device_info,
+        &interface_data,
+        Some(detail_data),
+        required_size,
+        None,
+        Some(&mut devinfo_data),
+      )
+      .is_err()
+      {
+        continue;
+      }
+
+      let Ok(disk) = CreateFileW(
+        PCWSTR((*detail_data).DevicePath.as_ptr()),
+        0,
+        FILE_SHARE_READ | FILE_SHARE_WRITE,
+        None,
+        OPEN_EXISTING,
+        FILE_FLAGS_AND_ATTRIBUTES(0),
+        HANDLE::default(),
+      ) else {
+        continue;
+      };
+
+      let mut disk_number = STORAGE_DEVICE_NUMBER::default();
+
+      let res = DeviceIoControl(
+        disk,
+        IOCTL_STORAGE_GET_DEVICE_NUMBER,
+        None,
+        0,
+        Some(&mut disk_number as *mut _ as *mut _),
+        mem::size_of::<STORAGE_DEVICE_NUMBER>() as u32,
+        None,
+        None,
+      );
+
+      _ = CloseHandle(disk);
+
+      if res.is_ok() && disk_number.DeviceNumber == device_number {
+        found_instance = Some(devinfo_data.DevInst);
+        break;
+      }
+    }
+
+    _ = SetupDiDestroyDeviceInfoList(device_info);
+
+    found_instance
+  }
+}
+
+#[cfg(not(any(
+  target_os = "windows",
+  target_os = "linux",
+  target_os = "macos"
+)))]
+pub fn eject_drive(_mount_point: &str) -> anyhow::Result<()> {
+  anyhow::bail!(
+    "Ejecting drives is not supported on this operating system."
+  )
+}
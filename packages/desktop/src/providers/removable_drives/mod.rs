@@ -0,0 +1,10 @@
+mod config;
+mod drives;
+mod eject;
+mod provider;
+mod variables;
+
+pub use config::*;
+pub use eject::*;
+pub use provider::*;
+pub use variables::*;
@@ -0,0 +1,130 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tokio::{
+  sync::{mpsc::Sender, Notify},
+  task::{self, AbortHandle},
+  time,
+};
+
+use super::{
+  drives::removable_drives, RemovableDrivesProviderConfig,
+  RemovableDrivesVariables,
+};
+use crate::providers::{
+  provider::Provider,
+  provider_ref::{ProviderOutput, VariablesResult},
+  variables::ProviderVariables,
+};
+
+pub struct RemovableDrivesProvider {
+  pub config: Arc<RemovableDrivesProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  drives_changed: Arc<Notify>,
+}
+
+impl RemovableDrivesProvider {
+  pub fn new(
+    config: RemovableDrivesProviderConfig,
+    drives_changed: Arc<Notify>,
+  ) -> RemovableDrivesProvider {
+    RemovableDrivesProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      drives_changed,
+    }
+  }
+
+  async fn get_variables() -> RemovableDrivesVariables {
+    // Enumerating drives can block on slow devices.
+    let drives = task::spawn_blocking(removable_drives)
+      .await
+      .unwrap_or_default();
+
+    RemovableDrivesVariables { drives }
+  }
+
+  fn to_output(
+    config_hash: &str,
+    variables: RemovableDrivesVariables,
+  ) -> ProviderOutput {
+    ProviderOutput {
+      config_hash: config_hash.to_string(),
+      variables: VariablesResult::Data(
+        ProviderVariables::RemovableDrives(variables),
+      ),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+}
+
+#[async_trait]
+impl Provider for RemovableDrivesProvider {
+  fn min_refresh_interval(&self) -> Option<Duration> {
+    // Output is only emitted on changes, so it's always up to date.
+    None
+  }
+
+  async fn on_start(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let config = self.config.clone();
+    let config_hash = config_hash.to_string();
+    let drives_changed = self.drives_changed.clone();
+
+    let task_handle = task::spawn(async move {
+      let mut interval =
+        time::interval(Duration::from_millis(config.refresh_interval));
+
+      let mut prev_variables = None;
+
+      loop {
+        // Poll for hotplug changes, or check immediately when a drive is
+        // ejected.
+        tokio::select! {
+          _ = interval.tick() => {},
+          _ = drives_changed.notified() => {},
+        }
+
+        let variables = Self::get_variables().await;
+
+        if prev_variables.as_ref() == Some(&variables) {
+          continue;
+        }
+
+        prev_variables = Some(variables.clone());
+
+        _ = emit_output_tx
+          .send(Self::to_output(&config_hash, variables))
+          .await;
+      }
+    });
+
+    self.abort_handle = Some(task_handle.abort_handle());
+    _ = task_handle.await;
+  }
+
+  async fn on_refresh(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let variables = Self::get_variables().await;
+
+    _ = emit_output_tx
+      .send(Self::to_output(config_hash, variables))
+      .await;
+  }
+
+  async fn on_stop(&mut self) {
+    if let Some(handle) = &self.abort_handle {
+      handle.abort();
+    }
+  }
+}
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovableDrivesVariables {
+  pub drives: Vec<RemovableDrive>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovableDrive {
+  pub label: Option<String>,
+  pub mount_point: String,
+  pub file_system: String,
+  pub total_space: u64,
+  pub available_space: u64,
+
+  /// Bus that the drive is connected via (e.g. `usb` or `sd`), if it
+  /// could be determined.
+  pub bus_type: Option<String>,
+}
@@ -0,0 +1,76 @@
+use std::{fmt, io, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// How often the retained sizes of providers are measured.
+pub const RETAINED_SIZE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// State that a provider retains between runs and that can grow over
+/// time, such as history buffers and caches.
+#[async_trait]
+pub trait RetainedSize: Send + Sync {
+  /// Approximate size of the state in bytes.
+  async fn retained_size(&self) -> usize;
+
+  /// Clears state that can be rebuilt (i.e. caches). Does nothing for
+  /// state that can't be, such as history.
+  async fn evict(&self) {}
+}
+
+/// Retained state of a provider, which is measured periodically by the
+/// provider manager.
+#[derive(Clone, Default)]
+pub struct RetainedState(Vec<Arc<dyn RetainedSize>>);
+
+impl RetainedState {
+  pub fn new(state: Vec<Arc<dyn RetainedSize>>) -> Self {
+    Self(state)
+  }
+
+  pub async fn size(&self) -> usize {
+    let mut size = 0;
+
+    for state in &self.0 {
+      size += state.retained_size().await;
+    }
+
+    size
+  }
+
+  pub async fn evict(&self) {
+    for state in &self.0 {
+      state.evict().await;
+    }
+  }
+}
+
+impl fmt::Debug for RetainedState {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "RetainedState({} entries)", self.0.len())
+  }
+}
+
+/// Approximate size in bytes of a value, based on its JSON encoding.
+pub fn json_size<T: Serialize + ?Sized>(value: &T) -> usize {
+  let mut counter = ByteCounter(0);
+
+  match serde_json::to_writer(&mut counter, value) {
+    Ok(_) => counter.0,
+    Err(_) => 0,
+  }
+}
+
+/// Writer that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0 += buf.len();
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
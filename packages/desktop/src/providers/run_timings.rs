@@ -0,0 +1,135 @@
+use std::{
+  collections::{HashSet, VecDeque},
+  time::Duration,
+};
+
+/// Number of recent runs that the p95 is computed over.
+const MAX_SAMPLES: usize = 100;
+
+/// Execution times of a provider's most recent runs.
+#[derive(Debug, Clone, Default)]
+pub struct RunTimings {
+  /// Durations of the most recent runs, oldest first.
+  samples: VecDeque<Duration>,
+}
+
+impl RunTimings {
+  pub fn record(&mut self, duration: Duration) {
+    if self.samples.len() == MAX_SAMPLES {
+      self.samples.pop_front();
+    }
+
+    self.samples.push_back(duration);
+  }
+
+  /// Duration of the most recent run.
+  pub fn last(&self) -> Option<Duration> {
+    self.samples.back().copied()
+  }
+
+  /// 95th percentile of the recent run durations (nearest-rank).
+  pub fn p95(&self) -> Option<Duration> {
+    if self.samples.is_empty() {
+      return None;
+    }
+
+    let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+    sorted.sort_unstable();
+
+    let rank = (sorted.len() * 95).div_ceil(100);
+    sorted.get(rank.saturating_sub(1)).copied()
+  }
+}
+
+/// Providers whose most recent run was slow, so that a slow provider is
+/// only warned about once rather than on every run.
+#[derive(Debug, Default)]
+pub struct SlowProviders {
+  config_hashes: HashSet<String>,
+}
+
+impl SlowProviders {
+  /// Records whether the provider's most recent run was slow. Returns
+  /// `true` if its runs have just become slow.
+  pub fn record(&mut self, config_hash: &str, is_slow: bool) -> bool {
+    if is_slow {
+      self.config_hashes.insert(config_hash.to_string())
+    } else {
+      self.config_hashes.remove(config_hash);
+      false
+    }
+  }
+
+  pub fn remove(&mut self, config_hash: &str) {
+    self.config_hashes.remove(config_hash);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn millis(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+  }
+
+  #[test]
+  fn empty_timings() {
+    let timings = RunTimings::default();
+
+    assert_eq!(timings.last(), None);
+    assert_eq!(timings.p95(), None);
+  }
+
+  #[test]
+  fn p95_is_nearest_rank() {
+    let mut timings = RunTimings::default();
+
+    for i in (1..=20).rev() {
+      timings.record(millis(i));
+    }
+
+    assert_eq!(timings.last(), Some(millis(1)));
+    assert_eq!(timings.p95(), Some(millis(19)));
+  }
+
+  #[test]
+  fn p95_of_single_sample() {
+    let mut timings = RunTimings::default();
+    timings.record(millis(7));
+
+    assert_eq!(timings.p95(), Some(millis(7)));
+  }
+
+  #[test]
+  fn oldest_samples_are_dropped() {
+    let mut timings = RunTimings::default();
+    timings.record(millis(10_000));
+
+    for _ in 0..MAX_SAMPLES {
+      timings.record(millis(1));
+    }
+
+    assert_eq!(timings.p95(), Some(millis(1)));
+  }
+
+  #[test]
+  fn slow_provider_is_reported_once() {
+    let mut slow_providers = SlowProviders::default();
+
+    assert!(slow_providers.record("a", true));
+    assert!(!slow_providers.record("a", true));
+    assert!(slow_providers.record("b", true));
+    assert!(!slow_providers.record("a", false));
+    assert!(slow_providers.record("a", true));
+  }
+
+  #[test]
+  fn removed_provider_is_reported_again() {
+    let mut slow_providers = SlowProviders::default();
+    slow_providers.record("a", true);
+    slow_providers.remove("a");
+
+    assert!(slow_providers.record("a", true));
+  }
+}
@@ -0,0 +1,224 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use super::power_state;
+
+/// Max difference between the time elapsed on the wall clock and on the
+/// monotonic clock before it's treated as a clock step.
+const MAX_CLOCK_DRIFT: Duration = Duration::from_secs(2);
+
+/// Factor of the expected interval between samples after which the gap
+/// is treated as a suspend (e.g. the machine went to sleep).
+const MAX_GAP_FACTOR: u32 = 4;
+
+/// Measures the time between samples of cumulative counters for rate
+/// calculations.
+///
+/// Elapsed time is measured on the monotonic clock, so that the wall
+/// clock being stepped (e.g. by NTP) doesn't skew rates. The wall clock
+/// is only used to detect discontinuities: when it disagrees with the
+/// monotonic clock, or when samples are much further apart than
+/// expected, counters may have kept running while the monotonic clock
+/// didn't (or vice versa), and the sample is discarded.
+#[derive(Debug, Default)]
+pub struct SampleClock {
+  prev_sample: Option<(Instant, SystemTime)>,
+}
+
+impl SampleClock {
+  /// Creates a clock with its first sample taken now.
+  pub fn started() -> Self {
+    Self {
+      prev_sample: Some((Instant::now(), SystemTime::now())),
+    }
+  }
+
+  /// Records a sample and gets the time elapsed since the previous one.
+  ///
+  /// `expected_interval` is how often samples are normally taken. It's
+  /// scaled by the energy saver multiplier, since interval providers are
+  /// refreshed less often while energy saver is on.
+  ///
+  /// Returns `None` for the first sample, and for the first sample after
+  /// a discontinuity. Rates over such a sample are meaningless, but the
+  /// caller should still record its counter values as the new baseline.
+  pub fn tick(&mut self, expected_interval: Duration) -> Option<Duration> {
+    let max_gap = expected_interval
+      .saturating_mul(MAX_GAP_FACTOR)
+      .saturating_mul(power_state::interval_multiplier() as u32)
+      + MAX_CLOCK_DRIFT;
+
+    self.tick_at(Instant::now(), SystemTime::now(), max_gap)
+  }
+
+  fn tick_at(
+    &mut self,
+    now: Instant,
+    wall_now: SystemTime,
+    max_gap: Duration,
+  ) -> Option<Duration> {
+    let (prev_now, prev_wall_now) =
+      self.prev_sample.replace((now, wall_now))?;
+
+    let elapsed = now.saturating_duration_since(prev_now);
+
+    // Errors if the wall clock went backwards.
+    let wall_elapsed = wall_now.duration_since(prev_wall_now).ok()?;
+
+    let drift = match wall_elapsed > elapsed {
+      true => wall_elapsed - elapsed,
+      false => elapsed - wall_elapsed,
+    };
+
+    match elapsed.is_zero() || elapsed > max_gap || drift > MAX_CLOCK_DRIFT
+    {
+      true => None,
+      false => Some(elapsed),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MAX_GAP: Duration = Duration::from_secs(10);
+
+  /// Clock with its first sample at the given times.
+  fn clock_at(now: Instant, wall_now: SystemTime) -> SampleClock {
+    let mut clock = SampleClock::default();
+    assert_eq!(clock.tick_at(now, wall_now, MAX_GAP), None);
+    clock
+  }
+
+  fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+  }
+
+  #[test]
+  fn first_sample_has_no_elapsed_time() {
+    let mut clock = SampleClock::default();
+
+    assert_eq!(
+      clock.tick_at(Instant::now(), SystemTime::now(), MAX_GAP),
+      None
+    );
+  }
+
+  #[test]
+  fn steady_samples_measure_monotonic_time() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start, wall_start);
+
+    for i in 1..=3 {
+      assert_eq!(
+        clock.tick_at(
+          start + secs(2 * i),
+          wall_start + secs(2 * i),
+          MAX_GAP
+        ),
+        Some(secs(2))
+      );
+    }
+  }
+
+  #[test]
+  fn small_drift_is_tolerated() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start, wall_start);
+
+    // Elapsed time comes from the monotonic clock.
+    assert_eq!(
+      clock.tick_at(
+        start + secs(2),
+        wall_start + Duration::from_millis(3500),
+        MAX_GAP
+      ),
+      Some(secs(2))
+    );
+  }
+
+  #[test]
+  fn wall_clock_step_forward_is_discontinuity() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start, wall_start);
+
+    assert_eq!(
+      clock.tick_at(start + secs(2), wall_start + secs(3602), MAX_GAP),
+      None
+    );
+
+    // The sample after the step is the new baseline.
+    assert_eq!(
+      clock.tick_at(start + secs(4), wall_start + secs(3604), MAX_GAP),
+      Some(secs(2))
+    );
+  }
+
+  #[test]
+  fn wall_clock_going_backwards_is_discontinuity() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start + secs(60), wall_start + secs(60));
+
+    assert_eq!(clock.tick_at(start + secs(62), wall_start, MAX_GAP), None);
+    assert_eq!(
+      clock.tick_at(start + secs(64), wall_start + secs(2), MAX_GAP),
+      Some(secs(2))
+    );
+  }
+
+  #[test]
+  fn suspend_with_stopped_monotonic_clock_is_discontinuity() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start, wall_start);
+
+    // The monotonic clock doesn't advance while suspended on some
+    // platforms, whereas the wall clock does.
+    assert_eq!(
+      clock.tick_at(start + secs(2), wall_start + secs(1800), MAX_GAP),
+      None
+    );
+  }
+
+  #[test]
+  fn gap_longer_than_max_is_discontinuity() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start, wall_start);
+
+    assert_eq!(
+      clock.tick_at(start + secs(11), wall_start + secs(11), MAX_GAP),
+      None
+    );
+    assert_eq!(
+      clock.tick_at(start + secs(21), wall_start + secs(21), MAX_GAP),
+      Some(secs(10))
+    );
+  }
+
+  #[test]
+  fn zero_elapsed_is_discontinuity() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start, wall_start);
+
+    assert_eq!(clock.tick_at(start, wall_start, MAX_GAP), None);
+  }
+
+  #[test]
+  fn monotonic_time_going_backwards_is_discontinuity() {
+    let (start, wall_start) = (Instant::now(), SystemTime::now());
+    let mut clock = clock_at(start + secs(2), wall_start);
+
+    // Elapsed time saturates at zero.
+    assert_eq!(clock.tick_at(start, wall_start + secs(2), MAX_GAP), None);
+  }
+
+  #[test]
+  fn started_clock_has_baseline() {
+    let mut clock = SampleClock::started();
+    let (now, wall_now) = clock.prev_sample.unwrap();
+
+    assert_eq!(
+      clock.tick_at(now + secs(1), wall_now + secs(1), MAX_GAP),
+      Some(secs(1))
+    );
+  }
+}
@@ -0,0 +1,803 @@
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use anyhow::{bail, Context};
+use chrono::{
+  DateTime, Datelike, Local, LocalResult, NaiveDate, NaiveDateTime,
+  TimeZone,
+};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+/// Max time that the scheduler sleeps between checks. Sleeps don't
+/// advance while the machine is suspended, so this bounds how long it
+/// takes to notice missed triggers after resuming.
+pub const SCHEDULER_MAX_SLEEP: Duration = Duration::from_secs(30);
+
+/// Default window in milliseconds within which a missed trigger is still
+/// fired (e.g. after the machine wakes from sleep).
+pub const DEFAULT_SCHEDULE_GRACE: u64 = 60 * 60 * 1000;
+
+/// Seconds that a trigger can fire late before it's considered missed,
+/// rather than just delayed by the scheduler's sleep.
+const MISSED_THRESHOLD_SECS: i64 = 60;
+
+/// Max number of days to search for the next occurrence. Covers
+/// schedules that only match on leap days.
+const MAX_SEARCH_DAYS: u32 = 8 * 366;
+
+/// Max length of a gap in local time when clocks go forward.
+const MAX_GAP_MINUTES: i64 = 3 * 60;
+
+const MONTH_NAMES: &[&str] = &[
+  "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct",
+  "nov", "dec",
+];
+
+const WEEKDAY_NAMES: &[&str] =
+  &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// Schedule on which a provider is refreshed, in local time.
+///
+/// Either a cron expression with 5 fields (e.g. `0 7,13 * * *`), a cron
+/// alias (e.g. `@daily`) or a list of times on given days (e.g.
+/// `daily@07:00`, `weekdays@07:00,13:00` or `mon,thu@09:30`).
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Schedule {
+  exprs: Vec<CronExpr>,
+}
+
+impl Schedule {
+  /// Gets the first trigger strictly after the given time.
+  ///
+  /// Times that don't exist because clocks go forward fire at the end
+  /// of the gap, and times that occur twice because clocks go back only
+  /// fire on their first occurrence.
+  pub fn next_after<Tz: TimeZone>(
+    &self,
+    after: &DateTime<Tz>,
+  ) -> Option<DateTime<Tz>> {
+    self
+      .exprs
+      .iter()
+      .filter_map(|expr| expr.next_after(after))
+      .min()
+  }
+}
+
+impl FromStr for Schedule {
+  type Err = anyhow::Error;
+
+  fn from_str(schedule: &str) -> anyhow::Result<Self> {
+    let schedule = schedule.trim().to_ascii_lowercase();
+
+    let exprs = match schedule.split_once('@') {
+      Some(("", alias)) => vec![CronExpr::parse(match alias {
+        "hourly" => "0 * * * *",
+        "daily" | "midnight" => "0 0 * * *",
+        "weekly" => "0 0 * * 0",
+        "monthly" => "0 0 1 * *",
+        "yearly" | "annually" => "0 0 1 1 *",
+        _ => bail!("Unknown schedule alias '@{}'.", alias),
+      })?],
+      Some((days, times)) => {
+        let days_of_week = match days {
+          "daily" => "*",
+          "weekdays" => "mon-fri",
+          "weekends" => "sat,sun",
+          _ => days,
+        };
+
+        times
+          .split(',')
+          .map(|time| {
+            let (hour, minute) = time
+              .trim()
+              .split_once(':')
+              .with_context(|| format!("Invalid time '{}'.", time))?;
+
+            CronExpr::parse(&format!(
+              "{} {} * * {}",
+              minute, hour, days_of_week
+            ))
+          })
+          .collect::<anyhow::Result<Vec<_>>>()?
+      }
+      None => vec![CronExpr::parse(&schedule)?],
+    };
+
+    Ok(Self { exprs })
+  }
+}
+
+impl TryFrom<String> for Schedule {
+  type Error = anyhow::Error;
+
+  fn try_from(schedule: String) -> anyhow::Result<Self> {
+    schedule
+      .parse()
+      .with_context(|| format!("Invalid schedule '{}'.", schedule))
+  }
+}
+
+/// Cron expression with minute, hour, day of month, month and day of
+/// week fields. Each field is stored as a bitset of the matching values.
+#[derive(Debug, Clone, PartialEq)]
+struct CronExpr {
+  minutes: u64,
+  hours: u32,
+  days_of_month: u32,
+  months: u16,
+  days_of_week: u8,
+
+  /// Whether the day of month and day of week fields are unrestricted
+  /// (i.e. start with `*`). If both are restricted, a day matches if
+  /// either field matches, same as with standard cron.
+  any_day_of_month: bool,
+  any_day_of_week: bool,
+}
+
+impl CronExpr {
+  fn parse(expr: &str) -> anyhow::Result<Self> {
+    let fields = expr.split_whitespace().collect::<Vec<_>>();
+
+    let [minute, hour, day_of_month, month, day_of_week] = fields[..]
+    else {
+      bail!("Cron expressions need 5 fields, got {}.", fields.len());
+    };
+
+    // Sunday is both 0 and 7.
+    let days_of_week = parse_field(day_of_week, 0, 7, WEEKDAY_NAMES)
+      .context("Invalid day of week.")?;
+
+    Ok(Self {
+      minutes: parse_field(minute, 0, 59, &[])
+        .context("Invalid minute.")?,
+      hours: parse_field(hour, 0, 23, &[]).context("Invalid hour.")?
+        as u32,
+      days_of_month: parse_field(day_of_month, 1, 31, &[])
+        .context("Invalid day of month.")? as u32,
+      months: parse_field(month, 1, 12, MONTH_NAMES)
+        .context("Invalid month.")? as u16,
+      days_of_week: ((days_of_week | days_of_week >> 7) & 0x7f) as u8,
+      any_day_of_month: day_of_month.starts_with('*'),
+      any_day_of_week: day_of_week.starts_with('*'),
+    })
+  }
+
+  fn matches_date(&self, date: NaiveDate) -> bool {
+    let month_matches = self.months & (1 << date.month()) != 0;
+
+    let day_of_month_matches = self.days_of_month & (1 << date.day()) != 0;
+
+    let day_of_week_matches = self.days_of_week
+      & (1 << date.weekday().num_days_from_sunday())
+      != 0;
+
+    let day_matches = match (self.any_day_of_month, self.any_day_of_week) {
+      (true, true) => true,
+      (true, false) => day_of_week_matches,
+      (false, true) => day_of_month_matches,
+      (false, false) => day_of_month_matches || day_of_week_matches,
+    };
+
+    month_matches && day_matches
+  }
+
+  fn next_after<Tz: TimeZone>(
+    &self,
+    after: &DateTime<Tz>,
+  ) -> Option<DateTime<Tz>> {
+    let timezone = after.timezone();
+    let start_date = after.naive_local().date();
+
+    // A trigger shortly before midnight can be shifted past `after` by a
+    // gap, so the search starts the day before.
+    let dates = start_date
+      .pred_opt()?
+      .iter_days()
+      .take(MAX_SEARCH_DAYS as usize)
+      .filter(|date| self.matches_date(*date));
+
+    for date in dates {
+      for hour in bits(self.hours as u64, 24) {
+        for minute in bits(self.minutes, 60) {
+          let naive = date.and_hms_opt(hour, minute, 0)?;
+
+          match resolve_local(&timezone, naive) {
+            Some(time) if time > *after => return Some(time),
+            _ => {}
+          }
+        }
+      }
+    }
+
+    None
+  }
+}
+
+/// Parses a cron field into a bitset of the matching values.
+///
+/// Supports `*`, single values, ranges (e.g. `1-5`), steps (e.g. `*/15`
+/// or `10-40/10`) and comma-separated lists of these. Values can also
+/// be given by name (e.g. `mon` or `jan`).
+fn parse_field(
+  field: &str,
+  min: u32,
+  max: u32,
+  names: &[&str],
+) -> anyhow::Result<u64> {
+  let parse_value = |value: &str| -> anyhow::Result<u32> {
+    let named_value = names
+      .iter()
+      .position(|name| *name == value)
+      .map(|index| index as u32 + min);
+
+    match named_value {
+      Some(value) => Ok(value),
+      None => value
+        .parse()
+        .with_context(|| format!("Invalid value '{}'.", value)),
+    }
+  };
+
+  let mut bitset = 0;
+
+  for part in field.split(',') {
+    let (range, step) = match part.split_once('/') {
+      Some((range, step)) => (
+        range,
+        step
+          .parse::<u32>()
+          .with_context(|| format!("Invalid step '{}'.", step))?,
+      ),
+      None => (part, 1),
+    };
+
+    let (start, end) = match range.split_once('-') {
+      _ if range == "*" => (min, max),
+      Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+      // A single value with a step runs until the max (e.g. `5/15`).
+      None if step > 1 => (parse_value(range)?, max),
+      None => {
+        let value = parse_value(range)?;
+        (value, value)
+      }
+    };
+
+    if step == 0 || start < min || end > max || start > end {
+      bail!("Invalid range '{}', expected {}-{}.", part, min, max);
+    }
+
+    for value in (start..=end).step_by(step as usize) {
+      bitset |= 1 << value;
+    }
+  }
+
+  Ok(bitset)
+}
+
+/// Iterates the set bits of a bitset below `len`.
+fn bits(bitset: u64, len: u32) -> impl Iterator<Item = u32> {
+  (0..len).filter(move |bit| bitset & (1 << bit) != 0)
+}
+
+/// Converts a local time to a time in the given timezone.
+///
+/// Ambiguous times resolve to their first occurrence, and times within
+/// a gap resolve to the first valid time after it.
+fn resolve_local<Tz: TimeZone>(
+  timezone: &Tz,
+  naive: NaiveDateTime,
+) -> Option<DateTime<Tz>> {
+  (0..=MAX_GAP_MINUTES).find_map(|offset| {
+    let naive = naive + chrono::Duration::minutes(offset);
+
+    match timezone.from_local_datetime(&naive) {
+      LocalResult::Single(time) => Some(time),
+      LocalResult::Ambiguous(earliest, _) => Some(earliest),
+      LocalResult::None => None,
+    }
+  })
+}
+
+/// Provider with a schedule, along with when it's next due.
+struct ScheduledProvider {
+  schedule: Schedule,
+  next_due: DateTime<Local>,
+
+  /// Window within which a missed trigger is still fired.
+  grace: chrono::Duration,
+}
+
+/// Keeps track of when scheduled providers are next due.
+///
+/// Triggers are checked against the wall clock, so that triggers missed
+/// while the machine was asleep are noticed on resume.
+#[derive(Default)]
+pub struct Scheduler {
+  providers: HashMap<String, ScheduledProvider>,
+}
+
+impl Scheduler {
+  /// Adds a provider to be refreshed on the given schedule.
+  pub fn add(
+    &mut self,
+    config_hash: &str,
+    schedule: Schedule,
+    grace: u64,
+  ) {
+    let Some(next_due) = schedule.next_after(&Local::now()) else {
+      warn!(
+        "Schedule of provider {} never triggers. Ignoring it.",
+        config_hash
+      );
+
+      return;
+    };
+
+    self.providers.insert(
+      config_hash.to_string(),
+      ScheduledProvider {
+        schedule,
+        next_due,
+        grace: chrono::Duration::milliseconds(grace as i64),
+      },
+    );
+  }
+
+  pub fn remove(&mut self, config_hash: &str) {
+    self.providers.remove(config_hash);
+  }
+
+  /// Duration until the next provider is due, capped at
+  /// `SCHEDULER_MAX_SLEEP`.
+  pub fn sleep_duration(&self, now: DateTime<Local>) -> Duration {
+    self
+      .providers
+      .values()
+      .map(|provider| {
+        (provider.next_due - now).to_std().unwrap_or(Duration::ZERO)
+      })
+      .min()
+      .unwrap_or(SCHEDULER_MAX_SLEEP)
+      .min(SCHEDULER_MAX_SLEEP)
+  }
+
+  /// Gets the config hashes of providers that are due, and advances
+  /// their schedules.
+  ///
+  /// Missed triggers are collapsed into a single one, which is skipped
+  /// if it's later than the provider's grace window.
+  pub fn take_due(&mut self, now: DateTime<Local>) -> Vec<String> {
+    let mut due = Vec::new();
+
+    for (config_hash, provider) in &mut self.providers {
+      if provider.next_due > now {
+        continue;
+      }
+
+      let lateness = now - provider.next_due;
+
+      let is_missed = lateness.num_seconds() > MISSED_THRESHOLD_SECS;
+
+      if !is_missed || lateness <= provider.grace {
+        due.push(config_hash.clone());
+      } else {
+        info!(
+          "Skipping missed trigger of provider {}, which was due at {}.",
+          config_hash, provider.next_due
+        );
+      }
+
+      match provider.schedule.next_after(&now) {
+        Some(next_due) => provider.next_due = next_due,
+        // Unreachable in practice, since the schedule triggered before.
+        None => provider.next_due = now + chrono::Duration::days(1),
+      }
+    }
+
+    due
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{FixedOffset, NaiveTime, Utc};
+
+  use super::*;
+
+  /// Central European time in 2024. Clocks go forward from 02:00 to 03:00
+  /// on March 31, and back from 03:00 to 02:00 on October 27.
+  #[derive(Debug, Clone, Copy)]
+  struct Cet2024;
+
+  impl Cet2024 {
+    fn winter() -> FixedOffset {
+      FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn summer() -> FixedOffset {
+      FixedOffset::east_opt(2 * 3600).unwrap()
+    }
+
+    fn is_summer(utc: &NaiveDateTime) -> bool {
+      let start = utc_time(2024, 3, 31, 1, 0);
+      let end = utc_time(2024, 10, 27, 1, 0);
+
+      *utc >= start && *utc < end
+    }
+  }
+
+  impl TimeZone for Cet2024 {
+    type Offset = FixedOffset;
+
+    fn from_offset(_: &FixedOffset) -> Self {
+      Cet2024
+    }
+
+    fn offset_from_local_date(
+      &self,
+      local: &NaiveDate,
+    ) -> LocalResult<FixedOffset> {
+      self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_local_datetime(
+      &self,
+      local: &NaiveDateTime,
+    ) -> LocalResult<FixedOffset> {
+      let is_winter =
+        !Self::is_summer(&(*local - chrono::Duration::hours(1)));
+      let is_summer =
+        Self::is_summer(&(*local - chrono::Duration::hours(2)));
+
+      match (is_summer, is_winter) {
+        (true, true) => {
+          LocalResult::Ambiguous(Self::summer(), Self::winter())
+        }
+        (true, false) => LocalResult::Single(Self::summer()),
+        (false, true) => LocalResult::Single(Self::winter()),
+        (false, false) => LocalResult::None,
+      }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+      self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_utc_datetime(
+      &self,
+      utc: &NaiveDateTime,
+    ) -> FixedOffset {
+      match Self::is_summer(utc) {
+        true => Self::summer(),
+        false => Self::winter(),
+      }
+    }
+  }
+
+  fn utc_time(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+  ) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+      .unwrap()
+      .and_hms_opt(hour, minute, 0)
+      .unwrap()
+  }
+
+  fn cet(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+  ) -> DateTime<Cet2024> {
+    Cet2024
+      .with_ymd_and_hms(year, month, day, hour, minute, 0)
+      .earliest()
+      .unwrap()
+  }
+
+  fn schedule(schedule: &str) -> Schedule {
+    schedule.parse().unwrap()
+  }
+
+  /// Gets the next `count` triggers after the given time.
+  fn triggers<Tz: TimeZone>(
+    schedule: &Schedule,
+    after: DateTime<Tz>,
+    count: usize,
+  ) -> Vec<DateTime<Tz>> {
+    let mut triggers = Vec::new();
+    let mut after = after;
+
+    for _ in 0..count {
+      after = schedule.next_after(&after).unwrap();
+      triggers.push(after.clone());
+    }
+
+    triggers
+  }
+
+  fn to_utc<Tz: TimeZone>(times: Vec<DateTime<Tz>>) -> Vec<DateTime<Utc>> {
+    times.iter().map(|time| time.with_timezone(&Utc)).collect()
+  }
+
+  fn utc(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+  ) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&utc_time(year, month, day, hour, minute))
+  }
+
+  #[test]
+  fn parses_time_lists() {
+    assert_eq!(schedule("daily@07:00"), schedule("0 7 * * *"));
+    assert_eq!(schedule("weekdays@07:00"), schedule("0 7 * * 1-5"));
+    assert_eq!(schedule("weekends@07:00"), schedule("0 7 * * sat,sun"));
+    assert_eq!(schedule("mon,thu@09:30"), schedule("30 9 * * 1,4"));
+    assert_eq!(schedule(" Daily@7:05 "), schedule("5 7 * * *"));
+    assert_eq!(schedule("daily@07:00,13:00").exprs.len(), 2);
+  }
+
+  #[test]
+  fn parses_aliases() {
+    assert_eq!(schedule("@daily"), schedule("0 0 * * *"));
+    assert_eq!(schedule("@midnight"), schedule("0 0 * * *"));
+    assert_eq!(schedule("@hourly"), schedule("0 * * * *"));
+    assert_eq!(schedule("@weekly"), schedule("0 0 * * sun"));
+    assert_eq!(schedule("@yearly"), schedule("0 0 1 jan *"));
+  }
+
+  #[test]
+  fn parses_fields() {
+    let expr = CronExpr::parse("*/15 9-17/4 1,15 * 7").unwrap();
+
+    assert_eq!(
+      bits(expr.minutes, 60).collect::<Vec<_>>(),
+      [0, 15, 30, 45]
+    );
+    assert_eq!(
+      bits(expr.hours as u64, 24).collect::<Vec<_>>(),
+      [9, 13, 17]
+    );
+    assert_eq!(
+      bits(expr.days_of_month as u64, 32).collect::<Vec<_>>(),
+      [1, 15]
+    );
+
+    // Sunday can be given as 7.
+    assert_eq!(expr.days_of_week, 1);
+    assert_eq!(
+      CronExpr::parse("5/20 * * * *").unwrap().minutes,
+      (1 << 5) | (1 << 25) | (1 << 45)
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_schedules() {
+    for invalid in [
+      "* * * *",
+      "* * * * * *",
+      "60 * * * *",
+      "* 24 * * *",
+      "* * 0 * *",
+      "* * * 13 *",
+      "* * * * 8",
+      "*/0 * * * *",
+      "5-1 * * * *",
+      "* * * foo *",
+      "@sometimes",
+      "daily@7",
+      "daily@07:60",
+      "someday@07:00",
+    ] {
+      assert!(
+        invalid.parse::<Schedule>().is_err(),
+        "Expected '{}' to be rejected.",
+        invalid
+      );
+    }
+  }
+
+  #[test]
+  fn next_after_is_strictly_after() {
+    let schedule = schedule("*/15 * * * *");
+
+    assert_eq!(
+      schedule.next_after(&utc(2024, 5, 1, 10, 7)),
+      Some(utc(2024, 5, 1, 10, 15))
+    );
+    assert_eq!(
+      schedule.next_after(&utc(2024, 5, 1, 10, 15)),
+      Some(utc(2024, 5, 1, 10, 30))
+    );
+    assert_eq!(
+      schedule.next_after(&utc(2024, 5, 1, 23, 50)),
+      Some(utc(2024, 5, 2, 0, 0))
+    );
+  }
+
+  #[test]
+  fn picks_earliest_of_multiple_times() {
+    let schedule = schedule("daily@13:00,07:00");
+
+    assert_eq!(
+      to_utc(triggers(&schedule, utc(2024, 5, 1, 8, 0), 3)),
+      [
+        utc(2024, 5, 1, 13, 0),
+        utc(2024, 5, 2, 7, 0),
+        utc(2024, 5, 2, 13, 0),
+      ]
+    );
+  }
+
+  #[test]
+  fn restricted_day_fields_match_either() {
+    // The 13th of each month, and every Friday.
+    let schedule = schedule("0 0 13 * fri");
+
+    // 2024-09-06 and 2024-09-13 are Fridays.
+    assert_eq!(
+      to_utc(triggers(&schedule, utc(2024, 9, 1, 0, 0), 3)),
+      [
+        utc(2024, 9, 6, 0, 0),
+        utc(2024, 9, 13, 0, 0),
+        utc(2024, 9, 20, 0, 0),
+      ]
+    );
+  }
+
+  #[test]
+  fn finds_leap_days() {
+    assert_eq!(
+      schedule("0 0 29 2 *").next_after(&utc(2025, 3, 1, 0, 0)),
+      Some(utc(2028, 2, 29, 0, 0))
+    );
+  }
+
+  #[test]
+  fn impossible_schedule_never_triggers() {
+    assert_eq!(
+      schedule("0 0 31 2 *").next_after(&utc(2024, 1, 1, 0, 0)),
+      None
+    );
+  }
+
+  #[test]
+  fn time_in_gap_fires_at_end_of_gap() {
+    let schedule = schedule("daily@02:30");
+
+    // 02:30 doesn't exist on March 31.
+    assert_eq!(
+      triggers(&schedule, cet(2024, 3, 30, 12, 0), 3),
+      [
+        cet(2024, 3, 31, 3, 0),
+        cet(2024, 4, 1, 2, 30),
+        cet(2024, 4, 2, 2, 30),
+      ]
+    );
+    assert_eq!(
+      to_utc(triggers(&schedule, cet(2024, 3, 30, 12, 0), 2)),
+      [utc(2024, 3, 31, 1, 0), utc(2024, 4, 1, 0, 30)]
+    );
+  }
+
+  #[test]
+  fn times_in_gap_fire_once() {
+    let schedule = schedule("*/15 * * * *");
+
+    // 02:00, 02:15, 02:30 and 02:45 all resolve to 03:00.
+    assert_eq!(
+      triggers(&schedule, cet(2024, 3, 31, 1, 40), 4),
+      [
+        cet(2024, 3, 31, 1, 45),
+        cet(2024, 3, 31, 3, 0),
+        cet(2024, 3, 31, 3, 15),
+        cet(2024, 3, 31, 3, 30),
+      ]
+    );
+  }
+
+  #[test]
+  fn trigger_before_midnight_isnt_skipped_by_gap() {
+    // Same as `time_in_gap_fires_at_end_of_gap`, but searching from just
+    // before the trigger.
+    assert_eq!(
+      schedule("daily@02:30").next_after(&cet(2024, 3, 31, 1, 59)),
+      Some(cet(2024, 3, 31, 3, 0))
+    );
+  }
+
+  #[test]
+  fn repeated_time_fires_on_first_occurrence() {
+    let schedule = schedule("daily@02:30");
+
+    // 02:30 occurs twice on October 27, at 00:30 and 01:30 UTC.
+    assert_eq!(
+      to_utc(triggers(&schedule, cet(2024, 10, 26, 12, 0), 3)),
+      [
+        utc(2024, 10, 27, 0, 30),
+        utc(2024, 10, 28, 1, 30),
+        utc(2024, 10, 29, 1, 30),
+      ]
+    );
+  }
+
+  #[test]
+  fn times_in_overlap_fire_once() {
+    let schedule = schedule("*/30 * * * *");
+
+    // The repeated hour from 02:00 to 03:00 is only fired once.
+    assert_eq!(
+      to_utc(triggers(&schedule, cet(2024, 10, 27, 1, 45), 4)),
+      [
+        utc(2024, 10, 27, 0, 0),
+        utc(2024, 10, 27, 0, 30),
+        utc(2024, 10, 27, 2, 0),
+        utc(2024, 10, 27, 2, 30),
+      ]
+    );
+  }
+
+  #[test]
+  fn day_of_dst_change_has_one_trigger() {
+    let schedule = schedule("@daily");
+
+    assert_eq!(
+      triggers(&schedule, cet(2024, 3, 30, 12, 0), 2),
+      [cet(2024, 3, 31, 0, 0), cet(2024, 4, 1, 0, 0)]
+    );
+    assert_eq!(
+      triggers(&schedule, cet(2024, 10, 26, 12, 0), 2),
+      [cet(2024, 10, 27, 0, 0), cet(2024, 10, 28, 0, 0)]
+    );
+  }
+
+  #[test]
+  fn scheduler_fires_due_and_skips_missed() {
+    let mut scheduler = Scheduler::default();
+    scheduler.add("a", schedule("* * * * *"), 60 * 60 * 1000);
+
+    let next_due = scheduler.providers["a"].next_due;
+
+    assert!(scheduler
+      .take_due(next_due - chrono::Duration::seconds(1))
+      .is_empty());
+    assert_eq!(scheduler.take_due(next_due), ["a"]);
+
+    // Missed, but within the grace window.
+    let now = next_due + chrono::Duration::minutes(30);
+    scheduler.providers.get_mut("a").unwrap().next_due = next_due;
+    assert_eq!(scheduler.take_due(now), ["a"]);
+    assert!(scheduler.providers["a"].next_due > now);
+
+    // Missed, and past the grace window.
+    let now = next_due + chrono::Duration::hours(2);
+    scheduler.providers.get_mut("a").unwrap().next_due = next_due;
+    assert!(scheduler.take_due(now).is_empty());
+    assert!(scheduler.providers["a"].next_due > now);
+  }
+
+  #[test]
+  fn scheduler_sleep_is_capped() {
+    let mut scheduler = Scheduler::default();
+    let now = Local::now();
+
+    assert_eq!(scheduler.sleep_duration(now), SCHEDULER_MAX_SLEEP);
+
+    scheduler.add("a", schedule("@yearly"), 0);
+    assert_eq!(scheduler.sleep_duration(now), SCHEDULER_MAX_SLEEP);
+
+    let next_due = scheduler.providers["a"].next_due;
+    assert_eq!(scheduler.sleep_duration(next_due), Duration::ZERO);
+  }
+}
@@ -0,0 +1,103 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use super::provider_ref::VariablesResult;
+#[cfg(windows)]
+use super::variables::ProviderVariables;
+
+/// Position and size of a window or monitor in physical pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bounds {
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+}
+
+impl Bounds {
+  /// Whether the center of the other bounds lies within these bounds.
+  pub fn contains_center_of(&self, other: &Bounds) -> bool {
+    let center_x = other.x + other.width / 2;
+    let center_y = other.y + other.height / 2;
+
+    center_x >= self.x
+      && center_x < self.x + self.width
+      && center_y >= self.y
+      && center_y < self.y + self.height
+  }
+}
+
+/// Bounds of windows, keyed by window label. Kept up to date from
+/// window events.
+#[derive(Debug, Default)]
+pub struct WindowBoundsMap(Mutex<HashMap<String, Bounds>>);
+
+impl WindowBoundsMap {
+  pub fn set_position(&self, window_label: &str, x: i32, y: i32) {
+    let mut map = self.0.lock().unwrap();
+    let bounds = map.entry(window_label.to_string()).or_default();
+    bounds.x = x;
+    bounds.y = y;
+  }
+
+  pub fn set_size(&self, window_label: &str, width: u32, height: u32) {
+    let mut map = self.0.lock().unwrap();
+    let bounds = map.entry(window_label.to_string()).or_default();
+    bounds.width = width as i32;
+    bounds.height = height as i32;
+  }
+
+  pub fn get(&self, window_label: &str) -> Option<Bounds> {
+    self.0.lock().unwrap().get(window_label).copied()
+  }
+
+  pub fn remove(&self, window_label: &str) {
+    self.0.lock().unwrap().remove(window_label);
+  }
+}
+
+/// Gets the bounds of the focused monitor from the output of a window
+/// manager provider. `None` for other providers.
+#[cfg_attr(
+  not(all(windows, feature = "komorebi")),
+  allow(unused_variables)
+)]
+pub fn focused_monitor_bounds(
+  variables: &VariablesResult,
+) -> Option<Bounds> {
+  #[cfg(all(windows, feature = "komorebi"))]
+  if let VariablesResult::Data(ProviderVariables::Komorebi(variables)) =
+    variables
+  {
+    let monitor = variables
+      .all_monitors
+      .get(variables.focused_monitor_index)?;
+
+    // Komorebi's `right` and `bottom` are the width and height.
+    return Some(Bounds {
+      x: monitor.size.left,
+      y: monitor.size.top,
+      width: monitor.size.right,
+      height: monitor.size.bottom,
+    });
+  }
+
+  None
+}
+
+/// Sets whether the window that the output is emitted to is on the
+/// focused monitor.
+#[cfg_attr(
+  not(all(windows, feature = "komorebi")),
+  allow(unused_variables)
+)]
+pub fn set_self_monitor_focused(
+  variables: &mut VariablesResult,
+  focused: Option<bool>,
+) {
+  #[cfg(all(windows, feature = "komorebi"))]
+  if let VariablesResult::Data(ProviderVariables::Komorebi(variables)) =
+    variables
+  {
+    variables.self_monitor_focused = focused;
+  }
+}
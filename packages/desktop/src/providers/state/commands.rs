@@ -0,0 +1,72 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Context;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use super::StatePathError;
+
+/// Mutation of a state provider's document.
+#[derive(Debug, Clone)]
+pub enum StateCommand {
+  Set { path: String, value: Value },
+  Increment { path: String, delta: f64 },
+  Reset,
+}
+
+/// Command sent to a state provider, along with a channel for the
+/// result.
+#[derive(Debug)]
+pub struct StateRequest {
+  pub command: StateCommand,
+  pub result_tx: oneshot::Sender<Result<(), StatePathError>>,
+}
+
+/// Channels to the running state providers, keyed by config hash.
+///
+/// Commands are handled one at a time by the provider's task, so
+/// concurrent mutations from multiple windows can't interleave.
+#[derive(Debug, Clone, Default)]
+pub struct StateChannels(
+  Arc<Mutex<HashMap<String, mpsc::Sender<StateRequest>>>>,
+);
+
+impl StateChannels {
+  pub async fn register(
+    &self,
+    config_hash: &str,
+    request_tx: mpsc::Sender<StateRequest>,
+  ) {
+    self
+      .0
+      .lock()
+      .await
+      .insert(config_hash.to_string(), request_tx);
+  }
+
+  pub async fn unregister(&self, config_hash: &str) {
+    self.0.lock().await.remove(config_hash);
+  }
+
+  /// Sends the command to the state provider with the given config hash
+  /// and waits for it to be applied.
+  pub async fn send(
+    &self,
+    config_hash: &str,
+    command: StateCommand,
+  ) -> anyhow::Result<()> {
+    let request_tx =
+      self.0.lock().await.get(config_hash).cloned().with_context(
+        || format!("No state provider running for '{}'.", config_hash),
+      )?;
+
+    let (result_tx, result_rx) = oneshot::channel();
+
+    request_tx
+      .send(StateRequest { command, result_tx })
+      .await
+      .context("State provider has stopped.")?;
+
+    Ok(result_rx.await.context("State provider has stopped.")??)
+  }
+}
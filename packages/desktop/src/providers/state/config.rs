@@ -0,0 +1,19 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "state")]
+pub struct StateProviderConfig {
+  /// Name of the file that the document is persisted to. Providers with
+  /// the same name share the persisted document.
+  pub name: String,
+
+  /// Document to start with if none is persisted, and to reset to.
+  #[serde(default = "default_initial")]
+  pub initial: Value,
+}
+
+fn default_initial() -> Value {
+  Value::Object(Default::default())
+}
@@ -0,0 +1,237 @@
+use std::{fmt, fs, path::PathBuf};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Number, Value};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::{StateCommand, StateProviderConfig};
+use crate::providers::retained_size::{json_size, RetainedSize};
+
+/// Error returned when a path in a state command is malformed or can't
+/// be applied to the document.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatePathError {
+  pub kind: &'static str,
+  pub path: String,
+  pub message: String,
+}
+
+impl StatePathError {
+  fn new(path: &str, message: String) -> Self {
+    Self {
+      kind: "invalid_state_path",
+      path: path.to_string(),
+      message,
+    }
+  }
+}
+
+impl fmt::Display for StatePathError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for StatePathError {}
+
+/// JSON document of a state provider, which is persisted to disk after
+/// every change.
+#[derive(Debug)]
+pub struct StateDocument {
+  value: Value,
+  initial: Value,
+  persist_path: Option<PathBuf>,
+}
+
+impl StateDocument {
+  pub fn new(
+    config: &StateProviderConfig,
+    data_dir: Option<PathBuf>,
+  ) -> anyhow::Result<Self> {
+    let is_valid_name = !config.name.is_empty()
+      && config.name.chars().all(|char| {
+        char.is_ascii_alphanumeric() || char == '-' || char == '_'
+      });
+
+    if !is_valid_name {
+      anyhow::bail!(
+        "Invalid state name '{}'. Only letters, digits, `-` and `_` are \
+        allowed.",
+        config.name
+      );
+    }
+
+    let persist_path = data_dir
+      .map(|dir| dir.join("state").join(format!("{}.json", config.name)));
+
+    let value = persist_path
+      .as_ref()
+      .and_then(|path| fs::read_to_string(path).ok())
+      .and_then(|contents| serde_json::from_str(&contents).ok())
+      .unwrap_or_else(|| config.initial.clone());
+
+    Ok(Self {
+      value,
+      initial: config.initial.clone(),
+      persist_path,
+    })
+  }
+
+  pub fn value(&self) -> &Value {
+    &self.value
+  }
+
+  /// Applies the command to the document. The document is left
+  /// unchanged if the command fails.
+  pub fn apply(
+    &mut self,
+    command: StateCommand,
+  ) -> Result<(), StatePathError> {
+    match command {
+      StateCommand::Set { path, value } => {
+        let mut next = self.value.clone();
+        *value_at_mut(&mut next, &path)? = value;
+        self.value = next;
+      }
+      StateCommand::Increment { path, delta } => {
+        let mut next = self.value.clone();
+        let target = value_at_mut(&mut next, &path)?;
+        *target = incremented(target, delta, &path)?;
+        self.value = next;
+      }
+      StateCommand::Reset => self.value = self.initial.clone(),
+    }
+
+    if let Err(err) = self.persist() {
+      warn!("Failed to write state document: {:?}", err);
+    }
+
+    Ok(())
+  }
+
+  fn persist(&self) -> anyhow::Result<()> {
+    let Some(persist_path) = &self.persist_path else {
+      return Ok(());
+    };
+
+    if let Some(parent) = persist_path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    fs::write(persist_path, serde_json::to_string(&self.value)?)?;
+
+    Ok(())
+  }
+}
+
+/// Gets the value at the dot-separated path (e.g. `tasks.0.done`),
+/// creating objects for missing keys along the way. An index equal to
+/// the length of an array appends to it.
+fn value_at_mut<'a>(
+  document: &'a mut Value,
+  path: &str,
+) -> Result<&'a mut Value, StatePathError> {
+  if path.is_empty() || path.split('.').any(|segment| segment.is_empty()) {
+    return Err(StatePathError::new(
+      path,
+      format!("Path '{}' has empty segments.", path),
+    ));
+  }
+
+  let mut value = document;
+
+  for (depth, segment) in path.split('.').enumerate() {
+    if value.is_null() {
+      *value = Value::Object(Default::default());
+    }
+
+    value = match value {
+      Value::Object(map) => map.entry(segment).or_insert(Value::Null),
+      Value::Array(items) => {
+        let index = segment.parse::<usize>().map_err(|_| {
+          StatePathError::new(
+            path,
+            format!(
+              "'{}' in path '{}' is not an array index.",
+              segment, path
+            ),
+          )
+        })?;
+
+        if index == items.len() {
+          items.push(Value::Null);
+        }
+
+        let length = items.len();
+
+        items.get_mut(index).ok_or_else(|| {
+          StatePathError::new(
+            path,
+            format!(
+              "Index {} in path '{}' is out of bounds for length {}.",
+              index, path, length
+            ),
+          )
+        })?
+      }
+      _ => {
+        let parent = path.split('.').take(depth).collect::<Vec<_>>();
+
+        return Err(StatePathError::new(
+          path,
+          format!(
+            "'{}' in path '{}' is not an object or array.",
+            parent.join("."),
+            path
+          ),
+        ));
+      }
+    };
+  }
+
+  Ok(value)
+}
+
+/// Adds the delta to a number. Missing values are treated as 0, and
+/// integers stay integers if the delta is whole.
+fn incremented(
+  value: &Value,
+  delta: f64,
+  path: &str,
+) -> Result<Value, StatePathError> {
+  let current = match value {
+    Value::Null => 0.,
+    Value::Number(number) => number.as_f64().unwrap_or_default(),
+    _ => {
+      return Err(StatePathError::new(
+        path,
+        format!("Value at path '{}' is not a number.", path),
+      ))
+    }
+  };
+
+  let next = current + delta;
+
+  if next.fract() == 0. && next.abs() < i64::MAX as f64 {
+    return Ok(Value::from(next as i64));
+  }
+
+  Number::from_f64(next).map(Value::Number).ok_or_else(|| {
+    StatePathError::new(
+      path,
+      format!("Incrementing path '{}' gives a non-finite number.", path),
+    )
+  })
+}
+
+/// The document is the provider's data, so it isn't evicted.
+#[async_trait]
+impl RetainedSize for Mutex<StateDocument> {
+  async fn retained_size(&self) -> usize {
+    let document = self.lock().await;
+    json_size(&document.value) + json_size(&document.initial)
+  }
+}
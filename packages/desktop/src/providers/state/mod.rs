@@ -0,0 +1,11 @@
+mod commands;
+mod config;
+mod document;
+mod provider;
+mod variables;
+
+pub use commands::*;
+pub use config::*;
+pub use document::*;
+pub use provider::*;
+pub use variables::*;
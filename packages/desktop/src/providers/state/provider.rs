@@ -0,0 +1,133 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tokio::{
+  sync::{mpsc, Mutex},
+  task::{self, AbortHandle},
+};
+
+use super::{
+  StateChannels, StateDocument, StateProviderConfig, StateRequest,
+  StateVariables,
+};
+use crate::providers::{
+  provider::Provider,
+  provider_ref::{ProviderOutput, VariablesResult},
+  retained_size::RetainedState,
+  variables::ProviderVariables,
+};
+
+/// Max number of pending commands per provider.
+const REQUEST_CHANNEL_SIZE: usize = 32;
+
+pub struct StateProvider {
+  pub config: Arc<StateProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  document: Arc<Mutex<StateDocument>>,
+  channels: StateChannels,
+  config_hash: Option<String>,
+}
+
+impl StateProvider {
+  pub fn new(
+    config: StateProviderConfig,
+    data_dir: Option<PathBuf>,
+    channels: StateChannels,
+  ) -> anyhow::Result<StateProvider> {
+    let document = StateDocument::new(&config, data_dir)?;
+
+    Ok(StateProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      document: Arc::new(Mutex::new(document)),
+      channels,
+      config_hash: None,
+    })
+  }
+
+  fn to_output(
+    config_hash: &str,
+    document: &StateDocument,
+  ) -> ProviderOutput {
+    ProviderOutput {
+      config_hash: config_hash.to_string(),
+      variables: VariablesResult::Data(ProviderVariables::State(
+        StateVariables {
+          value: document.value().clone(),
+        },
+      )),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+}
+
+#[async_trait]
+impl Provider for StateProvider {
+  fn min_refresh_interval(&self) -> Option<Duration> {
+    // Output is only emitted on changes, so it's always up to date.
+    None
+  }
+
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(vec![self.document.clone()])
+  }
+
+  async fn on_start(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: mpsc::Sender<ProviderOutput>,
+  ) {
+    let (request_tx, mut request_rx) =
+      mpsc::channel::<StateRequest>(REQUEST_CHANNEL_SIZE);
+
+    self.channels.register(config_hash, request_tx).await;
+    self.config_hash = Some(config_hash.to_string());
+
+    let config_hash = config_hash.to_string();
+    let document = self.document.clone();
+
+    let task_handle = task::spawn(async move {
+      let output = Self::to_output(&config_hash, &document.lock().await);
+      _ = emit_output_tx.send(output).await;
+
+      // Commands are applied in the order they're received.
+      while let Some(request) = request_rx.recv().await {
+        let mut document = document.lock().await;
+        let res = document.apply(request.command);
+
+        if res.is_ok() {
+          let output = Self::to_output(&config_hash, &document);
+          _ = emit_output_tx.send(output).await;
+        }
+
+        _ = request.result_tx.send(res);
+      }
+    });
+
+    self.abort_handle = Some(task_handle.abort_handle());
+    _ = task_handle.await;
+  }
+
+  async fn on_refresh(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: mpsc::Sender<ProviderOutput>,
+  ) {
+    let output = Self::to_output(config_hash, &self.document.lock().await);
+    _ = emit_output_tx.send(output).await;
+  }
+
+  async fn on_stop(&mut self) {
+    if let Some(config_hash) = &self.config_hash {
+      self.channels.unregister(config_hash).await;
+    }
+
+    if let Some(handle) = &self.abort_handle {
+      handle.abort();
+    }
+  }
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StateVariables {
+  /// Current JSON document.
+  pub value: Value,
+}
@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use sysinfo::System;
+
+#[cfg(not(target_os = "windows"))]
+use super::{library::FileCache, vdf};
+use crate::providers::process_snapshot::ProcessSnapshot;
+
+/// State of the Steam client.
+#[derive(Debug, Default)]
+pub struct ClientStatus {
+  pub running: bool,
+
+  /// App ID of the game that's running, as reported by Steam.
+  pub running_app_id: Option<u32>,
+}
+
+/// Reads the client state from the registry, where Steam stores the
+/// PID of the client and the ID of the running game.
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+pub struct ClientReader;
+
+#[cfg(target_os = "windows")]
+impl ClientReader {
+  pub fn read(
+    &mut self,
+    _: &Path,
+    sysinfo: &mut System,
+    _: &ProcessSnapshot,
+  ) -> ClientStatus {
+    use sysinfo::Pid;
+    use windows::{core::w, Win32::System::Registry::HKEY_CURRENT_USER};
+
+    use crate::util::registry::read_registry_dword;
+
+    let pid = read_registry_dword(
+      HKEY_CURRENT_USER,
+      w!("Software\\Valve\\Steam\\ActiveProcess"),
+      w!("pid"),
+    );
+
+    // The PID is left behind if Steam doesn't exit cleanly, so it's
+    // checked against the running processes.
+    let running = pid
+      .filter(|pid| *pid != 0)
+      .is_some_and(|pid| sysinfo.refresh_process(Pid::from_u32(pid)));
+
+    let running_app_id = read_registry_dword(
+      HKEY_CURRENT_USER,
+      w!("Software\\Valve\\Steam"),
+      w!("RunningAppID"),
+    );
+
+    ClientStatus {
+      running,
+      running_app_id: running_app_id.filter(|id| *id != 0 && running),
+    }
+  }
+}
+
+/// Name of the Steam client's process.
+#[cfg(target_os = "macos")]
+const STEAM_PROCESS_NAME: &str = "steam_osx";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const STEAM_PROCESS_NAME: &str = "steam";
+
+/// Reads the client state from `registry.vdf`, which Steam uses in
+/// place of the Windows registry.
+#[cfg(not(target_os = "windows"))]
+#[derive(Default)]
+pub struct ClientReader {
+  registry: FileCache<Option<u32>>,
+}
+
+#[cfg(not(target_os = "windows"))]
+impl ClientReader {
+  pub fn read(
+    &mut self,
+    steam_dir: &Path,
+    sysinfo: &mut System,
+    process_snapshot: &ProcessSnapshot,
+  ) -> ClientStatus {
+    process_snapshot.refresh_if_stale(sysinfo);
+
+    let running = sysinfo
+      .processes()
+      .values()
+      .any(|process| process.name() == STEAM_PROCESS_NAME);
+
+    if !running {
+      return ClientStatus::default();
+    }
+
+    // Native Linux installs keep the file in `~/.steam`, one level up
+    // from the `~/.steam/steam` symlink.
+    let registry_paths = [
+      steam_dir.join("registry.vdf"),
+      steam_dir.join("../registry.vdf"),
+    ];
+
+    let running_app_id = registry_paths.iter().find_map(|path| {
+      self
+        .registry
+        .get(path, parse_running_app_id)
+        .map(|(app_id, _)| *app_id)
+    });
+
+    ClientStatus {
+      running,
+      running_app_id: running_app_id.flatten(),
+    }
+  }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn parse_running_app_id(contents: &str) -> anyhow::Result<Option<u32>> {
+  let root = vdf::parse(contents)?;
+
+  let app_id = root
+    .get_path(&["Registry", "HKCU", "Software", "Valve", "Steam"])
+    .and_then(|steam| steam.get_u64("RunningAppID"))
+    .filter(|app_id| *app_id != 0)
+    .map(|app_id| app_id as u32);
+
+  Ok(app_id)
+}
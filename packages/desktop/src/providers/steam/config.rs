@@ -0,0 +1,16 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::impl_interval_config;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "steam")]
+pub struct SteamProviderConfig {
+  pub refresh_interval: u64,
+
+  /// Path to the Steam installation. Detected automatically if not
+  /// set.
+  pub steam_path: Option<String>,
+}
+
+impl_interval_config!(SteamProviderConfig);
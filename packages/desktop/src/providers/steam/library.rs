@@ -0,0 +1,378 @@
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use anyhow::Context;
+
+use super::vdf::{self, VdfValue};
+
+/// Bits of the `StateFlags` of an app manifest.
+const STATE_UPDATE_RUNNING: u64 = 0x100;
+const STATE_UPDATE_PAUSED: u64 = 0x200;
+const STATE_UPDATE_STARTED: u64 = 0x400;
+
+/// Finds the Steam installation, unless a path is configured.
+pub fn steam_dir(configured: Option<&str>) -> Option<PathBuf> {
+  if let Some(path) = configured {
+    return Some(PathBuf::from(path)).filter(|path| path.is_dir());
+  }
+
+  default_steam_dirs()
+    .into_iter()
+    .find(|path| path.join("steamapps").is_dir())
+}
+
+#[cfg(target_os = "windows")]
+fn default_steam_dirs() -> Vec<PathBuf> {
+  use windows::{core::w, Win32::System::Registry::HKEY_CURRENT_USER};
+
+  use crate::util::registry::read_registry_string;
+
+  let registry_path = read_registry_string(
+    HKEY_CURRENT_USER,
+    w!("Software\\Valve\\Steam"),
+    w!("SteamPath"),
+  );
+
+  registry_path
+    .map(PathBuf::from)
+    .into_iter()
+    .chain([PathBuf::from("C:\\Program Files (x86)\\Steam")])
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn default_steam_dirs() -> Vec<PathBuf> {
+  dirs::home_dir()
+    .map(|home| vec![home.join("Library/Application Support/Steam")])
+    .unwrap_or_default()
+}
+
+/// Native installs symlink `~/.steam/steam` to the actual install, and
+/// Flatpak installs live in the app's data directory.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn default_steam_dirs() -> Vec<PathBuf> {
+  dirs::home_dir()
+    .map(|home| {
+      vec![
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+      ]
+    })
+    .unwrap_or_default()
+}
+
+/// App manifest (`appmanifest_<id>.acf`) of an installed app.
+#[derive(Debug, Clone)]
+pub struct AppManifest {
+  pub app_id: u32,
+  pub name: String,
+  pub state_flags: u64,
+  pub bytes_downloaded: u64,
+  pub bytes_to_download: u64,
+}
+
+impl AppManifest {
+  fn parse(contents: &str) -> anyhow::Result<Self> {
+    let root = vdf::parse(contents)?;
+    let app_state = root.get("AppState").context("Missing AppState.")?;
+
+    Ok(Self {
+      app_id: app_state.get_u64("appid").context("Missing appid.")? as u32,
+      name: app_state
+        .get("name")
+        .and_then(VdfValue::as_str)
+        .unwrap_or_default()
+        .to_string(),
+      state_flags: app_state.get_u64("StateFlags").unwrap_or_default(),
+      bytes_downloaded: app_state
+        .get_u64("BytesDownloaded")
+        .unwrap_or_default(),
+      bytes_to_download: app_state
+        .get_u64("BytesToDownload")
+        .unwrap_or_default(),
+    })
+  }
+
+  /// Whether an update or install is in progress or paused.
+  pub fn is_updating(&self) -> bool {
+    self.state_flags & (STATE_UPDATE_RUNNING | STATE_UPDATE_STARTED) != 0
+      && self.bytes_to_download > 0
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.state_flags & STATE_UPDATE_PAUSED != 0
+  }
+}
+
+/// Parsed file along with the metadata it was parsed at.
+struct CachedFile<T> {
+  modified: Option<SystemTime>,
+  len: u64,
+  value: T,
+}
+
+/// Parsed files keyed by path. Files are only re-parsed once their
+/// modified time or size changes, so polling mostly costs a `stat` per
+/// file.
+pub struct FileCache<T>(HashMap<PathBuf, CachedFile<T>>);
+
+impl<T> Default for FileCache<T> {
+  fn default() -> Self {
+    Self(HashMap::new())
+  }
+}
+
+impl<T> FileCache<T> {
+  /// Gets the parsed file along with its modified time. Returns `None`
+  /// if the file can't be read or parsed.
+  pub fn get(
+    &mut self,
+    path: &Path,
+    parse: impl FnOnce(&str) -> anyhow::Result<T>,
+  ) -> Option<(&T, Option<SystemTime>)> {
+    let Ok(metadata) = fs::metadata(path) else {
+      self.0.remove(path);
+      return None;
+    };
+
+    let modified = metadata.modified().ok();
+
+    let is_fresh = self.0.get(path).is_some_and(|cached| {
+      cached.modified == modified && cached.len == metadata.len()
+    });
+
+    if !is_fresh {
+      // Steam rewrites files in place, so a read can see a partial
+      // write. It's retried on the next poll.
+      let value = fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|contents| parse(&contents));
+
+      match value {
+        Ok(value) => {
+          self.0.insert(
+            path.to_path_buf(),
+            CachedFile {
+              modified,
+              len: metadata.len(),
+              value,
+            },
+          );
+        }
+        Err(_) => {
+          self.0.remove(path);
+          return None;
+        }
+      }
+    }
+
+    self
+      .0
+      .get(path)
+      .map(|cached| (&cached.value, cached.modified))
+  }
+
+  /// Drops cached files that aren't in the given paths (e.g. manifests
+  /// of uninstalled apps).
+  pub fn retain(&mut self, paths: &[PathBuf]) {
+    self.0.retain(|path, _| paths.contains(path));
+  }
+}
+
+/// Reads the installed apps of all Steam libraries.
+#[derive(Default)]
+pub struct SteamLibraries {
+  library_folders: FileCache<Vec<PathBuf>>,
+  manifests: FileCache<AppManifest>,
+}
+
+impl SteamLibraries {
+  /// Gets the manifests of the installed apps, along with when each
+  /// was last modified.
+  pub fn manifests(
+    &mut self,
+    steam_dir: &Path,
+  ) -> Vec<(AppManifest, Option<SystemTime>)> {
+    let mut libraries = vec![steam_dir.to_path_buf()];
+
+    if let Some((folders, _)) = self.library_folders.get(
+      &steam_dir.join("steamapps").join("libraryfolders.vdf"),
+      parse_library_folders,
+    ) {
+      for folder in folders {
+        if !libraries.contains(folder) {
+          libraries.push(folder.clone());
+        }
+      }
+    }
+
+    let manifest_paths = libraries
+      .iter()
+      .filter_map(|library| fs::read_dir(library.join("steamapps")).ok())
+      .flatten()
+      .filter_map(|entry| entry.ok())
+      .map(|entry| entry.path())
+      .filter(|path| {
+        path.file_name().and_then(|name| name.to_str()).is_some_and(
+          |name| {
+            name.starts_with("appmanifest_") && name.ends_with(".acf")
+          },
+        )
+      })
+      .collect::<Vec<_>>();
+
+    self.manifests.retain(&manifest_paths);
+
+    manifest_paths
+      .iter()
+      .filter_map(|path| {
+        self
+          .manifests
+          .get(path, AppManifest::parse)
+          .map(|(manifest, modified)| (manifest.clone(), modified))
+      })
+      .collect()
+  }
+}
+
+/// Parses the library paths from `libraryfolders.vdf`. Older versions
+/// of Steam store the paths directly as values, and newer ones under a
+/// `path` key.
+fn parse_library_folders(contents: &str) -> anyhow::Result<Vec<PathBuf>> {
+  let root = vdf::parse(contents)?;
+
+  let folders = root
+    .get("libraryfolders")
+    .context("Missing libraryfolders.")?
+    .entries()
+    .iter()
+    .filter(|(key, _)| key.parse::<u32>().is_ok())
+    .filter_map(|(_, value)| match value {
+      VdfValue::String(path) => Some(PathBuf::from(path)),
+      VdfValue::Object(_) => value
+        .get("path")
+        .and_then(VdfValue::as_str)
+        .map(PathBuf::from),
+    })
+    .collect();
+
+  Ok(folders)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MANIFEST: &str = r#"
+"AppState"
+{
+	"appid"		"570"
+	"universe"		"1"
+	"name"		"Dota 2"
+	"StateFlags"		"1542"
+	"installdir"		"dota 2 beta"
+	"SizeOnDisk"		"37052938153"
+	"BytesToDownload"		"524288000"
+	"BytesDownloaded"		"131072000"
+	"InstalledDepots"
+	{
+		"373301"
+		{
+			"manifest"		"6453225398410451457"
+			"size"		"2129427052"
+		}
+	}
+	"UserConfig"
+	{
+		"language"		"english"
+	}
+}
+"#;
+
+  #[test]
+  fn parses_app_manifest() {
+    let manifest = AppManifest::parse(MANIFEST).unwrap();
+
+    assert_eq!(manifest.app_id, 570);
+    assert_eq!(manifest.name, "Dota 2");
+    assert_eq!(manifest.state_flags, 1542);
+    assert_eq!(manifest.bytes_downloaded, 131_072_000);
+    assert_eq!(manifest.bytes_to_download, 524_288_000);
+    assert!(manifest.is_updating());
+    assert!(manifest.is_paused());
+  }
+
+  #[test]
+  fn installed_app_isnt_updating() {
+    let manifest = AppManifest::parse(
+      r#""AppState" { "appid" "10" "name" "CS" "StateFlags" "4" }"#,
+    )
+    .unwrap();
+
+    assert_eq!(manifest.bytes_to_download, 0);
+    assert!(!manifest.is_updating());
+    assert!(!manifest.is_paused());
+  }
+
+  #[test]
+  fn manifest_without_app_id_fails() {
+    assert!(AppManifest::parse(r#""AppState" { "name" "x" }"#).is_err());
+    assert!(AppManifest::parse(r#""Other" { "appid" "1" }"#).is_err());
+  }
+
+  #[test]
+  fn parses_library_folders() {
+    let folders = parse_library_folders(
+      r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"contentid"		"4381093048370958823"
+		"apps"
+		{
+			"228980"		"412619432"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+	}
+}
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+      folders,
+      [
+        PathBuf::from("C:\\Program Files (x86)\\Steam"),
+        PathBuf::from("D:\\SteamLibrary"),
+      ]
+    );
+  }
+
+  #[test]
+  fn parses_legacy_library_folders() {
+    let folders = parse_library_folders(
+      r#"
+"LibraryFolders"
+{
+	"TimeNextStatsReport"		"1600000000"
+	"ContentStatsID"		"-123"
+	"1"		"D:\\SteamLibrary"
+}
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(folders, [PathBuf::from("D:\\SteamLibrary")]);
+  }
+}
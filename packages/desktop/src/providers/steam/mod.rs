@@ -0,0 +1,10 @@
+mod client;
+mod config;
+mod library;
+mod provider;
+mod variables;
+mod vdf;
+
+pub use config::*;
+pub use provider::*;
+pub use variables::*;
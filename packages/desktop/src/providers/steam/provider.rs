@@ -0,0 +1,210 @@
+use std::{
+  collections::HashMap,
+  sync::Arc,
+  time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use sysinfo::System;
+use tokio::{sync::Mutex, task::AbortHandle};
+
+use super::{
+  client::ClientReader,
+  library::{steam_dir, AppManifest, SteamLibraries},
+  SteamDownload, SteamGame, SteamProviderConfig, SteamVariables,
+};
+use crate::providers::{
+  process_snapshot::ProcessSnapshot, provider::IntervalProvider,
+  sample_clock::SampleClock, variables::ProviderVariables,
+};
+
+pub struct SteamProvider {
+  pub config: Arc<SteamProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  state: Arc<SteamState>,
+}
+
+pub struct SteamState {
+  sysinfo: Arc<Mutex<System>>,
+  process_snapshot: Arc<ProcessSnapshot>,
+  readers: Mutex<SteamReaders>,
+}
+
+#[derive(Default)]
+struct SteamReaders {
+  client: ClientReader,
+  libraries: SteamLibraries,
+  download_rates: HashMap<u32, DownloadRate>,
+
+  /// Time between polls. Manifests' modified times are on the wall
+  /// clock, so rates are dropped when it's stepped.
+  clock: SampleClock,
+}
+
+/// Progress of a download when its manifest was last written, for
+/// calculating the download rate.
+struct DownloadRate {
+  bytes_downloaded: u64,
+  modified: SystemTime,
+  bytes_per_sec: Option<f64>,
+}
+
+impl SteamProvider {
+  pub fn new(
+    config: SteamProviderConfig,
+    sysinfo: Arc<Mutex<System>>,
+    process_snapshot: Arc<ProcessSnapshot>,
+  ) -> SteamProvider {
+    SteamProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      state: Arc::new(SteamState {
+        sysinfo,
+        process_snapshot,
+        readers: Mutex::new(SteamReaders::default()),
+      }),
+    }
+  }
+
+  /// Converts the manifest of an app that's updating into a download.
+  ///
+  /// Steam only writes the progress to the manifest every so often, so
+  /// the rate is calculated from the manifest's modified times rather
+  /// than from when it was polled.
+  fn to_download(
+    manifest: &AppManifest,
+    modified: Option<SystemTime>,
+    download_rates: &mut HashMap<u32, DownloadRate>,
+  ) -> SteamDownload {
+    let prev = download_rates.remove(&manifest.app_id);
+
+    let bytes_per_sec = match (prev, modified) {
+      _ if manifest.is_paused() => None,
+      (Some(prev), Some(modified)) if modified > prev.modified => {
+        let elapsed_secs = modified
+          .duration_since(prev.modified)
+          .unwrap_or_default()
+          .as_secs_f64();
+
+        manifest
+          .bytes_downloaded
+          .checked_sub(prev.bytes_downloaded)
+          .map(|delta| delta as f64 / elapsed_secs)
+      }
+      // The manifest hasn't been written since the last poll.
+      (Some(prev), _) => prev.bytes_per_sec,
+      (None, _) => None,
+    };
+
+    if let Some(modified) = modified {
+      download_rates.insert(
+        manifest.app_id,
+        DownloadRate {
+          bytes_downloaded: manifest.bytes_downloaded,
+          modified,
+          bytes_per_sec,
+        },
+      );
+    }
+
+    SteamDownload {
+      app_id: manifest.app_id,
+      name: manifest.name.clone(),
+      bytes_downloaded: manifest.bytes_downloaded,
+      bytes_to_download: manifest.bytes_to_download,
+      progress: (manifest.bytes_downloaded as f64
+        / manifest.bytes_to_download as f64
+        * 100.)
+        .min(100.) as f32,
+      bytes_per_sec,
+      is_paused: manifest.is_paused(),
+    }
+  }
+}
+
+#[async_trait]
+impl IntervalProvider for SteamProvider {
+  type Config = SteamProviderConfig;
+  type State = SteamState;
+
+  fn config(&self) -> Arc<SteamProviderConfig> {
+    self.config.clone()
+  }
+
+  fn state(&self) -> Arc<SteamState> {
+    self.state.clone()
+  }
+
+  fn abort_handle(&self) -> &Option<AbortHandle> {
+    &self.abort_handle
+  }
+
+  fn set_abort_handle(&mut self, abort_handle: AbortHandle) {
+    self.abort_handle = Some(abort_handle)
+  }
+
+  async fn get_refreshed_variables(
+    config: &SteamProviderConfig,
+    state: &SteamState,
+  ) -> anyhow::Result<ProviderVariables> {
+    let Some(steam_dir) = steam_dir(config.steam_path.as_deref()) else {
+      return Ok(ProviderVariables::Steam(SteamVariables {
+        installed: false,
+        running: false,
+        running_game: None,
+        downloads: Vec::new(),
+      }));
+    };
+
+    let mut readers = state.readers.lock().await;
+    let readers = &mut *readers;
+
+    let client = readers.client.read(
+      &steam_dir,
+      &mut *state.sysinfo.lock().await,
+      &state.process_snapshot,
+    );
+
+    let manifests = readers.libraries.manifests(&steam_dir);
+
+    // Modified times from before a clock discontinuity can't be compared
+    // with those after it, so rates start over.
+    let elapsed = readers
+      .clock
+      .tick(Duration::from_millis(config.refresh_interval));
+
+    if elapsed.is_none() {
+      readers.download_rates.clear();
+    }
+
+    let running_game = client.running_app_id.map(|app_id| SteamGame {
+      app_id,
+      name: manifests
+        .iter()
+        .find(|(manifest, _)| manifest.app_id == app_id)
+        .map(|(manifest, _)| manifest.name.clone()),
+    });
+
+    let mut downloads = manifests
+      .iter()
+      .filter(|(manifest, _)| manifest.is_updating())
+      .map(|(manifest, modified)| {
+        Self::to_download(manifest, *modified, &mut readers.download_rates)
+      })
+      .collect::<Vec<_>>();
+
+    // Rates of finished downloads are dropped.
+    readers.download_rates.retain(|app_id, _| {
+      downloads.iter().any(|download| download.app_id == *app_id)
+    });
+
+    downloads.sort_by_key(|download| download.is_paused);
+
+    Ok(ProviderVariables::Steam(SteamVariables {
+      installed: true,
+      running: client.running,
+      running_game,
+      downloads,
+    }))
+  }
+}
@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamVariables {
+  /// Whether a Steam installation was found.
+  pub installed: bool,
+
+  /// Whether the Steam client is running.
+  pub running: bool,
+
+  /// Game that is currently running. `None` if no game is running or
+  /// Steam isn't running.
+  pub running_game: Option<SteamGame>,
+
+  /// Downloads that are in progress or paused, with active downloads
+  /// first.
+  pub downloads: Vec<SteamDownload>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamGame {
+  pub app_id: u32,
+
+  /// Name of the game. `None` if it isn't installed in a Steam library
+  /// (e.g. a non-Steam shortcut).
+  pub name: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamDownload {
+  pub app_id: u32,
+  pub name: String,
+  pub bytes_downloaded: u64,
+  pub bytes_to_download: u64,
+
+  /// Progress from 0 to 100.
+  pub progress: f32,
+
+  /// Download rate in bytes per second. `None` until the progress has
+  /// been updated at least twice, or if the download is paused.
+  pub bytes_per_sec: Option<f64>,
+
+  pub is_paused: bool,
+}
@@ -0,0 +1,340 @@
+use std::{iter::Peekable, str::Chars};
+
+use anyhow::bail;
+
+/// Value in a text VDF (KeyValues) file, as used by Steam for
+/// `libraryfolders.vdf`, app manifests (`.acf`) and `registry.vdf`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VdfValue {
+  String(String),
+  Object(Vec<(String, VdfValue)>),
+}
+
+impl VdfValue {
+  /// Gets the value of a key in an object. Keys are matched
+  /// case-insensitively, since Steam isn't consistent about casing.
+  pub fn get(&self, key: &str) -> Option<&VdfValue> {
+    match self {
+      VdfValue::Object(entries) => entries
+        .iter()
+        .find(|(entry_key, _)| entry_key.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value),
+      VdfValue::String(_) => None,
+    }
+  }
+
+  /// Gets the value at a path of nested keys.
+  ///
+  /// Only used for `registry.vdf`, which doesn't exist on Windows.
+  #[cfg_attr(target_os = "windows", allow(dead_code))]
+  pub fn get_path(&self, path: &[&str]) -> Option<&VdfValue> {
+    path.iter().try_fold(self, |value, key| value.get(key))
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      VdfValue::String(value) => Some(value),
+      VdfValue::Object(_) => None,
+    }
+  }
+
+  /// Gets the string value of a key parsed as a number.
+  pub fn get_u64(&self, key: &str) -> Option<u64> {
+    self.get(key)?.as_str()?.trim().parse().ok()
+  }
+
+  pub fn entries(&self) -> &[(String, VdfValue)] {
+    match self {
+      VdfValue::Object(entries) => entries,
+      VdfValue::String(_) => &[],
+    }
+  }
+}
+
+/// Parses a text VDF document into its root object.
+///
+/// Supports quoted and unquoted tokens, escape sequences in quoted
+/// tokens, `//` comments and conditionals (e.g. `[$WIN32]`), which are
+/// ignored.
+pub fn parse(input: &str) -> anyhow::Result<VdfValue> {
+  let mut tokenizer = Tokenizer {
+    chars: input.trim_start_matches('\u{FEFF}').chars().peekable(),
+  };
+
+  let entries = parse_entries(&mut tokenizer, false)?;
+
+  Ok(VdfValue::Object(entries))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+  String(String),
+  Open,
+  Close,
+}
+
+struct Tokenizer<'a> {
+  chars: Peekable<Chars<'a>>,
+}
+
+impl Tokenizer<'_> {
+  fn next_token(&mut self) -> anyhow::Result<Option<Token>> {
+    loop {
+      self.skip_whitespace_and_comments();
+
+      match self.chars.peek() {
+        None => return Ok(None),
+        Some('{') => {
+          self.chars.next();
+          return Ok(Some(Token::Open));
+        }
+        Some('}') => {
+          self.chars.next();
+          return Ok(Some(Token::Close));
+        }
+        // Conditionals are evaluated by Steam per platform, but the
+        // values they guard are the same for the purposes here.
+        Some('[') => {
+          while let Some(char) = self.chars.next() {
+            if char == ']' {
+              break;
+            }
+          }
+        }
+        Some('"') => {
+          self.chars.next();
+          return self.quoted().map(|token| Some(Token::String(token)));
+        }
+        Some(_) => return Ok(Some(Token::String(self.unquoted()))),
+      }
+    }
+  }
+
+  fn skip_whitespace_and_comments(&mut self) {
+    while let Some(&char) = self.chars.peek() {
+      if char.is_whitespace() {
+        self.chars.next();
+        continue;
+      }
+
+      // Comments start with `//` and run until the end of the line.
+      let mut lookahead = self.chars.clone();
+      lookahead.next();
+
+      if char != '/' || lookahead.peek() != Some(&'/') {
+        break;
+      }
+
+      while let Some(char) = self.chars.next() {
+        if char == '\n' {
+          break;
+        }
+      }
+    }
+  }
+
+  fn quoted(&mut self) -> anyhow::Result<String> {
+    let mut token = String::new();
+
+    while let Some(char) = self.chars.next() {
+      match char {
+        '"' => return Ok(token),
+        '\\' => match self.chars.next() {
+          Some('n') => token.push('\n'),
+          Some('t') => token.push('\t'),
+          Some(escaped) => token.push(escaped),
+          None => break,
+        },
+        _ => token.push(char),
+      }
+    }
+
+    bail!("Unterminated string in VDF.")
+  }
+
+  fn unquoted(&mut self) -> String {
+    let mut token = String::new();
+
+    while let Some(&char) = self.chars.peek() {
+      if char.is_whitespace() || matches!(char, '{' | '}' | '"') {
+        break;
+      }
+
+      token.push(char);
+      self.chars.next();
+    }
+
+    token
+  }
+}
+
+/// Parses key-value pairs until the closing brace of the object (or the
+/// end of input for the root object).
+fn parse_entries(
+  tokenizer: &mut Tokenizer,
+  is_nested: bool,
+) -> anyhow::Result<Vec<(String, VdfValue)>> {
+  let mut entries = Vec::new();
+
+  loop {
+    let key = match tokenizer.next_token()? {
+      Some(Token::String(key)) => key,
+      Some(Token::Close) if is_nested => return Ok(entries),
+      None if !is_nested => return Ok(entries),
+      None => bail!("Unexpected end of VDF."),
+      Some(token) => bail!("Unexpected {:?} in VDF.", token),
+    };
+
+    let value = match tokenizer.next_token()? {
+      Some(Token::String(value)) => VdfValue::String(value),
+      Some(Token::Open) => {
+        VdfValue::Object(parse_entries(tokenizer, true)?)
+      }
+      Some(Token::Close) | None => {
+        bail!("Missing value for key '{}' in VDF.", key)
+      }
+    };
+
+    entries.push((key, value));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn string(value: &str) -> VdfValue {
+    VdfValue::String(value.into())
+  }
+
+  #[test]
+  fn parses_nested_objects() {
+    let root = parse(
+      r#"
+      "AppState"
+      {
+        "appid"   "570"
+        "UserConfig"
+        {
+          "language"  "english"
+        }
+      }
+      "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+      root.get_path(&["AppState", "appid"]),
+      Some(&string("570"))
+    );
+    assert_eq!(
+      root.get_path(&["AppState", "UserConfig", "language"]),
+      Some(&string("english"))
+    );
+  }
+
+  #[test]
+  fn keys_are_case_insensitive() {
+    let root = parse(r#""AppState" { "StateFlags" "4" }"#).unwrap();
+
+    assert_eq!(
+      root
+        .get("appstate")
+        .and_then(|state| state.get_u64("stateflags")),
+      Some(4)
+    );
+  }
+
+  #[test]
+  fn parses_unquoted_tokens() {
+    let root = parse("Root { key value other{nested 1} }").unwrap();
+
+    assert_eq!(root.get_path(&["Root", "key"]), Some(&string("value")));
+    assert_eq!(
+      root.get_path(&["Root", "other", "nested"]),
+      Some(&string("1"))
+    );
+  }
+
+  #[test]
+  fn parses_escape_sequences() {
+    let root =
+      parse(r#""path" "C:\\Program Files\\Steam\t\"quoted\"\n""#).unwrap();
+
+    assert_eq!(
+      root.get("path").and_then(VdfValue::as_str),
+      Some("C:\\Program Files\\Steam\t\"quoted\"\n")
+    );
+  }
+
+  #[test]
+  fn skips_comments_and_conditionals() {
+    let root = parse(
+      r#"
+      // Comment before the root.
+      "Root"
+      {
+        "a" "1" [$WIN32]
+        // "b" "2"
+        "c" "3" // Trailing comment.
+        "url" "http://example.com"
+      }
+      "#,
+    )
+    .unwrap();
+
+    let keys = root
+      .get("Root")
+      .unwrap()
+      .entries()
+      .iter()
+      .map(|(key, _)| key.as_str())
+      .collect::<Vec<_>>();
+
+    assert_eq!(keys, ["a", "c", "url"]);
+    assert_eq!(
+      root.get_path(&["Root", "url"]),
+      Some(&string("http://example.com"))
+    );
+  }
+
+  #[test]
+  fn keeps_duplicate_keys_in_order() {
+    let root = parse(r#""k" "1" "k" "2""#).unwrap();
+
+    assert_eq!(root.entries().len(), 2);
+    assert_eq!(root.get("k"), Some(&string("1")));
+  }
+
+  #[test]
+  fn strips_bom() {
+    let root = parse("\u{FEFF}\"a\" \"1\"").unwrap();
+
+    assert_eq!(root.get("a"), Some(&string("1")));
+  }
+
+  #[test]
+  fn empty_input_is_empty_object() {
+    assert_eq!(parse("").unwrap(), VdfValue::Object(Vec::new()));
+    assert_eq!(parse("  // Only a comment.").unwrap().entries().len(), 0);
+  }
+
+  #[test]
+  fn rejects_malformed_input() {
+    assert!(parse(r#""a" "unterminated"#).is_err());
+    assert!(parse(r#""a" { "b" "1""#).is_err());
+    assert!(parse(r#""a""#).is_err());
+    assert!(parse(r#""a" { "b" }"#).is_err());
+    assert!(parse("}").is_err());
+    assert!(parse("{").is_err());
+  }
+
+  #[test]
+  fn value_accessors_on_strings() {
+    let value = string(" 42 ");
+
+    assert_eq!(value.get("key"), None);
+    assert!(value.entries().is_empty());
+    assert_eq!(value.as_str(), Some(" 42 "));
+  }
+}
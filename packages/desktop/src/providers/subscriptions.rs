@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::actions::ProviderAction;
+
+/// Provider that a window is subscribed to via `listen_provider`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSubscription {
+  pub config_hash: String,
+
+  /// Type of the provider (e.g. `cpu`).
+  pub provider_type: String,
+
+  /// Fields of the provider output that are accessed by the window.
+  /// Empty if all fields are accessed.
+  pub tracked_access: Vec<String>,
+
+  /// Unix timestamp in milliseconds of the provider's last output, if
+  /// it has emitted yet.
+  pub last_emitted_at: Option<u64>,
+
+  /// Duration in milliseconds of the provider's most recent run. Only
+  /// set for interval providers.
+  pub last_run_ms: Option<u64>,
+
+  /// 95th percentile duration in milliseconds of the provider's recent
+  /// runs. Only set for interval providers.
+  pub p95_run_ms: Option<u64>,
+
+  /// Approximate size in bytes of the state that the provider retains
+  /// (e.g. history buffers, caches and its latest output). Measured
+  /// every minute, so it's `None` for new providers.
+  pub retained_bytes: Option<u64>,
+
+  /// Actions that can be invoked on the provider via
+  /// `call_provider_action`.
+  pub actions: Vec<ProviderAction>,
+}
+
+/// Provider subscriptions of each window, keyed by window label.
+#[derive(Debug, Default)]
+pub struct WindowSubscriptions(HashMap<String, Vec<ProviderSubscription>>);
+
+impl WindowSubscriptions {
+  /// Adds a subscription for the window. Replaces any existing
+  /// subscription with the same config hash.
+  pub fn insert(
+    &mut self,
+    window_label: &str,
+    subscription: ProviderSubscription,
+  ) {
+    let subscriptions =
+      self.0.entry(window_label.to_string()).or_default();
+
+    subscriptions
+      .retain(|existing| existing.config_hash != subscription.config_hash);
+
+    subscriptions.push(subscription);
+  }
+
+  /// Removes a window's subscription to a provider. Returns whether no
+  /// other window is subscribed to the provider.
+  pub fn remove(&mut self, window_label: &str, config_hash: &str) -> bool {
+    if let Some(subscriptions) = self.0.get_mut(window_label) {
+      subscriptions
+        .retain(|subscription| subscription.config_hash != config_hash);
+    }
+
+    !self.is_subscribed(config_hash)
+  }
+
+  /// Removes all subscriptions of the window. Returns the config hashes
+  /// of providers that no other window is subscribed to.
+  pub fn remove_window(&mut self, window_label: &str) -> Vec<String> {
+    let removed = self.0.remove(window_label).unwrap_or_default();
+
+    removed
+      .into_iter()
+      .map(|subscription| subscription.config_hash)
+      .filter(|config_hash| !self.is_subscribed(config_hash))
+      .collect()
+  }
+
+  /// Whether any window is subscribed to the given provider.
+  pub fn is_subscribed(&self, config_hash: &str) -> bool {
+    self
+      .0
+      .values()
+      .flatten()
+      .any(|subscription| subscription.config_hash == config_hash)
+  }
+
+  /// Gets the labels of the windows that are subscribed to the given
+  /// provider.
+  pub fn subscribers(&self, config_hash: &str) -> Vec<String> {
+    self
+      .0
+      .iter()
+      .filter(|(_, subscriptions)| {
+        subscriptions
+          .iter()
+          .any(|subscription| subscription.config_hash == config_hash)
+      })
+      .map(|(label, _)| label.clone())
+      .collect()
+  }
+
+  pub fn get(&self, window_label: &str) -> Vec<ProviderSubscription> {
+    self.0.get(window_label).cloned().unwrap_or_default()
+  }
+
+  /// Gets the subscriptions of all windows, sorted by window label.
+  pub fn all(&self) -> Vec<(String, Vec<ProviderSubscription>)> {
+    let mut all = self
+      .0
+      .iter()
+      .filter(|(_, subscriptions)| !subscriptions.is_empty())
+      .map(|(label, subscriptions)| (label.clone(), subscriptions.clone()))
+      .collect::<Vec<_>>();
+
+    all.sort_by(|(a, _), (b, _)| a.cmp(b));
+    all
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn subscription(config_hash: &str) -> ProviderSubscription {
+    ProviderSubscription {
+      config_hash: config_hash.into(),
+      provider_type: "cpu".into(),
+      tracked_access: Vec::new(),
+      last_emitted_at: None,
+      last_run_ms: None,
+      p95_run_ms: None,
+      retained_bytes: None,
+      actions: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn remove_reports_shared_providers_as_used() {
+    let mut subscriptions = WindowSubscriptions::default();
+    subscriptions.insert("1-bar", subscription("cpu"));
+    subscriptions.insert("2-bar", subscription("cpu"));
+
+    assert!(!subscriptions.remove("1-bar", "cpu"));
+    assert_eq!(subscriptions.subscribers("cpu"), ["2-bar"]);
+
+    assert!(subscriptions.remove("2-bar", "cpu"));
+    assert!(subscriptions.subscribers("cpu").is_empty());
+  }
+
+  #[test]
+  fn remove_window_only_returns_unused_providers() {
+    let mut subscriptions = WindowSubscriptions::default();
+    subscriptions.insert("1-bar", subscription("cpu"));
+    subscriptions.insert("1-bar", subscription("memory"));
+    subscriptions.insert("2-bar", subscription("cpu"));
+
+    assert_eq!(subscriptions.remove_window("1-bar"), ["memory"]);
+    assert!(subscriptions.is_subscribed("cpu"));
+  }
+}
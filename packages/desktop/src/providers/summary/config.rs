@@ -0,0 +1,12 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::impl_interval_config;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "summary")]
+pub struct SummaryProviderConfig {
+  pub refresh_interval: u64,
+}
+
+impl_interval_config!(SummaryProviderConfig);
@@ -0,0 +1,7 @@
+mod config;
+mod provider;
+mod variables;
+
+pub use config::*;
+pub use provider::*;
+pub use variables::*;
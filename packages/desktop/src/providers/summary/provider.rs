@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use starship_battery::Manager;
+use sysinfo::System;
+use tokio::{sync::Mutex, task::AbortHandle};
+
+use super::{SummaryBattery, SummaryProviderConfig, SummaryVariables};
+use crate::providers::{
+  battery::BatteryProvider,
+  memory::MemoryProvider,
+  network::network_traffic,
+  network_counters::NetworkCounters,
+  provider::{is_tracked, IntervalProvider},
+  variables::ProviderVariables,
+};
+
+pub struct SummaryProvider {
+  pub config: Arc<SummaryProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  state: Arc<SummaryState>,
+}
+
+pub struct SummaryState {
+  sysinfo: Arc<Mutex<System>>,
+  netinfo: Arc<Mutex<NetworkCounters>>,
+
+  /// Battery manager, if battery info is available on the device.
+  battery_manager: Option<Manager>,
+  tracked_access: Vec<String>,
+}
+
+impl SummaryProvider {
+  pub fn new(
+    config: SummaryProviderConfig,
+    sysinfo: Arc<Mutex<System>>,
+    netinfo: Arc<Mutex<NetworkCounters>>,
+    tracked_access: Vec<String>,
+  ) -> SummaryProvider {
+    let battery_manager = is_tracked(&tracked_access, "battery")
+      .then(|| Manager::new().ok())
+      .flatten();
+
+    SummaryProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      state: Arc::new(SummaryState {
+        sysinfo,
+        netinfo,
+        battery_manager,
+        tracked_access,
+      }),
+    }
+  }
+
+  /// Battery manager from `starship_battery` is not thread-safe, so it
+  /// requires its own non-async function.
+  fn battery(state: &SummaryState) -> Option<SummaryBattery> {
+    let batteries =
+      BatteryProvider::system_batteries(state.battery_manager.as_ref()?)
+        .ok()?;
+
+    BatteryProvider::primary_battery(&batteries).map(|battery| {
+      SummaryBattery {
+        charge_percent: battery.charge_percent,
+        state: battery.state.clone(),
+        is_charging: battery.is_charging,
+      }
+    })
+  }
+}
+
+#[async_trait]
+impl IntervalProvider for SummaryProvider {
+  type Config = SummaryProviderConfig;
+  type State = SummaryState;
+
+  fn config(&self) -> Arc<SummaryProviderConfig> {
+    self.config.clone()
+  }
+
+  fn state(&self) -> Arc<SummaryState> {
+    self.state.clone()
+  }
+
+  fn abort_handle(&self) -> &Option<AbortHandle> {
+    &self.abort_handle
+  }
+
+  fn set_abort_handle(&mut self, abort_handle: AbortHandle) {
+    self.abort_handle = Some(abort_handle)
+  }
+
+  async fn get_refreshed_variables(
+    config: &SummaryProviderConfig,
+    state: &SummaryState,
+  ) -> anyhow::Result<ProviderVariables> {
+    let tracks = |field: &str| is_tracked(&state.tracked_access, field);
+
+    let (cpu, memory) = match (tracks("cpu"), tracks("memory")) {
+      (false, false) => (None, None),
+      (track_cpu, track_memory) => {
+        let mut sysinfo = state.sysinfo.lock().await;
+
+        if track_cpu {
+          sysinfo.refresh_cpu();
+        }
+
+        if track_memory {
+          sysinfo.refresh_memory();
+        }
+
+        (
+          track_cpu.then(|| sysinfo.global_cpu_info().cpu_usage()),
+          track_memory.then(|| MemoryProvider::usage(&sysinfo)),
+        )
+      }
+    };
+
+    let network = match tracks("network") {
+      true => {
+        let mut netinfo = state.netinfo.lock().await;
+        network_traffic(&mut netinfo, config.refresh_interval)
+      }
+      false => None,
+    };
+
+    Ok(ProviderVariables::Summary(SummaryVariables {
+      cpu,
+      memory,
+      battery: Self::battery(state),
+      network,
+    }))
+  }
+}
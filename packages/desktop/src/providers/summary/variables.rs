@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+use crate::providers::network::NetworkTraffic;
+
+/// Compact overview of system usage for minimal bars.
+///
+/// Sections that aren't accessed by the frontend are `None`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryVariables {
+  /// CPU usage as a percentage.
+  pub cpu: Option<f32>,
+
+  /// Memory usage as a percentage.
+  pub memory: Option<f32>,
+
+  /// Primary battery. This is also `None` if the device has no battery.
+  pub battery: Option<SummaryBattery>,
+
+  /// Traffic across all interfaces in bytes per second. Also `None` for
+  /// the first sample after the machine resumes from sleep or the clock
+  /// is stepped.
+  pub network: Option<NetworkTraffic>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryBattery {
+  pub charge_percent: f32,
+  pub state: String,
+  pub is_charging: bool,
+}
@@ -0,0 +1,16 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "system_state")]
+pub struct SystemStateProviderConfig {
+  /// How often to check for Game Mode and presentation mode, which have
+  /// no change notifications. Energy saver changes are picked up right
+  /// away. Output is only emitted when a state changes.
+  #[serde(default = "default_refresh_interval")]
+  pub refresh_interval: u64,
+}
+
+fn default_refresh_interval() -> u64 {
+  2000
+}
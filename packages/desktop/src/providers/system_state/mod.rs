@@ -0,0 +1,8 @@
+mod config;
+mod provider;
+mod query;
+mod variables;
+
+pub use config::*;
+pub use provider::*;
+pub use variables::*;
@@ -0,0 +1,130 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tokio::{
+  sync::{mpsc::Sender, Notify},
+  task::{self, AbortHandle},
+  time,
+};
+
+use super::{
+  query::system_state, SystemStateProviderConfig, SystemStateVariables,
+};
+use crate::providers::{
+  provider::Provider,
+  provider_ref::{ProviderOutput, VariablesResult},
+  variables::ProviderVariables,
+};
+
+pub struct SystemStateProvider {
+  pub config: Arc<SystemStateProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  energy_saver_changed: Arc<Notify>,
+}
+
+impl SystemStateProvider {
+  pub fn new(
+    config: SystemStateProviderConfig,
+    energy_saver_changed: Arc<Notify>,
+  ) -> SystemStateProvider {
+    SystemStateProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      energy_saver_changed,
+    }
+  }
+
+  async fn get_variables() -> SystemStateVariables {
+    task::spawn_blocking(system_state).await.unwrap_or(
+      SystemStateVariables {
+        game_mode_active: None,
+        energy_saver_active: None,
+        presentation_mode_active: None,
+      },
+    )
+  }
+
+  fn to_output(
+    config_hash: &str,
+    variables: SystemStateVariables,
+  ) -> ProviderOutput {
+    ProviderOutput {
+      config_hash: config_hash.to_string(),
+      variables: VariablesResult::Data(ProviderVariables::SystemState(
+        variables,
+      )),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+}
+
+#[async_trait]
+impl Provider for SystemStateProvider {
+  fn min_refresh_interval(&self) -> Option<Duration> {
+    // Output is only emitted on changes, so it's always up to date.
+    None
+  }
+
+  async fn on_start(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let config = self.config.clone();
+    let config_hash = config_hash.to_string();
+    let energy_saver_changed = self.energy_saver_changed.clone();
+
+    let task_handle = task::spawn(async move {
+      let mut interval =
+        time::interval(Duration::from_millis(config.refresh_interval));
+
+      let mut prev_variables = None;
+
+      loop {
+        // Poll for states without change notifications, or check
+        // immediately when energy saver is toggled.
+        tokio::select! {
+          _ = interval.tick() => {},
+          _ = energy_saver_changed.notified() => {},
+        }
+
+        let variables = Self::get_variables().await;
+
+        if prev_variables.as_ref() == Some(&variables) {
+          continue;
+        }
+
+        prev_variables = Some(variables.clone());
+
+        _ = emit_output_tx
+          .send(Self::to_output(&config_hash, variables))
+          .await;
+      }
+    });
+
+    self.abort_handle = Some(task_handle.abort_handle());
+    _ = task_handle.await;
+  }
+
+  async fn on_refresh(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let variables = Self::get_variables().await;
+
+    _ = emit_output_tx
+      .send(Self::to_output(config_hash, variables))
+      .await;
+  }
+
+  async fn on_stop(&mut self) {
+    if let Some(handle) = &self.abort_handle {
+      handle.abort();
+    }
+  }
+}
@@ -0,0 +1,53 @@
+#[cfg(target_os = "windows")]
+use windows::{
+  core::w,
+  Win32::{
+    System::Registry::HKEY_CURRENT_USER,
+    UI::Shell::{
+      SHQueryUserNotificationState, QUNS_PRESENTATION_MODE,
+      QUNS_RUNNING_D3D_FULL_SCREEN,
+    },
+  },
+};
+
+use super::SystemStateVariables;
+use crate::providers::power_state::energy_saver_active;
+#[cfg(target_os = "windows")]
+use crate::util::registry::read_registry_dword;
+
+/// Reads the current system states.
+///
+/// Can block briefly, so it should be called off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn system_state() -> SystemStateVariables {
+  // Same state that Windows uses to decide whether to show
+  // notifications.
+  let notification_state = unsafe { SHQueryUserNotificationState() }.ok();
+
+  // Game Mode is enabled by default if the setting was never changed.
+  let is_game_mode_enabled = read_registry_dword(
+    HKEY_CURRENT_USER,
+    w!("Software\\Microsoft\\GameBar"),
+    w!("AutoGameModeEnabled"),
+  )
+  .map_or(true, |value| value != 0);
+
+  SystemStateVariables {
+    game_mode_active: notification_state.map(|state| {
+      is_game_mode_enabled && state == QUNS_RUNNING_D3D_FULL_SCREEN
+    }),
+    energy_saver_active: energy_saver_active(),
+    presentation_mode_active: notification_state
+      .map(|state| state == QUNS_PRESENTATION_MODE),
+  }
+}
+
+/// Game Mode and presentation mode only exist on Windows.
+#[cfg(not(target_os = "windows"))]
+pub fn system_state() -> SystemStateVariables {
+  SystemStateVariables {
+    game_mode_active: None,
+    energy_saver_active: energy_saver_active(),
+    presentation_mode_active: None,
+  }
+}
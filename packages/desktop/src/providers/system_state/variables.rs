@@ -0,0 +1,19 @@
+use serde::Serialize;
+
+/// States of the OS that widgets might adapt to (e.g. by pausing
+/// animations). States are `None` on platforms or OS versions that
+/// don't report them.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStateVariables {
+  /// Whether Game Mode is active, i.e. a game is running full-screen
+  /// while Game Mode is enabled in settings.
+  pub game_mode_active: Option<bool>,
+
+  /// Whether energy saver (battery saver before Windows 11 24H2) is on.
+  pub energy_saver_active: Option<bool>,
+
+  /// Whether presentation mode is on (e.g. via "Presentation settings"
+  /// or while presenting slides), which suppresses notifications.
+  pub presentation_mode_active: Option<bool>,
+}
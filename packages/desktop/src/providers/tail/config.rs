@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "tail")]
+pub struct TailProviderConfig {
+  /// Milliseconds between checks of the file for new lines.
+  pub refresh_interval: u64,
+
+  /// Path to the file to follow. The file doesn't need to exist yet,
+  /// and is followed across rotation and truncation.
+  pub path: String,
+
+  /// Map of pattern names to regexes that lines are matched against.
+  /// Named capture groups are emitted with each match. Every line
+  /// matches if no patterns are given.
+  #[serde(default)]
+  pub patterns: HashMap<String, String>,
+
+  /// Max number of recent matches to emit.
+  #[serde(default = "default_max_matches")]
+  pub max_matches: usize,
+
+  /// Max length of a line in bytes. Longer lines are truncated.
+  #[serde(default = "default_max_line_length")]
+  pub max_line_length: usize,
+}
+
+fn default_max_matches() -> usize {
+  10
+}
+
+fn default_max_line_length() -> usize {
+  1000
+}
@@ -0,0 +1,227 @@
+use std::{
+  fs::{self, File, Metadata},
+  io::{self, Read, Seek, SeekFrom},
+  path::PathBuf,
+};
+
+use anyhow::{bail, Context};
+
+/// Max number of bytes read per poll. Anything beyond is read on the
+/// following polls, so a burst of writes can't stall the provider.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+/// Number of bytes at the start of a file that are checked for NUL
+/// bytes to detect binary files.
+const BINARY_CHECK_BYTES: u64 = 8 * 1024;
+
+/// Line that was read from the file.
+#[derive(Debug)]
+pub struct TailLine {
+  pub text: String,
+  pub is_truncated: bool,
+}
+
+/// Identifies the file behind a path, so that rotation (i.e. the path
+/// being replaced by a new file) can be detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+  #[cfg(unix)]
+  dev: u64,
+  #[cfg(unix)]
+  ino: u64,
+  #[cfg(not(unix))]
+  created: Option<std::time::SystemTime>,
+}
+
+impl FileIdentity {
+  #[cfg(unix)]
+  fn of(metadata: &Metadata) -> Self {
+    use std::os::unix::fs::MetadataExt;
+
+    Self {
+      dev: metadata.dev(),
+      ino: metadata.ino(),
+    }
+  }
+
+  #[cfg(not(unix))]
+  fn of(metadata: &Metadata) -> Self {
+    Self {
+      created: metadata.created().ok(),
+    }
+  }
+}
+
+/// Follows a file by name, the same as `tail -F`.
+///
+/// The file is re-opened from the start when it's replaced (e.g. by log
+/// rotation) or truncated. Content that exists when the follower starts
+/// is skipped, so that only new lines are read.
+pub struct FileFollower {
+  path: PathBuf,
+  max_line_length: usize,
+  file: Option<File>,
+  identity: Option<FileIdentity>,
+  offset: u64,
+  is_first_poll: bool,
+
+  /// Start of a line whose end hasn't been written yet. Capped at
+  /// `max_line_length`.
+  partial: Vec<u8>,
+  is_partial_truncated: bool,
+}
+
+impl FileFollower {
+  pub fn new(path: PathBuf, max_line_length: usize) -> Self {
+    Self {
+      path,
+      max_line_length,
+      file: None,
+      identity: None,
+      offset: 0,
+      is_first_poll: true,
+      partial: Vec::new(),
+      is_partial_truncated: false,
+    }
+  }
+
+  /// Whether the file was found on the last poll.
+  pub fn exists(&self) -> bool {
+    self.file.is_some()
+  }
+
+  /// Reads the lines that were completed since the last poll.
+  pub fn poll(&mut self) -> anyhow::Result<Vec<TailLine>> {
+    let is_first_poll = std::mem::replace(&mut self.is_first_poll, false);
+
+    let metadata = match fs::metadata(&self.path) {
+      Ok(metadata) => metadata,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => {
+        self.close();
+        return Ok(Vec::new());
+      }
+      Err(err) => {
+        self.close();
+        return Err(err).with_context(|| {
+          format!("Failed to read '{}'.", self.path.display())
+        });
+      }
+    };
+
+    let identity = FileIdentity::of(&metadata);
+    let is_rotated = self.identity.is_some_and(|prev| prev != identity);
+    let is_truncated = metadata.len() < self.offset;
+
+    if self.file.is_none() || is_rotated || is_truncated {
+      self.open(identity)?;
+
+      // Existing content is skipped on start, whereas files that are
+      // created, replaced or truncated afterwards are read in full.
+      self.offset = match is_first_poll {
+        true => metadata.len(),
+        false => 0,
+      };
+    }
+
+    let Some(file) = &mut self.file else {
+      return Ok(Vec::new());
+    };
+
+    let mut buffer = Vec::new();
+    file.seek(SeekFrom::Start(self.offset))?;
+    file
+      .by_ref()
+      .take(MAX_READ_BYTES)
+      .read_to_end(&mut buffer)?;
+    self.offset += buffer.len() as u64;
+
+    Ok(self.split_lines(&buffer))
+  }
+
+  fn open(&mut self, identity: FileIdentity) -> anyhow::Result<()> {
+    self.close();
+
+    let mut file = File::open(&self.path).with_context(|| {
+      format!("Failed to open '{}'.", self.path.display())
+    })?;
+
+    let mut head = Vec::new();
+    file
+      .by_ref()
+      .take(BINARY_CHECK_BYTES)
+      .read_to_end(&mut head)?;
+
+    if head.contains(&0) {
+      bail!("File '{}' appears to be binary.", self.path.display());
+    }
+
+    self.file = Some(file);
+    self.identity = Some(identity);
+
+    Ok(())
+  }
+
+  fn close(&mut self) {
+    self.file = None;
+    self.offset = 0;
+    self.partial.clear();
+    self.is_partial_truncated = false;
+  }
+
+  /// Splits the bytes into complete lines. The trailing incomplete line
+  /// is kept for the next poll.
+  fn split_lines(&mut self, buffer: &[u8]) -> Vec<TailLine> {
+    let mut lines = Vec::new();
+    let mut segments = buffer.split(|byte| *byte == b'\n').peekable();
+
+    while let Some(segment) = segments.next() {
+      self.append_partial(segment);
+
+      // The last segment has no newline after it.
+      if segments.peek().is_none() {
+        break;
+      }
+
+      let bytes = std::mem::take(&mut self.partial);
+      let is_truncated =
+        std::mem::replace(&mut self.is_partial_truncated, false);
+
+      if let Some(line) = Self::decode_line(&bytes, is_truncated) {
+        lines.push(line);
+      }
+    }
+
+    lines
+  }
+
+  fn append_partial(&mut self, bytes: &[u8]) {
+    let room = self.max_line_length.saturating_sub(self.partial.len());
+
+    if bytes.len() > room {
+      self.is_partial_truncated = true;
+    }
+
+    self
+      .partial
+      .extend_from_slice(&bytes[..bytes.len().min(room)]);
+  }
+
+  /// Decodes a line as UTF-8. Lines with NUL bytes are skipped, since
+  /// they're likely binary data.
+  fn decode_line(bytes: &[u8], is_truncated: bool) -> Option<TailLine> {
+    if bytes.contains(&0) {
+      return None;
+    }
+
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    let mut text = String::from_utf8_lossy(bytes).into_owned();
+
+    // Truncation can split a multi-byte character.
+    if is_truncated {
+      let trimmed_len = text.trim_end_matches('\u{FFFD}').len();
+      text.truncate(trimmed_len);
+    }
+
+    Some(TailLine { text, is_truncated })
+  }
+}
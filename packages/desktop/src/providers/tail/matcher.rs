@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use regex::Regex;
+
+use super::{follower::TailLine, TailMatch};
+use crate::providers::last_updated::now_millis;
+
+/// Matches lines against the configured patterns.
+pub struct LineMatcher {
+  /// Patterns sorted by name.
+  patterns: Vec<(String, Regex)>,
+}
+
+impl LineMatcher {
+  pub fn new(patterns: &HashMap<String, String>) -> anyhow::Result<Self> {
+    let mut patterns = patterns
+      .iter()
+      .map(|(name, pattern)| {
+        let regex = Regex::new(pattern).with_context(|| {
+          format!("Invalid regex for pattern '{}'.", name)
+        })?;
+
+        anyhow::Ok((name.clone(), regex))
+      })
+      .collect::<anyhow::Result<Vec<_>>>()?;
+
+    patterns.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(Self { patterns })
+  }
+
+  /// Names of all patterns.
+  pub fn pattern_names(&self) -> impl Iterator<Item = &str> {
+    self.patterns.iter().map(|(name, _)| name.as_str())
+  }
+
+  /// Matches the line against the patterns. Every line matches if there
+  /// are no patterns.
+  pub fn match_line(&self, line: TailLine) -> Option<TailMatch> {
+    let mut matched = Vec::new();
+    let mut captures = HashMap::new();
+
+    for (name, regex) in &self.patterns {
+      let Some(caps) = regex.captures(&line.text) else {
+        continue;
+      };
+
+      for capture_name in regex.capture_names().flatten() {
+        if let Some(value) = caps.name(capture_name) {
+          captures
+            .entry(capture_name.to_string())
+            .or_insert_with(|| value.as_str().to_string());
+        }
+      }
+
+      matched.push(name.clone());
+    }
+
+    if matched.is_empty() && !self.patterns.is_empty() {
+      return None;
+    }
+
+    Some(TailMatch {
+      line: line.text,
+      is_truncated: line.is_truncated,
+      patterns: matched,
+      captures,
+      matched_at: now_millis(),
+    })
+  }
+}
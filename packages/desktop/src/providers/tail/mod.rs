@@ -0,0 +1,9 @@
+mod config;
+mod follower;
+mod matcher;
+mod provider;
+mod variables;
+
+pub use config::*;
+pub use provider::*;
+pub use variables::*;
@@ -0,0 +1,293 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  path::PathBuf,
+  sync::Arc,
+  time::Duration,
+};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::{
+  sync::{
+    mpsc::{self, Sender},
+    Mutex,
+  },
+  task::{self, AbortHandle},
+  time::{self, MissedTickBehavior},
+};
+use tracing::warn;
+
+use super::{
+  follower::FileFollower, matcher::LineMatcher, TailMatch,
+  TailProviderConfig, TailVariables,
+};
+use crate::providers::{
+  actions::{ActionRequest, ProviderAction, ProviderActions},
+  provider::Provider,
+  provider_ref::ProviderOutput,
+  retained_size::{json_size, RetainedSize, RetainedState},
+  variables::ProviderVariables,
+};
+
+pub struct TailProvider {
+  pub config: Arc<TailProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  matcher: Arc<LineMatcher>,
+  follower: Arc<std::sync::Mutex<FileFollower>>,
+  state: Arc<Mutex<TailState>>,
+  actions: ProviderActions,
+  config_hash: Option<String>,
+}
+
+/// Counts and recent matches since the provider started.
+pub struct TailState {
+  exists: bool,
+  counts: HashMap<String, u64>,
+  matches: VecDeque<TailMatch>,
+}
+
+#[async_trait]
+impl RetainedSize for Mutex<TailState> {
+  async fn retained_size(&self) -> usize {
+    json_size(&self.lock().await.matches)
+  }
+}
+
+impl TailProvider {
+  pub fn new(
+    config: TailProviderConfig,
+    actions: ProviderActions,
+  ) -> anyhow::Result<TailProvider> {
+    let matcher = LineMatcher::new(&config.patterns)?;
+
+    let follower = FileFollower::new(
+      PathBuf::from(&config.path),
+      config.max_line_length,
+    );
+
+    let state = TailState {
+      exists: false,
+      counts: Self::zero_counts(&matcher),
+      matches: VecDeque::new(),
+    };
+
+    Ok(TailProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      matcher: Arc::new(matcher),
+      follower: Arc::new(std::sync::Mutex::new(follower)),
+      state: Arc::new(Mutex::new(state)),
+      actions,
+      config_hash: None,
+    })
+  }
+
+  fn zero_counts(matcher: &LineMatcher) -> HashMap<String, u64> {
+    matcher
+      .pattern_names()
+      .map(|name| (name.to_string(), 0))
+      .collect()
+  }
+
+  /// Reads new lines from the file. Returns whether the output changed.
+  async fn poll(
+    config: &TailProviderConfig,
+    matcher: &LineMatcher,
+    follower: &Arc<std::sync::Mutex<FileFollower>>,
+    state: &Mutex<TailState>,
+  ) -> anyhow::Result<bool> {
+    let follower = follower.clone();
+
+    // Reads can block, e.g. for files on network drives.
+    let (lines, exists) = task::spawn_blocking(move || {
+      let mut follower = follower.lock().unwrap();
+      follower.poll().map(|lines| (lines, follower.exists()))
+    })
+    .await??;
+
+    let mut state = state.lock().await;
+    let mut is_changed = state.exists != exists;
+    state.exists = exists;
+
+    for line in lines {
+      let Some(line_match) = matcher.match_line(line) else {
+        continue;
+      };
+
+      for pattern in &line_match.patterns {
+        *state.counts.entry(pattern.clone()).or_default() += 1;
+      }
+
+      state.matches.push_back(line_match);
+      is_changed = true;
+    }
+
+    while state.matches.len() > config.max_matches {
+      state.matches.pop_front();
+    }
+
+    Ok(is_changed)
+  }
+
+  fn to_output(
+    config_hash: &str,
+    config: &TailProviderConfig,
+    variables: anyhow::Result<&TailState>,
+  ) -> ProviderOutput {
+    let variables = variables.map(|state| {
+      ProviderVariables::Tail(TailVariables {
+        path: config.path.clone(),
+        exists: state.exists,
+        counts: state.counts.clone(),
+        matches: state.matches.iter().cloned().collect(),
+      })
+    });
+
+    ProviderOutput {
+      config_hash: config_hash.to_string(),
+      variables: variables.into(),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+}
+
+#[async_trait]
+impl Provider for TailProvider {
+  fn min_refresh_interval(&self) -> Option<Duration> {
+    // Output is only emitted on changes, so it's always up to date.
+    None
+  }
+
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(vec![self.state.clone()])
+  }
+
+  async fn on_start(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let (request_tx, mut request_rx) = mpsc::channel::<ActionRequest>(1);
+
+    let actions = vec![ProviderAction::new::<()>(
+      "reset_counts",
+      "Resets the match counts to zero.",
+    )];
+
+    self
+      .actions
+      .register(config_hash, actions, request_tx)
+      .await;
+
+    self.config_hash = Some(config_hash.to_string());
+
+    let config = self.config.clone();
+    let config_hash = config_hash.to_string();
+    let matcher = self.matcher.clone();
+    let follower = self.follower.clone();
+    let state = self.state.clone();
+
+    let task_handle = task::spawn(async move {
+      let mut interval =
+        time::interval(Duration::from_millis(config.refresh_interval));
+
+      interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+      // Errors are only emitted when they change, since a failing read
+      // is retried on every tick.
+      let mut prev_err = None;
+      let mut is_first_poll = true;
+
+      loop {
+        tokio::select! {
+          _ = interval.tick() => {
+            let res =
+              Self::poll(&config, &matcher, &follower, &state).await;
+
+            let output = match res {
+              Ok(is_changed) => {
+                let should_emit =
+                  is_changed || is_first_poll || prev_err.is_some();
+
+                prev_err = None;
+
+                match should_emit {
+                  true => Self::to_output(
+                    &config_hash,
+                    &config,
+                    Ok(&*state.lock().await),
+                  ),
+                  false => continue,
+                }
+              }
+              Err(err) => {
+                let message = err.to_string();
+
+                if prev_err.as_ref() == Some(&message) {
+                  continue;
+                }
+
+                warn!("Failed to read '{}': {:?}", config.path, err);
+                prev_err = Some(message);
+                Self::to_output(&config_hash, &config, Err(err))
+              }
+            };
+
+            is_first_poll = false;
+            _ = emit_output_tx.send(output).await;
+          }
+          Some(request) = request_rx.recv() => {
+            let res = match request.action.as_str() {
+              "reset_counts" => {
+                let mut state = state.lock().await;
+                state.counts = Self::zero_counts(&matcher);
+
+                let output =
+                  Self::to_output(&config_hash, &config, Ok(&*state));
+
+                drop(state);
+                _ = emit_output_tx.send(output).await;
+                Ok(Value::Null)
+              }
+              other => Err(anyhow!("Unknown action '{}'.", other)),
+            };
+
+            _ = request.result_tx.send(res);
+          }
+        }
+      }
+    });
+
+    self.abort_handle = Some(task_handle.abort_handle());
+    _ = task_handle.await;
+  }
+
+  async fn on_refresh(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let output = Self::to_output(
+      config_hash,
+      &self.config,
+      Ok(&*self.state.lock().await),
+    );
+
+    _ = emit_output_tx.send(output).await;
+  }
+
+  async fn on_stop(&mut self) {
+    if let Some(config_hash) = &self.config_hash {
+      self.actions.unregister(config_hash).await;
+    }
+
+    if let Some(handle) = &self.abort_handle {
+      handle.abort();
+    }
+  }
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TailVariables {
+  pub path: String,
+
+  /// Whether the file currently exists. Missing files are picked up
+  /// once they're created.
+  pub exists: bool,
+
+  /// Number of matches per pattern since the provider started or its
+  /// counts were last reset.
+  pub counts: HashMap<String, u64>,
+
+  /// Most recent matches, oldest first.
+  pub matches: Vec<TailMatch>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TailMatch {
+  pub line: String,
+
+  /// Whether the line was cut off at `max_line_length`.
+  pub is_truncated: bool,
+
+  /// Names of the patterns that matched the line.
+  pub patterns: Vec<String>,
+
+  /// Named capture groups of the matching patterns. If several patterns
+  /// capture the same name, the first pattern by name wins.
+  pub captures: HashMap<String, String>,
+
+  /// Unix timestamp in milliseconds of when the line was read.
+  pub matched_at: u64,
+}
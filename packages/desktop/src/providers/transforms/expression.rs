@@ -0,0 +1,466 @@
+use anyhow::{bail, Context};
+use serde_json::{Number, Value};
+
+/// Parsed transform expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+  Literal(Value),
+  Path(Vec<PathSegment>),
+  Negate(Box<Expr>),
+  Binary(BinaryOp, Box<Expr>, Box<Expr>),
+  Call(Function, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+  Field(String),
+  Index(i64),
+  Slice(Option<i64>, Option<i64>),
+  Wildcard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+  Add,
+  Subtract,
+  Multiply,
+  Divide,
+  Remainder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+  Round,
+  Floor,
+  Ceil,
+  Abs,
+  Min,
+  Max,
+  Len,
+  Sum,
+  Join,
+}
+
+impl Function {
+  pub fn from_name(name: &str) -> Option<Function> {
+    match name {
+      "round" => Some(Function::Round),
+      "floor" => Some(Function::Floor),
+      "ceil" => Some(Function::Ceil),
+      "abs" => Some(Function::Abs),
+      "min" => Some(Function::Min),
+      "max" => Some(Function::Max),
+      "len" => Some(Function::Len),
+      "sum" => Some(Function::Sum),
+      "join" => Some(Function::Join),
+      _ => None,
+    }
+  }
+
+  /// Minimum and maximum number of arguments accepted by the function.
+  pub fn arity(&self) -> (usize, usize) {
+    match self {
+      Function::Round => (1, 2),
+      Function::Floor
+      | Function::Ceil
+      | Function::Abs
+      | Function::Len
+      | Function::Sum => (1, 1),
+      Function::Min | Function::Max => (1, usize::MAX),
+      Function::Join => (2, 2),
+    }
+  }
+}
+
+impl Expr {
+  /// Evaluates the expression against the given input value.
+  pub fn evaluate(&self, input: &Value) -> anyhow::Result<Value> {
+    match self {
+      Expr::Literal(value) => Ok(value.clone()),
+      Expr::Path(segments) => Ok(resolve_path(input, segments)),
+      Expr::Negate(expr) => to_number(-as_f64(&expr.evaluate(input)?)?),
+      Expr::Binary(op, lhs, rhs) => {
+        evaluate_binary(*op, lhs.evaluate(input)?, rhs.evaluate(input)?)
+      }
+      Expr::Call(function, args) => {
+        let args = args
+          .iter()
+          .map(|arg| arg.evaluate(input))
+          .collect::<anyhow::Result<Vec<_>>>()?;
+
+        evaluate_call(*function, args)
+      }
+    }
+  }
+}
+
+/// Resolves a path against a value. Missing fields and out of range
+/// indices resolve to `null`.
+fn resolve_path(value: &Value, segments: &[PathSegment]) -> Value {
+  let Some((segment, rest)) = segments.split_first() else {
+    return value.clone();
+  };
+
+  match segment {
+    PathSegment::Field(name) => match value.get(name) {
+      Some(field) => resolve_path(field, rest),
+      None => Value::Null,
+    },
+    PathSegment::Index(index) => match value {
+      Value::Array(array) => match resolve_index(*index, array.len()) {
+        Some(index) => resolve_path(&array[index], rest),
+        None => Value::Null,
+      },
+      _ => Value::Null,
+    },
+    PathSegment::Slice(start, end) => match value {
+      Value::Array(array) => {
+        let start = start
+          .map(|start| clamp_index(start, array.len()))
+          .unwrap_or(0);
+
+        let end = end
+          .map(|end| clamp_index(end, array.len()))
+          .unwrap_or(array.len());
+
+        project(array.get(start..end).unwrap_or(&[]).iter(), rest)
+      }
+      _ => Value::Null,
+    },
+    PathSegment::Wildcard => match value {
+      Value::Array(array) => project(array.iter(), rest),
+      Value::Object(object) => project(object.values(), rest),
+      _ => Value::Null,
+    },
+  }
+}
+
+/// Applies the remaining path to each element, dropping `null` results.
+fn project<'a>(
+  elements: impl Iterator<Item = &'a Value>,
+  segments: &[PathSegment],
+) -> Value {
+  Value::Array(
+    elements
+      .map(|element| resolve_path(element, segments))
+      .filter(|element| !element.is_null())
+      .collect(),
+  )
+}
+
+/// Converts a possibly negative index into an index from the start.
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+  let index = match index < 0 {
+    true => len as i64 + index,
+    false => index,
+  };
+
+  match index >= 0 && (index as usize) < len {
+    true => Some(index as usize),
+    false => None,
+  }
+}
+
+/// Converts a possibly negative slice bound into a bound within
+/// `0..=len`.
+fn clamp_index(index: i64, len: usize) -> usize {
+  let index = match index < 0 {
+    true => len as i64 + index,
+    false => index,
+  };
+
+  index.clamp(0, len as i64) as usize
+}
+
+fn evaluate_binary(
+  op: BinaryOp,
+  lhs: Value,
+  rhs: Value,
+) -> anyhow::Result<Value> {
+  if op == BinaryOp::Add && (lhs.is_string() || rhs.is_string()) {
+    return Ok(Value::String(to_text(&lhs) + &to_text(&rhs)));
+  }
+
+  let lhs = as_f64(&lhs)?;
+  let rhs = as_f64(&rhs)?;
+
+  let result = match op {
+    BinaryOp::Add => lhs + rhs,
+    BinaryOp::Subtract => lhs - rhs,
+    BinaryOp::Multiply => lhs * rhs,
+    BinaryOp::Divide | BinaryOp::Remainder if rhs == 0. => {
+      bail!("Division by zero.")
+    }
+    BinaryOp::Divide => lhs / rhs,
+    BinaryOp::Remainder => lhs % rhs,
+  };
+
+  to_number(result)
+}
+
+fn evaluate_call(
+  function: Function,
+  args: Vec<Value>,
+) -> anyhow::Result<Value> {
+  match function {
+    Function::Round => {
+      let value = as_f64(&args[0])?;
+      let digits = match args.get(1) {
+        Some(digits) => as_f64(digits)? as i32,
+        None => 0,
+      };
+
+      let factor = 10f64.powi(digits);
+      to_number((value * factor).round() / factor)
+    }
+    Function::Floor => to_number(as_f64(&args[0])?.floor()),
+    Function::Ceil => to_number(as_f64(&args[0])?.ceil()),
+    Function::Abs => to_number(as_f64(&args[0])?.abs()),
+    Function::Min | Function::Max => {
+      let numbers = flatten_numbers(&args)?;
+
+      let result =
+        numbers.into_iter().reduce(|acc, number| {
+          match function == Function::Min {
+            true => acc.min(number),
+            false => acc.max(number),
+          }
+        });
+
+      match result {
+        Some(result) => to_number(result),
+        None => Ok(Value::Null),
+      }
+    }
+    Function::Len => match &args[0] {
+      Value::Array(array) => Ok(array.len().into()),
+      Value::Object(object) => Ok(object.len().into()),
+      Value::String(string) => Ok(string.chars().count().into()),
+      Value::Null => Ok(0.into()),
+      other => bail!("Cannot get length of {}.", type_name(other)),
+    },
+    Function::Sum => to_number(flatten_numbers(&args)?.iter().sum()),
+    Function::Join => {
+      let separator = match &args[1] {
+        Value::String(separator) => separator,
+        other => {
+          bail!("Expected a string separator, got {}.", type_name(other))
+        }
+      };
+
+      match &args[0] {
+        Value::Array(array) => Ok(Value::String(
+          array
+            .iter()
+            .map(to_text)
+            .collect::<Vec<_>>()
+            .join(separator),
+        )),
+        other => bail!("Cannot join {}.", type_name(other)),
+      }
+    }
+  }
+}
+
+/// Collects numbers from the arguments, where arrays are expanded into
+/// their elements.
+fn flatten_numbers(args: &[Value]) -> anyhow::Result<Vec<f64>> {
+  let mut numbers = Vec::new();
+
+  for arg in args {
+    match arg {
+      Value::Array(array) => {
+        for element in array {
+          numbers.push(as_f64(element)?);
+        }
+      }
+      other => numbers.push(as_f64(other)?),
+    }
+  }
+
+  Ok(numbers)
+}
+
+fn as_f64(value: &Value) -> anyhow::Result<f64> {
+  value.as_f64().with_context(|| {
+    format!("Expected a number, got {}.", type_name(value))
+  })
+}
+
+/// Converts a float into a JSON number, preferring integers for whole
+/// numbers.
+fn to_number(value: f64) -> anyhow::Result<Value> {
+  if value.fract() == 0. && value.abs() < i64::MAX as f64 {
+    return Ok(Value::Number((value as i64).into()));
+  }
+
+  Number::from_f64(value)
+    .map(Value::Number)
+    .context("Result is not a finite number.")
+}
+
+fn to_text(value: &Value) -> String {
+  match value {
+    Value::String(string) => string.clone(),
+    Value::Null => String::new(),
+    other => other.to_string(),
+  }
+}
+
+fn type_name(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "a boolean",
+    Value::Number(_) => "a number",
+    Value::String(_) => "a string",
+    Value::Array(_) => "an array",
+    Value::Object(_) => "an object",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::providers::transforms::parse_expression;
+
+  fn eval(expr: &str, input: &Value) -> anyhow::Result<Value> {
+    parse_expression(expr)?.evaluate(input)
+  }
+
+  fn input() -> Value {
+    json!({
+      "name": "cpu",
+      "usage": 42.5,
+      "cores": [10, 20, 30, 40],
+      "empty": [],
+      "monitors": [
+        { "name": "left", "workspaces": [{ "name": "1" }] },
+        { "workspaces": [{ "name": "2" }, { "name": "3" }] },
+      ],
+    })
+  }
+
+  #[test]
+  fn resolves_fields_and_indices() {
+    let input = input();
+
+    assert_eq!(eval(".name", &input).unwrap(), "cpu");
+    assert_eq!(eval(".cores[1]", &input).unwrap(), 20);
+    assert_eq!(eval(".cores[-1]", &input).unwrap(), 40);
+    assert_eq!(eval(".", &input).unwrap(), input);
+  }
+
+  #[test]
+  fn missing_paths_resolve_to_null() {
+    let input = input();
+
+    for expr in [
+      ".missing",
+      ".missing.field",
+      ".name.field",
+      ".cores[4]",
+      ".cores[-5]",
+      ".name[0]",
+      ".usage[*]",
+      ".usage[0:1]",
+    ] {
+      assert_eq!(eval(expr, &input).unwrap(), Value::Null, "{}", expr);
+    }
+  }
+
+  #[test]
+  fn slices_are_clamped() {
+    let input = input();
+
+    assert_eq!(eval(".cores[1:3]", &input).unwrap(), json!([20, 30]));
+    assert_eq!(eval(".cores[-2:]", &input).unwrap(), json!([30, 40]));
+    assert_eq!(eval(".cores[:-3]", &input).unwrap(), json!([10]));
+    assert_eq!(eval(".cores[2:100]", &input).unwrap(), json!([30, 40]));
+    assert_eq!(eval(".cores[3:1]", &input).unwrap(), json!([]));
+  }
+
+  #[test]
+  fn projections_drop_missing_values() {
+    let input = input();
+
+    assert_eq!(
+      eval(".monitors[*].name", &input).unwrap(),
+      json!(["left"])
+    );
+
+    assert_eq!(
+      eval(".monitors[*].workspaces[-1].name", &input).unwrap(),
+      json!(["1", "3"])
+    );
+
+    assert_eq!(
+      eval(".monitors[1:].workspaces[*].name", &input).unwrap(),
+      json!([["2", "3"]])
+    );
+  }
+
+  #[test]
+  fn evaluates_arithmetic() {
+    let input = input();
+
+    assert_eq!(eval(".usage * 2", &input).unwrap(), 85);
+    assert_eq!(eval(".usage / 4", &input).unwrap(), 10.625);
+    assert_eq!(eval("-.cores[0] % 3", &input).unwrap(), -1);
+  }
+
+  #[test]
+  fn concatenates_strings() {
+    let input = input();
+
+    assert_eq!(
+      eval(".name + ': ' + .usage", &input).unwrap(),
+      "cpu: 42.5"
+    );
+
+    assert_eq!(eval(".missing + 'x'", &input).unwrap(), "x");
+  }
+
+  #[test]
+  fn evaluates_functions() {
+    let input = input();
+
+    assert_eq!(eval("round(1.23456, 2)", &input).unwrap(), 1.23);
+    assert_eq!(eval("floor(.usage)", &input).unwrap(), 42);
+    assert_eq!(eval("ceil(.usage)", &input).unwrap(), 43);
+    assert_eq!(eval("abs(0 - 3)", &input).unwrap(), 3);
+    assert_eq!(eval("min(.cores, 5)", &input).unwrap(), 5);
+    assert_eq!(eval("max(.cores)", &input).unwrap(), 40);
+    assert_eq!(eval("min(.empty)", &input).unwrap(), Value::Null);
+    assert_eq!(eval("sum(.cores)", &input).unwrap(), 100);
+    assert_eq!(eval("len(.cores)", &input).unwrap(), 4);
+    assert_eq!(eval("len('héllo')", &input).unwrap(), 5);
+    assert_eq!(eval("len(.missing)", &input).unwrap(), 0);
+
+    assert_eq!(
+      eval("join(.monitors[*].workspaces[0].name, ', ')", &input).unwrap(),
+      "1, 2"
+    );
+  }
+
+  #[test]
+  fn evaluation_errors() {
+    let input = input();
+
+    for expr in [
+      "1 / 0",
+      "1 % 0",
+      ".name * 2",
+      "-.name",
+      ".missing - 1",
+      "round(.name)",
+      "sum(.monitors)",
+      "len(.usage)",
+      "join(.cores, 1)",
+      "join(.name, ', ')",
+    ] {
+      assert!(eval(expr, &input).is_err(), "Expected '{}' to fail.", expr);
+    }
+  }
+}
@@ -0,0 +1,24 @@
+//! Small expression language for deriving values from provider output.
+//!
+//! Expressions are made up of:
+//! - Paths into the provider output, e.g. `.usedMemory`,
+//!   `.allMonitors[0].workspaces[-1]`. Wildcards (`[*]`) and slices
+//!   (`[0:3]`) project the rest of the path over each element, e.g.
+//!   `.allMonitors[0].workspaces[0:3].name`.
+//! - Number, string (`'...'` or `"..."`), `true`, `false` and `null`
+//!   literals.
+//! - Arithmetic with `+`, `-`, `*`, `/` and `%`. `+` concatenates when
+//!   either side is a string.
+//! - Function calls: `round(x)`, `round(x, digits)`, `floor(x)`,
+//!   `ceil(x)`, `abs(x)`, `min(a, b, ...)`, `max(a, b, ...)`, `len(x)`,
+//!   `sum(array)` and `join(array, separator)`.
+//!
+//! This is intentionally not a general-purpose scripting language.
+
+mod expression;
+mod parser;
+mod transform_set;
+
+pub use expression::*;
+pub use parser::*;
+pub use transform_set::*;
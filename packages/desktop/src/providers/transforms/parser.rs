@@ -0,0 +1,432 @@
+use anyhow::{bail, Context};
+use serde_json::Value;
+
+use super::{BinaryOp, Expr, Function, PathSegment};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f64),
+  String(String),
+  Ident(String),
+  Dot,
+  Comma,
+  Colon,
+  LeftParen,
+  RightParen,
+  LeftBracket,
+  RightBracket,
+  Plus,
+  Minus,
+  Star,
+  Slash,
+  Percent,
+}
+
+/// Parses a transform expression (e.g. `round(.usage, 1)`).
+pub fn parse_expression(input: &str) -> anyhow::Result<Expr> {
+  let tokens = tokenize(input)?;
+  let mut parser = Parser {
+    tokens,
+    position: 0,
+  };
+
+  let expr = parser.parse_additive()?;
+
+  if let Some(token) = parser.peek() {
+    bail!("Unexpected token {:?}.", token);
+  }
+
+  Ok(expr)
+}
+
+fn tokenize(input: &str) -> anyhow::Result<Vec<Token>> {
+  let mut tokens = Vec::new();
+  let mut chars = input.chars().peekable();
+
+  while let Some(&ch) = chars.peek() {
+    let token = match ch {
+      _ if ch.is_whitespace() => {
+        chars.next();
+        continue;
+      }
+      '0'..='9' => {
+        let mut number = String::new();
+
+        while let Some(&ch) =
+          chars.peek().filter(|c| c.is_ascii_digit() || **c == '.')
+        {
+          number.push(ch);
+          chars.next();
+        }
+
+        Token::Number(
+          number
+            .parse()
+            .with_context(|| format!("Invalid number '{}'.", number))?,
+        )
+      }
+      '\'' | '"' => {
+        let quote = ch;
+        let mut string = String::new();
+        chars.next();
+
+        loop {
+          match chars.next() {
+            Some(ch) if ch == quote => break,
+            Some('\\') => match chars.next() {
+              Some(escaped) => string.push(escaped),
+              None => bail!("Unterminated string."),
+            },
+            Some(ch) => string.push(ch),
+            None => bail!("Unterminated string."),
+          }
+        }
+
+        Token::String(string)
+      }
+      _ if ch.is_alphabetic() || ch == '_' => {
+        let mut ident = String::new();
+
+        while let Some(&ch) =
+          chars.peek().filter(|c| c.is_alphanumeric() || **c == '_')
+        {
+          ident.push(ch);
+          chars.next();
+        }
+
+        Token::Ident(ident)
+      }
+      _ => {
+        chars.next();
+
+        match ch {
+          '.' => Token::Dot,
+          ',' => Token::Comma,
+          ':' => Token::Colon,
+          '(' => Token::LeftParen,
+          ')' => Token::RightParen,
+          '[' => Token::LeftBracket,
+          ']' => Token::RightBracket,
+          '+' => Token::Plus,
+          '-' => Token::Minus,
+          '*' => Token::Star,
+          '/' => Token::Slash,
+          '%' => Token::Percent,
+          other => bail!("Unexpected character '{}'.", other),
+        }
+      }
+    };
+
+    tokens.push(token);
+  }
+
+  Ok(tokens)
+}
+
+struct Parser {
+  tokens: Vec<Token>,
+  position: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.position)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.position).cloned();
+    self.position += 1;
+    token
+  }
+
+  fn expect(&mut self, expected: Token) -> anyhow::Result<()> {
+    match self.next() {
+      Some(token) if token == expected => Ok(()),
+      Some(token) => bail!("Expected {:?}, got {:?}.", expected, token),
+      None => bail!("Expected {:?}, got end of expression.", expected),
+    }
+  }
+
+  fn parse_additive(&mut self) -> anyhow::Result<Expr> {
+    let mut expr = self.parse_multiplicative()?;
+
+    loop {
+      let op = match self.peek() {
+        Some(Token::Plus) => BinaryOp::Add,
+        Some(Token::Minus) => BinaryOp::Subtract,
+        _ => return Ok(expr),
+      };
+
+      self.next();
+      let rhs = self.parse_multiplicative()?;
+      expr = Expr::Binary(op, Box::new(expr), Box::new(rhs));
+    }
+  }
+
+  fn parse_multiplicative(&mut self) -> anyhow::Result<Expr> {
+    let mut expr = self.parse_unary()?;
+
+    loop {
+      let op = match self.peek() {
+        Some(Token::Star) => BinaryOp::Multiply,
+        Some(Token::Slash) => BinaryOp::Divide,
+        Some(Token::Percent) => BinaryOp::Remainder,
+        _ => return Ok(expr),
+      };
+
+      self.next();
+      let rhs = self.parse_unary()?;
+      expr = Expr::Binary(op, Box::new(expr), Box::new(rhs));
+    }
+  }
+
+  fn parse_unary(&mut self) -> anyhow::Result<Expr> {
+    match self.peek() {
+      Some(Token::Minus) => {
+        self.next();
+        Ok(Expr::Negate(Box::new(self.parse_unary()?)))
+      }
+      _ => self.parse_primary(),
+    }
+  }
+
+  fn parse_primary(&mut self) -> anyhow::Result<Expr> {
+    match self.next() {
+      Some(Token::Number(number)) => Ok(Expr::Literal(number.into())),
+      Some(Token::String(string)) => Ok(Expr::Literal(string.into())),
+      Some(Token::Dot) => self.parse_path(),
+      Some(Token::LeftParen) => {
+        let expr = self.parse_additive()?;
+        self.expect(Token::RightParen)?;
+        Ok(expr)
+      }
+      Some(Token::Ident(ident)) => match ident.as_str() {
+        "true" => Ok(Expr::Literal(Value::Bool(true))),
+        "false" => Ok(Expr::Literal(Value::Bool(false))),
+        "null" => Ok(Expr::Literal(Value::Null)),
+        _ => self.parse_call(&ident),
+      },
+      Some(token) => bail!("Unexpected token {:?}.", token),
+      None => bail!("Unexpected end of expression."),
+    }
+  }
+
+  /// Parses a path, where the leading `.` has already been consumed.
+  fn parse_path(&mut self) -> anyhow::Result<Expr> {
+    let mut segments = Vec::new();
+
+    if let Some(Token::Ident(field)) = self.peek().cloned() {
+      self.next();
+      segments.push(PathSegment::Field(field));
+    }
+
+    loop {
+      match self.peek() {
+        Some(Token::Dot) => {
+          self.next();
+
+          match self.next() {
+            Some(Token::Ident(field)) => {
+              segments.push(PathSegment::Field(field))
+            }
+            _ => bail!("Expected a field name after '.'."),
+          }
+        }
+        Some(Token::LeftBracket) => {
+          self.next();
+          segments.push(self.parse_bracket()?);
+        }
+        _ => return Ok(Expr::Path(segments)),
+      }
+    }
+  }
+
+  /// Parses an index, slice or wildcard, where the leading `[` has
+  /// already been consumed.
+  fn parse_bracket(&mut self) -> anyhow::Result<PathSegment> {
+    if self.peek() == Some(&Token::Star) {
+      self.next();
+      self.expect(Token::RightBracket)?;
+      return Ok(PathSegment::Wildcard);
+    }
+
+    let start = self.parse_optional_integer()?;
+
+    if self.peek() == Some(&Token::Colon) {
+      self.next();
+      let end = self.parse_optional_integer()?;
+      self.expect(Token::RightBracket)?;
+      return Ok(PathSegment::Slice(start, end));
+    }
+
+    self.expect(Token::RightBracket)?;
+
+    match start {
+      Some(index) => Ok(PathSegment::Index(index)),
+      None => bail!("Expected an index within '[]'."),
+    }
+  }
+
+  fn parse_optional_integer(&mut self) -> anyhow::Result<Option<i64>> {
+    let is_negative = self.peek() == Some(&Token::Minus);
+
+    if is_negative {
+      self.next();
+    }
+
+    match self.peek() {
+      Some(Token::Number(number)) if number.fract() == 0. => {
+        let number = *number as i64;
+        self.next();
+
+        Ok(Some(match is_negative {
+          true => -number,
+          false => number,
+        }))
+      }
+      _ if is_negative => bail!("Expected an integer after '-'."),
+      _ => Ok(None),
+    }
+  }
+
+  fn parse_call(&mut self, name: &str) -> anyhow::Result<Expr> {
+    let function = Function::from_name(name)
+      .with_context(|| format!("Unknown function '{}'.", name))?;
+
+    self.expect(Token::LeftParen)?;
+    let mut args = Vec::new();
+
+    if self.peek() != Some(&Token::RightParen) {
+      loop {
+        args.push(self.parse_additive()?);
+
+        match self.next() {
+          Some(Token::Comma) => continue,
+          Some(Token::RightParen) => break,
+          _ => bail!("Expected ',' or ')' in call to '{}'.", name),
+        }
+      }
+    } else {
+      self.next();
+    }
+
+    let (min_args, max_args) = function.arity();
+
+    if args.len() < min_args || args.len() > max_args {
+      bail!(
+        "Wrong number of arguments in call to '{}' (got {}).",
+        name,
+        args.len()
+      );
+    }
+
+    Ok(Expr::Call(function, args))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn eval(input: &str) -> anyhow::Result<Value> {
+    parse_expression(input)?.evaluate(&Value::Null)
+  }
+
+  #[test]
+  fn tokenizes_paths_and_strings() {
+    assert_eq!(
+      tokenize(".cpu[0] + 'a\\'b' % \"c\"").unwrap(),
+      vec![
+        Token::Dot,
+        Token::Ident("cpu".into()),
+        Token::LeftBracket,
+        Token::Number(0.),
+        Token::RightBracket,
+        Token::Plus,
+        Token::String("a'b".into()),
+        Token::Percent,
+        Token::String("c".into()),
+      ]
+    );
+  }
+
+  #[test]
+  fn tokenize_errors() {
+    assert!(tokenize("'abc").is_err());
+    assert!(tokenize("'abc\\").is_err());
+    assert!(tokenize("1.2.3").is_err());
+    assert!(tokenize("1 # 2").is_err());
+  }
+
+  #[test]
+  fn parses_paths() {
+    assert_eq!(
+      parse_expression(".monitors[0].workspaces[-2:][*].name").unwrap(),
+      Expr::Path(vec![
+        PathSegment::Field("monitors".into()),
+        PathSegment::Index(0),
+        PathSegment::Field("workspaces".into()),
+        PathSegment::Slice(Some(-2), None),
+        PathSegment::Wildcard,
+        PathSegment::Field("name".into()),
+      ])
+    );
+
+    assert_eq!(parse_expression(".").unwrap(), Expr::Path(Vec::new()));
+  }
+
+  #[test]
+  fn multiplication_binds_tighter_than_addition() {
+    assert_eq!(eval("1 + 2 * 3").unwrap(), 7);
+    assert_eq!(eval("(1 + 2) * 3").unwrap(), 9);
+    assert_eq!(eval("7 % 4 + 1").unwrap(), 4);
+    assert_eq!(eval("1 + 8 / 4 - 1").unwrap(), 2);
+  }
+
+  #[test]
+  fn binary_operators_are_left_associative() {
+    assert_eq!(eval("10 - 4 - 3").unwrap(), 3);
+    assert_eq!(eval("16 / 4 / 2").unwrap(), 2);
+  }
+
+  #[test]
+  fn negation_binds_tightest() {
+    assert_eq!(eval("-2 * 3").unwrap(), -6);
+    assert_eq!(eval("--2").unwrap(), 2);
+    assert_eq!(eval("1 - -1").unwrap(), 2);
+  }
+
+  #[test]
+  fn parses_literals_and_calls() {
+    assert_eq!(eval("true").unwrap(), Value::Bool(true));
+    assert_eq!(eval("null").unwrap(), Value::Null);
+    assert_eq!(eval("max(1, 5, 3)").unwrap(), 5);
+    assert_eq!(eval("round(2.5)").unwrap(), 3);
+  }
+
+  #[test]
+  fn parse_errors() {
+    for input in [
+      "",
+      "1 +",
+      "1 2",
+      "(1 + 2",
+      "foo(1)",
+      "round()",
+      "round(1, 2, 3)",
+      "join(.a)",
+      "max(1 2)",
+      ".a.",
+      ".a[]",
+      ".a[1",
+      ".a[-]",
+      ".a[1.5]",
+    ] {
+      assert!(
+        parse_expression(input).is_err(),
+        "Expected '{}' to fail.",
+        input
+      );
+    }
+  }
+}
@@ -0,0 +1,84 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Context;
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use super::{parse_expression, Expr};
+use crate::providers::variables::ProviderVariables;
+
+/// Compiled set of transforms for a single provider config.
+#[derive(Debug, Default)]
+pub struct Transforms {
+  expressions: Vec<(String, Expr)>,
+
+  /// Names of transforms that have failed to evaluate. Used to only
+  /// warn once per transform rather than on every emission.
+  failed: HashSet<String>,
+}
+
+impl Transforms {
+  /// Parses the given map of transform names to expressions.
+  pub fn new(config: &HashMap<String, String>) -> anyhow::Result<Self> {
+    let mut expressions = config
+      .iter()
+      .map(|(name, expression)| {
+        parse_expression(expression)
+          .map(|expr| (name.clone(), expr))
+          .with_context(|| {
+            format!("Invalid transform '{}': {}", name, expression)
+          })
+      })
+      .collect::<anyhow::Result<Vec<_>>>()?;
+
+    expressions.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(Self {
+      expressions,
+      failed: HashSet::new(),
+    })
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.expressions.is_empty()
+  }
+
+  /// Evaluates all transforms against the given provider variables.
+  ///
+  /// Transforms that fail to evaluate are set to `null`.
+  pub fn apply(
+    &mut self,
+    variables: &ProviderVariables,
+  ) -> Option<Map<String, Value>> {
+    if self.is_empty() {
+      return None;
+    }
+
+    let input = match serde_json::to_value(variables) {
+      Ok(input) => input,
+      Err(err) => {
+        warn!("Failed to serialize variables for transforms: {}", err);
+        return None;
+      }
+    };
+
+    let mut computed = Map::new();
+
+    for (name, expr) in &self.expressions {
+      let value = match expr.evaluate(&input) {
+        Ok(value) => value,
+        Err(err) => {
+          if self.failed.insert(name.clone()) {
+            warn!("Failed to evaluate transform '{}': {}", name, err);
+          }
+
+          Value::Null
+        }
+      };
+
+      computed.insert(name.clone(), value);
+    }
+
+    Some(computed)
+  }
+}
@@ -1,23 +1,126 @@
 use serde::Serialize;
 
-#[cfg(windows)]
+#[cfg(feature = "audio_input")]
+use super::audio_input::AudioInputVariables;
+#[cfg(feature = "battery")]
+use super::battery::BatteryVariables;
+#[cfg(feature = "clipboard")]
+use super::clipboard::ClipboardVariables;
+#[cfg(feature = "cpu")]
+use super::cpu::CpuVariables;
+#[cfg(feature = "host")]
+use super::host::HostVariables;
+#[cfg(feature = "ip")]
+use super::ip::IpVariables;
+#[cfg(all(windows, feature = "komorebi"))]
 use super::komorebi::KomorebiVariables;
-use super::{
-  battery::BatteryVariables, cpu::CpuVariables, host::HostVariables,
-  ip::IpVariables, memory::MemoryVariables, network::NetworkVariables,
-  weather::WeatherVariables,
-};
+#[cfg(feature = "kubernetes")]
+use super::kubernetes::KubernetesVariables;
+#[cfg(feature = "memory")]
+use super::memory::MemoryVariables;
+#[cfg(feature = "network")]
+use super::network::NetworkVariables;
+#[cfg(feature = "openrgb")]
+use super::openrgb::OpenRgbVariables;
+#[cfg(feature = "removable_drives")]
+use super::removable_drives::RemovableDrivesVariables;
+use super::state::StateVariables;
+#[cfg(feature = "steam")]
+use super::steam::SteamVariables;
+#[cfg(feature = "summary")]
+use super::summary::SummaryVariables;
+#[cfg(feature = "system_state")]
+use super::system_state::SystemStateVariables;
+#[cfg(feature = "tail")]
+use super::tail::TailVariables;
+#[cfg(feature = "weather")]
+use super::weather::WeatherVariables;
+#[cfg(all(windows, feature = "windows_list"))]
+use super::windows_list::WindowsListVariables;
 
 #[derive(Serialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum ProviderVariables {
+  #[cfg(feature = "audio_input")]
+  AudioInput(AudioInputVariables),
+  #[cfg(feature = "battery")]
   Battery(BatteryVariables),
+  #[cfg(feature = "clipboard")]
+  Clipboard(ClipboardVariables),
+  #[cfg(feature = "cpu")]
   Cpu(CpuVariables),
+  #[cfg(feature = "host")]
   Host(HostVariables),
+  #[cfg(feature = "ip")]
   Ip(IpVariables),
-  #[cfg(windows)]
+  #[cfg(all(windows, feature = "komorebi"))]
   Komorebi(KomorebiVariables),
+  #[cfg(feature = "kubernetes")]
+  Kubernetes(KubernetesVariables),
+  #[cfg(feature = "memory")]
   Memory(MemoryVariables),
+  #[cfg(feature = "network")]
   Network(NetworkVariables),
+  #[cfg(feature = "openrgb")]
+  OpenRgb(OpenRgbVariables),
+  #[cfg(feature = "removable_drives")]
+  RemovableDrives(RemovableDrivesVariables),
+  State(StateVariables),
+  #[cfg(feature = "steam")]
+  Steam(SteamVariables),
+  #[cfg(feature = "summary")]
+  Summary(SummaryVariables),
+  #[cfg(feature = "system_state")]
+  SystemState(SystemStateVariables),
+  #[cfg(feature = "tail")]
+  Tail(TailVariables),
+  #[cfg(feature = "weather")]
   Weather(WeatherVariables),
+  #[cfg(all(windows, feature = "windows_list"))]
+  WindowsList(WindowsListVariables),
+}
+
+impl ProviderVariables {
+  /// Type of the provider that the variables are from (e.g. `cpu`).
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      #[cfg(feature = "audio_input")]
+      ProviderVariables::AudioInput(_) => "audio_input",
+      #[cfg(feature = "battery")]
+      ProviderVariables::Battery(_) => "battery",
+      #[cfg(feature = "clipboard")]
+      ProviderVariables::Clipboard(_) => "clipboard",
+      #[cfg(feature = "cpu")]
+      ProviderVariables::Cpu(_) => "cpu",
+      #[cfg(feature = "host")]
+      ProviderVariables::Host(_) => "host",
+      #[cfg(feature = "ip")]
+      ProviderVariables::Ip(_) => "ip",
+      #[cfg(all(windows, feature = "komorebi"))]
+      ProviderVariables::Komorebi(_) => "komorebi",
+      #[cfg(feature = "kubernetes")]
+      ProviderVariables::Kubernetes(_) => "kubernetes",
+      #[cfg(feature = "memory")]
+      ProviderVariables::Memory(_) => "memory",
+      #[cfg(feature = "network")]
+      ProviderVariables::Network(_) => "network",
+      #[cfg(feature = "openrgb")]
+      ProviderVariables::OpenRgb(_) => "openrgb",
+      #[cfg(feature = "removable_drives")]
+      ProviderVariables::RemovableDrives(_) => "removable_drives",
+      ProviderVariables::State(_) => "state",
+      #[cfg(feature = "steam")]
+      ProviderVariables::Steam(_) => "steam",
+      #[cfg(feature = "summary")]
+      ProviderVariables::Summary(_) => "summary",
+      #[cfg(feature = "system_state")]
+      ProviderVariables::SystemState(_) => "system_state",
+      #[cfg(feature = "tail")]
+      ProviderVariables::Tail(_) => "tail",
+      #[cfg(feature = "weather")]
+      ProviderVariables::Weather(_) => "weather",
+      #[cfg(all(windows, feature = "windows_list"))]
+      ProviderVariables::WindowsList(_) => "windows_list",
+    }
+  }
 }
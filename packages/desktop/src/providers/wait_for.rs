@@ -0,0 +1,191 @@
+use std::{str::FromStr, time::Duration};
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use sysinfo::{ProcessRefreshKind, System};
+use tokio::{net, task, time};
+use tracing::{info, warn};
+
+use super::{connection_cost::internet_access, http_client::HttpClient};
+
+/// Default max time in milliseconds to wait for a dependency before
+/// running the provider anyway.
+pub const DEFAULT_WAIT_FOR_TIMEOUT: u64 = 60 * 1000;
+
+/// Interval between checks for network readiness.
+const NETWORK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Interval between checks for a running process. Kept low, since each
+/// check enumerates all processes.
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Host that's resolved to check DNS for providers that don't make
+/// requests of their own.
+const FALLBACK_DNS_HOST: &str = "example.com";
+
+/// Dependency that a provider's first run waits for, e.g. at login when
+/// the app starts before the network is up or before a window manager.
+///
+/// Either `network` or `process:<name>` (e.g. `process:komorebi.exe`).
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String")]
+pub enum WaitFor {
+  /// The OS reports internet access (where it's reported), and DNS
+  /// lookups succeed.
+  Network,
+
+  /// A process with the given name is running. Matched case-insensitive
+  /// and with or without the `.exe` extension.
+  Process(String),
+}
+
+impl FromStr for WaitFor {
+  type Err = anyhow::Error;
+
+  fn from_str(wait_for: &str) -> anyhow::Result<Self> {
+    let wait_for = wait_for.trim();
+
+    if wait_for.eq_ignore_ascii_case("network") {
+      return Ok(Self::Network);
+    }
+
+    match wait_for.split_once(':') {
+      Some((kind, name)) if kind.eq_ignore_ascii_case("process") => {
+        let name = name.trim();
+
+        if name.is_empty() {
+          bail!("Process name is empty.");
+        }
+
+        Ok(Self::Process(name.to_string()))
+      }
+      _ => bail!("Expected `network` or `process:<name>`."),
+    }
+  }
+}
+
+impl TryFrom<String> for WaitFor {
+  type Error = anyhow::Error;
+
+  fn try_from(wait_for: String) -> anyhow::Result<Self> {
+    wait_for
+      .parse()
+      .with_context(|| format!("Invalid `wait_for` '{}'.", wait_for))
+  }
+}
+
+impl WaitFor {
+  /// Waits until the dependency is satisfied, or until the timeout
+  /// elapses.
+  ///
+  /// `dns_host` is a host that the provider makes requests to, which is
+  /// resolved to check the network.
+  pub async fn wait(
+    &self,
+    config_hash: &str,
+    timeout: Duration,
+    dns_host: Option<&str>,
+    http_client: &HttpClient,
+  ) {
+    // Requests fail right away in offline mode, so there's nothing to
+    // wait for.
+    if *self == Self::Network && http_client.is_offline() {
+      return;
+    }
+
+    let res = time::timeout(timeout, self.poll(dns_host)).await;
+
+    match res {
+      Ok(()) => info!(
+        "Dependency {:?} of provider {} is ready.",
+        self, config_hash
+      ),
+      Err(_) => warn!(
+        "Dependency {:?} of provider {} wasn't ready after {}ms. Starting \
+        anyway.",
+        self,
+        config_hash,
+        timeout.as_millis()
+      ),
+    }
+  }
+
+  /// Checks the dependency until it's satisfied.
+  async fn poll(&self, dns_host: Option<&str>) {
+    match self {
+      Self::Network => {
+        while !is_network_ready(dns_host).await {
+          time::sleep(NETWORK_POLL_INTERVAL).await;
+        }
+      }
+      Self::Process(name) => {
+        let mut system = System::new();
+
+        loop {
+          let (is_running, returned_system) =
+            is_process_running(name.clone(), system).await;
+
+          if is_running {
+            break;
+          }
+
+          system = returned_system;
+          time::sleep(PROCESS_POLL_INTERVAL).await;
+        }
+      }
+    }
+  }
+}
+
+/// Whether the network is ready for requests, i.e. the OS reports
+/// internet access and the host resolves.
+async fn is_network_ready(dns_host: Option<&str>) -> bool {
+  let has_internet_access =
+    task::spawn_blocking(internet_access).await.ok().flatten();
+
+  if has_internet_access == Some(false) {
+    return false;
+  }
+
+  let host = dns_host.unwrap_or(FALLBACK_DNS_HOST);
+
+  net::lookup_host((host, 443))
+    .await
+    .is_ok_and(|mut addrs| addrs.next().is_some())
+}
+
+/// Whether a process with the given name is running. Only names are
+/// refreshed, which avoids opening handles to each process.
+///
+/// Takes and returns the `System`, since enumerating processes blocks.
+async fn is_process_running(
+  name: String,
+  system: System,
+) -> (bool, System) {
+  task::spawn_blocking(move || {
+    let mut system = system;
+    system.refresh_processes_specifics(ProcessRefreshKind::new());
+
+    let is_running = system
+      .processes()
+      .values()
+      .any(|process| is_process_name(process.name(), &name));
+
+    (is_running, system)
+  })
+  .await
+  .unwrap_or_else(|_| (false, System::new()))
+}
+
+fn is_process_name(process_name: &str, name: &str) -> bool {
+  let strip_exe = |name: &str| {
+    let lowercase = name.to_ascii_lowercase();
+
+    match lowercase.strip_suffix(".exe") {
+      Some(stripped) => stripped.to_string(),
+      None => lowercase,
+    }
+  };
+
+  strip_exe(process_name) == strip_exe(name)
+}
@@ -1,13 +1,55 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-use crate::impl_interval_config;
-
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(tag = "type", rename = "weather")]
 pub struct WeatherProviderConfig {
   pub refresh_interval: u64,
-  pub latitude: f32,
-  pub longitude: f32,
+  pub latitude: Option<f32>,
+  pub longitude: Option<f32>,
+
+  /// Place name to resolve to coordinates (e.g. `Austin, TX`). Only used
+  /// if coordinates aren't given.
+  pub location: Option<String>,
+
+  /// Additional named locations to fetch weather for. Their conditions
+  /// are emitted under `locations`, keyed by name.
+  #[serde(default)]
+  pub locations: Vec<WeatherLocationConfig>,
+
+  /// How often to refresh the precipitation nowcast in milliseconds.
+  /// The nowcast is only fetched for the top-level location.
+  #[serde(default = "default_nowcast_refresh_interval")]
+  pub nowcast_refresh_interval: u64,
+
+  /// Units for pressure and visibility.
+  #[serde(default)]
+  pub units: WeatherUnits,
 }
 
-impl_interval_config!(WeatherProviderConfig);
+fn default_nowcast_refresh_interval() -> u64 {
+  10 * 60 * 1000
+}
+
+#[derive(
+  Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherUnits {
+  /// Pressure in hPa and visibility in km.
+  #[default]
+  Metric,
+  /// Pressure in inHg and visibility in miles.
+  Imperial,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct WeatherLocationConfig {
+  pub name: String,
+  pub latitude: Option<f32>,
+  pub longitude: Option<f32>,
+
+  /// Place name to resolve to coordinates. Only used if coordinates
+  /// aren't given.
+  pub location: Option<String>,
+}
@@ -1,8 +1,11 @@
 mod config;
+mod nowcast;
 mod open_meteo_res;
+mod pressure_trend;
 mod provider;
 mod variables;
 
 pub use config::*;
+pub use pressure_trend::PressureTrend;
 pub use provider::*;
 pub use variables::*;
@@ -0,0 +1,89 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::open_meteo_res::{OpenMeteoMinutely15, OpenMeteoNowcastRes};
+use crate::providers::http_client::HttpClient;
+
+/// Precipitation in mm/h from which a minute counts as precipitating.
+const PRECIPITATION_THRESHOLD: f32 = 0.1;
+
+/// Number of minutes covered by the nowcast.
+const NOWCAST_MINUTES: u64 = 60;
+
+/// Minute-by-minute precipitation for the next hour.
+#[derive(Debug, Clone)]
+pub struct PrecipitationNowcast {
+  /// Precipitation in mm/h for each minute, starting from the current
+  /// minute.
+  pub minutes: Vec<f32>,
+  pub starting_in_minutes: Option<u32>,
+  pub stopping_in_minutes: Option<u32>,
+}
+
+impl PrecipitationNowcast {
+  /// Fetches the nowcast at the given coordinates. Returns `None` if
+  /// there's no nowcast coverage at the location.
+  pub async fn fetch(
+    http_client: &HttpClient,
+    latitude: f32,
+    longitude: f32,
+  ) -> anyhow::Result<Option<Self>> {
+    // 5 intervals, since the first one is partially in the past.
+    let res = http_client
+      .get("https://api.open-meteo.com/v1/forecast")?
+      .query(&[
+        ("latitude", latitude.to_string().as_str()),
+        ("longitude", &longitude.to_string()),
+        ("minutely_15", "precipitation"),
+        ("forecast_minutely_15", "5"),
+        ("timeformat", "unixtime"),
+      ])
+      .send()
+      .await?
+      .json::<OpenMeteoNowcastRes>()
+      .await?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    Ok(Self::from_intervals(&res.minutely_15, now))
+  }
+
+  /// Expands 15-minute intervals into per-minute rates, starting from
+  /// `now` (a Unix timestamp in seconds).
+  fn from_intervals(
+    intervals: &OpenMeteoMinutely15,
+    now: u64,
+  ) -> Option<Self> {
+    let minutes = (0..NOWCAST_MINUTES)
+      .map(|minute| {
+        let time = now + minute * 60;
+
+        // Each value covers the 15 minutes preceding its time, so the
+        // minute belongs to the first interval that ends after it.
+        let index = intervals.time.iter().position(|end| *end > time)?;
+
+        // Convert from mm per 15 minutes to mm/h.
+        intervals
+          .precipitation
+          .get(index)
+          .copied()
+          .flatten()
+          .map(|precipitation| precipitation * 4.)
+      })
+      .collect::<Option<Vec<_>>>()?;
+
+    let is_wet = |rate: &f32| *rate >= PRECIPITATION_THRESHOLD;
+    let is_precipitating = minutes.first().is_some_and(is_wet);
+
+    let (starting_in_minutes, stopping_in_minutes) = match is_precipitating
+    {
+      true => (None, minutes.iter().position(|rate| !is_wet(rate))),
+      false => (minutes.iter().position(is_wet), None),
+    };
+
+    Some(Self {
+      starting_in_minutes: starting_in_minutes.map(|index| index as u32),
+      stopping_in_minutes: stopping_in_minutes.map(|index| index as u32),
+      minutes,
+    })
+  }
+}
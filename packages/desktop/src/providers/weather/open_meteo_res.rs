@@ -3,6 +3,25 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug)]
 pub struct OpenMeteoRes {
   pub current_weather: OpenMeteoWeather,
+
+  /// Additional current variables. Each is `null` if there's no data for
+  /// the location.
+  #[serde(default)]
+  pub current: OpenMeteoCurrent,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct OpenMeteoCurrent {
+  pub apparent_temperature: Option<f32>,
+  #[serde(rename = "relative_humidity_2m")]
+  pub relative_humidity: Option<f32>,
+  #[serde(rename = "dew_point_2m")]
+  pub dew_point: Option<f32>,
+  pub uv_index: Option<f32>,
+  /// Visibility in meters.
+  pub visibility: Option<f32>,
+  /// Surface pressure in hPa.
+  pub surface_pressure: Option<f32>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -16,3 +35,18 @@ pub struct OpenMeteoWeather {
   pub weather_code: u32,
   pub is_day: u32,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct OpenMeteoNowcastRes {
+  pub minutely_15: OpenMeteoMinutely15,
+}
+
+/// Values of 15-minute intervals. Each precipitation value is the sum in
+/// mm over the 15 minutes preceding its time, and is `null` if there's
+/// no data for the location.
+#[derive(Deserialize, Debug)]
+pub struct OpenMeteoMinutely15 {
+  /// Unix timestamps in seconds.
+  pub time: Vec<u64>,
+  pub precipitation: Vec<Option<f32>>,
+}
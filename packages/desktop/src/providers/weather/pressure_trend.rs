@@ -0,0 +1,114 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  mem,
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::providers::retained_size::RetainedSize;
+
+/// Period over which the trend is computed. Matches the 3-hour pressure
+/// tendency used in weather reports.
+const TREND_WINDOW: Duration = Duration::from_secs(3 * 60 * 60);
+
+/// Minimum time between the oldest and latest sample for a trend to be
+/// computed. Open-Meteo updates current values every 15 minutes.
+const MIN_TREND_SPAN: Duration = Duration::from_secs(30 * 60);
+
+/// Minimum time between retained samples, so that frequent refreshes
+/// don't crowd out older samples.
+const MIN_SAMPLE_SPACING: Duration = Duration::from_secs(5 * 60);
+
+/// Change in hPa per `TREND_WINDOW` within which pressure is considered
+/// steady.
+const STEADY_THRESHOLD_HPA: f32 = 1.;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureTrend {
+  Rising,
+  Steady,
+  Falling,
+}
+
+/// Recent surface pressure samples in hPa, keyed by location. Retained
+/// across refreshes to compute the pressure trend.
+#[derive(Debug, Default)]
+pub struct PressureHistory(Mutex<HashMap<String, VecDeque<Sample>>>);
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+  taken_at: Instant,
+  pressure_hpa: f32,
+}
+
+impl PressureHistory {
+  /// Records a sample for the location, and returns the trend based on
+  /// the samples within the trend window.
+  ///
+  /// `None` until the samples span at least `MIN_TREND_SPAN`.
+  pub fn record(
+    &self,
+    location_key: &str,
+    pressure_hpa: f32,
+  ) -> Option<PressureTrend> {
+    let now = Instant::now();
+    let mut history = self.0.lock().unwrap();
+    let samples = history.entry(location_key.to_string()).or_default();
+
+    while samples
+      .front()
+      .is_some_and(|sample| now - sample.taken_at > TREND_WINDOW)
+    {
+      samples.pop_front();
+    }
+
+    let is_spaced = samples
+      .back()
+      .map_or(true, |sample| now - sample.taken_at >= MIN_SAMPLE_SPACING);
+
+    if is_spaced {
+      samples.push_back(Sample {
+        taken_at: now,
+        pressure_hpa,
+      });
+    }
+
+    let oldest = samples.front()?;
+    let span = now - oldest.taken_at;
+
+    if span < MIN_TREND_SPAN {
+      return None;
+    }
+
+    // Scale the change to the trend window, so that the threshold
+    // applies regardless of how long the samples span.
+    let change = (pressure_hpa - oldest.pressure_hpa)
+      * (TREND_WINDOW.as_secs_f32() / span.as_secs_f32());
+
+    Some(match change {
+      change if change > STEADY_THRESHOLD_HPA => PressureTrend::Rising,
+      change if change < -STEADY_THRESHOLD_HPA => PressureTrend::Falling,
+      _ => PressureTrend::Steady,
+    })
+  }
+}
+
+/// Samples are bounded by `TREND_WINDOW`, so they aren't evicted.
+#[async_trait]
+impl RetainedSize for PressureHistory {
+  async fn retained_size(&self) -> usize {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .iter()
+      .map(|(key, samples)| {
+        key.len() + samples.capacity() * mem::size_of::<Sample>()
+      })
+      .sum()
+  }
+}
@@ -1,30 +1,370 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
+use anyhow::bail;
 use async_trait::async_trait;
-use reqwest::Client;
-use tokio::task::AbortHandle;
+use tokio::{
+  sync::{mpsc::Sender, Mutex, Semaphore},
+  task::{self, AbortHandle, JoinSet},
+  time,
+};
+use tracing::warn;
 
 use super::{
-  open_meteo_res::OpenMeteoRes, WeatherProviderConfig, WeatherStatus,
+  nowcast::PrecipitationNowcast, open_meteo_res::OpenMeteoRes,
+  pressure_trend::PressureHistory, LocationWeatherResult,
+  WeatherConditions, WeatherProviderConfig, WeatherStatus, WeatherUnits,
   WeatherVariables,
 };
 use crate::providers::{
-  provider::IntervalProvider, variables::ProviderVariables,
+  geocoding::{GeocodedLocation, GeocodingCache},
+  http_client::{HttpClient, MeteredTicks},
+  provider::Provider,
+  provider_ref::{ProviderOutput, VariablesResult},
+  retained_size::RetainedState,
+  variables::ProviderVariables,
 };
 
+/// Limits concurrent requests to the weather API across all weather
+/// providers.
+static REQUEST_LIMIT: Semaphore = Semaphore::const_new(3);
+
+/// Key of the top-level location in the pressure history. Named
+/// locations are keyed by their name.
+const CURRENT_PRESSURE_KEY: &str = "";
+
+const INHG_PER_HPA: f32 = 0.02953;
+
+const MILES_PER_METER: f32 = 0.000621371;
+
 pub struct WeatherProvider {
   pub config: Arc<WeatherProviderConfig>,
   abort_handle: Option<AbortHandle>,
-  http_client: Arc<Client>,
+  state: Arc<WeatherState>,
+}
+
+pub struct WeatherState {
+  http_client: HttpClient,
+  geocoding_cache: Arc<Mutex<GeocodingCache>>,
+  pressure_history: Arc<PressureHistory>,
 }
 
 impl WeatherProvider {
-  pub fn new(config: WeatherProviderConfig) -> WeatherProvider {
+  pub fn new(
+    config: WeatherProviderConfig,
+    geocoding_cache: Arc<Mutex<GeocodingCache>>,
+    http_client: HttpClient,
+  ) -> WeatherProvider {
     WeatherProvider {
       config: Arc::new(config),
       abort_handle: None,
-      http_client: Arc::new(Client::new()),
+      state: Arc::new(WeatherState {
+        http_client,
+        geocoding_cache,
+        pressure_history: Arc::default(),
+      }),
+    }
+  }
+
+  /// Fetches current conditions at the given coordinates.
+  ///
+  /// The surface pressure is recorded to the pressure history under the
+  /// given key to get its trend.
+  async fn fetch_conditions(
+    http_client: &HttpClient,
+    pressure_history: &PressureHistory,
+    pressure_key: &str,
+    units: WeatherUnits,
+    latitude: f32,
+    longitude: f32,
+  ) -> anyhow::Result<WeatherConditions> {
+    let _permit = REQUEST_LIMIT.acquire().await?;
+
+    let res = http_client
+      .get("https://api.open-meteo.com/v1/forecast")?
+      .query(&[
+        ("temperature_unit", "celsius"),
+        ("latitude", &latitude.to_string()),
+        ("longitude", &longitude.to_string()),
+        ("current_weather", "true"),
+        (
+          "current",
+          "apparent_temperature,relative_humidity_2m,dew_point_2m,\
+           uv_index,visibility,surface_pressure",
+        ),
+        ("daily", "sunset,sunrise"),
+        ("timezone", "auto"),
+      ])
+      .send()
+      .await?
+      .json::<OpenMeteoRes>()
+      .await?;
+
+    let current_weather = res.current_weather;
+    let current = res.current;
+    let is_daytime = current_weather.is_day == 1;
+
+    let pressure_trend = current.surface_pressure.and_then(|pressure| {
+      pressure_history.record(pressure_key, pressure)
+    });
+
+    Ok(WeatherConditions {
+      is_daytime,
+      status: Self::get_weather_status(
+        current_weather.weather_code,
+        is_daytime,
+      ),
+      celsius_temp: current_weather.temperature,
+      fahrenheit_temp: Self::celsius_to_fahrenheit(
+        current_weather.temperature,
+      ),
+      wind_speed: current_weather.wind_speed,
+      celsius_feels_like_temp: current.apparent_temperature,
+      fahrenheit_feels_like_temp: current
+        .apparent_temperature
+        .map(Self::celsius_to_fahrenheit),
+      humidity: current.relative_humidity,
+      celsius_dew_point: current.dew_point,
+      fahrenheit_dew_point: current
+        .dew_point
+        .map(Self::celsius_to_fahrenheit),
+      uv_index: current.uv_index,
+      visibility: current.visibility.map(|meters| match units {
+        WeatherUnits::Metric => meters / 1000.,
+        WeatherUnits::Imperial => meters * MILES_PER_METER,
+      }),
+      pressure: current.surface_pressure.map(|hpa| match units {
+        WeatherUnits::Metric => hpa,
+        WeatherUnits::Imperial => hpa * INHG_PER_HPA,
+      }),
+      pressure_trend,
+      resolved_location: None,
+    })
+  }
+
+  /// Gets coordinates from either the given coordinates or a place name
+  /// (e.g. `Austin, TX`). Coordinates take precedence.
+  ///
+  /// Returns the resolved location if a place name was used.
+  async fn resolve_coordinates(
+    http_client: &HttpClient,
+    geocoding_cache: &Mutex<GeocodingCache>,
+    latitude: Option<f32>,
+    longitude: Option<f32>,
+    location: Option<&str>,
+  ) -> anyhow::Result<(f32, f32, Option<GeocodedLocation>)> {
+    if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+      return Ok((latitude, longitude, None));
     }
+
+    let Some(location) = location else {
+      bail!(
+        "Either `latitude` and `longitude` or `location` is required."
+      );
+    };
+
+    let resolved_location = geocoding_cache
+      .lock()
+      .await
+      .resolve(location, http_client)
+      .await?;
+
+    Ok((
+      resolved_location.latitude,
+      resolved_location.longitude,
+      Some(resolved_location),
+    ))
+  }
+
+  /// Fetches current conditions at either the given coordinates or a
+  /// place name (e.g. `Austin, TX`). Coordinates take precedence.
+  async fn fetch_location(
+    http_client: &HttpClient,
+    geocoding_cache: &Mutex<GeocodingCache>,
+    pressure_history: &PressureHistory,
+    pressure_key: &str,
+    units: WeatherUnits,
+    latitude: Option<f32>,
+    longitude: Option<f32>,
+    location: Option<&str>,
+  ) -> anyhow::Result<WeatherConditions> {
+    let (latitude, longitude, resolved_location) =
+      Self::resolve_coordinates(
+        http_client,
+        geocoding_cache,
+        latitude,
+        longitude,
+        location,
+      )
+      .await?;
+
+    let conditions = Self::fetch_conditions(
+      http_client,
+      pressure_history,
+      pressure_key,
+      units,
+      latitude,
+      longitude,
+    )
+    .await?;
+
+    Ok(WeatherConditions {
+      resolved_location,
+      ..conditions
+    })
+  }
+
+  /// Fetches the precipitation nowcast for the top-level location.
+  ///
+  /// Returns `None` if there's no top-level location, no nowcast
+  /// coverage, or the request fails. Failures don't affect the current
+  /// conditions.
+  async fn fetch_nowcast(
+    config: &WeatherProviderConfig,
+    state: &WeatherState,
+  ) -> Option<PrecipitationNowcast> {
+    if !Self::has_current(config) || state.http_client.is_offline() {
+      return None;
+    }
+
+    let res = state
+      .http_client
+      .lookup(async {
+        let (latitude, longitude, _) = Self::resolve_coordinates(
+          &state.http_client,
+          &state.geocoding_cache,
+          config.latitude,
+          config.longitude,
+          config.location.as_deref(),
+        )
+        .await?;
+
+        let _permit = REQUEST_LIMIT.acquire().await?;
+
+        PrecipitationNowcast::fetch(
+          &state.http_client,
+          latitude,
+          longitude,
+        )
+        .await
+      })
+      .await;
+
+    res.unwrap_or_else(|err| {
+      warn!("Failed to fetch precipitation nowcast: {:?}", err);
+      None
+    })
+  }
+
+  /// Whether a top-level location is configured.
+  fn has_current(config: &WeatherProviderConfig) -> bool {
+    config.latitude.is_some()
+      || config.longitude.is_some()
+      || config.location.is_some()
+  }
+
+  /// Combines the current conditions with the latest nowcast.
+  fn to_output(
+    config_hash: &str,
+    variables: &VariablesResult,
+    nowcast: &Option<PrecipitationNowcast>,
+  ) -> ProviderOutput {
+    let mut variables = variables.clone();
+
+    if let VariablesResult::Data(ProviderVariables::Weather(variables)) =
+      &mut variables
+    {
+      variables.precipitation_nowcast =
+        nowcast.as_ref().map(|nowcast| nowcast.minutes.clone());
+      variables.precipitation_starting_in_minutes = nowcast
+        .as_ref()
+        .and_then(|nowcast| nowcast.starting_in_minutes);
+      variables.precipitation_stopping_in_minutes = nowcast
+        .as_ref()
+        .and_then(|nowcast| nowcast.stopping_in_minutes);
+    }
+
+    ProviderOutput {
+      config_hash: config_hash.to_string(),
+      variables,
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+
+  /// Fetches current conditions at the top-level and named locations.
+  async fn get_variables(
+    config: &WeatherProviderConfig,
+    state: &WeatherState,
+  ) -> anyhow::Result<ProviderVariables> {
+    let has_current = Self::has_current(config);
+
+    if !has_current && config.locations.is_empty() {
+      bail!("Weather provider requires a location or coordinates.");
+    }
+
+    // Fail the whole provider rather than each location individually.
+    state.http_client.ensure_online()?;
+
+    // Named locations are fetched concurrently.
+    let mut location_tasks = JoinSet::new();
+
+    for location in config.locations.clone() {
+      let http_client = state.http_client.clone();
+      let geocoding_cache = state.geocoding_cache.clone();
+      let pressure_history = state.pressure_history.clone();
+      let units = config.units;
+
+      location_tasks.spawn(async move {
+        let result = Self::fetch_location(
+          &http_client,
+          &geocoding_cache,
+          &pressure_history,
+          &location.name,
+          units,
+          location.latitude,
+          location.longitude,
+          location.location.as_deref(),
+        )
+        .await;
+
+        (location.name, LocationWeatherResult::from(result))
+      });
+    }
+
+    let current = match has_current {
+      true => Some(
+        Self::fetch_location(
+          &state.http_client,
+          &state.geocoding_cache,
+          &state.pressure_history,
+          CURRENT_PRESSURE_KEY,
+          config.units,
+          config.latitude,
+          config.longitude,
+          config.location.as_deref(),
+        )
+        .await?,
+      ),
+      false => None,
+    };
+
+    let mut locations = HashMap::new();
+
+    while let Some(task_result) = location_tasks.join_next().await {
+      let (name, result) = task_result?;
+      locations.insert(name, result);
+    }
+
+    Ok(ProviderVariables::Weather(WeatherVariables {
+      current,
+      locations,
+      precipitation_nowcast: None,
+      precipitation_starting_in_minutes: None,
+      precipitation_stopping_in_minutes: None,
+    }))
   }
 
   fn celsius_to_fahrenheit(celsius_temp: f32) -> f32 {
@@ -71,59 +411,112 @@ impl WeatherProvider {
 }
 
 #[async_trait]
-impl IntervalProvider for WeatherProvider {
-  type Config = WeatherProviderConfig;
-  type State = Client;
-
-  fn config(&self) -> Arc<WeatherProviderConfig> {
-    self.config.clone()
+impl Provider for WeatherProvider {
+  fn min_refresh_interval(&self) -> Option<Duration> {
+    Some(Duration::from_secs(2))
   }
 
-  fn state(&self) -> Arc<Client> {
-    self.http_client.clone()
+  /// The geocoding cache is shared between weather providers, so it's
+  /// included in the retained size of each.
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(vec![
+      self.state.geocoding_cache.clone(),
+      self.state.pressure_history.clone(),
+    ])
   }
 
-  fn abort_handle(&self) -> &Option<AbortHandle> {
-    &self.abort_handle
-  }
+  /// Conditions and the nowcast are refreshed on separate intervals.
+  /// Either refresh emits the latest of both.
+  async fn on_start(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let config = self.config.clone();
+    let state = self.state.clone();
+    let config_hash = config_hash.to_string();
+
+    let task_handle = task::spawn(async move {
+      let mut conditions_interval =
+        time::interval(Duration::from_millis(config.refresh_interval));
+
+      let mut nowcast_interval = time::interval(Duration::from_millis(
+        config.nowcast_refresh_interval,
+      ));
+
+      // The first ticks fire immediately.
+      conditions_interval.tick().await;
+      nowcast_interval.tick().await;
+
+      let (variables, mut nowcast) = tokio::join!(
+        state
+          .http_client
+          .lookup(Self::get_variables(&config, &state)),
+        Self::fetch_nowcast(&config, &state),
+      );
+
+      let mut variables = VariablesResult::from(variables);
+
+      // Refreshes are throttled or paused while the connection is
+      // metered. The initial fetch counts as the first run of each.
+      let mut conditions_ticks = MeteredTicks::default();
+      let mut nowcast_ticks = MeteredTicks::default();
+      conditions_ticks.should_run(&state.http_client);
+      nowcast_ticks.should_run(&state.http_client);
+
+      loop {
+        _ = emit_output_tx
+          .send(Self::to_output(&config_hash, &variables, &nowcast))
+          .await;
 
-  fn set_abort_handle(&mut self, abort_handle: AbortHandle) {
-    self.abort_handle = Some(abort_handle)
+        loop {
+          tokio::select! {
+            _ = conditions_interval.tick() => {
+              if conditions_ticks.should_run(&state.http_client) {
+                variables = state
+                  .http_client
+                  .lookup(Self::get_variables(&config, &state))
+                  .await
+                  .into();
+                break;
+              }
+            }
+            _ = nowcast_interval.tick() => {
+              if nowcast_ticks.should_run(&state.http_client) {
+                nowcast = Self::fetch_nowcast(&config, &state).await;
+                break;
+              }
+            }
+          }
+        }
+      }
+    });
+
+    self.abort_handle = Some(task_handle.abort_handle());
+    _ = task_handle.await;
   }
 
-  async fn get_refreshed_variables(
-    config: &WeatherProviderConfig,
-    http_client: &Client,
-  ) -> anyhow::Result<ProviderVariables> {
-    let res = http_client
-      .get("https://api.open-meteo.com/v1/forecast")
-      .query(&[
-        ("temperature_unit", "celsius"),
-        ("latitude", &config.latitude.to_string()),
-        ("longitude", &config.longitude.to_string()),
-        ("current_weather", "true"),
-        ("daily", "sunset,sunrise"),
-        ("timezone", "auto"),
-      ])
-      .send()
-      .await?
-      .json::<OpenMeteoRes>()
-      .await?;
+  async fn on_refresh(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let (variables, nowcast) = tokio::join!(
+      self
+        .state
+        .http_client
+        .lookup(Self::get_variables(&self.config, &self.state)),
+      Self::fetch_nowcast(&self.config, &self.state),
+    );
 
-    let current_weather = res.current_weather;
-    let is_daytime = current_weather.is_day == 1;
+    _ = emit_output_tx
+      .send(Self::to_output(config_hash, &variables.into(), &nowcast))
+      .await;
+  }
 
-    Ok(ProviderVariables::Weather(WeatherVariables {
-      is_daytime,
-      status: Self::get_weather_status(
-        current_weather.weather_code,
-        is_daytime,
-      ),
-      celsius_temp: current_weather.temperature,
-      fahrenheit_temp: Self::celsius_to_fahrenheit(
-        current_weather.temperature,
-      ),
-      wind_speed: current_weather.wind_speed,
-    }))
+  async fn on_stop(&mut self) {
+    if let Some(handle) = &self.abort_handle {
+      handle.abort();
+    }
   }
 }
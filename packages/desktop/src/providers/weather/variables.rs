@@ -1,13 +1,85 @@
+use std::collections::HashMap;
+
 use serde::Serialize;
 
+use super::pressure_trend::PressureTrend;
+use crate::providers::geocoding::GeocodedLocation;
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WeatherVariables {
+  /// Conditions at the top-level coordinates, if any are configured.
+  #[serde(flatten)]
+  pub current: Option<WeatherConditions>,
+
+  pub locations: HashMap<String, LocationWeatherResult>,
+
+  /// Precipitation in mm/h for each minute of the next hour, starting
+  /// from the current minute. `None` if there's no nowcast coverage.
+  pub precipitation_nowcast: Option<Vec<f32>>,
+
+  /// Minutes until precipitation starts, if it's currently dry and
+  /// precipitation is expected within the hour.
+  pub precipitation_starting_in_minutes: Option<u32>,
+
+  /// Minutes until precipitation stops, if it's currently precipitating
+  /// and expected to stop within the hour.
+  pub precipitation_stopping_in_minutes: Option<u32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherConditions {
   pub is_daytime: bool,
   pub status: WeatherStatus,
   pub celsius_temp: f32,
   pub fahrenheit_temp: f32,
   pub wind_speed: f32,
+
+  /// Apparent temperature, accounting for wind chill and humidity.
+  pub celsius_feels_like_temp: Option<f32>,
+  pub fahrenheit_feels_like_temp: Option<f32>,
+
+  /// Relative humidity in percent.
+  pub humidity: Option<f32>,
+
+  pub celsius_dew_point: Option<f32>,
+  pub fahrenheit_dew_point: Option<f32>,
+
+  pub uv_index: Option<f32>,
+
+  /// Visibility in km, or in miles if `units` is `imperial`.
+  pub visibility: Option<f32>,
+
+  /// Surface pressure in hPa, or in inHg if `units` is `imperial`.
+  pub pressure: Option<f32>,
+
+  /// Trend of the surface pressure over the last few hours. `None`
+  /// until enough samples have been retained across refreshes.
+  pub pressure_trend: Option<PressureTrend>,
+
+  /// Location that a place name was resolved to, if the conditions are
+  /// for a place name rather than coordinates.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub resolved_location: Option<GeocodedLocation>,
+}
+
+/// Weather of a named location. A failure for one location doesn't
+/// affect the others.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum LocationWeatherResult {
+  Data(WeatherConditions),
+  Error(String),
+}
+
+impl From<anyhow::Result<WeatherConditions>> for LocationWeatherResult {
+  fn from(result: anyhow::Result<WeatherConditions>) -> Self {
+    match result {
+      Ok(data) => LocationWeatherResult::Data(data),
+      Err(err) => LocationWeatherResult::Error(err.to_string()),
+    }
+  }
 }
 
 #[derive(Serialize, Debug, Clone)]
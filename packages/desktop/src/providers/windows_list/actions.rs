@@ -0,0 +1,24 @@
+use anyhow::{bail, Context};
+use windows::Win32::{
+  Foundation::{HWND, LPARAM, WPARAM},
+  UI::WindowsAndMessaging::{IsWindow, PostMessageW, WM_CLOSE},
+};
+
+fn to_window(hwnd: u64) -> anyhow::Result<HWND> {
+  let window = HWND(hwnd as isize);
+
+  if !unsafe { IsWindow(window) }.as_bool() {
+    bail!("No window with handle {}.", hwnd);
+  }
+
+  Ok(window)
+}
+
+/// Asks the window to close. Apps can still prompt the user (e.g. to
+/// save changes) or ignore the request.
+pub fn close_window(hwnd: u64) -> anyhow::Result<()> {
+  let window = to_window(hwnd)?;
+
+  unsafe { PostMessageW(window, WM_CLOSE, WPARAM(0), LPARAM(0)) }
+    .with_context(|| format!("Failed to close window {}.", hwnd))
+}
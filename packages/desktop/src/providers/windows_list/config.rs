@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type", rename = "windows_list")]
+pub struct WindowsListProviderConfig {
+  /// Whether to include minimized windows.
+  #[serde(default = "default_include_minimized")]
+  pub include_minimized: bool,
+}
+
+fn default_include_minimized() -> bool {
+  true
+}
@@ -0,0 +1,230 @@
+use std::{ffi::c_void, mem, path::Path};
+
+use windows::{
+  core::PWSTR,
+  Win32::{
+    Foundation::{CloseHandle, BOOL, HWND, LPARAM, TRUE},
+    Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED},
+    System::{
+      Com::{CoInitializeEx, COINIT_APARTMENTTHREADED},
+      Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+      },
+    },
+    UI::WindowsAndMessaging::{
+      EnumChildWindows, EnumWindows, GetAncestor, GetForegroundWindow,
+      GetLastActivePopup, GetWindowLongPtrW, GetWindowTextLengthW,
+      GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindowVisible,
+      GA_ROOTOWNER, GWL_EXSTYLE, WINDOW_EX_STYLE, WS_EX_APPWINDOW,
+      WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
+    },
+  },
+};
+
+use super::{icons::icon_url, ListedWindow};
+
+/// Host process of UWP apps, whose windows are owned by the app's
+/// process via a child window.
+const UWP_FRAME_HOST: &str = "ApplicationFrameHost.exe";
+
+/// Enumerates the windows that are shown in the alt-tab switcher, in
+/// z-order. Windows of the current process are excluded.
+///
+/// Icons are extracted once per executable, so this can block on the
+/// first call for a hung window.
+pub fn list_windows(include_minimized: bool) -> Vec<ListedWindow> {
+  // The shell requires COM to be initialized for extracting icons.
+  // Fails harmlessly if the thread was already initialized.
+  _ = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) };
+
+  let foreground_hwnd = unsafe { GetForegroundWindow() };
+  let own_pid = std::process::id();
+
+  top_level_windows()
+    .into_iter()
+    .filter(|hwnd| is_alt_tab_window(*hwnd))
+    .filter_map(|hwnd| {
+      let is_minimized = unsafe { IsIconic(hwnd) }.as_bool();
+
+      if is_minimized && !include_minimized {
+        return None;
+      }
+
+      let pid = app_process_id(hwnd);
+
+      if pid == own_pid {
+        return None;
+      }
+
+      let exe_path = exe_path(pid);
+
+      Some(ListedWindow {
+        hwnd: hwnd.0 as u64,
+        title: window_title(hwnd),
+        exe_name: exe_path.as_deref().and_then(|path| {
+          Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+        }),
+        icon_url: icon_url(exe_path.as_deref(), hwnd),
+        exe_path,
+        is_focused: hwnd == foreground_hwnd,
+        is_minimized,
+      })
+    })
+    .collect()
+}
+
+fn top_level_windows() -> Vec<HWND> {
+  let mut hwnds = Vec::<HWND>::new();
+
+  unsafe extern "system" fn callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let hwnds = &mut *(lparam.0 as *mut Vec<HWND>);
+    hwnds.push(hwnd);
+    TRUE
+  }
+
+  _ = unsafe {
+    EnumWindows(Some(callback), LPARAM(&mut hwnds as *mut _ as isize))
+  };
+
+  hwnds
+}
+
+/// Whether the window is shown in the alt-tab switcher.
+///
+/// Follows the approach from "Which windows appear in the Alt+Tab
+/// list?" on The Old New Thing, with cloaked windows (e.g. on other
+/// virtual desktops or suspended UWP apps) additionally excluded.
+fn is_alt_tab_window(hwnd: HWND) -> bool {
+  unsafe {
+    if !IsWindowVisible(hwnd).as_bool()
+      || is_cloaked(hwnd)
+      || GetWindowTextLengthW(hwnd) == 0
+    {
+      return false;
+    }
+
+    let ex_style =
+      WINDOW_EX_STYLE(GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32);
+
+    if ex_style.contains(WS_EX_APPWINDOW) {
+      return true;
+    }
+
+    if ex_style.contains(WS_EX_TOOLWINDOW)
+      || ex_style.contains(WS_EX_NOACTIVATE)
+    {
+      return false;
+    }
+
+    // Of a group of owned windows, only the last active popup is shown.
+    let mut walk = GetAncestor(hwnd, GA_ROOTOWNER);
+
+    loop {
+      let popup = GetLastActivePopup(walk);
+
+      if popup == walk || IsWindowVisible(popup).as_bool() {
+        break;
+      }
+
+      walk = popup;
+    }
+
+    walk == hwnd
+  }
+}
+
+fn is_cloaked(hwnd: HWND) -> bool {
+  let mut cloaked = 0u32;
+
+  let res = unsafe {
+    DwmGetWindowAttribute(
+      hwnd,
+      DWMWA_CLOAKED,
+      &mut cloaked as *mut _ as *mut c_void,
+      mem::size_of::<u32>() as u32,
+    )
+  };
+
+  res.is_ok() && cloaked != 0
+}
+
+fn window_title(hwnd: HWND) -> String {
+  let mut buffer =
+    vec![0u16; unsafe { GetWindowTextLengthW(hwnd) } as usize + 1];
+  let len = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+
+  String::from_utf16_lossy(&buffer[..len.max(0) as usize])
+}
+
+fn process_id(hwnd: HWND) -> u32 {
+  let mut pid = 0;
+  unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+  pid
+}
+
+/// Gets the process that the window belongs to. For UWP apps, this is
+/// the app's process rather than the frame host.
+fn app_process_id(hwnd: HWND) -> u32 {
+  let pid = process_id(hwnd);
+
+  let is_frame_host = exe_path(pid).is_some_and(|path| {
+    path
+      .to_ascii_lowercase()
+      .ends_with(&UWP_FRAME_HOST.to_ascii_lowercase())
+  });
+
+  if !is_frame_host {
+    return pid;
+  }
+
+  // The app's content is a child window owned by the app's process.
+  let mut child_pids = (pid, None::<u32>);
+
+  unsafe extern "system" fn callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let (host_pid, app_pid) = &mut *(lparam.0 as *mut (u32, Option<u32>));
+    let child_pid = process_id(hwnd);
+
+    if child_pid != *host_pid {
+      *app_pid = Some(child_pid);
+      return BOOL(0);
+    }
+
+    TRUE
+  }
+
+  _ = unsafe {
+    EnumChildWindows(
+      hwnd,
+      Some(callback),
+      LPARAM(&mut child_pids as *mut _ as isize),
+    )
+  };
+
+  child_pids.1.unwrap_or(pid)
+}
+
+fn exe_path(pid: u32) -> Option<String> {
+  let process = unsafe {
+    OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?
+  };
+
+  let mut buffer = [0u16; 1024];
+  let mut len = buffer.len() as u32;
+
+  let res = unsafe {
+    QueryFullProcessImageNameW(
+      process,
+      PROCESS_NAME_WIN32,
+      PWSTR(buffer.as_mut_ptr()),
+      &mut len,
+    )
+  };
+
+  _ = unsafe { CloseHandle(process) };
+  res.ok()?;
+
+  Some(String::from_utf16_lossy(&buffer[..len as usize]))
+}
@@ -0,0 +1,144 @@
+use std::{cell::RefCell, sync::mpsc as std_mpsc, thread};
+
+use anyhow::Context;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::debug;
+use windows::Win32::{
+  Foundation::{HMODULE, HWND, LPARAM, WPARAM},
+  System::Threading::GetCurrentThreadId,
+  UI::{
+    Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK},
+    WindowsAndMessaging::{
+      DispatchMessageW, GetMessageW, PostThreadMessageW, CHILDID_SELF,
+      EVENT_OBJECT_CLOAKED, EVENT_OBJECT_CREATE, EVENT_OBJECT_DESTROY,
+      EVENT_OBJECT_HIDE, EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_SHOW,
+      EVENT_OBJECT_UNCLOAKED, EVENT_SYSTEM_FOREGROUND,
+      EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART, MSG,
+      OBJID_WINDOW, WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS,
+      WM_QUIT,
+    },
+  },
+};
+
+/// Events that can change the list of windows or their state.
+const WATCHED_EVENTS: &[u32] = &[
+  EVENT_SYSTEM_FOREGROUND,
+  EVENT_SYSTEM_MINIMIZESTART,
+  EVENT_SYSTEM_MINIMIZEEND,
+  EVENT_OBJECT_CREATE,
+  EVENT_OBJECT_DESTROY,
+  EVENT_OBJECT_SHOW,
+  EVENT_OBJECT_HIDE,
+  EVENT_OBJECT_NAMECHANGE,
+  EVENT_OBJECT_CLOAKED,
+  EVENT_OBJECT_UNCLOAKED,
+];
+
+thread_local! {
+  /// Sender for the hook thread. Hook callbacks don't receive any
+  /// context, so the sender is stored per thread instead.
+  static EVENT_TX: RefCell<Option<UnboundedSender<()>>> =
+    RefCell::new(None);
+}
+
+/// WinEvent hooks that notify on changes to top-level windows.
+///
+/// Hooks run on a dedicated thread with a message loop, which is stopped
+/// when this is dropped.
+pub struct WindowEventHook {
+  thread_id: u32,
+}
+
+impl WindowEventHook {
+  /// Installs the hooks. A message is sent to `event_tx` for each event.
+  pub fn start(event_tx: UnboundedSender<()>) -> anyhow::Result<Self> {
+    let (thread_id_tx, thread_id_rx) = std_mpsc::channel();
+
+    thread::spawn(move || {
+      EVENT_TX.with(|tx| *tx.borrow_mut() = Some(event_tx));
+
+      // Out-of-context hooks are called on the installing thread while
+      // it's pumping messages. System and object events are far apart,
+      // so two hooks are used instead of one spanning both ranges.
+      let hooks = unsafe {
+        [
+          (EVENT_SYSTEM_FOREGROUND, EVENT_SYSTEM_MINIMIZEEND),
+          (EVENT_OBJECT_CREATE, EVENT_OBJECT_UNCLOAKED),
+        ]
+        .map(|(event_min, event_max)| {
+          SetWinEventHook(
+            event_min,
+            event_max,
+            HMODULE::default(),
+            Some(on_event),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+          )
+        })
+      };
+
+      if hooks.iter().any(|hook| hook.is_invalid()) {
+        _ = thread_id_tx.send(None);
+        return;
+      }
+
+      _ = thread_id_tx.send(Some(unsafe { GetCurrentThreadId() }));
+      debug!("Installed window event hooks.");
+
+      let mut msg = MSG::default();
+
+      while unsafe { GetMessageW(&mut msg, HWND::default(), 0, 0) }
+        .as_bool()
+      {
+        unsafe { DispatchMessageW(&msg) };
+      }
+
+      for hook in hooks {
+        _ = unsafe { UnhookWinEvent(hook) };
+      }
+
+      debug!("Removed window event hooks.");
+    });
+
+    let thread_id = thread_id_rx
+      .recv()
+      .ok()
+      .flatten()
+      .context("Failed to install window event hooks.")?;
+
+    Ok(Self { thread_id })
+  }
+}
+
+impl Drop for WindowEventHook {
+  fn drop(&mut self) {
+    _ = unsafe {
+      PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0))
+    };
+  }
+}
+
+unsafe extern "system" fn on_event(
+  _hook: HWINEVENTHOOK,
+  event: u32,
+  _hwnd: HWND,
+  id_object: i32,
+  id_child: i32,
+  _event_thread: u32,
+  _event_time: u32,
+) {
+  // Ignore events for controls and other non-window objects.
+  if id_object != OBJID_WINDOW.0
+    || id_child != CHILDID_SELF as i32
+    || !WATCHED_EVENTS.contains(&event)
+  {
+    return;
+  }
+
+  EVENT_TX.with(|tx| {
+    if let Some(tx) = tx.borrow().as_ref() {
+      _ = tx.send(());
+    }
+  });
+}
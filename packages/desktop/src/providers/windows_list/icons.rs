@@ -0,0 +1,311 @@
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap},
+  ffi::c_void,
+  hash::{Hash, Hasher},
+  mem,
+  sync::{Arc, Mutex, OnceLock},
+};
+
+use async_trait::async_trait;
+use windows::{
+  core::HSTRING,
+  Win32::{
+    Foundation::{HWND, LPARAM, WPARAM},
+    Graphics::Gdi::{
+      CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW,
+      BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+      HBITMAP, HDC, HGDIOBJ,
+    },
+    Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES,
+    UI::{
+      Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON},
+      WindowsAndMessaging::{
+        DestroyIcon, GetClassLongPtrW, GetIconInfo, SendMessageTimeoutW,
+        GCLP_HICON, HICON, ICONINFO, ICON_BIG, SMTO_ABORTIFHUNG,
+        WM_GETICON,
+      },
+    },
+  },
+};
+
+use crate::{
+  asset_protocol::protocol_url, providers::retained_size::RetainedSize,
+};
+
+/// How long to wait for a window to respond with its icon.
+const WINDOW_ICON_TIMEOUT_MS: u32 = 100;
+
+/// App icons that have been extracted and encoded as PNGs.
+///
+/// Extraction and encoding is comparatively slow, so icons are only
+/// extracted the first time an executable is seen. Icons are only
+/// evicted by the memory watchdog, since there's typically only a few
+/// dozen distinct apps.
+#[derive(Default)]
+struct IconCache {
+  /// Icon IDs keyed by lowercase executable path (or window handle if
+  /// the executable is unknown). `None` if extraction failed, so that
+  /// it isn't retried.
+  ids: HashMap<String, Option<String>>,
+
+  /// PNG-encoded icons keyed by icon ID.
+  pngs: HashMap<String, Arc<Vec<u8>>>,
+}
+
+static ICON_CACHE: OnceLock<Mutex<IconCache>> = OnceLock::new();
+
+fn icon_cache() -> &'static Mutex<IconCache> {
+  ICON_CACHE.get_or_init(Default::default)
+}
+
+/// Retained size of the icon cache, which is shared by all windows list
+/// providers.
+pub struct IconCacheSize;
+
+#[async_trait]
+impl RetainedSize for IconCacheSize {
+  async fn retained_size(&self) -> usize {
+    let cache = icon_cache().lock().unwrap();
+
+    let ids_size = cache
+      .ids
+      .iter()
+      .map(|(key, id)| key.len() + id.as_ref().map_or(0, String::len))
+      .sum::<usize>();
+
+    let pngs_size = cache
+      .pngs
+      .iter()
+      .map(|(id, png)| id.len() + png.len())
+      .sum::<usize>();
+
+    ids_size + pngs_size
+  }
+
+  /// Icons are extracted again on next use. The webview keeps showing
+  /// icons that it has already loaded, since their URLs don't change.
+  async fn evict(&self) {
+    let mut cache = icon_cache().lock().unwrap();
+    cache.ids.clear();
+    cache.pngs.clear();
+  }
+}
+
+/// Gets the PNG-encoded icon with the given ID.
+pub fn icon_png(id: &str) -> Option<Arc<Vec<u8>>> {
+  icon_cache().lock().unwrap().pngs.get(id).cloned()
+}
+
+/// Gets the URL of the app icon for the given executable, extracting it
+/// on first use. The window's own icon is used for executables without
+/// an icon or that can't be inspected.
+pub fn icon_url(exe_path: Option<&str>, hwnd: HWND) -> Option<String> {
+  let key = match exe_path {
+    Some(exe_path) => exe_path.to_lowercase(),
+    None => format!("hwnd:{}", hwnd.0),
+  };
+
+  let cached_id = icon_cache().lock().unwrap().ids.get(&key).cloned();
+
+  let id = match cached_id {
+    Some(id) => id,
+    None => {
+      // Extract without holding the lock, since it can block on hung
+      // windows.
+      let png =
+        extract_icon(exe_path, hwnd).and_then(|image| encode_png(&image));
+
+      let mut cache = icon_cache().lock().unwrap();
+
+      // IDs are derived from the contents, so that the webview can cache
+      // the icon indefinitely.
+      let id = png.map(|png| {
+        let mut hasher = DefaultHasher::new();
+        png.hash(&mut hasher);
+
+        let id = format!("{:016x}", hasher.finish());
+        cache.pngs.insert(id.clone(), Arc::new(png));
+        id
+      });
+
+      cache.ids.insert(key, id.clone());
+      id
+    }
+  }?;
+
+  Some(protocol_url(&format!("icons/{}.png", id)))
+}
+
+/// Icon as 8-bit RGBA pixels.
+struct IconImage {
+  width: u32,
+  height: u32,
+  rgba: Vec<u8>,
+}
+
+fn extract_icon(exe_path: Option<&str>, hwnd: HWND) -> Option<IconImage> {
+  if let Some(icon) = exe_path.and_then(shell_icon) {
+    let image = icon_image(icon);
+    _ = unsafe { DestroyIcon(icon) };
+
+    if image.is_some() {
+      return image;
+    }
+  }
+
+  // Icons from the window are owned by it and mustn't be destroyed.
+  window_icon(hwnd).and_then(icon_image)
+}
+
+/// Gets the large icon of the executable as shown in Explorer.
+fn shell_icon(exe_path: &str) -> Option<HICON> {
+  let mut file_info = SHFILEINFOW::default();
+
+  let res = unsafe {
+    SHGetFileInfoW(
+      &HSTRING::from(exe_path),
+      FILE_FLAGS_AND_ATTRIBUTES(0),
+      Some(&mut file_info),
+      mem::size_of::<SHFILEINFOW>() as u32,
+      SHGFI_ICON | SHGFI_LARGEICON,
+    )
+  };
+
+  (res != 0 && !file_info.hIcon.is_invalid()).then_some(file_info.hIcon)
+}
+
+/// Gets the icon that the window shows in its title bar, falling back
+/// to the icon of its window class.
+fn window_icon(hwnd: HWND) -> Option<HICON> {
+  let mut icon = 0usize;
+
+  unsafe {
+    SendMessageTimeoutW(
+      hwnd,
+      WM_GETICON,
+      WPARAM(ICON_BIG as usize),
+      LPARAM(0),
+      SMTO_ABORTIFHUNG,
+      WINDOW_ICON_TIMEOUT_MS,
+      Some(&mut icon),
+    );
+
+    if icon == 0 {
+      icon = GetClassLongPtrW(hwnd, GCLP_HICON);
+    }
+  }
+
+  (icon != 0).then(|| HICON(icon as isize))
+}
+
+/// Reads the pixels of an icon. Monochrome icons aren't supported.
+fn icon_image(icon: HICON) -> Option<IconImage> {
+  let mut icon_info = ICONINFO::default();
+  unsafe { GetIconInfo(icon, &mut icon_info) }.ok()?;
+
+  let hdc = unsafe { CreateCompatibleDC(HDC::default()) };
+
+  let image = (!icon_info.hbmColor.is_invalid())
+    .then(|| bitmap_pixels(hdc, icon_info.hbmColor))
+    .flatten()
+    .map(|(width, height, mut pixels)| {
+      // Icons without an alpha channel use the mask for transparency,
+      // where set bits are transparent.
+      if pixels.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        let mask = bitmap_pixels(hdc, icon_info.hbmMask);
+
+        for (index, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+          let is_transparent = mask
+            .as_ref()
+            .is_some_and(|(_, _, mask)| mask[index * 4] != 0);
+
+          pixel[3] = if is_transparent { 0 } else { 255 };
+        }
+      }
+
+      // Convert from BGRA to RGBA.
+      for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+      }
+
+      IconImage {
+        width,
+        height,
+        rgba: pixels,
+      }
+    });
+
+  unsafe {
+    _ = DeleteDC(hdc);
+    _ = DeleteObject(HGDIOBJ(icon_info.hbmColor.0));
+    _ = DeleteObject(HGDIOBJ(icon_info.hbmMask.0));
+  }
+
+  image
+}
+
+/// Reads a bitmap as top-down 32-bit BGRA pixels.
+fn bitmap_pixels(
+  hdc: HDC,
+  bitmap: HBITMAP,
+) -> Option<(u32, u32, Vec<u8>)> {
+  let mut bitmap_info = BITMAP::default();
+
+  let res = unsafe {
+    GetObjectW(
+      HGDIOBJ(bitmap.0),
+      mem::size_of::<BITMAP>() as i32,
+      Some(&mut bitmap_info as *mut _ as *mut c_void),
+    )
+  };
+
+  let (width, height) = (bitmap_info.bmWidth, bitmap_info.bmHeight);
+
+  if res == 0 || width <= 0 || height <= 0 {
+    return None;
+  }
+
+  let mut dib_info = BITMAPINFO {
+    bmiHeader: BITMAPINFOHEADER {
+      biSize: mem::size_of::<BITMAPINFOHEADER>() as u32,
+      biWidth: width,
+      // A negative height gives top-down rows.
+      biHeight: -height,
+      biPlanes: 1,
+      biBitCount: 32,
+      biCompression: BI_RGB.0,
+      ..Default::default()
+    },
+    ..Default::default()
+  };
+
+  let mut pixels = vec![0u8; width as usize * height as usize * 4];
+
+  let lines = unsafe {
+    GetDIBits(
+      hdc,
+      bitmap,
+      0,
+      height as u32,
+      Some(pixels.as_mut_ptr() as *mut c_void),
+      &mut dib_info,
+      DIB_RGB_COLORS,
+    )
+  };
+
+  (lines != 0).then_some((width as u32, height as u32, pixels))
+}
+
+fn encode_png(image: &IconImage) -> Option<Vec<u8>> {
+  let mut png = Vec::new();
+
+  let mut encoder = png::Encoder::new(&mut png, image.width, image.height);
+  encoder.set_color(png::ColorType::Rgba);
+  encoder.set_depth(png::BitDepth::Eight);
+
+  let mut writer = encoder.write_header().ok()?;
+  writer.write_image_data(&image.rgba).ok()?;
+  writer.finish().ok()?;
+
+  Some(png)
+}
@@ -0,0 +1,13 @@
+mod actions;
+mod config;
+mod enumerate;
+mod event_hook;
+mod icons;
+mod provider;
+mod variables;
+
+pub use actions::*;
+pub use config::*;
+pub use icons::icon_png;
+pub use provider::*;
+pub use variables::*;
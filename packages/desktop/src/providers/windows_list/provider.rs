@@ -0,0 +1,197 @@
+use std::{
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::{
+  sync::mpsc::{self, Sender},
+  task::{self, AbortHandle},
+  time,
+};
+
+use super::{
+  enumerate::list_windows, event_hook::WindowEventHook,
+  icons::IconCacheSize, ListedWindow, WindowsListProviderConfig,
+  WindowsListVariables,
+};
+use crate::providers::{
+  provider::Provider,
+  provider_ref::{ProviderOutput, VariablesResult},
+  retained_size::RetainedState,
+  variables::ProviderVariables,
+};
+
+/// How long to wait for further window events before enumerating.
+/// Opening or closing an app fires a burst of events.
+const EVENT_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub struct WindowsListProvider {
+  pub config: Arc<WindowsListProviderConfig>,
+  abort_handle: Option<AbortHandle>,
+  order: Arc<Mutex<WindowOrder>>,
+}
+
+impl WindowsListProvider {
+  pub fn new(config: WindowsListProviderConfig) -> WindowsListProvider {
+    WindowsListProvider {
+      config: Arc::new(config),
+      abort_handle: None,
+      order: Default::default(),
+    }
+  }
+
+  async fn get_variables(
+    config: &WindowsListProviderConfig,
+    order: &Mutex<WindowOrder>,
+  ) -> WindowsListVariables {
+    let include_minimized = config.include_minimized;
+
+    let mut windows =
+      task::spawn_blocking(move || list_windows(include_minimized))
+        .await
+        .unwrap_or_default();
+
+    order.lock().unwrap().sort(&mut windows);
+
+    WindowsListVariables {
+      focused_hwnd: windows
+        .iter()
+        .find(|window| window.is_focused)
+        .map(|window| window.hwnd),
+      windows,
+    }
+  }
+
+  fn to_output(
+    config_hash: &str,
+    variables: WindowsListVariables,
+  ) -> ProviderOutput {
+    ProviderOutput {
+      config_hash: config_hash.to_string(),
+      variables: VariablesResult::Data(ProviderVariables::WindowsList(
+        variables,
+      )),
+      computed: None,
+      last_updated: None,
+      last_updated_relative: None,
+      run_duration: None,
+      type_name: None,
+    }
+  }
+}
+
+#[async_trait]
+impl Provider for WindowsListProvider {
+  fn min_refresh_interval(&self) -> Option<Duration> {
+    // Output is emitted on window events, so it's always up to date.
+    None
+  }
+
+  fn retained_state(&self) -> RetainedState {
+    RetainedState::new(vec![Arc::new(IconCacheSize)])
+  }
+
+  async fn on_start(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let config = self.config.clone();
+    let config_hash = config_hash.to_string();
+    let order = self.order.clone();
+
+    let task_handle = task::spawn(async move {
+      let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+
+      // Hooks are removed when the task is aborted and this is dropped.
+      let _hook = match WindowEventHook::start(event_tx) {
+        Ok(hook) => hook,
+        Err(err) => {
+          _ = emit_output_tx
+            .send(ProviderOutput {
+              config_hash,
+              variables: VariablesResult::Error(err.to_string()),
+              computed: None,
+              last_updated: None,
+              last_updated_relative: None,
+              run_duration: None,
+              type_name: None,
+            })
+            .await;
+
+          return;
+        }
+      };
+
+      let mut prev_variables = None;
+
+      loop {
+        let variables = Self::get_variables(&config, &order).await;
+
+        if prev_variables.as_ref() != Some(&variables) {
+          prev_variables = Some(variables.clone());
+
+          _ = emit_output_tx
+            .send(Self::to_output(&config_hash, variables))
+            .await;
+        }
+
+        if event_rx.recv().await.is_none() {
+          break;
+        }
+
+        time::sleep(EVENT_DEBOUNCE).await;
+        while event_rx.try_recv().is_ok() {}
+      }
+    });
+
+    self.abort_handle = Some(task_handle.abort_handle());
+    _ = task_handle.await;
+  }
+
+  async fn on_refresh(
+    &mut self,
+    config_hash: &str,
+    emit_output_tx: Sender<ProviderOutput>,
+  ) {
+    let variables = Self::get_variables(&self.config, &self.order).await;
+
+    _ = emit_output_tx
+      .send(Self::to_output(config_hash, variables))
+      .await;
+  }
+
+  async fn on_stop(&mut self) {
+    if let Some(handle) = &self.abort_handle {
+      handle.abort();
+    }
+  }
+}
+
+/// Keeps windows in the order they were first seen, so that entries
+/// don't jump around whenever the z-order changes (e.g. on focus).
+#[derive(Default)]
+struct WindowOrder {
+  hwnds: Vec<u64>,
+}
+
+impl WindowOrder {
+  /// Sorts windows given in z-order. New windows are appended in
+  /// z-order after all previously seen windows.
+  fn sort(&mut self, windows: &mut [ListedWindow]) {
+    self
+      .hwnds
+      .retain(|hwnd| windows.iter().any(|window| window.hwnd == *hwnd));
+
+    for window in windows.iter() {
+      if !self.hwnds.contains(&window.hwnd) {
+        self.hwnds.push(window.hwnd);
+      }
+    }
+
+    windows.sort_by_key(|window| {
+      self.hwnds.iter().position(|hwnd| *hwnd == window.hwnd)
+    });
+  }
+}
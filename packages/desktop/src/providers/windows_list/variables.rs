@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowsListVariables {
+  /// Windows that are shown in the alt-tab switcher, in the order that
+  /// they were first seen.
+  pub windows: Vec<ListedWindow>,
+
+  /// Handle of the focused window, if it's in the list.
+  pub focused_hwnd: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListedWindow {
+  pub hwnd: u64,
+  pub title: String,
+
+  /// File name of the executable (e.g. `firefox.exe`). This is `None`
+  /// if the process can't be inspected (e.g. when it's elevated).
+  pub exe_name: Option<String>,
+  pub exe_path: Option<String>,
+
+  /// URL of the app icon as a PNG, served via the asset protocol.
+  pub icon_url: Option<String>,
+  pub is_focused: bool,
+  pub is_minimized: bool,
+}
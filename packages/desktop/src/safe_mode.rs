@@ -0,0 +1,36 @@
+use anyhow::Context;
+use tauri::{App, AppHandle, Manager, Runtime};
+
+/// Reopens the currently open windows in safe mode.
+///
+/// Set up by the task that opens windows, since it owns the open args of
+/// each window.
+///
+/// Safe mode applies per window. Windows opened in it ignore custom
+/// frontend URLs, `custom_css` and `custom_js`, presets, permissions,
+/// geometry and providers from the config, and their frontend uses the
+/// default global settings. This gives users a working baseline when
+/// their config or a widget breaks windows on load, without affecting
+/// other windows.
+pub struct SafeModeRestart(Box<dyn Fn() + Send + Sync>);
+
+/// Registers how to reopen windows for `restart`.
+pub fn init<F>(app: &App, reopen_windows: F)
+where
+  F: Fn() + Send + Sync + 'static,
+{
+  app.manage(SafeModeRestart(Box::new(reopen_windows)));
+}
+
+/// Reopens the open windows in safe mode.
+pub fn restart<R: Runtime>(
+  app_handle: &AppHandle<R>,
+) -> anyhow::Result<()> {
+  let restart = app_handle
+    .try_state::<SafeModeRestart>()
+    .context("Windows can't be reopened yet.")?;
+
+  (restart.0)();
+
+  Ok(())
+}
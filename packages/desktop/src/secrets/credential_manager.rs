@@ -0,0 +1,96 @@
+use std::{ptr, slice};
+
+use anyhow::Context;
+use windows::{
+  core::{HSTRING, PWSTR},
+  Win32::{
+    Foundation::ERROR_NOT_FOUND,
+    Security::Credentials::{
+      CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW,
+      CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
+    },
+  },
+};
+
+/// Secrets stored as generic credentials in the Windows Credential
+/// Manager.
+pub struct PlatformKeychain;
+
+impl PlatformKeychain {
+  pub fn new() -> Option<Self> {
+    Some(Self)
+  }
+
+  pub fn get(&self, name: &str) -> anyhow::Result<Option<String>> {
+    let mut credential = ptr::null_mut::<CREDENTIALW>();
+
+    let res = unsafe {
+      CredReadW(&target_name(name), CRED_TYPE_GENERIC, 0, &mut credential)
+    };
+
+    match res {
+      Ok(()) => {
+        let bytes = unsafe {
+          let blob = slice::from_raw_parts(
+            (*credential).CredentialBlob,
+            (*credential).CredentialBlobSize as usize,
+          )
+          .to_vec();
+
+          CredFree(credential as *const _);
+          blob
+        };
+
+        Ok(Some(
+          String::from_utf8(bytes).context("Secret isn't valid UTF-8.")?,
+        ))
+      }
+      Err(err) if err.code() == ERROR_NOT_FOUND.to_hresult() => Ok(None),
+      Err(err) => {
+        Err(err).context("Failed to read secret from Credential Manager.")
+      }
+    }
+  }
+
+  pub fn set(&self, name: &str, value: &str) -> anyhow::Result<()> {
+    let mut target_name = target_name(name)
+      .as_wide()
+      .iter()
+      .copied()
+      .chain([0])
+      .collect::<Vec<_>>();
+
+    let mut blob = value.as_bytes().to_vec();
+
+    let credential = CREDENTIALW {
+      Type: CRED_TYPE_GENERIC,
+      TargetName: PWSTR(target_name.as_mut_ptr()),
+      CredentialBlobSize: blob.len() as u32,
+      CredentialBlob: blob.as_mut_ptr(),
+      Persist: CRED_PERSIST_LOCAL_MACHINE,
+      ..Default::default()
+    };
+
+    unsafe { CredWriteW(&credential, 0) }
+      .context("Failed to store secret in Credential Manager.")
+  }
+
+  /// Returns whether the secret existed.
+  pub fn delete(&self, name: &str) -> anyhow::Result<bool> {
+    let res =
+      unsafe { CredDeleteW(&target_name(name), CRED_TYPE_GENERIC, 0) };
+
+    match res {
+      Ok(()) => Ok(true),
+      Err(err) if err.code() == ERROR_NOT_FOUND.to_hresult() => Ok(false),
+      Err(err) => Err(err)
+        .context("Failed to delete secret from Credential Manager."),
+    }
+  }
+}
+
+/// Credentials are global to the user, so names are prefixed to avoid
+/// clashing with other apps.
+fn target_name(name: &str) -> HSTRING {
+  HSTRING::from(format!("zebar/{}", name))
+}
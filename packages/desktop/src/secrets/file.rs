@@ -0,0 +1,102 @@
+use std::{
+  collections::BTreeMap,
+  fs::{self, OpenOptions},
+  io::Write,
+  path::PathBuf,
+};
+
+use anyhow::Context;
+
+/// Secrets stored in a YAML file of name-value pairs. On Unix, the file
+/// is only readable by the current user.
+pub struct FileStore {
+  path: PathBuf,
+}
+
+impl FileStore {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  pub fn get(&self, name: &str) -> anyhow::Result<Option<String>> {
+    Ok(self.read()?.remove(name))
+  }
+
+  pub fn set(&self, name: &str, value: &str) -> anyhow::Result<()> {
+    let mut secrets = self.read()?;
+    secrets.insert(name.to_string(), value.to_string());
+    self.write(&secrets)
+  }
+
+  /// Returns whether the secret existed.
+  pub fn delete(&self, name: &str) -> anyhow::Result<bool> {
+    let mut secrets = self.read()?;
+
+    if secrets.remove(name).is_none() {
+      return Ok(false);
+    }
+
+    self.write(&secrets)?;
+    Ok(true)
+  }
+
+  pub fn values(&self) -> anyhow::Result<Vec<String>> {
+    Ok(self.read()?.into_values().collect())
+  }
+
+  fn read(&self) -> anyhow::Result<BTreeMap<String, String>> {
+    if !self.path.exists() {
+      return Ok(BTreeMap::new());
+    }
+
+    let contents = fs::read_to_string(&self.path).with_context(|| {
+      format!("Unable to read {}.", self.path.display())
+    })?;
+
+    // Parse errors aren't included, since they can quote values.
+    serde_yaml::from_str::<Option<BTreeMap<String, String>>>(&contents)
+      .map(Option::unwrap_or_default)
+      .map_err(|_| {
+        anyhow::anyhow!(
+          "Invalid secrets file {}. Expected a map of names to string \
+          values.",
+          self.path.display()
+        )
+      })
+  }
+
+  fn write(
+    &self,
+    secrets: &BTreeMap<String, String>,
+  ) -> anyhow::Result<()> {
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+      options.mode(0o600);
+
+      // The mode only applies to newly created files.
+      if self.path.exists() {
+        fs::set_permissions(
+          &self.path,
+          fs::Permissions::from_mode(0o600),
+        )?;
+      }
+    }
+
+    let mut file = options.open(&self.path).with_context(|| {
+      format!("Unable to write {}.", self.path.display())
+    })?;
+
+    file.write_all(serde_yaml::to_string(secrets)?.as_bytes())?;
+
+    Ok(())
+  }
+}
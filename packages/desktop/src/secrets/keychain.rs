@@ -0,0 +1,45 @@
+use anyhow::Context;
+use security_framework::passwords::{
+  delete_generic_password, get_generic_password, set_generic_password,
+};
+
+/// Service that all of Zebar's secrets are stored under.
+const SERVICE: &str = "zebar";
+
+/// `errSecItemNotFound` from the Security framework.
+const ERR_ITEM_NOT_FOUND: i32 = -25300;
+
+/// Secrets stored as generic passwords in the login keychain.
+pub struct PlatformKeychain;
+
+impl PlatformKeychain {
+  pub fn new() -> Option<Self> {
+    Some(Self)
+  }
+
+  pub fn get(&self, name: &str) -> anyhow::Result<Option<String>> {
+    match get_generic_password(SERVICE, name) {
+      Ok(bytes) => Ok(Some(
+        String::from_utf8(bytes).context("Secret isn't valid UTF-8.")?,
+      )),
+      Err(err) if err.code() == ERR_ITEM_NOT_FOUND => Ok(None),
+      Err(err) => Err(err).context("Failed to read secret from keychain."),
+    }
+  }
+
+  pub fn set(&self, name: &str, value: &str) -> anyhow::Result<()> {
+    set_generic_password(SERVICE, name, value.as_bytes())
+      .context("Failed to store secret in keychain.")
+  }
+
+  /// Returns whether the secret existed.
+  pub fn delete(&self, name: &str) -> anyhow::Result<bool> {
+    match delete_generic_password(SERVICE, name) {
+      Ok(()) => Ok(true),
+      Err(err) if err.code() == ERR_ITEM_NOT_FOUND => Ok(false),
+      Err(err) => {
+        Err(err).context("Failed to delete secret from keychain.")
+      }
+    }
+  }
+}
@@ -0,0 +1,454 @@
+use std::{
+  collections::{HashMap, HashSet},
+  path::Path,
+  sync::{Mutex, OnceLock},
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+#[cfg(target_os = "windows")]
+use self::credential_manager::PlatformKeychain;
+use self::file::FileStore;
+#[cfg(target_os = "macos")]
+use self::keychain::PlatformKeychain;
+#[cfg(target_os = "linux")]
+use self::secret_service::PlatformKeychain;
+use crate::{
+  cli::CommandOutput, permissions::host_matches,
+  providers::config::ProviderConfig, user_config,
+};
+
+#[cfg(target_os = "windows")]
+mod credential_manager;
+mod file;
+#[cfg(target_os = "macos")]
+mod keychain;
+#[cfg(target_os = "linux")]
+mod secret_service;
+
+/// Replacement for secret values in logs and diagnostics.
+const MASK: &str = "[REDACTED]";
+
+/// Where secrets are stored.
+#[derive(
+  Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretsBackend {
+  /// The platform's keychain if available, otherwise `secrets.yaml`.
+  /// Secrets are read from both.
+  #[default]
+  Auto,
+
+  /// Credential Manager on Windows, Keychain on macOS, and the Secret
+  /// Service (via `secret-tool`) on Linux.
+  Keychain,
+
+  /// `secrets.yaml` within the config directory.
+  File,
+}
+
+/// Reference to a secret from a provider config or an `http_request`
+/// (e.g. `{ secret: "owm_key" }`).
+#[derive(
+  Deserialize, Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(deny_unknown_fields)]
+pub struct SecretRef {
+  pub secret: String,
+}
+
+/// Value that is either given as is or read from the secrets store.
+#[derive(
+  Deserialize, Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(untagged)]
+pub enum ValueOrSecret {
+  Value(String),
+  Secret(SecretRef),
+}
+
+impl ValueOrSecret {
+  pub fn resolve(&self, store: &SecretsStore) -> anyhow::Result<String> {
+    match self {
+      ValueOrSecret::Value(value) => Ok(value.clone()),
+      ValueOrSecret::Secret(secret_ref) => store.get(&secret_ref.secret),
+    }
+  }
+
+  /// Resolves the value for a request to the given host. Errors if it's
+  /// a secret that isn't allowed to be sent to the host.
+  pub fn resolve_for_host(
+    &self,
+    store: &SecretsStore,
+    host: &str,
+  ) -> anyhow::Result<String> {
+    match self {
+      ValueOrSecret::Value(value) => Ok(value.clone()),
+      ValueOrSecret::Secret(secret_ref) => {
+        store.get_for_host(&secret_ref.secret, host)
+      }
+    }
+  }
+}
+
+/// Store of named secrets (e.g. API keys), which are kept outside of the
+/// main config so that the config can be shared.
+pub struct SecretsStore {
+  backend: Mutex<SecretsBackend>,
+  file: FileStore,
+
+  /// Hosts that each secret may be sent to by requests from windows,
+  /// keyed by secret name.
+  hosts: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl SecretsStore {
+  pub fn new(backend: SecretsBackend, config_dir: &Path) -> Self {
+    Self {
+      backend: Mutex::new(backend),
+      file: FileStore::new(config_dir.join("secrets.yaml")),
+      hosts: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Creates a store with the backend from the global config. Used by
+  /// the CLI, where the config isn't otherwise loaded.
+  pub fn from_config(app_handle: &AppHandle) -> anyhow::Result<Self> {
+    let backend = user_config::read_parsed(app_handle.clone())
+      .and_then(|config| config.global())
+      .map(|global_config| global_config.secrets_backend)
+      .unwrap_or_default();
+
+    Ok(Self::new(backend, &user_config::config_dir(app_handle)?))
+  }
+
+  /// Changes the backend after the global config is reloaded.
+  pub fn set_backend(&self, backend: SecretsBackend) {
+    *self.backend.lock().unwrap() = backend;
+  }
+
+  /// Changes the hosts that secrets may be sent to, from
+  /// `secret_hosts` in the global config.
+  pub fn set_hosts(&self, hosts: HashMap<String, Vec<String>>) {
+    *self.hosts.lock().unwrap() = hosts;
+  }
+
+  /// Gets the value of a secret for a request to the given host. Errors
+  /// if the host isn't in the secret's `secret_hosts`.
+  pub fn get_for_host(
+    &self,
+    name: &str,
+    host: &str,
+  ) -> anyhow::Result<String> {
+    let is_allowed =
+      self.hosts.lock().unwrap().get(name).is_some_and(|hosts| {
+        hosts.iter().any(|pattern| host_matches(pattern, host))
+      });
+
+    if !is_allowed {
+      bail!(
+        "Secret '{}' isn't allowed to be sent to '{}'. Add the host to \
+        `secret_hosts` in the global config.",
+        name,
+        host
+      );
+    }
+
+    self.get(name)
+  }
+
+  /// Gets the value of a secret. Errors if it isn't defined.
+  ///
+  /// Values are remembered, so that they're redacted from error messages
+  /// and diagnostics exports.
+  pub fn get(&self, name: &str) -> anyhow::Result<String> {
+    let value = match self.backend() {
+      SecretsBackend::Auto => {
+        let from_keychain = match PlatformKeychain::new() {
+          Some(keychain) => keychain.get(name)?,
+          None => None,
+        };
+
+        match from_keychain {
+          Some(value) => Some(value),
+          None => self.file.get(name)?,
+        }
+      }
+      SecretsBackend::Keychain => self.required_keychain()?.get(name)?,
+      SecretsBackend::File => self.file.get(name)?,
+    };
+
+    let value = value.with_context(|| {
+      format!(
+        "Secret '{}' isn't defined. Add it via `zebar secret set`.",
+        name
+      )
+    })?;
+
+    remember(&value);
+    Ok(value)
+  }
+
+  /// Sets the value of a secret. Returns the backend it was stored in.
+  pub fn set(
+    &self,
+    name: &str,
+    value: &str,
+  ) -> anyhow::Result<SecretsBackend> {
+    validate_name(name)?;
+
+    match self.backend() {
+      SecretsBackend::Auto => match PlatformKeychain::new() {
+        Some(keychain) => {
+          keychain.set(name, value)?;
+
+          // Otherwise, a stale value in the file could be read if the
+          // keychain becomes unavailable.
+          self.file.delete(name)?;
+          Ok(SecretsBackend::Keychain)
+        }
+        None => {
+          self.file.set(name, value)?;
+          Ok(SecretsBackend::File)
+        }
+      },
+      SecretsBackend::Keychain => {
+        self.required_keychain()?.set(name, value)?;
+        Ok(SecretsBackend::Keychain)
+      }
+      SecretsBackend::File => {
+        self.file.set(name, value)?;
+        Ok(SecretsBackend::File)
+      }
+    }
+  }
+
+  /// Deletes a secret. Errors if it isn't defined.
+  pub fn delete(&self, name: &str) -> anyhow::Result<()> {
+    let deleted = match self.backend() {
+      SecretsBackend::Auto => {
+        let from_keychain = match PlatformKeychain::new() {
+          Some(keychain) => keychain.delete(name)?,
+          None => false,
+        };
+
+        self.file.delete(name)? || from_keychain
+      }
+      SecretsBackend::Keychain => {
+        self.required_keychain()?.delete(name)?
+      }
+      SecretsBackend::File => self.file.delete(name)?,
+    };
+
+    if !deleted {
+      bail!("Secret '{}' isn't defined.", name);
+    }
+
+    Ok(())
+  }
+
+  /// Values of secrets in `secrets.yaml`. Keychain values can't be
+  /// listed, so only ones that have been read are known.
+  pub fn file_values(&self) -> Vec<String> {
+    self.file.values().unwrap_or_default()
+  }
+
+  fn backend(&self) -> SecretsBackend {
+    *self.backend.lock().unwrap()
+  }
+
+  fn required_keychain(&self) -> anyhow::Result<PlatformKeychain> {
+    PlatformKeychain::new().context(
+      "Keychain isn't available. Set `secrets_backend` to `file` in the \
+      global config to use `secrets.yaml` instead.",
+    )
+  }
+}
+
+/// Replaces all secret references in the config (e.g. `{ secret:
+/// "owm_key" }`) with the values of the secrets.
+pub fn resolve_refs(
+  config: &Value,
+  store: &SecretsStore,
+) -> anyhow::Result<Value> {
+  match config {
+    Value::Object(map) => {
+      if map.len() == 1 {
+        if let Ok(secret_ref) = SecretRef::deserialize(config) {
+          return Ok(Value::String(store.get(&secret_ref.secret)?));
+        }
+      }
+
+      map
+        .iter()
+        .map(|(key, value)| Ok((key.clone(), resolve_refs(value, store)?)))
+        .collect::<anyhow::Result<_>>()
+        .map(Value::Object)
+    }
+    Value::Array(items) => items
+      .iter()
+      .map(|value| resolve_refs(value, store))
+      .collect::<anyhow::Result<_>>()
+      .map(Value::Array),
+    _ => Ok(config.clone()),
+  }
+}
+
+/// Replaces the secret references in a provider config with the values
+/// of the secrets.
+///
+/// Only fields of the provider type are resolved. Shared options (e.g.
+/// `fallback` and `transforms`) can end up in outputs sent to windows,
+/// so references in them are rejected.
+pub fn resolve_provider_refs(
+  config: &Value,
+  store: &SecretsStore,
+) -> anyhow::Result<Value> {
+  let Value::Object(map) = config else {
+    return resolve_refs(config, store);
+  };
+
+  map
+    .iter()
+    .map(|(key, value)| {
+      if !ProviderConfig::SHARED_OPTIONS.contains(&key.as_str()) {
+        return Ok((key.clone(), resolve_refs(value, store)?));
+      }
+
+      if mask_refs(value) != *value {
+        bail!("Secrets can't be referenced in `{}`.", key);
+      }
+
+      Ok((key.clone(), value.clone()))
+    })
+    .collect::<anyhow::Result<_>>()
+    .map(Value::Object)
+}
+
+/// Replaces all secret references in the config with a placeholder, so
+/// that the config can be validated without reading the secrets.
+pub fn mask_refs(config: &Value) -> Value {
+  match config {
+    Value::Object(map) => {
+      if map.len() == 1 && SecretRef::deserialize(config).is_ok() {
+        return Value::String(MASK.into());
+      }
+
+      map
+        .iter()
+        .map(|(key, value)| (key.clone(), mask_refs(value)))
+        .collect()
+    }
+    Value::Array(items) => items.iter().map(mask_refs).collect(),
+    _ => config.clone(),
+  }
+}
+
+/// Values of secrets that have been read by this process.
+fn known_values() -> &'static Mutex<HashSet<String>> {
+  static KNOWN_VALUES: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+  KNOWN_VALUES.get_or_init(Default::default)
+}
+
+fn remember(value: &str) {
+  if !value.is_empty() {
+    known_values().lock().unwrap().insert(value.to_string());
+  }
+}
+
+/// Masks the values of secrets that have been read by this process, as
+/// well as the given values.
+pub fn redact_known(text: &str, extra_values: &[String]) -> String {
+  let known_values = known_values().lock().unwrap();
+
+  let mut values = known_values
+    .iter()
+    .chain(extra_values)
+    .filter(|value| !value.is_empty())
+    .collect::<Vec<_>>();
+
+  // Longest first, in case a value contains another.
+  values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+
+  values.into_iter().fold(text.to_string(), |text, value| {
+    text.replace(value.as_str(), MASK)
+  })
+}
+
+/// Masks the values of known secrets in an error, e.g. a deserialization
+/// error that quotes a resolved config value.
+pub fn redact_error(err: impl Into<anyhow::Error>) -> anyhow::Error {
+  anyhow::anyhow!(redact_known(&format!("{:#}", err.into()), &[]))
+}
+
+/// Reads the value of a secret from the first line of stdin.
+pub fn read_value_from_stdin() -> anyhow::Result<String> {
+  let mut value = String::new();
+  std::io::stdin()
+    .read_line(&mut value)
+    .context("Failed to read secret from stdin.")?;
+
+  let value = value.trim_end_matches(['\r', '\n']);
+
+  if value.is_empty() {
+    bail!("Secret value can't be empty.");
+  }
+
+  Ok(value.to_string())
+}
+
+/// Names are used as keychain account names and YAML keys, so they're
+/// restricted to a safe set of characters.
+fn validate_name(name: &str) -> anyhow::Result<()> {
+  let is_valid = !name.is_empty()
+    && name
+      .chars()
+      .all(|char| char.is_ascii_alphanumeric() || "_-.".contains(char));
+
+  if !is_valid {
+    bail!(
+      "Invalid secret name '{}'. Names can only contain letters, \
+      numbers, `_`, `-` and `.`.",
+      name
+    );
+  }
+
+  Ok(())
+}
+
+/// Output of the `secret get` CLI command.
+#[derive(Serialize, Debug)]
+pub struct SecretOutput {
+  pub name: String,
+  pub value: String,
+}
+
+impl CommandOutput for SecretOutput {
+  fn to_text(&self) -> String {
+    format!("{}\n", self.value)
+  }
+}
+
+/// Output of the `secret set` CLI command.
+#[derive(Serialize, Debug)]
+pub struct SecretSetOutput {
+  pub name: String,
+
+  /// Backend that the secret was stored in.
+  pub backend: SecretsBackend,
+}
+
+impl CommandOutput for SecretSetOutput {
+  fn to_text(&self) -> String {
+    let location = match self.backend {
+      SecretsBackend::File => "secrets.yaml",
+      _ => "the keychain",
+    };
+
+    format!("Stored secret '{}' in {}.\n", self.name, location)
+  }
+}
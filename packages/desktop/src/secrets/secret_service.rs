@@ -0,0 +1,97 @@
+use std::{
+  io::Write,
+  process::{Command, Stdio},
+  sync::OnceLock,
+};
+
+use anyhow::{bail, Context};
+
+/// Attribute that all of Zebar's secrets are stored under.
+const SERVICE: &str = "zebar";
+
+/// Secrets stored in the Secret Service (e.g. GNOME Keyring or KWallet)
+/// via `secret-tool` from libsecret.
+pub struct PlatformKeychain;
+
+impl PlatformKeychain {
+  /// Returns `None` if `secret-tool` isn't installed.
+  pub fn new() -> Option<Self> {
+    static IS_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+    // Prints its usage without arguments, so only whether it can be
+    // spawned is checked.
+    let is_available = *IS_AVAILABLE.get_or_init(|| {
+      Command::new("secret-tool")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+    });
+
+    is_available.then_some(Self)
+  }
+
+  pub fn get(&self, name: &str) -> anyhow::Result<Option<String>> {
+    let output = Command::new("secret-tool")
+      .args(["lookup", "service", SERVICE, "name", name])
+      .output()
+      .context("Failed to run secret-tool.")?;
+
+    // Exits with 1 and no output if the secret doesn't exist.
+    if !output.status.success() {
+      return Ok(None);
+    }
+
+    let value = String::from_utf8(output.stdout)
+      .context("Secret isn't valid UTF-8.")?;
+
+    Ok(Some(value.trim_end_matches('\n').to_string()))
+  }
+
+  pub fn set(&self, name: &str, value: &str) -> anyhow::Result<()> {
+    // The value is passed via stdin, so that it doesn't show up in the
+    // process list.
+    let mut child = Command::new("secret-tool")
+      .args(["store", "--label"])
+      .arg(format!("Zebar: {}", name))
+      .args(["service", SERVICE, "name", name])
+      .stdin(Stdio::piped())
+      .stdout(Stdio::null())
+      .stderr(Stdio::piped())
+      .spawn()
+      .context("Failed to run secret-tool.")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+      stdin.write_all(value.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+      bail!(
+        "Failed to store secret in keychain: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+      );
+    }
+
+    Ok(())
+  }
+
+  /// Returns whether the secret existed.
+  pub fn delete(&self, name: &str) -> anyhow::Result<bool> {
+    if self.get(name)?.is_none() {
+      return Ok(false);
+    }
+
+    let status = Command::new("secret-tool")
+      .args(["clear", "service", SERVICE, "name", name])
+      .status()
+      .context("Failed to run secret-tool.")?;
+
+    if !status.success() {
+      bail!("Failed to delete secret from keychain.");
+    }
+
+    Ok(true)
+  }
+}
@@ -1,43 +1,426 @@
-use anyhow::Context;
+use std::{
+  sync::Mutex,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context};
+use serde::Serialize;
 use tauri::{
-  menu::MenuBuilder,
+  menu::{
+    CheckMenuItemBuilder, IsMenuItem, Menu, MenuBuilder, MenuItemBuilder,
+    PredefinedMenuItem, SubmenuBuilder,
+  },
   tray::{TrayIcon, TrayIconBuilder},
+  AppHandle, Emitter, Manager, Runtime,
+};
+use tauri_plugin_notification::NotificationExt;
+use tokio::task;
+use tracing::{error, info, warn};
+
+use crate::{
+  app_errors::{report_error, AppError},
+  diagnostics::export_diagnostics,
+  providers::{
+    provider_manager::ProviderManager, subscriptions::ProviderSubscription,
+  },
+  safe_mode, tooltip,
+  user_config::open_config_dir,
 };
-use tracing::{error, info};
 
-use crate::user_config::open_config_dir;
+/// Item of the system tray menu.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TrayMenuItem {
+  Action {
+    id: String,
+    label: String,
+    enabled: bool,
+  },
+  Checkbox {
+    id: String,
+    label: String,
+    checked: bool,
+  },
+  Submenu {
+    label: String,
+    items: Vec<TrayMenuItem>,
+  },
+  Separator,
+}
+
+impl TrayMenuItem {
+  /// Whether the items include an enabled item with the given ID,
+  /// including items within submenus.
+  fn has_enabled(items: &[TrayMenuItem], action_id: &str) -> bool {
+    items.iter().any(|item| match item {
+      TrayMenuItem::Action { id, enabled, .. } => {
+        id == action_id && *enabled
+      }
+      TrayMenuItem::Checkbox { id, .. } => id == action_id,
+      TrayMenuItem::Submenu { items, .. } => {
+        TrayMenuItem::has_enabled(items, action_id)
+      }
+      TrayMenuItem::Separator => false,
+    })
+  }
+}
+
+/// Current items of the system tray menu.
+///
+/// The tray menu is always built from this, so that the menu and the
+/// state returned to the frontend can't disagree.
+#[derive(Default)]
+pub struct TrayState(Mutex<Vec<TrayMenuItem>>);
+
+impl TrayState {
+  pub fn items(&self) -> Vec<TrayMenuItem> {
+    self.0.lock().unwrap().clone()
+  }
+}
 
 pub fn setup_sys_tray(app: &mut tauri::App) -> anyhow::Result<TrayIcon> {
   let icon_image = app
     .default_window_icon()
     .context("No icon defined in Tauri config.")?;
 
-  let tray_menu = MenuBuilder::new(app)
-    .text("show_config_folder", "Show config folder")
-    .separator()
-    .text("exit", "Exit")
-    .build()?;
+  let items = tray_items(&[], &[], false);
+  let tray_menu = build_tray_menu(app, &items)?;
+  *app.state::<TrayState>().0.lock().unwrap() = items;
 
   let tray_icon = TrayIconBuilder::with_id("tray")
     .icon(icon_image.clone())
     .menu(&tray_menu)
     .tooltip(format!("Zebar v{}", env!("VERSION_NUMBER")))
-    .on_menu_event(move |app, event| match event.id().as_ref() {
-      "show_config_folder" => {
-        info!("Opening config folder from system tray.");
-        if let Err(err) = open_config_dir(app) {
-          error!("Failed to open config folder: {}", err);
-        }
-      }
-      "exit" => {
-        info!("Exiting through system tray.");
-        app.exit(0)
-      }
-      other => {
-        error!("Unknown menu event: {}", other);
+    .on_menu_event(move |app, event| {
+      if let Err(err) = run_tray_action(app, event.id().as_ref()) {
+        error!("Failed to run tray action: {:?}", err);
       }
     })
     .build(app)?;
 
   Ok(tray_icon)
 }
+
+/// Runs the action of a tray menu item. Used both when the item is
+/// clicked and when it's triggered from the frontend.
+pub fn run_tray_action<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  action_id: &str,
+) -> anyhow::Result<()> {
+  match action_id {
+    "show_config_folder" => {
+      info!("Opening config folder from system tray.");
+      if let Err(err) = open_config_dir(app_handle) {
+        report_error(
+          app_handle,
+          AppError::error(
+            "tray",
+            "open_config_folder_failed",
+            format!("Failed to open config folder: {:#}", err),
+          ),
+        );
+      }
+    }
+    "export_diagnostics" => {
+      info!("Exporting diagnostics from system tray.");
+      let app_handle = app_handle.clone();
+
+      task::spawn(async move {
+        if let Err(err) = export_tray_diagnostics(&app_handle).await {
+          report_error(
+            &app_handle,
+            AppError::error(
+              "tray",
+              "diagnostics_export_failed",
+              format!("Failed to export diagnostics: {:#}", err),
+            ),
+          );
+        }
+      });
+    }
+    "redaction" => {
+      let app_handle = app_handle.clone();
+
+      task::spawn(async move {
+        let provider_manager = app_handle.state::<ProviderManager>();
+        let enabled = !provider_manager.is_redaction_enabled();
+        provider_manager.set_redaction(enabled).await;
+
+        if let Err(err) = update_tray_menu(&app_handle).await {
+          warn!("Failed to update tray menu: {:?}", err);
+        }
+      });
+    }
+    "restart_safe_mode" => {
+      info!("Restarting in safe mode from system tray.");
+      safe_mode::restart(app_handle)?;
+    }
+    "exit" => {
+      info!("Exiting through system tray.");
+      app_handle.exit(0)
+    }
+    // Provider subscriptions are shown for debugging only.
+    other if other.starts_with("subscription:") => {}
+    other => match other.strip_prefix("window_visible:") {
+      Some(window_label) => {
+        toggle_window_visible(app_handle, window_label)?
+      }
+      None => bail!("Unknown tray action '{}'.", other),
+    },
+  }
+
+  Ok(())
+}
+
+/// Runs the action of a tray menu item on behalf of the frontend. Only
+/// items that are currently in the menu and enabled can be triggered.
+pub fn trigger_tray_action<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  action_id: &str,
+) -> anyhow::Result<()> {
+  let items = app_handle.state::<TrayState>().items();
+
+  if !TrayMenuItem::has_enabled(&items, action_id) {
+    bail!("Tray action '{}' isn't available.", action_id);
+  }
+
+  run_tray_action(app_handle, action_id)
+}
+
+/// Shows the window if it's hidden, and hides it otherwise.
+fn toggle_window_visible<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  window_label: &str,
+) -> anyhow::Result<()> {
+  let window = app_handle
+    .get_webview_window(window_label)
+    .with_context(|| format!("Window '{}' not found.", window_label))?;
+
+  match window.is_visible()? {
+    true => window.hide()?,
+    false => window.show()?,
+  }
+
+  let app_handle = app_handle.clone();
+
+  task::spawn(async move {
+    if let Err(err) = update_tray_menu(&app_handle).await {
+      warn!("Failed to update tray menu: {:?}", err);
+    }
+  });
+
+  Ok(())
+}
+
+/// Exports diagnostics to the downloads folder, including a snapshot of
+/// the running providers, and shows a notification with the file path.
+async fn export_tray_diagnostics<R: Runtime>(
+  app_handle: &AppHandle<R>,
+) -> anyhow::Result<()> {
+  let dir = app_handle
+    .path()
+    .download_dir()
+    .or_else(|_| app_handle.path().home_dir())?;
+
+  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  let path = dir.join(format!("zebar-diagnostics-{}.zip", timestamp));
+
+  let providers = app_handle
+    .state::<ProviderManager>()
+    .all_subscriptions()
+    .await;
+
+  let output =
+    export_diagnostics(app_handle, &path, true, Some(&providers))?;
+
+  info!("Included files in diagnostics: {}", output.files.join(", "));
+
+  app_handle
+    .notification()
+    .builder()
+    .title("Diagnostics exported")
+    .body(path.display().to_string())
+    .show()?;
+
+  Ok(())
+}
+
+/// Shows a count on the system tray icon. On MacOS, the count is shown
+/// next to the icon, whereas elsewhere it's only shown in the tooltip.
+pub fn set_tray_badge<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  count: Option<u32>,
+) -> anyhow::Result<()> {
+  let tray_icon = app_handle
+    .tray_by_id("tray")
+    .context("System tray icon not found.")?;
+
+  let tooltip = match count {
+    Some(count) => {
+      format!("Zebar v{} ({})", env!("VERSION_NUMBER"), count)
+    }
+    None => format!("Zebar v{}", env!("VERSION_NUMBER")),
+  };
+
+  tray_icon.set_tooltip(Some(tooltip))?;
+
+  #[cfg(target_os = "macos")]
+  tray_icon.set_title(count.map(|count| count.to_string()))?;
+
+  Ok(())
+}
+
+/// Rebuilds the system tray menu with the open windows and their
+/// current provider subscriptions, and emits the new state to the
+/// frontend.
+pub async fn update_tray_menu<R: Runtime>(
+  app_handle: &AppHandle<R>,
+) -> anyhow::Result<()> {
+  let provider_manager = app_handle.state::<ProviderManager>();
+  let subscriptions = provider_manager.all_subscriptions().await;
+  let is_redaction_enabled = provider_manager.is_redaction_enabled();
+
+  let mut windows = app_handle
+    .webview_windows()
+    .into_iter()
+    .filter(|(label, _)| !tooltip::is_tooltip_label(label))
+    .map(|(label, window)| {
+      let is_visible = window.is_visible().unwrap_or(false);
+      (label, is_visible)
+    })
+    .collect::<Vec<_>>();
+
+  windows.sort();
+
+  let items = tray_items(&windows, &subscriptions, is_redaction_enabled);
+
+  let tray_icon = app_handle
+    .tray_by_id("tray")
+    .context("System tray icon not found.")?;
+
+  tray_icon.set_menu(Some(build_tray_menu(app_handle, &items)?))?;
+
+  *app_handle.state::<TrayState>().0.lock().unwrap() = items.clone();
+  app_handle.emit("tray-state-changed", items)?;
+
+  Ok(())
+}
+
+/// Gets the items of the tray menu, with a submenu per window to toggle
+/// its visibility and list its provider subscriptions.
+fn tray_items(
+  windows: &[(String, bool)],
+  subscriptions: &[(String, Vec<ProviderSubscription>)],
+  is_redaction_enabled: bool,
+) -> Vec<TrayMenuItem> {
+  let mut items = Vec::new();
+
+  for (window_label, is_visible) in windows {
+    let mut window_items = vec![TrayMenuItem::Checkbox {
+      id: format!("window_visible:{}", window_label),
+      label: "Visible".into(),
+      checked: *is_visible,
+    }];
+
+    let window_subscriptions = subscriptions
+      .iter()
+      .find(|(label, _)| label == window_label)
+      .map(|(_, window_subscriptions)| window_subscriptions.as_slice())
+      .unwrap_or_default();
+
+    if !window_subscriptions.is_empty() {
+      window_items.push(TrayMenuItem::Separator);
+    }
+
+    for subscription in window_subscriptions {
+      let fields = match subscription.tracked_access.len() {
+        0 => "all fields".to_string(),
+        count => format!("{} fields", count),
+      };
+
+      window_items.push(TrayMenuItem::Action {
+        id: format!("subscription:{}", subscription.config_hash),
+        label: format!("{} ({})", subscription.provider_type, fields),
+        enabled: false,
+      });
+    }
+
+    items.push(TrayMenuItem::Submenu {
+      label: window_label.clone(),
+      items: window_items,
+    });
+  }
+
+  if !windows.is_empty() {
+    items.push(TrayMenuItem::Separator);
+  }
+
+  let action = |id: &str, label: &str| TrayMenuItem::Action {
+    id: id.into(),
+    label: label.into(),
+    enabled: true,
+  };
+
+  items.extend([
+    TrayMenuItem::Checkbox {
+      id: "redaction".into(),
+      label: "Streaming mode (redact fields)".into(),
+      checked: is_redaction_enabled,
+    },
+    action("show_config_folder", "Show config folder"),
+    action("export_diagnostics", "Export diagnostics"),
+    action("restart_safe_mode", "Restart in safe mode"),
+    TrayMenuItem::Separator,
+    action("exit", "Exit"),
+  ]);
+
+  items
+}
+
+fn build_tray_menu<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  items: &[TrayMenuItem],
+) -> anyhow::Result<Menu<R>> {
+  let menu_items = build_menu_items(manager, items)?;
+
+  Ok(
+    MenuBuilder::new(manager)
+      .items(&menu_items.iter().map(Box::as_ref).collect::<Vec<_>>())
+      .build()?,
+  )
+}
+
+fn build_menu_items<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  items: &[TrayMenuItem],
+) -> anyhow::Result<Vec<Box<dyn IsMenuItem<R>>>> {
+  items
+    .iter()
+    .map(|item| -> anyhow::Result<Box<dyn IsMenuItem<R>>> {
+      Ok(match item {
+        TrayMenuItem::Action { id, label, enabled } => Box::new(
+          MenuItemBuilder::with_id(id.as_str(), label)
+            .enabled(*enabled)
+            .build(manager)?,
+        ),
+        TrayMenuItem::Checkbox { id, label, checked } => Box::new(
+          CheckMenuItemBuilder::with_id(id.as_str(), label)
+            .checked(*checked)
+            .build(manager)?,
+        ),
+        TrayMenuItem::Submenu { label, items } => {
+          let children = build_menu_items(manager, items)?;
+
+          Box::new(
+            SubmenuBuilder::new(manager, label)
+              .items(&children.iter().map(Box::as_ref).collect::<Vec<_>>())
+              .build()?,
+          )
+        }
+        TrayMenuItem::Separator => {
+          Box::new(PredefinedMenuItem::separator(manager)?)
+        }
+      })
+    })
+    .collect()
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use core_foundation::{
+  attributed_string::CFMutableAttributedString,
+  base::{CFRange, TCFType},
+  string::CFString,
+};
+use core_text::{
+  font::{new_from_name, CTFont},
+  font_descriptor::kCTFontBoldTrait,
+  line::CTLine,
+  string_attributes::kCTFontAttributeName,
+};
+
+use super::FontOptions;
+
+/// Min CSS font weight that is rendered with the bold variant.
+const BOLD_WEIGHT: u16 = 600;
+
+/// Measures text with CoreText. Sizes are in points, which are the same
+/// as CSS pixels.
+pub struct PlatformMeasurer {
+  /// Fonts keyed by font options, which are reused across lines.
+  fonts: HashMap<FontOptions, CTFont>,
+}
+
+impl PlatformMeasurer {
+  pub fn new() -> anyhow::Result<Self> {
+    Ok(Self {
+      fonts: HashMap::new(),
+    })
+  }
+
+  pub fn measure(
+    &mut self,
+    font: &FontOptions,
+    text: &str,
+  ) -> anyhow::Result<(f64, f64)> {
+    let ct_font = match self.fonts.get(font) {
+      Some(ct_font) => ct_font.clone(),
+      None => {
+        let mut ct_font =
+          new_from_name(&font.font_family, font.size() as f64)
+            .ok()
+            .with_context(|| {
+              format!("Font '{}' not found.", font.font_family)
+            })?;
+
+        if font.weight >= BOLD_WEIGHT {
+          if let Some(bold_font) = ct_font
+            .clone_with_symbolic_traits(kCTFontBoldTrait, kCTFontBoldTrait)
+          {
+            ct_font = bold_font;
+          }
+        }
+
+        self.fonts.insert(font.clone(), ct_font.clone());
+        ct_font
+      }
+    };
+
+    let mut string = CFMutableAttributedString::new();
+    string.replace_str(&CFString::new(text), CFRange::init(0, 0));
+
+    let range = CFRange::init(0, string.char_len());
+    string.set_attribute(range, unsafe { kCTFontAttributeName }, &ct_font);
+
+    let line = CTLine::new_with_attributed_string(
+      string.as_concrete_TypeRef() as _,
+    );
+
+    let bounds = line.get_typographic_bounds();
+
+    Ok((
+      bounds.width,
+      bounds.ascent + bounds.descent + bounds.leading,
+    ))
+  }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use windows::{
+  core::{w, HSTRING},
+  Win32::Graphics::DirectWrite::{
+    DWriteCreateFactory, IDWriteFactory, IDWriteFontCollection,
+    IDWriteTextFormat, DWRITE_FACTORY_TYPE_SHARED,
+    DWRITE_FONT_STRETCH_NORMAL, DWRITE_FONT_STYLE_NORMAL,
+    DWRITE_FONT_WEIGHT, DWRITE_TEXT_METRICS, DWRITE_WORD_WRAPPING_NO_WRAP,
+  },
+};
+
+use super::FontOptions;
+
+/// Measures text with DirectWrite. Sizes are in DIPs, which are the same
+/// as CSS pixels.
+pub struct PlatformMeasurer {
+  factory: IDWriteFactory,
+
+  /// Text formats keyed by font, which are reused across layouts.
+  formats: HashMap<FontOptions, IDWriteTextFormat>,
+}
+
+impl PlatformMeasurer {
+  pub fn new() -> anyhow::Result<Self> {
+    let factory =
+      unsafe { DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED) }?;
+
+    Ok(Self {
+      factory,
+      formats: HashMap::new(),
+    })
+  }
+
+  pub fn measure(
+    &mut self,
+    font: &FontOptions,
+    text: &str,
+  ) -> anyhow::Result<(f64, f64)> {
+    let format = match self.formats.get(font) {
+      Some(format) => format.clone(),
+      None => {
+        let format = unsafe {
+          self.factory.CreateTextFormat(
+            &HSTRING::from(&font.font_family),
+            None::<&IDWriteFontCollection>,
+            DWRITE_FONT_WEIGHT(font.weight as i32),
+            DWRITE_FONT_STYLE_NORMAL,
+            DWRITE_FONT_STRETCH_NORMAL,
+            font.size(),
+            w!("en-us"),
+          )?
+        };
+
+        unsafe { format.SetWordWrapping(DWRITE_WORD_WRAPPING_NO_WRAP) }?;
+        self.formats.insert(font.clone(), format.clone());
+        format
+      }
+    };
+
+    let text = text.encode_utf16().collect::<Vec<_>>();
+
+    let layout = unsafe {
+      self
+        .factory
+        .CreateTextLayout(&text, &format, f32::MAX, f32::MAX)
+    }?;
+
+    let mut metrics = DWRITE_TEXT_METRICS::default();
+    unsafe { layout.GetMetrics(&mut metrics) }?;
+
+    // Trailing whitespace is included, same as in the webview.
+    Ok((
+      metrics.widthIncludingTrailingWhitespace as f64,
+      metrics.height as f64,
+    ))
+  }
+}
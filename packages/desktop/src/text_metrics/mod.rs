@@ -0,0 +1,130 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "macos")]
+use self::coretext::PlatformMeasurer;
+#[cfg(target_os = "windows")]
+use self::direct_write::PlatformMeasurer;
+#[cfg(target_os = "linux")]
+use self::pango_cairo::PlatformMeasurer;
+
+#[cfg(target_os = "macos")]
+mod coretext;
+#[cfg(target_os = "windows")]
+mod direct_write;
+#[cfg(target_os = "linux")]
+mod pango_cairo;
+
+/// Max number of measurements that are cached per font. The cache of a
+/// font is cleared once it's full.
+const MAX_CACHED_PER_FONT: usize = 2048;
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct FontOptions {
+  pub font_family: String,
+
+  /// Font size in CSS pixels. Kept as bits so that fonts can be used as
+  /// cache keys.
+  #[serde(deserialize_with = "deserialize_size")]
+  pub size: u32,
+
+  /// CSS font weight (e.g. 400 for normal and 700 for bold).
+  #[serde(default = "default_weight")]
+  pub weight: u16,
+}
+
+impl FontOptions {
+  pub fn size(&self) -> f32 {
+    f32::from_bits(self.size)
+  }
+}
+
+fn default_weight() -> u16 {
+  400
+}
+
+fn deserialize_size<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> Result<u32, D::Error> {
+  f32::deserialize(deserializer).map(f32::to_bits)
+}
+
+/// Size of a measured string in physical pixels.
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct TextSize {
+  pub width: f64,
+  pub height: f64,
+}
+
+thread_local! {
+  /// Platform text APIs aren't thread-safe, so measurers and their caches
+  /// are kept per thread. Commands that measure text run on the main
+  /// thread.
+  static MEASURER: RefCell<Option<TextMeasurer>> = RefCell::new(None);
+}
+
+/// Measures text with the platform's text layout engine, so that sizes
+/// match the native font rendering (including emoji and ZWJ sequences).
+struct TextMeasurer {
+  platform: PlatformMeasurer,
+
+  /// Logical sizes keyed by font and then by text.
+  cache: HashMap<FontOptions, HashMap<String, (f64, f64)>>,
+}
+
+impl TextMeasurer {
+  fn measure(
+    &mut self,
+    font: &FontOptions,
+    text: &str,
+  ) -> anyhow::Result<(f64, f64)> {
+    let cached = self.cache.entry(font.clone()).or_default();
+
+    if let Some(size) = cached.get(text) {
+      return Ok(*size);
+    }
+
+    let size = self.platform.measure(font, text)?;
+
+    if cached.len() >= MAX_CACHED_PER_FONT {
+      cached.clear();
+    }
+
+    cached.insert(text.to_string(), size);
+    Ok(size)
+  }
+}
+
+/// Measures each of the texts when rendered in the given font. Sizes are
+/// scaled from logical to physical pixels by `scale_factor`.
+pub fn measure_texts(
+  texts: &[String],
+  font: &FontOptions,
+  scale_factor: f64,
+) -> anyhow::Result<Vec<TextSize>> {
+  MEASURER.with(|measurer| {
+    let mut measurer = measurer.borrow_mut();
+
+    let measurer = match measurer.as_mut() {
+      Some(measurer) => measurer,
+      None => measurer.insert(TextMeasurer {
+        platform: PlatformMeasurer::new()?,
+        cache: HashMap::new(),
+      }),
+    };
+
+    texts
+      .iter()
+      .map(|text| {
+        let (width, height) = measurer.measure(font, text)?;
+
+        Ok(TextSize {
+          width: width * scale_factor,
+          height: height * scale_factor,
+        })
+      })
+      .collect()
+  })
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use pango::{
+  prelude::FontMapExt, Context, FontDescription, Layout, Weight,
+};
+
+use super::FontOptions;
+
+/// Measures text with Pango. The Cairo font map has a resolution of 96
+/// DPI, so sizes are in CSS pixels.
+pub struct PlatformMeasurer {
+  context: Context,
+
+  /// Font descriptions keyed by font, which are reused across layouts.
+  descriptions: HashMap<FontOptions, FontDescription>,
+}
+
+impl PlatformMeasurer {
+  pub fn new() -> anyhow::Result<Self> {
+    let context = pangocairo::FontMap::new().create_context();
+
+    Ok(Self {
+      context,
+      descriptions: HashMap::new(),
+    })
+  }
+
+  pub fn measure(
+    &mut self,
+    font: &FontOptions,
+    text: &str,
+  ) -> anyhow::Result<(f64, f64)> {
+    let description =
+      self.descriptions.entry(font.clone()).or_insert_with(|| {
+        let mut description = FontDescription::new();
+        description.set_family(&font.font_family);
+        description
+          .set_absolute_size(font.size() as f64 * pango::SCALE as f64);
+        description.set_weight(to_pango_weight(font.weight));
+        description
+      });
+
+    let layout = Layout::new(&self.context);
+    layout.set_font_description(Some(description));
+    layout.set_text(text);
+
+    let (_, logical) = layout.extents();
+
+    Ok((
+      logical.width() as f64 / pango::SCALE as f64,
+      logical.height() as f64 / pango::SCALE as f64,
+    ))
+  }
+}
+
+fn to_pango_weight(weight: u16) -> Weight {
+  match weight {
+    0..=149 => Weight::Thin,
+    150..=249 => Weight::Ultralight,
+    250..=349 => Weight::Light,
+    350..=449 => Weight::Normal,
+    450..=549 => Weight::Medium,
+    550..=649 => Weight::Semibold,
+    650..=749 => Weight::Bold,
+    750..=849 => Weight::Ultrabold,
+    _ => Weight::Heavy,
+  }
+}
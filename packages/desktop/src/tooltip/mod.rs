@@ -0,0 +1,190 @@
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+  },
+  time::Duration,
+};
+
+use anyhow::bail;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, PhysicalPosition, Runtime, Window};
+use tokio::{task, time};
+
+#[cfg(target_os = "windows")]
+use self::native::NativeTooltip;
+use self::webview::WebviewTooltip;
+
+#[cfg(target_os = "windows")]
+mod native;
+mod webview;
+
+/// Default duration in milliseconds after which a tooltip is hidden.
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// Tooltip requested by a window via `show_tooltip`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TooltipOptions {
+  /// Plain text content. Shown as a native tooltip on Windows.
+  pub text: Option<String>,
+
+  /// Rich content, shown in an auxiliary webview. Takes precedence over
+  /// `text`.
+  pub html: Option<String>,
+
+  /// Position relative to the top-left of the window's content, in
+  /// physical pixels. Can be outside of the window's bounds.
+  pub x: i32,
+  pub y: i32,
+
+  /// Logical size of webview tooltips, since they aren't sized to their
+  /// content.
+  pub width: Option<f64>,
+  pub height: Option<f64>,
+
+  /// Duration in milliseconds after which the tooltip is hidden. Set to
+  /// 0 to only hide it via `hide_tooltip`.
+  pub timeout: Option<u64>,
+}
+
+enum TooltipHandle {
+  #[cfg(target_os = "windows")]
+  Native(NativeTooltip),
+  Webview(WebviewTooltip),
+}
+
+struct ActiveTooltip {
+  /// Unique ID, so that a timeout doesn't hide a tooltip that has since
+  /// been replaced.
+  id: u64,
+  handle: TooltipHandle,
+}
+
+/// Tooltips that are currently shown, keyed by the label of the window
+/// that they belong to. Each window shows at most one tooltip.
+#[derive(Default)]
+pub struct Tooltips {
+  active: Mutex<HashMap<String, ActiveTooltip>>,
+  next_id: AtomicU64,
+}
+
+/// Whether the window with the given label is a webview tooltip rather
+/// than a window opened from the config.
+pub fn is_tooltip_label(label: &str) -> bool {
+  label.starts_with(webview::LABEL_PREFIX)
+}
+
+/// Shows a tooltip for the window, replacing any tooltip that it's
+/// already showing.
+///
+/// Tooltips are top-level windows, so they aren't clipped by the bounds
+/// of the window. They're never focused and let mouse events through to
+/// whatever is beneath them.
+pub async fn show_tooltip<R: Runtime>(
+  window: &Window<R>,
+  options: TooltipOptions,
+) -> anyhow::Result<()> {
+  let app_handle = window.app_handle();
+  let tooltips = app_handle.state::<Tooltips>();
+
+  hide_tooltip(app_handle, window.label());
+
+  let origin = window.inner_position()?;
+  let position =
+    PhysicalPosition::new(origin.x + options.x, origin.y + options.y);
+
+  let id = tooltips.next_id.fetch_add(1, Ordering::Relaxed);
+  let size = (options.width, options.height);
+
+  let handle = match (&options.html, &options.text) {
+    (Some(html), _) => TooltipHandle::Webview(WebviewTooltip::show(
+      window, id, html, position, size,
+    )?),
+    (None, Some(text)) => show_text(window, id, text, position).await?,
+    (None, None) => bail!("Tooltip requires either `text` or `html`."),
+  };
+
+  tooltips
+    .active
+    .lock()
+    .unwrap()
+    .insert(window.label().to_string(), ActiveTooltip { id, handle });
+
+  let timeout = options.timeout.unwrap_or(DEFAULT_TIMEOUT_MS);
+
+  if timeout != 0 {
+    let app_handle = app_handle.clone();
+    let window_label = window.label().to_string();
+
+    task::spawn(async move {
+      time::sleep(Duration::from_millis(timeout)).await;
+      remove_tooltip(&app_handle, &window_label, Some(id));
+    });
+  }
+
+  Ok(())
+}
+
+/// Hides the tooltip of the window, if it's showing one.
+pub fn hide_tooltip<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  window_label: &str,
+) {
+  remove_tooltip(app_handle, window_label, None);
+}
+
+/// Hides the tooltip of the window. If `id` is given, the tooltip is
+/// only hidden if it hasn't been replaced.
+fn remove_tooltip<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  window_label: &str,
+  id: Option<u64>,
+) {
+  let Some(tooltips) = app_handle.try_state::<Tooltips>() else {
+    return;
+  };
+
+  let tooltip = {
+    let mut active = tooltips.active.lock().unwrap();
+
+    match active.get(window_label) {
+      Some(tooltip) if id.map_or(true, |id| id == tooltip.id) => {
+        active.remove(window_label)
+      }
+      _ => None,
+    }
+  };
+
+  match tooltip.map(|tooltip| tooltip.handle) {
+    #[cfg(target_os = "windows")]
+    Some(TooltipHandle::Native(tooltip)) => tooltip.hide(app_handle),
+    Some(TooltipHandle::Webview(tooltip)) => tooltip.hide(app_handle),
+    None => {}
+  }
+}
+
+#[cfg(target_os = "windows")]
+async fn show_text<R: Runtime>(
+  window: &Window<R>,
+  _id: u64,
+  text: &str,
+  position: PhysicalPosition<i32>,
+) -> anyhow::Result<TooltipHandle> {
+  let tooltip = NativeTooltip::show(window, text, position).await?;
+  Ok(TooltipHandle::Native(tooltip))
+}
+
+/// Plain text tooltips are shown in a webview on platforms without a
+/// native tooltip that can be shown at an arbitrary position.
+#[cfg(not(target_os = "windows"))]
+async fn show_text<R: Runtime>(
+  window: &Window<R>,
+  id: u64,
+  text: &str,
+  position: PhysicalPosition<i32>,
+) -> anyhow::Result<TooltipHandle> {
+  let tooltip = WebviewTooltip::show_text(window, id, text, position)?;
+  Ok(TooltipHandle::Webview(tooltip))
+}
@@ -0,0 +1,122 @@
+use std::{iter, mem};
+
+use anyhow::{bail, Context};
+use tauri::{AppHandle, PhysicalPosition, Runtime, Window};
+use tokio::sync::oneshot;
+use windows::{
+  core::{PCWSTR, PWSTR},
+  Win32::{
+    Foundation::{HINSTANCE, HWND, LPARAM, WPARAM},
+    UI::{
+      Controls::{
+        TOOLTIPS_CLASSW, TTF_ABSOLUTE, TTF_TRACK, TTM_ADDTOOLW,
+        TTM_SETMAXTIPWIDTH, TTM_TRACKACTIVATE, TTM_TRACKPOSITION,
+        TTS_ALWAYSTIP, TTS_NOPREFIX, TTTOOLINFOW,
+      },
+      WindowsAndMessaging::{
+        CreateWindowExW, DestroyWindow, SendMessageW, CW_USEDEFAULT,
+        HMENU, WINDOW_STYLE, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
+        WS_EX_TOPMOST, WS_POPUP,
+      },
+    },
+  },
+};
+
+/// Width in pixels after which tooltip text is wrapped. Also enables
+/// line breaks in the text.
+const MAX_TIP_WIDTH: isize = 400;
+
+/// Tracking tooltip control, which is shown at an absolute screen
+/// position without being activated.
+pub struct NativeTooltip {
+  hwnd: HWND,
+}
+
+impl NativeTooltip {
+  /// Shows the text at the given screen position.
+  ///
+  /// Tooltip controls belong to the thread that creates them, so they're
+  /// created and destroyed on the main thread.
+  pub async fn show<R: Runtime>(
+    window: &Window<R>,
+    text: &str,
+    position: PhysicalPosition<i32>,
+  ) -> anyhow::Result<Self> {
+    let owner = window.hwnd().context("Failed to get window handle.")?;
+    let text = text.to_string();
+    let (hwnd_tx, hwnd_rx) = oneshot::channel();
+
+    window.run_on_main_thread(move || {
+      _ = hwnd_tx.send(unsafe { create_tooltip(owner, &text, position) });
+    })?;
+
+    let hwnd = hwnd_rx.await??;
+
+    Ok(Self { hwnd })
+  }
+
+  pub fn hide<R: Runtime>(self, app_handle: &AppHandle<R>) {
+    let hwnd = self.hwnd;
+
+    _ = app_handle.run_on_main_thread(move || {
+      _ = unsafe { DestroyWindow(hwnd) };
+    });
+  }
+}
+
+unsafe fn create_tooltip(
+  owner: HWND,
+  text: &str,
+  position: PhysicalPosition<i32>,
+) -> anyhow::Result<HWND> {
+  let hwnd = CreateWindowExW(
+    WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+    TOOLTIPS_CLASSW,
+    PCWSTR::null(),
+    WS_POPUP | WINDOW_STYLE(TTS_NOPREFIX | TTS_ALWAYSTIP),
+    CW_USEDEFAULT,
+    CW_USEDEFAULT,
+    CW_USEDEFAULT,
+    CW_USEDEFAULT,
+    owner,
+    HMENU::default(),
+    HINSTANCE::default(),
+    None,
+  );
+
+  if hwnd.0 == 0 {
+    bail!("Failed to create tooltip window.");
+  }
+
+  // The text is copied by the control when the tool is added.
+  let mut text =
+    text.encode_utf16().chain(iter::once(0)).collect::<Vec<_>>();
+
+  let tool_info = TTTOOLINFOW {
+    cbSize: mem::size_of::<TTTOOLINFOW>() as u32,
+    uFlags: TTF_TRACK | TTF_ABSOLUTE,
+    hwnd: owner,
+    lpszText: PWSTR(text.as_mut_ptr()),
+    ..Default::default()
+  };
+
+  let tool_info_ptr = LPARAM(&tool_info as *const _ as isize);
+
+  SendMessageW(hwnd, TTM_ADDTOOLW, WPARAM(0), tool_info_ptr);
+  SendMessageW(hwnd, TTM_SETMAXTIPWIDTH, WPARAM(0), LPARAM(MAX_TIP_WIDTH));
+
+  // Coordinates are packed into the low and high words.
+  let packed_position =
+    (position.x as u16 as u32) | ((position.y as u16 as u32) << 16);
+
+  SendMessageW(
+    hwnd,
+    TTM_TRACKPOSITION,
+    WPARAM(0),
+    LPARAM(packed_position as isize),
+  );
+
+  SendMessageW(hwnd, TTM_TRACKACTIVATE, WPARAM(1), tool_info_ptr);
+
+  Ok(hwnd)
+}
@@ -0,0 +1,137 @@
+use tauri::{
+  AppHandle, Manager, PhysicalPosition, Runtime, Url, WebviewUrl,
+  WebviewWindowBuilder, Window,
+};
+
+use crate::webview_profile;
+
+/// Prefix of the labels of tooltip windows.
+pub const LABEL_PREFIX: &str = "tooltip_";
+
+/// Logical size of webview tooltips if none is given.
+const DEFAULT_WIDTH: f64 = 240.;
+const DEFAULT_HEIGHT: f64 = 80.;
+
+/// Tooltip shown in an auxiliary always-on-top webview window.
+pub struct WebviewTooltip {
+  label: String,
+}
+
+impl WebviewTooltip {
+  /// Opens a webview with the given HTML at the given screen position.
+  ///
+  /// The webview loads the HTML from a data URL, so it doesn't match any
+  /// capability and has no access to commands.
+  pub fn show<R: Runtime>(
+    window: &Window<R>,
+    id: u64,
+    html: &str,
+    position: PhysicalPosition<i32>,
+    (width, height): (Option<f64>, Option<f64>),
+  ) -> anyhow::Result<Self> {
+    let scale_factor = window.scale_factor()?;
+    let label = format!("{}{}", LABEL_PREFIX, id);
+
+    let url = Url::parse(&format!(
+      "data:text/html;charset=utf-8,{}",
+      percent_encode(&document(html))
+    ))?;
+
+    let builder = WebviewWindowBuilder::new(
+      window.app_handle(),
+      &label,
+      WebviewUrl::External(url),
+    )
+    .title("Zebar - tooltip")
+    .position(
+      position.x as f64 / scale_factor,
+      position.y as f64 / scale_factor,
+    )
+    .inner_size(
+      width.unwrap_or(DEFAULT_WIDTH),
+      height.unwrap_or(DEFAULT_HEIGHT),
+    )
+    // Shown without being activated, so that focus stays with the app
+    // under the cursor.
+    .focused(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible_on_all_workspaces(true)
+    .transparent(true)
+    .shadow(false)
+    .decorations(false)
+    .resizable(false);
+
+    let tooltip = webview_profile::shared_profile(builder).build()?;
+
+    // Mouse events pass through to whatever is beneath the tooltip.
+    tooltip.set_ignore_cursor_events(true)?;
+
+    Ok(Self { label })
+  }
+
+  /// Opens a webview with the given text, styled as a tooltip.
+  #[cfg(not(target_os = "windows"))]
+  pub fn show_text<R: Runtime>(
+    window: &Window<R>,
+    id: u64,
+    text: &str,
+    position: PhysicalPosition<i32>,
+  ) -> anyhow::Result<Self> {
+    let html = format!(
+      "<div style=\"display: inline-block; padding: 4px 8px; \
+      border-radius: 4px; background: rgba(32, 32, 32, 0.9); \
+      color: #fff; font: 12px sans-serif; white-space: pre-wrap\">\
+      {}</div>",
+      escape_html(text)
+    );
+
+    Self::show(window, id, &html, position, (None, None))
+  }
+
+  pub fn hide<R: Runtime>(self, app_handle: &AppHandle<R>) {
+    if let Some(tooltip) = app_handle.get_webview_window(&self.label) {
+      _ = tooltip.close();
+    }
+  }
+}
+
+/// Wraps the HTML in a document with a transparent background.
+fn document(html: &str) -> String {
+  format!(
+    "<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head>\
+    <body style=\"margin: 0; overflow: hidden; background: transparent\">\
+    {}</body></html>",
+    html
+  )
+}
+
+/// Percent-encodes everything except unreserved characters, since `#`
+/// and `%` would otherwise be misread in data URLs.
+fn percent_encode(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+
+  for byte in value.bytes() {
+    match byte {
+      b'A'..=b'Z'
+      | b'a'..=b'z'
+      | b'0'..=b'9'
+      | b'-'
+      | b'.'
+      | b'_'
+      | b'~' => encoded.push(byte as char),
+      _ => encoded.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+
+  encoded
+}
+
+#[cfg(not(target_os = "windows"))]
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
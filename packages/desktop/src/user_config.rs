@@ -1,9 +1,385 @@
-use std::{fs, path::PathBuf};
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicU64, Ordering},
+  thread,
+  time::{Duration, SystemTime},
+};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
+use serde::Deserialize;
 use tauri::{path::BaseDirectory, AppHandle, Manager};
+use tokio::task;
+use tracing::warn;
+
+use crate::{
+  locale::Locale,
+  permissions::WindowPermissions,
+  providers::{config::ProviderConfig, redaction::RedactionConfig},
+  secrets::{self, SecretsBackend},
+  util::text_encoding,
+};
+
+/// Subset of the user config that is used on the Rust side.
+///
+/// Most of the config is only relevant to the frontend, so unknown fields
+/// are ignored.
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct UserConfig {
+  /// Top-level entries of the config (e.g. `window/bar`).
+  #[serde(flatten)]
+  entries: HashMap<String, serde_yaml::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GlobalConfig {
+  /// Whether to block all outbound network requests from providers.
+  #[serde(default)]
+  pub offline_mode: bool,
+
+  /// Delay in milliseconds before the first run of each provider.
+  #[serde(default)]
+  pub provider_start_delay: u64,
+
+  /// Max random delay in milliseconds that is added to the start delay
+  /// of each provider. Staggers the first runs of providers that are
+  /// started at the same time (e.g. when opening windows at login).
+  #[serde(default = "default_provider_jitter")]
+  pub provider_jitter: u64,
+
+  /// Whether to emit provider outputs from a dedicated thread with
+  /// above-normal priority. Reduces stutter of widgets under heavy CPU
+  /// load. Only has an effect on Windows.
+  #[serde(default)]
+  pub high_priority_emit: bool,
+
+  /// Whether to include the relative age of provider outputs (e.g. `3m
+  /// ago`) as `lastUpdatedRelative`, which is kept up to date without the
+  /// providers refreshing.
+  #[serde(default)]
+  pub last_updated_relative: bool,
+
+  /// Duration in milliseconds after which a provider run is logged as
+  /// slow. Set to 0 to disable.
+  #[serde(default = "default_slow_provider_threshold")]
+  pub slow_provider_threshold: u64,
+
+  /// Size in megabytes of the state that a single provider retains
+  /// (e.g. history buffers and caches) above which a warning is logged.
+  /// Set to 0 to disable.
+  #[serde(default = "default_provider_memory_cap")]
+  pub provider_memory_cap: u64,
+
+  /// Whether to clear the caches of providers that exceed
+  /// `provider_memory_cap`.
+  #[serde(default)]
+  pub evict_over_memory_cap: bool,
+
+  /// How network-hungry providers (e.g. `weather` and `ip`) refresh
+  /// while the active connection is metered.
+  #[serde(default)]
+  pub metered_behavior: MeteredBehavior,
+
+  /// Factor that the refresh intervals of network-hungry providers are
+  /// multiplied by when `metered_behavior` is `throttle`.
+  #[serde(default = "default_metered_interval_multiplier")]
+  pub metered_interval_multiplier: u64,
+
+  /// Factor that the refresh intervals of interval providers are
+  /// multiplied by while energy saver is on. `1` to refresh as normal.
+  #[serde(default = "default_energy_saver_interval_multiplier")]
+  pub energy_saver_interval_multiplier: u64,
+
+  /// Where secrets that are referenced via `{ secret: "<name>" }` are
+  /// stored.
+  #[serde(default)]
+  pub secrets_backend: SecretsBackend,
+
+  /// Hosts that each secret may be sent to by HTTP requests from
+  /// windows, keyed by secret name (e.g. `github_token:
+  /// [api.github.com]`). Hosts can start with `*.` to match subdomains.
+  /// Secrets without hosts can't be used in requests.
+  #[serde(default)]
+  pub secret_hosts: HashMap<String, Vec<String>>,
+
+  /// Locale that strings formatted by the app (e.g. the relative age of
+  /// provider outputs) are localized to. Defaults to the OS locale.
+  #[serde(default)]
+  pub locale: Option<Locale>,
+
+  /// Whether each window gets its own webview user data folder and
+  /// browser process, instead of sharing them with other windows. Uses
+  /// more memory, so it's mainly for debugging. Applies to windows
+  /// opened afterwards.
+  #[serde(default)]
+  pub isolated_webviews: bool,
+
+  /// Provider fields that are masked in outputs sent to windows while
+  /// redaction is enabled (e.g. when streaming).
+  #[serde(default)]
+  pub redaction: RedactionConfig,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteredBehavior {
+  /// Refresh as normal.
+  #[default]
+  Ignore,
+
+  /// Refresh less often, by `metered_interval_multiplier`.
+  Throttle,
+
+  /// Stop refreshing until back on an unmetered connection.
+  Pause,
+}
+
+impl Default for GlobalConfig {
+  fn default() -> Self {
+    Self {
+      offline_mode: false,
+      provider_start_delay: 0,
+      provider_jitter: default_provider_jitter(),
+      high_priority_emit: false,
+      last_updated_relative: false,
+      slow_provider_threshold: default_slow_provider_threshold(),
+      provider_memory_cap: default_provider_memory_cap(),
+      evict_over_memory_cap: false,
+      metered_behavior: MeteredBehavior::default(),
+      metered_interval_multiplier: default_metered_interval_multiplier(),
+      energy_saver_interval_multiplier:
+        default_energy_saver_interval_multiplier(),
+      secrets_backend: SecretsBackend::default(),
+      secret_hosts: HashMap::new(),
+      locale: None,
+      isolated_webviews: false,
+      redaction: RedactionConfig::default(),
+    }
+  }
+}
+
+fn default_provider_jitter() -> u64 {
+  500
+}
+
+fn default_slow_provider_threshold() -> u64 {
+  1000
+}
+
+fn default_provider_memory_cap() -> u64 {
+  64
+}
+
+fn default_metered_interval_multiplier() -> u64 {
+  4
+}
+
+fn default_energy_saver_interval_multiplier() -> u64 {
+  1
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct DaemonConfig {
+  /// Providers to run in daemon mode, keyed by name. Kept unparsed
+  /// until secret references are resolved.
+  #[serde(default)]
+  pub providers: HashMap<String, serde_json::Value>,
+}
+
+/// Named shorthand for opening a window with a set of arguments (i.e.
+/// `zebar open @<name>`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct PresetConfig {
+  /// ID of the window to open.
+  pub window: String,
+
+  #[serde(default)]
+  pub args: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct WindowConfig {
+  /// URL of a custom frontend to load instead of the bundled one.
+  pub url: Option<String>,
+
+  /// Whether `url` is allowed to point to hosts other than localhost.
+  #[serde(default)]
+  pub allow_remote_url: bool,
+
+  /// Capabilities granted to the window. Defaults to least-privilege for
+  /// windows with a custom frontend.
+  pub permissions: Option<WindowPermissions>,
+
+  /// Width of the window in physical pixels. Only used as the initial
+  /// size if it's a plain number (i.e. not a template).
+  #[serde(default, deserialize_with = "deserialize_plain_number")]
+  pub width: Option<f64>,
+
+  /// Height of the window in physical pixels. Only used as the initial
+  /// size if it's a plain number (i.e. not a template).
+  #[serde(default, deserialize_with = "deserialize_plain_number")]
+  pub height: Option<f64>,
+
+  /// Providers declared on the window, which are pre-created when the
+  /// window is opened.
+  #[serde(default)]
+  pub providers: Vec<serde_json::Value>,
+
+  /// CSS to inject into the window, either inline or a path to a `.css`
+  /// file within the `assets/` folder of the config directory.
+  pub custom_css: Option<String>,
+
+  /// JS to run in the window, either inline or a path to a `.js` file
+  /// within the `assets/` folder of the config directory.
+  pub custom_js: Option<String>,
+}
+
+/// Deserializes a number or a numeric string. Other values (e.g.
+/// templates that are rendered by the frontend) are ignored.
+fn deserialize_plain_number<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+  let value = serde_yaml::Value::deserialize(deserializer)?;
+
+  Ok(match value {
+    serde_yaml::Value::Number(number) => number.as_f64(),
+    serde_yaml::Value::String(string) => string.trim().parse().ok(),
+    _ => None,
+  })
+}
+
+impl UserConfig {
+  /// Gets the `global` entry of the config.
+  pub fn global(&self) -> anyhow::Result<GlobalConfig> {
+    match self.entries.get("global") {
+      Some(value) => serde_yaml::from_value(value.clone())
+        .context("Invalid global config."),
+      None => Ok(GlobalConfig::default()),
+    }
+  }
+
+  /// Gets the `daemon` entry of the config.
+  pub fn daemon(&self) -> anyhow::Result<DaemonConfig> {
+    let daemon_config = match self.entries.get("daemon") {
+      Some(value) => serde_yaml::from_value::<DaemonConfig>(value.clone())
+        .context("Invalid daemon config.")?,
+      None => DaemonConfig::default(),
+    };
+
+    for (name, config) in &daemon_config.providers {
+      ProviderConfig::deserialize(secrets::mask_refs(config))
+        .with_context(|| format!("Invalid daemon provider '{}'.", name))?;
+    }
+
+    Ok(daemon_config)
+  }
+
+  /// Gets the `presets` entry of the config.
+  pub fn presets(&self) -> anyhow::Result<HashMap<String, PresetConfig>> {
+    match self.entries.get("presets") {
+      Some(value) => serde_yaml::from_value(value.clone())
+        .context("Invalid presets config."),
+      None => Ok(HashMap::new()),
+    }
+  }
+
+  /// Validates the entries that are used on the Rust side. Returns an
+  /// error message for each invalid entry.
+  pub fn validate(&self) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    let mut check = |res: anyhow::Result<()>| {
+      if let Err(err) = res {
+        errors.push(format!("{:#}", err));
+      }
+    };
+
+    check(self.global().map(|_| ()));
+    check(self.presets().map(|_| ()));
+    check(self.daemon().map(|_| ()));
+
+    for window_id in self
+      .entries
+      .keys()
+      .filter_map(|key| key.strip_prefix("window/"))
+    {
+      check(self.window(window_id).map(|_| ()));
+    }
+
+    errors
+  }
+
+  /// Gets the keys of top-level entries that differ from `other`,
+  /// including entries that were added or removed.
+  pub fn changed_entries(&self, other: &UserConfig) -> Vec<String> {
+    let mut changed = self
+      .entries
+      .keys()
+      .chain(other.entries.keys())
+      .filter(|key| self.entries.get(*key) != other.entries.get(*key))
+      .cloned()
+      .collect::<Vec<_>>();
+
+    changed.sort();
+    changed.dedup();
+    changed
+  }
+
+  /// Whether a window with the given ID is defined (i.e. there's a
+  /// `window/<id>` entry).
+  pub fn has_window(&self, window_id: &str) -> bool {
+    self.entries.contains_key(&format!("window/{}", window_id))
+  }
+
+  /// Gets the config for the window with the given ID (i.e. the
+  /// `window/<id>` entry).
+  pub fn window(&self, window_id: &str) -> anyhow::Result<WindowConfig> {
+    match self.entries.get(&format!("window/{}", window_id)) {
+      Some(value) => {
+        serde_yaml::from_value(value.clone()).with_context(|| {
+          format!("Invalid config for window '{}'.", window_id)
+        })
+      }
+      None => Ok(WindowConfig::default()),
+    }
+  }
+}
+
+/// Reads and parses the config file at `~/.glzr/zebar/config.yaml`.
+pub fn read_parsed(app_handle: AppHandle) -> anyhow::Result<UserConfig> {
+  let config_str = read_file(None, app_handle)?;
+
+  serde_yaml::from_str(&config_str).context("Unable to parse config file.")
+}
+
+/// Same as [`read_parsed`], but on a blocking thread, since reads of an
+/// incomplete file are retried with delays.
+pub async fn read_parsed_async(
+  app_handle: AppHandle,
+) -> anyhow::Result<UserConfig> {
+  task::spawn_blocking(move || read_parsed(app_handle)).await?
+}
+
+/// Number of attempts at reading a config file that looks incomplete.
+const READ_ATTEMPTS: u32 = 5;
+
+/// Base delay between attempts at reading the config file. Grows
+/// linearly with each attempt.
+const READ_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// How recently the config file has to have been modified for invalid
+/// contents to be retried, since it might still be being written.
+const WRITE_SETTLE_TIME: Duration = Duration::from_secs(1);
+
+/// Size of the config file when it was last read successfully.
+static LAST_READ_LEN: AtomicU64 = AtomicU64::new(0);
 
 /// Reads the config file at `~/.glzr/zebar/config.yaml`.
+///
+/// Files saved as UTF-16 or with a BOM (e.g. by Notepad) are converted
+/// to UTF-8, and line endings are normalized to `\n`. Reads that look
+/// incomplete are retried (see [`read_settled`]).
 pub fn read_file(
   config_path_override: Option<&str>,
   app_handle: AppHandle,
@@ -23,7 +399,138 @@ pub fn read_file(
     create_from_sample(&config_path, app_handle)?;
   }
 
-  fs::read_to_string(&config_path).context("Unable to read config file.")
+  read_settled(&config_path, &LAST_READ_LEN)
+}
+
+/// Same as [`read_file`], but on a blocking thread, since reads of an
+/// incomplete file are retried with delays.
+pub async fn read_file_async(
+  config_path_override: Option<String>,
+  app_handle: AppHandle,
+) -> anyhow::Result<String> {
+  task::spawn_blocking(move || {
+    read_file(config_path_override.as_deref(), app_handle)
+  })
+  .await?
+}
+
+/// Reads and decodes the config file, retrying a few times with short
+/// delays if the contents look incomplete.
+///
+/// Config files in synced folders (e.g. OneDrive) can be read while the
+/// sync client is still writing them, in which case they're empty or cut
+/// off. A read is retried if it's empty while the last successful read
+/// wasn't, or if the file size changed during the read.
+///
+/// Invalid text or YAML is only retried while the file is still being
+/// written, i.e. if it was modified within the last second or its size
+/// or modification time changed since the previous attempt. Otherwise,
+/// it's most likely a mistake in the config, and its contents are
+/// returned as is so that the actual parse error surfaces.
+///
+/// `last_read_len` is the size of the last successful read, and is
+/// updated on success.
+fn read_settled(
+  config_path: &Path,
+  last_read_len: &AtomicU64,
+) -> anyhow::Result<String> {
+  let mut attempt = 1;
+  let mut last_stamp = None;
+
+  loop {
+    let bytes =
+      fs::read(config_path).context("Unable to read config file.")?;
+
+    let text = text_encoding::decode_text(&bytes);
+    let stamp = file_stamp(config_path);
+
+    let is_writing = match (stamp, last_stamp) {
+      (Some(stamp), Some(last_stamp)) if stamp != last_stamp => true,
+      (Some((_, modified)), _) => modified
+        .elapsed()
+        .is_ok_and(|elapsed| elapsed < WRITE_SETTLE_TIME),
+      (None, _) => false,
+    };
+
+    let Some(reason) = incomplete_reason(
+      config_path,
+      &bytes,
+      text.as_deref().ok(),
+      last_read_len.load(Ordering::Relaxed),
+      is_writing,
+    ) else {
+      let text = text.with_context(|| {
+        format!("Unable to decode config file {}.", config_path.display())
+      })?;
+
+      last_read_len.store(bytes.len() as u64, Ordering::Relaxed);
+      return Ok(text);
+    };
+
+    if attempt >= READ_ATTEMPTS {
+      if bytes.is_empty() {
+        bail!(
+          "Config file {} is empty. It might still be syncing.",
+          config_path.display()
+        );
+      }
+
+      return text.with_context(|| {
+        format!("Unable to decode config file {}.", config_path.display())
+      });
+    }
+
+    warn!(
+      "Config file {} looks incomplete ({}). Retrying read ({}/{}).",
+      config_path.display(),
+      reason,
+      attempt,
+      READ_ATTEMPTS,
+    );
+
+    thread::sleep(READ_RETRY_DELAY * attempt);
+    last_stamp = stamp;
+    attempt += 1;
+  }
+}
+
+/// Gets the size and modification time of the file.
+fn file_stamp(path: &Path) -> Option<(u64, SystemTime)> {
+  let metadata = fs::metadata(path).ok()?;
+  Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// Gets why a read of the config file looks incomplete, if it does.
+///
+/// Invalid contents are only considered incomplete if the file is still
+/// being written.
+fn incomplete_reason(
+  config_path: &Path,
+  bytes: &[u8],
+  text: Option<&str>,
+  last_read_len: u64,
+  is_writing: bool,
+) -> Option<&'static str> {
+  if bytes.is_empty() {
+    return (last_read_len > 0).then_some("file is empty");
+  }
+
+  let len = fs::metadata(config_path).map(|metadata| metadata.len());
+
+  if len.is_ok_and(|len| len != bytes.len() as u64) {
+    return Some("file size changed while reading");
+  }
+
+  if !is_writing {
+    return None;
+  }
+
+  match text {
+    None => Some("file isn't valid text"),
+    Some(text) => serde_yaml::from_str::<serde_yaml::Value>(text)
+      .is_err()
+      .then_some("file isn't valid YAML"),
+  }
 }
 
 /// Initialize config at the given path from the sample config resource.
@@ -64,12 +571,21 @@ fn create_from_sample(
   Ok(())
 }
 
-pub fn open_config_dir(app_handle: &AppHandle) -> anyhow::Result<()> {
-  let dir_path = app_handle
+/// Gets the path to the config directory at `~/.glzr/zebar`.
+pub fn config_dir(app_handle: &AppHandle) -> anyhow::Result<PathBuf> {
+  app_handle
     .path()
     .resolve(".glzr/zebar", BaseDirectory::Home)
-    .context("Unable to get home directory.")?
-    .canonicalize()?;
+    .context("Unable to get home directory.")
+}
+
+/// Gets the path to the assets directory at `~/.glzr/zebar/assets`.
+pub fn assets_dir(app_handle: &AppHandle) -> anyhow::Result<PathBuf> {
+  Ok(config_dir(app_handle)?.join("assets"))
+}
+
+pub fn open_config_dir(app_handle: &AppHandle) -> anyhow::Result<()> {
+  let dir_path = config_dir(app_handle)?.canonicalize()?;
 
   #[cfg(target_os = "windows")]
   {
@@ -95,3 +611,175 @@ pub fn open_config_dir(app_handle: &AppHandle) -> anyhow::Result<()> {
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use std::process;
+
+  use super::*;
+
+  /// Path of a file in the temp dir that's unique to the test.
+  fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+      "zebar-user-config-{}-{}.yaml",
+      process::id(),
+      name
+    ))
+  }
+
+  #[test]
+  fn reads_complete_file() {
+    let path = temp_path("complete");
+    fs::write(&path, "widgets:\r\n  bar: {}\r\n").unwrap();
+
+    let last_read_len = AtomicU64::new(0);
+    let text = read_settled(&path, &last_read_len).unwrap();
+
+    assert_eq!(text, "widgets:\n  bar: {}\n");
+    assert_eq!(last_read_len.load(Ordering::Relaxed), 21);
+
+    fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn empty_file_is_read_if_it_was_empty_before() {
+    let path = temp_path("empty-before");
+    fs::write(&path, "").unwrap();
+
+    let text = read_settled(&path, &AtomicU64::new(0)).unwrap();
+
+    assert_eq!(text, "");
+
+    fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn empty_file_is_retried_until_written() {
+    let path = temp_path("empty-then-written");
+    fs::write(&path, "").unwrap();
+
+    let writer = {
+      let path = path.clone();
+
+      thread::spawn(move || {
+        thread::sleep(READ_RETRY_DELAY * 2);
+        fs::write(path, "widgets: {}\n").unwrap();
+      })
+    };
+
+    let text = read_settled(&path, &AtomicU64::new(100)).unwrap();
+    writer.join().unwrap();
+
+    assert_eq!(text, "widgets: {}\n");
+
+    fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn empty_file_fails_after_retries() {
+    let path = temp_path("empty-after");
+    fs::write(&path, "").unwrap();
+
+    let err = read_settled(&path, &AtomicU64::new(100)).unwrap_err();
+
+    assert!(err.to_string().contains("might still be syncing"));
+
+    fs::remove_file(path).unwrap();
+  }
+
+  /// Sets the modification time of the file to a minute ago, so that it
+  /// isn't considered to still be written.
+  fn set_settled(path: &Path) {
+    fs::File::options()
+      .write(true)
+      .open(path)
+      .unwrap()
+      .set_modified(SystemTime::now() - Duration::from_secs(60))
+      .unwrap();
+  }
+
+  #[test]
+  fn invalid_yaml_is_returned_without_retries() {
+    let path = temp_path("invalid-yaml");
+    fs::write(&path, "widgets: [bar\n").unwrap();
+    set_settled(&path);
+
+    let start = std::time::Instant::now();
+    let text = read_settled(&path, &AtomicU64::new(100)).unwrap();
+
+    assert_eq!(text, "widgets: [bar\n");
+    assert!(start.elapsed() < READ_RETRY_DELAY);
+
+    fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn partial_write_is_retried_until_complete() {
+    let path = temp_path("partial-write");
+    let contents = "widgets: [bar, settings, notifications, clock]\n";
+    let (first_half, second_half) = contents.split_at(contents.len() / 2);
+    fs::write(&path, first_half).unwrap();
+
+    let writer = {
+      let path = path.clone();
+      let second_half = second_half.to_string();
+
+      thread::spawn(move || {
+        thread::sleep(READ_RETRY_DELAY * 2);
+
+        let mut file =
+          fs::File::options().append(true).open(path).unwrap();
+
+        std::io::Write::write_all(&mut file, second_half.as_bytes())
+          .unwrap();
+      })
+    };
+
+    let text = read_settled(&path, &AtomicU64::new(100)).unwrap();
+    writer.join().unwrap();
+
+    assert_eq!(text, contents);
+
+    fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn incomplete_reasons() {
+    let path = temp_path("reasons");
+    fs::write(&path, "a: 1\n").unwrap();
+
+    assert_eq!(
+      incomplete_reason(&path, b"a: 1\n", Some("a: 1\n"), 0, true),
+      None
+    );
+    assert_eq!(
+      incomplete_reason(&path, b"a:", Some("a:"), 0, true),
+      Some("file size changed while reading")
+    );
+    assert_eq!(
+      incomplete_reason(&path, b"a: \xFF\n", None, 0, true),
+      Some("file isn't valid text")
+    );
+    assert_eq!(
+      incomplete_reason(&path, b"a: [", Some("a: ["), 0, true),
+      Some("file size changed while reading")
+    );
+    assert_eq!(incomplete_reason(&path, b"", Some(""), 0, true), None);
+    assert_eq!(
+      incomplete_reason(&path, b"", Some(""), 5, true),
+      Some("file is empty")
+    );
+
+    fs::write(&path, "a: [").unwrap();
+    assert_eq!(
+      incomplete_reason(&path, b"a: [", Some("a: ["), 0, true),
+      Some("file isn't valid YAML")
+    );
+    assert_eq!(
+      incomplete_reason(&path, b"a: [", Some("a: ["), 0, false),
+      None
+    );
+
+    fs::remove_file(path).unwrap();
+  }
+}
@@ -1 +1,5 @@
+#[cfg(target_os = "windows")]
+pub mod registry;
+pub mod text_encoding;
+pub mod thread_priority;
 pub mod window_ext;
@@ -0,0 +1,130 @@
+use windows::{
+  core::PCWSTR,
+  Win32::System::Registry::{
+    RegGetValueW, HKEY, RRF_RT_REG_BINARY, RRF_RT_REG_DWORD, RRF_RT_REG_SZ,
+  },
+};
+
+/// Reads a DWORD value from the registry. Returns `None` if the key or
+/// value doesn't exist or isn't readable.
+pub fn read_registry_dword(
+  hkey: HKEY,
+  sub_key: PCWSTR,
+  value: PCWSTR,
+) -> Option<u32> {
+  let mut data = 0u32;
+  let mut data_size = std::mem::size_of::<u32>() as u32;
+
+  let res = unsafe {
+    RegGetValueW(
+      hkey,
+      sub_key,
+      value,
+      RRF_RT_REG_DWORD,
+      None,
+      Some(&mut data as *mut _ as _),
+      Some(&mut data_size),
+    )
+  };
+
+  res.is_ok().then_some(data)
+}
+
+/// Reads a binary value from the registry. Returns `None` if the key or
+/// value doesn't exist or isn't readable.
+pub fn read_registry_binary(
+  hkey: HKEY,
+  sub_key: PCWSTR,
+  value: PCWSTR,
+) -> Option<Vec<u8>> {
+  let mut data_size = 0u32;
+
+  // Get the size of the value first.
+  let res = unsafe {
+    RegGetValueW(
+      hkey,
+      sub_key,
+      value,
+      RRF_RT_REG_BINARY,
+      None,
+      None,
+      Some(&mut data_size),
+    )
+  };
+
+  if res.is_err() {
+    return None;
+  }
+
+  let mut data = vec![0u8; data_size as usize];
+
+  let res = unsafe {
+    RegGetValueW(
+      hkey,
+      sub_key,
+      value,
+      RRF_RT_REG_BINARY,
+      None,
+      Some(data.as_mut_ptr() as _),
+      Some(&mut data_size),
+    )
+  };
+
+  res.is_ok().then(|| {
+    data.truncate(data_size as usize);
+    data
+  })
+}
+
+/// Reads a string value from the registry. Returns `None` if the key or
+/// value doesn't exist or isn't readable.
+pub fn read_registry_string(
+  hkey: HKEY,
+  sub_key: PCWSTR,
+  value: PCWSTR,
+) -> Option<String> {
+  let mut data_size = 0u32;
+
+  // Get the size of the value first.
+  let res = unsafe {
+    RegGetValueW(
+      hkey,
+      sub_key,
+      value,
+      RRF_RT_REG_SZ,
+      None,
+      None,
+      Some(&mut data_size),
+    )
+  };
+
+  if res.is_err() {
+    return None;
+  }
+
+  let mut data = vec![0u16; (data_size as usize).div_ceil(2)];
+
+  let res = unsafe {
+    RegGetValueW(
+      hkey,
+      sub_key,
+      value,
+      RRF_RT_REG_SZ,
+      None,
+      Some(data.as_mut_ptr() as _),
+      Some(&mut data_size),
+    )
+  };
+
+  if res.is_err() {
+    return None;
+  }
+
+  // The size includes the null terminator.
+  let len = data
+    .iter()
+    .position(|char| *char == 0)
+    .unwrap_or(data.len());
+
+  Some(String::from_utf16_lossy(&data[..len]))
+}
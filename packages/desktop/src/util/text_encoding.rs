@@ -0,0 +1,244 @@
+use std::fmt;
+
+use anyhow::bail;
+
+/// Encoding of a text file, as detected from its BOM or contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+  Utf8,
+  Utf16Le,
+  Utf16Be,
+}
+
+impl fmt::Display for TextEncoding {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TextEncoding::Utf8 => write!(f, "UTF-8"),
+      TextEncoding::Utf16Le => write!(f, "UTF-16 LE"),
+      TextEncoding::Utf16Be => write!(f, "UTF-16 BE"),
+    }
+  }
+}
+
+/// Decodes a text file into a UTF-8 string without a BOM and with `\n`
+/// line endings.
+///
+/// Files without a BOM are read as UTF-8, unless they look like UTF-16
+/// (e.g. saved as "Unicode" by older versions of Notepad).
+pub fn decode_text(bytes: &[u8]) -> anyhow::Result<String> {
+  let (encoding, content) = detect_encoding(bytes);
+
+  let text = match encoding {
+    TextEncoding::Utf8 => match std::str::from_utf8(content) {
+      Ok(text) => text.to_string(),
+      Err(err) => bail!(
+        "File isn't valid {} (invalid byte on line {}). Save it as \
+        UTF-8 and try again.",
+        encoding,
+        line_number(&content[..err.valid_up_to()])
+      ),
+    },
+    TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+      decode_utf16(encoding, content)?
+    }
+  };
+
+  Ok(normalize_line_endings(&text))
+}
+
+/// Detects the encoding from the BOM, and returns the content after it.
+fn detect_encoding(bytes: &[u8]) -> (TextEncoding, &[u8]) {
+  match bytes {
+    [0xEF, 0xBB, 0xBF, rest @ ..] => (TextEncoding::Utf8, rest),
+    [0xFF, 0xFE, rest @ ..] => (TextEncoding::Utf16Le, rest),
+    [0xFE, 0xFF, rest @ ..] => (TextEncoding::Utf16Be, rest),
+    // Checked before UTF-8, since ASCII text in UTF-16 is also valid
+    // UTF-8 (with a NUL byte after or before each character).
+    _ => (guess_utf16(bytes).unwrap_or(TextEncoding::Utf8), bytes),
+  }
+}
+
+/// Guesses whether text without a BOM is UTF-16, based on where the
+/// zero bytes of ASCII characters are.
+fn guess_utf16(bytes: &[u8]) -> Option<TextEncoding> {
+  if bytes.len() < 2 || bytes.len() % 2 != 0 {
+    return None;
+  }
+
+  let pairs = bytes.len() / 2;
+  let (even_zeros, odd_zeros) =
+    bytes.chunks_exact(2).fold((0, 0), |(even, odd), pair| {
+      (
+        even + (pair[0] == 0) as usize,
+        odd + (pair[1] == 0) as usize,
+      )
+    });
+
+  // Mostly ASCII text has a zero in most pairs, on the same side.
+  match (even_zeros * 2 > pairs, odd_zeros * 2 > pairs) {
+    (false, true) => Some(TextEncoding::Utf16Le),
+    (true, false) => Some(TextEncoding::Utf16Be),
+    _ => None,
+  }
+}
+
+fn decode_utf16(
+  encoding: TextEncoding,
+  content: &[u8],
+) -> anyhow::Result<String> {
+  if content.len() % 2 != 0 {
+    bail!(
+      "File looks like {} but has an odd number of bytes. Save it as \
+      UTF-8 and try again.",
+      encoding
+    );
+  }
+
+  let units = content.chunks_exact(2).map(|pair| match encoding {
+    TextEncoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+    _ => u16::from_le_bytes([pair[0], pair[1]]),
+  });
+
+  let mut text = String::with_capacity(content.len() / 2);
+
+  for char in char::decode_utf16(units) {
+    match char {
+      Ok(char) => text.push(char),
+      Err(_) => bail!(
+        "File looks like {} but has an invalid character on line {}. \
+        Save it as UTF-8 and try again.",
+        encoding,
+        line_number(text.as_bytes())
+      ),
+    }
+  }
+
+  Ok(text)
+}
+
+/// Line number of the position after the given text.
+fn line_number(text_before: &[u8]) -> usize {
+  text_before.iter().filter(|byte| **byte == b'\n').count() + 1
+}
+
+/// Converts `\r\n` and lone `\r` line endings to `\n`.
+fn normalize_line_endings(text: &str) -> String {
+  if !text.contains('\r') {
+    return text.to_string();
+  }
+
+  text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const YAML: &str = "widgets:\r\n  bar:\r\n    label: 'Grüße ✓'\r\n";
+  const NORMALIZED: &str = "widgets:\n  bar:\n    label: 'Grüße ✓'\n";
+
+  fn utf16_le(text: &str) -> Vec<u8> {
+    text
+      .encode_utf16()
+      .flat_map(|unit| unit.to_le_bytes())
+      .collect()
+  }
+
+  fn utf16_be(text: &str) -> Vec<u8> {
+    text
+      .encode_utf16()
+      .flat_map(|unit| unit.to_be_bytes())
+      .collect()
+  }
+
+  fn with_bom(bom: &[u8], content: Vec<u8>) -> Vec<u8> {
+    [bom.to_vec(), content].concat()
+  }
+
+  #[test]
+  fn utf8_without_bom() {
+    assert_eq!(decode_text(YAML.as_bytes()).unwrap(), NORMALIZED);
+  }
+
+  #[test]
+  fn utf8_with_bom() {
+    let bytes = with_bom(&[0xEF, 0xBB, 0xBF], YAML.as_bytes().to_vec());
+
+    assert_eq!(decode_text(&bytes).unwrap(), NORMALIZED);
+  }
+
+  #[test]
+  fn utf16_le_with_bom() {
+    let bytes = with_bom(&[0xFF, 0xFE], utf16_le(YAML));
+
+    assert_eq!(decode_text(&bytes).unwrap(), NORMALIZED);
+  }
+
+  #[test]
+  fn utf16_be_with_bom() {
+    let bytes = with_bom(&[0xFE, 0xFF], utf16_be(YAML));
+
+    assert_eq!(decode_text(&bytes).unwrap(), NORMALIZED);
+  }
+
+  #[test]
+  fn utf16_le_without_bom() {
+    let bytes = utf16_le(YAML);
+
+    assert_eq!(detect_encoding(&bytes).0, TextEncoding::Utf16Le);
+    assert_eq!(decode_text(&bytes).unwrap(), NORMALIZED);
+  }
+
+  #[test]
+  fn utf16_be_without_bom() {
+    let bytes = utf16_be(YAML);
+
+    assert_eq!(detect_encoding(&bytes).0, TextEncoding::Utf16Be);
+    assert_eq!(decode_text(&bytes).unwrap(), NORMALIZED);
+  }
+
+  #[test]
+  fn utf16_surrogate_pairs() {
+    let bytes = with_bom(&[0xFF, 0xFE], utf16_le("icon: '🚀'\n"));
+
+    assert_eq!(decode_text(&bytes).unwrap(), "icon: '🚀'\n");
+  }
+
+  #[test]
+  fn empty_files() {
+    assert_eq!(decode_text(&[]).unwrap(), "");
+    assert_eq!(decode_text(&[0xEF, 0xBB, 0xBF]).unwrap(), "");
+    assert_eq!(decode_text(&[0xFF, 0xFE]).unwrap(), "");
+  }
+
+  #[test]
+  fn lone_carriage_returns_are_normalized() {
+    assert_eq!(decode_text(b"a\rb\r\nc").unwrap(), "a\nb\nc");
+  }
+
+  #[test]
+  fn invalid_utf8_names_line() {
+    let err = decode_text(b"a: 1\nb: \xFF\n").unwrap_err();
+
+    assert!(err.to_string().contains("UTF-8"));
+    assert!(err.to_string().contains("line 2"));
+  }
+
+  #[test]
+  fn unpaired_surrogate_names_line() {
+    let mut bytes = with_bom(&[0xFF, 0xFE], utf16_le("a: 1\nb: "));
+    bytes.extend_from_slice(&0xD800u16.to_le_bytes());
+
+    let err = decode_text(&bytes).unwrap_err();
+
+    assert!(err.to_string().contains("UTF-16 LE"));
+    assert!(err.to_string().contains("line 2"));
+  }
+
+  #[test]
+  fn odd_length_utf16_fails() {
+    let err = decode_text(&[0xFE, 0xFF, 0x00, 0x61, 0x00]).unwrap_err();
+
+    assert!(err.to_string().contains("odd number of bytes"));
+  }
+}
@@ -0,0 +1,47 @@
+use std::{future::Future, thread};
+
+use tokio::runtime;
+use tracing::warn;
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{
+  GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_ABOVE_NORMAL,
+};
+
+/// Runs the future on a dedicated thread with above-normal priority.
+///
+/// The thread has its own single-threaded Tokio runtime, so the future
+/// isn't starved by tasks on the main runtime under heavy CPU load.
+pub fn spawn_high_priority<F>(name: &str, future: F) -> anyhow::Result<()>
+where
+  F: Future<Output = ()> + Send + 'static,
+{
+  let runtime = runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()?;
+
+  thread::Builder::new().name(name.into()).spawn(move || {
+    if let Err(err) = raise_thread_priority() {
+      warn!("Failed to raise thread priority: {:?}", err);
+    }
+
+    runtime.block_on(future);
+  })?;
+
+  Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn raise_thread_priority() -> anyhow::Result<()> {
+  unsafe {
+    SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_ABOVE_NORMAL)
+  }?;
+
+  Ok(())
+}
+
+/// Raising thread priority requires elevated privileges on other
+/// platforms, so this is a no-op.
+#[cfg(not(target_os = "windows"))]
+fn raise_thread_priority() -> anyhow::Result<()> {
+  Ok(())
+}
@@ -1,21 +1,111 @@
 use anyhow::Context;
 #[cfg(target_os = "macos")]
 use cocoa::{
-  appkit::{NSMainMenuWindowLevel, NSWindow},
-  base::id,
+  appkit::{NSApp, NSMainMenuWindowLevel, NSWindow},
+  base::{id, nil},
+  foundation::NSString,
 };
+#[cfg(target_os = "macos")]
+use objc::{msg_send, sel, sel_impl};
+use serde::Deserialize;
 use tauri::{Runtime, Window};
 #[cfg(target_os = "windows")]
-use windows::Win32::UI::WindowsAndMessaging::{
-  SetWindowLongPtrW, GWL_EXSTYLE, WS_EX_APPWINDOW, WS_EX_TOOLWINDOW,
+use windows::{
+  core::{GUID, HSTRING},
+  Win32::{
+    Foundation::{COLORREF, HWND, RECT, TRUE},
+    Graphics::Dwm::{
+      DwmSetWindowAttribute, DWMWA_WINDOW_CORNER_PREFERENCE,
+      DWMWCP_DEFAULT, DWMWCP_DONOTROUND, DWMWCP_ROUND,
+      DWM_WINDOW_CORNER_PREFERENCE,
+    },
+    Graphics::Gdi::{
+      CreateBitmap, CreateCompatibleBitmap, CreateCompatibleDC,
+      CreateSolidBrush, DeleteDC, DeleteObject, DrawTextW, FillRect,
+      GetDC, ReleaseDC, SelectObject, SetBkMode, SetTextColor, DT_CENTER,
+      DT_SINGLELINE, DT_VCENTER, TRANSPARENT,
+    },
+    System::Com::{CoCreateInstance, CLSCTX_ALL, CLSCTX_INPROC_SERVER},
+    UI::{
+      Shell::{
+        ITaskbarList3, IVirtualDesktopManager, TaskbarList,
+        VirtualDesktopManager,
+      },
+      WindowsAndMessaging::{
+        CreateIconIndirect, DestroyIcon, GetWindowLongPtrW,
+        SetWindowLongPtrW, SetWindowPos, GWL_EXSTYLE, GWL_STYLE, HICON,
+        HWND_TOPMOST, ICONINFO, SWP_FRAMECHANGED, SWP_NOACTIVATE,
+        SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, WS_EX_APPWINDOW,
+        WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_MAXIMIZEBOX, WS_THICKFRAME,
+      },
+    },
+  },
 };
 
+/// Max distance in physical pixels between a window and the edge of its
+/// monitor for the window to be considered docked to the edge.
+#[cfg(target_os = "windows")]
+const EDGE_THRESHOLD: i32 = 16;
+
+/// Rounding of the window's corners. Only has an effect on Windows 11.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CornerPreference {
+  /// Let the system decide (i.e. rounded for most windows).
+  Default,
+  Round,
+  Square,
+}
+
 pub trait WindowExt {
   #[cfg(target_os = "macos")]
   fn set_above_menu_bar(&self) -> anyhow::Result<()>;
 
   #[cfg(target_os = "windows")]
   fn set_tool_window(&self, enable: bool) -> anyhow::Result<()>;
+
+  /// Shows the window on all virtual desktops, or only on the one it's
+  /// on. Windows only shows tool windows on all desktops, so this
+  /// toggles `WS_EX_TOOLWINDOW` while keeping the other extended styles.
+  ///
+  /// Windows that are shown in the taskbar are never shown on all
+  /// desktops.
+  #[cfg(target_os = "windows")]
+  fn set_visible_on_all_desktops(
+    &self,
+    visible: bool,
+  ) -> anyhow::Result<()>;
+
+  /// Moves the window to the virtual desktop with the given ID.
+  ///
+  /// The shell re-evaluates windows that are moved between desktops,
+  /// which can reset their z-order and add them back to the taskbar, so
+  /// both are restored afterwards.
+  #[cfg(target_os = "windows")]
+  fn move_to_desktop(&self, desktop_id: GUID) -> anyhow::Result<()>;
+
+  /// Errors on versions before Windows 11, which don't round corners.
+  #[cfg(target_os = "windows")]
+  fn set_corner_preference(
+    &self,
+    preference: CornerPreference,
+  ) -> anyhow::Result<()>;
+
+  /// Removes the maximize box and the resizable frame, which excludes
+  /// the window from snap layouts and from FancyZones.
+  #[cfg(target_os = "windows")]
+  fn exclude_from_snap(&self) -> anyhow::Result<()>;
+
+  /// Whether the window lies along an edge of its monitor like a bar
+  /// (i.e. is thin, spans at least half of the edge, and is within
+  /// `EDGE_THRESHOLD` of it).
+  #[cfg(target_os = "windows")]
+  fn is_docked_to_edge(&self) -> anyhow::Result<bool>;
+
+  /// Shows a count badge on the window's taskbar button (Windows) or the
+  /// dock icon (MacOS). Passing `None` clears the badge.
+  #[cfg(any(target_os = "windows", target_os = "macos"))]
+  fn set_badge(&self, count: Option<u32>) -> anyhow::Result<()>;
 }
 
 impl<R: Runtime> WindowExt for Window<R> {
@@ -60,4 +150,272 @@ impl<R: Runtime> WindowExt for Window<R> {
 
     Ok(())
   }
+
+  #[cfg(target_os = "windows")]
+  fn set_visible_on_all_desktops(
+    &self,
+    visible: bool,
+  ) -> anyhow::Result<()> {
+    let handle = self.hwnd().context("Failed to get window handle.")?;
+
+    unsafe {
+      let ex_style = GetWindowLongPtrW(handle, GWL_EXSTYLE);
+      let is_in_taskbar = ex_style & WS_EX_APPWINDOW.0 as isize != 0;
+
+      let new_ex_style = match visible && !is_in_taskbar {
+        true => ex_style | WS_EX_TOOLWINDOW.0 as isize,
+        false => ex_style & !(WS_EX_TOOLWINDOW.0 as isize),
+      };
+
+      if new_ex_style == ex_style {
+        return Ok(());
+      }
+
+      SetWindowLongPtrW(handle, GWL_EXSTYLE, new_ex_style);
+
+      SetWindowPos(
+        handle,
+        HWND::default(),
+        0,
+        0,
+        0,
+        0,
+        SWP_FRAMECHANGED
+          | SWP_NOACTIVATE
+          | SWP_NOMOVE
+          | SWP_NOSIZE
+          | SWP_NOZORDER,
+      )?;
+    }
+
+    Ok(())
+  }
+
+  #[cfg(target_os = "windows")]
+  fn move_to_desktop(&self, desktop_id: GUID) -> anyhow::Result<()> {
+    let handle = self.hwnd().context("Failed to get window handle.")?;
+    let ex_style = unsafe { GetWindowLongPtrW(handle, GWL_EXSTYLE) };
+
+    // Tool windows can't be moved, since they're shown on all desktops.
+    self.set_visible_on_all_desktops(false)?;
+
+    unsafe {
+      let manager: IVirtualDesktopManager =
+        CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL)?;
+
+      manager
+        .MoveWindowToDesktop(handle, &desktop_id)
+        .context("Failed to move window to virtual desktop.")?;
+
+      if ex_style & WS_EX_TOPMOST.0 as isize != 0 {
+        SetWindowPos(
+          handle,
+          HWND_TOPMOST,
+          0,
+          0,
+          0,
+          0,
+          SWP_NOACTIVATE | SWP_NOMOVE | SWP_NOSIZE,
+        )?;
+      }
+    }
+
+    // Windows that are hidden from the taskbar don't have the
+    // `WS_EX_APPWINDOW` style (see `set_tool_window`).
+    if ex_style & WS_EX_APPWINDOW.0 as isize == 0 {
+      self.set_skip_taskbar(true)?;
+    }
+
+    Ok(())
+  }
+
+  #[cfg(target_os = "windows")]
+  fn set_corner_preference(
+    &self,
+    preference: CornerPreference,
+  ) -> anyhow::Result<()> {
+    let handle = self.hwnd().context("Failed to get window handle.")?;
+
+    let preference = match preference {
+      CornerPreference::Default => DWMWCP_DEFAULT,
+      CornerPreference::Round => DWMWCP_ROUND,
+      CornerPreference::Square => DWMWCP_DONOTROUND,
+    };
+
+    unsafe {
+      DwmSetWindowAttribute(
+        handle,
+        DWMWA_WINDOW_CORNER_PREFERENCE,
+        &preference as *const _ as *const _,
+        std::mem::size_of::<DWM_WINDOW_CORNER_PREFERENCE>() as u32,
+      )
+    }
+    .context("Failed to set corner preference.")
+  }
+
+  #[cfg(target_os = "windows")]
+  fn exclude_from_snap(&self) -> anyhow::Result<()> {
+    let handle = self.hwnd().context("Failed to get window handle.")?;
+
+    unsafe {
+      let style = GetWindowLongPtrW(handle, GWL_STYLE);
+      let excluded = (WS_MAXIMIZEBOX.0 | WS_THICKFRAME.0) as isize;
+      SetWindowLongPtrW(handle, GWL_STYLE, style & !excluded);
+
+      // Style changes to the frame only apply after `SWP_FRAMECHANGED`.
+      SetWindowPos(
+        handle,
+        HWND::default(),
+        0,
+        0,
+        0,
+        0,
+        SWP_FRAMECHANGED
+          | SWP_NOACTIVATE
+          | SWP_NOMOVE
+          | SWP_NOSIZE
+          | SWP_NOZORDER,
+      )?;
+    }
+
+    Ok(())
+  }
+
+  #[cfg(target_os = "windows")]
+  fn is_docked_to_edge(&self) -> anyhow::Result<bool> {
+    let Some(monitor) = self.current_monitor()? else {
+      return Ok(false);
+    };
+
+    let position = self.outer_position()?;
+    let size = self.outer_size()?;
+    let (width, height) = (size.width as i32, size.height as i32);
+
+    let monitor_position = monitor.position();
+    let monitor_width = monitor.size().width as i32;
+    let monitor_height = monitor.size().height as i32;
+
+    let near = |a: i32, b: i32| (a - b).abs() <= EDGE_THRESHOLD;
+
+    let is_horizontal_bar = width >= monitor_width / 2
+      && height <= monitor_height / 4
+      && (near(position.y, monitor_position.y)
+        || near(position.y + height, monitor_position.y + monitor_height));
+
+    let is_vertical_bar = height >= monitor_height / 2
+      && width <= monitor_width / 4
+      && (near(position.x, monitor_position.x)
+        || near(position.x + width, monitor_position.x + monitor_width));
+
+    Ok(is_horizontal_bar || is_vertical_bar)
+  }
+
+  #[cfg(target_os = "macos")]
+  fn set_badge(&self, count: Option<u32>) -> anyhow::Result<()> {
+    unsafe {
+      let label = match count {
+        Some(count) => NSString::alloc(nil).init_str(&count.to_string()),
+        None => nil,
+      };
+
+      let dock_tile: id = msg_send![NSApp(), dockTile];
+      let _: () = msg_send![dock_tile, setBadgeLabel: label];
+    }
+
+    Ok(())
+  }
+
+  #[cfg(target_os = "windows")]
+  fn set_badge(&self, count: Option<u32>) -> anyhow::Result<()> {
+    let handle = self.hwnd().context("Failed to get window handle.")?;
+
+    unsafe {
+      let taskbar: ITaskbarList3 =
+        CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)?;
+
+      taskbar.HrInit()?;
+
+      let icon = match count {
+        Some(count) => create_badge_icon(count)?,
+        None => HICON::default(),
+      };
+
+      let description = count.map(|count| count.to_string());
+      let res = taskbar.SetOverlayIcon(
+        handle,
+        icon,
+        &HSTRING::from(description.unwrap_or_default()),
+      );
+
+      // The taskbar keeps its own copy of the icon.
+      if !icon.is_invalid() {
+        let _ = DestroyIcon(icon);
+      }
+
+      res?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Size of taskbar overlay icons in pixels.
+#[cfg(target_os = "windows")]
+const BADGE_SIZE: i32 = 16;
+
+/// Draws a taskbar overlay icon with the given count as white text on a
+/// red background. Counts above 9 are shown as `9+` to fit the icon.
+#[cfg(target_os = "windows")]
+unsafe fn create_badge_icon(count: u32) -> anyhow::Result<HICON> {
+  let label = match count {
+    0..=9 => count.to_string(),
+    _ => "9+".to_string(),
+  };
+
+  let screen_dc = GetDC(HWND::default());
+  let dc = CreateCompatibleDC(screen_dc);
+  let color_bitmap =
+    CreateCompatibleBitmap(screen_dc, BADGE_SIZE, BADGE_SIZE);
+
+  // An all-zero mask makes the whole icon opaque.
+  let mask_bitmap = CreateBitmap(BADGE_SIZE, BADGE_SIZE, 1, 1, None);
+
+  let prev_bitmap = SelectObject(dc, color_bitmap);
+  let background = CreateSolidBrush(COLORREF(0x0000_00CC));
+  let mut rect = RECT {
+    left: 0,
+    top: 0,
+    right: BADGE_SIZE,
+    bottom: BADGE_SIZE,
+  };
+
+  FillRect(dc, &rect, background);
+  SetBkMode(dc, TRANSPARENT);
+  SetTextColor(dc, COLORREF(0x00FF_FFFF));
+
+  let mut text = label.encode_utf16().collect::<Vec<_>>();
+  DrawTextW(
+    dc,
+    &mut text,
+    &mut rect,
+    DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+  );
+
+  SelectObject(dc, prev_bitmap);
+
+  let icon = CreateIconIndirect(&ICONINFO {
+    fIcon: TRUE,
+    xHotspot: 0,
+    yHotspot: 0,
+    hbmMask: mask_bitmap,
+    hbmColor: color_bitmap,
+  });
+
+  DeleteObject(background);
+  DeleteObject(color_bitmap);
+  DeleteObject(mask_bitmap);
+  DeleteDC(dc);
+  ReleaseDC(HWND::default(), screen_dc);
+
+  Ok(icon?)
 }
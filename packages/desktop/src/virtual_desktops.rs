@@ -0,0 +1,61 @@
+use std::{collections::HashSet, sync::Mutex};
+
+#[cfg(target_os = "windows")]
+use anyhow::bail;
+#[cfg(target_os = "windows")]
+use windows::core::GUID;
+
+/// Labels of windows that are only shown on their current workspace
+/// (virtual desktop), rather than on all workspaces.
+#[derive(Default)]
+pub struct PinnedWindows(Mutex<HashSet<String>>);
+
+impl PinnedWindows {
+  pub fn set(&self, window_label: &str, pinned: bool) {
+    let mut pinned_windows = self.0.lock().unwrap();
+
+    match pinned {
+      true => pinned_windows.insert(window_label.to_string()),
+      false => pinned_windows.remove(window_label),
+    };
+  }
+
+  pub fn is_pinned(&self, window_label: &str) -> bool {
+    self.0.lock().unwrap().contains(window_label)
+  }
+}
+
+/// Gets the ID of the virtual desktop at the given index, in the order
+/// shown in Task View.
+#[cfg(target_os = "windows")]
+pub fn desktop_id(desktop_index: usize) -> anyhow::Result<GUID> {
+  use windows::{core::w, Win32::System::Registry::HKEY_CURRENT_USER};
+
+  use crate::util::registry::read_registry_binary;
+
+  // Explorer stores the IDs of all desktops as consecutive GUIDs. The
+  // value doesn't exist until a second desktop is created.
+  let desktop_ids = read_registry_binary(
+    HKEY_CURRENT_USER,
+    w!("Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\VirtualDesktops"),
+    w!("VirtualDesktopIDs"),
+  )
+  .unwrap_or_default();
+
+  let desktop_count = desktop_ids.len() / 16;
+
+  let Some(bytes) = desktop_ids.chunks_exact(16).nth(desktop_index) else {
+    bail!(
+      "No virtual desktop at index {} ({} desktops found).",
+      desktop_index,
+      desktop_count.max(1)
+    );
+  };
+
+  Ok(GUID::from_values(
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    u16::from_le_bytes([bytes[4], bytes[5]]),
+    u16::from_le_bytes([bytes[6], bytes[7]]),
+    bytes[8..16].try_into()?,
+  ))
+}
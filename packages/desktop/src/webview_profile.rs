@@ -0,0 +1,97 @@
+use std::{
+  path::PathBuf,
+  sync::atomic::{AtomicBool, Ordering},
+};
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+use tracing::warn;
+
+/// Browser arguments for webviews in the shared profile.
+///
+/// The features are the ones that Tauri disables by default, since
+/// setting custom arguments replaces its defaults. `--process-per-site`
+/// lets webviews of the same site (e.g. all windows with the bundled
+/// frontend) share a renderer process, instead of each getting its own.
+///
+/// WebView2 only shares a browser process between webviews that use the
+/// same user data folder with the same arguments, and fails to create
+/// webviews that don't match a running browser process. So every
+/// webview in the shared profile must use exactly these arguments.
+const SHARED_BROWSER_ARGS: &str = "--disable-features=msWebOOUI,\
+  msPdfOOUI,msSmartScreenProtection --process-per-site";
+
+/// Directory under the app's local data directory that holds the user
+/// data folders of isolated webviews.
+const ISOLATED_DATA_DIR: &str = "isolated-webviews";
+
+/// Whether windows are opened with isolated webviews.
+static IS_ISOLATED: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether windows opened from now on get isolated webviews. Open
+/// windows keep their webviews.
+pub fn set_isolated(is_isolated: bool) {
+  IS_ISOLATED.store(is_isolated, Ordering::Relaxed);
+}
+
+/// Sets up the webview of a Zebar window.
+///
+/// By default, all windows share one user data folder (Tauri's default
+/// one, so that existing storage is kept) and with it one WebView2
+/// browser process, and windows of the same site share a renderer. Each
+/// window still has its own label, open args and document. Storage is
+/// per-origin as before, and is thereby shared between windows of the
+/// same site; the client API's `getWindowStorage` namespaces it by
+/// window label.
+///
+/// With `isolated_webviews` enabled, each window instead gets its own
+/// user data folder, and thereby its own browser process tree.
+pub fn window_profile<'a, R: Runtime, M: Manager<R>>(
+  builder: WebviewWindowBuilder<'a, R, M>,
+  app_handle: &AppHandle<R>,
+  window_label: &str,
+) -> WebviewWindowBuilder<'a, R, M> {
+  if !IS_ISOLATED.load(Ordering::Relaxed) {
+    return shared_profile(builder);
+  }
+
+  match isolated_data_dir(app_handle, window_label) {
+    Some(data_dir) => builder.data_directory(data_dir),
+    None => {
+      warn!(
+        "No data directory for isolated webview of window '{}'. Using \
+        the shared profile.",
+        window_label
+      );
+
+      shared_profile(builder)
+    }
+  }
+}
+
+/// Sets up a webview in the shared profile. Used for webviews other than
+/// Zebar windows (e.g. tooltips), which always share the profile.
+pub fn shared_profile<'a, R: Runtime, M: Manager<R>>(
+  builder: WebviewWindowBuilder<'a, R, M>,
+) -> WebviewWindowBuilder<'a, R, M> {
+  builder.additional_browser_args(SHARED_BROWSER_ARGS)
+}
+
+/// Gets the user data folder for an isolated webview. Window labels are
+/// unique among open windows, so folders aren't used by two webviews at
+/// once.
+fn isolated_data_dir<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  window_label: &str,
+) -> Option<PathBuf> {
+  let dir_name = window_label
+    .chars()
+    .map(|char| match char.is_ascii_alphanumeric() || char == '-' {
+      true => char,
+      false => '_',
+    })
+    .collect::<String>();
+
+  let data_dir = app_handle.path().app_local_data_dir().ok()?;
+
+  Some(data_dir.join(ISOLATED_DATA_DIR).join(dir_name))
+}
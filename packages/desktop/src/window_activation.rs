@@ -0,0 +1,111 @@
+use std::fmt;
+
+use anyhow::bail;
+use serde::Serialize;
+use windows::Win32::{
+  Foundation::HWND,
+  System::Threading::{AttachThreadInput, GetCurrentThreadId},
+  UI::WindowsAndMessaging::{
+    AllowSetForegroundWindow, BringWindowToTop, GetForegroundWindow,
+    GetLastActivePopup, GetWindowThreadProcessId, IsIconic, IsWindow,
+    IsWindowVisible, SetForegroundWindow, ShowWindow, SW_RESTORE,
+  },
+};
+
+/// Error returned when the OS refuses to bring a window to the
+/// foreground, e.g. due to foreground lock rules. Widgets can flash the
+/// window instead.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationDeniedError {
+  pub kind: &'static str,
+  pub native_handle: u64,
+  pub message: String,
+}
+
+impl ActivationDeniedError {
+  fn new(native_handle: u64) -> Self {
+    Self {
+      kind: "activation_denied",
+      native_handle,
+      message: format!(
+        "Windows refused to bring window {} to the foreground.",
+        native_handle
+      ),
+    }
+  }
+}
+
+impl fmt::Display for ActivationDeniedError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for ActivationDeniedError {}
+
+/// Brings the window with the given handle to the foreground, restoring
+/// it if minimized.
+///
+/// Zebar's windows never take focus, so Windows usually doesn't allow
+/// it to change the foreground window. The foreground permission is
+/// borrowed from the current foreground window's thread in that case.
+pub fn activate_window(native_handle: u64) -> anyhow::Result<()> {
+  let window = HWND(native_handle as isize);
+
+  if !unsafe { IsWindow(window) }.as_bool() {
+    bail!("No window with handle {}.", native_handle);
+  }
+
+  unsafe {
+    if IsIconic(window).as_bool() {
+      _ = ShowWindow(window, SW_RESTORE);
+    }
+
+    // Same as the taskbar, a window that's disabled by a modal dialog
+    // activates the dialog instead.
+    let popup = GetLastActivePopup(window);
+    let target = match IsWindowVisible(popup).as_bool() {
+      true => popup,
+      false => window,
+    };
+
+    if SetForegroundWindow(target).as_bool()
+      && GetForegroundWindow() == target
+    {
+      return Ok(());
+    }
+
+    // Only the process that received the last input event (i.e. the
+    // foreground window's process) can change the foreground window.
+    // Attaching to the input of the foreground window's thread lifts
+    // that restriction.
+    let foreground_thread =
+      GetWindowThreadProcessId(GetForegroundWindow(), None);
+    let current_thread = GetCurrentThreadId();
+
+    let is_attached = foreground_thread != 0
+      && foreground_thread != current_thread
+      && AttachThreadInput(current_thread, foreground_thread, true)
+        .as_bool();
+
+    // Also lets the target's process bring its other windows to the
+    // foreground, e.g. if it shows a dialog once activated.
+    let mut target_process_id = 0;
+    GetWindowThreadProcessId(target, Some(&mut target_process_id));
+    _ = AllowSetForegroundWindow(target_process_id);
+
+    _ = BringWindowToTop(target);
+    _ = SetForegroundWindow(target);
+
+    if is_attached {
+      _ = AttachThreadInput(current_thread, foreground_thread, false);
+    }
+
+    if GetForegroundWindow() != target {
+      return Err(ActivationDeniedError::new(native_handle).into());
+    }
+  }
+
+  Ok(())
+}
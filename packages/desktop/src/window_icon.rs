@@ -0,0 +1,35 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use tauri::{image::Image, AppHandle};
+
+use crate::asset_protocol;
+
+/// Resolves the path of a window icon. Relative paths are resolved
+/// against the `assets/` folder within the config directory.
+pub fn resolve_icon_path(
+  app_handle: &AppHandle,
+  icon: &str,
+) -> anyhow::Result<PathBuf> {
+  let path = Path::new(icon);
+
+  match path.is_absolute() {
+    true => Ok(path.to_path_buf()),
+    false => asset_protocol::resolve_user_asset(app_handle, icon),
+  }
+}
+
+/// Loads a PNG or ICO file to use as a window icon.
+pub fn load_icon(path: &Path) -> anyhow::Result<Image<'static>> {
+  let extension = path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .map(|extension| extension.to_ascii_lowercase());
+
+  if !matches!(extension.as_deref(), Some("png" | "ico")) {
+    bail!("Icon '{}' must be a PNG or ICO file.", path.display());
+  }
+
+  Image::from_path(path)
+    .with_context(|| format!("Failed to load icon '{}'.", path.display()))
+}
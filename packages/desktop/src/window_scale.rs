@@ -0,0 +1,379 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use serde::Serialize;
+use tauri::{
+  Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime,
+  Window,
+};
+use tracing::{info, warn};
+
+/// Event that is emitted to a window when its scale factor changes.
+pub const SCALE_CHANGED_EVENT: &str = "scale-changed";
+
+/// Payload of the `scale-changed` event.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleChanged {
+  pub scale_factor: f64,
+}
+
+/// Geometry that was last applied to a window via `move_window` or
+/// `resize_window`, in logical pixels relative to its monitor.
+///
+/// The geometry is only derived from physical pixels when it's applied,
+/// and never from the re-scaled geometry. Scale changes therefore don't
+/// accumulate rounding errors, and changing the scale back restores the
+/// exact original geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowGeometry {
+  /// Name of the monitor that the geometry is relative to.
+  monitor_name: Option<String>,
+
+  /// Offset from the monitor's left edge, or from its right edge if the
+  /// window is anchored to it.
+  x_offset: f64,
+  /// Offset from the monitor's top edge, or from its bottom edge if the
+  /// window is anchored to it.
+  y_offset: f64,
+  width: f64,
+  height: f64,
+
+  /// Whether the window's right edge is at the monitor's right edge
+  /// (e.g. for a vertical bar on the right), in which case it stays
+  /// there when the width changes.
+  anchor_right: bool,
+  /// Whether the window's bottom edge is at the monitor's bottom edge
+  /// (e.g. for a bar at the bottom).
+  anchor_bottom: bool,
+}
+
+/// Monitor bounds and scale factor that geometry is converted against.
+#[derive(Debug, Clone, Copy)]
+pub struct MonitorRect {
+  pub position: PhysicalPosition<i32>,
+  pub size: PhysicalSize<u32>,
+  pub scale_factor: f64,
+}
+
+impl From<&Monitor> for MonitorRect {
+  fn from(monitor: &Monitor) -> Self {
+    Self {
+      position: *monitor.position(),
+      size: *monitor.size(),
+      scale_factor: monitor.scale_factor(),
+    }
+  }
+}
+
+impl WindowGeometry {
+  pub fn from_physical(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    monitor_name: Option<String>,
+    monitor: MonitorRect,
+  ) -> Self {
+    let scale = monitor.scale_factor;
+    let monitor_right = monitor.position.x + monitor.size.width as i32;
+    let monitor_bottom = monitor.position.y + monitor.size.height as i32;
+    let right = position.x + size.width as i32;
+    let bottom = position.y + size.height as i32;
+
+    // Windows that span the full monitor are anchored to the near edge.
+    let anchor_right =
+      right == monitor_right && position.x > monitor.position.x;
+    let anchor_bottom =
+      bottom == monitor_bottom && position.y > monitor.position.y;
+
+    let x_offset = match anchor_right {
+      true => monitor_right - right,
+      false => position.x - monitor.position.x,
+    };
+
+    let y_offset = match anchor_bottom {
+      true => monitor_bottom - bottom,
+      false => position.y - monitor.position.y,
+    };
+
+    Self {
+      monitor_name,
+      x_offset: x_offset as f64 / scale,
+      y_offset: y_offset as f64 / scale,
+      width: size.width as f64 / scale,
+      height: size.height as f64 / scale,
+      anchor_right,
+      anchor_bottom,
+    }
+  }
+
+  /// Converts the geometry to physical pixels on the given monitor.
+  ///
+  /// The size and offset are rounded separately, rather than the edges
+  /// being derived from one another, so that each is off by at most half
+  /// a pixel regardless of the scale factor.
+  pub fn to_physical(
+    &self,
+    monitor: MonitorRect,
+  ) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let scale = monitor.scale_factor;
+    let width = (self.width * scale).round().max(1.) as u32;
+    let height = (self.height * scale).round().max(1.) as u32;
+    let x_offset = (self.x_offset * scale).round() as i32;
+    let y_offset = (self.y_offset * scale).round() as i32;
+
+    let x = match self.anchor_right {
+      true => {
+        monitor.position.x + monitor.size.width as i32
+          - x_offset
+          - width as i32
+      }
+      false => monitor.position.x + x_offset,
+    };
+
+    let y = match self.anchor_bottom {
+      true => {
+        monitor.position.y + monitor.size.height as i32
+          - y_offset
+          - height as i32
+      }
+      false => monitor.position.y + y_offset,
+    };
+
+    (
+      PhysicalPosition::new(x, y),
+      PhysicalSize::new(width, height),
+    )
+  }
+}
+
+/// Last applied geometry of windows, keyed by window label.
+#[derive(Default)]
+pub struct WindowGeometries(Mutex<HashMap<String, WindowGeometry>>);
+
+impl WindowGeometries {
+  /// Records the current geometry of the window. Called after Zebar
+  /// moves or resizes the window.
+  pub fn record<R: Runtime>(
+    &self,
+    window: &Window<R>,
+  ) -> anyhow::Result<()> {
+    let Some(monitor) = window.current_monitor()? else {
+      return Ok(());
+    };
+
+    let geometry = WindowGeometry::from_physical(
+      window.outer_position()?,
+      window.outer_size()?,
+      monitor.name().cloned(),
+      MonitorRect::from(&monitor),
+    );
+
+    self
+      .0
+      .lock()
+      .unwrap()
+      .insert(window.label().to_string(), geometry);
+
+    Ok(())
+  }
+
+  pub fn remove(&self, window_label: &str) {
+    self.0.lock().unwrap().remove(window_label);
+  }
+
+  fn get(&self, window_label: &str) -> Option<WindowGeometry> {
+    self.0.lock().unwrap().get(window_label).cloned()
+  }
+}
+
+/// Handles a change to the window's scale factor, e.g. when the display
+/// scaling is changed or a laptop is docked.
+///
+/// The OS resizes the window to keep its logical size, which is based on
+/// its current (rather than its intended) geometry. The recorded
+/// geometry is instead re-applied at the new scale factor. Windows that
+/// were moved to a different monitor are left as is, since the scale
+/// change is then caused by the move itself.
+pub fn handle_scale_change<R: Runtime>(
+  window: &Window<R>,
+  scale_factor: f64,
+) {
+  info!(
+    "Scale factor of window '{}' changed to {}.",
+    window.label(),
+    scale_factor
+  );
+
+  if let Err(err) = window.emit_to(
+    window.label(),
+    SCALE_CHANGED_EVENT,
+    ScaleChanged { scale_factor },
+  ) {
+    warn!("Failed to emit scale change: {:?}", err);
+  }
+
+  let Some(geometry) = window
+    .try_state::<WindowGeometries>()
+    .and_then(|geometries| geometries.get(window.label()))
+  else {
+    return;
+  };
+
+  let window = window.clone();
+
+  // The OS applies its suggested size after the event is handled, so the
+  // geometry is re-applied afterwards.
+  tauri::async_runtime::spawn(async move {
+    if let Err(err) = reapply_geometry(&window, &geometry) {
+      warn!(
+        "Failed to re-apply geometry of window '{}': {:?}",
+        window.label(),
+        err
+      );
+    }
+  });
+}
+
+fn reapply_geometry<R: Runtime>(
+  window: &Window<R>,
+  geometry: &WindowGeometry,
+) -> anyhow::Result<()> {
+  let Some(monitor) = window.current_monitor()? else {
+    return Ok(());
+  };
+
+  if monitor.name() != geometry.monitor_name.as_ref() {
+    return Ok(());
+  }
+
+  let (position, size) = geometry.to_physical(MonitorRect::from(&monitor));
+  window.set_size(size)?;
+  window.set_position(position)?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn monitor(x: i32, width: u32, scale_factor: f64) -> MonitorRect {
+    MonitorRect {
+      position: PhysicalPosition::new(x, 0),
+      size: PhysicalSize::new(width, 1440),
+      scale_factor,
+    }
+  }
+
+  fn geometry(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    monitor: MonitorRect,
+  ) -> WindowGeometry {
+    WindowGeometry::from_physical(
+      PhysicalPosition::new(x, y),
+      PhysicalSize::new(width, height),
+      Some("DISPLAY1".into()),
+      monitor,
+    )
+  }
+
+  #[test]
+  fn round_trips_at_same_scale() {
+    let monitor = monitor(0, 2560, 1.25);
+    let geometry = geometry(13, 7, 1001, 33, monitor);
+
+    assert_eq!(
+      geometry.to_physical(monitor),
+      (PhysicalPosition::new(13, 7), PhysicalSize::new(1001, 33))
+    );
+  }
+
+  #[test]
+  fn changing_scale_back_restores_geometry() {
+    let original = monitor(0, 2560, 1.);
+    let geometry = geometry(10, 10, 2540, 40, original);
+
+    for scale_factor in [1.25, 1.5, 1.75, 2., 1.25, 3.] {
+      geometry.to_physical(monitor(0, 2560, scale_factor));
+    }
+
+    assert_eq!(
+      geometry.to_physical(original),
+      (PhysicalPosition::new(10, 10), PhysicalSize::new(2540, 40))
+    );
+  }
+
+  #[test]
+  fn scales_size_and_offset() {
+    let geometry = geometry(10, 10, 2540, 40, monitor(0, 2560, 1.));
+
+    assert_eq!(
+      geometry.to_physical(monitor(0, 2560, 1.5)),
+      (PhysicalPosition::new(15, 15), PhysicalSize::new(3810, 60))
+    );
+  }
+
+  #[test]
+  fn bottom_bar_stays_anchored_to_bottom() {
+    let geometry = geometry(0, 1400, 2560, 40, monitor(0, 2560, 1.));
+
+    assert!(geometry.anchor_bottom);
+    assert!(!geometry.anchor_right);
+    assert_eq!(
+      geometry.to_physical(monitor(0, 2560, 2.)),
+      (
+        PhysicalPosition::new(0, 1360),
+        PhysicalSize::new(2560 * 2, 80)
+      )
+    );
+  }
+
+  #[test]
+  fn right_bar_stays_anchored_to_right() {
+    let geometry = geometry(2510, 0, 50, 1440, monitor(0, 2560, 1.));
+
+    assert!(geometry.anchor_right);
+
+    let (position, size) = geometry.to_physical(monitor(0, 2560, 1.5));
+    assert_eq!(size.width, 75);
+    assert_eq!(position.x, 2560 - 75);
+  }
+
+  #[test]
+  fn full_size_window_isnt_anchored() {
+    let geometry = geometry(0, 0, 2560, 1440, monitor(0, 2560, 1.));
+
+    assert!(!geometry.anchor_right);
+    assert!(!geometry.anchor_bottom);
+  }
+
+  #[test]
+  fn offsets_are_relative_to_monitor() {
+    let geometry = geometry(2580, 20, 100, 40, monitor(2560, 1920, 1.));
+
+    assert_eq!(
+      geometry.to_physical(monitor(2560, 1920, 1.25)),
+      (PhysicalPosition::new(2585, 25), PhysicalSize::new(125, 50))
+    );
+  }
+
+  #[test]
+  fn rounding_is_within_half_pixel() {
+    let geometry = geometry(3, 3, 101, 31, monitor(0, 2560, 1.));
+    let (position, size) = geometry.to_physical(monitor(0, 2560, 1.25));
+
+    assert!((size.width as f64 - 126.25).abs() <= 0.5);
+    assert!((size.height as f64 - 38.75).abs() <= 0.5);
+    assert!((position.x as f64 - 3.75).abs() <= 0.5);
+  }
+
+  #[test]
+  fn size_is_at_least_one_pixel() {
+    let geometry = geometry(0, 0, 1, 1, monitor(0, 2560, 1.));
+    let (_, size) = geometry.to_physical(monitor(0, 2560, 0.25));
+
+    assert_eq!(size, PhysicalSize::new(1, 1));
+  }
+}
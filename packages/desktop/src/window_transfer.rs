@@ -0,0 +1,135 @@
+use anyhow::bail;
+use serde::Serialize;
+use tauri::{
+  AppHandle, Emitter, Manager, PhysicalPosition, Runtime, WebviewWindow,
+  Window,
+};
+
+use crate::tooltip;
+
+/// Event that is emitted to the target window of `transfer_state`.
+pub const STATE_TRANSFER_EVENT: &str = "state-transfer";
+
+/// State that is handed off from one window to another (e.g. a widget
+/// that is dragged between bars).
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTransfer {
+  pub from_window: String,
+  pub payload: serde_json::Value,
+}
+
+/// Gets the visible window that contains the given point in physical
+/// screen coordinates. Tooltips are ignored.
+///
+/// Physical coordinates are used since they're consistent across
+/// monitors with different scale factors, unlike logical ones.
+pub fn window_at_point<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  point: PhysicalPosition<i32>,
+) -> Option<WebviewWindow<R>> {
+  let mut candidates = app_handle
+    .webview_windows()
+    .into_iter()
+    .filter(|(label, window)| {
+      !tooltip::is_tooltip_label(label)
+        && window.is_visible().unwrap_or(false)
+        && contains_point(window, point)
+    })
+    .collect::<Vec<_>>();
+
+  // Sort for a stable result when windows overlap and the topmost one
+  // can't be determined.
+  candidates.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+  #[cfg(target_os = "windows")]
+  if candidates.len() > 1 {
+    if let Some(hwnd) = topmost_hwnd_at_point(point) {
+      if let Some((_, window)) = candidates.iter().find(|(_, window)| {
+        window.hwnd().is_ok_and(|handle| handle == hwnd)
+      }) {
+        return Some(window.clone());
+      }
+    }
+  }
+
+  candidates.into_iter().next().map(|(_, window)| window)
+}
+
+fn contains_point<R: Runtime>(
+  window: &WebviewWindow<R>,
+  point: PhysicalPosition<i32>,
+) -> bool {
+  let (Ok(position), Ok(size)) =
+    (window.outer_position(), window.outer_size())
+  else {
+    return false;
+  };
+
+  point.x >= position.x
+    && point.y >= position.y
+    && point.x < position.x + size.width as i32
+    && point.y < position.y + size.height as i32
+}
+
+/// Gets the top-level window under the given point, taking z-order into
+/// account.
+#[cfg(target_os = "windows")]
+fn topmost_hwnd_at_point(
+  point: PhysicalPosition<i32>,
+) -> Option<windows::Win32::Foundation::HWND> {
+  use windows::Win32::{
+    Foundation::POINT,
+    UI::WindowsAndMessaging::{GetAncestor, WindowFromPoint, GA_ROOT},
+  };
+
+  let hwnd = unsafe {
+    WindowFromPoint(POINT {
+      x: point.x,
+      y: point.y,
+    })
+  };
+
+  // The point is over the webview, which is a child of the window.
+  match hwnd.0 {
+    0 => None,
+    _ => Some(unsafe { GetAncestor(hwnd, GA_ROOT) }),
+  }
+}
+
+/// Delivers state from one window to another. The event is received even
+/// if the target window is hidden, since its webview keeps running.
+pub fn transfer_state<R: Runtime>(
+  window: &Window<R>,
+  from_window: &str,
+  to_window: &str,
+  payload: serde_json::Value,
+) -> anyhow::Result<()> {
+  // Windows can't hand off state on behalf of other windows.
+  if from_window != window.label() {
+    bail!(
+      "Window '{}' can't transfer state on behalf of '{}'.",
+      window.label(),
+      from_window
+    );
+  }
+
+  let app_handle = window.app_handle();
+
+  if tooltip::is_tooltip_label(to_window)
+    || app_handle.get_webview_window(to_window).is_none()
+  {
+    bail!("No window with label '{}'.", to_window);
+  }
+
+  app_handle.emit_to(
+    to_window,
+    STATE_TRANSFER_EVENT,
+    StateTransfer {
+      from_window: from_window.to_string(),
+      payload,
+    },
+  )?;
+
+  Ok(())
+}